@@ -8,16 +8,20 @@ use std::env;
 
 use ariadne_core::graph_backend::GraphBackend;
 
-use crate::agent::prompts::agentic_prompt;
+use crate::agent::prompts::{agentic_prompt, with_instructions};
+use crate::agent::tools::{ToolCall, ToolRegistry};
 use crate::agent::types::{
     AgentAction, AgentPlan, AgentStep, ConversationTurn, LlmConfig, LlmUsage,
 };
-use crate::agent::util::{clean_json_response, extract_cypher, map_llm_error};
+use crate::agent::util::{extract_cypher, map_llm_error, parse_json_response};
 use crate::error::CliResult;
+use crate::telemetry::{LlmSpan, SpanModel};
 use crate::validation::validate_cypher;
 
 const DEFAULT_MAX_STEPS: usize = 3;
 const DEFAULT_MAX_RETRIES: usize = 1;
+/// Characters of a tool's output the agent sees in its history.
+const MAX_TOOL_OUTPUT_CHARS: usize = 2_000;
 
 #[async_trait]
 pub trait Agentic: Send + Sync {
@@ -27,11 +31,13 @@ pub trait Agentic: Send + Sync {
         context: &[ConversationTurn],
         context_summary: Option<&str>,
         backend: &dyn GraphBackend,
+        tools: &ToolRegistry,
     ) -> CliResult<AgentPlan>;
 }
 
 pub struct LlmAgentic {
     llm: Box<dyn ::llm::LLMProvider>,
+    span_model: SpanModel,
     structured_output: bool,
     max_steps: usize,
     max_retries: usize,
@@ -51,7 +57,10 @@ impl LlmAgentic {
             .model(config.model.clone())
             .timeout_seconds(config.timeout_secs)
             .normalize_response(true)
-            .system(agentic_prompt(config.structured_output));
+            .system(with_instructions(
+                agentic_prompt(config.structured_output, config.prompt_profile),
+                config.instructions.as_deref(),
+            ));
 
         if config.structured_output {
             builder = builder.schema(agent_step_schema());
@@ -67,6 +76,7 @@ impl LlmAgentic {
         let llm = builder.build()?;
         Ok(Self {
             llm,
+            span_model: SpanModel::from(&config),
             structured_output: config.structured_output,
             max_steps: read_max_steps(),
             max_retries: read_max_retries(),
@@ -82,6 +92,7 @@ impl Agentic for LlmAgentic {
         context: &[ConversationTurn],
         context_summary: Option<&str>,
         backend: &dyn GraphBackend,
+        tools: &ToolRegistry,
     ) -> CliResult<AgentPlan> {
         let mut steps: Vec<AgentStep> = Vec::new();
         let mut usage = UsageAccumulator::default();
@@ -97,14 +108,21 @@ impl Agentic for LlmAgentic {
                     context,
                     context_summary,
                     &steps,
+                    tools,
                     feedback.as_deref(),
                 );
+                let span = LlmSpan::start("agent_step", &self.span_model);
                 let response = match self.llm.chat(&messages).await {
                     Ok(response) => response,
-                    Err(err) => return Err(map_llm_error(err, self.structured_output)),
+                    Err(err) => {
+                        span.fail(&err);
+                        return Err(map_llm_error(err, self.structured_output));
+                    }
                 };
-                if let Some(usage_details) = response.usage() {
-                    usage.add(&LlmUsage::from(usage_details));
+                let step_usage = response.usage().map(LlmUsage::from);
+                span.finish(step_usage.as_ref());
+                if let Some(step_usage) = &step_usage {
+                    usage.add(step_usage);
                 }
                 let text = response
                     .text()
@@ -115,7 +133,39 @@ impl Agentic for LlmAgentic {
                 } else {
                     parse_unstructured_step(&text)
                 };
-                step.usage = response.usage().map(LlmUsage::from);
+                step.usage = step_usage;
+
+                if step.action == AgentAction::Tool {
+                    let issue = match &step.tool {
+                        _ if tools.is_empty() => Some("No tools are available."),
+                        None => Some("The tool step did not name a tool."),
+                        Some(_) if step_index + 1 >= self.max_steps => {
+                            Some("No steps are left for tools.")
+                        }
+                        Some(_) => None,
+                    };
+                    if let Some(issue) = issue {
+                        if attempt <= self.max_retries {
+                            feedback = Some(format!("{issue} Use action=query or action=final."));
+                            continue;
+                        }
+                        return Err(format!("Agent asked for a tool: {issue}").into());
+                    }
+                    let call = step.tool.as_ref().expect("tool step names a tool");
+                    let output = match tools.call(call).await {
+                        Ok(output) => {
+                            tracing::info!(tool = %call.describe(), "Agent tool call succeeded");
+                            truncate_text(output.trim(), MAX_TOOL_OUTPUT_CHARS)
+                        }
+                        Err(err) => {
+                            tracing::warn!(tool = %call.describe(), "Agent tool failed: {err}");
+                            format!("error: {err}")
+                        }
+                    };
+                    step.result_summary = Some(output);
+                    steps.push(step);
+                    break;
+                }
 
                 if step.cypher.trim().is_empty() {
                     if attempt <= self.max_retries {
@@ -179,19 +229,32 @@ impl Agentic for LlmAgentic {
 #[derive(Debug, Deserialize)]
 struct AgentStepPayload {
     action: String,
+    #[serde(default)]
     cypher: String,
     #[serde(default)]
     params: Option<HashMap<String, Value>>,
+    #[serde(default)]
+    tool: Option<String>,
+    #[serde(default)]
+    args: Option<HashMap<String, Value>>,
 }
 
 fn parse_structured_step(text: &str) -> CliResult<AgentStep> {
-    let cleaned = clean_json_response(text);
-    let payload: AgentStepPayload =
-        serde_json::from_str(&cleaned).map_err(|e| format!("Invalid JSON response: {e}"))?;
+    let payload: AgentStepPayload = parse_json_response(text)?;
+    let action = parse_action(&payload.action);
+    let tool = match action {
+        AgentAction::Tool => tool_call(payload.tool.as_deref(), payload.args),
+        _ => None,
+    };
     Ok(AgentStep {
-        action: parse_action(&payload.action),
-        cypher: extract_cypher(&payload.cypher),
+        action,
+        cypher: if tool.is_some() {
+            String::new()
+        } else {
+            extract_cypher(&payload.cypher)
+        },
         params: payload.params,
+        tool,
         result_summary: None,
         usage: None,
     })
@@ -200,12 +263,16 @@ fn parse_structured_step(text: &str) -> CliResult<AgentStep> {
 fn parse_unstructured_step(text: &str) -> AgentStep {
     let mut action = AgentAction::Final;
     let mut cypher_line: Option<String> = None;
+    let mut tool_name: Option<String> = None;
+    let mut tool_args: Option<HashMap<String, Value>> = None;
     for line in text.lines() {
         let trimmed = line.trim();
         let lower = trimmed.to_lowercase();
         if lower.starts_with("action:") {
             if lower.contains("query") {
                 action = AgentAction::Query;
+            } else if lower.contains("tool") {
+                action = AgentAction::Tool;
             } else if lower.contains("final") {
                 action = AgentAction::Final;
             }
@@ -218,20 +285,50 @@ fn parse_unstructured_step(text: &str) -> AgentStep {
                 }
             }
         }
+        if lower.starts_with("tool:") {
+            if let Some((_, value)) = trimmed.split_once(':') {
+                tool_name = Some(value.trim().to_string());
+            }
+        }
+        if lower.starts_with("args:") {
+            if let Some((_, value)) = trimmed.split_once(':') {
+                tool_args = serde_json::from_str(value.trim()).ok();
+            }
+        }
+    }
+    if action == AgentAction::Tool {
+        return AgentStep {
+            action,
+            cypher: String::new(),
+            params: None,
+            tool: tool_call(tool_name.as_deref(), tool_args),
+            result_summary: None,
+            usage: None,
+        };
     }
     let cypher = cypher_line.unwrap_or_else(|| extract_cypher(text));
     AgentStep {
         action,
         cypher,
         params: None,
+        tool: None,
         result_summary: None,
         usage: None,
     }
 }
 
+fn tool_call(name: Option<&str>, args: Option<HashMap<String, Value>>) -> Option<ToolCall> {
+    let name = name.map(str::trim).filter(|name| !name.is_empty())?;
+    Some(ToolCall {
+        name: name.to_string(),
+        args: args.unwrap_or_default(),
+    })
+}
+
 fn parse_action(raw: &str) -> AgentAction {
     match raw.trim().to_lowercase().as_str() {
         "query" => AgentAction::Query,
+        "tool" => AgentAction::Tool,
         _ => AgentAction::Final,
     }
 }
@@ -241,9 +338,20 @@ fn build_agent_messages(
     context: &[ConversationTurn],
     context_summary: Option<&str>,
     steps: &[AgentStep],
+    tools: &ToolRegistry,
     feedback: Option<&str>,
 ) -> Vec<ChatMessage> {
     let mut messages = Vec::new();
+    if !tools.is_empty() {
+        messages.push(
+            ChatMessage::user()
+                .content(format!(
+                    "Tools you can call with action=tool:\n{}",
+                    tools.describe()
+                ))
+                .build(),
+        );
+    }
     if let Some(summary) = context_summary {
         let summary = summary.trim();
         if !summary.is_empty() {
@@ -279,6 +387,15 @@ fn build_agent_messages(
     if !steps.is_empty() {
         let mut history = String::new();
         for (index, step) in steps.iter().enumerate() {
+            if let Some(call) = &step.tool {
+                history.push_str(&format!(
+                    "Step {}: action=tool\nTool: {}\nOutput:\n{}\n",
+                    index + 1,
+                    call.describe(),
+                    step.result_summary.as_deref().unwrap_or("").trim()
+                ));
+                continue;
+            }
             history.push_str(&format!(
                 "Step {}: action={}\nCypher:\n{}\n",
                 index + 1,
@@ -314,7 +431,7 @@ fn build_agent_messages(
             messages.push(
                 ChatMessage::user()
                     .content(format!(
-                        "Previous Cypher failed validation: {feedback}\n\
+                        "Previous Cypher failed: {feedback}\n\
 Please correct it and return a valid JSON action."
                     ))
                     .build(),
@@ -412,7 +529,11 @@ fn truncate_text(text: &str, max_len: usize) -> String {
     if text.len() <= max_len {
         return text.to_string();
     }
-    let mut clipped = text[..max_len].to_string();
+    let mut end = max_len;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let mut clipped = text[..end].to_string();
     clipped.push_str("...");
     clipped
 }
@@ -443,9 +564,11 @@ fn agent_step_schema() -> StructuredOutputFormat {
             "type": "object",
             "additionalProperties": false,
             "properties": {
-                "action": { "type": "string", "enum": ["query", "final"] },
+                "action": { "type": "string", "enum": ["query", "tool", "final"] },
                 "cypher": { "type": "string" },
-                "params": { "type": "object", "additionalProperties": true }
+                "params": { "type": "object", "additionalProperties": true },
+                "tool": { "type": "string" },
+                "args": { "type": "object", "additionalProperties": true }
             },
             "required": ["action", "cypher"]
         }
@@ -518,6 +641,33 @@ impl UsageAccumulator {
             total_tokens: self.total_tokens,
             reasoning_tokens: self.reasoning_tokens,
             cached_tokens: self.cached_tokens,
+            model: None,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn tool_steps_are_read_from_both_formats() {
+        let structured = parse_structured_step(
+            r#"{"action": "tool", "cypher": "", "tool": "get_logs",
+                "args": {"namespace": "shop", "pod": "web-1"}}"#,
+        )
+        .expect("valid step");
+        assert_eq!(structured.action, AgentAction::Tool);
+        let call = structured.tool.expect("tool call");
+        assert_eq!(call.describe(), "get_logs(namespace=shop, pod=web-1)");
+
+        let unstructured =
+            parse_unstructured_step("action: tool\ntool: list_events\nargs: {\"uid\": \"1234\"}");
+        assert_eq!(unstructured.cypher, "");
+        assert_eq!(
+            unstructured.tool.map(|call| call.args),
+            Some(HashMap::from([("uid".to_string(), json!("1234"))]))
+        );
+    }
+}
@@ -1,15 +1,43 @@
 use ::llm::builder::LLMBuilder;
-use ::llm::chat::{ChatMessage, StructuredOutputFormat};
+use ::llm::chat::{ChatMessage, StructuredOutputFormat, Tool, ToolChoice};
 use async_trait::async_trait;
+use futures::StreamExt;
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::agent::prompts::{analysis_compaction_prompt, analysis_prompt};
+use crate::agent::prompts::{
+    analysis_compaction_prompt, analysis_prompt, followup_prompt, monitor_prompt,
+    session_report_prompt, with_answer_language, with_instructions,
+};
+use crate::agent::token_budget::{estimate_tokens, TokenBudget};
 use crate::agent::types::{
-    AnalysisResult, ContextCompaction, ConversationTurn, LlmConfig, LlmUsage,
+    AnalysisResult, ContextCompaction, ConversationTurn, FollowUp, FollowUpSuggestions,
+    IncidentReport, LlmConfig, LlmUsage, MonitorProposals, ProposedMonitor,
+};
+use crate::agent::util::{
+    extract_cypher, map_llm_error, output_tool, parse_json_response, reask_for_json, sum_usage,
+    supports_tool_calls, tool_call_arguments,
 };
-use crate::agent::util::{clean_json_response, map_llm_error};
+use crate::alerts::AlertCondition;
+use crate::docs::{cited_passages, DocPassage};
 use crate::error::CliResult;
+use crate::pipeline::{truncate_text, ReportEntry};
+use crate::telemetry::{LlmSpan, SpanModel};
+use crate::validation::{unknown_labels, validate_cypher};
+
+/// Follow-up suggestions kept after validation.
+const MAX_FOLLOW_UPS: usize = 3;
+/// Proposed monitors kept after validation.
+const MAX_MONITORS: usize = 3;
+/// Rows of the latest query shown to the model when suggesting follow-ups.
+const FOLLOW_UP_SAMPLE_ROWS: usize = 20;
+/// Rows of each query shown to the model when writing the incident report.
+const REPORT_SAMPLE_ROWS: usize = 5;
+/// Characters of each query's row sample in the incident report request.
+const REPORT_SAMPLE_CHARS: usize = 1_500;
+
+/// Receives the sections of an answer written so far while it streams in.
+pub type AnalysisSink<'a> = &'a (dyn Fn(AnalysisResult) + Send + Sync);
 
 #[async_trait]
 pub trait Analyst: Send + Sync {
@@ -23,12 +51,56 @@ pub trait Analyst: Send + Sync {
         context_summary: Option<&str>,
     ) -> CliResult<AnalysisResult>;
 
+    /// [`Analyst::analyze`], passing the sections written so far to `on_partial` while the
+    /// answer streams in. Analysts that cannot stream only return the finished answer.
+    #[allow(clippy::too_many_arguments)]
+    async fn analyze_streaming(
+        &self,
+        question: &str,
+        cypher: &str,
+        records: &[Value],
+        summary: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+        _on_partial: AnalysisSink<'_>,
+    ) -> CliResult<AnalysisResult> {
+        self.analyze(question, cypher, records, summary, context, context_summary)
+            .await
+    }
+
+    /// Only the GUI windows compact their context, write reports and propose monitors.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
     async fn compact_context(&self, context: &[ConversationTurn]) -> CliResult<ContextCompaction>;
+
+    /// Next questions for the last turn of `context`, whose query returned `records`. Every
+    /// suggestion comes with a query that passed the validator, so it can be run as is.
+    async fn suggest_followups(
+        &self,
+        records: &[Value],
+        context: &[ConversationTurn],
+    ) -> CliResult<FollowUpSuggestions>;
+
+    /// An incident report of the whole session: timeline, root cause hypothesis, evidence and
+    /// follow-up actions, written from every turn of the feed.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    async fn summarize_session(&self, turns: &[ReportEntry]) -> CliResult<IncidentReport>;
+
+    /// Alert rules that would catch what the session found next time, each with a query that
+    /// passed the validator and a condition that parses.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    async fn propose_monitors(&self, turns: &[ReportEntry]) -> CliResult<MonitorProposals>;
 }
 
 pub struct SreAnalyst {
     llm: Box<dyn ::llm::LLMProvider>,
     structured_output: bool,
+    /// Set when structured output goes through a forced tool call instead of a JSON schema.
+    output_tool: Option<Tool>,
+    /// Provider for streamed answers. Tool calls do not stream, so it always asks for the
+    /// JSON schema when the output is structured.
+    stream_llm: Option<Box<dyn ::llm::LLMProvider>>,
+    /// What the model's window leaves for result rows after the system prompt.
+    budget: TokenBudget,
     config: LlmConfig,
 }
 
@@ -41,31 +113,100 @@ impl SreAnalyst {
             return Err("LLM model is empty".into());
         }
 
-        let mut builder = LLMBuilder::new()
-            .backend(config.backend.clone())
-            .model(config.model.clone())
-            .timeout_seconds(config.timeout_secs)
-            .normalize_response(true)
-            .system(analysis_prompt(config.structured_output));
-
-        if config.structured_output {
-            builder = builder.schema(analysis_schema());
-        }
-
-        if !config.base_url.trim().is_empty() {
-            builder = builder.base_url(config.base_url.clone());
-        }
-        if let Some(api_key) = &config.api_key {
-            builder = builder.api_key(api_key.clone());
-        }
-
-        let llm = builder.build()?;
+        let (llm, tool) = build_analysis_provider(&config, true)?;
+        let stream_llm = if config.stream_analysis {
+            Some(build_analysis_provider(&config, false)?.0)
+        } else {
+            None
+        };
+        let budget = TokenBudget::for_model(&config.model).after(&system_prompt(
+            analysis_prompt(config.structured_output),
+            &config,
+        ));
         Ok(Self {
             llm,
             structured_output: config.structured_output,
+            output_tool: tool,
+            stream_llm,
+            budget,
             config,
         })
     }
+
+    /// The documentation passages for an answer about `records`, when the analyst has any.
+    fn doc_passages(&self, question: &str, summary: &str, records: &[Value]) -> Vec<&DocPassage> {
+        match &self.config.docs {
+            Some(docs) => docs.search_results(question, summary, records),
+            None => Vec::new(),
+        }
+    }
+
+    /// Parses a structured answer, asking `llm` once more when it is not valid JSON.
+    async fn parse_or_reask(
+        &self,
+        llm: &dyn ::llm::LLMProvider,
+        messages: &[ChatMessage],
+        text: &str,
+        usage: Option<LlmUsage>,
+    ) -> CliResult<AnalysisResult> {
+        let err = match parse_structured_analysis(text) {
+            Ok(mut result) => {
+                result.usage = usage;
+                return Ok(result);
+            }
+            Err(err) => err,
+        };
+        tracing::warn!("Analysis is not valid JSON, asking again: {err}");
+        let span_model = SpanModel::from(&self.config);
+        let (retry, retry_usage) =
+            reask_for_json(llm, messages, text, &err.to_string(), &span_model).await?;
+        let mut result = parse_structured_analysis(&retry)?;
+        result.usage = sum_usage(usage, retry_usage);
+        Ok(result)
+    }
+}
+
+/// `prompt` with the operator's instructions and the answer language of `config`.
+fn system_prompt(prompt: String, config: &LlmConfig) -> String {
+    with_answer_language(
+        with_instructions(prompt, config.instructions.as_deref()),
+        config.answer_language.as_deref(),
+    )
+}
+
+/// Builds the analyst's provider; structured output goes through a forced tool call when
+/// `tools` is set and the backend supports it, through the JSON schema otherwise.
+fn build_analysis_provider(
+    config: &LlmConfig,
+    tools: bool,
+) -> CliResult<(Box<dyn ::llm::LLMProvider>, Option<Tool>)> {
+    let mut builder = LLMBuilder::new()
+        .backend(config.backend.clone())
+        .model(config.model.clone())
+        .timeout_seconds(config.timeout_secs)
+        .normalize_response(true)
+        .system(system_prompt(
+            analysis_prompt(config.structured_output),
+            config,
+        ));
+
+    let mut tool = None;
+    if config.structured_output && tools && supports_tool_calls(&config.backend) {
+        let analysis_tool = output_tool(analysis_schema());
+        builder = builder.tool_choice(ToolChoice::Tool(analysis_tool.function.name.clone()));
+        tool = Some(analysis_tool);
+    } else if config.structured_output {
+        builder = builder.schema(analysis_schema());
+    }
+
+    if !config.base_url.trim().is_empty() {
+        builder = builder.base_url(config.base_url.clone());
+    }
+    if let Some(api_key) = &config.api_key {
+        builder = builder.api_key(api_key.clone());
+    }
+
+    Ok((builder.build()?, tool))
 }
 
 #[async_trait]
@@ -79,33 +220,138 @@ impl Analyst for SreAnalyst {
         context: &[ConversationTurn],
         context_summary: Option<&str>,
     ) -> CliResult<AnalysisResult> {
-        let messages =
-            build_analysis_messages(question, cypher, records, summary, context, context_summary);
-        let response = match self.llm.chat(&messages).await {
+        let passages = self.doc_passages(question, summary, records);
+        let messages = build_analysis_messages(
+            question,
+            cypher,
+            records,
+            summary,
+            context,
+            context_summary,
+            &passages,
+            self.budget,
+        );
+        let span = LlmSpan::start("analyze", &SpanModel::from(&self.config));
+        let response = match &self.output_tool {
+            Some(tool) => {
+                self.llm
+                    .chat_with_tools(&messages, Some(std::slice::from_ref(tool)))
+                    .await
+            }
+            None => self.llm.chat(&messages).await,
+        };
+        let response = match response {
             Ok(response) => response,
-            Err(err) => return Err(map_llm_error(err, self.structured_output)),
+            Err(err) => {
+                span.fail(&err);
+                return Err(map_llm_error(err, self.structured_output));
+            }
         };
         let usage = response.usage().map(LlmUsage::from);
+        span.finish(usage.as_ref());
+        if let Some(tool) = &self.output_tool {
+            let payload = tool_call_arguments(response.as_ref(), &tool.function.name)?;
+            let payload: AnalysisPayload = serde_json::from_value(payload)
+                .map_err(|e| format!("Invalid {} tool arguments: {e}", tool.function.name))?;
+            let mut result = payload.into_result();
+            result.usage = usage;
+            return Ok(with_citations(result, &passages));
+        }
         let text = response
             .text()
             .ok_or_else(|| "LLM response missing text".to_string())?;
-        let mut result = if self.structured_output {
-            parse_structured_analysis(&text)?
+        if self.structured_output {
+            return self
+                .parse_or_reask(self.llm.as_ref(), &messages, &text, usage)
+                .await
+                .map(|result| with_citations(result, &passages));
+        }
+        let mut result = parse_unstructured_analysis(&text);
+        result.usage = usage;
+        Ok(with_citations(result, &passages))
+    }
+
+    async fn analyze_streaming(
+        &self,
+        question: &str,
+        cypher: &str,
+        records: &[Value],
+        summary: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+        on_partial: AnalysisSink<'_>,
+    ) -> CliResult<AnalysisResult> {
+        let Some(stream_llm) = &self.stream_llm else {
+            return self
+                .analyze(question, cypher, records, summary, context, context_summary)
+                .await;
+        };
+        let passages = self.doc_passages(question, summary, records);
+        let messages = build_analysis_messages(
+            question,
+            cypher,
+            records,
+            summary,
+            context,
+            context_summary,
+            &passages,
+            self.budget,
+        );
+        let span = LlmSpan::start("analyze", &SpanModel::from(&self.config));
+        let mut stream = match stream_llm.chat_stream(&messages).await {
+            Ok(stream) => stream,
+            Err(err) => {
+                tracing::warn!("Cannot stream the analysis, waiting for the whole answer: {err}");
+                return self
+                    .analyze(question, cypher, records, summary, context, context_summary)
+                    .await;
+            }
+        };
+        let mut text = String::new();
+        let mut shown: Option<AnalysisResult> = None;
+        while let Some(chunk) = stream.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(err) => {
+                    span.fail(&err);
+                    return Err(map_llm_error(err, self.structured_output));
+                }
+            };
+            text.push_str(&chunk);
+            let partial = if self.structured_output {
+                parse_partial_analysis(&text)
+            } else {
+                Some(parse_unstructured_analysis(&text))
+            };
+            if let Some(partial) = partial.filter(|partial| shown.as_ref() != Some(partial)) {
+                on_partial(partial.clone());
+                shown = Some(partial);
+            }
+        }
+        // Streamed responses carry no usage, so the answer is returned without it.
+        span.finish(None);
+        let result = if self.structured_output {
+            self.parse_or_reask(stream_llm.as_ref(), &messages, &text, None)
+                .await?
         } else {
             parse_unstructured_analysis(&text)
         };
-        result.usage = usage;
-        Ok(result)
+        Ok(with_citations(result, &passages))
     }
 
     async fn compact_context(&self, context: &[ConversationTurn]) -> CliResult<ContextCompaction> {
         let provider = build_compaction_provider(&self.config)?;
         let messages = build_compaction_messages(context);
+        let span = LlmSpan::start("compact", &SpanModel::from(&self.config));
         let response = match provider.chat(&messages).await {
             Ok(response) => response,
-            Err(err) => return Err(map_llm_error(err, false)),
+            Err(err) => {
+                span.fail(&err);
+                return Err(map_llm_error(err, false));
+            }
         };
         let usage = response.usage().map(LlmUsage::from);
+        span.finish(usage.as_ref());
         let text = response
             .text()
             .ok_or_else(|| "LLM response missing text".to_string())?;
@@ -114,6 +360,360 @@ impl Analyst for SreAnalyst {
             usage,
         })
     }
+
+    async fn suggest_followups(
+        &self,
+        records: &[Value],
+        context: &[ConversationTurn],
+    ) -> CliResult<FollowUpSuggestions> {
+        let (provider, tool) = build_followup_provider(&self.config)?;
+        let messages = build_followup_messages(records, context);
+        let span = LlmSpan::start("follow_ups", &SpanModel::from(&self.config));
+        let response = match &tool {
+            Some(tool) => {
+                provider
+                    .chat_with_tools(&messages, Some(std::slice::from_ref(tool)))
+                    .await
+            }
+            None => provider.chat(&messages).await,
+        };
+        let response = match response {
+            Ok(response) => response,
+            Err(err) => {
+                span.fail(&err);
+                return Err(map_llm_error(err, self.structured_output));
+            }
+        };
+        let usage = response.usage().map(LlmUsage::from);
+        span.finish(usage.as_ref());
+        let candidates = match &tool {
+            Some(tool) => {
+                let payload = tool_call_arguments(response.as_ref(), &tool.function.name)?;
+                let payload: FollowUpPayload = serde_json::from_value(payload)
+                    .map_err(|e| format!("Invalid {} tool arguments: {e}", tool.function.name))?;
+                payload.follow_ups
+            }
+            None => {
+                let text = response
+                    .text()
+                    .ok_or_else(|| "LLM response missing text".to_string())?;
+                if self.structured_output {
+                    let payload: FollowUpPayload = parse_json_response(&text)?;
+                    payload.follow_ups
+                } else {
+                    parse_unstructured_followups(&text)
+                }
+            }
+        };
+        Ok(FollowUpSuggestions {
+            follow_ups: grounded_followups(candidates, context),
+            usage,
+        })
+    }
+
+    async fn summarize_session(&self, turns: &[ReportEntry]) -> CliResult<IncidentReport> {
+        let provider = build_session_provider(
+            &self.config,
+            session_report_prompt(self.config.structured_output),
+            report_schema(),
+        )?;
+        let messages = build_report_messages(turns);
+        let span = LlmSpan::start("session_report", &SpanModel::from(&self.config));
+        let response = match provider.chat(&messages).await {
+            Ok(response) => response,
+            Err(err) => {
+                span.fail(&err);
+                return Err(map_llm_error(err, self.structured_output));
+            }
+        };
+        let usage = response.usage().map(LlmUsage::from);
+        span.finish(usage.as_ref());
+        let text = response
+            .text()
+            .ok_or_else(|| "LLM response missing text".to_string())?;
+        let mut report = if self.structured_output {
+            let payload: IncidentReportPayload = parse_json_response(&text)?;
+            IncidentReport {
+                title: payload.title,
+                timeline: payload.timeline,
+                root_cause: payload.root_cause,
+                evidence: payload.evidence,
+                actions: payload.actions,
+                confidence: payload.confidence,
+                usage: None,
+            }
+        } else {
+            parse_unstructured_report(&text)
+        };
+        report.usage = usage;
+        Ok(report)
+    }
+
+    async fn propose_monitors(&self, turns: &[ReportEntry]) -> CliResult<MonitorProposals> {
+        let provider = build_session_provider(
+            &self.config,
+            monitor_prompt(self.config.structured_output, self.config.prompt_profile),
+            monitor_schema(),
+        )?;
+        let messages = build_report_messages(turns);
+        let span = LlmSpan::start("monitors", &SpanModel::from(&self.config));
+        let response = match provider.chat(&messages).await {
+            Ok(response) => response,
+            Err(err) => {
+                span.fail(&err);
+                return Err(map_llm_error(err, self.structured_output));
+            }
+        };
+        let usage = response.usage().map(LlmUsage::from);
+        span.finish(usage.as_ref());
+        let text = response
+            .text()
+            .ok_or_else(|| "LLM response missing text".to_string())?;
+        let candidates = if self.structured_output {
+            let payload: MonitorPayload = parse_json_response(&text)?;
+            payload.monitors
+        } else {
+            parse_unstructured_monitors(&text)
+        };
+        Ok(MonitorProposals {
+            monitors: valid_monitors(candidates),
+            usage,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MonitorPayload {
+    #[serde(default)]
+    monitors: Vec<MonitorCandidate>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MonitorCandidate {
+    name: String,
+    query: String,
+    condition: String,
+    #[serde(default)]
+    rationale: String,
+}
+
+/// Reads `Name:`, `Query:`, `Condition:` and `Why:` lines; a `Name:` line starts the next
+/// monitor.
+fn parse_unstructured_monitors(text: &str) -> Vec<MonitorCandidate> {
+    let mut candidates: Vec<MonitorCandidate> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim().trim_start_matches(['-', '•', '*', ' ']);
+        if let Some(rest) = strip_prefix_ignore_case(line, "name:") {
+            candidates.push(MonitorCandidate {
+                name: rest.trim().to_string(),
+                ..MonitorCandidate::default()
+            });
+            continue;
+        }
+        let Some(candidate) = candidates.last_mut() else {
+            continue;
+        };
+        if let Some(rest) = strip_prefix_ignore_case(line, "query:") {
+            candidate.query = rest.trim().to_string();
+        } else if let Some(rest) = strip_prefix_ignore_case(line, "condition:") {
+            candidate.condition = rest.trim().to_string();
+        } else if let Some(rest) = strip_prefix_ignore_case(line, "why:") {
+            candidate.rationale = rest.trim().to_string();
+        }
+    }
+    candidates
+}
+
+/// The candidates that can become alert rules: a query that passes the validator and names
+/// only known labels, a condition that parses, and a name not taken by an earlier one.
+fn valid_monitors(candidates: Vec<MonitorCandidate>) -> Vec<ProposedMonitor> {
+    let mut monitors: Vec<ProposedMonitor> = Vec::new();
+    for candidate in candidates {
+        let name = candidate.name.trim().to_string();
+        let query = extract_cypher(&candidate.query);
+        if name.is_empty()
+            || query.is_empty()
+            || monitors
+                .iter()
+                .any(|monitor| monitor.name.eq_ignore_ascii_case(&name))
+        {
+            continue;
+        }
+        if let Err(issue) = validate_cypher(&query) {
+            tracing::debug!("Dropping monitor '{name}': {issue}");
+            continue;
+        }
+        let unknown = unknown_labels(&query);
+        if !unknown.is_empty() {
+            tracing::debug!("Dropping monitor '{name}': unknown labels {unknown:?}");
+            continue;
+        }
+        let condition = match AlertCondition::parse(&candidate.condition) {
+            Ok(condition) => condition.source,
+            Err(err) => {
+                tracing::debug!("Dropping monitor '{name}': {err}");
+                continue;
+            }
+        };
+        monitors.push(ProposedMonitor {
+            name,
+            query,
+            condition,
+            rationale: candidate.rationale.trim().to_string(),
+        });
+        if monitors.len() == MAX_MONITORS {
+            break;
+        }
+    }
+    monitors
+}
+
+#[derive(Debug, Deserialize)]
+struct IncidentReportPayload {
+    title: String,
+    #[serde(default)]
+    timeline: Vec<String>,
+    root_cause: String,
+    #[serde(default)]
+    evidence: Vec<String>,
+    #[serde(default)]
+    actions: Vec<String>,
+    #[serde(default)]
+    confidence: String,
+}
+
+/// Reads the `Title:`, `Timeline:`, `Root cause:`, `Evidence:`, `Actions:` and `Confidence:`
+/// sections of a report written without structured output.
+fn parse_unstructured_report(text: &str) -> IncidentReport {
+    let mut report = IncidentReport {
+        title: String::new(),
+        timeline: Vec::new(),
+        root_cause: String::new(),
+        evidence: Vec::new(),
+        actions: Vec::new(),
+        confidence: String::new(),
+        usage: None,
+    };
+    let mut section = "";
+    for line in text.lines() {
+        let line = line.trim().trim_start_matches('#').trim();
+        if line.is_empty() {
+            continue;
+        }
+        let heading = [
+            "title:",
+            "timeline:",
+            "root cause:",
+            "evidence:",
+            "actions:",
+            "confidence:",
+        ]
+        .into_iter()
+        .find_map(|heading| Some((heading, strip_prefix_ignore_case(line, heading)?)));
+        let content = match heading {
+            Some((heading, rest)) => {
+                section = heading;
+                rest.trim()
+            }
+            None => line,
+        };
+        if content.is_empty() {
+            continue;
+        }
+        let item = content
+            .trim_start_matches(['-', '•', '*'])
+            .trim()
+            .to_string();
+        match section {
+            "title:" => append_line(&mut report.title, &item),
+            "timeline:" => report.timeline.push(item),
+            "root cause:" => append_line(&mut report.root_cause, &item),
+            "evidence:" => report.evidence.push(item),
+            "actions:" => report.actions.push(item),
+            "confidence:" => append_line(&mut report.confidence, &item),
+            _ => {}
+        }
+    }
+    report
+}
+
+fn append_line(text: &mut String, line: &str) {
+    if !text.is_empty() {
+        text.push(' ');
+    }
+    text.push_str(line);
+}
+
+#[derive(Debug, Deserialize)]
+struct FollowUpPayload {
+    #[serde(default)]
+    follow_ups: Vec<FollowUpCandidate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FollowUpCandidate {
+    question: String,
+    cypher: String,
+}
+
+fn parse_unstructured_followups(text: &str) -> Vec<FollowUpCandidate> {
+    let mut candidates = Vec::new();
+    let mut question: Option<String> = None;
+    for line in text.lines() {
+        let line = line.trim().trim_start_matches(['-', '•', '*', ' ']);
+        if let Some(rest) = strip_prefix_ignore_case(line, "question:") {
+            question = Some(rest.trim().to_string());
+        } else if let Some(rest) = strip_prefix_ignore_case(line, "cypher:") {
+            if let Some(question) = question.take() {
+                candidates.push(FollowUpCandidate {
+                    question,
+                    cypher: rest.trim().to_string(),
+                });
+            }
+        }
+    }
+    candidates
+}
+
+fn strip_prefix_ignore_case<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = line.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &line[prefix.len()..])
+}
+
+/// The candidates worth showing: a query that passes the validator and names only known labels,
+/// and a question that was not asked before or suggested twice.
+fn grounded_followups(
+    candidates: Vec<FollowUpCandidate>,
+    context: &[ConversationTurn],
+) -> Vec<FollowUp> {
+    let mut seen: Vec<String> = context
+        .iter()
+        .map(|turn| turn.question.trim().to_lowercase())
+        .collect();
+    let mut follow_ups = Vec::new();
+    for candidate in candidates {
+        let question = candidate.question.trim().to_string();
+        let cypher = extract_cypher(&candidate.cypher);
+        if question.is_empty() || cypher.is_empty() || seen.contains(&question.to_lowercase()) {
+            continue;
+        }
+        if let Err(issue) = validate_cypher(&cypher) {
+            tracing::debug!("Dropping follow-up '{question}': {issue}");
+            continue;
+        }
+        let unknown = unknown_labels(&cypher);
+        if !unknown.is_empty() {
+            tracing::debug!("Dropping follow-up '{question}': unknown labels {unknown:?}");
+            continue;
+        }
+        seen.push(question.to_lowercase());
+        follow_ups.push(FollowUp { question, cypher });
+        if follow_ups.len() == MAX_FOLLOW_UPS {
+            break;
+        }
+    }
+    follow_ups
 }
 
 #[derive(Debug, Deserialize)]
@@ -130,18 +730,144 @@ struct AnalysisPayload {
 }
 
 fn parse_structured_analysis(text: &str) -> CliResult<AnalysisResult> {
-    let cleaned = clean_json_response(text);
-    let payload: AnalysisPayload =
-        serde_json::from_str(&cleaned).map_err(|e| format!("Invalid JSON response: {e}"))?;
-    Ok(AnalysisResult {
-        title: payload.title.trim().to_string(),
-        summary: payload.summary.trim().to_string(),
-        bullets: payload.bullets,
-        rows: payload.rows,
-        follow_ups: payload.follow_ups,
-        confidence: payload.confidence.trim().to_string(),
-        usage: None,
-    })
+    let payload: AnalysisPayload = parse_json_response(text)?;
+    Ok(payload.into_result())
+}
+
+/// [`AnalysisPayload`] of an answer that is still being written: every key may be missing.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PartialAnalysisPayload {
+    title: String,
+    summary: String,
+    bullets: Vec<String>,
+    rows: Vec<Value>,
+    follow_ups: Vec<String>,
+    confidence: String,
+}
+
+/// The sections of a streamed JSON answer received so far, including the text of a string
+/// that is still being written. `None` until the object has started.
+fn parse_partial_analysis(text: &str) -> Option<AnalysisResult> {
+    close_partial_json(text)
+        .into_iter()
+        .find_map(|json| serde_json::from_str::<PartialAnalysisPayload>(&json).ok())
+        .map(|payload| AnalysisResult {
+            title: payload.title.trim().to_string(),
+            summary: payload.summary.trim().to_string(),
+            bullets: payload.bullets,
+            rows: payload.rows,
+            follow_ups: payload.follow_ups,
+            confidence: payload.confidence.trim().to_string(),
+            citations: Vec::new(),
+            usage: None,
+        })
+}
+
+#[derive(Debug, Clone, Copy)]
+enum OpenJson {
+    Object { expects_key: bool },
+    Array,
+}
+
+/// Ways to close the JSON object at the start of the truncated `text`, most complete first:
+/// with the string value being written ended where the text stops, and cut back to the last
+/// complete value.
+fn close_partial_json(text: &str) -> Vec<String> {
+    let Some(start) = text.find('{') else {
+        return Vec::new();
+    };
+    let text = &text[start..];
+    let closers = |stack: &[OpenJson]| -> String {
+        stack
+            .iter()
+            .rev()
+            .map(|open| match open {
+                OpenJson::Object { .. } => '}',
+                OpenJson::Array => ']',
+            })
+            .collect()
+    };
+    let mut stack: Vec<OpenJson> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut string_is_key = false;
+    let mut complete: Option<String> = None;
+    for (index, c) in text.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+                if !string_is_key {
+                    complete = Some(format!("{}{}", &text[..=index], closers(&stack)));
+                }
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                string_is_key =
+                    matches!(stack.last(), Some(OpenJson::Object { expects_key: true }));
+            }
+            '{' | '[' => {
+                stack.push(if c == '{' {
+                    OpenJson::Object { expects_key: true }
+                } else {
+                    OpenJson::Array
+                });
+                complete = Some(format!("{}{}", &text[..=index], closers(&stack)));
+            }
+            '}' | ']' => {
+                stack.pop();
+                if stack.is_empty() {
+                    return vec![text[..=index].to_string()];
+                }
+                complete = Some(format!("{}{}", &text[..=index], closers(&stack)));
+            }
+            ':' => {
+                if let Some(OpenJson::Object { expects_key }) = stack.last_mut() {
+                    *expects_key = false;
+                }
+            }
+            ',' => {
+                complete = Some(format!("{}{}", &text[..index], closers(&stack)));
+                if let Some(OpenJson::Object { expects_key }) = stack.last_mut() {
+                    *expects_key = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut candidates = Vec::new();
+    if in_string && !string_is_key {
+        let written = if escaped {
+            &text[..text.len() - 1]
+        } else {
+            text
+        };
+        candidates.push(format!("{written}\"{}", closers(&stack)));
+    }
+    candidates.extend(complete);
+    candidates
+}
+
+impl AnalysisPayload {
+    fn into_result(self) -> AnalysisResult {
+        AnalysisResult {
+            title: self.title.trim().to_string(),
+            summary: self.summary.trim().to_string(),
+            bullets: self.bullets,
+            rows: self.rows,
+            follow_ups: self.follow_ups,
+            confidence: self.confidence.trim().to_string(),
+            citations: Vec::new(),
+            usage: None,
+        }
+    }
 }
 
 fn parse_unstructured_analysis(text: &str) -> AnalysisResult {
@@ -192,10 +918,23 @@ fn parse_unstructured_analysis(text: &str) -> AnalysisResult {
         rows: Vec::new(),
         follow_ups,
         confidence: confidence.unwrap_or_else(|| "unknown".to_string()),
+        citations: Vec::new(),
         usage: None,
     }
 }
 
+/// `result` with the passages its summary and bullets cite.
+fn with_citations(mut result: AnalysisResult, passages: &[&DocPassage]) -> AnalysisResult {
+    let text = std::iter::once(&result.summary)
+        .chain(&result.bullets)
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join("\n");
+    result.citations = cited_passages(passages, &text);
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
 fn build_analysis_messages(
     question: &str,
     cypher: &str,
@@ -203,8 +942,9 @@ fn build_analysis_messages(
     summary: &str,
     context: &[ConversationTurn],
     context_summary: Option<&str>,
+    passages: &[&DocPassage],
+    budget: TokenBudget,
 ) -> Vec<ChatMessage> {
-    let records_json = serde_json::to_string(records).unwrap_or_else(|_| "[]".to_string());
     let mut body = String::new();
     if let Some(summary) = context_summary {
         let summary = summary.trim();
@@ -239,6 +979,21 @@ fn build_analysis_messages(
             body.push('\n');
         }
     }
+    if !passages.is_empty() {
+        body.push_str(
+            "Kubernetes documentation (when a passage explains something in your answer, cite \
+             it by its number, like [1]):\n",
+        );
+        for (index, passage) in passages.iter().enumerate() {
+            body.push_str(&format!(
+                "[{}] {}: {}\n",
+                index + 1,
+                passage.title,
+                passage.text
+            ));
+        }
+        body.push('\n');
+    }
     body.push_str("Question:\n");
     body.push_str(question.trim());
     body.push_str("\n\nCypher:\n");
@@ -246,6 +1001,7 @@ fn build_analysis_messages(
     body.push_str("\n\nResult summary:\n");
     body.push_str(summary.trim());
     body.push_str("\n\nRows (JSON):\n");
+    let records_json = budget.fit_records(records, estimate_tokens(&body));
     body.push_str(&records_json);
     vec![ChatMessage::user().content(body).build()]
 }
@@ -269,6 +1025,134 @@ fn build_compaction_provider(config: &LlmConfig) -> CliResult<Box<dyn ::llm::LLM
     Ok(llm)
 }
 
+fn build_followup_provider(
+    config: &LlmConfig,
+) -> CliResult<(Box<dyn ::llm::LLMProvider>, Option<Tool>)> {
+    let mut builder = LLMBuilder::new()
+        .backend(config.backend.clone())
+        .model(config.model.clone())
+        .timeout_seconds(config.timeout_secs)
+        .normalize_response(true)
+        .system(system_prompt(
+            followup_prompt(config.structured_output, config.prompt_profile),
+            config,
+        ));
+
+    let mut tool = None;
+    if config.structured_output && supports_tool_calls(&config.backend) {
+        let followup_tool = output_tool(followup_schema());
+        builder = builder.tool_choice(ToolChoice::Tool(followup_tool.function.name.clone()));
+        tool = Some(followup_tool);
+    } else if config.structured_output {
+        builder = builder.schema(followup_schema());
+    }
+
+    if !config.base_url.trim().is_empty() {
+        builder = builder.base_url(config.base_url.clone());
+    }
+    if let Some(api_key) = &config.api_key {
+        builder = builder.api_key(api_key.clone());
+    }
+
+    Ok((builder.build()?, tool))
+}
+
+fn build_followup_messages(records: &[Value], context: &[ConversationTurn]) -> Vec<ChatMessage> {
+    let mut body = String::from("Conversation so far:\n");
+    for (idx, turn) in context.iter().enumerate() {
+        body.push_str(&format!("\nTurn {}:\n", idx + 1));
+        body.push_str("User: ");
+        body.push_str(turn.question.trim());
+        body.push_str("\nCypher: ");
+        body.push_str(turn.cypher.trim());
+        body.push('\n');
+        if let Some(summary) = &turn.result_summary {
+            if !summary.trim().is_empty() {
+                body.push_str("Result summary: ");
+                body.push_str(summary.trim());
+                body.push('\n');
+            }
+        }
+    }
+    let sample = &records[..records.len().min(FOLLOW_UP_SAMPLE_ROWS)];
+    let sample_json = serde_json::to_string(sample).unwrap_or_else(|_| "[]".to_string());
+    body.push_str(&format!(
+        "\nRows of the latest query ({} of {}, JSON):\n{sample_json}",
+        sample.len(),
+        records.len()
+    ));
+    vec![ChatMessage::user().content(body).build()]
+}
+
+/// Builds a provider for a task over the whole session, such as the incident report, with
+/// `prompt` as its system prompt and `schema` for structured output.
+fn build_session_provider(
+    config: &LlmConfig,
+    prompt: String,
+    schema: StructuredOutputFormat,
+) -> CliResult<Box<dyn ::llm::LLMProvider>> {
+    let mut builder = LLMBuilder::new()
+        .backend(config.backend.clone())
+        .model(config.model.clone())
+        .timeout_seconds(config.timeout_secs)
+        .normalize_response(true)
+        .system(system_prompt(prompt, config));
+
+    if config.structured_output {
+        builder = builder.schema(schema);
+    }
+    if !config.base_url.trim().is_empty() {
+        builder = builder.base_url(config.base_url.clone());
+    }
+    if let Some(api_key) = &config.api_key {
+        builder = builder.api_key(api_key.clone());
+    }
+
+    Ok(builder.build()?)
+}
+
+fn build_report_messages(turns: &[ReportEntry]) -> Vec<ChatMessage> {
+    let mut body = String::from("Investigation session:\n");
+    for (idx, turn) in turns.iter().enumerate() {
+        body.push_str(&format!(
+            "\nTurn {} at {}:\nUser: {}\n",
+            idx + 1,
+            turn.asked_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            turn.question.trim()
+        ));
+        if let Some(cypher) = &turn.cypher {
+            body.push_str("Cypher: ");
+            body.push_str(cypher.trim());
+            body.push('\n');
+        }
+        if let Some(error) = &turn.error {
+            body.push_str("Error: ");
+            body.push_str(error.trim());
+            body.push('\n');
+        } else if turn.cypher.is_some() {
+            let sample = &turn.records[..turn.records.len().min(REPORT_SAMPLE_ROWS)];
+            let sample_json = serde_json::to_string(sample).unwrap_or_else(|_| "[]".to_string());
+            body.push_str(&format!(
+                "Rows ({} of {}, JSON): {}\n",
+                sample.len(),
+                turn.records.len(),
+                truncate_text(&sample_json, REPORT_SAMPLE_CHARS)
+            ));
+        }
+        if let Some(analysis) = &turn.analysis {
+            body.push_str(&format!(
+                "Answer ({}): {}\n",
+                analysis.confidence,
+                analysis.summary.trim()
+            ));
+            for bullet in &analysis.bullets {
+                body.push_str(&format!("- {}\n", bullet.trim()));
+            }
+        }
+    }
+    vec![ChatMessage::user().content(body).build()]
+}
+
 fn build_compaction_messages(context: &[ConversationTurn]) -> Vec<ChatMessage> {
     let mut body = String::from(
         "Summarize the following conversation context for reuse in future SRE answers.\n",
@@ -336,6 +1220,92 @@ fn analysis_schema() -> StructuredOutputFormat {
     serde_json::from_str(SCHEMA).expect("invalid SreAnalysis schema JSON")
 }
 
+fn followup_schema() -> StructuredOutputFormat {
+    const SCHEMA: &str = r#"
+    {
+        "name": "FollowUps",
+        "description": "Follow-up questions, each with a Cypher query that answers it",
+        "strict": true,
+        "schema": {
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "follow_ups": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "additionalProperties": false,
+                        "properties": {
+                            "question": { "type": "string" },
+                            "cypher": { "type": "string" }
+                        },
+                        "required": ["question", "cypher"]
+                    }
+                }
+            },
+            "required": ["follow_ups"]
+        }
+    }
+    "#;
+    serde_json::from_str(SCHEMA).expect("invalid FollowUps schema JSON")
+}
+
+fn report_schema() -> StructuredOutputFormat {
+    const SCHEMA: &str = r#"
+    {
+        "name": "IncidentReport",
+        "description": "Incident report of an investigation session",
+        "strict": true,
+        "schema": {
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "title": { "type": "string" },
+                "timeline": { "type": "array", "items": { "type": "string" } },
+                "root_cause": { "type": "string" },
+                "evidence": { "type": "array", "items": { "type": "string" } },
+                "actions": { "type": "array", "items": { "type": "string" } },
+                "confidence": { "type": "string", "enum": ["low", "medium", "high"] }
+            },
+            "required": ["title", "timeline", "root_cause", "evidence", "actions", "confidence"]
+        }
+    }
+    "#;
+    serde_json::from_str(SCHEMA).expect("invalid IncidentReport schema JSON")
+}
+
+fn monitor_schema() -> StructuredOutputFormat {
+    const SCHEMA: &str = r#"
+    {
+        "name": "Monitors",
+        "description": "Alert rules that would catch the problem of an investigation session",
+        "strict": true,
+        "schema": {
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "monitors": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "additionalProperties": false,
+                        "properties": {
+                            "name": { "type": "string" },
+                            "query": { "type": "string" },
+                            "condition": { "type": "string" },
+                            "rationale": { "type": "string" }
+                        },
+                        "required": ["name", "query", "condition", "rationale"]
+                    }
+                }
+            },
+            "required": ["monitors"]
+        }
+    }
+    "#;
+    serde_json::from_str(SCHEMA).expect("invalid Monitors schema JSON")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -344,5 +1314,131 @@ mod tests {
     fn analysis_schema_parses() {
         let schema = analysis_schema();
         assert_eq!(schema.name, "SreAnalysis");
+        assert_eq!(followup_schema().name, "FollowUps");
+        assert_eq!(monitor_schema().name, "Monitors");
+        assert_eq!(report_schema().name, "IncidentReport");
+    }
+
+    #[test]
+    fn only_valid_and_new_followups_are_kept() {
+        let candidate = |question: &str, cypher: &str| FollowUpCandidate {
+            question: question.to_string(),
+            cypher: cypher.to_string(),
+        };
+        let context = vec![ConversationTurn {
+            question: "Which pods are pending?".to_string(),
+            cypher: "MATCH (p:Pod) RETURN p".to_string(),
+            result_summary: None,
+            bindings: None,
+        }];
+        let follow_ups = grounded_followups(
+            vec![
+                candidate(
+                    "Which nodes do they run on?",
+                    "```cypher\nMATCH (p:Pod)-[:RunsOn]->(n:Node) RETURN n\n```",
+                ),
+                candidate("which pods are pending?", "MATCH (p:Pod) RETURN p"),
+                candidate("Delete them", "MATCH (p:Pod) DETACH DELETE p"),
+                candidate(
+                    "Which services?",
+                    "MATCH (p:Pod)-[:RunsOn]->(s:Service) RETURN s",
+                ),
+                candidate("Which widgets?", "MATCH (w:Widget) RETURN w"),
+            ],
+            &context,
+        );
+        assert_eq!(
+            follow_ups,
+            [FollowUp {
+                question: "Which nodes do they run on?".to_string(),
+                cypher: "MATCH (p:Pod)-[:RunsOn]->(n:Node) RETURN n".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn partial_answers_show_the_sections_written_so_far() {
+        assert_eq!(parse_partial_analysis("```json\n"), None);
+
+        let partial =
+            parse_partial_analysis(r#"{"title": "Pending pods", "summary": "Two pods wa"#).unwrap();
+        assert_eq!(partial.title, "Pending pods");
+        assert_eq!(partial.summary, "Two pods wa");
+        assert!(partial.bullets.is_empty());
+
+        let partial = parse_partial_analysis(
+            r#"{"title": "Pending", "summary": "Two wait.", "bullets": ["web-1 idle", "web-2"#,
+        )
+        .unwrap();
+        assert_eq!(partial.bullets, ["web-1 idle", "web-2"]);
+
+        let partial = parse_partial_analysis(r#"{"title": "Pending", "confid"#).unwrap();
+        assert_eq!(partial.title, "Pending");
+        assert_eq!(partial.confidence, "");
+
+        let partial =
+            parse_partial_analysis(r#"{"title": "A", "rows": [{"pod": "web-1", "phase": "Pen"#)
+                .unwrap();
+        assert_eq!(partial.rows.len(), 1);
+    }
+
+    #[test]
+    fn unstructured_followups_pair_questions_with_queries() {
+        let text = "Question: Which node runs web-1?\n\
+Cypher: MATCH (p:Pod)-[:RunsOn]->(n:Node) RETURN n\n\
+- question: Orphan without a query\n\
+- Question: Events for web-1?\n\
+- Cypher: MATCH (e:Event)-[:Concerns]->(p:Pod) RETURN e";
+        let candidates = parse_unstructured_followups(text);
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].question, "Which node runs web-1?");
+        assert_eq!(candidates[1].question, "Events for web-1?");
+    }
+
+    #[test]
+    fn proposed_monitors_need_a_valid_query_and_condition() {
+        let text = "Name: Pending pods\n\
+Query: MATCH (p:Pod) WHERE p.status.phase = 'Pending' RETURN p.metadata.name AS name\n\
+Condition: rows > 0\n\
+Why: checkout pods stayed Pending\n\
+- Name: Deleting monitor\n\
+- Query: MATCH (p:Pod) DETACH DELETE p\n\
+- Condition: rows > 0\n\
+Name: Vague monitor\n\
+Query: MATCH (p:Pod) RETURN p\n\
+Condition: rows are many";
+        let monitors = valid_monitors(parse_unstructured_monitors(text));
+        assert_eq!(monitors.len(), 1);
+        assert_eq!(monitors[0].name, "Pending pods");
+        assert_eq!(monitors[0].condition, "rows > 0");
+        assert_eq!(monitors[0].rationale, "checkout pods stayed Pending");
+    }
+
+    #[test]
+    fn unstructured_reports_are_split_into_sections() {
+        let report = parse_unstructured_report(
+            "Here is the report.\n\
+## Title: checkout outage\n\
+Timeline:\n\
+- 10:02 payments-api pods start crashing\n\
+- 10:05 checkout returns 502\n\
+Root cause:\n\
+The payments-api rollout\n\
+lowered the memory limit.\n\
+Evidence:\n\
+- web-1 was OOMKilled 12 times\n\
+Actions: \n\
+- Restore the memory limit\n\
+Confidence: medium",
+        );
+        assert_eq!(report.title, "checkout outage");
+        assert_eq!(report.timeline.len(), 2);
+        assert_eq!(
+            report.root_cause,
+            "The payments-api rollout lowered the memory limit."
+        );
+        assert_eq!(report.evidence, ["web-1 was OOMKilled 12 times"]);
+        assert_eq!(report.actions, ["Restore the memory limit"]);
+        assert_eq!(report.confidence, "medium");
     }
 }
@@ -13,6 +13,8 @@ struct ContextWindowConfig {
     providers: HashMap<String, HashMap<String, usize>>,
 }
 
+/// Context window of `model`: `LLM_CONTEXT_WINDOW_TOKENS` when set, then what the model's
+/// server reported (see [`remember_served_context_windows`]), then the config table.
 pub fn context_window_tokens_for_model(model: &str) -> Option<usize> {
     if let Ok(raw) = env::var("LLM_CONTEXT_WINDOW_TOKENS") {
         match raw.trim().parse::<usize>() {
@@ -20,11 +22,24 @@ pub fn context_window_tokens_for_model(model: &str) -> Option<usize> {
             _ => warn!("LLM_CONTEXT_WINDOW_TOKENS is not a valid positive integer: {raw}"),
         }
     }
+    if let Some(tokens) = SERVED_WINDOWS.get().and_then(|windows| windows.get(model)) {
+        return Some(*tokens);
+    }
 
     let config = context_window_config()?;
     resolve_context_window_tokens(model, config)
 }
 
+static SERVED_WINDOWS: OnceLock<HashMap<String, usize>> = OnceLock::new();
+
+/// Records context windows reported by a local model server at startup. Only the first call
+/// takes effect.
+pub fn remember_served_context_windows(windows: HashMap<String, usize>) {
+    if SERVED_WINDOWS.set(windows).is_err() {
+        warn!("Served context windows were already recorded");
+    }
+}
+
 fn context_window_config() -> Option<&'static ContextWindowConfig> {
     static CONFIG: OnceLock<Option<ContextWindowConfig>> = OnceLock::new();
     CONFIG.get_or_init(read_context_window_config).as_ref()
@@ -0,0 +1,305 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use ariadne_core::errors::AriadneError;
+use ariadne_core::graph_backend::GraphBackend;
+
+use crate::agent::agentic::Agentic;
+use crate::agent::analyst::{AnalysisSink, Analyst};
+use crate::agent::router::Router;
+use crate::agent::tools::ToolRegistry;
+use crate::agent::translator::Translator;
+use crate::agent::types::{
+    AgentPlan, AnalysisResult, ContextCompaction, ConversationTurn, EntityCandidate, EntityHint,
+    FewShotExample, FollowUpSuggestions, IncidentReport, LlmUsage, MonitorProposals, RouteResult,
+    Translation,
+};
+use crate::agent::util::is_transient_llm_error;
+use crate::error::CliResult;
+use crate::pipeline::ReportEntry;
+
+/// Whether a call that failed with `err` is worth repeating on the next model of the chain:
+/// a retriable [`AriadneError`], or an unclassified error that reads like a transient one.
+fn is_failover_error(err: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    match err.downcast_ref::<AriadneError>() {
+        Some(err) => err.is_retriable(),
+        None => is_transient_llm_error(&err.to_string()),
+    }
+}
+
+/// One agent per model of [`LlmConfig::failover_chain`](crate::agent::LlmConfig), tried in
+/// order: a call moves on to the next model when it times out, is rate limited or hits a
+/// server error.
+pub struct Failover<T: ?Sized> {
+    /// Name of each model and its agent, the configured model first.
+    links: Vec<(String, Arc<T>)>,
+}
+
+impl<T: ?Sized + Send + Sync> Failover<T> {
+    pub fn new(links: Vec<(String, Arc<T>)>) -> Self {
+        Self { links }
+    }
+
+    /// Runs `call` on each model in turn until one answers or fails with an error the next
+    /// would fail with too. Answers from a fallback name it in their usage.
+    async fn call<'a, R, F, Fut>(&'a self, call: F) -> CliResult<R>
+    where
+        R: Served + Send,
+        F: Fn(&'a T) -> Fut + Send + Sync,
+        Fut: Future<Output = CliResult<R>> + Send,
+    {
+        for (index, (model, agent)) in self.links.iter().enumerate() {
+            let next = self.links.get(index + 1);
+            match call(agent.as_ref()).await {
+                Ok(mut result) => {
+                    if index > 0 {
+                        result
+                            .usage_mut()
+                            .get_or_insert_with(LlmUsage::default)
+                            .model = Some(model.clone());
+                    }
+                    return Ok(result);
+                }
+                Err(err) => match next {
+                    Some((next, _)) if is_failover_error(err.as_ref()) => {
+                        tracing::warn!("{model} failed, trying {next}: {err}");
+                    }
+                    _ => return Err(err),
+                },
+            }
+        }
+        Err("no model configured".into())
+    }
+}
+
+/// Results whose usage records the model that served them.
+trait Served {
+    fn usage_mut(&mut self) -> &mut Option<LlmUsage>;
+}
+
+impl Served for Translation {
+    fn usage_mut(&mut self) -> &mut Option<LlmUsage> {
+        match self {
+            Translation::Query(result) => &mut result.usage,
+            Translation::NeedsClarification { usage, .. } => usage,
+        }
+    }
+}
+
+macro_rules! served_via_usage_field {
+    ($($result:ty),*) => {
+        $(impl Served for $result {
+            fn usage_mut(&mut self) -> &mut Option<LlmUsage> {
+                &mut self.usage
+            }
+        })*
+    };
+}
+
+served_via_usage_field!(
+    RouteResult,
+    AgentPlan,
+    AnalysisResult,
+    ContextCompaction,
+    FollowUpSuggestions,
+    IncidentReport,
+    MonitorProposals
+);
+
+#[async_trait]
+impl Translator for Failover<dyn Translator> {
+    #[allow(clippy::too_many_arguments)]
+    async fn translate(
+        &self,
+        question: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+        feedback: Option<&str>,
+        examples: &[FewShotExample],
+        hints: &[EntityHint],
+        candidates: &[EntityCandidate],
+    ) -> CliResult<Translation> {
+        self.call(|translator| {
+            translator.translate(
+                question,
+                context,
+                context_summary,
+                feedback,
+                examples,
+                hints,
+                candidates,
+            )
+        })
+        .await
+    }
+
+    /// The configured model's: translations cached while a fallback stood in are kept for it.
+    fn schema_version(&self) -> u64 {
+        self.links[0].1.schema_version()
+    }
+}
+
+#[async_trait]
+impl Router for Failover<dyn Router> {
+    async fn classify(
+        &self,
+        question: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+    ) -> CliResult<RouteResult> {
+        self.call(|router| router.classify(question, context, context_summary))
+            .await
+    }
+}
+
+#[async_trait]
+impl Agentic for Failover<dyn Agentic> {
+    async fn plan(
+        &self,
+        question: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+        backend: &dyn GraphBackend,
+        tools: &ToolRegistry,
+    ) -> CliResult<AgentPlan> {
+        self.call(|agentic| agentic.plan(question, context, context_summary, backend, tools))
+            .await
+    }
+}
+
+#[async_trait]
+impl Analyst for Failover<dyn Analyst> {
+    async fn analyze(
+        &self,
+        question: &str,
+        cypher: &str,
+        records: &[Value],
+        summary: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+    ) -> CliResult<AnalysisResult> {
+        self.call(|analyst| {
+            analyst.analyze(question, cypher, records, summary, context, context_summary)
+        })
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn analyze_streaming(
+        &self,
+        question: &str,
+        cypher: &str,
+        records: &[Value],
+        summary: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+        on_partial: AnalysisSink<'_>,
+    ) -> CliResult<AnalysisResult> {
+        self.call(|analyst| {
+            analyst.analyze_streaming(
+                question,
+                cypher,
+                records,
+                summary,
+                context,
+                context_summary,
+                on_partial,
+            )
+        })
+        .await
+    }
+
+    async fn compact_context(&self, context: &[ConversationTurn]) -> CliResult<ContextCompaction> {
+        self.call(|analyst| analyst.compact_context(context)).await
+    }
+
+    async fn suggest_followups(
+        &self,
+        records: &[Value],
+        context: &[ConversationTurn],
+    ) -> CliResult<FollowUpSuggestions> {
+        self.call(|analyst| analyst.suggest_followups(records, context))
+            .await
+    }
+
+    async fn summarize_session(&self, turns: &[ReportEntry]) -> CliResult<IncidentReport> {
+        self.call(|analyst| analyst.summarize_session(turns)).await
+    }
+
+    async fn propose_monitors(&self, turns: &[ReportEntry]) -> CliResult<MonitorProposals> {
+        self.call(|analyst| analyst.propose_monitors(turns)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::types::RouteDecision;
+
+    /// Fails with `error` when one is set, else answers directly.
+    struct FakeRouter {
+        error: Option<&'static str>,
+    }
+
+    #[async_trait]
+    impl Router for FakeRouter {
+        async fn classify(
+            &self,
+            _question: &str,
+            _context: &[ConversationTurn],
+            _context_summary: Option<&str>,
+        ) -> CliResult<RouteResult> {
+            match self.error {
+                Some(error) => Err(error.into()),
+                None => Ok(RouteResult {
+                    decision: RouteDecision::OneShot,
+                    reply: None,
+                    usage: None,
+                }),
+            }
+        }
+    }
+
+    fn chain(primary_error: &'static str) -> Failover<dyn Router> {
+        Failover::new(vec![
+            (
+                "gpt-4o".to_string(),
+                Arc::new(FakeRouter {
+                    error: Some(primary_error),
+                }) as Arc<dyn Router>,
+            ),
+            (
+                "gpt-4o-mini".to_string(),
+                Arc::new(FakeRouter { error: None }) as Arc<dyn Router>,
+            ),
+        ])
+    }
+
+    #[test]
+    fn server_errors_fail_over_and_rejections_do_not() {
+        let served = futures::executor::block_on(chain("HTTP 503 Service Unavailable").classify(
+            "list pods",
+            &[],
+            None,
+        ))
+        .unwrap();
+        assert_eq!(served.usage.unwrap().model.as_deref(), Some("gpt-4o-mini"));
+
+        let rejected =
+            futures::executor::block_on(chain("invalid api key").classify("list pods", &[], None));
+        assert!(rejected.is_err());
+    }
+
+    #[test]
+    fn classified_errors_fail_over_when_retriable() {
+        let overloaded: Box<dyn std::error::Error + Send + Sync> =
+            Box::new(AriadneError::llm("quota exhausted", true));
+        assert!(is_failover_error(overloaded.as_ref()));
+        let rejected: Box<dyn std::error::Error + Send + Sync> =
+            Box::new(AriadneError::llm("prompt mentions 503", false));
+        assert!(!is_failover_error(rejected.as_ref()));
+    }
+}
@@ -1,15 +1,38 @@
 mod agentic;
 mod analyst;
 mod context;
+mod failover;
+mod models;
+mod ollama;
+#[cfg(feature = "gui")]
+mod pricing;
+mod prompt_files;
 mod prompts;
 mod router;
+mod schema_subset;
+mod token_budget;
+mod tools;
 mod translator;
 mod types;
 mod util;
 
 pub use agentic::{Agentic, LlmAgentic};
-pub use analyst::{Analyst, SreAnalyst};
-pub use context::context_window_tokens_for_model;
+pub use analyst::{AnalysisSink, Analyst, SreAnalyst};
+pub use context::{context_window_tokens_for_model, remember_served_context_windows};
+pub use models::LlmAgents;
+pub use ollama::{discover_models, OllamaModel};
+#[cfg(feature = "gui")]
+pub use pricing::{price_for_model, ModelPrice, UsageCost};
+#[cfg(feature = "gui")]
+pub use prompt_files::PromptFingerprint;
+pub use prompt_files::{install_default_prompts, prompts_dir};
 pub use router::{LlmRouter, Router};
+pub use token_budget::{estimate_tokens, TokenBudget};
+pub use tools::{required_arg, AgentTool, ToolCall, ToolRegistry};
 pub use translator::{LlmTranslator, Translator};
-pub use types::{AnalysisResult, ConversationTurn, LlmConfig, LlmUsage, RouteDecision};
+pub use types::{
+    with_previous_question, AgentStep, AnalysisResult, ConversationTurn, EntityCandidate,
+    EntityHint, FewShotExample, FollowUp, FollowUpSuggestions, IncidentReport, LlmConfig, LlmUsage,
+    MonitorProposals, PromptProfile, ProposedMonitor, QueryCandidate, RouteDecision, RouteResult,
+    Translation, TranslationResult,
+};
@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use ::llm::builder::LLMBackend;
+
+use crate::agent::agentic::{Agentic, LlmAgentic};
+use crate::agent::analyst::{Analyst, SreAnalyst};
+use crate::agent::context::context_window_tokens_for_model;
+use crate::agent::failover::Failover;
+use crate::agent::router::{LlmRouter, Router};
+use crate::agent::translator::{LlmTranslator, Translator};
+use crate::agent::types::LlmConfig;
+use crate::error::CliResult;
+
+/// Every agent the pipeline needs, built from one model configuration so the model can be
+/// swapped at runtime.
+#[derive(Clone)]
+pub struct LlmAgents {
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub config: LlmConfig,
+    pub translator: Arc<dyn Translator>,
+    pub router: Arc<dyn Router>,
+    pub agentic: Arc<dyn Agentic>,
+    pub analyst: Arc<dyn Analyst>,
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub context_window_tokens: Option<usize>,
+}
+
+impl LlmAgents {
+    /// Agents for `config`, which fail over to its fallbacks when it has any.
+    pub fn try_new(config: LlmConfig) -> CliResult<Self> {
+        let mut translators: Vec<(String, Arc<dyn Translator>)> = Vec::new();
+        let mut routers: Vec<(String, Arc<dyn Router>)> = Vec::new();
+        let mut agentics: Vec<(String, Arc<dyn Agentic>)> = Vec::new();
+        let mut analysts: Vec<(String, Arc<dyn Analyst>)> = Vec::new();
+        for link in config.failover_chain() {
+            let model = link.model.clone();
+            translators.push((
+                model.clone(),
+                Arc::new(LlmTranslator::try_new(link.clone())?),
+            ));
+            routers.push((model.clone(), Arc::new(LlmRouter::try_new(link.clone())?)));
+            agentics.push((model.clone(), Arc::new(LlmAgentic::try_new(link.clone())?)));
+            analysts.push((model, Arc::new(SreAnalyst::try_new(link)?)));
+        }
+        Ok(Self {
+            translator: chained(translators, |chain| Arc::new(chain) as Arc<dyn Translator>),
+            router: chained(routers, |chain| Arc::new(chain) as Arc<dyn Router>),
+            agentic: chained(agentics, |chain| Arc::new(chain) as Arc<dyn Agentic>),
+            analyst: chained(analysts, |chain| Arc::new(chain) as Arc<dyn Analyst>),
+            context_window_tokens: context_window_tokens_for_model(&config.model),
+            config,
+        })
+    }
+}
+
+/// The agent itself when there is no fallback, else the chain of agents `wrap` turns into one.
+fn chained<T: ?Sized + Send + Sync>(
+    mut links: Vec<(String, Arc<T>)>,
+    wrap: impl FnOnce(Failover<T>) -> Arc<T>,
+) -> Arc<T> {
+    if links.len() == 1 {
+        links.remove(0).1
+    } else {
+        wrap(Failover::new(links))
+    }
+}
+
+impl LlmConfig {
+    /// Derives another model configuration from `spec`, written as
+    /// `[BACKEND:]MODEL[@BASE_URL]`. Omitted parts are taken from `self`; the API key is only
+    /// reused when the base URL stays the same, so it is never sent to another provider.
+    pub fn alternative(&self, spec: &str) -> CliResult<LlmConfig> {
+        let spec = spec.trim();
+        let (model, base_url) = match spec.split_once('@') {
+            Some((model, base_url)) => (model, base_url.trim()),
+            None => (spec, self.base_url.as_str()),
+        };
+        // Model names such as `llama3:8b` contain colons too, so only a known backend name
+        // counts as a prefix.
+        let (backend, model) = match model.split_once(':') {
+            Some((prefix, rest)) => match prefix.parse::<LLMBackend>() {
+                Ok(backend) => (backend, rest),
+                Err(_) => (self.backend.clone(), model),
+            },
+            None => (self.backend.clone(), model),
+        };
+        let model = model.trim();
+        if model.is_empty() {
+            return Err(format!("no model name in '{spec}'").into());
+        }
+        Ok(LlmConfig {
+            backend,
+            base_url: base_url.to_string(),
+            model: model.to_string(),
+            api_key: if base_url == self.base_url {
+                self.api_key.clone()
+            } else {
+                None
+            },
+            timeout_secs: self.timeout_secs,
+            structured_output: self.structured_output,
+            prompt_profile: self.prompt_profile,
+            schema_subset: self.schema_subset,
+            stream_analysis: self.stream_analysis,
+            candidates: self.candidates,
+            instructions: self.instructions.clone(),
+            docs: self.docs.clone(),
+            answer_language: self.answer_language.clone(),
+            fallbacks: self.fallbacks.clone(),
+        })
+    }
+
+    /// This model followed by its fallbacks. A fallback keeps its backend, model, base URL and
+    /// API key and takes every other setting, such as the prompts and the timeout, from `self`.
+    pub fn failover_chain(&self) -> Vec<LlmConfig> {
+        let primary = LlmConfig {
+            fallbacks: Vec::new(),
+            ..self.clone()
+        };
+        let fallbacks = self.fallbacks.iter().map(|fallback| LlmConfig {
+            backend: fallback.backend.clone(),
+            base_url: fallback.base_url.clone(),
+            model: fallback.model.clone(),
+            api_key: fallback.api_key.clone(),
+            ..primary.clone()
+        });
+        std::iter::once(primary.clone()).chain(fallbacks).collect()
+    }
+
+    /// Short name for model pickers: the model, plus the host when it is not the default one.
+    pub fn label(&self, default_base_url: &str) -> String {
+        if self.base_url == default_base_url {
+            self.model.clone()
+        } else {
+            format!("{} @ {}", self.model, self.base_url)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::types::PromptProfile;
+
+    fn primary() -> LlmConfig {
+        LlmConfig {
+            backend: LLMBackend::OpenAI,
+            base_url: "https://api.openai.com/v1".to_string(),
+            model: "gpt-4o".to_string(),
+            api_key: Some("secret".to_string()),
+            timeout_secs: 60,
+            structured_output: true,
+            prompt_profile: PromptProfile::Full,
+            schema_subset: false,
+            stream_analysis: true,
+            candidates: 1,
+            instructions: None,
+            docs: None,
+            answer_language: None,
+            fallbacks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn alternative_keeps_defaults_for_plain_model_names() {
+        let config = primary().alternative("gpt-4o-mini").unwrap();
+        assert_eq!(config.model, "gpt-4o-mini");
+        assert_eq!(config.base_url, "https://api.openai.com/v1");
+        assert_eq!(config.api_key.as_deref(), Some("secret"));
+
+        let config = primary().alternative("llama3:8b").unwrap();
+        assert_eq!(config.model, "llama3:8b");
+    }
+
+    #[test]
+    fn alternative_with_backend_and_url_drops_the_api_key() {
+        let config = primary()
+            .alternative("ollama:llama3:8b@http://localhost:11434")
+            .unwrap();
+        assert!(matches!(config.backend, LLMBackend::Ollama));
+        assert_eq!(config.model, "llama3:8b");
+        assert_eq!(config.base_url, "http://localhost:11434");
+        assert_eq!(config.api_key, None);
+        assert_eq!(
+            config.label(&primary().base_url),
+            "llama3:8b @ http://localhost:11434"
+        );
+
+        assert!(primary().alternative(" @http://localhost").is_err());
+    }
+
+    #[test]
+    fn failover_chain_takes_prompt_settings_from_the_primary() {
+        let mut config = primary();
+        config.fallbacks = vec![config
+            .alternative("ollama:llama3:8b@http://localhost:11434")
+            .unwrap()];
+        config.answer_language = Some("German".to_string());
+
+        let chain = config.failover_chain();
+        assert_eq!(chain.len(), 2);
+        assert!(chain[0].fallbacks.is_empty());
+        assert_eq!(chain[1].model, "llama3:8b");
+        assert_eq!(chain[1].api_key, None);
+        assert_eq!(chain[1].answer_language.as_deref(), Some("German"));
+    }
+}
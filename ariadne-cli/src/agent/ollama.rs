@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::CliResult;
+
+/// How long startup waits for the Ollama server before going on without its metadata.
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A model pulled on an Ollama server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OllamaModel {
+    pub name: String,
+    /// Tokens the server gives the model per request: the `num_ctx` parameter when the model
+    /// sets one, else the length the model was trained for.
+    pub context_length: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<TagsModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsModel {
+    name: String,
+}
+
+/// Lists the models of the Ollama server at `base_url` with their context lengths. A model
+/// whose details cannot be read is listed without a length.
+pub async fn discover_models(base_url: &str) -> CliResult<Vec<OllamaModel>> {
+    let root = server_root(base_url);
+    let client = reqwest::Client::builder()
+        .timeout(DISCOVERY_TIMEOUT)
+        .build()?;
+    let tags: TagsResponse = client
+        .get(format!("{root}/api/tags"))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let mut models = Vec::with_capacity(tags.models.len());
+    for model in tags.models {
+        let show = client
+            .post(format!("{root}/api/show"))
+            .json(&serde_json::json!({ "model": model.name }))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        let context_length = match show {
+            Ok(response) => response
+                .json::<Value>()
+                .await
+                .ok()
+                .and_then(|show| context_length(&show)),
+            Err(err) => {
+                tracing::warn!("Failed to read Ollama model {}: {err}", model.name);
+                None
+            }
+        };
+        models.push(OllamaModel {
+            name: model.name,
+            context_length,
+        });
+    }
+    Ok(models)
+}
+
+/// The server's address without the OpenAI-compatible `/v1` suffix the LLM client may use.
+fn server_root(base_url: &str) -> &str {
+    let trimmed = base_url.trim().trim_end_matches('/');
+    trimmed.strip_suffix("/v1").unwrap_or(trimmed)
+}
+
+/// Context length from an `/api/show` response. `num_ctx` in `parameters` is what the server
+/// actually allocates; `<architecture>.context_length` in `model_info` is the model's maximum.
+fn context_length(show: &Value) -> Option<usize> {
+    let num_ctx = show
+        .get("parameters")
+        .and_then(Value::as_str)
+        .into_iter()
+        .flat_map(str::lines)
+        .find_map(|line| {
+            let mut parts = line.split_whitespace();
+            (parts.next() == Some("num_ctx"))
+                .then(|| parts.next()?.parse::<usize>().ok())
+                .flatten()
+        });
+    if num_ctx.is_some() {
+        return num_ctx;
+    }
+    let info = show.get("model_info")?.as_object()?;
+    info.iter()
+        .find(|(key, _)| key.ends_with(".context_length"))
+        .and_then(|(_, value)| value.as_u64())
+        .and_then(|tokens| usize::try_from(tokens).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_root_drops_the_openai_suffix() {
+        assert_eq!(
+            server_root("http://localhost:11434/v1/"),
+            "http://localhost:11434"
+        );
+        assert_eq!(
+            server_root("http://localhost:11434"),
+            "http://localhost:11434"
+        );
+    }
+
+    #[test]
+    fn context_length_prefers_num_ctx() {
+        let show = serde_json::json!({
+            "parameters": "stop \"<|eot_id|>\"\nnum_ctx 16384",
+            "model_info": { "general.architecture": "llama", "llama.context_length": 131072 }
+        });
+        assert_eq!(context_length(&show), Some(16384));
+
+        let show = serde_json::json!({
+            "model_info": { "general.architecture": "qwen2", "qwen2.context_length": 32768 }
+        });
+        assert_eq!(context_length(&show), Some(32768));
+        assert_eq!(context_length(&serde_json::json!({})), None);
+    }
+}
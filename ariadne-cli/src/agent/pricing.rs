@@ -0,0 +1,180 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing::warn;
+
+use crate::agent::types::LlmUsage;
+
+/// Price of a model in USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    /// Rate for prompt tokens served from the provider's cache; defaults to `input`.
+    #[serde(default)]
+    pub cached_input: Option<f64>,
+}
+
+impl ModelPrice {
+    pub fn cost(&self, usage: &LlmUsage) -> f64 {
+        let cached = usage.cached_tokens.unwrap_or(0).min(usage.prompt_tokens);
+        let uncached = usage.prompt_tokens - cached;
+        let cached_rate = self.cached_input.unwrap_or(self.input);
+        (uncached as f64 * self.input
+            + cached as f64 * cached_rate
+            + usage.completion_tokens as f64 * self.output)
+            / 1_000_000.0
+    }
+}
+
+/// Running total of tokens and their cost. Tokens of models without a price are counted but
+/// not charged, so the cost is a lower bound whenever `unpriced_tokens` is non-zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UsageCost {
+    pub tokens: u64,
+    pub cost: f64,
+    pub unpriced_tokens: u64,
+}
+
+impl UsageCost {
+    pub fn add(&mut self, usage: Option<&LlmUsage>, price: Option<&ModelPrice>) {
+        let Some(usage) = usage else {
+            return;
+        };
+        self.tokens += u64::from(usage.total_tokens);
+        match price {
+            Some(price) => self.cost += price.cost(usage),
+            None => self.unpriced_tokens += u64::from(usage.total_tokens),
+        }
+    }
+
+    pub fn merge(&mut self, other: &UsageCost) {
+        self.tokens += other.tokens;
+        self.cost += other.cost;
+        self.unpriced_tokens += other.unpriced_tokens;
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PricingConfig {
+    #[serde(default)]
+    models: HashMap<String, ModelPrice>,
+    #[serde(default)]
+    providers: HashMap<String, HashMap<String, ModelPrice>>,
+}
+
+pub fn price_for_model(model: &str) -> Option<ModelPrice> {
+    let config = pricing_config()?;
+    resolve_price(model, config)
+}
+
+fn pricing_config() -> Option<&'static PricingConfig> {
+    static CONFIG: OnceLock<Option<PricingConfig>> = OnceLock::new();
+    CONFIG.get_or_init(read_pricing_config).as_ref()
+}
+
+fn read_pricing_config() -> Option<PricingConfig> {
+    let path = locate_pricing_config_path()?;
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            warn!("Failed to read pricing config at {}: {err}", path.display());
+            return None;
+        }
+    };
+    match toml::from_str::<PricingConfig>(&contents) {
+        Ok(config) => Some(config),
+        Err(err) => {
+            warn!(
+                "Failed to parse pricing config at {}: {err}",
+                path.display()
+            );
+            None
+        }
+    }
+}
+
+fn locate_pricing_config_path() -> Option<PathBuf> {
+    if let Ok(path) = env::var("LLM_PRICING_CONFIG") {
+        let path = PathBuf::from(path);
+        if path.exists() {
+            return Some(path);
+        }
+        warn!(
+            "LLM_PRICING_CONFIG points to a missing file: {}",
+            path.display()
+        );
+    }
+
+    let cwd = env::current_dir().ok()?;
+    let candidates = [
+        cwd.join("config/model_pricing.toml"),
+        cwd.join("ariadne-cli/config/model_pricing.toml"),
+    ];
+    candidates.into_iter().find(|path| path.exists())
+}
+
+fn resolve_price(model: &str, config: &PricingConfig) -> Option<ModelPrice> {
+    if let Some(price) = config.models.get(model).copied() {
+        return Some(price);
+    }
+    for models in config.providers.values() {
+        if let Some(price) = models.get(model).copied() {
+            return Some(price);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(prompt: u32, completion: u32, cached: Option<u32>) -> LlmUsage {
+        LlmUsage {
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+            total_tokens: prompt + completion,
+            reasoning_tokens: None,
+            cached_tokens: cached,
+            model: None,
+        }
+    }
+
+    #[test]
+    fn pricing_models_take_precedence() {
+        let input = r#"
+[models]
+"gpt-5-mini" = { input = 0.5, output = 4.0 }
+
+[providers.openai]
+"gpt-5-mini" = { input = 0.25, output = 2.0, cached_input = 0.025 }
+"gpt-5-nano" = { input = 0.05, output = 0.4 }
+"#;
+        let config: PricingConfig = toml::from_str(input).unwrap();
+        assert_eq!(resolve_price("gpt-5-mini", &config).unwrap().input, 0.5);
+        assert_eq!(resolve_price("gpt-5-nano", &config).unwrap().output, 0.4);
+        assert!(resolve_price("llama3:8b", &config).is_none());
+    }
+
+    #[test]
+    fn cost_charges_cached_tokens_at_the_cached_rate() {
+        let price = ModelPrice {
+            input: 1.0,
+            output: 10.0,
+            cached_input: Some(0.1),
+        };
+        let cost = price.cost(&usage(1_000_000, 100_000, Some(500_000)));
+        assert!((cost - (0.5 + 0.05 + 1.0)).abs() < 1e-9);
+
+        let mut total = UsageCost::default();
+        total.add(Some(&usage(1_000_000, 0, None)), Some(&price));
+        total.add(Some(&usage(200, 100, None)), None);
+        total.add(None, Some(&price));
+        assert_eq!(total.tokens, 1_000_300);
+        assert_eq!(total.unpriced_tokens, 300);
+        assert!((total.cost - 1.0).abs() < 1e-9);
+    }
+}
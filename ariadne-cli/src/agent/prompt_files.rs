@@ -0,0 +1,245 @@
+use std::path::{Path, PathBuf};
+#[cfg(feature = "gui")]
+use std::time::SystemTime;
+
+use ariadne_tools::{EXAMPLES_PLACEHOLDER, RELATIONSHIPS_PLACEHOLDER, SCHEMA_PLACEHOLDER};
+
+use crate::settings::config_dir;
+
+const ANALYST_PROMPT: &str = "You are a Kubernetes SRE assistant. Use only the provided Cypher query results to answer the question.\
+If the results are empty or insufficient, say so and suggest follow-up questions or Cypher queries for clarity.\
+Be concise, actionable, and avoid speculation.";
+
+const COMPACTION_PROMPT: &str =
+    "You summarize short-term investigation context for future SRE answers. \
+Return a concise, plain-text summary with key entities, filters, assumptions, and results. \
+Keep it under 1200 characters. Do not return Cypher.";
+
+/// A prompt that can be replaced by a file in [`prompts_dir`]. Files are read whenever a
+/// prompt is built, so an edit applies to the next provider built from it; a missing file or
+/// one that fails [`PromptFile::validate`] falls back to the built-in text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptFile {
+    /// Rules and schema of the translator, the agent and the follow-up suggestions.
+    Translator,
+    /// [`PromptFile::Translator`] for the compact prompt profile.
+    TranslatorCompact,
+    /// Instructions of the analyst; the output format is appended to them.
+    Analyst,
+    /// Instructions for summarizing the conversation context.
+    Compaction,
+}
+
+impl PromptFile {
+    pub const ALL: [PromptFile; 4] = [
+        PromptFile::Translator,
+        PromptFile::TranslatorCompact,
+        PromptFile::Analyst,
+        PromptFile::Compaction,
+    ];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            PromptFile::Translator => "translator.txt",
+            PromptFile::TranslatorCompact => "translator_compact.txt",
+            PromptFile::Analyst => "analyst.txt",
+            PromptFile::Compaction => "compaction.txt",
+        }
+    }
+
+    /// Placeholders the prompt must contain; no others than these and the
+    /// [`PromptFile::optional_placeholders`] are allowed.
+    pub fn placeholders(self) -> &'static [&'static str] {
+        match self {
+            PromptFile::Translator | PromptFile::TranslatorCompact => {
+                &[SCHEMA_PLACEHOLDER, RELATIONSHIPS_PLACEHOLDER]
+            }
+            PromptFile::Analyst | PromptFile::Compaction => &[],
+        }
+    }
+
+    /// Placeholders the prompt may contain.
+    pub fn optional_placeholders(self) -> &'static [&'static str] {
+        match self {
+            PromptFile::Translator | PromptFile::TranslatorCompact => &[EXAMPLES_PLACEHOLDER],
+            PromptFile::Analyst | PromptFile::Compaction => &[],
+        }
+    }
+
+    pub fn builtin(self) -> &'static str {
+        match self {
+            PromptFile::Translator => ariadne_tools::default_prompt_template(false),
+            PromptFile::TranslatorCompact => ariadne_tools::default_prompt_template(true),
+            PromptFile::Analyst => ANALYST_PROMPT,
+            PromptFile::Compaction => COMPACTION_PROMPT,
+        }
+    }
+
+    /// Checks that `text` is not empty, contains the placeholders of this prompt and no others
+    /// than its optional ones.
+    pub fn validate(self, text: &str) -> Result<(), String> {
+        if text.trim().is_empty() {
+            return Err("the prompt is empty".to_string());
+        }
+        let required = self.placeholders();
+        let optional = self.optional_placeholders();
+        if let Some(unknown) = placeholders_in(text).into_iter().find(|placeholder| {
+            !required.contains(&placeholder.as_str()) && !optional.contains(&placeholder.as_str())
+        }) {
+            return Err(format!("unknown placeholder {unknown}"));
+        }
+        match required
+            .iter()
+            .find(|placeholder| !text.contains(**placeholder))
+        {
+            Some(missing) => Err(format!("missing placeholder {missing}")),
+            None => Ok(()),
+        }
+    }
+
+    /// The prompt from [`prompts_dir`] when it holds a valid one, the built-in text otherwise.
+    pub fn load(self) -> String {
+        let Some(path) = self.path() else {
+            return self.builtin().to_string();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => match self.validate(&text) {
+                Ok(()) => text,
+                Err(err) => {
+                    tracing::warn!(
+                        "Ignoring prompt file {}: {err}; using the built-in prompt",
+                        path.display()
+                    );
+                    self.builtin().to_string()
+                }
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => self.builtin().to_string(),
+            Err(err) => {
+                tracing::warn!("Failed to read prompt file {}: {err}", path.display());
+                self.builtin().to_string()
+            }
+        }
+    }
+
+    fn path(self) -> Option<PathBuf> {
+        prompts_dir().map(|dir| dir.join(self.file_name()))
+    }
+}
+
+/// Directory of the prompt files: `LLM_PROMPTS_DIR`, or `prompts` in the config directory.
+pub fn prompts_dir() -> Option<PathBuf> {
+    match std::env::var("LLM_PROMPTS_DIR") {
+        Ok(dir) if !dir.trim().is_empty() => Some(PathBuf::from(dir)),
+        _ => config_dir().map(|dir| dir.join("prompts")),
+    }
+}
+
+/// Writes the built-in prompts to a new [`prompts_dir`] so they can be edited. Does nothing
+/// once the directory exists, so deleted files stay deleted.
+pub fn install_default_prompts() -> std::io::Result<()> {
+    let Some(dir) = prompts_dir() else {
+        return Ok(());
+    };
+    if dir.exists() {
+        return Ok(());
+    }
+    write_prompts(&dir)?;
+    tracing::info!("Wrote the default prompts to {}", dir.display());
+    Ok(())
+}
+
+fn write_prompts(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    for file in PromptFile::ALL {
+        std::fs::write(dir.join(file.file_name()), file.builtin())?;
+    }
+    Ok(())
+}
+
+/// Modification times of the prompt files, to notice when one is edited, added or removed.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromptFingerprint(Vec<Option<SystemTime>>);
+
+#[cfg(feature = "gui")]
+impl PromptFingerprint {
+    pub fn current() -> Self {
+        let Some(dir) = prompts_dir() else {
+            return Self::default();
+        };
+        Self(
+            PromptFile::ALL
+                .iter()
+                .map(|file| {
+                    std::fs::metadata(dir.join(file.file_name()))
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                })
+                .collect(),
+        )
+    }
+}
+
+/// The `{{NAME}}` placeholders of `text`, in order.
+fn placeholders_in(text: &str) -> Vec<String> {
+    let mut placeholders = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        placeholders.push(rest[start..start + end + 2].to_string());
+        rest = &rest[start + end + 2..];
+    }
+    placeholders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_prompts_validate() {
+        for file in PromptFile::ALL {
+            assert_eq!(
+                file.validate(file.builtin()),
+                Ok(()),
+                "{}",
+                file.file_name()
+            );
+        }
+    }
+
+    #[test]
+    fn placeholders_are_checked() {
+        let translator = PromptFile::Translator;
+        assert_eq!(
+            translator.validate("Rules\n{{SCHEMA}}"),
+            Err("missing placeholder {{RELATIONSHIPS}}".to_string())
+        );
+        assert_eq!(
+            translator.validate("{{SCHEMA}} {{RELATIONSHIPS}} {{HISTORY}}"),
+            Err("unknown placeholder {{HISTORY}}".to_string())
+        );
+        assert_eq!(
+            translator.validate("{{SCHEMA}} {{RELATIONSHIPS}} {{EXAMPLES}}"),
+            Ok(())
+        );
+        assert_eq!(
+            PromptFile::Analyst.validate("Answer briefly, {{SCHEMA}}"),
+            Err("unknown placeholder {{SCHEMA}}".to_string())
+        );
+        assert!(PromptFile::Compaction.validate("  ").is_err());
+    }
+
+    #[test]
+    fn written_prompts_round_trip() {
+        let dir = std::env::temp_dir().join(format!("ariadne-prompts-{}", std::process::id()));
+        write_prompts(&dir).unwrap();
+        for file in PromptFile::ALL {
+            let text = std::fs::read_to_string(dir.join(file.file_name())).unwrap();
+            assert_eq!(text, file.builtin());
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
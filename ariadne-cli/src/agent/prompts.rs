@@ -1,28 +1,103 @@
-pub fn base_prompt(structured: bool) -> String {
-    let prompt = ariadne_tools::full_prompt();
+use crate::agent::prompt_files::PromptFile;
+use crate::agent::types::PromptProfile;
+
+/// The schema prompt of `profile`, from its prompt file, limited to `labels` when given.
+fn schema_prompt(profile: PromptProfile, labels: Option<&[&str]>) -> String {
+    let (file, compact) = match profile {
+        PromptProfile::Full => (PromptFile::Translator, false),
+        PromptProfile::Compact => (PromptFile::TranslatorCompact, true),
+    };
+    ariadne_tools::prompt_from_template(&file.load(), compact, labels)
+}
+
+/// How the earlier turns passed along with a question are meant to be used.
+const CONVERSATION_GUIDANCE: &str = "Earlier questions of the conversation come before the current one, each answered with the Cypher that was run and a summary of its results. \
+When the current question only refines or continues the previous one (e.g. \"and now only in namespace prod\", \"what about the failing ones?\"), start from the previous Cypher and apply the change instead of writing an unrelated query.";
+
+/// `prompt` followed by the operator's instructions for the cluster, when there are any.
+pub fn with_instructions(prompt: String, instructions: Option<&str>) -> String {
+    match instructions.map(str::trim).filter(|text| !text.is_empty()) {
+        Some(text) => format!(
+            "{prompt}\n\nOperator instructions for this cluster (follow them unless they contradict the rules above):\n{text}"
+        ),
+        None => prompt,
+    }
+}
+
+/// `prompt` asking for answers in `language`, when one is set. Everything the code reads back
+/// stays as it is.
+pub fn with_answer_language(prompt: String, language: Option<&str>) -> String {
+    match language.map(str::trim).filter(|text| !text.is_empty()) {
+        Some(language) => format!(
+            "{prompt}\n\nWrite the text of your answer in {language}. Keep JSON keys, section headings, Cypher queries, labels, property names, resource names and values from the rows exactly as they are."
+        ),
+        None => prompt,
+    }
+}
+
+/// The translator's system prompt. With more than one `candidates`, it also asks for other
+/// queries the question could be answered with.
+pub fn base_prompt(
+    structured: bool,
+    profile: PromptProfile,
+    labels: Option<&[&str]>,
+    candidates: usize,
+) -> String {
+    let prompt = format!(
+        "{}\n\n{CONVERSATION_GUIDANCE}",
+        schema_prompt(profile, labels)
+    );
+    if candidates > 1 {
+        return candidates_prompt(&prompt, structured, candidates - 1);
+    }
     if structured {
-        let guidance = "Return JSON with keys `cypher` and `params` (array). \
+        let guidance = "Return JSON with keys `cypher`, `params` (array) and `reasoning`. \
 `params` should be a list of objects with keys `key` (string) and `value` (string). \
 Always include `params`; use [] when there are no parameters. \
 The `value` must be a JSON-encoded literal (e.g. \"\\\"name\\\"\", \"42\", \"true\", \"[1,2]\", \"{\\\"k\\\":\\\"v\\\"}\"). \
-Do not include extra fields, explanations, or code fences.";
+`reasoning` is one or two short sentences on which labels, relationships and filters were chosen and why. \
+`clarification` is an empty string, unless the question cannot be translated without guessing what the user means (e.g. it refers to \"the app\" and neither the conversation nor the resource names say which one); then it is one short question to the user and `cypher` is an empty string. \
+Do not include extra fields or code fences.";
         format!("{prompt}\n\n{guidance}")
     } else {
-        format!("{prompt}\n\nReturn only Cypher. Do not include explanations or code fences.")
+        format!(
+            "{prompt}\n\nReturn only Cypher. Do not include explanations or code fences. \
+If the question cannot be translated without guessing what the user means, return `Clarify: <one short question to the user>` instead."
+        )
+    }
+}
+
+fn candidates_prompt(prompt: &str, structured: bool, alternatives: usize) -> String {
+    if structured {
+        format!(
+            "{prompt}\n\nReturn JSON with keys `cypher`, `params` (array), `reasoning`, `clarification` and `alternatives`. \
+`cypher` is the query you consider best. \
+`params` should be a list of objects with keys `key` (string) and `value` (string). \
+Always include `params`; use [] when there are no parameters. \
+The `value` must be a JSON-encoded literal (e.g. \"\\\"name\\\"\", \"42\", \"true\", \"[1,2]\", \"{{\\\"k\\\":\\\"v\\\"}}\"). \
+`reasoning` is one or two short sentences on which labels, relationships and filters were chosen and why. \
+`alternatives` holds up to {alternatives} other queries (objects with keys `cypher` and `params`) that read the question differently or take another path through the schema, best first; they are tried when the first query fails or finds nothing. Use [] when there is only one sensible query. \
+`clarification` is an empty string, unless the question cannot be translated without guessing what the user means; then it is one short question to the user, `cypher` is an empty string and `alternatives` is []. \
+Do not include extra fields or code fences."
+        )
+    } else {
+        format!(
+            "{prompt}\n\nReturn up to {} Cypher queries, best first, separated by a line containing only `---`. \
+Later queries read the question differently or take another path through the schema; they are tried when the first one fails or finds nothing. \
+Do not include explanations or code fences. \
+If the question cannot be translated without guessing what the user means, return `Clarify: <one short question to the user>` instead.",
+            alternatives + 1
+        )
     }
 }
 
 pub fn analysis_compaction_prompt() -> String {
-    "You summarize short-term investigation context for future SRE answers. \
-Return a concise, plain-text summary with key entities, filters, assumptions, and results. \
-Keep it under 1200 characters. Do not return Cypher."
-        .to_string()
+    PromptFile::Compaction.load()
 }
 
 pub fn analysis_prompt(structured: bool) -> String {
-    let base = "You are a Kubernetes SRE assistant. Use only the provided Cypher query results to answer the question.\
-If the results are empty or insufficient, say so and suggest follow-up questions or Cypher queries for clarity.\
-Be concise, actionable, and avoid speculation.";
+    let base = PromptFile::Analyst.load();
+    let base = base.trim_end();
     if structured {
         format!(
             "{base}\n\nReturn JSON with keys: title (string), summary (string), bullets (array of strings), rows (array of objects), follow_ups (array of strings), confidence (low|medium|high). Always include all keys. Use empty arrays when needed."
@@ -32,29 +107,77 @@ Be concise, actionable, and avoid speculation.";
     }
 }
 
+pub fn session_report_prompt(structured: bool) -> String {
+    let base = "You are an SRE writing an incident report from a whole investigation session of a Kubernetes cluster: the questions asked, the Cypher queries run, samples of their rows, the answers given and the errors hit.\n\
+Reconstruct what happened from the session alone. Name the resources involved, keep timestamps from the rows, and say so when the evidence is thin.\n\
+Write a title, a timeline (what happened, oldest first, each entry starting with its time when the session gives one), the most likely root cause as a hypothesis, the evidence for and against it, follow-up actions for the team, and your confidence (low|medium|high).";
+    if structured {
+        format!(
+            "{base}\n\nReturn JSON with keys: title (string), timeline (array of strings), root_cause (string), evidence (array of strings), actions (array of strings), confidence (low|medium|high). Always include all keys. Do not include code fences."
+        )
+    } else {
+        format!(
+            "{base}\n\nWrite the sections `Title:`, `Timeline:`, `Root cause:`, `Evidence:`, `Actions:` and `Confidence:` in that order, each heading on its own line and list entries as lines starting with `- `."
+        )
+    }
+}
+
+pub fn monitor_prompt(structured: bool, profile: PromptProfile) -> String {
+    let prompt = schema_prompt(profile, None);
+    let base = "You turn a Kubernetes investigation session into monitors that would catch the same problem next time.\n\
+Given the questions, queries, row samples and answers of the session, propose up to 3 alert rules. Each rule has a short name, one read-only Cypher query that follows every rule above, and a condition on its result of the form `<rows|column> <op> <number>`: `rows` is the number of rows, any other name reads that numeric column of the first row, and op is one of > >= < <= == !=.\n\
+The condition must be false while the cluster is healthy and turn true when the problem comes back. Prefer queries that return the affected resources with `rows > 0`, or a count with a threshold. Give one sentence on what the rule catches.";
+    let tail = if structured {
+        "Return JSON with key monitors: an array of objects with keys name, query, condition and rationale. Do not include code fences."
+    } else {
+        "For each monitor, write the lines `Name: <name>`, `Query: <query on a single line>`, `Condition: <condition>` and `Why: <sentence>`. Do not include anything else."
+    };
+    format!("{prompt}\n\n{base}\n{tail}")
+}
+
+pub fn followup_prompt(structured: bool, profile: PromptProfile) -> String {
+    let prompt = schema_prompt(profile, None);
+    let tail = if structured {
+        "You suggest follow-up questions for a Kubernetes SRE investigation.\n\
+Given the conversation so far and the rows of the latest query, propose up to 3 short questions that dig deeper into what the rows show.\n\
+Each question needs one read-only Cypher query that answers it and follows every rule above; only suggest questions such a query can answer.\n\
+Return JSON with key follow_ups: an array of objects with keys question and cypher. Do not include code fences."
+    } else {
+        "You suggest follow-up questions for a Kubernetes SRE investigation.\n\
+Given the conversation so far and the rows of the latest query, propose up to 3 short questions that dig deeper into what the rows show.\n\
+Each question needs one read-only Cypher query that answers it and follows every rule above; only suggest questions such a query can answer.\n\
+For each follow-up, write a line `Question: <question>` followed by a line `Cypher: <query on a single line>`. Do not include anything else."
+    };
+    format!("{prompt}\n\n{tail}")
+}
+
 pub fn router_prompt() -> String {
     "You are a routing classifier for a Kubernetes graph query assistant.\n\
-Decide whether a single Cypher query is enough (one_shot) or whether a multi-turn agent loop is needed (multi_turn).\n\
-Choose multi_turn when the question is multi-hop, ambiguous, missing identifiers, or needs exploration.\n\
-Choose one_shot for direct lookups or simple single-hop queries.\n\
-Return JSON with key: route (one_shot|multi_turn). Do not include extra fields."
+Pick one route for the latest question:\n\
+- direct: the earlier turns of the conversation already contain the answer (e.g. a count or a name that was returned). Never choose direct without earlier turns.\n\
+- one_shot: a single Cypher query is enough, such as direct lookups or simple single-hop queries.\n\
+- multi_turn: an agent loop of several queries is needed because the question is multi-hop, is missing identifiers, or needs exploration.\n\
+- clarify: the question cannot be answered or queried without guessing what the user means (e.g. \"fix it\" with no earlier turns).\n\
+Return JSON with keys: route (direct|one_shot|multi_turn|clarify) and reply. For direct, reply is the answer; for clarify, reply is one short question to the user; otherwise reply is an empty string. Do not include extra fields."
         .to_string()
 }
 
-pub fn agentic_prompt(structured: bool) -> String {
-    let prompt = ariadne_tools::full_prompt();
+pub fn agentic_prompt(structured: bool, profile: PromptProfile) -> String {
+    let prompt = schema_prompt(profile, None);
     let tail = if structured {
         "You are operating in agentic multi-turn mode.\n\
-At each step, output JSON with keys: action (\"query\"|\"final\"), cypher (string), and optional params (object).\n\
+At each step, output JSON with keys: action (\"query\"|\"tool\"|\"final\"), cypher (string), and optional params (object).\n\
 Use action=\"query\" for focused probe queries to gather missing facts.\n\
+When tools are listed, use action=\"tool\" with keys tool (its name) and args (object) to fetch evidence the graph does not carry, such as logs or events; leave cypher empty.\n\
 Use action=\"final\" only when you can answer the user with a single Cypher query.\n\
 Do not include explanations or code fences."
     } else {
         "You are operating in agentic multi-turn mode.\n\
 At each step, output:\n\
-action: query|final\n\
+action: query|tool|final\n\
 cypher: <cypher>\n\
 Use action=query for probe queries, action=final for the final answer query.\n\
+When tools are listed, call one with action: tool, then tool: <name> and args: <JSON object> instead of cypher.\n\
 Do not include explanations or code fences."
     };
     format!("{prompt}\n\n{tail}")
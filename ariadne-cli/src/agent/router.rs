@@ -4,18 +4,27 @@ use async_trait::async_trait;
 use serde::Deserialize;
 
 use crate::agent::prompts::router_prompt;
-use crate::agent::types::{LlmConfig, LlmUsage, RouteDecision, RouteResult};
-use crate::agent::util::{clean_json_response, map_llm_error};
+use crate::agent::types::{ConversationTurn, LlmConfig, LlmUsage, RouteDecision, RouteResult};
+use crate::agent::util::{map_llm_error, parse_json_response};
 use crate::error::CliResult;
+use crate::telemetry::{LlmSpan, SpanModel};
 
 #[async_trait]
 pub trait Router: Send + Sync {
-    async fn classify(&self, question: &str) -> CliResult<RouteResult>;
+    /// Picks how to handle `question`. The conversation lets the router answer follow-ups it
+    /// already has the facts for and tell an ambiguous question from a terse follow-up.
+    async fn classify(
+        &self,
+        question: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+    ) -> CliResult<RouteResult>;
 }
 
 pub struct LlmRouter {
     llm: Box<dyn ::llm::LLMProvider>,
     structured_output: bool,
+    span_model: SpanModel,
 }
 
 impl LlmRouter {
@@ -49,56 +58,144 @@ impl LlmRouter {
         Ok(Self {
             llm,
             structured_output: config.structured_output,
+            span_model: SpanModel::from(&config),
         })
     }
 }
 
 #[async_trait]
 impl Router for LlmRouter {
-    async fn classify(&self, question: &str) -> CliResult<RouteResult> {
-        let messages = vec![ChatMessage::user().content(question.trim()).build()];
+    async fn classify(
+        &self,
+        question: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+    ) -> CliResult<RouteResult> {
+        let messages = build_messages(question, context, context_summary);
+        let span = LlmSpan::start("route", &self.span_model);
         let response = match self.llm.chat(&messages).await {
             Ok(response) => response,
-            Err(err) => return Err(map_llm_error(err, self.structured_output)),
+            Err(err) => {
+                span.fail(&err);
+                return Err(map_llm_error(err, self.structured_output));
+            }
         };
         let usage = response.usage().map(LlmUsage::from);
+        span.finish(usage.as_ref());
         let text = response
             .text()
             .ok_or_else(|| "LLM response missing text".to_string())?;
 
-        let decision = if self.structured_output {
+        let (decision, reply) = if self.structured_output {
             parse_structured_route(&text)?
         } else {
             parse_unstructured_route(&text)
         };
+        let has_context = !context.is_empty()
+            || context_summary.is_some_and(|summary| !summary.trim().is_empty());
+        let (decision, reply) = checked_route(decision, reply, has_context);
 
-        Ok(RouteResult { decision, usage })
+        Ok(RouteResult {
+            decision,
+            reply,
+            usage,
+        })
+    }
+}
+
+fn build_messages(
+    question: &str,
+    context: &[ConversationTurn],
+    context_summary: Option<&str>,
+) -> Vec<ChatMessage> {
+    let mut messages = Vec::new();
+    if let Some(summary) = context_summary {
+        let summary = summary.trim();
+        if !summary.is_empty() {
+            messages.push(
+                ChatMessage::assistant()
+                    .content(format!("Context summary:\n{summary}"))
+                    .build(),
+            );
+        }
+    }
+    for turn in context {
+        if turn.question.trim().is_empty() {
+            continue;
+        }
+        messages.push(ChatMessage::user().content(turn.question.trim()).build());
+        let mut assistant = format!("Cypher:\n{}", turn.cypher.trim());
+        if let Some(summary) = &turn.result_summary {
+            if !summary.trim().is_empty() {
+                assistant.push_str("\nResult summary:\n");
+                assistant.push_str(summary.trim());
+            }
+        }
+        messages.push(ChatMessage::assistant().content(assistant).build());
+    }
+    messages.push(ChatMessage::user().content(question.trim()).build());
+    messages
+}
+
+/// Falls back to a query when the router wants to reply but has nothing to say, or claims to
+/// answer from a conversation that does not exist.
+fn checked_route(
+    decision: RouteDecision,
+    reply: Option<String>,
+    has_context: bool,
+) -> (RouteDecision, Option<String>) {
+    let reply = reply
+        .map(|reply| reply.trim().to_string())
+        .filter(|reply| !reply.is_empty());
+    match (decision, reply) {
+        (RouteDecision::Direct, Some(_)) if !has_context => (RouteDecision::OneShot, None),
+        (RouteDecision::Direct | RouteDecision::Clarify, None) => (RouteDecision::OneShot, None),
+        (decision, _) if decision.needs_query() => (decision, None),
+        (decision, reply) => (decision, reply),
     }
 }
 
 #[derive(Debug, Deserialize)]
 struct RoutePayload {
     route: String,
+    #[serde(default)]
+    reply: Option<String>,
 }
 
-fn parse_structured_route(text: &str) -> CliResult<RouteDecision> {
-    let cleaned = clean_json_response(text);
-    let payload: RoutePayload =
-        serde_json::from_str(&cleaned).map_err(|e| format!("Invalid JSON response: {e}"))?;
-    Ok(parse_route(&payload.route))
+fn parse_structured_route(text: &str) -> CliResult<(RouteDecision, Option<String>)> {
+    let payload: RoutePayload = parse_json_response(text)?;
+    Ok((parse_route(&payload.route), payload.reply))
 }
 
-fn parse_unstructured_route(text: &str) -> RouteDecision {
+/// Reads `route: <name>` and `reply: <text>` lines, or failing that any route name in the text.
+fn parse_unstructured_route(text: &str) -> (RouteDecision, Option<String>) {
+    let mut route = None;
+    let mut reply = None;
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        match key.trim().to_lowercase().as_str() {
+            "route" => route = Some(parse_route(value)),
+            "reply" => reply = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    if let Some(route) = route {
+        return (route, reply);
+    }
     let lower = text.to_lowercase();
     if lower.contains("multi_turn") || lower.contains("multiturn") || lower.contains("multi-turn") {
-        return RouteDecision::MultiTurn;
+        return (RouteDecision::MultiTurn, None);
     }
-    RouteDecision::OneShot
+    (RouteDecision::OneShot, None)
 }
 
 fn parse_route(raw: &str) -> RouteDecision {
     match raw.trim().to_lowercase().as_str() {
+        "direct" | "answer" => RouteDecision::Direct,
         "multi_turn" | "multi-turn" | "multiturn" => RouteDecision::MultiTurn,
+        "clarify" | "clarification" => RouteDecision::Clarify,
         _ => RouteDecision::OneShot,
     }
 }
@@ -113,9 +210,13 @@ fn router_schema() -> StructuredOutputFormat {
             "type": "object",
             "additionalProperties": false,
             "properties": {
-                "route": { "type": "string", "enum": ["one_shot", "multi_turn"] }
+                "route": {
+                    "type": "string",
+                    "enum": ["direct", "one_shot", "multi_turn", "clarify"]
+                },
+                "reply": { "type": "string" }
             },
-            "required": ["route"]
+            "required": ["route", "reply"]
         }
     }
     "#;
@@ -129,7 +230,43 @@ mod tests {
     #[test]
     fn parse_structured_route_payload() {
         let input = r#"{"route":"multi_turn"}"#;
-        let decision = parse_structured_route(input).unwrap();
+        let (decision, reply) = parse_structured_route(input).unwrap();
         assert_eq!(decision, RouteDecision::MultiTurn);
+        assert_eq!(reply, None);
+
+        let input = r#"{"route":"clarify","reply":"Which namespace?"}"#;
+        let (decision, reply) = parse_structured_route(input).unwrap();
+        assert_eq!(decision, RouteDecision::Clarify);
+        assert_eq!(reply.as_deref(), Some("Which namespace?"));
+    }
+
+    #[test]
+    fn parse_unstructured_route_lines() {
+        let (decision, reply) = parse_unstructured_route("route: direct\nreply: 3 pods.");
+        assert_eq!(decision, RouteDecision::Direct);
+        assert_eq!(reply.as_deref(), Some("3 pods."));
+        let (decision, _) = parse_unstructured_route("I think multi-turn is needed");
+        assert_eq!(decision, RouteDecision::MultiTurn);
+    }
+
+    #[test]
+    fn replies_need_text_and_direct_answers_need_context() {
+        let answer = Some("3 pods.".to_string());
+        assert_eq!(
+            checked_route(RouteDecision::Direct, answer.clone(), true),
+            (RouteDecision::Direct, answer.clone())
+        );
+        assert_eq!(
+            checked_route(RouteDecision::Direct, answer.clone(), false),
+            (RouteDecision::OneShot, None)
+        );
+        assert_eq!(
+            checked_route(RouteDecision::Clarify, Some("  ".to_string()), true),
+            (RouteDecision::OneShot, None)
+        );
+        assert_eq!(
+            checked_route(RouteDecision::MultiTurn, answer, true),
+            (RouteDecision::MultiTurn, None)
+        );
     }
 }
@@ -0,0 +1,193 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use crate::few_shot::question_words;
+
+/// Node types every subset keeps, as most questions filter or group by them.
+const ALWAYS_INCLUDED: &[&str] = &["Namespace"];
+/// Relationships nearly every node type has. Paths between the types of a question do not go
+/// through them, or every pair would be connected via the cluster or a namespace.
+const HUB_EDGES: &[&str] = &["PartOf", "BelongsTo", "Concerns"];
+/// Words that point at node types without naming them.
+const KEYWORDS: &[(&str, &[&str])] = &[
+    ("dns", &["Host"]),
+    ("hostname", &["Host"]),
+    ("domain", &["Host"]),
+    ("url", &["Host"]),
+    ("address", &["EndpointAddress"]),
+    ("backend", &["IngressServiceBackend"]),
+    ("route", &["Ingress"]),
+    ("log", &["Container"]),
+    ("image", &["Container"]),
+    ("memory", &["Pod", "Container"]),
+    ("cpu", &["Pod", "Container"]),
+    ("restart", &["Pod"]),
+    ("restarted", &["Pod"]),
+    ("crash", &["Pod", "Event"]),
+    ("crashing", &["Pod", "Event"]),
+    ("crashlooping", &["Pod", "Event"]),
+    ("oom", &["Pod", "Event"]),
+    ("oomkilled", &["Pod", "Event"]),
+    ("warning", &["Event"]),
+    ("replica", &["Deployment", "ReplicaSet", "StatefulSet"]),
+    (
+        "owner",
+        &[
+            "Deployment",
+            "ReplicaSet",
+            "StatefulSet",
+            "DaemonSet",
+            "Job",
+        ],
+    ),
+    (
+        "own",
+        &[
+            "Deployment",
+            "ReplicaSet",
+            "StatefulSet",
+            "DaemonSet",
+            "Job",
+        ],
+    ),
+    (
+        "workload",
+        &["Deployment", "StatefulSet", "DaemonSet", "Job"],
+    ),
+    ("storage", &["PersistentVolume", "StorageClass"]),
+    ("disk", &["PersistentVolumeClaim", "PersistentVolume"]),
+];
+
+/// Node types a translation of `question` most likely needs: the types it names or hints at,
+/// every type on a shortest path between two of them, and [`ALWAYS_INCLUDED`]. `None` when the
+/// question mentions no type, in which case the full schema is the better prompt.
+pub fn relevant_labels(question: &str) -> Option<Vec<String>> {
+    let words = question_words(question);
+    let relationships = ariadne_tools::graph_relationships();
+    let labels: BTreeSet<&str> = relationships
+        .iter()
+        .flat_map(|relationship| [relationship.from.as_str(), relationship.to.as_str()])
+        .collect();
+
+    let mut matched: BTreeSet<&str> = labels
+        .iter()
+        .copied()
+        .filter(|label| names_label(&words, label))
+        .collect();
+    for (keyword, hinted) in KEYWORDS {
+        if words.contains(*keyword) {
+            matched.extend(hinted.iter().filter(|label| labels.contains(*label)));
+        }
+    }
+    if matched.is_empty() {
+        return None;
+    }
+
+    let mut neighbours: HashMap<&str, Vec<&str>> = HashMap::new();
+    for relationship in relationships
+        .iter()
+        .filter(|relationship| !HUB_EDGES.contains(&relationship.edge.as_str()))
+    {
+        let (from, to) = (relationship.from.as_str(), relationship.to.as_str());
+        neighbours.entry(from).or_default().push(to);
+        neighbours.entry(to).or_default().push(from);
+    }
+    let distances: HashMap<&str, HashMap<&str, usize>> = matched
+        .iter()
+        .map(|label| (*label, distances_from(label, &neighbours)))
+        .collect();
+
+    let mut selected: BTreeSet<&str> = matched.clone();
+    let matched: Vec<&str> = matched.into_iter().collect();
+    for (index, a) in matched.iter().enumerate() {
+        for b in &matched[index + 1..] {
+            let Some(&length) = distances[a].get(b) else {
+                continue;
+            };
+            // A type lies on some shortest path when it splits the path without detour.
+            selected.extend(labels.iter().copied().filter(|label| {
+                match (distances[a].get(label), distances[b].get(label)) {
+                    (Some(to_a), Some(to_b)) => to_a + to_b == length,
+                    _ => false,
+                }
+            }));
+        }
+    }
+    selected.extend(
+        ALWAYS_INCLUDED
+            .iter()
+            .filter(|label| labels.contains(*label)),
+    );
+    Some(selected.into_iter().map(str::to_string).collect())
+}
+
+/// Whether the question names `label`, as one word (`configmap`) or as all of its camel-case
+/// parts (`persistent volume claim`).
+fn names_label(words: &HashSet<String>, label: &str) -> bool {
+    if words.contains(&label.to_lowercase()) {
+        return true;
+    }
+    let parts = question_words(&split_camel_case(label));
+    !parts.is_empty() && parts.is_subset(words)
+}
+
+fn split_camel_case(label: &str) -> String {
+    let mut output = String::with_capacity(label.len() + 4);
+    for (index, c) in label.char_indices() {
+        if index > 0 && c.is_uppercase() {
+            output.push(' ');
+        }
+        output.push(c);
+    }
+    output
+}
+
+/// Hops from `start` to every type reachable from it.
+fn distances_from<'a>(
+    start: &'a str,
+    neighbours: &HashMap<&'a str, Vec<&'a str>>,
+) -> HashMap<&'a str, usize> {
+    let mut distances = HashMap::from([(start, 0)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(label) = queue.pop_front() {
+        let distance = distances[label];
+        for next in neighbours.get(label).into_iter().flatten() {
+            if !distances.contains_key(next) {
+                distances.insert(*next, distance + 1);
+                queue.push_back(*next);
+            }
+        }
+    }
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_types_are_connected_through_the_types_between_them() {
+        let labels = relevant_labels("Which pods back the checkout svc?").unwrap();
+        assert_eq!(
+            labels,
+            [
+                "EndpointAddress",
+                "EndpointSlice",
+                "Namespace",
+                "Pod",
+                "Service",
+            ]
+        );
+
+        let labels = relevant_labels("which pvcs are bound to a volume").unwrap();
+        assert!(labels.contains(&"PersistentVolumeClaim".to_string()));
+        assert!(labels.contains(&"PersistentVolume".to_string()));
+        assert!(!labels.contains(&"Pod".to_string()));
+    }
+
+    #[test]
+    fn keywords_hint_at_types_and_unrelated_questions_keep_the_full_schema() {
+        let labels = relevant_labels("what does the dns name shop.example.com resolve to").unwrap();
+        assert!(labels.contains(&"Host".to_string()));
+        assert_eq!(relevant_labels("how is everything doing?"), None);
+    }
+}
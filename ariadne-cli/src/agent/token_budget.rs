@@ -0,0 +1,115 @@
+use serde_json::Value;
+
+use crate::agent::context::context_window_tokens_for_model;
+
+/// Window assumed for models whose context window is unknown.
+const DEFAULT_WINDOW_TOKENS: usize = 32_768;
+/// Tokens kept free for the model's answer.
+const ANSWER_RESERVE_TOKENS: usize = 2_048;
+/// Tokens kept for the marker that says how many rows were left out.
+const MARKER_TOKENS: usize = 32;
+
+/// Rough token count of `text`, at four bytes a token.
+pub fn estimate_tokens(text: &str) -> usize {
+    if text.is_empty() {
+        0
+    } else {
+        (text.len() / 4).max(1)
+    }
+}
+
+/// What a model's context window leaves for the rows of a query once the prompts and the
+/// answer are paid for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBudget {
+    window: usize,
+}
+
+impl TokenBudget {
+    pub fn new(window: Option<usize>) -> Self {
+        Self {
+            window: window.unwrap_or(DEFAULT_WINDOW_TOKENS),
+        }
+    }
+
+    pub fn for_model(model: &str) -> Self {
+        Self::new(context_window_tokens_for_model(model))
+    }
+
+    /// The budget left once `text`, such as a system prompt sent with every call, is paid for.
+    pub fn after(self, text: &str) -> Self {
+        Self {
+            window: self.window.saturating_sub(estimate_tokens(text)),
+        }
+    }
+
+    /// `records` as JSON that fits next to `prompt_tokens` of other text. When they do not
+    /// all fit, rows spread evenly over the result are kept and a marker says how many were
+    /// left out.
+    pub fn fit_records(&self, records: &[Value], prompt_tokens: usize) -> String {
+        let available = self
+            .window
+            .saturating_sub(prompt_tokens + ANSWER_RESERVE_TOKENS);
+        let full = serde_json::to_string(records).unwrap_or_else(|_| "[]".to_string());
+        let full_tokens = estimate_tokens(&full);
+        if full_tokens <= available {
+            return full;
+        }
+
+        let rows: Vec<String> = records
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap_or_default())
+            .collect();
+        let available = available.saturating_sub(MARKER_TOKENS);
+        // A first guess from the average row size; rows differ, so shrink until it fits.
+        let mut keep = (records.len() * available / full_tokens).min(records.len());
+        let sample = loop {
+            let sample = format!("[{}]", even_sample(&rows, keep).join(","));
+            if keep == 0 || estimate_tokens(&sample) <= available {
+                break sample;
+            }
+            keep = keep.saturating_sub((keep / 10).max(1));
+        };
+        format!(
+            "{sample}\n({} of {} rows omitted to fit the context window; the rows shown are \
+             spread evenly over the result)",
+            records.len() - keep,
+            records.len()
+        )
+    }
+}
+
+/// `keep` of `rows`, spread evenly and in their order.
+fn even_sample(rows: &[String], keep: usize) -> Vec<&str> {
+    (0..keep)
+        .map(|index| rows[index * rows.len() / keep].as_str())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn rows_over_the_budget_are_sampled_with_a_marker() {
+        let records: Vec<Value> = (0..1000)
+            .map(|index| json!({"pod": format!("web-{index}"), "phase": "Running"}))
+            .collect();
+        let budget = TokenBudget::new(Some(ANSWER_RESERVE_TOKENS + 1_000));
+
+        let fitted = budget.fit_records(&records, 200);
+        let (rows, marker) = fitted.split_once('\n').unwrap();
+        let rows: Vec<Value> = serde_json::from_str(rows).unwrap();
+        assert!(!rows.is_empty() && rows.len() < records.len());
+        assert_eq!(rows[0], records[0]);
+        assert!(estimate_tokens(&fitted) <= 850);
+        assert!(marker.starts_with(&format!("({} of 1000 rows omitted", 1000 - rows.len())));
+
+        let few = &records[..3];
+        assert_eq!(
+            budget.fit_records(few, 200),
+            serde_json::to_string(few).unwrap()
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::CliResult;
+
+/// An operation the agent may call between probe queries to fetch evidence the graph does not
+/// carry, such as logs.
+#[async_trait]
+pub trait AgentTool: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// What the tool returns and the arguments it takes, as shown to the agent.
+    fn description(&self) -> &'static str;
+    async fn call(&self, args: &HashMap<String, Value>) -> CliResult<String>;
+}
+
+/// The tool an agent step asked for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCall {
+    pub name: String,
+    pub args: HashMap<String, Value>,
+}
+
+impl ToolCall {
+    /// `name(key=value, …)` with the arguments sorted, for logs and the feed.
+    pub fn describe(&self) -> String {
+        let mut args: Vec<String> = self
+            .args
+            .iter()
+            .map(|(key, value)| match value {
+                Value::String(text) => format!("{key}={text}"),
+                other => format!("{key}={other}"),
+            })
+            .collect();
+        args.sort();
+        format!("{}({})", self.name, args.join(", "))
+    }
+}
+
+/// The tools offered to the agent; empty when the cluster has no API access.
+#[derive(Clone, Default)]
+pub struct ToolRegistry {
+    tools: Vec<Arc<dyn AgentTool>>,
+}
+
+impl ToolRegistry {
+    pub fn new(tools: Vec<Arc<dyn AgentTool>>) -> Self {
+        Self { tools }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+
+    /// One line per tool for the agent's prompt.
+    pub fn describe(&self) -> String {
+        self.tools
+            .iter()
+            .map(|tool| format!("- {}: {}", tool.name(), tool.description()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub async fn call(&self, call: &ToolCall) -> CliResult<String> {
+        let tool = self
+            .tools
+            .iter()
+            .find(|tool| tool.name() == call.name)
+            .ok_or_else(|| format!("unknown tool {}", call.name))?;
+        tool.call(&call.args).await
+    }
+}
+
+/// The string argument `key` of a tool call, trimmed; an error names the missing argument.
+pub fn required_arg<'a>(args: &'a HashMap<String, Value>, key: &str) -> CliResult<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| format!("missing argument {key}").into())
+}
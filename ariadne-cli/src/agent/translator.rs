@@ -1,26 +1,54 @@
 use ::llm::builder::LLMBuilder;
-use ::llm::chat::{ChatMessage, StructuredOutputFormat};
+use ::llm::chat::{ChatMessage, StructuredOutputFormat, Tool, ToolChoice};
 use async_trait::async_trait;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
-use crate::agent::prompts::base_prompt;
-use crate::agent::types::{ConversationTurn, LlmConfig, LlmUsage, TranslationResult};
-use crate::agent::util::{extract_cypher, map_llm_error, parse_structured_cypher};
+use crate::agent::prompts::{base_prompt, with_instructions};
+use crate::agent::schema_subset::relevant_labels;
+use crate::agent::types::{
+    with_previous_question, ConversationTurn, EntityCandidate, EntityHint, FewShotExample,
+    LlmConfig, LlmUsage, QueryCandidate, Translation, TranslationResult,
+};
+use crate::agent::util::{
+    clarification_from_payload, cypher_from_payload, extract_clarification, extract_cypher,
+    map_llm_error, output_tool, parse_json_response, parse_structured_clarification,
+    parse_structured_cypher, parse_structured_reasoning, reask_for_json, reasoning_from_payload,
+    sum_usage, supports_tool_calls, tool_call_arguments,
+};
 use crate::error::CliResult;
+use crate::telemetry::{LlmSpan, SpanModel};
 
 #[async_trait]
 pub trait Translator: Send + Sync {
+    #[allow(clippy::too_many_arguments)]
     async fn translate(
         &self,
         question: &str,
         context: &[ConversationTurn],
         context_summary: Option<&str>,
         feedback: Option<&str>,
-    ) -> CliResult<TranslationResult>;
+        examples: &[FewShotExample],
+        hints: &[EntityHint],
+        candidates: &[EntityCandidate],
+    ) -> CliResult<Translation>;
+
+    /// Changes whenever the same question could be translated differently: with another model,
+    /// system prompt or graph schema. Cached translations are only reused within one version.
+    fn schema_version(&self) -> u64;
 }
 
 pub struct LlmTranslator {
     llm: Box<dyn ::llm::LLMProvider>,
     structured_output: bool,
+    /// Set when structured output goes through a forced tool call instead of a JSON schema.
+    output_tool: Option<Tool>,
+    /// Set when each question gets a system prompt with only the part of the schema it needs.
+    subset_config: Option<LlmConfig>,
+    /// Queries asked for besides the first choice.
+    alternatives: usize,
+    schema_version: u64,
+    span_model: SpanModel,
 }
 
 impl LlmTranslator {
@@ -32,61 +60,272 @@ impl LlmTranslator {
             return Err("LLM model is empty".into());
         }
 
-        let mut builder = LLMBuilder::new()
-            .backend(config.backend.clone())
-            .model(config.model.clone())
-            .timeout_seconds(config.timeout_secs)
-            .normalize_response(true)
-            .system(base_prompt(config.structured_output));
-
-        if config.structured_output {
-            builder = builder.schema(cypher_schema());
-        }
-
-        if !config.base_url.trim().is_empty() {
-            builder = builder.base_url(config.base_url.clone());
-        }
-        if let Some(api_key) = &config.api_key {
-            builder = builder.api_key(api_key.clone());
-        }
-
-        let llm = builder.build()?;
+        let (llm, tool) = build_provider(&config, None)?;
         Ok(Self {
             llm,
             structured_output: config.structured_output,
+            output_tool: tool,
+            subset_config: config.schema_subset.then(|| config.clone()),
+            alternatives: config.candidates.saturating_sub(1),
+            schema_version: schema_version(&config),
+            span_model: SpanModel::from(&config),
         })
     }
+
+    /// A provider whose system prompt only describes the node types relevant to `question`
+    /// and the turn before it, or `None` when subsetting is off or nothing matched.
+    fn subset_provider(
+        &self,
+        question: &str,
+        context: &[ConversationTurn],
+    ) -> CliResult<Option<Box<dyn ::llm::LLMProvider>>> {
+        let Some(config) = &self.subset_config else {
+            return Ok(None);
+        };
+        let Some(labels) = relevant_labels(&with_previous_question(question, context)) else {
+            return Ok(None);
+        };
+        tracing::debug!(labels = ?labels, "Translating with a schema subset");
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+        let (llm, _) = build_provider(config, Some(&labels))?;
+        Ok(Some(llm))
+    }
+}
+
+/// A hash of the model and the full system prompt, which carries the schema. Prompt subsets
+/// follow from the question, so they need no part in it.
+fn schema_version(config: &LlmConfig) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    format!("{:?}", config.backend).hash(&mut hasher);
+    config.base_url.hash(&mut hasher);
+    config.model.hash(&mut hasher);
+    config.schema_subset.hash(&mut hasher);
+    with_instructions(
+        base_prompt(
+            config.structured_output,
+            config.prompt_profile,
+            None,
+            config.candidates,
+        ),
+        config.instructions.as_deref(),
+    )
+    .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Builds the translator's provider, with a system prompt limited to `labels` when given.
+fn build_provider(
+    config: &LlmConfig,
+    labels: Option<&[&str]>,
+) -> CliResult<(Box<dyn ::llm::LLMProvider>, Option<Tool>)> {
+    let mut builder = LLMBuilder::new()
+        .backend(config.backend.clone())
+        .model(config.model.clone())
+        .timeout_seconds(config.timeout_secs)
+        .normalize_response(true)
+        .system(with_instructions(
+            base_prompt(
+                config.structured_output,
+                config.prompt_profile,
+                labels,
+                config.candidates,
+            ),
+            config.instructions.as_deref(),
+        ));
+
+    let schema = if config.candidates > 1 {
+        candidates_schema()
+    } else {
+        cypher_schema()
+    };
+    let mut tool = None;
+    if config.structured_output && supports_tool_calls(&config.backend) {
+        let cypher_tool = output_tool(schema);
+        builder = builder.tool_choice(ToolChoice::Tool(cypher_tool.function.name.clone()));
+        tool = Some(cypher_tool);
+    } else if config.structured_output {
+        builder = builder.schema(schema);
+    }
+
+    if !config.base_url.trim().is_empty() {
+        builder = builder.base_url(config.base_url.clone());
+    }
+    if let Some(api_key) = &config.api_key {
+        builder = builder.api_key(api_key.clone());
+    }
+
+    Ok((builder.build()?, tool))
 }
 
 #[async_trait]
 impl Translator for LlmTranslator {
+    #[allow(clippy::too_many_arguments)]
     async fn translate(
         &self,
         question: &str,
         context: &[ConversationTurn],
         context_summary: Option<&str>,
         feedback: Option<&str>,
-    ) -> CliResult<TranslationResult> {
-        let messages = build_messages(question, context, context_summary, feedback);
-        let response = match self.llm.chat(&messages).await {
+        examples: &[FewShotExample],
+        hints: &[EntityHint],
+        candidates: &[EntityCandidate],
+    ) -> CliResult<Translation> {
+        let messages = build_messages(
+            question,
+            context,
+            context_summary,
+            feedback,
+            examples,
+            hints,
+            candidates,
+        );
+        // A retry after a failed attempt sees the whole schema, in case the subset missed a type.
+        let subset = match feedback {
+            Some(_) => None,
+            None => self.subset_provider(question, context)?,
+        };
+        let llm = subset.as_deref().unwrap_or(self.llm.as_ref());
+        let span = LlmSpan::start("translate", &self.span_model);
+        let response = match &self.output_tool {
+            Some(tool) => {
+                llm.chat_with_tools(&messages, Some(std::slice::from_ref(tool)))
+                    .await
+            }
+            None => llm.chat(&messages).await,
+        };
+        let response = match response {
             Ok(response) => response,
-            Err(err) => return Err(map_llm_error(err, self.structured_output)),
+            Err(err) => {
+                span.fail(&err);
+                return Err(map_llm_error(err, self.structured_output));
+            }
         };
         let usage = response.usage().map(LlmUsage::from);
-        let text = response
+        span.finish(usage.as_ref());
+        if let Some(tool) = &self.output_tool {
+            let payload = tool_call_arguments(response.as_ref(), &tool.function.name)?;
+            if let Some(question) = clarification_from_payload(&payload) {
+                return Ok(Translation::NeedsClarification { question, usage });
+            }
+            let (cypher, params) = cypher_from_payload(&payload)?;
+            return Ok(Translation::Query(TranslationResult {
+                cypher,
+                params,
+                reasoning: reasoning_from_payload(&payload),
+                usage,
+                alternatives: alternatives_from_payload(&payload, self.alternatives),
+            }));
+        }
+        let mut text = response
             .text()
             .ok_or_else(|| "LLM response missing text".to_string())?;
-        let (cypher, params) = if self.structured_output {
-            parse_structured_cypher(&text)?
+        let mut usage = usage;
+        if self.structured_output {
+            if let Err(err) = parse_json_response::<serde_json::Value>(&text) {
+                tracing::warn!("Translation is not valid JSON, asking again: {err}");
+                let (retry, retry_usage) =
+                    reask_for_json(llm, &messages, &text, &err.to_string(), &self.span_model)
+                        .await?;
+                text = retry;
+                usage = sum_usage(usage, retry_usage);
+            }
+        }
+        let clarification = if self.structured_output {
+            parse_structured_clarification(&text)
+        } else {
+            extract_clarification(&text)
+        };
+        if let Some(question) = clarification {
+            return Ok(Translation::NeedsClarification { question, usage });
+        }
+        if !self.structured_output && self.alternatives > 0 {
+            let mut queries = split_queries(&text).into_iter();
+            let cypher = queries.next().unwrap_or_default();
+            return Ok(Translation::Query(TranslationResult {
+                cypher,
+                params: None,
+                reasoning: None,
+                usage,
+                alternatives: queries
+                    .take(self.alternatives)
+                    .map(|cypher| QueryCandidate {
+                        cypher,
+                        params: None,
+                    })
+                    .collect(),
+            }));
+        }
+        let (cypher, params, reasoning, alternatives) = if self.structured_output {
+            let (cypher, params) = parse_structured_cypher(&text)?;
+            let alternatives = parse_json_response::<serde_json::Value>(&text)
+                .map(|payload| alternatives_from_payload(&payload, self.alternatives))
+                .unwrap_or_default();
+            (
+                cypher,
+                params,
+                parse_structured_reasoning(&text),
+                alternatives,
+            )
         } else {
-            (extract_cypher(&text), None)
+            (extract_cypher(&text), None, None, Vec::new())
         };
-        Ok(TranslationResult {
+        Ok(Translation::Query(TranslationResult {
             cypher,
             params,
+            reasoning,
             usage,
+            alternatives,
+        }))
+    }
+
+    fn schema_version(&self) -> u64 {
+        self.schema_version
+    }
+}
+
+/// Up to `limit` well-formed entries of the `alternatives` of a `CypherCandidates` payload.
+/// Entries that cannot be read are dropped, as the first query is still usable without them.
+fn alternatives_from_payload(payload: &serde_json::Value, limit: usize) -> Vec<QueryCandidate> {
+    let Some(items) = payload
+        .get("alternatives")
+        .and_then(serde_json::Value::as_array)
+    else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| match cypher_from_payload(item) {
+            Ok((cypher, params)) if !cypher.trim().is_empty() => {
+                Some(QueryCandidate { cypher, params })
+            }
+            Ok(_) => None,
+            Err(err) => {
+                tracing::debug!("Dropping alternative query: {err}");
+                None
+            }
         })
+        .take(limit)
+        .collect()
+}
+
+/// The queries of an unstructured reply listing several, separated by `---` lines.
+fn split_queries(text: &str) -> Vec<String> {
+    let mut queries = Vec::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        if line.trim() == "---" {
+            queries.push(std::mem::take(&mut current));
+        } else {
+            current.push_str(line);
+            current.push('\n');
+        }
     }
+    queries.push(current);
+    queries
+        .iter()
+        .map(|query| extract_cypher(query))
+        .filter(|query| !query.is_empty())
+        .collect()
 }
 
 fn build_messages(
@@ -94,8 +333,19 @@ fn build_messages(
     context: &[ConversationTurn],
     context_summary: Option<&str>,
     feedback: Option<&str>,
+    examples: &[FewShotExample],
+    hints: &[EntityHint],
+    candidates: &[EntityCandidate],
 ) -> Vec<ChatMessage> {
     let mut messages = Vec::new();
+    for example in examples {
+        messages.push(ChatMessage::user().content(example.question.trim()).build());
+        messages.push(
+            ChatMessage::assistant()
+                .content(format!("Cypher:\n{}", example.cypher.trim()))
+                .build(),
+        );
+    }
     if let Some(summary) = context_summary {
         let summary = summary.trim();
         if !summary.is_empty() {
@@ -133,17 +383,60 @@ fn build_messages(
             messages.push(
                 ChatMessage::user()
                     .content(format!(
-                        "Previous Cypher failed validation: {feedback}\n\
+                        "Previous Cypher failed: {feedback}\n\
 Please correct the Cypher. Return only the fixed query."
                     ))
                     .build(),
             );
         }
     }
-    messages.push(ChatMessage::user().content(question.trim()).build());
+    messages.push(
+        ChatMessage::user()
+            .content(with_entity_hints(question.trim(), hints, candidates))
+            .build(),
+    );
     messages
 }
 
+/// Appends the likely intended resource names and the graph nodes the question may be about,
+/// so a typo or a guessed name does not end up as a literal in the query.
+fn with_entity_hints(
+    question: &str,
+    hints: &[EntityHint],
+    candidates: &[EntityCandidate],
+) -> String {
+    let mut content = question.to_string();
+    if !hints.is_empty() {
+        content.push_str(
+            "\n\nResource names in the cluster that words of the question probably refer to \
+             (use these exact names):",
+        );
+        for hint in hints {
+            content.push_str(&format!(
+                "\n- \"{}\" -> {} \"{}\"",
+                hint.mentioned, hint.kind, hint.name
+            ));
+            if let Some(namespace) = &hint.namespace {
+                content.push_str(&format!(" in namespace \"{namespace}\""));
+            }
+        }
+    }
+    if !candidates.is_empty() {
+        content.push_str(
+            "\n\nGraph nodes the question may be about (match them by metadata.name or \
+             metadata.uid; do not invent names that are not listed or asked for):",
+        );
+        for candidate in candidates {
+            content.push_str(&format!("\n- {} \"{}\"", candidate.kind, candidate.name));
+            if let Some(namespace) = &candidate.namespace {
+                content.push_str(&format!(" in namespace \"{namespace}\""));
+            }
+            content.push_str(&format!(" (uid {})", candidate.uid));
+        }
+    }
+    content
+}
+
 fn format_bindings(bindings: &std::collections::HashMap<String, serde_json::Value>) -> String {
     let mut entries: Vec<String> = bindings
         .iter()
@@ -178,15 +471,74 @@ fn cypher_schema() -> StructuredOutputFormat {
                         },
                         "required": ["key", "value"]
                     }
-                }
+                },
+                "reasoning": { "type": "string" },
+                "clarification": { "type": "string" }
             },
-            "required": ["cypher", "params"]
+            "required": ["cypher", "params", "reasoning", "clarification"]
         }
     }
     "#;
     serde_json::from_str(SCHEMA).expect("invalid CypherQuery schema JSON")
 }
 
+/// [`cypher_schema`] with the other queries the translator considered.
+fn candidates_schema() -> StructuredOutputFormat {
+    const SCHEMA: &str = r#"
+    {
+        "name": "CypherCandidates",
+        "description": "Cypher query result with alternative queries",
+        "strict": true,
+        "schema": {
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "cypher": { "type": "string" },
+                "params": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "additionalProperties": false,
+                        "properties": {
+                            "key": { "type": "string" },
+                            "value": { "type": "string" }
+                        },
+                        "required": ["key", "value"]
+                    }
+                },
+                "reasoning": { "type": "string" },
+                "clarification": { "type": "string" },
+                "alternatives": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "additionalProperties": false,
+                        "properties": {
+                            "cypher": { "type": "string" },
+                            "params": {
+                                "type": "array",
+                                "items": {
+                                    "type": "object",
+                                    "additionalProperties": false,
+                                    "properties": {
+                                        "key": { "type": "string" },
+                                        "value": { "type": "string" }
+                                    },
+                                    "required": ["key", "value"]
+                                }
+                            }
+                        },
+                        "required": ["cypher", "params"]
+                    }
+                }
+            },
+            "required": ["cypher", "params", "reasoning", "clarification", "alternatives"]
+        }
+    }
+    "#;
+    serde_json::from_str(SCHEMA).expect("invalid CypherCandidates schema JSON")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -195,5 +547,102 @@ mod tests {
     fn cypher_schema_parses() {
         let schema = cypher_schema();
         assert_eq!(schema.name, "CypherQuery");
+        assert_eq!(candidates_schema().name, "CypherCandidates");
+    }
+
+    #[test]
+    fn alternative_queries_are_read_from_both_formats() {
+        let payload = serde_json::json!({
+            "cypher": "MATCH (d:Deployment) RETURN d",
+            "alternatives": [
+                { "cypher": "MATCH (s:StatefulSet) RETURN s", "params": [] },
+                { "cypher": " ", "params": [] },
+                { "params": [] },
+                {
+                    "cypher": "MATCH (p:Pod {name: $name}) RETURN p",
+                    "params": [{ "key": "name", "value": "\"web\"" }]
+                }
+            ]
+        });
+        let alternatives = alternatives_from_payload(&payload, 5);
+        assert_eq!(alternatives.len(), 2);
+        assert_eq!(alternatives[0].cypher, "MATCH (s:StatefulSet) RETURN s");
+        assert_eq!(
+            alternatives[1].params.as_ref().unwrap()["name"],
+            serde_json::json!("web")
+        );
+        assert_eq!(alternatives_from_payload(&payload, 1).len(), 1);
+
+        let text = "MATCH (d:Deployment) RETURN d\n---\n\
+                    ```cypher\nMATCH (s:StatefulSet) RETURN s\n```\n---\n";
+        assert_eq!(
+            split_queries(text),
+            [
+                "MATCH (d:Deployment) RETURN d",
+                "MATCH (s:StatefulSet) RETURN s"
+            ]
+        );
+    }
+
+    #[test]
+    fn entity_hints_are_appended_to_the_question() {
+        assert_eq!(with_entity_hints("list pods", &[], &[]), "list pods");
+        let hint = EntityHint {
+            mentioned: "paymets-api".to_string(),
+            name: "payments-api".to_string(),
+            kind: "Deployment".to_string(),
+            namespace: Some("shop".to_string()),
+        };
+        let content = with_entity_hints("why is paymets-api down?", &[hint], &[]);
+        assert!(content.starts_with("why is paymets-api down?\n\n"));
+        assert!(content
+            .ends_with("\n- \"paymets-api\" -> Deployment \"payments-api\" in namespace \"shop\""));
+    }
+
+    #[test]
+    fn entity_candidates_are_listed_with_their_uid() {
+        let candidate = EntityCandidate {
+            uid: "0b5c".to_string(),
+            name: "payments-api".to_string(),
+            kind: "Deployment".to_string(),
+            namespace: Some("shop".to_string()),
+        };
+        let content = with_entity_hints("is the payment api healthy?", &[], &[candidate]);
+        assert!(content.starts_with("is the payment api healthy?\n\nGraph nodes"));
+        assert!(
+            content.ends_with("\n- Deployment \"payments-api\" in namespace \"shop\" (uid 0b5c)")
+        );
+    }
+
+    #[test]
+    fn earlier_turns_precede_the_question() {
+        let turn = ConversationTurn {
+            question: "list failing pods".to_string(),
+            cypher: "MATCH (p:Pod) RETURN p".to_string(),
+            result_summary: Some("3 rows".to_string()),
+            bindings: None,
+        };
+        let messages = build_messages(
+            "and now only in namespace prod",
+            &[turn],
+            Some("Looked at pods of the shop team."),
+            None,
+            &[],
+            &[],
+            &[],
+        );
+        let contents: Vec<&str> = messages
+            .iter()
+            .map(|message| message.content.as_str())
+            .collect();
+        assert_eq!(
+            contents,
+            [
+                "Context summary:\nLooked at pods of the shop team.",
+                "list failing pods",
+                "Cypher:\nMATCH (p:Pod) RETURN p\nResult summary:\n3 rows",
+                "and now only in namespace prod",
+            ]
+        );
     }
 }
@@ -1,14 +1,22 @@
 use ::llm::builder::LLMBackend;
+use clap::ValueEnum;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
 
-#[derive(Debug, Clone)]
+use crate::agent::tools::ToolCall;
+use crate::docs::{DocCitation, DocsIndex};
+
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct LlmUsage {
     pub prompt_tokens: u32,
     pub completion_tokens: u32,
     pub total_tokens: u32,
     pub reasoning_tokens: Option<u32>,
     pub cached_tokens: Option<u32>,
+    /// The fallback that answered when the configured model failed; see
+    /// [`LlmConfig::fallbacks`].
+    pub model: Option<String>,
 }
 
 impl From<::llm::chat::Usage> for LlmUsage {
@@ -25,6 +33,7 @@ impl From<::llm::chat::Usage> for LlmUsage {
             total_tokens: value.total_tokens,
             reasoning_tokens,
             cached_tokens,
+            model: None,
         }
     }
 }
@@ -33,7 +42,55 @@ impl From<::llm::chat::Usage> for LlmUsage {
 pub struct TranslationResult {
     pub cypher: String,
     pub params: Option<HashMap<String, Value>>,
+    pub reasoning: Option<String>,
     pub usage: Option<LlmUsage>,
+    /// Other queries for the same question, in the translator's order of preference; empty
+    /// unless [`LlmConfig::candidates`] is above one.
+    pub alternatives: Vec<QueryCandidate>,
+}
+
+/// A query the translator wrote besides its first choice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryCandidate {
+    pub cypher: String,
+    pub params: Option<HashMap<String, Value>>,
+}
+
+/// What the translator made of a question.
+#[derive(Debug, Clone)]
+pub enum Translation {
+    Query(TranslationResult),
+    /// The question is too ambiguous to translate without guessing; `question` is what to ask
+    /// the user back.
+    NeedsClarification {
+        question: String,
+        usage: Option<LlmUsage>,
+    },
+}
+
+/// An approved question/Cypher pair shown to the translator before the actual question.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FewShotExample {
+    pub question: String,
+    pub cypher: String,
+}
+
+/// A word of the question that is close to, but not exactly, a resource name in the cluster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityHint {
+    pub mentioned: String,
+    pub name: String,
+    pub kind: String,
+    pub namespace: Option<String>,
+}
+
+/// A graph node whose name resembles the question, found in the cluster's entity index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityCandidate {
+    pub uid: String,
+    pub name: String,
+    pub kind: String,
+    pub namespace: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -44,6 +101,15 @@ pub struct ConversationTurn {
     pub bindings: Option<HashMap<String, Value>>,
 }
 
+/// `question` preceded by the question of the last turn, for lookups by the words of a question
+/// that may only refine the one before it ("and now only in namespace prod").
+pub fn with_previous_question(question: &str, context: &[ConversationTurn]) -> String {
+    match context.last() {
+        Some(turn) => format!("{}\n{question}", turn.question),
+        None => question.to_string(),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LlmConfig {
     pub backend: LLMBackend,
@@ -52,6 +118,35 @@ pub struct LlmConfig {
     pub api_key: Option<String>,
     pub timeout_secs: u64,
     pub structured_output: bool,
+    pub prompt_profile: PromptProfile,
+    /// Give the translator only the node types a question names or hints at, plus the types
+    /// connecting them, instead of the whole schema.
+    pub schema_subset: bool,
+    /// Stream the analyst's answer so it can be shown section by section while it is written.
+    pub stream_analysis: bool,
+    /// Queries the translator writes for each question, for the pipeline to pick the best
+    /// from; `1` asks for a single query.
+    pub candidates: usize,
+    /// The operator's instructions for the cluster, such as naming conventions or escalation
+    /// policies, appended to the translator's and the analyst's system prompts.
+    pub instructions: Option<String>,
+    /// Documentation passages the analyst may cite to explain what the rows show.
+    pub docs: Option<Arc<DocsIndex>>,
+    /// Language the analyst writes answers, follow-ups and reports in, such as `German`;
+    /// queries stay as they are. Unset leaves it to the model.
+    pub answer_language: Option<String>,
+    /// Models tried in order when a call to this one times out, is rate limited or fails with
+    /// a server error. Only their backend, model, base URL and API key are used.
+    pub fallbacks: Vec<LlmConfig>,
+}
+
+/// How much of the graph schema the system prompts carry. `Compact` suits small local models
+/// with short context windows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum PromptProfile {
+    #[default]
+    Full,
+    Compact,
 }
 
 #[derive(Debug, Clone)]
@@ -60,7 +155,7 @@ pub struct ContextCompaction {
     pub usage: Option<LlmUsage>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct AnalysisResult {
     pub title: String,
     pub summary: String,
@@ -68,33 +163,97 @@ pub struct AnalysisResult {
     pub rows: Vec<Value>,
     pub follow_ups: Vec<String>,
     pub confidence: String,
+    /// The documentation passages the answer cites.
+    pub citations: Vec<DocCitation>,
+    pub usage: Option<LlmUsage>,
+}
+
+/// A postmortem of the whole session, written from every question, query, row sample and
+/// answer of the feed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncidentReport {
+    pub title: String,
+    /// What happened, oldest first, each entry starting with its time when one is known.
+    pub timeline: Vec<String>,
+    /// The most likely root cause, as a hypothesis.
+    pub root_cause: String,
+    /// Facts from the session that support or contradict the hypothesis.
+    pub evidence: Vec<String>,
+    pub actions: Vec<String>,
+    pub confidence: String,
+    pub usage: Option<LlmUsage>,
+}
+
+/// An alert rule proposed from an investigation session. The query passed the validator and
+/// the condition parses, so it can be added to the alert rules as is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProposedMonitor {
+    pub name: String,
+    pub query: String,
+    /// `<rows|column> <op> <number>`, as in the alert rules file.
+    pub condition: String,
+    /// What the monitor would have caught in the session.
+    pub rationale: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorProposals {
+    pub monitors: Vec<ProposedMonitor>,
+    pub usage: Option<LlmUsage>,
+}
+
+/// A suggested next question with the query that answers it, which passed the validator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FollowUp {
+    pub question: String,
+    pub cypher: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct FollowUpSuggestions {
+    pub follow_ups: Vec<FollowUp>,
     pub usage: Option<LlmUsage>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RouteDecision {
+    /// Answered from the conversation so far, without a query.
+    Direct,
     OneShot,
     MultiTurn,
+    /// Too ambiguous to query; the router asks the user a question instead.
+    Clarify,
 }
 
 impl RouteDecision {
     pub fn as_str(&self) -> &'static str {
         match self {
+            RouteDecision::Direct => "direct",
             RouteDecision::OneShot => "one_shot",
             RouteDecision::MultiTurn => "multi_turn",
+            RouteDecision::Clarify => "clarify",
         }
     }
+
+    /// Whether the route needs a Cypher query, as opposed to a reply from the router.
+    pub fn needs_query(&self) -> bool {
+        matches!(self, RouteDecision::OneShot | RouteDecision::MultiTurn)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct RouteResult {
     pub decision: RouteDecision,
+    /// The answer for [`RouteDecision::Direct`] or the question for [`RouteDecision::Clarify`].
+    pub reply: Option<String>,
     pub usage: Option<LlmUsage>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AgentAction {
     Query,
+    /// Calls one of the agent's tools instead of querying the graph.
+    Tool,
     Final,
 }
 
@@ -102,6 +261,7 @@ impl AgentAction {
     pub fn as_str(&self) -> &'static str {
         match self {
             AgentAction::Query => "query",
+            AgentAction::Tool => "tool",
             AgentAction::Final => "final",
         }
     }
@@ -112,6 +272,8 @@ pub struct AgentStep {
     pub action: AgentAction,
     pub cypher: String,
     pub params: Option<HashMap<String, Value>>,
+    /// The tool a [`AgentAction::Tool`] step called; its `cypher` is empty.
+    pub tool: Option<ToolCall>,
     pub result_summary: Option<String>,
     pub usage: Option<LlmUsage>,
 }
@@ -1,9 +1,15 @@
+use ::llm::builder::LLMBackend;
+use ::llm::chat::{ChatMessage, ChatResponse, FunctionTool, StructuredOutputFormat, Tool};
 use ::llm::error::LLMError;
+use ariadne_core::errors::AriadneError;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 use std::collections::HashMap;
 use tracing::error;
 
+use crate::agent::types::LlmUsage;
 use crate::error::CliResult;
+use crate::telemetry::{LlmSpan, SpanModel};
 
 pub fn extract_cypher(text: &str) -> String {
     let trimmed = text.trim();
@@ -26,9 +32,12 @@ pub fn extract_cypher(text: &str) -> String {
 }
 
 pub fn parse_structured_cypher(text: &str) -> CliResult<(String, Option<HashMap<String, Value>>)> {
-    let cleaned = clean_json_response(text);
-    let payload: Value =
-        serde_json::from_str(&cleaned).map_err(|e| format!("Invalid JSON response: {e}"))?;
+    let payload: Value = parse_json_response(text)?;
+    cypher_from_payload(&payload)
+}
+
+/// The query and parameters of a `CypherQuery` object, from a JSON response or tool call.
+pub fn cypher_from_payload(payload: &Value) -> CliResult<(String, Option<HashMap<String, Value>>)> {
     let cypher = payload
         .get("cypher")
         .and_then(|value| value.as_str())
@@ -71,6 +80,77 @@ pub fn parse_structured_cypher(text: &str) -> CliResult<(String, Option<HashMap<
     Ok((extract_cypher(cypher), params))
 }
 
+pub fn parse_structured_reasoning(text: &str) -> Option<String> {
+    let payload: Value = parse_json_response(text).ok()?;
+    reasoning_from_payload(&payload)
+}
+
+pub fn reasoning_from_payload(payload: &Value) -> Option<String> {
+    payload
+        .get("reasoning")
+        .and_then(|value| value.as_str())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// The question to ask the user back when a `CypherQuery` object asks for clarification
+/// instead of holding a query.
+pub fn clarification_from_payload(payload: &Value) -> Option<String> {
+    payload
+        .get("clarification")
+        .and_then(|value| value.as_str())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+pub fn parse_structured_clarification(text: &str) -> Option<String> {
+    let payload: Value = parse_json_response(text).ok()?;
+    clarification_from_payload(&payload)
+}
+
+/// The question of a plain-text response of the form `Clarify: <question>`.
+pub fn extract_clarification(text: &str) -> Option<String> {
+    const PREFIX: &str = "clarify:";
+    let trimmed = text.trim();
+    if !trimmed.get(..PREFIX.len())?.eq_ignore_ascii_case(PREFIX) {
+        return None;
+    }
+    let question = trimmed[PREFIX.len()..].trim();
+    (!question.is_empty()).then(|| question.to_string())
+}
+
+/// Backends whose chat APIs accept tool definitions and can be made to call a given tool.
+pub fn supports_tool_calls(backend: &LLMBackend) -> bool {
+    matches!(backend, LLMBackend::OpenAI | LLMBackend::Anthropic)
+}
+
+/// A structured output format as a function tool. The tool's arguments follow the schema, so
+/// they are parsed directly instead of being dug out of the response text.
+pub fn output_tool(format: StructuredOutputFormat) -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: FunctionTool {
+            name: format.name,
+            description: format.description.unwrap_or_default(),
+            parameters: format
+                .schema
+                .unwrap_or_else(|| serde_json::json!({ "type": "object" })),
+        },
+    }
+}
+
+/// Arguments of the response's call to the tool `name`, parsed as JSON.
+pub fn tool_call_arguments(response: &dyn ChatResponse, name: &str) -> CliResult<Value> {
+    let call = response
+        .tool_calls()
+        .unwrap_or_default()
+        .into_iter()
+        .find(|call| call.function.name == name)
+        .ok_or_else(|| format!("LLM response did not call the {name} tool"))?;
+    serde_json::from_str(&call.function.arguments)
+        .map_err(|e| format!("Invalid {name} tool arguments: {e}").into())
+}
+
 pub fn clean_json_response(response_text: &str) -> String {
     let text = response_text.trim();
 
@@ -89,6 +169,138 @@ pub fn clean_json_response(response_text: &str) -> String {
     text.to_string()
 }
 
+/// Parses a structured response. When the strict parse fails, what models commonly get wrong
+/// is repaired first: prose or a code fence around the object and trailing commas. The error
+/// is the strict parser's.
+pub fn parse_json_response<T: DeserializeOwned>(text: &str) -> CliResult<T> {
+    match serde_json::from_str(&clean_json_response(text)) {
+        Ok(payload) => Ok(payload),
+        Err(err) => serde_json::from_str(&repair_json(text))
+            .map_err(|_| format!("Invalid JSON response: {err}").into()),
+    }
+}
+
+/// `text` cut to its outermost JSON object, without commas before a closing bracket.
+fn repair_json(text: &str) -> String {
+    let object = match (text.find('{'), text.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &text[start..=end],
+        _ => text.trim(),
+    };
+    let chars: Vec<char> = object.chars().collect();
+    let mut repaired = String::with_capacity(object.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, &ch) in chars.iter().enumerate() {
+        if in_string {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if ch == '"' {
+            in_string = true;
+        } else if ch == ','
+            && matches!(
+                chars[index + 1..].iter().find(|next| !next.is_whitespace()),
+                Some('}' | ']')
+            )
+        {
+            continue;
+        }
+        repaired.push(ch);
+    }
+    repaired
+}
+
+/// Asks once more after a structured reply that did not parse, showing the model its reply
+/// and the parse error. Returns the new reply's text and usage.
+pub async fn reask_for_json(
+    llm: &dyn ::llm::LLMProvider,
+    messages: &[ChatMessage],
+    reply: &str,
+    error: &str,
+    span_model: &SpanModel,
+) -> CliResult<(String, Option<LlmUsage>)> {
+    let mut messages = messages.to_vec();
+    messages.push(ChatMessage::assistant().content(reply).build());
+    messages.push(
+        ChatMessage::user()
+            .content(format!(
+                "Your reply is not valid JSON ({error}). Reply again with only the JSON object \
+                 the schema asks for."
+            ))
+            .build(),
+    );
+    let span = LlmSpan::start("reask", span_model);
+    let response = match llm.chat(&messages).await {
+        Ok(response) => response,
+        Err(err) => {
+            span.fail(&err);
+            return Err(map_llm_error(err, true));
+        }
+    };
+    let usage = response.usage().map(LlmUsage::from);
+    span.finish(usage.as_ref());
+    let text = response
+        .text()
+        .ok_or_else(|| "LLM response missing text".to_string())?;
+    Ok((text, usage))
+}
+
+/// The usage of two calls made for one answer.
+pub fn sum_usage(first: Option<LlmUsage>, second: Option<LlmUsage>) -> Option<LlmUsage> {
+    match (first, second) {
+        (Some(first), Some(second)) => Some(LlmUsage {
+            prompt_tokens: first.prompt_tokens.saturating_add(second.prompt_tokens),
+            completion_tokens: first
+                .completion_tokens
+                .saturating_add(second.completion_tokens),
+            total_tokens: first.total_tokens.saturating_add(second.total_tokens),
+            reasoning_tokens: first
+                .reasoning_tokens
+                .zip(second.reasoning_tokens)
+                .map(|(first, second)| first.saturating_add(second)),
+            cached_tokens: first
+                .cached_tokens
+                .zip(second.cached_tokens)
+                .map(|(first, second)| first.saturating_add(second)),
+            model: second.model.or(first.model),
+        }),
+        (first, second) => first.or(second),
+    }
+}
+
+/// Error fragments (lowercase) of calls the provider may well answer when repeated: timeouts,
+/// rate limits and server errors. A request the model rejected would fail the same way again.
+const TRANSIENT_LLM_ERRORS: &[&str] = &[
+    "timed out",
+    "timeout",
+    "429",
+    "too many requests",
+    "rate limit",
+    "rate_limit",
+    "overloaded",
+    "500",
+    "502",
+    "503",
+    "504",
+    "internal server error",
+    "bad gateway",
+    "service unavailable",
+];
+
+/// Whether an LLM error message reads like a transient failure; the `llm` crate reports HTTP
+/// statuses only in its messages.
+pub fn is_transient_llm_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    TRANSIENT_LLM_ERRORS
+        .iter()
+        .any(|fragment| message.contains(fragment))
+}
+
+/// The error of a failed LLM call as an [`AriadneError`] of class `llm`, retriable when it
+/// looks transient.
 pub fn map_llm_error(err: LLMError, structured: bool) -> Box<dyn std::error::Error + Send + Sync> {
     if structured {
         match err {
@@ -102,25 +314,43 @@ pub fn map_llm_error(err: LLMError, structured: bool) -> Box<dyn std::error::Err
 This provider/model may not support structured output. \
 Set LLM_STRUCTURED_OUTPUT=0 to disable."
                 );
-                return std::io::Error::other(msg).into();
+                return Box::new(AriadneError::llm(msg, false));
             }
             LLMError::InvalidRequest(message) => {
                 let msg = format!(
                     "LLM request rejected: {message}. \
 If this is due to response_format, set LLM_STRUCTURED_OUTPUT=0."
                 );
-                return std::io::Error::other(msg).into();
+                return Box::new(AriadneError::llm(msg, false));
             }
-            other => return Box::new(other),
+            other => return llm_error(other),
         }
     }
-    Box::new(err)
+    llm_error(err)
+}
+
+fn llm_error(err: LLMError) -> Box<dyn std::error::Error + Send + Sync> {
+    let message = err.to_string();
+    let retriable = is_transient_llm_error(&message);
+    Box::new(AriadneError::llm(message, retriable))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn malformed_json_is_repaired() {
+        let input = "Here is the query:\n```json\n{\"cypher\": \"MATCH (n) RETURN n\", \
+                     \"params\": {\"ns\": \"a,}\",},}\n```\nHope this helps!";
+        let (cypher, params) = parse_structured_cypher(input).unwrap();
+        assert_eq!(cypher, "MATCH (n) RETURN n");
+        assert_eq!(params.unwrap().get("ns"), Some(&Value::from("a,}")));
+
+        let err = parse_json_response::<Value>("{\"cypher\": ").unwrap_err();
+        assert!(err.to_string().starts_with("Invalid JSON response: EOF"));
+    }
+
     #[test]
     fn extract_cypher_from_fence() {
         let input = "```cypher\nMATCH (n) RETURN n\n```";
@@ -133,6 +363,22 @@ mod tests {
         assert_eq!(extract_cypher(input), "MATCH (n) RETURN n");
     }
 
+    #[test]
+    fn clarification_requests_are_recognized() {
+        assert_eq!(
+            extract_clarification("Clarify: Which namespace do you mean?").as_deref(),
+            Some("Which namespace do you mean?")
+        );
+        assert_eq!(extract_clarification("MATCH (n) RETURN n"), None);
+        let input = r#"{"cypher":"","params":[],"reasoning":"","clarification":"Which pod?"}"#;
+        assert_eq!(
+            parse_structured_clarification(input).as_deref(),
+            Some("Which pod?")
+        );
+        let input = r#"{"cypher":"MATCH (n) RETURN n","clarification":" "}"#;
+        assert_eq!(parse_structured_clarification(input), None);
+    }
+
     #[test]
     fn parse_structured_cypher_from_json() {
         let input = r#"{"cypher":"MATCH (n) RETURN n"}"#;
@@ -167,4 +413,33 @@ mod tests {
         assert_eq!(params.get("pod_name").and_then(|v| v.as_str()), Some("bar"));
         assert_eq!(params.get("count").and_then(|v| v.as_i64()), Some(3));
     }
+
+    #[test]
+    fn output_tool_carries_the_schema() {
+        let format: StructuredOutputFormat = serde_json::from_str(
+            r#"{"name":"CypherQuery","description":"Cypher query result",
+                "schema":{"type":"object","properties":{"cypher":{"type":"string"}}}}"#,
+        )
+        .unwrap();
+        let tool = output_tool(format);
+        assert_eq!(tool.tool_type, "function");
+        assert_eq!(tool.function.name, "CypherQuery");
+        assert_eq!(tool.function.description, "Cypher query result");
+        assert_eq!(
+            tool.function.parameters["properties"]["cypher"]["type"],
+            "string"
+        );
+        assert!(supports_tool_calls(&LLMBackend::Anthropic));
+        assert!(!supports_tool_calls(&LLMBackend::Ollama));
+    }
+
+    #[test]
+    fn parse_structured_reasoning_from_json() {
+        let input = r#"{"cypher":"MATCH (n) RETURN n","params":[],"reasoning":" All nodes. "}"#;
+        assert_eq!(
+            parse_structured_reasoning(input).as_deref(),
+            Some("All nodes.")
+        );
+        assert!(parse_structured_reasoning(r#"{"cypher":"MATCH (n) RETURN n"}"#).is_none());
+    }
 }
@@ -0,0 +1,321 @@
+//! Alert rules re-evaluated after every cluster diff. Only the GUI loads and runs them; the
+//! conditions are also checked for monitors the analyst proposes.
+
+#[cfg(feature = "gui")]
+use std::io::Write;
+#[cfg(feature = "gui")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "gui")]
+use std::sync::Arc;
+
+#[cfg(feature = "gui")]
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+#[cfg(feature = "gui")]
+use tokio::sync::watch;
+#[cfg(feature = "gui")]
+use tokio_util::sync::CancellationToken;
+
+#[cfg(feature = "gui")]
+use ariadne_core::graph_backend::GraphBackend;
+
+use crate::error::CliResult;
+#[cfg(feature = "gui")]
+use crate::settings::config_dir;
+#[cfg(feature = "gui")]
+use crate::validation::validate_cypher;
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Deserialize, Serialize)]
+struct AlertRulesFile {
+    #[serde(default)]
+    rules: Vec<AlertRuleConfig>,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Deserialize, Serialize)]
+struct AlertRuleConfig {
+    name: String,
+    query: String,
+    condition: String,
+}
+
+/// A read-only query re-evaluated after every applied cluster diff. The rule fires when its
+/// condition turns true and re-arms once it is false again.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub name: String,
+    pub query: String,
+    pub condition: AlertCondition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertCondition {
+    pub source: String,
+    target: ConditionTarget,
+    op: CompareOp,
+    threshold: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ConditionTarget {
+    /// Number of returned rows.
+    Rows,
+    /// Numeric column of the first row.
+    Column(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone)]
+pub struct AlertFired {
+    pub cluster: String,
+    pub rule: String,
+    pub condition: String,
+    pub observed: f64,
+    pub cypher: String,
+    pub records: Vec<Value>,
+}
+
+impl AlertCondition {
+    /// Parses `<rows|column> <op> <number>`, e.g. `rows > 0` or `restarts >= 5`.
+    pub fn parse(source: &str) -> CliResult<Self> {
+        let parts: Vec<&str> = source.split_whitespace().collect();
+        let [target, op, threshold] = parts.as_slice() else {
+            return Err(format!(
+                "invalid alert condition '{source}', expected '<rows|column> <op> <number>'"
+            )
+            .into());
+        };
+        let op = match *op {
+            ">" => CompareOp::Gt,
+            ">=" => CompareOp::Ge,
+            "<" => CompareOp::Lt,
+            "<=" => CompareOp::Le,
+            "==" | "=" => CompareOp::Eq,
+            "!=" => CompareOp::Ne,
+            other => return Err(format!("unknown operator '{other}' in '{source}'").into()),
+        };
+        let threshold: f64 = threshold
+            .parse()
+            .map_err(|_| format!("threshold in '{source}' is not a number"))?;
+        let target = if *target == "rows" {
+            ConditionTarget::Rows
+        } else {
+            ConditionTarget::Column(target.to_string())
+        };
+        Ok(Self {
+            source: source.trim().to_string(),
+            target,
+            op,
+            threshold,
+        })
+    }
+
+    /// Returns the observed value when the condition holds for these records.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub fn evaluate(&self, records: &[Value]) -> Option<f64> {
+        let observed = match &self.target {
+            ConditionTarget::Rows => records.len() as f64,
+            ConditionTarget::Column(column) => records.first()?.get(column)?.as_f64()?,
+        };
+        let holds = match self.op {
+            CompareOp::Gt => observed > self.threshold,
+            CompareOp::Ge => observed >= self.threshold,
+            CompareOp::Lt => observed < self.threshold,
+            CompareOp::Le => observed <= self.threshold,
+            CompareOp::Eq => observed == self.threshold,
+            CompareOp::Ne => observed != self.threshold,
+        };
+        holds.then_some(observed)
+    }
+}
+
+#[cfg(feature = "gui")]
+pub fn load_alert_rules(path: &Path) -> CliResult<Vec<AlertRule>> {
+    let contents = std::fs::read_to_string(path)?;
+    parse_alert_rules(&contents)
+}
+
+/// Where alert rules accepted in the app are kept when `--alert-rules` is not given.
+#[cfg(feature = "gui")]
+pub fn default_alert_rules_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("alert_rules.toml"))
+}
+
+/// Adds `rule` to the rules file at `path` as another `[[rules]]` table, creating the file when
+/// it does not exist yet. Comments and rules already in the file are left alone.
+#[cfg(feature = "gui")]
+pub fn append_alert_rule(path: &Path, rule: &AlertRule) -> CliResult<()> {
+    let table = toml::to_string(&AlertRulesFile {
+        rules: vec![AlertRuleConfig {
+            name: rule.name.clone(),
+            query: rule.query.clone(),
+            condition: rule.condition.source.clone(),
+        }],
+    })?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    if file.metadata()?.len() > 0 {
+        writeln!(file)?;
+    }
+    file.write_all(table.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(feature = "gui")]
+fn parse_alert_rules(contents: &str) -> CliResult<Vec<AlertRule>> {
+    let file: AlertRulesFile = toml::from_str(contents)?;
+    file.rules
+        .into_iter()
+        .map(|rule| {
+            validate_cypher(&rule.query)
+                .map_err(|issue| format!("alert rule '{}': {issue}", rule.name))?;
+            let condition = AlertCondition::parse(&rule.condition)
+                .map_err(|err| format!("alert rule '{}': {err}", rule.name))?;
+            Ok(AlertRule {
+                name: rule.name,
+                query: rule.query,
+                condition,
+            })
+        })
+        .collect()
+}
+
+/// Evaluates the rules once on start and again whenever `diffs` reports an applied diff,
+/// calling `on_fire` on each false → true transition.
+#[cfg(feature = "gui")]
+pub async fn run_alert_loop(
+    cluster: String,
+    backend: Arc<dyn GraphBackend>,
+    rules: Vec<AlertRule>,
+    mut diffs: watch::Receiver<u64>,
+    token: CancellationToken,
+    on_fire: impl Fn(AlertFired) + Send,
+) {
+    let mut firing = vec![false; rules.len()];
+    loop {
+        for (rule, was_firing) in rules.iter().zip(firing.iter_mut()) {
+            let records = match backend.execute_query(rule.query.clone(), None).await {
+                Ok(records) => records,
+                Err(err) => {
+                    tracing::warn!("Alert rule '{}' failed on {cluster}: {err}", rule.name);
+                    continue;
+                }
+            };
+            let observed = rule.condition.evaluate(&records);
+            if let (Some(observed), false) = (observed, *was_firing) {
+                tracing::info!("Alert rule '{}' fired on {cluster}", rule.name);
+                on_fire(AlertFired {
+                    cluster: cluster.clone(),
+                    rule: rule.name.clone(),
+                    condition: rule.condition.source.clone(),
+                    observed,
+                    cypher: rule.query.clone(),
+                    records,
+                });
+            }
+            *was_firing = observed.is_some();
+        }
+
+        tokio::select! {
+            _ = token.cancelled() => break,
+            changed = diffs.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+pub fn show_desktop_notification(alert: &AlertFired) {
+    let result = notify_rust::Notification::new()
+        .appname("Ariadne")
+        .summary(&format!("Alert: {}", alert.rule))
+        .body(&format!(
+            "{}: {} (observed {})",
+            alert.cluster, alert.condition, alert.observed
+        ))
+        .show();
+    if let Err(err) = result {
+        tracing::warn!("Failed to show desktop notification: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn alert_condition_evaluates_rows_and_columns() {
+        let rows = AlertCondition::parse("rows > 1").unwrap();
+        assert_eq!(rows.evaluate(&[json!({}), json!({})]), Some(2.0));
+        assert_eq!(rows.evaluate(&[json!({})]), None);
+
+        let column = AlertCondition::parse("restarts >= 5").unwrap();
+        assert_eq!(column.evaluate(&[json!({"restarts": 7})]), Some(7.0));
+        assert_eq!(column.evaluate(&[json!({"restarts": 1})]), None);
+        assert_eq!(column.evaluate(&[]), None);
+
+        assert!(AlertCondition::parse("rows >").is_err());
+        assert!(AlertCondition::parse("rows ~ 1").is_err());
+    }
+
+    #[cfg(feature = "gui")]
+    #[test]
+    fn parse_alert_rules_from_toml() {
+        let rules = parse_alert_rules(
+            r#"
+            [[rules]]
+            name = "pending pods"
+            query = "MATCH (p:Pod) WHERE p.status.phase = 'Pending' RETURN p.metadata.name AS name"
+            condition = "rows > 0"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].name, "pending pods");
+        assert_eq!(rules[0].condition.source, "rows > 0");
+    }
+
+    #[cfg(feature = "gui")]
+    #[test]
+    fn accepted_rules_are_appended_to_the_file() {
+        let dir = std::env::temp_dir().join(format!("ariadne-alerts-{}", std::process::id()));
+        let path = dir.join("alert_rules.toml");
+        let _ = std::fs::remove_file(&path);
+        for (name, condition) in [("pending pods", "rows > 0"), ("restarts", "restarts >= 5")] {
+            let rule = AlertRule {
+                name: name.to_string(),
+                query: "MATCH (p:Pod) RETURN count(p) AS restarts".to_string(),
+                condition: AlertCondition::parse(condition).unwrap(),
+            };
+            append_alert_rule(&path, &rule).unwrap();
+        }
+
+        let rules = load_alert_rules(&path).unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[1].name, "restarts");
+        assert_eq!(rules[1].condition.source, "restarts >= 5");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
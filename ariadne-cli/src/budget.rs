@@ -0,0 +1,149 @@
+use clap::ValueEnum;
+
+#[cfg(feature = "gui")]
+use crate::agent::{price_for_model, LlmConfig, ModelPrice, UsageCost};
+
+/// What happens to the next LLM call once the session budget is spent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum BudgetAction {
+    /// Switch to the cheapest configured model that costs less than the active one, and ask
+    /// for confirmation once there is none.
+    #[default]
+    Downgrade,
+    /// Ask for confirmation before every call.
+    Confirm,
+}
+
+/// Token and cost limits for the LLM calls of one session. A limit that is `None` is not
+/// enforced; with neither set the budget never runs out.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SessionBudget {
+    pub max_tokens: Option<u64>,
+    /// Limit in USD. Tokens of models without a price do not count towards it.
+    pub max_cost: Option<f64>,
+    pub action: BudgetAction,
+}
+
+#[cfg(feature = "gui")]
+impl SessionBudget {
+    pub fn is_enabled(&self) -> bool {
+        self.max_tokens.is_some() || self.max_cost.is_some()
+    }
+
+    /// The largest share of a limit that `spent` uses, e.g. `0.5` for half of the tokens.
+    pub fn used_fraction(&self, spent: &UsageCost) -> Option<f64> {
+        let tokens = self
+            .max_tokens
+            .map(|max| spent.tokens as f64 / max.max(1) as f64);
+        let cost = self
+            .max_cost
+            .map(|max| if max > 0.0 { spent.cost / max } else { 1.0 });
+        match (tokens, cost) {
+            (Some(tokens), Some(cost)) => Some(tokens.max(cost)),
+            (tokens, cost) => tokens.or(cost),
+        }
+    }
+
+    pub fn is_exceeded(&self, spent: &UsageCost) -> bool {
+        self.used_fraction(spent)
+            .is_some_and(|fraction| fraction >= 1.0)
+    }
+
+    /// Spent against the limits, e.g. `12.3k / 50.0k tokens · $0.41 / $1.00`.
+    pub fn describe(&self, spent: &UsageCost) -> String {
+        let mut parts = Vec::new();
+        if let Some(max) = self.max_tokens {
+            parts.push(format!(
+                "{} / {} tokens",
+                format_tokens(spent.tokens),
+                format_tokens(max)
+            ));
+        }
+        if let Some(max) = self.max_cost {
+            parts.push(format!("${:.2} / ${max:.2}", spent.cost));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Index of the cheapest model in `models` that costs less than the one at `active`. Models
+/// without a price are never picked, and nothing is picked while the active one has no price.
+#[cfg(feature = "gui")]
+pub fn cheaper_model(models: &[LlmConfig], active: usize) -> Option<usize> {
+    let prices: Vec<Option<ModelPrice>> = models
+        .iter()
+        .map(|config| price_for_model(&config.model))
+        .collect();
+    cheapest_below(&prices, active)
+}
+
+#[cfg(feature = "gui")]
+fn cheapest_below(prices: &[Option<ModelPrice>], active: usize) -> Option<usize> {
+    let current = blended(prices.get(active).copied().flatten()?);
+    prices
+        .iter()
+        .enumerate()
+        .filter_map(|(index, price)| Some((index, blended((*price)?))))
+        .filter(|(_, price)| *price < current)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+/// One number to compare models by: a million input tokens and a million output tokens.
+#[cfg(feature = "gui")]
+fn blended(price: ModelPrice) -> f64 {
+    price.input + price.output
+}
+
+#[cfg(feature = "gui")]
+fn format_tokens(tokens: u64) -> String {
+    if tokens >= 1_000_000 {
+        format!("{:.1}M", tokens as f64 / 1_000_000.0)
+    } else if tokens >= 1_000 {
+        format!("{:.1}k", tokens as f64 / 1_000.0)
+    } else {
+        tokens.to_string()
+    }
+}
+
+#[cfg(all(test, feature = "gui"))]
+mod tests {
+    use super::*;
+
+    fn price(input: f64, output: f64) -> Option<ModelPrice> {
+        Some(ModelPrice {
+            input,
+            output,
+            cached_input: None,
+        })
+    }
+
+    #[test]
+    fn the_tighter_limit_decides() {
+        let budget = SessionBudget {
+            max_tokens: Some(10_000),
+            max_cost: Some(1.0),
+            action: BudgetAction::Downgrade,
+        };
+        let spent = UsageCost {
+            tokens: 5_000,
+            cost: 1.2,
+            unpriced_tokens: 0,
+        };
+        assert!(budget.is_exceeded(&spent));
+        assert_eq!(
+            budget.describe(&spent),
+            "5.0k / 10.0k tokens · $1.20 / $1.00"
+        );
+        assert!(!SessionBudget::default().is_exceeded(&spent));
+    }
+
+    #[test]
+    fn downgrades_pick_the_cheapest_priced_model() {
+        let prices = [price(3.0, 15.0), None, price(0.25, 1.25), price(1.0, 5.0)];
+        assert_eq!(cheapest_below(&prices, 0), Some(2));
+        assert_eq!(cheapest_below(&prices, 2), None);
+        assert_eq!(cheapest_below(&prices, 1), None);
+    }
+}
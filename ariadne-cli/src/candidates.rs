@@ -0,0 +1,127 @@
+use crate::validation::{unknown_labels, validate_cypher};
+
+/// How a query the translator wrote held up before it was run for real.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// Failed validation or the dry run; never picked.
+    Rejected(String),
+    /// Names labels the graph does not have, so it can only return nothing.
+    UnknownLabels(Vec<String>),
+    /// The dry run found no rows.
+    Empty,
+    /// Passed validation and names only known labels; not dry-run.
+    Valid,
+    /// The dry run found a row.
+    Rows,
+}
+
+impl Verdict {
+    fn rank(&self) -> Option<u8> {
+        match self {
+            Verdict::Rejected(_) => None,
+            Verdict::UnknownLabels(_) => Some(0),
+            Verdict::Empty => Some(1),
+            Verdict::Valid => Some(2),
+            Verdict::Rows => Some(3),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Verdict::Rejected(reason) => format!("was rejected ({reason})"),
+            Verdict::UnknownLabels(labels) => {
+                format!("uses labels the graph does not have: {}", labels.join(", "))
+            }
+            Verdict::Empty => "found no rows".to_string(),
+            Verdict::Valid => "passed validation".to_string(),
+            Verdict::Rows => "found rows".to_string(),
+        }
+    }
+}
+
+/// Validates and lints `cypher`; a [`Verdict::Valid`] query may still be dry-run.
+pub fn check(cypher: &str) -> Verdict {
+    if let Err(issue) = validate_cypher(cypher) {
+        return Verdict::Rejected(issue.to_string());
+    }
+    let unknown = unknown_labels(cypher);
+    if unknown.is_empty() {
+        Verdict::Valid
+    } else {
+        Verdict::UnknownLabels(unknown)
+    }
+}
+
+/// Index of the best verdict, the first of equals since the translator lists its preferred
+/// query first. `None` when every query was rejected.
+pub fn best(verdicts: &[Verdict]) -> Option<usize> {
+    verdicts
+        .iter()
+        .enumerate()
+        .filter_map(|(index, verdict)| Some((index, verdict.rank()?)))
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+        .map(|(index, _)| index)
+}
+
+/// `cypher` limited to one row, to check that it runs and finds anything. A query that already
+/// ends with a `LIMIT` is left as it is.
+pub fn dry_run_query(cypher: &str) -> String {
+    let cypher = cypher.trim().trim_end_matches(';').trim_end();
+    let upper = cypher.to_ascii_uppercase();
+    let tail = upper.rfind("RETURN").map_or("", |start| &upper[start..]);
+    if tail.split_whitespace().any(|word| word == "LIMIT") {
+        cypher.to_string()
+    } else {
+        format!("{cypher}\nLIMIT 1")
+    }
+}
+
+/// Why the query at `picked` was run instead of the translator's first choice.
+pub fn selection_note(verdicts: &[Verdict], picked: usize) -> String {
+    let first = verdicts.first().map(Verdict::describe).unwrap_or_default();
+    format!(
+        "Ran query {} of {} the translator wrote: the first one {first}.",
+        picked + 1,
+        verdicts.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_best_verdict_wins_and_ties_keep_the_translator_order() {
+        let verdicts = [
+            Verdict::Rejected("unknown relationship".to_string()),
+            Verdict::Empty,
+            Verdict::Rows,
+            Verdict::Rows,
+        ];
+        assert_eq!(best(&verdicts), Some(2));
+        assert_eq!(
+            selection_note(&verdicts, 2),
+            "Ran query 3 of 4 the translator wrote: the first one was rejected \
+             (unknown relationship)."
+        );
+        assert_eq!(best(&[Verdict::Valid, Verdict::Valid]), Some(0));
+        assert_eq!(
+            best(&[
+                Verdict::UnknownLabels(vec!["Foo".to_string()]),
+                Verdict::Empty
+            ]),
+            Some(1)
+        );
+        assert_eq!(best(&[Verdict::Rejected(String::new())]), None);
+    }
+
+    #[test]
+    fn dry_runs_fetch_a_single_row() {
+        assert_eq!(
+            dry_run_query("MATCH (p:Pod) RETURN p.metadata.name;"),
+            "MATCH (p:Pod) RETURN p.metadata.name\nLIMIT 1"
+        );
+        let limited = "MATCH (p:Pod) RETURN p ORDER BY p.metadata.name limit 5";
+        assert_eq!(dry_run_query(limited), limited);
+    }
+}
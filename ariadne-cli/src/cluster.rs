@@ -0,0 +1,292 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use kube::config::KubeConfigOptions;
+use serde_json::Value;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+use ariadne_core::entity_index::SharedEntityIndex;
+use ariadne_core::graph_backend::GraphBackend;
+use ariadne_core::in_memory::InMemoryBackend;
+use ariadne_core::kube_client::{
+    CachedKubeClient, KubeClient, RecordingKubeClient, RecordingOptions, ReplayKubeClient,
+    SnapshotKubeClient, DEFAULT_MAX_TRACE_BYTES,
+};
+#[cfg(feature = "memgraph")]
+use ariadne_core::memgraph_async::MemgraphAsync;
+use ariadne_core::prelude::Result;
+use ariadne_core::redaction::RedactionPolicy;
+use ariadne_core::state::{ClusterStateDiff, SharedClusterState};
+use ariadne_core::state_resolver::{
+    ClusterStateResolver, ResolverHealth, ResolverOptions, DEFAULT_POLL_INTERVAL,
+};
+
+use crate::error::CliResult;
+
+/// Where to read a cluster from and which graph backend to load it into.
+#[derive(Debug, Clone)]
+pub struct ClusterSpec {
+    pub name: String,
+    pub kube_context: Option<String>,
+    pub kube_namespace: Option<String>,
+    pub snapshot_dir: Option<String>,
+    /// Directory that every listing of the live cluster is recorded into.
+    pub record_dir: Option<String>,
+    /// How many bytes the trace of `record_dir` may take.
+    pub record_max_bytes: u64,
+    /// Directory recorded with `record_dir` to replay instead of reading a cluster.
+    pub replay_dir: Option<String>,
+    pub memgraph_url: Option<String>,
+    /// Delay between two listings of the live cluster.
+    pub poll_interval: Duration,
+    /// Which ConfigMap values and environment variables are masked before the backend gets them.
+    pub redaction: RedactionPolicy,
+}
+
+impl ClusterSpec {
+    /// Parses an `--extra-cluster` value: `NAME=CONTEXT`, or a bare kube context that doubles
+    /// as the cluster name. Extra clusters always use an in-memory backend.
+    pub fn parse_extra(value: &str, kube_namespace: Option<String>) -> CliResult<Self> {
+        let value = value.trim();
+        let (name, context) = match value.split_once('=') {
+            Some((name, context)) => (name.trim(), context.trim()),
+            None => (value, value),
+        };
+        if name.is_empty() || context.is_empty() {
+            return Err(format!("invalid extra cluster '{value}', expected NAME=CONTEXT").into());
+        }
+        Ok(Self {
+            name: name.to_string(),
+            kube_context: Some(context.to_string()),
+            kube_namespace,
+            snapshot_dir: None,
+            record_dir: None,
+            record_max_bytes: DEFAULT_MAX_TRACE_BYTES,
+            replay_dir: None,
+            memgraph_url: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            redaction: RedactionPolicy::default(),
+        })
+    }
+}
+
+/// A connected cluster: its graph backend and the live state that feeds it.
+#[derive(Clone)]
+pub struct ClusterSession {
+    /// Name of the cluster as configured, which keys its operator instructions.
+    pub name: String,
+    pub label: String,
+    pub backend: Arc<dyn GraphBackend>,
+    pub backend_label: String,
+    pub cluster_state: SharedClusterState,
+    /// Node names of the cluster, kept in line with `cluster_state` by the resolver.
+    pub entity_index: SharedEntityIndex,
+    /// Counter bumped after every diff the resolver applies to `backend`.
+    pub diffs: watch::Receiver<u64>,
+    /// Properties that changed in the latest update of each node, by uid.
+    pub changed_fields: watch::Receiver<ChangedFields>,
+    /// Direct API access for on-demand reads such as pod logs; `None` for snapshots and replays.
+    pub kube_client: Option<kube::Client>,
+    /// Health of the live sync; `None` when the cluster was loaded once without watching.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub health: Option<watch::Receiver<ResolverHealth>>,
+}
+
+/// Top-level properties, such as `spec` or `status`, that changed on a node, by uid.
+pub type ChangedFields = HashMap<String, BTreeSet<String>>;
+
+/// Forwards to the wrapped backend and bumps a counter after every applied diff, so the UI can
+/// react to cluster changes without polling.
+#[derive(Debug)]
+struct DiffNotifyingBackend {
+    inner: Arc<dyn GraphBackend>,
+    diffs: watch::Sender<u64>,
+    changed_fields: watch::Sender<ChangedFields>,
+}
+
+#[async_trait]
+impl GraphBackend for DiffNotifyingBackend {
+    async fn create(&self, cluster_state: SharedClusterState) -> Result<()> {
+        self.inner.create(cluster_state).await
+    }
+
+    async fn update(&self, diff: ClusterStateDiff) -> Result<()> {
+        let removed: Vec<String> = diff
+            .removed_nodes
+            .iter()
+            .map(|node| node.id.uid.clone())
+            .collect();
+        let modified = diff.modified_fields.clone();
+        self.inner.update(diff).await?;
+        self.changed_fields.send_modify(|changed| {
+            for uid in &removed {
+                changed.remove(uid);
+            }
+            changed.extend(modified);
+        });
+        self.diffs.send_modify(|count| *count += 1);
+        Ok(())
+    }
+
+    async fn execute_query(
+        &self,
+        query: String,
+        params: Option<HashMap<String, Value>>,
+    ) -> Result<Vec<Value>> {
+        self.inner.execute_query(query, params).await
+    }
+
+    async fn profile_query(
+        &self,
+        query: String,
+        params: Option<HashMap<String, Value>>,
+    ) -> Result<Vec<Value>> {
+        self.inner.profile_query(query, params).await
+    }
+
+    async fn shutdown(&self) {
+        self.inner.shutdown().await
+    }
+}
+
+#[cfg(feature = "memgraph")]
+fn memgraph_backend(memgraph_url: &str) -> CliResult<(Arc<dyn GraphBackend>, String)> {
+    Ok((
+        Arc::new(MemgraphAsync::try_new_from_url(memgraph_url)?),
+        format!("memgraph ({memgraph_url})"),
+    ))
+}
+
+#[cfg(not(feature = "memgraph"))]
+fn memgraph_backend(memgraph_url: &str) -> CliResult<(Arc<dyn GraphBackend>, String)> {
+    Err(format!(
+        "cannot connect to {memgraph_url}: this build has no Memgraph support; \
+         rebuild with the `memgraph` feature or drop --memgraph-url"
+    )
+    .into())
+}
+
+/// Resolves the cluster state, loads it into a fresh backend and, when a token is given,
+/// keeps the backend in sync until the token is cancelled.
+pub async fn connect_cluster(
+    spec: &ClusterSpec,
+    watch: Option<&CancellationToken>,
+) -> CliResult<ClusterSession> {
+    let (backend, backend_label): (Arc<dyn GraphBackend>, String) =
+        if let Some(memgraph_url) = &spec.memgraph_url {
+            if !memgraph_url.starts_with("bolt://") {
+                return Err(
+                    format!("memgraph url must use bolt:// scheme (got {memgraph_url})").into(),
+                );
+            }
+            memgraph_backend(memgraph_url)?
+        } else {
+            (Arc::new(InMemoryBackend::new()), "in-memory".to_string())
+        };
+    let (diff_tx, diffs) = watch::channel(0);
+    let (changed_fields_tx, changed_fields) = watch::channel(ChangedFields::new());
+    let backend: Arc<dyn GraphBackend> = Arc::new(DiffNotifyingBackend {
+        inner: backend,
+        diffs: diff_tx,
+        changed_fields: changed_fields_tx,
+    });
+
+    let mut kube_client = None;
+    let resolver_client: Box<dyn KubeClient> = if let Some(snapshot_dir) = &spec.snapshot_dir {
+        Box::new(SnapshotKubeClient::from_dir(snapshot_dir.clone())?)
+    } else if let Some(replay_dir) = &spec.replay_dir {
+        Box::new(ReplayKubeClient::from_dir(replay_dir)?)
+    } else {
+        let kube_opts = KubeConfigOptions {
+            context: spec.kube_context.clone(),
+            cluster: None,
+            user: None,
+        };
+        let config = kube::Config::from_kubeconfig(&kube_opts).await?;
+        kube_client = Some(kube::Client::try_from(config)?);
+        let cached = CachedKubeClient::new(&kube_opts, spec.kube_namespace.as_deref()).await?;
+        if let Some(record_dir) = &spec.record_dir {
+            let options = RecordingOptions {
+                redaction: spec.redaction.clone(),
+                max_trace_bytes: spec.record_max_bytes,
+            };
+            let recording =
+                RecordingKubeClient::new(&spec.name, Box::new(cached), record_dir, options).await?;
+            tracing::info!("Recording cluster {} into {record_dir}", spec.name);
+            Box::new(recording)
+        } else {
+            Box::new(cached)
+        }
+    };
+    let options = ResolverOptions {
+        redaction: spec.redaction.clone(),
+        ..Default::default()
+    };
+    let mut resolver =
+        ClusterStateResolver::new_with_options(spec.name.clone(), resolver_client, options).await?;
+
+    resolver.set_poll_interval(spec.poll_interval);
+    let cluster_state = resolver.resolve().await?;
+    if let Err(err) = backend.create(cluster_state.clone()).await {
+        tracing::error!(
+            "Graph backend initialization failed for {}: {err}",
+            spec.name
+        );
+        return Err(err.into());
+    }
+
+    let health = watch.map(|token| {
+        resolver.start_diff_loop(backend.clone(), token.clone());
+        resolver.health()
+    });
+
+    let label = {
+        let state = cluster_state.load();
+        let version = format_k8s_version(&state.cluster.info);
+        format!("{} (K8s {})", state.cluster.name, version)
+    };
+
+    Ok(ClusterSession {
+        name: spec.name.clone(),
+        label,
+        backend,
+        backend_label,
+        cluster_state,
+        entity_index: resolver.entity_index(),
+        diffs,
+        changed_fields,
+        kube_client,
+        health,
+    })
+}
+
+fn format_k8s_version(info: &k8s_openapi::apimachinery::pkg::version::Info) -> String {
+    let version = info.git_version.trim();
+    if version.is_empty() {
+        "unknown".to_string()
+    } else {
+        version.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_extra_cluster_specs() {
+        let spec = ClusterSpec::parse_extra("staging=kind-staging", None).unwrap();
+        assert_eq!(spec.name, "staging");
+        assert_eq!(spec.kube_context.as_deref(), Some("kind-staging"));
+
+        let spec = ClusterSpec::parse_extra("kind-prod", Some("default".to_string())).unwrap();
+        assert_eq!(spec.name, "kind-prod");
+        assert_eq!(spec.kube_context.as_deref(), Some("kind-prod"));
+        assert_eq!(spec.kube_namespace.as_deref(), Some("default"));
+
+        assert!(ClusterSpec::parse_extra("=ctx", None).is_err());
+    }
+}
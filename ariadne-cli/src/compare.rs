@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+/// Row-level difference between two results. Rows are matched by the objects they describe
+/// (see [`record_identity`]); rows without an identity only count as added or removed.
+#[derive(Debug, Default, PartialEq)]
+pub struct RecordComparison {
+    pub added: Vec<Value>,
+    pub removed: Vec<Value>,
+    pub changed: Vec<ChangedRecord>,
+    pub unchanged: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ChangedRecord {
+    pub identity: String,
+    pub before: Value,
+    pub after: Value,
+    /// Top-level columns whose values differ.
+    pub columns: Vec<String>,
+}
+
+impl RecordComparison {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+pub fn compare_records(before: &[Value], after: &[Value]) -> RecordComparison {
+    let mut comparison = RecordComparison::default();
+    let mut after_groups = group_by_identity(after);
+    // Rows that could not be paired by identity, as indices into `before` and `after`.
+    let mut before_rest: Vec<usize> = Vec::new();
+    let mut after_rest: Vec<usize> = Vec::new();
+
+    for (identity, old) in group_by_identity(before) {
+        let new = after_groups.remove(&identity);
+        match (identity, new) {
+            (Some(identity), Some(new)) if old.len() == 1 && new.len() == 1 => {
+                let (old, new) = (&before[old[0]], &after[new[0]]);
+                if old == new {
+                    comparison.unchanged += 1;
+                } else {
+                    comparison.changed.push(ChangedRecord {
+                        identity,
+                        before: old.clone(),
+                        after: new.clone(),
+                        columns: changed_columns(old, new),
+                    });
+                }
+            }
+            (_, new) => {
+                before_rest.extend(old);
+                after_rest.extend(new.into_iter().flatten());
+            }
+        }
+    }
+    after_rest.extend(after_groups.into_values().flatten());
+    before_rest.sort_unstable();
+    after_rest.sort_unstable();
+
+    // Whatever could not be paired is compared by content.
+    for index in before_rest {
+        let record = &before[index];
+        match after_rest.iter().position(|other| &after[*other] == record) {
+            Some(position) => {
+                after_rest.remove(position);
+                comparison.unchanged += 1;
+            }
+            None => comparison.removed.push(record.clone()),
+        }
+    }
+    comparison
+        .added
+        .extend(after_rest.into_iter().map(|index| after[index].clone()));
+    comparison
+}
+
+/// Which object a row describes: the `metadata.uid` of a whole-object row, or else the
+/// identifying columns (names, namespaces, kinds, uids and ids, plus the uids of nested
+/// objects). `None` when the row has none of them.
+pub fn record_identity(record: &Value) -> Option<String> {
+    if let Some(uid) = record.pointer("/metadata/uid").and_then(Value::as_str) {
+        return Some(uid.to_string());
+    }
+    let object = record.as_object()?;
+    let parts: Vec<String> = object
+        .iter()
+        .filter_map(|(column, value)| {
+            let part = match value {
+                Value::Object(_) => value.pointer("/metadata/uid")?.as_str()?.to_string(),
+                Value::String(text) if is_identity_column(column) => text.clone(),
+                Value::Number(number) if is_identity_column(column) => number.to_string(),
+                _ => return None,
+            };
+            Some(format!("{column}={part}"))
+        })
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+fn is_identity_column(column: &str) -> bool {
+    let column = column.to_lowercase();
+    let column = column.rsplit(['.', '_']).next().unwrap_or_default();
+    ["name", "namespace", "kind", "uid", "id"].contains(&column)
+}
+
+/// Row indices grouped by [`record_identity`].
+fn group_by_identity(records: &[Value]) -> BTreeMap<Option<String>, Vec<usize>> {
+    let mut groups: BTreeMap<Option<String>, Vec<usize>> = BTreeMap::new();
+    for (index, record) in records.iter().enumerate() {
+        groups
+            .entry(record_identity(record))
+            .or_default()
+            .push(index);
+    }
+    groups
+}
+
+fn changed_columns(before: &Value, after: &Value) -> Vec<String> {
+    match (before.as_object(), after.as_object()) {
+        (Some(before), Some(after)) => {
+            let mut columns: Vec<String> = before
+                .iter()
+                .filter(|(column, value)| after.get(*column) != Some(*value))
+                .map(|(column, _)| column.clone())
+                .collect();
+            columns.extend(
+                after
+                    .keys()
+                    .filter(|column| !before.contains_key(*column))
+                    .cloned(),
+            );
+            columns
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn rows_are_paired_by_identity() {
+        let before = vec![
+            json!({"name": "web", "namespace": "shop", "restarts": 0}),
+            json!({"name": "db", "namespace": "shop", "restarts": 1}),
+            json!({"name": "old", "namespace": "shop", "restarts": 0}),
+            json!({"count": 3}),
+        ];
+        let after = vec![
+            json!({"name": "web", "namespace": "shop", "restarts": 4}),
+            json!({"name": "db", "namespace": "shop", "restarts": 1}),
+            json!({"name": "new", "namespace": "shop", "restarts": 0}),
+            json!({"count": 5}),
+        ];
+        let comparison = compare_records(&before, &after);
+        assert_eq!(comparison.unchanged, 1);
+        assert_eq!(comparison.changed.len(), 1);
+        assert_eq!(comparison.changed[0].identity, "name=web, namespace=shop");
+        assert_eq!(comparison.changed[0].columns, vec!["restarts"]);
+        assert_eq!(
+            comparison.removed,
+            vec![
+                json!({"name": "old", "namespace": "shop", "restarts": 0}),
+                json!({"count": 3})
+            ]
+        );
+        assert_eq!(
+            comparison.added,
+            vec![
+                json!({"name": "new", "namespace": "shop", "restarts": 0}),
+                json!({"count": 5})
+            ]
+        );
+    }
+
+    #[test]
+    fn whole_objects_are_identified_by_uid() {
+        let pod = json!({"metadata": {"uid": "a", "name": "web"}, "status": {"phase": "Running"}});
+        assert_eq!(record_identity(&pod).as_deref(), Some("a"));
+        let row = json!({"pod": {"metadata": {"uid": "a"}}, "restarts": 2});
+        assert_eq!(record_identity(&row).as_deref(), Some("pod=a"));
+        assert_eq!(record_identity(&json!({"restarts": 2})), None);
+    }
+}
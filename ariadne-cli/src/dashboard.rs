@@ -0,0 +1,112 @@
+use serde_json::Value;
+
+/// A cluster overview tile. Its value is the number of rows `cypher` returns; clicking the tile
+/// runs the same query in the feed.
+#[derive(Debug, Clone, Copy)]
+pub struct DashboardTile {
+    pub title: &'static str,
+    pub detail: &'static str,
+    pub cypher: &'static str,
+    /// Whether a non-zero count needs attention (failing pods) rather than being informational
+    /// (namespaces).
+    pub alerting: bool,
+}
+
+pub const DASHBOARD_TILES: &[DashboardTile] = &[
+    DashboardTile {
+        title: "Namespaces",
+        detail: "All namespaces and their phase",
+        cypher: "MATCH (n:Namespace) \
+                 RETURN n.metadata.name AS name, n.status.phase AS phase \
+                 ORDER BY name",
+        alerting: false,
+    },
+    DashboardTile {
+        title: "Nodes not ready",
+        detail: "Nodes whose Ready condition is not True",
+        cypher: "MATCH (n:Node) \
+                 WHERE ANY(c IN n.status.conditions WHERE c.type = 'Ready' AND c.status <> 'True') \
+                 RETURN n.metadata.name AS name",
+        alerting: true,
+    },
+    DashboardTile {
+        title: "Failing pods",
+        detail: "Failed or Unknown pods and containers stuck in a back-off",
+        cypher: "MATCH (p:Pod) \
+                 WHERE p.status.phase IN ['Failed', 'Unknown'] \
+                 OR ANY(s IN p.status.containerStatuses \
+                 WHERE s.state.waiting.reason IN ['CrashLoopBackOff', 'ImagePullBackOff', 'ErrImagePull']) \
+                 RETURN p.metadata.namespace AS namespace, p.metadata.name AS name, \
+                 p.status.phase AS phase",
+        alerting: true,
+    },
+    DashboardTile {
+        title: "Pending PVCs",
+        detail: "Claims that are not bound yet",
+        cypher: "MATCH (c:PersistentVolumeClaim) WHERE c.status.phase = 'Pending' \
+                 RETURN c.metadata.namespace AS namespace, c.metadata.name AS name",
+        alerting: true,
+    },
+    DashboardTile {
+        title: "Warning events",
+        detail: "Most recent Warning events",
+        cypher: "MATCH (e:Event) WHERE e.type = 'Warning' \
+                 RETURN e.metadata.namespace AS namespace, e.reason AS reason, e.note AS note, \
+                 e.metadata.creationTimestamp AS time \
+                 ORDER BY time DESC LIMIT 50",
+        alerting: true,
+    },
+];
+
+/// The single value of a result with one row and one column (e.g. `RETURN count(p) AS pods`),
+/// shown as a metric instead of a table on the Dashboard tab.
+pub fn pinned_metric(records: &[Value]) -> Option<(String, String)> {
+    let [record] = records else {
+        return None;
+    };
+    let object = record.as_object()?;
+    if object.len() != 1 {
+        return None;
+    }
+    let (column, value) = object.iter().next()?;
+    let text = match value {
+        Value::Number(number) => number.to_string(),
+        Value::String(text) => text.clone(),
+        Value::Bool(flag) => flag.to_string(),
+        _ => return None,
+    };
+    Some((column.clone(), text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::validate_cypher;
+    use serde_json::json;
+
+    #[test]
+    fn dashboard_queries_pass_validation() {
+        for tile in DASHBOARD_TILES {
+            if let Err(issue) = validate_cypher(tile.cypher) {
+                panic!("tile '{}' has an invalid query: {issue}", tile.title);
+            }
+        }
+    }
+
+    #[test]
+    fn single_values_are_shown_as_metrics() {
+        assert_eq!(
+            pinned_metric(&[json!({"pods": 12})]),
+            Some(("pods".to_string(), "12".to_string()))
+        );
+        assert_eq!(
+            pinned_metric(&[json!({"pods": 12, "namespace": "shop"})]),
+            None
+        );
+        assert_eq!(
+            pinned_metric(&[json!({"pods": 1}), json!({"pods": 2})]),
+            None
+        );
+        assert_eq!(pinned_metric(&[json!({"pod": {"name": "web"}})]), None);
+    }
+}
@@ -0,0 +1,453 @@
+use serde_json::Value;
+
+use crate::pipeline::format_value;
+
+/// Kinds with a curated, `kubectl describe`-like inspector layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DescribeKind {
+    Pod,
+    Deployment,
+    Service,
+}
+
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+impl DescribeKind {
+    /// Uses the object's `kind` when present, otherwise recognises the kind by its shape since
+    /// objects listed from the API server usually omit `kind`. Flattened rows without
+    /// `metadata` are not described.
+    pub fn detect(object: &Value) -> Option<Self> {
+        object
+            .get("metadata")
+            .filter(|metadata| metadata.is_object())?;
+        match object.get("kind").and_then(Value::as_str) {
+            Some("Pod") => return Some(DescribeKind::Pod),
+            Some("Deployment") => return Some(DescribeKind::Deployment),
+            Some("Service") => return Some(DescribeKind::Service),
+            Some(_) => return None,
+            None => {}
+        }
+        let spec = object.get("spec")?;
+        if spec.get("containers").is_some() {
+            Some(DescribeKind::Pod)
+        } else if spec.get("template").is_some() && spec.get("strategy").is_some() {
+            Some(DescribeKind::Deployment)
+        } else if spec.get("ports").is_some() && spec.get("clusterIP").is_some() {
+            Some(DescribeKind::Service)
+        } else {
+            None
+        }
+    }
+
+    /// Graph node label for this kind.
+    pub fn label(&self) -> &'static str {
+        match self {
+            DescribeKind::Pod => "Pod",
+            DescribeKind::Deployment => "Deployment",
+            DescribeKind::Service => "Service",
+        }
+    }
+
+    /// Cypher returning the events attached to one object of this kind, newest first.
+    pub fn events_query(&self) -> String {
+        format!(
+            "MATCH (e:Event)-[:Concerns]->(r:{}) \
+             WHERE r.metadata.name = $name AND r.metadata.namespace = $namespace \
+             RETURN e.type AS type, e.reason AS reason, e.note AS note, \
+             e.metadata.creationTimestamp AS time \
+             ORDER BY time DESC LIMIT 20",
+            self.label()
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DescribeBlock {
+    Fields(Vec<(String, String)>),
+    Table {
+        columns: Vec<&'static str>,
+        rows: Vec<Vec<String>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DescribeSection {
+    pub title: &'static str,
+    pub block: DescribeBlock,
+}
+
+/// Builds the describe layout for a Pod, Deployment or Service object.
+pub fn describe_object(kind: DescribeKind, object: &Value) -> Vec<DescribeSection> {
+    match kind {
+        DescribeKind::Pod => describe_pod(object),
+        DescribeKind::Deployment => describe_deployment(object),
+        DescribeKind::Service => describe_service(object),
+    }
+}
+
+/// Renders rows returned by [`DescribeKind::events_query`] as an events section.
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn events_section(records: &[Value]) -> DescribeSection {
+    let rows = records
+        .iter()
+        .map(|record| {
+            vec![
+                text(record, "/type"),
+                text(record, "/reason"),
+                text(record, "/time"),
+                text(record, "/note"),
+            ]
+        })
+        .collect();
+    DescribeSection {
+        title: "Events",
+        block: DescribeBlock::Table {
+            columns: vec!["Type", "Reason", "Time", "Message"],
+            rows,
+        },
+    }
+}
+
+fn describe_pod(pod: &Value) -> Vec<DescribeSection> {
+    let mut fields = metadata_fields(pod);
+    fields.extend([
+        ("Node".to_string(), text(pod, "/spec/nodeName")),
+        ("Status".to_string(), text(pod, "/status/phase")),
+        ("IP".to_string(), text(pod, "/status/podIP")),
+        ("Start Time".to_string(), text(pod, "/status/startTime")),
+        ("QoS Class".to_string(), text(pod, "/status/qosClass")),
+        ("Controlled By".to_string(), controlled_by(pod)),
+    ]);
+
+    let statuses = array(pod, "/status/containerStatuses");
+    let containers = array(pod, "/spec/containers")
+        .iter()
+        .map(|container| {
+            let name = text(container, "/name");
+            let status = statuses
+                .iter()
+                .find(|status| status.get("name").and_then(Value::as_str) == Some(name.as_str()));
+            vec![
+                name.clone(),
+                text(container, "/image"),
+                status
+                    .map(container_state)
+                    .unwrap_or_else(|| "-".to_string()),
+                status
+                    .map(|status| text(status, "/ready"))
+                    .unwrap_or_else(|| "-".to_string()),
+                status
+                    .map(|status| text(status, "/restartCount"))
+                    .unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+
+    vec![
+        DescribeSection {
+            title: "Overview",
+            block: DescribeBlock::Fields(fields),
+        },
+        DescribeSection {
+            title: "Containers",
+            block: DescribeBlock::Table {
+                columns: vec!["Name", "Image", "State", "Ready", "Restarts"],
+                rows: containers,
+            },
+        },
+        conditions_section(pod),
+    ]
+}
+
+fn describe_deployment(deployment: &Value) -> Vec<DescribeSection> {
+    let mut fields = metadata_fields(deployment);
+    fields.extend([
+        (
+            "Selector".to_string(),
+            key_values(deployment.pointer("/spec/selector/matchLabels")),
+        ),
+        (
+            "Replicas".to_string(),
+            format!(
+                "{} desired | {} updated | {} total | {} available | {} unavailable",
+                number(deployment, "/spec/replicas"),
+                number(deployment, "/status/updatedReplicas"),
+                number(deployment, "/status/replicas"),
+                number(deployment, "/status/availableReplicas"),
+                number(deployment, "/status/unavailableReplicas"),
+            ),
+        ),
+        (
+            "Strategy".to_string(),
+            text(deployment, "/spec/strategy/type"),
+        ),
+    ]);
+
+    let containers = array(deployment, "/spec/template/spec/containers")
+        .iter()
+        .map(|container| {
+            let ports: Vec<String> = array(container, "/ports")
+                .iter()
+                .map(|port| {
+                    format!(
+                        "{}/{}",
+                        text(port, "/containerPort"),
+                        port.get("protocol")
+                            .and_then(Value::as_str)
+                            .unwrap_or("TCP")
+                    )
+                })
+                .collect();
+            vec![
+                text(container, "/name"),
+                text(container, "/image"),
+                join_or_dash(ports),
+            ]
+        })
+        .collect();
+
+    vec![
+        DescribeSection {
+            title: "Overview",
+            block: DescribeBlock::Fields(fields),
+        },
+        DescribeSection {
+            title: "Pod Template",
+            block: DescribeBlock::Table {
+                columns: vec!["Container", "Image", "Ports"],
+                rows: containers,
+            },
+        },
+        conditions_section(deployment),
+    ]
+}
+
+fn describe_service(service: &Value) -> Vec<DescribeSection> {
+    let ingress: Vec<String> = array(service, "/status/loadBalancer/ingress")
+        .iter()
+        .filter_map(|ingress| {
+            ingress
+                .get("ip")
+                .or_else(|| ingress.get("hostname"))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .collect();
+    let mut fields = metadata_fields(service);
+    fields.extend([
+        ("Type".to_string(), text(service, "/spec/type")),
+        (
+            "Selector".to_string(),
+            key_values(service.pointer("/spec/selector")),
+        ),
+        ("IP".to_string(), text(service, "/spec/clusterIP")),
+        ("LoadBalancer Ingress".to_string(), join_or_dash(ingress)),
+        (
+            "Session Affinity".to_string(),
+            text(service, "/spec/sessionAffinity"),
+        ),
+    ]);
+
+    let ports = array(service, "/spec/ports")
+        .iter()
+        .map(|port| {
+            vec![
+                text(port, "/name"),
+                text(port, "/port"),
+                text(port, "/targetPort"),
+                text(port, "/protocol"),
+                text(port, "/nodePort"),
+            ]
+        })
+        .collect();
+
+    vec![
+        DescribeSection {
+            title: "Overview",
+            block: DescribeBlock::Fields(fields),
+        },
+        DescribeSection {
+            title: "Ports",
+            block: DescribeBlock::Table {
+                columns: vec!["Name", "Port", "Target", "Protocol", "NodePort"],
+                rows: ports,
+            },
+        },
+    ]
+}
+
+fn metadata_fields(object: &Value) -> Vec<(String, String)> {
+    vec![
+        ("Name".to_string(), text(object, "/metadata/name")),
+        ("Namespace".to_string(), text(object, "/metadata/namespace")),
+        (
+            "Labels".to_string(),
+            key_values(object.pointer("/metadata/labels")),
+        ),
+        (
+            "Created".to_string(),
+            text(object, "/metadata/creationTimestamp"),
+        ),
+    ]
+}
+
+fn conditions_section(object: &Value) -> DescribeSection {
+    let rows = array(object, "/status/conditions")
+        .iter()
+        .map(|condition| {
+            vec![
+                text(condition, "/type"),
+                text(condition, "/status"),
+                text(condition, "/reason"),
+            ]
+        })
+        .collect();
+    DescribeSection {
+        title: "Conditions",
+        block: DescribeBlock::Table {
+            columns: vec!["Type", "Status", "Reason"],
+            rows,
+        },
+    }
+}
+
+fn container_state(status: &Value) -> String {
+    let Some(state) = status.get("state").and_then(Value::as_object) else {
+        return "-".to_string();
+    };
+    match state.iter().next() {
+        Some((name, detail)) => match detail.get("reason").and_then(Value::as_str) {
+            Some(reason) => format!("{} ({reason})", capitalize(name)),
+            None => capitalize(name),
+        },
+        None => "-".to_string(),
+    }
+}
+
+fn controlled_by(object: &Value) -> String {
+    let owners: Vec<String> = array(object, "/metadata/ownerReferences")
+        .iter()
+        .map(|owner| format!("{}/{}", text(owner, "/kind"), text(owner, "/name")))
+        .collect();
+    join_or_dash(owners)
+}
+
+fn text(value: &Value, pointer: &str) -> String {
+    match value.pointer(pointer) {
+        None | Some(Value::Null) => "-".to_string(),
+        Some(value) => format_value(value),
+    }
+}
+
+fn number(value: &Value, pointer: &str) -> i64 {
+    value.pointer(pointer).and_then(Value::as_i64).unwrap_or(0)
+}
+
+fn array<'a>(value: &'a Value, pointer: &str) -> &'a [Value] {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+fn key_values(value: Option<&Value>) -> String {
+    let pairs: Vec<String> = value
+        .and_then(Value::as_object)
+        .map(|map| {
+            map.iter()
+                .map(|(key, value)| format!("{key}={}", format_value(value)))
+                .collect()
+        })
+        .unwrap_or_default();
+    join_or_dash(pairs)
+}
+
+fn join_or_dash(values: Vec<String>) -> String {
+    if values.is_empty() {
+        "-".to_string()
+    } else {
+        values.join(", ")
+    }
+}
+
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn describe_pod_merges_container_specs_and_statuses() {
+        let pod = json!({
+            "metadata": {"name": "web-1", "namespace": "shop", "labels": {"app": "web"}},
+            "spec": {
+                "nodeName": "worker-0",
+                "containers": [{"name": "app", "image": "web:1.2"}],
+            },
+            "status": {
+                "phase": "Running",
+                "containerStatuses": [{
+                    "name": "app",
+                    "ready": false,
+                    "restartCount": 4,
+                    "state": {"waiting": {"reason": "CrashLoopBackOff"}},
+                }],
+                "conditions": [{"type": "Ready", "status": "False"}],
+            },
+        });
+        assert_eq!(DescribeKind::detect(&pod), Some(DescribeKind::Pod));
+
+        let sections = describe_object(DescribeKind::Pod, &pod);
+        let DescribeBlock::Fields(fields) = &sections[0].block else {
+            panic!("overview should be fields");
+        };
+        assert!(fields.contains(&("Labels".to_string(), "app=web".to_string())));
+        assert!(fields.contains(&("Node".to_string(), "worker-0".to_string())));
+
+        let DescribeBlock::Table { rows, .. } = &sections[1].block else {
+            panic!("containers should be a table");
+        };
+        assert_eq!(
+            rows[0],
+            vec!["app", "web:1.2", "Waiting (CrashLoopBackOff)", "false", "4"]
+        );
+        let DescribeBlock::Table { rows, .. } = &sections[2].block else {
+            panic!("conditions should be a table");
+        };
+        assert_eq!(rows[0], vec!["Ready", "False", "-"]);
+    }
+
+    #[test]
+    fn detect_kind_by_field_or_shape() {
+        assert_eq!(
+            DescribeKind::detect(&json!({"kind": "Service", "metadata": {}})),
+            Some(DescribeKind::Service)
+        );
+        assert_eq!(
+            DescribeKind::detect(&json!({"kind": "ConfigMap", "metadata": {}})),
+            None
+        );
+        assert_eq!(
+            DescribeKind::detect(&json!({"kind": "Pod", "metadata_name": "web-1"})),
+            None
+        );
+        assert_eq!(
+            DescribeKind::detect(&json!({
+                "metadata": {},
+                "spec": {"ports": [], "clusterIP": "10.0.0.1"},
+            })),
+            Some(DescribeKind::Service)
+        );
+        assert_eq!(
+            DescribeKind::detect(&json!({"metadata": {}, "data": {}})),
+            None
+        );
+    }
+}
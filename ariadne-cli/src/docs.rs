@@ -0,0 +1,163 @@
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::CliResult;
+use crate::text_index::TextIndex;
+
+/// Passages on upstream Kubernetes concepts shipped with the CLI.
+const BUNDLED_DOCS: &str = include_str!("../config/k8s_docs.toml");
+/// Passages given to the analyst for one answer.
+const MAX_PASSAGES: usize = 3;
+/// Cosine similarity below which a passage is not worth the tokens.
+const MIN_SCORE: f64 = 0.15;
+/// Rows whose values are matched against the passages, besides the question.
+const MAX_MATCHED_ROWS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DocPassage {
+    pub title: String,
+    pub url: String,
+    pub text: String,
+}
+
+/// A passage the answer cites as `[number]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocCitation {
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub number: usize,
+    pub title: String,
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DocsFile {
+    #[serde(default)]
+    passages: Vec<DocPassage>,
+}
+
+/// Documentation passages embedded as TF-IDF vectors over their words, so the analyst gets the
+/// ones closest to a question and its rows.
+#[derive(Debug)]
+pub struct DocsIndex {
+    passages: Vec<DocPassage>,
+    index: TextIndex,
+}
+
+impl DocsIndex {
+    pub fn new(passages: Vec<DocPassage>) -> Self {
+        let index = TextIndex::new(
+            passages
+                .iter()
+                .map(|passage| (passage.title.as_str(), passage.text.as_str())),
+        );
+        Self { passages, index }
+    }
+
+    /// The bundled passages, followed by those of `extra`.
+    pub fn load(extra: Option<&Path>) -> CliResult<Self> {
+        let mut passages = parse_passages(BUNDLED_DOCS)?;
+        if let Some(path) = extra {
+            passages.extend(parse_passages(&std::fs::read_to_string(path)?)?);
+        }
+        Ok(Self::new(passages))
+    }
+
+    /// Up to [`MAX_PASSAGES`] passages for `text`, most similar first.
+    pub fn search(&self, text: &str) -> Vec<&DocPassage> {
+        self.index
+            .search(text, MIN_SCORE)
+            .into_iter()
+            .take(MAX_PASSAGES)
+            .map(|index| &self.passages[index])
+            .collect()
+    }
+
+    /// Passages for an answer about `records`, matched on the question, the result summary and
+    /// the string values of the first rows, where states such as `CrashLoopBackOff` show up.
+    pub fn search_results(
+        &self,
+        question: &str,
+        summary: &str,
+        records: &[Value],
+    ) -> Vec<&DocPassage> {
+        let mut text = format!("{question}\n{summary}");
+        for record in records.iter().take(MAX_MATCHED_ROWS) {
+            push_strings(record, &mut text);
+        }
+        self.search(&text)
+    }
+}
+
+fn parse_passages(contents: &str) -> CliResult<Vec<DocPassage>> {
+    let file: DocsFile = toml::from_str(contents)?;
+    Ok(file
+        .passages
+        .into_iter()
+        .map(|passage| DocPassage {
+            text: passage
+                .text
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" "),
+            ..passage
+        })
+        .collect())
+}
+
+fn push_strings(value: &Value, text: &mut String) {
+    match value {
+        Value::String(value) => {
+            text.push(' ');
+            text.push_str(value);
+        }
+        Value::Array(items) => items.iter().for_each(|item| push_strings(item, text)),
+        Value::Object(map) => map.values().for_each(|item| push_strings(item, text)),
+        _ => {}
+    }
+}
+
+/// The passages of `passages` whose number, counted from 1, appears as `[number]` in `text`.
+pub fn cited_passages(passages: &[&DocPassage], text: &str) -> Vec<DocCitation> {
+    passages
+        .iter()
+        .enumerate()
+        .map(|(index, passage)| (index + 1, passage))
+        .filter(|(number, _)| text.contains(&format!("[{number}]")))
+        .map(|(number, passage)| DocCitation {
+            number,
+            title: passage.title.clone(),
+            url: passage.url.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn rows_find_the_passage_for_their_state() {
+        let index = DocsIndex::load(None).unwrap();
+        let records = vec![json!({
+            "pod": "checkout-7d9f",
+            "reason": "CrashLoopBackOff",
+            "restarts": 14,
+        })];
+        let passages = index.search_results("why is checkout crashlooping?", "", &records);
+        assert_eq!(passages[0].title, "CrashLoopBackOff");
+        assert!(index.search("checkout payments").is_empty());
+
+        let citations = cited_passages(&passages, "It crashes on startup [1].");
+        assert_eq!(
+            citations,
+            vec![DocCitation {
+                number: 1,
+                title: "CrashLoopBackOff".to_string(),
+                url: passages[0].url.clone(),
+            }]
+        );
+    }
+}
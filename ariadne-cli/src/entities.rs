@@ -0,0 +1,170 @@
+use ariadne_core::state::ClusterState;
+use ariadne_core::types::ResourceType;
+
+use crate::agent::EntityHint;
+
+/// Kinds whose names show up in questions. Logical nodes, events and replica sets (whose names
+/// carry a generated hash) are left out.
+const NAMED_KINDS: &[ResourceType] = &[
+    ResourceType::Namespace,
+    ResourceType::Node,
+    ResourceType::Deployment,
+    ResourceType::StatefulSet,
+    ResourceType::DaemonSet,
+    ResourceType::Job,
+    ResourceType::Pod,
+    ResourceType::Service,
+    ResourceType::Ingress,
+    ResourceType::ConfigMap,
+    ResourceType::PersistentVolumeClaim,
+    ResourceType::PersistentVolume,
+    ResourceType::StorageClass,
+    ResourceType::ServiceAccount,
+    ResourceType::NetworkPolicy,
+];
+
+/// Words shorter than this are never corrected; too many short words are one edit away from
+/// some resource name.
+const MIN_WORD_LEN: usize = 5;
+const MAX_HINTS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownName {
+    pub name: String,
+    pub kind: ResourceType,
+    pub namespace: Option<String>,
+}
+
+pub fn known_names(state: &ClusterState) -> Vec<KnownName> {
+    NAMED_KINDS
+        .iter()
+        .flat_map(|kind| {
+            state.get_nodes_by_type(kind).map(|object| KnownName {
+                name: object.id.name.clone(),
+                kind: kind.clone(),
+                namespace: object.id.namespace.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Words of `question` that are not a resource name but are within a small edit distance of
+/// one, paired with the closest names. Words that already match a name exactly are left alone.
+pub fn resolve_entities(question: &str, names: &[KnownName]) -> Vec<EntityHint> {
+    let mut hints: Vec<EntityHint> = Vec::new();
+    for word in question_words(question) {
+        if names.iter().any(|known| known.name == word) {
+            continue;
+        }
+        let max_distance = if word.chars().count() >= 10 { 2 } else { 1 };
+        let mut best: Vec<(&KnownName, usize)> = Vec::new();
+        for known in names {
+            let length_gap = known.name.chars().count().abs_diff(word.chars().count());
+            if length_gap > max_distance {
+                continue;
+            }
+            let distance = edit_distance(&word, &known.name);
+            if distance > max_distance {
+                continue;
+            }
+            match best.first() {
+                Some((_, current)) if distance > *current => {}
+                Some((_, current)) if distance < *current => best = vec![(known, distance)],
+                _ => best.push((known, distance)),
+            }
+        }
+        for (known, _) in best {
+            let hint = EntityHint {
+                mentioned: word.clone(),
+                name: known.name.clone(),
+                kind: known.kind.to_string(),
+                namespace: known.namespace.clone(),
+            };
+            if hints.len() < MAX_HINTS && !hints.contains(&hint) {
+                hints.push(hint);
+            }
+        }
+    }
+    hints
+}
+
+/// Lowercased words that could be resource names: DNS-label characters only, at least
+/// [`MIN_WORD_LEN`] long, and not a resource kind such as "pods".
+fn question_words(question: &str) -> Vec<String> {
+    let kinds: Vec<String> = NAMED_KINDS
+        .iter()
+        .map(|kind| kind.to_string().to_lowercase())
+        .collect();
+    let mut words: Vec<String> = Vec::new();
+    for word in question.split(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '.' | '_'))) {
+        let word = word.trim_matches(|c: char| matches!(c, '-' | '.' | '_'));
+        let word = word.to_lowercase();
+        if word.chars().count() < MIN_WORD_LEN {
+            continue;
+        }
+        let singular = word.strip_suffix('s').unwrap_or(&word);
+        if kinds.iter().any(|kind| kind == &word || kind == singular) {
+            continue;
+        }
+        if !words.contains(&word) {
+            words.push(word);
+        }
+    }
+    words
+}
+
+/// Levenshtein distance over characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn known(name: &str, kind: ResourceType, namespace: Option<&str>) -> KnownName {
+        KnownName {
+            name: name.to_string(),
+            kind,
+            namespace: namespace.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn misspelled_names_resolve_to_the_closest_resources() {
+        let names = vec![
+            known("payments-api", ResourceType::Deployment, Some("shop")),
+            known("payments-api", ResourceType::Service, Some("shop")),
+            known("payments-db", ResourceType::StatefulSet, Some("shop")),
+            known("shop", ResourceType::Namespace, None),
+        ];
+        let hints = resolve_entities("Why are the paymets-api pods in shop crashing?", &names);
+        assert_eq!(hints.len(), 2);
+        assert!(hints.iter().all(|hint| hint.mentioned == "paymets-api"));
+        assert!(hints.iter().all(|hint| hint.name == "payments-api"));
+        assert_eq!(hints[0].kind, "Deployment");
+        assert_eq!(hints[0].namespace.as_deref(), Some("shop"));
+
+        assert!(resolve_entities("Show payments-api and its pods", &names).is_empty());
+        assert!(resolve_entities("List deployments", &names).is_empty());
+    }
+
+    #[test]
+    fn edit_distance_counts_single_edits() {
+        assert_eq!(edit_distance("paymets", "payments"), 1);
+        assert_eq!(edit_distance("kube-sytem", "kube-system"), 1);
+        assert_eq!(edit_distance("abc", "abc"), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
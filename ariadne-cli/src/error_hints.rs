@@ -0,0 +1,121 @@
+/// A readable explanation of a query error and whether rewriting the query can help.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorHint {
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub hint: &'static str,
+    /// The query itself is at fault, so asking the translator for a corrected one makes sense.
+    pub fixable: bool,
+}
+
+/// Error fragments (lowercase) of Memgraph, bolt and the query validator, most specific first.
+const HINTS: &[(&[&str], ErrorHint)] = &[
+    (
+        &[
+            "connection refused",
+            "connection reset",
+            "broken pipe",
+            "disconnected",
+            "failed to connect",
+            "not connected",
+            "unreachable",
+        ],
+        ErrorHint {
+            hint: "The graph backend is unreachable. Check that Memgraph is running and that \
+                   the bolt:// URL is right; the query itself may be fine.",
+            fixable: false,
+        },
+    ),
+    (
+        &[
+            "timed out",
+            "timeout",
+            "deadline",
+            "execution took too long",
+        ],
+        ErrorHint {
+            hint: "The query took too long. Narrowing it with a namespace or name filter, or a \
+                   LIMIT, usually helps.",
+            fixable: true,
+        },
+    ),
+    (
+        &["memory limit", "out of memory"],
+        ErrorHint {
+            hint: "The query ran out of memory. Avoid unbounded variable-length paths and \
+                   return fewer or smaller rows.",
+            fixable: true,
+        },
+    ),
+    (
+        &[
+            "unknown property",
+            "no such property",
+            "property not found",
+            "unknown label",
+            "unknown relationship",
+            "not in the graph schema",
+        ],
+        ErrorHint {
+            hint: "The query refers to a property, label or relationship the graph does not \
+                   have. Properties of Kubernetes objects are nested, e.g. \
+                   `p.metadata.name`, `p.status.phase`.",
+            fixable: true,
+        },
+    ),
+    (
+        &["unbound variable", "not defined", "undefined variable"],
+        ErrorHint {
+            hint: "The query uses a variable that no MATCH or WITH introduces.",
+            fixable: true,
+        },
+    ),
+    (
+        &[
+            "syntax error",
+            "mismatched input",
+            "invalid input",
+            "no viable alternative",
+            "unexpected token",
+            "parse error",
+        ],
+        ErrorHint {
+            hint: "The query is not valid Cypher.",
+            fixable: true,
+        },
+    ),
+    (
+        &["read-only", "read only", "write clause", "not allowed"],
+        ErrorHint {
+            hint: "Only read-only queries are allowed; the query tries to change the graph.",
+            fixable: true,
+        },
+    ),
+];
+
+/// Hint for a validation or backend error, when it matches a known kind.
+pub fn error_hint(error: &str) -> Option<ErrorHint> {
+    let error = error.to_lowercase();
+    HINTS
+        .iter()
+        .find(|(fragments, _)| fragments.iter().any(|fragment| error.contains(fragment)))
+        .map(|(_, hint)| *hint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_backend_errors_get_hints() {
+        let unreachable = error_hint("Failed to connect: Connection refused (os error 111)")
+            .expect("connection hint");
+        assert!(!unreachable.fixable);
+
+        let property = error_hint("Query failed: Unknown property 'phase' on Pod").unwrap();
+        assert!(property.fixable);
+        assert!(property.hint.contains("metadata.name"));
+
+        assert!(error_hint("Query execution timed out").unwrap().fixable);
+        assert_eq!(error_hint("something else entirely"), None);
+    }
+}
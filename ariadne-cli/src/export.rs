@@ -0,0 +1,126 @@
+use serde_json::{Map, Value};
+use std::path::Path;
+
+use crate::error::CliResult;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+        }
+    }
+}
+
+pub fn export_records(path: &Path, records: &[Value], format: ExportFormat) -> CliResult<()> {
+    let content = match format {
+        ExportFormat::Csv => records_to_csv(records),
+        ExportFormat::Json => serde_json::to_string_pretty(records)?,
+    };
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// Flattens records into CSV. Columns are the union of object keys in first-seen order;
+/// nested values are written as compact JSON and non-object records land in a `value` column.
+pub fn records_to_csv(records: &[Value]) -> String {
+    let mut columns: Vec<String> = Vec::new();
+    for record in records {
+        match record {
+            Value::Object(obj) => {
+                for key in obj.keys() {
+                    if !columns.iter().any(|column| column == key) {
+                        columns.push(key.clone());
+                    }
+                }
+            }
+            _ => {
+                if !columns.iter().any(|column| column == "value") {
+                    columns.push("value".to_string());
+                }
+            }
+        }
+    }
+
+    let mut out = String::new();
+    let header: Vec<String> = columns.iter().map(|column| escape_csv(column)).collect();
+    out.push_str(&header.join(","));
+    out.push('\n');
+    let empty = Map::new();
+    for record in records {
+        let (obj, scalar) = match record {
+            Value::Object(obj) => (obj, None),
+            other => (&empty, Some(other)),
+        };
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|column| {
+                let value = match (obj.get(column), scalar) {
+                    (Some(value), _) => Some(value),
+                    (None, Some(scalar)) if column == "value" => Some(scalar),
+                    _ => None,
+                };
+                value.map(csv_cell).unwrap_or_default()
+            })
+            .collect();
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn csv_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => escape_csv(s),
+        Value::Bool(_) | Value::Number(_) => value.to_string(),
+        other => escape_csv(&other.to_string()),
+    }
+}
+
+fn escape_csv(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn records_to_csv_unions_columns() {
+        let records = vec![
+            json!({"name": "pod-a", "restarts": 2}),
+            json!({"name": "pod,b", "node": "n1"}),
+        ];
+        let csv = records_to_csv(&records);
+        assert_eq!(csv, "name,restarts,node\npod-a,2,\n\"pod,b\",,n1\n");
+    }
+
+    #[test]
+    fn records_to_csv_handles_scalars_and_nested() {
+        let records = vec![json!("plain"), json!({"labels": {"app": "web"}})];
+        let csv = records_to_csv(&records);
+        assert_eq!(
+            csv,
+            "value,labels\nplain,\n,\"{\"\"app\"\":\"\"web\"\"}\"\n"
+        );
+    }
+}
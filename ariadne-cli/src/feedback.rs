@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::FewShotExample;
+use crate::error::CliResult;
+use crate::settings::data_dir;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackTarget {
+    Translation,
+    Analysis,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rating {
+    Up,
+    Down,
+}
+
+/// One thumbs up/down, stored as a line of `feedback.jsonl`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedbackRecord {
+    pub question: String,
+    pub cypher: String,
+    pub target: FeedbackTarget,
+    pub rating: Rating,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub analysis: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// RFC 3339 timestamp.
+    pub recorded_at: String,
+}
+
+/// Local dataset of rated question/Cypher pairs. Approved translations are offered to the
+/// translator as few-shot examples for similar questions, see [`crate::few_shot`].
+#[derive(Debug, Default)]
+pub struct FeedbackStore {
+    path: Option<PathBuf>,
+    records: Mutex<Vec<FeedbackRecord>>,
+}
+
+impl FeedbackStore {
+    /// Loads `$XDG_DATA_HOME/ariadne-cli/feedback.jsonl`; an unreadable file yields an empty
+    /// store that still appends to it.
+    pub fn load() -> Self {
+        let Some(path) = feedback_path() else {
+            return Self::default();
+        };
+        match Self::load_from(&path) {
+            Ok(store) => store,
+            Err(err) => {
+                tracing::warn!("Failed to load feedback from {}: {err}", path.display());
+                Self {
+                    path: Some(path),
+                    records: Mutex::default(),
+                }
+            }
+        }
+    }
+
+    pub fn load_from(path: &Path) -> CliResult<Self> {
+        let mut records = Vec::new();
+        if path.exists() {
+            for (index, line) in std::fs::read_to_string(path)?.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str(line) {
+                    Ok(record) => records.push(record),
+                    Err(err) => tracing::warn!(
+                        "Skipping feedback line {} in {}: {err}",
+                        index + 1,
+                        path.display()
+                    ),
+                }
+            }
+        }
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            records: Mutex::new(records),
+        })
+    }
+
+    /// Keeps the record for this session and appends it to the feedback file.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub fn record(&self, record: FeedbackRecord) -> CliResult<()> {
+        let line = serde_json::to_string(&record)?;
+        self.records
+            .lock()
+            .expect("feedback lock poisoned")
+            .push(record);
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(file, "{line}")?;
+        Ok(())
+    }
+
+    /// Question/Cypher pairs whose latest translation rating is a thumbs up.
+    pub fn approved_translations(&self) -> Vec<FewShotExample> {
+        let records = self.records.lock().expect("feedback lock poisoned");
+        let mut latest: HashMap<(&str, &str), Rating> = HashMap::new();
+        for record in records
+            .iter()
+            .filter(|record| record.target == FeedbackTarget::Translation)
+        {
+            latest.insert(
+                (record.question.as_str(), record.cypher.as_str()),
+                record.rating,
+            );
+        }
+        latest
+            .into_iter()
+            .filter(|(_, rating)| *rating == Rating::Up)
+            .map(|((question, cypher), _)| FewShotExample {
+                question: question.to_string(),
+                cypher: cypher.to_string(),
+            })
+            .collect()
+    }
+}
+
+pub fn feedback_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("feedback.jsonl"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::few_shot::rank_examples;
+
+    fn rating(question: &str, cypher: &str, rating: Rating) -> FeedbackRecord {
+        FeedbackRecord {
+            question: question.to_string(),
+            cypher: cypher.to_string(),
+            target: FeedbackTarget::Translation,
+            rating,
+            analysis: None,
+            model: None,
+            recorded_at: "2025-03-01T12:00:00Z".to_string(),
+        }
+    }
+
+    fn examples_for(store: &FeedbackStore, question: &str, limit: usize) -> Vec<FewShotExample> {
+        let approved = store
+            .approved_translations()
+            .into_iter()
+            .map(|example| (example, 1.0));
+        rank_examples(question, approved, limit)
+    }
+
+    #[test]
+    fn examples_prefer_similar_approved_questions() {
+        let store = FeedbackStore::default();
+        let pods = "MATCH (p:Pod) RETURN p";
+        store
+            .record(rating("which pods are crashing in prod", pods, Rating::Up))
+            .unwrap();
+        store
+            .record(rating(
+                "list services in prod",
+                "MATCH (s:Service) RETURN s",
+                Rating::Up,
+            ))
+            .unwrap();
+        store
+            .record(rating(
+                "which nodes are crashing",
+                "MATCH (n:Node) RETURN n",
+                Rating::Up,
+            ))
+            .unwrap();
+        store
+            .record(rating(
+                "which nodes are crashing",
+                "MATCH (n:Node) RETURN n",
+                Rating::Down,
+            ))
+            .unwrap();
+
+        let examples = examples_for(&store, "Which pods are crashing?", 2);
+        assert_eq!(examples.len(), 1);
+        assert_eq!(examples[0].cypher, pods);
+        assert!(examples_for(&store, "unrelated", 2).is_empty());
+    }
+
+    #[test]
+    fn records_are_appended_and_reloaded() {
+        let dir = std::env::temp_dir().join(format!("ariadne-feedback-{}", std::process::id()));
+        let path = dir.join("feedback.jsonl");
+        let store = FeedbackStore::load_from(&path).unwrap();
+        store
+            .record(rating("list pods", "MATCH (p:Pod) RETURN p", Rating::Up))
+            .unwrap();
+        std::fs::write(
+            &path,
+            format!("{}\nnot json\n", std::fs::read_to_string(&path).unwrap()),
+        )
+        .unwrap();
+
+        let reloaded = FeedbackStore::load_from(&path).unwrap();
+        assert_eq!(examples_for(&reloaded, "list pods", 3).len(), 1);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
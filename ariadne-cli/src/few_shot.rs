@@ -0,0 +1,234 @@
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use crate::agent::FewShotExample;
+use crate::feedback::FeedbackStore;
+use crate::validation::validate_cypher;
+
+/// Seeded pairs shipped with the CLI, so the translator gets examples before anyone has rated
+/// a translation.
+const SEED_EXAMPLES: &str = include_str!("../config/few_shot_examples.toml");
+/// A seeded example ranks just below an equally similar approved translation, which reflects
+/// how this cluster's users phrase things.
+const SEED_WEIGHT: f64 = 0.9;
+
+#[derive(Debug, Deserialize)]
+struct SeedFile {
+    #[serde(default)]
+    examples: Vec<SeedExample>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SeedExample {
+    question: String,
+    cypher: String,
+}
+
+/// Up to `limit` examples for `question`: approved translations from `feedback` and the
+/// seeded pairs, most similar question first.
+pub fn examples_for(question: &str, feedback: &FeedbackStore, limit: usize) -> Vec<FewShotExample> {
+    let approved = feedback
+        .approved_translations()
+        .into_iter()
+        .map(|example| (example, 1.0));
+    let seeded = seed_examples()
+        .iter()
+        .cloned()
+        .map(|example| (example, SEED_WEIGHT));
+    rank_examples(question, approved.chain(seeded), limit)
+}
+
+/// The seeded pairs whose query passes the validator.
+pub fn seed_examples() -> &'static [FewShotExample] {
+    static SEEDS: OnceLock<Vec<FewShotExample>> = OnceLock::new();
+    SEEDS.get_or_init(|| parse_seeds(SEED_EXAMPLES))
+}
+
+fn parse_seeds(contents: &str) -> Vec<FewShotExample> {
+    let file: SeedFile = match toml::from_str(contents) {
+        Ok(file) => file,
+        Err(err) => {
+            tracing::warn!("Failed to parse the seeded few-shot examples: {err}");
+            return Vec::new();
+        }
+    };
+    file.examples
+        .into_iter()
+        .filter_map(|seed| {
+            let cypher = seed.cypher.trim().to_string();
+            match validate_cypher(&cypher) {
+                Ok(()) => Some(FewShotExample {
+                    question: seed.question.trim().to_string(),
+                    cypher,
+                }),
+                Err(issue) => {
+                    tracing::warn!("Skipping few-shot example '{}': {issue}", seed.question);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Orders weighted candidates by how similar their question is to `question`, times their
+/// weight, and keeps the first `limit`. Candidates sharing no word with the question are
+/// dropped, as are later candidates with a question already taken.
+pub fn rank_examples(
+    question: &str,
+    candidates: impl IntoIterator<Item = (FewShotExample, f64)>,
+    limit: usize,
+) -> Vec<FewShotExample> {
+    let words = question_words(question);
+    let mut scored: Vec<(f64, FewShotExample)> = candidates
+        .into_iter()
+        .filter_map(|(example, weight)| {
+            let score = similarity(&words, &question_words(&example.question)) * weight;
+            (score > 0.0).then_some((score, example))
+        })
+        .collect();
+    scored.sort_by(|a, b| {
+        b.0.total_cmp(&a.0)
+            .then_with(|| a.1.question.cmp(&b.1.question))
+    });
+    let mut seen = HashSet::new();
+    scored
+        .into_iter()
+        .map(|(_, example)| example)
+        .filter(|example| seen.insert(example.question.to_lowercase()))
+        .take(limit)
+        .collect()
+}
+
+/// Abbreviations SREs type, spelled out the way the seeded questions are.
+const ALIASES: &[(&str, &[&str])] = &[
+    ("ns", &["namespace"]),
+    ("svc", &["service"]),
+    ("deploy", &["deployment"]),
+    ("sts", &["stateful", "set"]),
+    ("statefulset", &["stateful", "set"]),
+    ("ds", &["daemon", "set"]),
+    ("daemonset", &["daemon", "set"]),
+    ("rs", &["replica", "set"]),
+    ("replicaset", &["replica", "set"]),
+    ("pvc", &["persistent", "volume", "claim"]),
+    ("pv", &["persistent", "volume"]),
+    ("crashloop", &["crashlooping"]),
+    ("crashloopbackoff", &["crashlooping"]),
+];
+
+/// Words that carry no meaning for matching questions.
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "are", "for", "with", "that", "this", "from", "which", "what", "there", "does",
+    "all", "show", "list", "give",
+];
+
+/// Lower-cased content words of a question with aliases spelled out and a plural `s` dropped,
+/// so "svcs in ns prod" and "services in namespace prod" match.
+pub fn question_words(question: &str) -> HashSet<String> {
+    let mut words = HashSet::new();
+    for raw in question
+        .split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+    {
+        let singular = singular(&raw);
+        let alias = ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == raw || *alias == singular);
+        match alias {
+            Some((_, expanded)) => words.extend(expanded.iter().map(|word| word.to_string())),
+            None if singular.len() >= 3 && !STOP_WORDS.contains(&singular.as_str()) => {
+                words.insert(singular);
+            }
+            None => {}
+        }
+    }
+    words
+}
+
+fn singular(word: &str) -> String {
+    match word.strip_suffix('s') {
+        Some(stem) if stem.len() >= 2 && !stem.ends_with('s') => stem.to_string(),
+        _ => word.to_string(),
+    }
+}
+
+/// Jaccard similarity of two word sets.
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let shared = a.intersection(b).count();
+    if shared == 0 {
+        return 0.0;
+    }
+    shared as f64 / a.union(b).count() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_seeded_example_validates() {
+        let file: SeedFile = toml::from_str(SEED_EXAMPLES).unwrap();
+        for seed in &file.examples {
+            if let Err(issue) = validate_cypher(seed.cypher.trim()) {
+                panic!("seed '{}' does not validate: {issue}", seed.question);
+            }
+        }
+        assert_eq!(seed_examples().len(), file.examples.len());
+    }
+
+    #[test]
+    fn abbreviations_and_plurals_match_seeded_phrasing() {
+        let examples = rank_examples(
+            "svcs backing pods",
+            seed_examples()
+                .iter()
+                .cloned()
+                .map(|example| (example, 1.0)),
+            1,
+        );
+        assert_eq!(
+            examples[0].question,
+            "Which pods back the checkout service?"
+        );
+
+        let examples = rank_examples(
+            "pvcs that are not bound",
+            seed_examples()
+                .iter()
+                .cloned()
+                .map(|example| (example, 1.0)),
+            1,
+        );
+        assert_eq!(
+            examples[0].question,
+            "Which persistent volume claims are not bound?"
+        );
+    }
+
+    #[test]
+    fn approved_translations_outrank_equally_similar_seeds() {
+        let example = |question: &str, cypher: &str| FewShotExample {
+            question: question.to_string(),
+            cypher: cypher.to_string(),
+        };
+        let ranked = rank_examples(
+            "pods per node",
+            [
+                (
+                    example("Count pods per node.", "MATCH (seed) RETURN 1"),
+                    0.9,
+                ),
+                (
+                    example("count pods per node", "MATCH (approved) RETURN 1"),
+                    1.0,
+                ),
+                (example("list namespaces", "MATCH (n) RETURN n"), 1.0),
+            ],
+            3,
+        );
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].cypher, "MATCH (approved) RETURN 1");
+    }
+}
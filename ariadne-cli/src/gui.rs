@@ -1,7 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::{mpsc, Arc};
 use std::time::{Duration, Instant};
 
+use ::llm::builder::LLMBackend;
+use chrono::{DateTime, Utc};
 use eframe::egui;
 use eframe::egui::{
     text::LayoutJob, Align, Align2, Color32, CornerRadius, FontFamily, FontId, Frame, Layout,
@@ -9,39 +12,143 @@ use eframe::egui::{
 };
 use egui_extras::{Column, TableBuilder};
 use serde_json::{Map, Value};
+use strum::IntoEnumIterator;
 use tokio::runtime::Handle;
 use tokio_util::sync::CancellationToken;
 
-use ariadne_core::graph_backend::GraphBackend;
-use ariadne_core::state::SharedClusterState;
+use ariadne_core::errors::ErrorClass;
+use ariadne_core::state_resolver::{ListingFailure, ResolverHealth, DEFAULT_POLL_INTERVAL};
 use ariadne_core::types::ResourceType;
-use strum::IntoEnumIterator;
 
 use crate::agent::{
-    Agentic, AnalysisResult, Analyst, ConversationTurn, LlmUsage, RouteDecision, Router, Translator,
+    estimate_tokens, price_for_model, AgentStep, Agentic, AnalysisResult, Analyst,
+    ConversationTurn, EntityHint, FollowUp, IncidentReport, LlmAgents, LlmConfig, LlmUsage,
+    ModelPrice, MonitorProposals, PromptFingerprint, ProposedMonitor, RouteDecision, Router,
+    Translator, UsageCost,
+};
+use crate::alerts::{
+    append_alert_rule, run_alert_loop, show_desktop_notification, AlertCondition, AlertFired,
+    AlertRule,
+};
+use crate::budget::{cheaper_model, BudgetAction, SessionBudget};
+use crate::cluster::ClusterSession;
+use crate::compare::{compare_records, RecordComparison};
+use crate::dashboard::{pinned_metric, DashboardTile, DASHBOARD_TILES};
+use crate::describe::{
+    describe_object, events_section, DescribeBlock, DescribeKind, DescribeSection,
 };
 use crate::error::CliResult;
-use crate::validation::validate_cypher;
+use crate::error_hints::error_hint;
+use crate::export::{export_records, ExportFormat};
+use crate::feedback::{FeedbackRecord, FeedbackStore, FeedbackTarget, Rating};
+use crate::i18n::{set_locale, tr, Locale};
+use crate::instructions::OperatorInstructions;
+use crate::kube_tools::kube_tools;
+use crate::logs::{fetch_logs, matching_lines, LogSince, LogSource, LogTarget, FOLLOW_INTERVAL};
+use crate::memory::{render_memories, MemoryKind, MemoryStore};
+use crate::pipeline::{
+    clarified_question, extract_context_bindings, format_value, summarize_records, truncate_text,
+    Pipeline, PipelineEvent, ReportEntry,
+};
+use crate::report::{render_incident_markdown, write_report, Report, ReportFormat};
+use crate::schema_browser::{schema_catalog, SchemaLabel};
+use crate::settings::{
+    ConnectionSettings, GuiSettings, PinnedQuery, SavedQuery, ThemeChoice, MAX_UI_SCALE,
+    MIN_UI_SCALE,
+};
+use crate::suggestions::{anomaly_questions, build_completions, SuggestedQuestion};
+use crate::telemetry::in_question;
+use crate::templates::{
+    fill_template, parameterize_template, template_params, template_placeholders,
+};
+use crate::timeline::{build_timeline, resource_uids, TimelineEntry, TimelineKind, TIMELINE_QUERY};
+use crate::translation_cache::TranslationCache;
+use crate::voting::VotePolicy;
 
 const SHORT_TERM_CONTEXT_LIMIT: usize = 4;
 const COMPACT_CONTEXT_LIMIT: usize = 12;
 const CONTEXT_RESERVED_TOKENS: usize = 2048;
 const CONTEXT_MIN_TOKENS: usize = 512;
 const GRAPH_PULSE_HEIGHT: f32 = 40.0;
-const LLM_MAX_RETRIES: usize = 1;
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+/// How often the prompt files are checked for edits.
+const PROMPT_RELOAD_INTERVAL: Duration = Duration::from_secs(2);
+const WATCH_MAX_REMOVED_SHOWN: usize = 10;
+const COMPARE_MAX_ROWS_SHOWN: usize = 200;
+const TIMELINE_PLOT_HEIGHT: f32 = 64.0;
+const UI_SCALE_STEP: f32 = 0.1;
+const QUESTION_INPUT_ID: &str = "question-input";
+const PALETTE_INPUT_ID: &str = "command-palette-input";
+const PALETTE_MAX_RESULTS: usize = 12;
+const PALETTE_MAX_RESOURCES: usize = 5000;
+const SLASH_COMMANDS: &[(&str, &str)] = &[
+    ("/explain", "Profile the last query and show its reasoning"),
+    (
+        "/investigate",
+        "Let the agent run several queries and conclude (/investigate <question>)",
+    ),
+    (
+        "/profile",
+        "Toggle query statistics (scans, index hits, clause timings) under each result",
+    ),
+    ("/history", "Show question history"),
+    ("/save", "Save the last question (/save <name>)"),
+    (
+        "/template",
+        "Save a question or Cypher with {{placeholders}} (/template <name> <text>)",
+    ),
+    (
+        "/report",
+        "Write an incident report of the session, or export the feed (/report [md|html])",
+    ),
+    (
+        "/monitors",
+        "Propose alert rules that would catch what the session found",
+    ),
+    (
+        "/settings",
+        "Edit the LLM endpoint, graph backend and theme",
+    ),
+];
+
+/// Backend names accepted by `LLM_BACKEND`, offered by the Settings window.
+const LLM_BACKENDS: &[&str] = &[
+    "openai",
+    "anthropic",
+    "ollama",
+    "deepseek",
+    "xai",
+    "google",
+    "groq",
+    "aws",
+];
 
 pub struct GuiArgs {
     pub runtime_handle: tokio::runtime::Handle,
-    pub backend: Arc<dyn GraphBackend>,
+    /// Connected clusters; the first one is active on startup.
+    pub clusters: Vec<ClusterSession>,
     pub translator: Arc<dyn Translator>,
     pub router: Arc<dyn Router>,
     pub agentic: Arc<dyn Agentic>,
     pub analyst: Arc<dyn Analyst>,
-    pub cluster_state: SharedClusterState,
     pub token: CancellationToken,
-    pub cluster_label: String,
-    pub backend_label: String,
     pub context_window_tokens: Option<usize>,
+    pub alert_rules: Vec<AlertRule>,
+    /// File that alert rules accepted from `/monitors` are added to.
+    pub alert_rules_path: Option<PathBuf>,
+    pub operator_instructions: OperatorInstructions,
+    /// Models offered by the header picker; the agents above are built from the first one.
+    pub llm_models: Vec<LlmConfig>,
+    pub feedback: Arc<FeedbackStore>,
+    /// Findings of earlier sessions; `None` when memory is turned off.
+    pub memory: Option<Arc<MemoryStore>>,
+    /// See [`Pipeline::dry_run_candidates`].
+    pub dry_run_candidates: bool,
+    pub analysis_votes: VotePolicy,
+    pub translation_cache: Option<Arc<TranslationCache>>,
+    /// Give the agent tools over the API of the active cluster.
+    pub agent_kube_tools: bool,
+    pub budget: SessionBudget,
 }
 
 pub fn run_gui(args: GuiArgs) -> CliResult<()> {
@@ -49,32 +156,47 @@ pub fn run_gui(args: GuiArgs) -> CliResult<()> {
         viewport: egui::ViewportBuilder::default().with_inner_size([1400.0, 900.0]),
         ..Default::default()
     };
+    if args.clusters.is_empty() {
+        return Err("at least one cluster is required".into());
+    }
     let runtime_handle = args.runtime_handle.clone();
-    let backend = args.backend.clone();
+    let clusters = args.clusters.clone();
     let translator = args.translator.clone();
     let router = args.router.clone();
     let agentic = args.agentic.clone();
-    let cluster_state = args.cluster_state.clone();
     let token = args.token.clone();
-    let cluster_label = args.cluster_label.clone();
     eframe::run_native(
         "Ariadne",
         native_options,
         Box::new(|cc| {
-            let palette = Palette::default();
-            setup_style(&cc.egui_ctx, &palette);
+            let settings = GuiSettings::load();
+            set_locale(settings.locale());
+            setup_style(&cc.egui_ctx, &Palette::from_settings(&settings));
+            // Ctrl +/- are handled by the app so the zoom factor is saved with the settings.
+            cc.egui_ctx
+                .options_mut(|options| options.zoom_with_keyboard = false);
+            cc.egui_ctx.set_zoom_factor(settings.ui_scale());
             Ok(Box::new(GuiApp::new(
                 runtime_handle.clone(),
-                backend.clone(),
+                clusters.clone(),
                 translator.clone(),
                 router.clone(),
                 agentic.clone(),
                 args.analyst.clone(),
-                cluster_state.clone(),
                 token.clone(),
-                cluster_label.clone(),
-                args.backend_label.clone(),
                 args.context_window_tokens,
+                args.alert_rules.clone(),
+                args.alert_rules_path.clone(),
+                args.operator_instructions.clone(),
+                args.llm_models.clone(),
+                args.feedback.clone(),
+                args.memory.clone(),
+                args.dry_run_candidates,
+                args.analysis_votes.clone(),
+                args.translation_cache.clone(),
+                args.agent_kube_tools,
+                args.budget,
+                settings,
                 cc.egui_ctx.clone(),
             )))
         }),
@@ -83,15 +205,25 @@ pub fn run_gui(args: GuiArgs) -> CliResult<()> {
     Ok(())
 }
 
-#[derive(Clone)]
-struct ClusterMeta {
-    label: String,
-    connected: bool,
-    backend_label: String,
+struct ClusterView {
+    session: ClusterSession,
+    pulse: PulseStats,
+}
+
+#[derive(Default)]
+struct PulseStats {
+    nodes: Vec<f64>,
+    props: Vec<f64>,
+    pods: Vec<f64>,
+    services: Vec<f64>,
+    namespaces: Vec<f64>,
 }
 
 #[derive(Clone)]
 struct Palette {
+    dark: bool,
+    /// Draws widget outlines thicker on top of the palette's own colors.
+    high_contrast: bool,
     bg_primary: Color32,
     bg_panel: Color32,
     bg_elevated: Color32,
@@ -113,7 +245,91 @@ struct Palette {
 
 impl Default for Palette {
     fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Palette {
+    fn from_settings(settings: &GuiSettings) -> Self {
+        match settings.theme {
+            ThemeChoice::Dark => Self::dark(),
+            ThemeChoice::Light => Self::light(),
+            ThemeChoice::HighContrast => Self::high_contrast(),
+            ThemeChoice::Custom => {
+                let mut palette = match settings.custom_base {
+                    ThemeChoice::Light => Self::light(),
+                    ThemeChoice::HighContrast => Self::high_contrast(),
+                    _ => Self::dark(),
+                };
+                palette.apply_overrides(&settings.custom_colors);
+                palette
+            }
+        }
+    }
+
+    fn light() -> Self {
+        Self {
+            dark: false,
+            high_contrast: false,
+            bg_primary: Color32::from_rgb(0xF3, 0xF5, 0xF8),
+            bg_panel: Color32::from_rgb(0xFF, 0xFF, 0xFF),
+            bg_elevated: Color32::from_rgb(0xEA, 0xEE, 0xF3),
+            accent: Color32::from_rgb(0x1F, 0x6F, 0xB2),
+            accent_warm: Color32::from_rgb(0xB3, 0x5C, 0x1E),
+            success: Color32::from_rgb(0x1C, 0x86, 0x56),
+            danger: Color32::from_rgb(0xC0, 0x39, 0x2B),
+            text_primary: Color32::from_rgb(0x14, 0x1C, 0x24),
+            text_muted: Color32::from_rgb(0x4E, 0x5C, 0x6A),
+            border: Color32::from_rgb(0xC6, 0xCF, 0xD9),
+            keyword: Color32::from_rgb(0xA3, 0x4E, 0x12),
+            string: Color32::from_rgb(0x15, 0x65, 0x9E),
+            spark_nodes: Color32::from_rgb(0xC2, 0x4C, 0x4C),
+            spark_props: Color32::from_rgb(0x7A, 0x4C, 0xC8),
+            spark_pods: Color32::from_rgb(0x25, 0x76, 0xC2),
+            spark_services: Color32::from_rgb(0x1E, 0x93, 0x5E),
+            spark_namespaces: Color32::from_rgb(0x1F, 0x86, 0xC9),
+        }
+    }
+
+    /// Applies `#rrggbb` overrides by slot name; unknown slots and malformed colors are logged
+    /// and skipped.
+    fn apply_overrides(&mut self, colors: &BTreeMap<String, String>) {
+        for (slot, value) in colors {
+            let Some(color) = parse_hex_color(value) else {
+                tracing::warn!("Ignoring invalid color {value:?} for theme slot {slot}");
+                continue;
+            };
+            let target = match slot.as_str() {
+                "bg_primary" => &mut self.bg_primary,
+                "bg_panel" => &mut self.bg_panel,
+                "bg_elevated" => &mut self.bg_elevated,
+                "accent" => &mut self.accent,
+                "accent_warm" => &mut self.accent_warm,
+                "success" => &mut self.success,
+                "danger" => &mut self.danger,
+                "text_primary" => &mut self.text_primary,
+                "text_muted" => &mut self.text_muted,
+                "border" => &mut self.border,
+                "keyword" => &mut self.keyword,
+                "string" => &mut self.string,
+                "spark_nodes" => &mut self.spark_nodes,
+                "spark_props" => &mut self.spark_props,
+                "spark_pods" => &mut self.spark_pods,
+                "spark_services" => &mut self.spark_services,
+                "spark_namespaces" => &mut self.spark_namespaces,
+                _ => {
+                    tracing::warn!("Ignoring unknown theme slot {slot}");
+                    continue;
+                }
+            };
+            *target = color;
+        }
+    }
+
+    fn dark() -> Self {
         Self {
+            dark: true,
+            high_contrast: false,
             bg_primary: Color32::from_rgb(0x0F, 0x14, 0x1B),
             bg_panel: Color32::from_rgb(0x14, 0x1C, 0x24),
             bg_elevated: Color32::from_rgb(0x1B, 0x25, 0x30),
@@ -133,6 +349,45 @@ impl Default for Palette {
             spark_namespaces: Color32::from_rgb(0x7D, 0xC4, 0xFF),
         }
     }
+
+    /// Pure black and white with saturated accents; every text color keeps a contrast ratio of
+    /// at least 7:1 against the backgrounds.
+    fn high_contrast() -> Self {
+        Self {
+            dark: true,
+            high_contrast: true,
+            bg_primary: Color32::BLACK,
+            bg_panel: Color32::BLACK,
+            bg_elevated: Color32::from_rgb(0x1A, 0x1A, 0x1A),
+            accent: Color32::from_rgb(0x5C, 0xC8, 0xFF),
+            accent_warm: Color32::from_rgb(0xFF, 0xD1, 0x3B),
+            success: Color32::from_rgb(0x3D, 0xFF, 0x8A),
+            danger: Color32::from_rgb(0xFF, 0x6B, 0x6B),
+            text_primary: Color32::WHITE,
+            text_muted: Color32::from_rgb(0xD0, 0xD0, 0xD0),
+            border: Color32::from_rgb(0xE0, 0xE0, 0xE0),
+            keyword: Color32::from_rgb(0xFF, 0xD1, 0x3B),
+            string: Color32::from_rgb(0x7F, 0xFF, 0xD4),
+            spark_nodes: Color32::from_rgb(0xFF, 0x8F, 0x8F),
+            spark_props: Color32::from_rgb(0xD9, 0xA6, 0xFF),
+            spark_pods: Color32::from_rgb(0x5C, 0xC8, 0xFF),
+            spark_services: Color32::from_rgb(0x3D, 0xFF, 0x8A),
+            spark_namespaces: Color32::from_rgb(0xFF, 0xD1, 0x3B),
+        }
+    }
+}
+
+fn parse_hex_color(value: &str) -> Option<Color32> {
+    let hex = value.trim().strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |range: std::ops::Range<usize>| u8::from_str_radix(hex.get(range)?, 16).ok();
+    Some(Color32::from_rgb(
+        channel(0..2)?,
+        channel(2..4)?,
+        channel(4..6)?,
+    ))
 }
 
 // ... (FeedState, ResultPayload, etc. unchanged) ...
@@ -142,7 +397,11 @@ fn lighten_color(color: Color32, factor: f32) -> Color32 {
 }
 
 fn setup_style(ctx: &egui::Context, palette: &Palette) {
-    let mut visuals = egui::Visuals::dark();
+    let mut visuals = if palette.dark {
+        egui::Visuals::dark()
+    } else {
+        egui::Visuals::light()
+    };
     visuals.panel_fill = palette.bg_panel;
     visuals.window_fill = palette.bg_primary;
     visuals.faint_bg_color = lighten_color(palette.bg_panel, 1.04);
@@ -189,6 +448,15 @@ fn setup_style(ctx: &egui::Context, palette: &Palette) {
     visuals.button_frame = true;
     visuals.collapsing_header_frame = true;
     visuals.indent_has_left_vline = false;
+    if palette.high_contrast {
+        visuals.widgets.noninteractive.bg_stroke = Stroke::new(1.5, palette.border);
+        visuals.widgets.inactive.bg_stroke = Stroke::new(1.5, palette.border);
+        visuals.widgets.hovered.bg_stroke = Stroke::new(2.0, palette.accent);
+        visuals.widgets.active.bg_stroke = Stroke::new(2.5, palette.accent);
+        visuals.selection.bg_fill = palette.accent.gamma_multiply(0.55);
+        visuals.selection.stroke = Stroke::new(2.0, palette.text_primary);
+        visuals.window_stroke = Stroke::new(2.0, palette.border);
+    }
 
     ctx.set_visuals(visuals);
 
@@ -225,6 +493,7 @@ enum FeedState {
     Running,
     Ready,
     Error(String),
+    Cancelled,
 }
 
 #[derive(Debug, Clone)]
@@ -259,6 +528,11 @@ struct RowCard {
 #[derive(Debug, Clone)]
 struct GraphNode {
     label: String,
+    /// Resource kind from the node's `kind` (or `type`) field, when it names a known one.
+    kind: Option<ResourceType>,
+    /// `metadata.uid` (or `uid`) and `metadata.name` (or `name`), used to query a selection.
+    uid: Option<String>,
+    name: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -268,16 +542,104 @@ struct GraphEdge {
     label: Option<String>,
 }
 
+/// A rule proposed by `/monitors`; `status` says where it went once accepted.
+struct MonitorCard {
+    monitor: ProposedMonitor,
+    status: Option<String>,
+}
+
 #[derive(Debug, Clone)]
+struct AlertCard {
+    id: u64,
+    fired_at: Instant,
+    alert: AlertFired,
+}
+
+enum AlertAction {
+    ShowRows(u64),
+    Dismiss(u64),
+}
+
+/// Clicks on a Dashboard card, by index into the pinned queries.
+enum PinAction {
+    Open(usize),
+    Unpin(usize),
+}
+
+/// User interactions collected while rendering the feed and applied once rendering is done.
+enum FeedAction {
+    Run {
+        id: u64,
+        cypher: String,
+    },
+    Select(RowCard),
+    Export {
+        id: u64,
+        format: ExportFormat,
+    },
+    Cancel(u64),
+    ToggleWatch(u64),
+    OpenLogs(LogSource),
+    OpenTimeline(u64),
+    /// Picks a result for comparison; the second pick opens the comparison.
+    Compare(u64),
+    /// Sends the failed query and its error back to the translator.
+    FixQuery(u64),
+    /// Puts a query scaffold into the input box for the user to refine.
+    FillInput(String),
+    /// Pins the item's query to the Dashboard tab, or unpins it.
+    TogglePin(u64),
+    /// Opens one of the agent's intermediate queries as a feed item of its own.
+    RunAgentStep {
+        id: u64,
+        step: usize,
+    },
+    /// Runs a suggested follow-up's validated query as a new item under `parent`.
+    FollowUp {
+        parent: u64,
+        follow_up: FollowUp,
+    },
+    /// Adds monitor `index` proposed in item `id` to the alert rules.
+    AcceptMonitor {
+        id: u64,
+        index: usize,
+    },
+    Rate {
+        id: u64,
+        target: FeedbackTarget,
+        rating: Rating,
+    },
+}
+
+/// Live re-execution of a feed item's query. `added` holds row keys that appeared in the latest
+/// refresh and `removed` the rows that disappeared, so the table can highlight both.
+struct WatchState {
+    last_run: Instant,
+    refreshing: bool,
+    refreshes: u64,
+    records: Vec<Value>,
+    added: HashSet<String>,
+    removed: Vec<Value>,
+    error: Option<String>,
+}
+
 struct FeedItem {
     id: u64,
     user_text: String,
+    /// What was sent to the pipeline when it differs from `user_text`: the question the user
+    /// clarified, together with the clarifying question and this answer.
+    asked: Option<String>,
     cypher: Option<String>,
     params: Option<HashMap<String, Value>>,
+    reasoning: Option<String>,
+    /// Misspelled resource names in the question and the names the translator was pointed to.
+    entity_hints: Vec<EntityHint>,
     result: ResultPayload,
     state: FeedState,
     llm_usage: Option<LlmUsage>,
     llm_duration_ms: Option<u128>,
+    /// The query was reused from the translation cache.
+    translation_cached: bool,
     exec_duration_ms: Option<u128>,
     analysis: Option<AnalysisResult>,
     analysis_duration_ms: Option<u128>,
@@ -287,6 +649,27 @@ struct FeedItem {
     context_bindings: Option<HashMap<String, Value>>,
     route: Option<RouteDecision>,
     agent_steps: Option<usize>,
+    /// The router's answer or clarifying question, for items that ran no query.
+    reply: Option<String>,
+    /// Intermediate queries the agent ran before the planned query, in order.
+    investigation: Vec<AgentStep>,
+    /// Next questions suggested after the analysis, each with a query that passed validation.
+    follow_ups: Vec<FollowUp>,
+    /// Alert rules proposed by `/monitors`.
+    monitors: Vec<MonitorCard>,
+    export_status: Option<String>,
+    watch: Option<WatchState>,
+    /// Item whose analysis suggested this question, when asked via a follow-up button.
+    follow_up_of: Option<u64>,
+    /// Model that was active when the question was asked.
+    model: Option<String>,
+    /// Price of that model, when the pricing table knows it.
+    price: Option<ModelPrice>,
+    translation_rating: Option<Rating>,
+    analysis_rating: Option<Rating>,
+    asked_at: DateTime<Utc>,
+    /// Profile rows of the query, loaded when query statistics are switched on.
+    query_stats: Option<Result<Vec<Value>, String>>,
 }
 
 impl FeedItem {
@@ -294,12 +677,16 @@ impl FeedItem {
         Self {
             id,
             user_text,
+            asked: None,
             cypher: None,
             params: None,
+            reasoning: None,
+            entity_hints: Vec::new(),
             result: ResultPayload::Empty,
             state: FeedState::Translating,
             llm_usage: None,
             llm_duration_ms: None,
+            translation_cached: false,
             exec_duration_ms: None,
             analysis: None,
             analysis_duration_ms: None,
@@ -309,6 +696,50 @@ impl FeedItem {
             context_bindings: None,
             route: None,
             agent_steps: None,
+            reply: None,
+            investigation: Vec::new(),
+            follow_ups: Vec::new(),
+            monitors: Vec::new(),
+            export_status: None,
+            watch: None,
+            follow_up_of: None,
+            model: None,
+            price: None,
+            translation_rating: None,
+            analysis_rating: None,
+            asked_at: Utc::now(),
+            query_stats: None,
+        }
+    }
+
+    /// The question the LLMs saw for this item.
+    fn question(&self) -> &str {
+        self.asked.as_deref().unwrap_or(&self.user_text)
+    }
+
+    /// Tokens and cost of the translation and the analysis of this item, each at the price
+    /// of the model that served it.
+    fn usage_cost(&self) -> UsageCost {
+        let mut cost = UsageCost::default();
+        for usage in [
+            self.llm_usage.as_ref(),
+            self.analysis
+                .as_ref()
+                .and_then(|analysis| analysis.usage.as_ref()),
+        ] {
+            let fallback_price = usage
+                .and_then(|usage| usage.model.as_deref())
+                .map(price_for_model);
+            cost.add(usage, fallback_price.unwrap_or(self.price).as_ref());
+        }
+        cost
+    }
+
+    /// Names the fallback model when one answered instead of the model that was active when
+    /// the question was asked.
+    fn note_fallback(&mut self, usage: Option<&LlmUsage>) {
+        if let Some(model) = usage.and_then(|usage| usage.model.as_deref()) {
+            self.model = Some(format!("{model} (fallback)"));
         }
     }
 }
@@ -322,12 +753,26 @@ enum AppEvent {
     TranslationStarted {
         id: u64,
     },
+    Replied {
+        id: u64,
+        text: String,
+    },
+    EntitiesResolved {
+        id: u64,
+        hints: Vec<EntityHint>,
+    },
+    AgentStepsCompleted {
+        id: u64,
+        steps: Vec<AgentStep>,
+    },
     TranslationCompleted {
         id: u64,
         cypher: String,
         params: Option<HashMap<String, Value>>,
+        reasoning: Option<String>,
         usage: Option<LlmUsage>,
         duration_ms: u128,
+        cached: bool,
     },
     TranslationFailed {
         id: u64,
@@ -358,6 +803,10 @@ enum AppEvent {
     AnalysisStarted {
         id: u64,
     },
+    AnalysisProgress {
+        id: u64,
+        analysis: AnalysisResult,
+    },
     AnalysisCompleted {
         id: u64,
         analysis: AnalysisResult,
@@ -368,6 +817,28 @@ enum AppEvent {
         error: String,
         duration_ms: u128,
     },
+    FollowUpsSuggested {
+        id: u64,
+        follow_ups: Vec<FollowUp>,
+    },
+    SessionSummarized {
+        id: u64,
+        report: IncidentReport,
+        duration_ms: u128,
+    },
+    SessionSummaryFailed {
+        id: u64,
+        error: String,
+    },
+    MonitorsProposed {
+        id: u64,
+        proposals: MonitorProposals,
+        duration_ms: u128,
+    },
+    MonitorsFailed {
+        id: u64,
+        error: String,
+    },
     ContextCompactionStarted,
     ContextCompactionCompleted {
         summary: String,
@@ -377,43 +848,429 @@ enum AppEvent {
     ContextCompactionFailed {
         error: String,
     },
+    TaskFinished {
+        id: u64,
+    },
+    WatchRefreshed {
+        id: u64,
+        records: Vec<Value>,
+        duration_ms: u128,
+    },
+    WatchFailed {
+        id: u64,
+        error: String,
+    },
+    QueryStatsLoaded {
+        id: u64,
+        result: Result<Vec<Value>, String>,
+    },
+    AlertFired(AlertFired),
+    DashboardTileLoaded {
+        generation: u64,
+        index: usize,
+        result: Result<usize, String>,
+    },
+    PinnedLoaded {
+        generation: u64,
+        cypher: String,
+        result: Result<Vec<Value>, String>,
+    },
+    LogTargetResolved {
+        request: u64,
+        result: Result<LogTarget, String>,
+    },
+    LogsFetched {
+        request: u64,
+        result: Result<String, String>,
+    },
+    InspectorEventsLoaded {
+        request: u64,
+        result: Result<Vec<Value>, String>,
+    },
+    TimelineLoaded {
+        request: u64,
+        result: Result<Vec<Value>, String>,
+    },
+}
+
+impl AppEvent {
+    fn item_id(&self) -> Option<u64> {
+        match self {
+            AppEvent::RouteDecided { id, .. }
+            | AppEvent::TranslationStarted { id }
+            | AppEvent::Replied { id, .. }
+            | AppEvent::EntitiesResolved { id, .. }
+            | AppEvent::AgentStepsCompleted { id, .. }
+            | AppEvent::TranslationCompleted { id, .. }
+            | AppEvent::TranslationFailed { id, .. }
+            | AppEvent::ValidationFailed { id, .. }
+            | AppEvent::QueryStarted { id, .. }
+            | AppEvent::QueryCompleted { id, .. }
+            | AppEvent::QueryFailed { id, .. }
+            | AppEvent::AnalysisStarted { id }
+            | AppEvent::AnalysisProgress { id, .. }
+            | AppEvent::AnalysisCompleted { id, .. }
+            | AppEvent::AnalysisFailed { id, .. }
+            | AppEvent::FollowUpsSuggested { id, .. }
+            | AppEvent::SessionSummarized { id, .. }
+            | AppEvent::SessionSummaryFailed { id, .. }
+            | AppEvent::MonitorsProposed { id, .. }
+            | AppEvent::MonitorsFailed { id, .. }
+            | AppEvent::TaskFinished { id }
+            | AppEvent::WatchRefreshed { id, .. }
+            | AppEvent::WatchFailed { id, .. }
+            | AppEvent::QueryStatsLoaded { id, .. } => Some(*id),
+            AppEvent::ContextCompactionStarted
+            | AppEvent::ContextCompactionCompleted { .. }
+            | AppEvent::ContextCompactionFailed { .. }
+            | AppEvent::AlertFired(_)
+            | AppEvent::DashboardTileLoaded { .. }
+            | AppEvent::PinnedLoaded { .. }
+            | AppEvent::LogTargetResolved { .. }
+            | AppEvent::LogsFetched { .. }
+            | AppEvent::InspectorEventsLoaded { .. }
+            | AppEvent::TimelineLoaded { .. } => None,
+        }
+    }
+
+    fn from_pipeline(id: u64, event: PipelineEvent) -> Self {
+        match event {
+            PipelineEvent::RouteDecided { route, steps } => {
+                AppEvent::RouteDecided { id, route, steps }
+            }
+            PipelineEvent::TranslationStarted => AppEvent::TranslationStarted { id },
+            PipelineEvent::Replied { text } => AppEvent::Replied { id, text },
+            PipelineEvent::EntitiesResolved { hints } => AppEvent::EntitiesResolved { id, hints },
+            PipelineEvent::AgentStepsCompleted { steps } => {
+                AppEvent::AgentStepsCompleted { id, steps }
+            }
+            PipelineEvent::TranslationCompleted {
+                cypher,
+                params,
+                reasoning,
+                usage,
+                duration_ms,
+                cached,
+            } => AppEvent::TranslationCompleted {
+                id,
+                cypher,
+                params,
+                reasoning,
+                usage,
+                duration_ms,
+                cached,
+            },
+            PipelineEvent::TranslationFailed { error } => AppEvent::TranslationFailed { id, error },
+            PipelineEvent::ValidationFailed { error, cypher } => {
+                AppEvent::ValidationFailed { id, error, cypher }
+            }
+            PipelineEvent::QueryStarted { cypher, params } => {
+                AppEvent::QueryStarted { id, cypher, params }
+            }
+            PipelineEvent::QueryCompleted {
+                cypher,
+                records,
+                duration_ms,
+            } => AppEvent::QueryCompleted {
+                id,
+                cypher,
+                records,
+                duration_ms,
+            },
+            PipelineEvent::QueryFailed {
+                error,
+                cypher,
+                duration_ms,
+            } => AppEvent::QueryFailed {
+                id,
+                error,
+                cypher,
+                duration_ms,
+            },
+            PipelineEvent::AnalysisStarted => AppEvent::AnalysisStarted { id },
+            PipelineEvent::AnalysisProgress { analysis } => {
+                AppEvent::AnalysisProgress { id, analysis }
+            }
+            PipelineEvent::AnalysisCompleted {
+                analysis,
+                duration_ms,
+            } => AppEvent::AnalysisCompleted {
+                id,
+                analysis,
+                duration_ms,
+            },
+            PipelineEvent::AnalysisFailed { error, duration_ms } => AppEvent::AnalysisFailed {
+                id,
+                error,
+                duration_ms,
+            },
+            PipelineEvent::FollowUpsSuggested { follow_ups } => {
+                AppEvent::FollowUpsSuggested { id, follow_ups }
+            }
+        }
+    }
 }
 
 pub struct GuiApp {
     runtime: Handle,
-    backend: Arc<dyn GraphBackend>,
-    translator: Arc<dyn Translator>,
-    router: Arc<dyn Router>,
-    agentic: Arc<dyn Agentic>,
-    analyst: Arc<dyn Analyst>,
-    cluster_state: SharedClusterState,
-    cluster_meta: ClusterMeta,
+    pipeline: Pipeline,
+    clusters: Vec<ClusterView>,
+    active_cluster: usize,
     token: CancellationToken,
     egui_ctx: egui::Context,
+    settings: GuiSettings,
     palette: Palette,
     feed: Vec<FeedItem>,
     next_id: u64,
     input: String,
+    /// Questions from the input box go to the agentic investigator instead of a single query.
+    investigate_mode: bool,
     search: String,
     input_rect: Option<egui::Rect>,
     suggestions: Vec<String>,
+    suggested_questions: Vec<SuggestedQuestion>,
     filtered_suggestions: Vec<String>,
+    suggestion_index: usize,
+    input_history: InputHistory,
+    command_palette: CommandPalette,
     events_tx: mpsc::Sender<AppEvent>,
     events_rx: mpsc::Receiver<AppEvent>,
+    in_flight: HashMap<u64, CancellationToken>,
+    alerts: Vec<AlertCard>,
+    /// See [`GuiArgs::alert_rules_path`].
+    alert_rules_path: Option<PathBuf>,
+    /// See [`GuiArgs::memory`].
+    memory: Option<Arc<MemoryStore>>,
     inspector: InspectorState,
-    pulse_nodes: Vec<f64>,
-    pulse_props: Vec<f64>,
-    pulse_pods: Vec<f64>,
-    pulse_services: Vec<f64>,
-    pulse_namespaces: Vec<f64>,
+    log_panel: Option<LogPanel>,
+    timeline_panel: Option<TimelinePanel>,
+    /// Result picked with Compare, waiting for the second one.
+    compare_pick: Option<u64>,
+    compare_panel: Option<ComparePanel>,
+    settings_dialog: Option<SettingsDialog>,
+    template_dialog: Option<TemplateDialog>,
+    /// Last value entered for each template placeholder, offered the next time it is asked.
+    template_values: HashMap<String, String>,
+    view: MainView,
+    dashboard: DashboardState,
+    pinned: PinnedState,
+    schema_browser: SchemaBrowser,
     last_pulse_update: Instant,
     context_cutoff_id: u64,
     context_compact_summary: Option<String>,
     context_compact_usage: Option<LlmUsage>,
     context_compact_duration_ms: Option<u128>,
     context_compact_error: Option<String>,
+    report_status: Option<String>,
     context_compacting: bool,
+    auto_compact_attempted_for: Option<u64>,
     context_window_tokens: Option<usize>,
+    llm_models: Vec<LlmConfig>,
+    active_model: usize,
+    model_input: String,
+    model_error: Option<String>,
+    /// Usage of cleared feed items and context compactions, still part of the session total.
+    usage_carry: UsageCost,
+    budget: SessionBudget,
+    /// A call held back because the budget is spent, until the user confirms or cancels it.
+    budget_held: Option<BudgetHeld>,
+    /// Set when the budget made the app switch to a cheaper model.
+    budget_notice: Option<String>,
+    agent_kube_tools: bool,
+    /// Instructions merged into the prompts for the active cluster; see [`rebuild_agents`].
+    ///
+    /// [`rebuild_agents`]: GuiApp::rebuild_agents
+    operator_instructions: OperatorInstructions,
+    /// Prompt files as of the last check; the agents are rebuilt when they change.
+    prompt_fingerprint: PromptFingerprint,
+    prompts_checked_at: Instant,
+}
+
+/// An LLM call that waits for confirmation because the session budget is spent.
+enum BudgetHeld {
+    Ask { question: String, investigate: bool },
+    FixQuery(u64),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MainView {
+    Feed,
+    Dashboard,
+    Pinned,
+    Schema,
+}
+
+/// Node labels and relationships of the graph model. The catalog is built the first time the
+/// Schema tab is opened.
+#[derive(Default)]
+struct SchemaBrowser {
+    catalog: Vec<SchemaLabel>,
+    filter: String,
+    selected: Option<String>,
+}
+
+/// Overview tile values for the active cluster. `generation` is bumped on every refresh so
+/// results from an earlier refresh or cluster are dropped.
+#[derive(Default)]
+struct DashboardState {
+    generation: u64,
+    refreshed_at: Option<Instant>,
+    tiles: Vec<TileState>,
+}
+
+enum TileState {
+    Loading,
+    Loaded(Result<usize, String>),
+}
+
+/// Latest results of the pinned queries, keyed by Cypher. Like [`DashboardState`], a cluster
+/// switch bumps `generation` so runs against the previous cluster are dropped.
+#[derive(Default)]
+struct PinnedState {
+    generation: u64,
+    runs: HashMap<String, PinnedRun>,
+}
+
+struct PinnedRun {
+    last_run: Instant,
+    refreshing: bool,
+    result: Option<Result<Vec<Value>, String>>,
+}
+
+/// Pod log viewer window. `target` is `None` while a `Logs` node's pod is being looked up;
+/// `request` drops responses for an earlier target or setting.
+struct LogPanel {
+    target: Option<LogTarget>,
+    since: LogSince,
+    follow: bool,
+    search: String,
+    content: String,
+    error: Option<String>,
+    loading: bool,
+    last_fetch: Instant,
+    request: u64,
+}
+
+/// Events and creation times of the objects in one feed item's result. `records` is kept so
+/// the timeline can be rebuilt when the events arrive.
+/// Row diff between two feed results, the older one first.
+struct ComparePanel {
+    before: String,
+    after: String,
+    comparison: RecordComparison,
+}
+
+struct TimelinePanel {
+    question: String,
+    records: Vec<Value>,
+    entries: Vec<TimelineEntry>,
+    loading: bool,
+    error: Option<String>,
+    request: u64,
+}
+
+/// Editable copy of the connection settings and theme; nothing changes until Save.
+/// Empty text fields mean "not set".
+struct SettingsDialog {
+    llm_backend: String,
+    llm_base_url: String,
+    llm_model: String,
+    llm_api_key: String,
+    answer_language: String,
+    memgraph_url: String,
+    poll_interval_secs: u64,
+    theme: ThemeChoice,
+    ui_scale: f32,
+    locale: Locale,
+    status: Option<String>,
+}
+
+/// Values for the placeholders of a saved query, asked for before it runs.
+struct TemplateDialog {
+    template: SavedQuery,
+    values: Vec<(String, String)>,
+}
+
+/// Up/Down recall of earlier questions; the text typed before recall started is kept as a draft.
+#[derive(Default)]
+struct InputHistory {
+    cursor: Option<usize>,
+    draft: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum HistoryStep {
+    Older,
+    Newer,
+}
+
+impl InputHistory {
+    fn step(&mut self, entries: &[String], current: &str, step: HistoryStep) -> Option<String> {
+        if entries.is_empty() {
+            return None;
+        }
+        match (step, self.cursor) {
+            (HistoryStep::Older, None) => {
+                self.draft = current.to_string();
+                self.cursor = Some(entries.len() - 1);
+            }
+            (HistoryStep::Older, Some(0)) => return None,
+            (HistoryStep::Older, Some(index)) => self.cursor = Some(index - 1),
+            (HistoryStep::Newer, None) => return None,
+            (HistoryStep::Newer, Some(index)) if index + 1 >= entries.len() => {
+                self.cursor = None;
+                return Some(std::mem::take(&mut self.draft));
+            }
+            (HistoryStep::Newer, Some(index)) => self.cursor = Some(index + 1),
+        }
+        self.cursor.map(|index| entries[index].clone())
+    }
+
+    fn reset(&mut self) {
+        self.cursor = None;
+        self.draft.clear();
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum PaletteAction {
+    Command(String),
+    Question(String),
+    Saved(SavedQuery),
+    Resource {
+        kind: String,
+        name: String,
+        namespace: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug)]
+struct PaletteEntry {
+    category: &'static str,
+    label: String,
+    detail: String,
+    action: PaletteAction,
+}
+
+#[derive(Default)]
+struct CommandPalette {
+    is_open: bool,
+    query: String,
+    selected: usize,
+    entries: Vec<PaletteEntry>,
+    matches: Vec<usize>,
+}
+
+impl CommandPalette {
+    fn refresh_matches(&mut self) {
+        self.matches = filter_palette_entries(&self.entries, &self.query);
+        self.selected = self.selected.min(self.matches.len().saturating_sub(1));
+    }
+
+    fn selected_action(&self) -> Option<PaletteAction> {
+        let index = *self.matches.get(self.selected)?;
+        Some(self.entries[index].action.clone())
+    }
 }
 
 #[derive(Default, Clone)]
@@ -421,8 +1278,25 @@ struct InspectorState {
     is_open: bool,
     node_type: Option<String>,
     node_id: Option<String>,
+    /// `metadata.uid` of the selected row, which keys the properties changed by the live sync.
+    uid: Option<String>,
     properties: Vec<InspectorProperty>,
     relationships: Vec<(String, String)>,
+    /// Curated layout for well-known kinds; empty means only the flat properties are shown.
+    describe: Vec<DescribeSection>,
+    events: InspectorEvents,
+    log_source: Option<LogSource>,
+    /// Bumped on every selection so late event lookups for an earlier row are dropped.
+    request: u64,
+}
+
+#[derive(Default, Clone)]
+enum InspectorEvents {
+    #[default]
+    None,
+    Loading,
+    Loaded(DescribeSection),
+    Failed(String),
 }
 
 #[derive(Clone, Debug)]
@@ -441,60 +1315,128 @@ impl GuiApp {
     #[allow(clippy::too_many_arguments)]
     fn new(
         runtime: Handle,
-        backend: Arc<dyn GraphBackend>,
+        clusters: Vec<ClusterSession>,
         translator: Arc<dyn Translator>,
         router: Arc<dyn Router>,
         agentic: Arc<dyn Agentic>,
         analyst: Arc<dyn Analyst>,
-        cluster_state: SharedClusterState,
         token: CancellationToken,
-        cluster_label: String,
-        backend_label: String,
         context_window_tokens: Option<usize>,
+        alert_rules: Vec<AlertRule>,
+        alert_rules_path: Option<PathBuf>,
+        operator_instructions: OperatorInstructions,
+        llm_models: Vec<LlmConfig>,
+        feedback: Arc<FeedbackStore>,
+        memory: Option<Arc<MemoryStore>>,
+        dry_run_candidates: bool,
+        analysis_votes: VotePolicy,
+        translation_cache: Option<Arc<TranslationCache>>,
+        agent_kube_tools: bool,
+        budget: SessionBudget,
+        settings: GuiSettings,
         egui_ctx: egui::Context,
     ) -> Self {
         let (events_tx, events_rx) = mpsc::channel();
-        let suggestions = build_suggestions();
-        let palette = Palette::default();
+        let (suggestions, suggested_questions) = {
+            let state = clusters[0].cluster_state.load();
+            (build_completions(&state), anomaly_questions(&state))
+        };
+        let palette = Palette::from_settings(&settings);
+        if !alert_rules.is_empty() {
+            for cluster in &clusters {
+                spawn_alert_loop(
+                    &runtime,
+                    cluster,
+                    alert_rules.clone(),
+                    &token,
+                    &events_tx,
+                    &egui_ctx,
+                );
+            }
+        }
         Self {
             runtime,
-            backend,
-            translator,
-            router,
-            agentic,
-            analyst,
-            cluster_state,
-            cluster_meta: ClusterMeta {
-                label: cluster_label,
-                connected: true,
-                backend_label,
+            pipeline: Pipeline {
+                backend: clusters[0].backend.clone(),
+                cluster_state: clusters[0].cluster_state.clone(),
+                entity_index: clusters[0].entity_index.clone(),
+                translator,
+                router,
+                agentic,
+                analyst,
+                feedback,
+                suggest_followups: true,
+                dry_run_candidates,
+                analysis_votes,
+                translation_cache,
+                agent_tools: kube_tools(
+                    clusters[0].kube_client.clone().filter(|_| agent_kube_tools),
+                ),
             },
+            clusters: clusters
+                .into_iter()
+                .map(|session| ClusterView {
+                    session,
+                    pulse: PulseStats::default(),
+                })
+                .collect(),
+            active_cluster: 0,
             token,
             egui_ctx,
+            settings,
             palette,
             feed: Vec::new(),
             next_id: 1,
             input: String::new(),
+            investigate_mode: false,
             search: String::new(),
             input_rect: None,
             suggestions,
+            suggested_questions,
             filtered_suggestions: Vec::new(),
+            suggestion_index: 0,
+            input_history: InputHistory::default(),
+            command_palette: CommandPalette::default(),
             events_tx,
             events_rx,
+            in_flight: HashMap::new(),
+            alerts: Vec::new(),
+            alert_rules_path,
+            memory,
             inspector: InspectorState::default(),
-            pulse_nodes: vec![],
-            pulse_props: vec![],
-            pulse_pods: vec![],
-            pulse_services: vec![],
-            pulse_namespaces: vec![],
+            log_panel: None,
+            timeline_panel: None,
+            compare_pick: None,
+            compare_panel: None,
+            settings_dialog: None,
+            template_dialog: None,
+            template_values: HashMap::new(),
+            view: MainView::Feed,
+            dashboard: DashboardState::default(),
+            pinned: PinnedState::default(),
+            schema_browser: SchemaBrowser::default(),
             last_pulse_update: Instant::now() - Duration::from_secs(10),
             context_cutoff_id: 0,
             context_compact_summary: None,
             context_compact_usage: None,
             context_compact_duration_ms: None,
             context_compact_error: None,
+            report_status: None,
             context_compacting: false,
+            auto_compact_attempted_for: None,
             context_window_tokens,
+            llm_models,
+            active_model: 0,
+            model_input: String::new(),
+            model_error: None,
+            usage_carry: UsageCost::default(),
+            budget,
+            budget_held: None,
+            budget_notice: None,
+            agent_kube_tools,
+            operator_instructions,
+            prompt_fingerprint: PromptFingerprint::current(),
+            prompts_checked_at: Instant::now(),
         }
     }
 
@@ -506,440 +1448,1546 @@ impl GuiApp {
 
         if self.handle_slash_command(&question) {
             self.input.clear();
+            self.input_history.reset();
+            return;
+        }
+
+        self.input.clear();
+        self.input_history.reset();
+        self.ask(question, self.investigate_mode);
+    }
+
+    /// Starts a feed item for `question`. With `investigate`, the agent plans and runs several
+    /// queries and the analysis concludes from all of them. Once the session budget is spent,
+    /// the question may switch the model or wait for confirmation.
+    fn ask(&mut self, question: String, investigate: bool) {
+        if self.hold_for_budget(|| BudgetHeld::Ask {
+            question: question.clone(),
+            investigate,
+        }) {
             return;
         }
+        self.start_ask(question, investigate);
+    }
 
+    fn start_ask(&mut self, question: String, investigate: bool) {
         let id = self.next_id;
         self.next_id += 1;
-        self.feed.push(FeedItem::new(id, question.clone()));
-        self.input.clear();
+        let asked = self
+            .pending_clarification()
+            .map(|(original, clarifying)| clarified_question(&original, &clarifying, &question));
+        let mut item = FeedItem::new(id, question.clone());
+        item.asked = asked.clone();
+        item.model = self.model_label(self.active_model);
+        item.price = self.active_price();
+        self.feed.push(item);
+        let question = asked.unwrap_or(question);
 
         let tx = self.events_tx.clone();
-        let translator = self.translator.clone();
-        let router = self.router.clone();
-        let agentic = self.agentic.clone();
-        let analyst = self.analyst.clone();
-        let backend = self.backend.clone();
-        let runtime = self.runtime.clone();
+        let pipeline = self.pipeline.clone();
         let analysis_context = self.build_context_with_budget();
-        let analysis_summary = self.context_compact_summary.clone();
+        let analysis_summary = self.context_summary_for(&question);
         let ctx = self.egui_ctx.clone();
 
-        runtime.spawn(async move {
-            let send_event = |event| {
-                let _ = tx.send(event);
+        let task = async move {
+            let send_event = |event: PipelineEvent| {
+                let _ = tx.send(AppEvent::from_pipeline(id, event));
                 ctx.request_repaint();
             };
-            let mut usage_acc = UsageAccumulator::default();
-
-            send_event(AppEvent::TranslationStarted { id });
-
-            let mut route = RouteDecision::OneShot;
-            let route_start = Instant::now();
-            match router.classify(&question).await {
-                Ok(route_result) => {
-                    let route_ms = route_start.elapsed().as_millis();
-                    log_llm_call("router", route_ms, route_result.usage.as_ref());
-                    usage_acc.add(route_result.usage.as_ref());
-                    route = route_result.decision;
-                    let steps = if route == RouteDecision::OneShot {
-                        Some(0)
-                    } else {
-                        None
-                    };
-                    send_event(AppEvent::RouteDecided { id, route, steps });
-                }
-                Err(err) => {
-                    tracing::warn!("Router failed, falling back to one-shot: {err}");
-                    send_event(AppEvent::RouteDecided {
-                        id,
-                        route: RouteDecision::OneShot,
-                        steps: Some(0),
-                    });
-                }
-            }
-
-            if route == RouteDecision::MultiTurn {
-                let plan_start = Instant::now();
-                match agentic
-                    .plan(
+            let _ = if investigate {
+                pipeline
+                    .investigate(
                         &question,
                         &analysis_context,
                         analysis_summary.as_deref(),
-                        backend.as_ref(),
+                        &send_event,
                     )
                     .await
-                {
-                    Ok(plan) => {
-                        let plan_ms = plan_start.elapsed().as_millis();
-                        log_llm_call("agentic", plan_ms, plan.usage.as_ref());
-                        usage_acc.add(plan.usage.as_ref());
-                        let params = merge_params(plan.params.clone(), &analysis_context);
-                        send_event(AppEvent::RouteDecided {
-                            id,
-                            route: RouteDecision::MultiTurn,
-                            steps: Some(plan.steps.len()),
-                        });
+            } else {
+                pipeline
+                    .ask(
+                        &question,
+                        &analysis_context,
+                        analysis_summary.as_deref(),
+                        &send_event,
+                    )
+                    .await
+            };
+        };
+        self.spawn_item_task(id, in_question(task));
+    }
 
-                        send_event(AppEvent::TranslationCompleted {
-                            id,
-                            cypher: plan.cypher.clone(),
-                            params: params.clone(),
-                            usage: usage_acc.build(),
-                            duration_ms: plan_ms,
-                        });
+    /// The compaction summary of this session, followed by what earlier sessions on the active
+    /// cluster found about `question`.
+    fn context_summary_for(&self, question: &str) -> Option<String> {
+        let memories = self.memory.as_ref().and_then(|memory| {
+            let cluster = &self.clusters[self.active_cluster].session.name;
+            render_memories(&memory.recall(cluster, question))
+        });
+        match (self.context_compact_summary.clone(), memories) {
+            (Some(summary), Some(memories)) => Some(format!("{summary}\n\n{memories}")),
+            (summary, memories) => summary.or(memories),
+        }
+    }
 
-                        match validate_cypher(&plan.cypher) {
-                            Ok(()) => {
-                                let cypher = plan.cypher.clone();
-                                send_event(AppEvent::QueryStarted {
-                                    id,
-                                    cypher: cypher.clone(),
-                                    params: params.clone(),
-                                });
-                                let exec_start = Instant::now();
-                                match backend.execute_query(cypher.clone(), params.clone()).await {
-                                    Ok(records) => {
-                                        let exec_ms = exec_start.elapsed().as_millis();
-                                        let summary = summarize_records(&records);
-                                        send_event(AppEvent::QueryCompleted {
-                                            id,
-                                            cypher: cypher.clone(),
-                                            records: records.clone(),
-                                            duration_ms: exec_ms,
-                                        });
-                                        send_event(AppEvent::AnalysisStarted { id });
-                                        let analysis_start = Instant::now();
-                                        match analyst
-                                            .analyze(
-                                                &question,
-                                                &cypher,
-                                                &records,
-                                                &summary,
-                                                &analysis_context,
-                                                analysis_summary.as_deref(),
-                                            )
-                                            .await
-                                        {
-                                            Ok(analysis) => {
-                                                let analysis_ms =
-                                                    analysis_start.elapsed().as_millis();
-                                                log_llm_call(
-                                                    "analysis",
-                                                    analysis_ms,
-                                                    analysis.usage.as_ref(),
-                                                );
-                                                send_event(AppEvent::AnalysisCompleted {
-                                                    id,
-                                                    analysis,
-                                                    duration_ms: analysis_ms,
-                                                });
-                                            }
-                                            Err(err) => {
-                                                let analysis_ms =
-                                                    analysis_start.elapsed().as_millis();
-                                                tracing::error!("Analysis failed: {err}");
-                                                send_event(AppEvent::AnalysisFailed {
-                                                    id,
-                                                    error: err.to_string(),
-                                                    duration_ms: analysis_ms,
-                                                });
-                                            }
-                                        }
-                                    }
-                                    Err(err) => {
-                                        let exec_ms = exec_start.elapsed().as_millis();
-                                        tracing::error!("Query failed: {err}");
-                                        send_event(AppEvent::QueryFailed {
-                                            id,
-                                            error: err.to_string(),
-                                            cypher,
-                                            duration_ms: exec_ms,
-                                        });
-                                    }
-                                }
-                                return;
+    /// Appends a finding or summary of the active cluster to the memory file.
+    fn remember(&self, kind: MemoryKind, question: &str, text: &str) {
+        let Some(memory) = &self.memory else {
+            return;
+        };
+        let cluster = &self.clusters[self.active_cluster].session.name;
+        if let Err(err) = memory.remember(cluster, kind, question, text) {
+            tracing::warn!("Failed to save memory: {err}");
+        }
+    }
+
+    /// The question and the clarifying question of the newest item when it asks the user to
+    /// clarify, so the next message is taken as the answer.
+    fn pending_clarification(&self) -> Option<(String, String)> {
+        let item = self.feed.last()?;
+        if item.route != Some(RouteDecision::Clarify) || !matches!(item.state, FeedState::Ready) {
+            return None;
+        }
+        Some((item.question().to_string(), item.reply.clone()?))
+    }
+
+    fn run_agent_step(&mut self, id: u64, step: usize) {
+        let Some(agent_step) = self
+            .feed
+            .iter()
+            .find(|item| item.id == id)
+            .and_then(|item| item.investigation.get(step).cloned())
+            .filter(|step| step.tool.is_none())
+        else {
+            return;
+        };
+        let new_id = self.next_id;
+        self.next_id += 1;
+        let mut item = FeedItem::new(new_id, format!("Investigation step {}", step + 1));
+        item.cypher = Some(agent_step.cypher.clone());
+        item.params = agent_step.params;
+        self.feed.push(item);
+        self.rerun_cypher(new_id, agent_step.cypher);
+    }
+
+    /// Asks a suggested follow-up by running its already validated query, skipping translation.
+    fn run_follow_up(&mut self, parent: u64, follow_up: FollowUp) {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut item = FeedItem::new(id, follow_up.question);
+        item.follow_up_of = Some(parent);
+        item.model = self.model_label(self.active_model);
+        item.price = self.active_price();
+        item.cypher = Some(follow_up.cypher.clone());
+        self.feed.push(item);
+        self.rerun_cypher(id, follow_up.cypher);
+    }
+
+    /// Replaces a failed query with the translator's correction and runs it in the same item.
+    fn fix_query(&mut self, id: u64) {
+        if self.hold_for_budget(|| BudgetHeld::FixQuery(id)) {
+            return;
+        }
+        self.start_fix_query(id);
+    }
+
+    fn start_fix_query(&mut self, id: u64) {
+        let Some(item) = self.feed.iter().find(|item| item.id == id) else {
+            return;
+        };
+        let (Some(cypher), FeedState::Error(error)) = (item.cypher.clone(), &item.state) else {
+            return;
+        };
+        let error = error.clone();
+        let question = item.question().to_string();
+        let tx = self.events_tx.clone();
+        let pipeline = self.pipeline.clone();
+        let analysis_context = self.build_context_with_budget();
+        let analysis_summary = self.context_summary_for(&question);
+        let ctx = self.egui_ctx.clone();
+        if let Some(item) = self.feed_item_mut(id) {
+            item.state = FeedState::Translating;
+            item.result = ResultPayload::Empty;
+        }
+
+        let task = async move {
+            let send_event = |event: PipelineEvent| {
+                let _ = tx.send(AppEvent::from_pipeline(id, event));
+                ctx.request_repaint();
+            };
+            let Ok(planned) = pipeline
+                .fix_query(
+                    &question,
+                    &cypher,
+                    &error,
+                    &analysis_context,
+                    analysis_summary.as_deref(),
+                    &send_event,
+                )
+                .await
+            else {
+                return;
+            };
+            let Ok(records) = pipeline
+                .execute(&planned.cypher, planned.params, &send_event)
+                .await
+            else {
+                return;
+            };
+            pipeline
+                .analyze(
+                    &question,
+                    &planned.cypher,
+                    &records,
+                    &analysis_context,
+                    analysis_summary.as_deref(),
+                    &send_event,
+                )
+                .await;
+        };
+        self.spawn_item_task(id, in_question(task));
+    }
+
+    fn rerun_cypher(&mut self, id: u64, cypher: String) {
+        let tx = self.events_tx.clone();
+        let pipeline = self.pipeline.clone();
+        let ctx = self.egui_ctx.clone();
+        let question = self
+            .feed
+            .iter()
+            .find(|item| item.id == id)
+            .map(|item| item.question().to_string())
+            .unwrap_or_default();
+        let params = self
+            .feed
+            .iter()
+            .find(|item| item.id == id)
+            .and_then(|item| item.params.clone());
+        let analysis_context = self.build_context_with_budget();
+        let analysis_summary = self.context_compact_summary.clone();
+        if let Some(item) = self.feed_item_mut(id) {
+            item.state = FeedState::Validating;
+        }
+
+        let task = async move {
+            let send_event = |event: PipelineEvent| {
+                let _ = tx.send(AppEvent::from_pipeline(id, event));
+                ctx.request_repaint();
+            };
+            let _ = pipeline
+                .run_cypher(
+                    &question,
+                    &cypher,
+                    params,
+                    &analysis_context,
+                    analysis_summary.as_deref(),
+                    &send_event,
+                )
+                .await;
+        };
+        self.spawn_item_task(id, in_question(task));
+    }
+
+    fn handle_slash_command(&mut self, input: &str) -> bool {
+        if input.starts_with("/history") {
+            let id = self.next_id;
+            self.next_id += 1;
+            let mut item = FeedItem::new(id, input.to_string());
+            item.state = FeedState::Ready;
+            item.result = ResultPayload::Raw {
+                text: "History is not implemented yet.".to_string(),
+            };
+            self.feed.push(item);
+            return true;
+        }
+        if input.starts_with("/settings") {
+            self.open_settings();
+            return true;
+        }
+        if let Some(question) = input.strip_prefix("/investigate") {
+            let question = question.trim();
+            if question.is_empty() {
+                self.investigate_mode = !self.investigate_mode;
+            } else {
+                self.ask(question.to_string(), true);
+            }
+            return true;
+        }
+        if input.starts_with("/explain") {
+            self.explain_last_query(input);
+            return true;
+        }
+        if input.starts_with("/profile") {
+            self.toggle_query_stats(input);
+            return true;
+        }
+        if let Some(name) = input.strip_prefix("/save") {
+            self.save_last_question(input, name.trim());
+            return true;
+        }
+        if let Some(rest) = input.strip_prefix("/template") {
+            self.save_template(input, rest.trim());
+            return true;
+        }
+        if input == "/monitors" {
+            self.propose_monitors(input);
+            return true;
+        }
+        if let Some(format) = input.strip_prefix("/report") {
+            let format = match format.trim() {
+                "" => {
+                    self.summarize_session(input);
+                    return true;
+                }
+                "html" => ReportFormat::Html,
+                _ => ReportFormat::Markdown,
+            };
+            let id = self.next_id;
+            self.next_id += 1;
+            let mut item = FeedItem::new(id, input.to_string());
+            item.state = FeedState::Ready;
+            let text = self
+                .export_report(format)
+                .unwrap_or_else(|| "Report export cancelled.".to_string());
+            item.result = ResultPayload::Raw { text };
+            self.feed.push(item);
+            return true;
+        }
+        false
+    }
+
+    fn save_last_question(&mut self, input: &str, name: &str) {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut item = FeedItem::new(id, input.to_string());
+        item.state = FeedState::Ready;
+        let last_question = self
+            .feed
+            .iter()
+            .rev()
+            .find(|item| !item.user_text.starts_with('/'))
+            .map(|item| item.user_text.clone());
+        let text = match last_question {
+            Some(question) => {
+                let name = if name.is_empty() {
+                    truncate_text(&question, 48)
+                } else {
+                    name.to_string()
+                };
+                self.settings
+                    .saved_queries
+                    .retain(|saved| saved.name != name);
+                self.settings.saved_queries.push(SavedQuery {
+                    name: name.clone(),
+                    question,
+                    cypher: None,
+                });
+                match self.settings.save() {
+                    Ok(()) => format!("Saved as \"{name}\"."),
+                    Err(err) => format!("Saved as \"{name}\" for this session ({err})."),
+                }
+            }
+            None => "Nothing to save yet. Ask a question first.".to_string(),
+        };
+        item.result = ResultPayload::Raw { text };
+        self.feed.push(item);
+    }
+
+    /// `/template <name> <text>`: text that parses as Cypher once its placeholders are
+    /// parameters is saved as a Cypher template, anything else as a question template.
+    fn save_template(&mut self, input: &str, args: &str) {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut item = FeedItem::new(id, input.to_string());
+        item.state = FeedState::Ready;
+        let text = match args.split_once(char::is_whitespace) {
+            Some((name, text)) if !text.trim().is_empty() => {
+                let text = text.trim();
+                let is_cypher = ariadne_cypher::parse_query(&parameterize_template(text)).is_ok();
+                let template = if is_cypher {
+                    SavedQuery {
+                        name: name.to_string(),
+                        question: name.to_string(),
+                        cypher: Some(text.to_string()),
+                    }
+                } else {
+                    SavedQuery {
+                        name: name.to_string(),
+                        question: text.to_string(),
+                        cypher: None,
+                    }
+                };
+                let fields = template_placeholders(template.cypher.as_deref().unwrap_or(text));
+                self.settings
+                    .saved_queries
+                    .retain(|saved| saved.name != template.name);
+                self.settings.saved_queries.push(template);
+                let kind = if is_cypher { "Cypher" } else { "question" };
+                let fields = if fields.is_empty() {
+                    "no placeholders".to_string()
+                } else {
+                    format!("placeholders: {}", fields.join(", "))
+                };
+                match self.settings.save() {
+                    Ok(()) => format!("Saved {kind} template \"{name}\" ({fields})."),
+                    Err(err) => format!(
+                        "Saved {kind} template \"{name}\" ({fields}) for this session ({err})."
+                    ),
+                }
+            }
+            _ => "Usage: /template <name> <question or Cypher with {{placeholders}}>".to_string(),
+        };
+        item.result = ResultPayload::Raw { text };
+        self.feed.push(item);
+    }
+
+    /// Runs a saved query right away, or asks for its placeholders first.
+    fn run_saved_query(&mut self, saved: SavedQuery) {
+        let fields = template_placeholders(saved.cypher.as_deref().unwrap_or(&saved.question));
+        if fields.is_empty() {
+            self.run_template(&saved, &BTreeMap::new());
+            return;
+        }
+        let values = fields
+            .into_iter()
+            .map(|name| {
+                let value = self.template_values.get(&name).cloned().unwrap_or_default();
+                (name, value)
+            })
+            .collect();
+        self.template_dialog = Some(TemplateDialog {
+            template: saved,
+            values,
+        });
+    }
+
+    /// Fills a saved query: question templates are translated as usual, Cypher templates run
+    /// directly with the values as query parameters.
+    fn run_template(&mut self, template: &SavedQuery, values: &BTreeMap<String, String>) {
+        self.template_values.extend(
+            values
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone())),
+        );
+        let Some(cypher) = &template.cypher else {
+            self.input = fill_template(&template.question, values);
+            self.submit_question();
+            return;
+        };
+        let title = if values.is_empty() {
+            template.question.clone()
+        } else {
+            let values: Vec<String> = values
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect();
+            format!("{} ({})", template.question, values.join(", "))
+        };
+        let cypher = parameterize_template(cypher);
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut item = FeedItem::new(id, title);
+        item.cypher = Some(cypher.clone());
+        item.params = (!values.is_empty()).then(|| template_params(values));
+        self.feed.push(item);
+        self.view = MainView::Feed;
+        self.rerun_cypher(id, cypher);
+    }
+
+    fn render_template_dialog(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = self.template_dialog.as_mut() else {
+            return;
+        };
+        let palette = self.palette.clone();
+        let mut open = true;
+        let mut run = false;
+        let mut cancel = false;
+        egui::Window::new(format!("Run \"{}\"", dialog.template.name))
+            .id(egui::Id::new("template-dialog"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                let text = dialog
+                    .template
+                    .cypher
+                    .as_deref()
+                    .unwrap_or(&dialog.template.question);
+                ui.label(
+                    RichText::new(text)
+                        .color(palette.text_muted)
+                        .monospace()
+                        .size(11.0),
+                );
+                ui.add_space(6.0);
+                egui::Grid::new("template-grid")
+                    .num_columns(2)
+                    .spacing([12.0, 8.0])
+                    .show(ui, |ui| {
+                        for (index, (name, value)) in dialog.values.iter_mut().enumerate() {
+                            ui.label(name.as_str());
+                            let response = ui.add(TextEdit::singleline(value).desired_width(260.0));
+                            if index == 0 && !ui.memory(|mem| mem.focused().is_some()) {
+                                response.request_focus();
                             }
-                            Err(issue) => {
-                                tracing::error!("Validation failed: {issue}");
-                                send_event(AppEvent::ValidationFailed {
-                                    id,
-                                    error: issue.to_string(),
-                                    cypher: plan.cypher,
-                                });
-                                return;
+                            if response.lost_focus()
+                                && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                            {
+                                run = true;
                             }
+                            ui.end_row();
                         }
+                    });
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    run |= ui.button(tr("Run")).clicked();
+                    cancel = ui.button(tr("Cancel")).clicked();
+                });
+            });
+        if !open || cancel {
+            self.template_dialog = None;
+        } else if run {
+            if let Some(dialog) = self.template_dialog.take() {
+                let values = dialog
+                    .values
+                    .into_iter()
+                    .map(|(name, value)| (name, value.trim().to_string()))
+                    .collect();
+                self.run_template(&dialog.template, &values);
+            }
+        }
+    }
+
+    fn explain_last_query(&mut self, input: &str) {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut item = FeedItem::new(id, input.to_string());
+        let Some(last) = self
+            .feed
+            .iter()
+            .rev()
+            .find(|item| !item.user_text.starts_with('/') && item.cypher.is_some())
+        else {
+            item.state = FeedState::Ready;
+            item.result = ResultPayload::Raw {
+                text: "Nothing to explain yet. Ask a question first.".to_string(),
+            };
+            self.feed.push(item);
+            return;
+        };
+        let cypher = last.cypher.clone().unwrap_or_default();
+        let params = last.params.clone();
+        item.reasoning = last.reasoning.clone();
+        item.cypher = Some(cypher.clone());
+        item.params = params.clone();
+        item.state = FeedState::Running;
+        self.feed.push(item);
+
+        let tx = self.events_tx.clone();
+        let backend = self.pipeline.backend.clone();
+        let ctx = self.egui_ctx.clone();
+        self.spawn_item_task(id, async move {
+            let send_event = |event| {
+                let _ = tx.send(event);
+                ctx.request_repaint();
+            };
+            let exec_start = Instant::now();
+            match backend.profile_query(cypher.clone(), params).await {
+                Ok(records) => {
+                    send_event(AppEvent::QueryCompleted {
+                        id,
+                        cypher,
+                        records,
+                        duration_ms: exec_start.elapsed().as_millis(),
+                    });
+                }
+                Err(err) => {
+                    tracing::error!("Explain failed: {err}");
+                    send_event(AppEvent::QueryFailed {
+                        id,
+                        error: err.to_string(),
+                        cypher,
+                        duration_ms: exec_start.elapsed().as_millis(),
+                    });
+                }
+            }
+        });
+    }
+
+    /// `/report`: asks the analyst for an incident report of every question in the feed.
+    fn summarize_session(&mut self, input: &str) {
+        let turns: Vec<ReportEntry> = self
+            .feed
+            .iter()
+            .filter(|item| !item.user_text.starts_with('/'))
+            .map(report_entry)
+            .collect();
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut item = FeedItem::new(id, input.to_string());
+        if turns.is_empty() {
+            item.state = FeedState::Ready;
+            item.result = ResultPayload::Raw {
+                text: "Nothing to report yet. Ask a question first.".to_string(),
+            };
+            self.feed.push(item);
+            return;
+        }
+        item.state = FeedState::Running;
+        item.model = self.model_label(self.active_model);
+        item.price = self.active_price();
+        self.feed.push(item);
+
+        let tx = self.events_tx.clone();
+        let analyst = self.pipeline.analyst.clone();
+        let ctx = self.egui_ctx.clone();
+        self.spawn_item_task(id, async move {
+            let start = Instant::now();
+            let event = match analyst.summarize_session(&turns).await {
+                Ok(report) => AppEvent::SessionSummarized {
+                    id,
+                    report,
+                    duration_ms: start.elapsed().as_millis(),
+                },
+                Err(err) => {
+                    tracing::error!("Session report failed: {err}");
+                    AppEvent::SessionSummaryFailed {
+                        id,
+                        error: err.to_string(),
                     }
-                    Err(err) => {
-                        tracing::warn!("Agentic planning failed, falling back to one-shot: {err}");
-                        send_event(AppEvent::RouteDecided {
-                            id,
-                            route: RouteDecision::OneShot,
-                            steps: Some(0),
-                        });
+                }
+            };
+            let _ = tx.send(event);
+            ctx.request_repaint();
+        });
+    }
+
+    /// `/monitors`: asks the analyst for alert rules derived from the questions in the feed.
+    fn propose_monitors(&mut self, input: &str) {
+        let turns: Vec<ReportEntry> = self
+            .feed
+            .iter()
+            .filter(|item| !item.user_text.starts_with('/'))
+            .map(report_entry)
+            .collect();
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut item = FeedItem::new(id, input.to_string());
+        if turns.is_empty() {
+            item.state = FeedState::Ready;
+            item.result = ResultPayload::Raw {
+                text: "Nothing to monitor yet. Ask a question first.".to_string(),
+            };
+            self.feed.push(item);
+            return;
+        }
+        item.state = FeedState::Running;
+        item.model = self.model_label(self.active_model);
+        item.price = self.active_price();
+        self.feed.push(item);
+
+        let tx = self.events_tx.clone();
+        let analyst = self.pipeline.analyst.clone();
+        let ctx = self.egui_ctx.clone();
+        self.spawn_item_task(id, async move {
+            let start = Instant::now();
+            let event = match analyst.propose_monitors(&turns).await {
+                Ok(proposals) => AppEvent::MonitorsProposed {
+                    id,
+                    proposals,
+                    duration_ms: start.elapsed().as_millis(),
+                },
+                Err(err) => {
+                    tracing::error!("Monitor proposals failed: {err}");
+                    AppEvent::MonitorsFailed {
+                        id,
+                        error: err.to_string(),
                     }
                 }
-            }
+            };
+            let _ = tx.send(event);
+            ctx.request_repaint();
+        });
+    }
+
+    /// Adds a proposed monitor to the alert rules file and starts evaluating it on every
+    /// cluster.
+    fn accept_monitor(&mut self, id: u64, index: usize) {
+        let Some(monitor) = self
+            .feed_item_mut(id)
+            .and_then(|item| item.monitors.get(index))
+            .map(|card| card.monitor.clone())
+        else {
+            return;
+        };
+        let status = match self.add_alert_rule(monitor) {
+            Ok(path) => format!("Added to {}", path.display()),
+            Err(err) => format!("Could not add the rule: {err}"),
+        };
+        if let Some(card) = self
+            .feed_item_mut(id)
+            .and_then(|item| item.monitors.get_mut(index))
+        {
+            card.status = Some(status);
+        }
+    }
 
-            let mut attempt = 0usize;
-            let mut feedback: Option<String> = None;
+    fn add_alert_rule(&mut self, monitor: ProposedMonitor) -> CliResult<PathBuf> {
+        let path = self
+            .alert_rules_path
+            .clone()
+            .ok_or("no configuration directory for the alert rules file")?;
+        let rule = AlertRule {
+            condition: AlertCondition::parse(&monitor.condition)?,
+            name: monitor.name,
+            query: monitor.query,
+        };
+        append_alert_rule(&path, &rule)?;
+        for cluster in &self.clusters {
+            spawn_alert_loop(
+                &self.runtime,
+                &cluster.session,
+                vec![rule.clone()],
+                &self.token,
+                &self.events_tx,
+                &self.egui_ctx,
+            );
+        }
+        Ok(path)
+    }
 
-            loop {
-                attempt += 1;
-                send_event(AppEvent::TranslationStarted { id });
-                let llm_start = Instant::now();
-                let result = translator
-                    .translate(
-                        &question,
-                        &analysis_context,
-                        analysis_summary.as_deref(),
-                        feedback.as_deref(),
-                    )
-                    .await;
-                let llm_ms = llm_start.elapsed().as_millis();
+    fn toggle_query_stats(&mut self, input: &str) {
+        self.settings.show_query_stats = !self.settings.show_query_stats;
+        let mut text = if self.settings.show_query_stats {
+            "Query statistics on: each new result is profiled and shows its scans, index hits \
+             and per-clause timings."
+                .to_string()
+        } else {
+            "Query statistics off.".to_string()
+        };
+        if let Err(err) = self.settings.save() {
+            text.push_str(&format!(" Not saved: {err}"));
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut item = FeedItem::new(id, input.to_string());
+        item.state = FeedState::Ready;
+        item.result = ResultPayload::Raw { text };
+        self.feed.push(item);
+    }
+
+    /// Profiles an item's query in the background. This runs the query a second time, so it is
+    /// only done while query statistics are switched on.
+    fn load_query_stats(&mut self, id: u64) {
+        let Some(item) = self.feed.iter().find(|item| item.id == id) else {
+            return;
+        };
+        // `/explain` results already are the profile.
+        if item.user_text.starts_with("/explain") {
+            return;
+        }
+        let Some(cypher) = item.cypher.clone() else {
+            return;
+        };
+        let params = item.params.clone();
+        let backend = self.pipeline.backend.clone();
+        let tx = self.events_tx.clone();
+        let ctx = self.egui_ctx.clone();
+        self.runtime.spawn(async move {
+            let result = backend
+                .profile_query(cypher, params)
+                .await
+                .map_err(|err| err.to_string());
+            let _ = tx.send(AppEvent::QueryStatsLoaded { id, result });
+            ctx.request_repaint();
+        });
+    }
 
-                let result = match result {
-                    Ok(result) => result,
+    /// Spawns the work behind a feed item so it can be aborted with `cancel_item`. Rerunning an
+    /// item cancels whatever was still in flight for it.
+    fn spawn_item_task<F>(&mut self, id: u64, task: F)
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let token = self.token.child_token();
+        if let Some(previous) = self.in_flight.insert(id, token.clone()) {
+            previous.cancel();
+        }
+        let tx = self.events_tx.clone();
+        let ctx = self.egui_ctx.clone();
+        self.runtime.spawn(async move {
+            tokio::select! {
+                _ = token.cancelled() => {}
+                _ = task => {}
+            }
+            let _ = tx.send(AppEvent::TaskFinished { id });
+            ctx.request_repaint();
+        });
+    }
+
+    fn toggle_watch(&mut self, id: u64) {
+        let Some(item) = self.feed_item_mut(id) else {
+            return;
+        };
+        if item.watch.take().is_some() {
+            return;
+        }
+        item.watch = Some(WatchState {
+            last_run: Instant::now(),
+            refreshing: false,
+            refreshes: 0,
+            records: result_records(&item.result).unwrap_or_default(),
+            added: HashSet::new(),
+            removed: Vec::new(),
+            error: None,
+        });
+    }
+
+    /// Re-runs watched queries whose interval has elapsed. Refreshes skip analysis and never
+    /// overlap for the same item.
+    fn refresh_watches(&mut self) {
+        let mut due = Vec::new();
+        for item in &mut self.feed {
+            let Some(watch) = item.watch.as_mut() else {
+                continue;
+            };
+            if watch.refreshing || watch.last_run.elapsed() < WATCH_INTERVAL {
+                continue;
+            }
+            let Some(cypher) = item.cypher.clone() else {
+                continue;
+            };
+            watch.refreshing = true;
+            watch.last_run = Instant::now();
+            due.push((item.id, cypher, item.params.clone()));
+        }
+        for (id, cypher, params) in due {
+            let tx = self.events_tx.clone();
+            let backend = self.pipeline.backend.clone();
+            let ctx = self.egui_ctx.clone();
+            self.runtime.spawn(async move {
+                let exec_start = Instant::now();
+                let event = match backend.execute_query(cypher, params).await {
+                    Ok(records) => AppEvent::WatchRefreshed {
+                        id,
+                        records,
+                        duration_ms: exec_start.elapsed().as_millis(),
+                    },
                     Err(err) => {
-                        tracing::error!("Translation failed: {err}");
-                        send_event(AppEvent::TranslationFailed {
+                        tracing::warn!("Watch refresh failed: {err}");
+                        AppEvent::WatchFailed {
                             id,
                             error: err.to_string(),
-                        });
-                        return;
+                        }
                     }
                 };
-                log_llm_call("translator", llm_ms, result.usage.as_ref());
-                usage_acc.add(result.usage.as_ref());
+                let _ = tx.send(event);
+                ctx.request_repaint();
+            });
+        }
+        if self.feed.iter().any(|item| item.watch.is_some()) {
+            self.egui_ctx.request_repaint_after(WATCH_INTERVAL);
+        }
+    }
 
-                let params = merge_params(result.params.clone(), &analysis_context);
+    fn is_pinned(&self, cypher: &str) -> bool {
+        self.settings
+            .pinned_queries
+            .iter()
+            .any(|pinned| pinned.cypher == cypher)
+    }
 
-                send_event(AppEvent::TranslationCompleted {
-                    id,
-                    cypher: result.cypher.clone(),
-                    params: params.clone(),
-                    usage: usage_acc.build(),
-                    duration_ms: llm_ms,
+    fn toggle_pin(&mut self, id: u64) {
+        let Some(item) = self.feed.iter().find(|item| item.id == id) else {
+            return;
+        };
+        let Some(cypher) = item.cypher.clone() else {
+            return;
+        };
+        if self.is_pinned(&cypher) {
+            self.settings
+                .pinned_queries
+                .retain(|pinned| pinned.cypher != cypher);
+            self.pinned.runs.remove(&cypher);
+        } else {
+            self.settings.pinned_queries.push(PinnedQuery {
+                title: item.user_text.clone(),
+                cypher,
+                params: item.params.clone(),
+                interval_secs: None,
+            });
+        }
+        if let Err(err) = self.settings.save() {
+            tracing::warn!("Failed to save pinned queries: {err}");
+        }
+    }
+
+    fn handle_pin_action(&mut self, action: PinAction) {
+        match action {
+            PinAction::Open(index) => {
+                let Some(pinned) = self.settings.pinned_queries.get(index).cloned() else {
+                    return;
+                };
+                let id = self.next_id;
+                self.next_id += 1;
+                let mut item = FeedItem::new(id, pinned.title);
+                item.cypher = Some(pinned.cypher.clone());
+                item.params = pinned.params;
+                self.feed.push(item);
+                self.view = MainView::Feed;
+                self.rerun_cypher(id, pinned.cypher);
+            }
+            PinAction::Unpin(index) => {
+                if index >= self.settings.pinned_queries.len() {
+                    return;
+                }
+                let pinned = self.settings.pinned_queries.remove(index);
+                self.pinned.runs.remove(&pinned.cypher);
+                if let Err(err) = self.settings.save() {
+                    tracing::warn!("Failed to save pinned queries: {err}");
+                }
+            }
+        }
+    }
+
+    /// Re-runs pinned queries whose interval has elapsed, whichever tab is shown, so the
+    /// Dashboard is current when opened. Runs of the same query never overlap.
+    fn refresh_pinned(&mut self) {
+        let generation = self.pinned.generation;
+        let mut next_due = None::<Duration>;
+        for pinned in &self.settings.pinned_queries {
+            let interval = pinned.interval();
+            if let Some(run) = self.pinned.runs.get(&pinned.cypher) {
+                let elapsed = run.last_run.elapsed();
+                if run.refreshing || elapsed < interval {
+                    let wait = interval.saturating_sub(elapsed);
+                    next_due = Some(next_due.map_or(wait, |due| due.min(wait)));
+                    continue;
+                }
+            }
+            let previous = self
+                .pinned
+                .runs
+                .remove(&pinned.cypher)
+                .and_then(|run| run.result);
+            self.pinned.runs.insert(
+                pinned.cypher.clone(),
+                PinnedRun {
+                    last_run: Instant::now(),
+                    refreshing: true,
+                    result: previous,
+                },
+            );
+            next_due = Some(next_due.map_or(interval, |due| due.min(interval)));
+
+            let cypher = pinned.cypher.clone();
+            let params = pinned.params.clone();
+            let backend = self.pipeline.backend.clone();
+            let tx = self.events_tx.clone();
+            let ctx = self.egui_ctx.clone();
+            self.runtime.spawn(async move {
+                let result = backend
+                    .execute_query(cypher.clone(), params)
+                    .await
+                    .map_err(|err| err.to_string());
+                let _ = tx.send(AppEvent::PinnedLoaded {
+                    generation,
+                    cypher,
+                    result,
                 });
+                ctx.request_repaint();
+            });
+        }
+        if let Some(due) = next_due {
+            self.egui_ctx.request_repaint_after(due);
+        }
+    }
 
-                match validate_cypher(&result.cypher) {
-                    Ok(()) => {
-                        let cypher = result.cypher.clone();
-                        send_event(AppEvent::QueryStarted {
-                            id,
-                            cypher: cypher.clone(),
-                            params: params.clone(),
-                        });
-                        let exec_start = Instant::now();
-                        match backend.execute_query(cypher.clone(), params.clone()).await {
-                            Ok(records) => {
-                                let exec_ms = exec_start.elapsed().as_millis();
-                                let summary = summarize_records(&records);
-                                send_event(AppEvent::QueryCompleted {
-                                    id,
-                                    cypher: cypher.clone(),
-                                    records: records.clone(),
-                                    duration_ms: exec_ms,
-                                });
-                                send_event(AppEvent::AnalysisStarted { id });
-                                let analysis_start = Instant::now();
-                                match analyst
-                                    .analyze(
-                                        &question,
-                                        &cypher,
-                                        &records,
-                                        &summary,
-                                        &analysis_context,
-                                        analysis_summary.as_deref(),
-                                    )
-                                    .await
-                                {
-                                    Ok(analysis) => {
-                                        let analysis_ms = analysis_start.elapsed().as_millis();
-                                        log_llm_call(
-                                            "analysis",
-                                            analysis_ms,
-                                            analysis.usage.as_ref(),
-                                        );
-                                        send_event(AppEvent::AnalysisCompleted {
-                                            id,
-                                            analysis,
-                                            duration_ms: analysis_ms,
-                                        });
-                                    }
-                                    Err(err) => {
-                                        let analysis_ms = analysis_start.elapsed().as_millis();
-                                        tracing::error!("Analysis failed: {err}");
-                                        send_event(AppEvent::AnalysisFailed {
-                                            id,
-                                            error: err.to_string(),
-                                            duration_ms: analysis_ms,
-                                        });
+    fn handle_alert_action(&mut self, action: AlertAction) {
+        match action {
+            AlertAction::Dismiss(id) => self.alerts.retain(|card| card.id != id),
+            AlertAction::ShowRows(id) => {
+                let Some(card) = self.alerts.iter().find(|card| card.id == id) else {
+                    return;
+                };
+                let alert = &card.alert;
+                let mut item = FeedItem::new(
+                    self.next_id,
+                    format!("Alert: {} ({})", alert.rule, alert.cluster),
+                );
+                self.next_id += 1;
+                item.cypher = Some(alert.cypher.clone());
+                item.result = classify_result(&alert.records);
+                item.context_summary = Some(summarize_records(&alert.records));
+                item.context_bindings = extract_context_bindings(&alert.records);
+                item.state = FeedState::Ready;
+                self.feed.push(item);
+            }
+        }
+    }
+
+    /// Shows the overview, re-running its queries when they are older than `WATCH_INTERVAL`.
+    fn show_dashboard(&mut self) {
+        self.view = MainView::Dashboard;
+        let fresh = self
+            .dashboard
+            .refreshed_at
+            .is_some_and(|at| at.elapsed() < WATCH_INTERVAL);
+        if !fresh {
+            self.refresh_dashboard();
+        }
+    }
+
+    fn refresh_dashboard(&mut self) {
+        self.dashboard.generation += 1;
+        self.dashboard.refreshed_at = Some(Instant::now());
+        self.dashboard.tiles = DASHBOARD_TILES.iter().map(|_| TileState::Loading).collect();
+        let generation = self.dashboard.generation;
+        for (index, tile) in DASHBOARD_TILES.iter().enumerate() {
+            let backend = self.pipeline.backend.clone();
+            let tx = self.events_tx.clone();
+            let ctx = self.egui_ctx.clone();
+            self.runtime.spawn(async move {
+                let result = backend
+                    .execute_query(tile.cypher.to_string(), None)
+                    .await
+                    .map(|records| records.len())
+                    .map_err(|err| err.to_string());
+                let _ = tx.send(AppEvent::DashboardTileLoaded {
+                    generation,
+                    index,
+                    result,
+                });
+                ctx.request_repaint();
+            });
+        }
+    }
+
+    /// Runs a tile's query as a new feed item and switches back to the feed.
+    fn drill_into_tile(&mut self, tile: &DashboardTile) {
+        self.run_canned_query(format!("Overview: {}", tile.title), tile.cypher.to_string());
+    }
+
+    fn run_canned_query(&mut self, title: String, cypher: String) {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut item = FeedItem::new(id, title);
+        item.cypher = Some(cypher.clone());
+        self.feed.push(item);
+        self.view = MainView::Feed;
+        self.rerun_cypher(id, cypher);
+    }
+
+    fn show_schema(&mut self) {
+        self.view = MainView::Schema;
+        if self.schema_browser.catalog.is_empty() {
+            self.schema_browser.catalog = schema_catalog();
+        }
+    }
+
+    fn open_logs(&mut self, source: LogSource) {
+        let request = self.next_id;
+        self.next_id += 1;
+        let target = match &source {
+            LogSource::Target(target) => Some(target.clone()),
+            LogSource::LogsNode { .. } => None,
+        };
+        let resolving = target.is_none();
+        self.log_panel = Some(LogPanel {
+            target,
+            since: LogSince::default(),
+            follow: false,
+            search: String::new(),
+            content: String::new(),
+            error: None,
+            loading: resolving,
+            last_fetch: Instant::now(),
+            request,
+        });
+        if !resolving {
+            self.fetch_logs();
+            return;
+        }
+
+        let backend = self.pipeline.backend.clone();
+        let tx = self.events_tx.clone();
+        let ctx = self.egui_ctx.clone();
+        self.runtime.spawn(async move {
+            let result = source.resolve(backend).await.map_err(|err| err.to_string());
+            let _ = tx.send(AppEvent::LogTargetResolved { request, result });
+            ctx.request_repaint();
+        });
+    }
+
+    fn fetch_logs(&mut self) {
+        let request = self.next_id;
+        self.next_id += 1;
+        let client = self.clusters[self.active_cluster]
+            .session
+            .kube_client
+            .clone();
+        let Some(panel) = self.log_panel.as_mut() else {
+            return;
+        };
+        let Some(target) = panel.target.clone() else {
+            return;
+        };
+        panel.request = request;
+        panel.last_fetch = Instant::now();
+        let Some(client) = client else {
+            panel.loading = false;
+            panel.error =
+                Some("Logs need a live cluster connection; snapshots have none".to_string());
+            return;
+        };
+        panel.loading = true;
+
+        let since = panel.since;
+        let tx = self.events_tx.clone();
+        let ctx = self.egui_ctx.clone();
+        self.runtime.spawn(async move {
+            let result = fetch_logs(client, &target, since)
+                .await
+                .map_err(|err| err.to_string());
+            let _ = tx.send(AppEvent::LogsFetched { request, result });
+            ctx.request_repaint();
+        });
+    }
+
+    fn refresh_log_panel(&mut self) {
+        let due = self.log_panel.as_ref().is_some_and(|panel| {
+            panel.follow
+                && !panel.loading
+                && panel.error.is_none()
+                && panel.last_fetch.elapsed() >= FOLLOW_INTERVAL
+        });
+        if due {
+            self.fetch_logs();
+        }
+        if self.log_panel.as_ref().is_some_and(|panel| panel.follow) {
+            self.egui_ctx.request_repaint_after(FOLLOW_INTERVAL);
+        }
+    }
+
+    fn render_log_panel(&mut self, ctx: &egui::Context) {
+        let Some(panel) = self.log_panel.as_mut() else {
+            return;
+        };
+        let palette = self.palette.clone();
+        let title = match &panel.target {
+            Some(target) => format!("Logs · {}/{}", target.namespace, target.pod),
+            None => "Logs".to_string(),
+        };
+        let mut open = true;
+        let mut refetch = false;
+        egui::Window::new(title)
+            .id(egui::Id::new("log-panel"))
+            .open(&mut open)
+            .default_size([760.0, 420.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if let Some(target) = panel.target.as_mut() {
+                        if target.containers.len() > 1 {
+                            egui::ComboBox::from_id_salt("log-container")
+                                .selected_text(target.container.clone().unwrap_or_default())
+                                .show_ui(ui, |ui| {
+                                    for container in target.containers.clone() {
+                                        let selected =
+                                            target.container.as_deref() == Some(container.as_str());
+                                        if ui.selectable_label(selected, &container).clicked()
+                                            && !selected
+                                        {
+                                            target.container = Some(container);
+                                            refetch = true;
+                                        }
                                     }
-                                }
-                            }
-                            Err(err) => {
-                                let exec_ms = exec_start.elapsed().as_millis();
-                                tracing::error!("Query failed: {err}");
-                                send_event(AppEvent::QueryFailed {
-                                    id,
-                                    error: err.to_string(),
-                                    cypher,
-                                    duration_ms: exec_ms,
                                 });
-                            }
                         }
-                        return;
                     }
-                    Err(issue) => {
-                        tracing::error!("Validation failed: {issue}");
-                        if attempt <= LLM_MAX_RETRIES && issue.retriable() {
-                            feedback = Some(issue.feedback());
-                            continue;
-                        }
-                        send_event(AppEvent::ValidationFailed {
-                            id,
-                            error: issue.to_string(),
-                            cypher: result.cypher,
+                    egui::ComboBox::from_id_salt("log-since")
+                        .selected_text(panel.since.label())
+                        .show_ui(ui, |ui| {
+                            for since in LogSince::ALL {
+                                if ui
+                                    .selectable_value(&mut panel.since, since, since.label())
+                                    .changed()
+                                {
+                                    refetch = true;
+                                }
+                            }
                         });
-                        return;
+                    if ui.checkbox(&mut panel.follow, "Follow").changed() && panel.follow {
+                        panel.error = None;
                     }
+                    ui.add(
+                        TextEdit::singleline(&mut panel.search)
+                            .hint_text("Search")
+                            .desired_width(180.0),
+                    );
+                    if ui
+                        .add_enabled(
+                            !panel.loading && panel.target.is_some(),
+                            egui::Button::new("Refresh"),
+                        )
+                        .clicked()
+                    {
+                        refetch = true;
+                    }
+                    if panel.loading {
+                        ui.spinner();
+                    }
+                });
+                if let Some(error) = &panel.error {
+                    ui.label(RichText::new(error).color(palette.danger));
                 }
-            }
-        });
+                let lines = matching_lines(&panel.content, &panel.search);
+                if !panel.search.trim().is_empty() {
+                    ui.label(
+                        RichText::new(format!("{} matching lines", lines.len()))
+                            .color(palette.text_muted)
+                            .size(11.0),
+                    );
+                }
+                ui.separator();
+                let row_height = ui.text_style_height(&TextStyle::Monospace);
+                ScrollArea::both()
+                    .auto_shrink([false; 2])
+                    .stick_to_bottom(panel.follow)
+                    .show_rows(ui, row_height, lines.len(), |ui, range| {
+                        for line in &lines[range] {
+                            ui.label(RichText::new(*line).monospace().color(palette.text_primary));
+                        }
+                    });
+            });
+        if !open {
+            self.log_panel = None;
+        } else if refetch {
+            self.fetch_logs();
+        }
+    }
+
+    /// Stores a thumbs up/down for an item's translation or analysis. Approved translations
+    /// become few-shot examples for similar questions.
+    fn rate_item(&mut self, id: u64, target: FeedbackTarget, rating: Rating) {
+        let Some(item) = self.feed_item_mut(id) else {
+            return;
+        };
+        let Some(cypher) = item.cypher.clone() else {
+            return;
+        };
+        let slot = match target {
+            FeedbackTarget::Translation => &mut item.translation_rating,
+            FeedbackTarget::Analysis => &mut item.analysis_rating,
+        };
+        if *slot == Some(rating) {
+            return;
+        }
+        *slot = Some(rating);
+        let record = FeedbackRecord {
+            question: item.question().to_string(),
+            cypher,
+            target,
+            rating,
+            analysis: match target {
+                FeedbackTarget::Analysis => item
+                    .analysis
+                    .as_ref()
+                    .map(|analysis| format!("{}: {}", analysis.title, analysis.summary)),
+                FeedbackTarget::Translation => None,
+            },
+            model: item.model.clone(),
+            recorded_at: Utc::now().to_rfc3339(),
+        };
+        if let Err(err) = self.pipeline.feedback.record(record) {
+            tracing::warn!("Failed to save feedback: {err}");
+        }
     }
 
-    fn rerun_cypher(&mut self, id: u64, cypher: String) {
+    fn open_timeline(&mut self, id: u64) {
+        let request = self.next_id;
+        let Some(item) = self.feed.iter().find(|item| item.id == id) else {
+            return;
+        };
+        self.next_id += 1;
+        let records = result_records(&item.result).unwrap_or_default();
+        let uids = resource_uids(&records);
+        self.timeline_panel = Some(TimelinePanel {
+            question: item.user_text.clone(),
+            entries: build_timeline(&records, &[]),
+            records,
+            loading: !uids.is_empty(),
+            error: uids
+                .is_empty()
+                .then(|| "This result has no Kubernetes objects to follow.".to_string()),
+            request,
+        });
+        if uids.is_empty() {
+            return;
+        }
+
+        let backend = self.pipeline.backend.clone();
         let tx = self.events_tx.clone();
-        let backend = self.backend.clone();
-        let analyst = self.analyst.clone();
-        let runtime = self.runtime.clone();
         let ctx = self.egui_ctx.clone();
-        let question = self
-            .feed
-            .iter()
-            .find(|item| item.id == id)
-            .map(|item| item.user_text.clone())
-            .unwrap_or_default();
-        let params = self
-            .feed
-            .iter()
-            .find(|item| item.id == id)
-            .and_then(|item| item.params.clone());
-        let analysis_context = self.build_context_with_budget();
-        let analysis_summary = self.context_compact_summary.clone();
+        self.runtime.spawn(async move {
+            let params = HashMap::from([(
+                "uids".to_string(),
+                Value::Array(uids.into_iter().map(Value::String).collect()),
+            )]);
+            let result = backend
+                .execute_query(TIMELINE_QUERY.to_string(), Some(params))
+                .await
+                .map_err(|err| err.to_string());
+            let _ = tx.send(AppEvent::TimelineLoaded { request, result });
+            ctx.request_repaint();
+        });
+    }
 
-        runtime.spawn(async move {
-            let send_event = |event| {
-                let _ = tx.send(event);
-                ctx.request_repaint();
-            };
-            match validate_cypher(&cypher) {
-                Ok(()) => {
-                    send_event(AppEvent::QueryStarted {
-                        id,
-                        cypher: cypher.clone(),
-                        params: params.clone(),
+    fn pick_for_compare(&mut self, id: u64) {
+        match self.compare_pick.take() {
+            None => self.compare_pick = Some(id),
+            Some(first) if first == id => {}
+            Some(first) => self.open_comparison(first.min(id), first.max(id)),
+        }
+    }
+
+    fn open_comparison(&mut self, before: u64, after: u64) {
+        let result = |id: u64| {
+            self.feed
+                .iter()
+                .find(|item| item.id == id)
+                .map(|item| (item.user_text.clone(), result_records(&item.result)))
+        };
+        let (Some((before, Some(before_records))), Some((after, Some(after_records)))) =
+            (result(before), result(after))
+        else {
+            return;
+        };
+        self.compare_panel = Some(ComparePanel {
+            before,
+            after,
+            comparison: compare_records(&before_records, &after_records),
+        });
+    }
+
+    fn render_compare_panel(&mut self, ctx: &egui::Context) {
+        let Some(panel) = self.compare_panel.as_ref() else {
+            return;
+        };
+        let palette = self.palette.clone();
+        let mut open = true;
+        egui::Window::new("Compare results")
+            .id(egui::Id::new("compare-panel"))
+            .open(&mut open)
+            .default_size([760.0, 460.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                for (label, question) in [("Before", &panel.before), ("After", &panel.after)] {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(label).color(palette.text_muted).size(11.0));
+                        ui.label(
+                            RichText::new(truncate_text(question, 96)).color(palette.text_primary),
+                        );
                     });
-                    let exec_start = Instant::now();
-                    match backend.execute_query(cypher.clone(), params.clone()).await {
-                        Ok(records) => {
-                            let exec_ms = exec_start.elapsed().as_millis();
-                            let summary = summarize_records(&records);
-                            send_event(AppEvent::QueryCompleted {
-                                id,
-                                cypher: cypher.clone(),
-                                records: records.clone(),
-                                duration_ms: exec_ms,
-                            });
-                            send_event(AppEvent::AnalysisStarted { id });
-                            let analysis_start = Instant::now();
-                            match analyst
-                                .analyze(
-                                    &question,
-                                    &cypher,
-                                    &records,
-                                    &summary,
-                                    &analysis_context,
-                                    analysis_summary.as_deref(),
-                                )
-                                .await
-                            {
-                                Ok(analysis) => {
-                                    let analysis_ms = analysis_start.elapsed().as_millis();
-                                    log_llm_call("analysis", analysis_ms, analysis.usage.as_ref());
-                                    send_event(AppEvent::AnalysisCompleted {
-                                        id,
-                                        analysis,
-                                        duration_ms: analysis_ms,
-                                    });
-                                }
-                                Err(err) => {
-                                    let analysis_ms = analysis_start.elapsed().as_millis();
-                                    tracing::error!("Analysis failed: {err}");
-                                    send_event(AppEvent::AnalysisFailed {
-                                        id,
-                                        error: err.to_string(),
-                                        duration_ms: analysis_ms,
-                                    });
-                                }
-                            }
-                        }
-                        Err(err) => {
-                            let exec_ms = exec_start.elapsed().as_millis();
-                            tracing::error!("Query failed: {err}");
-                            send_event(AppEvent::QueryFailed {
-                                id,
-                                error: err.to_string(),
-                                cypher: cypher.clone(),
-                                duration_ms: exec_ms,
-                            });
-                        }
-                    }
                 }
-                Err(err) => {
-                    tracing::error!("Validation failed: {err}");
-                    send_event(AppEvent::ValidationFailed {
-                        id,
-                        error: err.to_string(),
-                        cypher,
+                let comparison = &panel.comparison;
+                ui.label(
+                    RichText::new(format!(
+                        "+{} added • -{} removed • ~{} changed • {} unchanged",
+                        comparison.added.len(),
+                        comparison.removed.len(),
+                        comparison.changed.len(),
+                        comparison.unchanged
+                    ))
+                    .color(palette.text_muted)
+                    .size(11.0),
+                );
+                ui.separator();
+                if comparison.is_empty() {
+                    ui.label(
+                        RichText::new("Both results have the same rows.")
+                            .color(palette.text_muted)
+                            .italics(),
+                    );
+                    return;
+                }
+                ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        render_compare_rows(ui, comparison, &palette);
                     });
+            });
+        if !open {
+            self.compare_panel = None;
+        }
+    }
+
+    fn render_timeline_panel(&mut self, ctx: &egui::Context) {
+        let Some(panel) = self.timeline_panel.as_ref() else {
+            return;
+        };
+        let palette = self.palette.clone();
+        let mut open = true;
+        egui::Window::new(format!("Timeline · {}", truncate_text(&panel.question, 48)))
+            .id(egui::Id::new("timeline-panel"))
+            .open(&mut open)
+            .default_size([760.0, 420.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let warnings = panel
+                        .entries
+                        .iter()
+                        .filter(|entry| entry.kind == TimelineKind::Warning)
+                        .count();
+                    ui.label(
+                        RichText::new(format!(
+                            "{} entries • {warnings} warnings",
+                            panel.entries.len()
+                        ))
+                        .color(palette.text_muted)
+                        .size(11.0),
+                    );
+                    if panel.loading {
+                        ui.spinner();
+                    }
+                });
+                if let Some(error) = &panel.error {
+                    ui.label(RichText::new(error).color(palette.danger));
                 }
+                if !panel.entries.is_empty() {
+                    render_timeline_plot(ui, &panel.entries, &palette);
+                }
+                ui.separator();
+                ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        egui::Grid::new("timeline-entries")
+                            .striped(true)
+                            .num_columns(4)
+                            .show(ui, |ui| {
+                                for entry in &panel.entries {
+                                    ui.label(
+                                        RichText::new(
+                                            entry.time.format("%Y-%m-%d %H:%M:%S").to_string(),
+                                        )
+                                        .monospace()
+                                        .color(palette.text_muted),
+                                    );
+                                    ui.label(
+                                        RichText::new(&entry.reason)
+                                            .color(timeline_color(entry.kind, &palette))
+                                            .strong(),
+                                    );
+                                    ui.label(
+                                        RichText::new(&entry.resource).color(palette.text_primary),
+                                    );
+                                    ui.label(RichText::new(&entry.note).color(palette.text_muted));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+        if !open {
+            self.timeline_panel = None;
+        }
+    }
+
+    fn cancel_item(&mut self, id: u64) {
+        let Some(token) = self.in_flight.remove(&id) else {
+            return;
+        };
+        token.cancel();
+        if let Some(item) = self.feed_item_mut(id) {
+            if matches!(
+                item.state,
+                FeedState::Translating | FeedState::Validating | FeedState::Running
+            ) {
+                item.state = FeedState::Cancelled;
             }
-        });
+            item.analysis_pending = false;
+        }
     }
 
-    fn handle_slash_command(&mut self, input: &str) -> bool {
-        if input.starts_with("/history") {
-            let id = self.next_id;
-            self.next_id += 1;
-            let mut item = FeedItem::new(id, input.to_string());
-            item.state = FeedState::Ready;
-            item.result = ResultPayload::Raw {
-                text: "History is not implemented yet.".to_string(),
-            };
-            self.feed.push(item);
-            return true;
+    fn export_result(&mut self, id: u64, format: ExportFormat) {
+        let Some(records) = self
+            .feed
+            .iter()
+            .find(|item| item.id == id)
+            .and_then(|item| result_records(&item.result))
+        else {
+            return;
+        };
+        let extension = format.extension();
+        let Some(path) = rfd::FileDialog::new()
+            .set_file_name(format!("ariadne-result-{id}.{extension}"))
+            .add_filter(format.label(), &[extension])
+            .save_file()
+        else {
+            return;
+        };
+        let status = match export_records(&path, &records, format) {
+            Ok(()) => format!("Exported {} rows to {}", records.len(), path.display()),
+            Err(err) => {
+                tracing::error!("Export to {} failed: {err}", path.display());
+                format!("Export failed: {err}")
+            }
+        };
+        if let Some(item) = self.feed_item_mut(id) {
+            item.export_status = Some(status);
         }
-        if input.starts_with("/explain") {
-            let id = self.next_id;
-            self.next_id += 1;
-            let mut item = FeedItem::new(id, input.to_string());
-            item.state = FeedState::Ready;
-            item.result = ResultPayload::Raw {
-                text: "Explain mode is not implemented yet.".to_string(),
-            };
-            self.feed.push(item);
-            return true;
+    }
+
+    /// Writes every question of the feed to a report file chosen by the user and returns a
+    /// status line, or `None` when the dialog was dismissed.
+    fn export_report(&mut self, format: ReportFormat) -> Option<String> {
+        let entries: Vec<ReportEntry> = self
+            .feed
+            .iter()
+            .filter(|item| !item.user_text.starts_with('/'))
+            .map(report_entry)
+            .collect();
+        if entries.is_empty() {
+            return Some("Nothing to report yet. Ask a question first.".to_string());
         }
-        false
+        let generated_at = Utc::now();
+        let extension = format.extension();
+        let path = rfd::FileDialog::new()
+            .set_file_name(format!(
+                "ariadne-report-{}.{extension}",
+                generated_at.format("%Y%m%d-%H%M%S")
+            ))
+            .add_filter(format.label(), &[extension])
+            .save_file()?;
+        let report = Report {
+            cluster: &self.clusters[self.active_cluster].session.label,
+            generated_at,
+            entries: &entries,
+        };
+        Some(match write_report(&path, &report, format) {
+            Ok(()) => format!("Exported {} questions to {}", entries.len(), path.display()),
+            Err(err) => {
+                tracing::error!("Report export to {} failed: {err}", path.display());
+                format!("Report export failed: {err}")
+            }
+        })
     }
 
     fn drain_events(&mut self) -> bool {
         let mut handled = false;
         while let Ok(event) = self.events_rx.try_recv() {
             handled = true;
+            if let Some(id) = event.item_id() {
+                // Events queued before a cancel must not resurrect the item.
+                let cancelled = self
+                    .feed
+                    .iter()
+                    .any(|item| item.id == id && matches!(item.state, FeedState::Cancelled));
+                if cancelled && !matches!(event, AppEvent::TaskFinished { .. }) {
+                    continue;
+                }
+            }
             match event {
                 AppEvent::RouteDecided { id, route, steps } => {
                     if let Some(item) = self.feed_item_mut(id) {
@@ -954,19 +3002,40 @@ impl GuiApp {
                         item.state = FeedState::Translating;
                     }
                 }
+                AppEvent::Replied { id, text } => {
+                    if let Some(item) = self.feed_item_mut(id) {
+                        item.reply = Some(text);
+                        item.state = FeedState::Ready;
+                    }
+                }
+                AppEvent::EntitiesResolved { id, hints } => {
+                    if let Some(item) = self.feed_item_mut(id) {
+                        item.entity_hints = hints;
+                    }
+                }
+                AppEvent::AgentStepsCompleted { id, steps } => {
+                    if let Some(item) = self.feed_item_mut(id) {
+                        item.investigation = steps;
+                    }
+                }
                 AppEvent::TranslationCompleted {
                     id,
                     cypher,
                     params,
+                    reasoning,
                     usage,
                     duration_ms,
+                    cached,
                 } => {
                     if let Some(item) = self.feed_item_mut(id) {
                         item.cypher = Some(cypher);
                         item.params = params;
+                        item.reasoning = reasoning;
                         item.state = FeedState::Validating;
+                        item.note_fallback(usage.as_ref());
                         item.llm_usage = usage;
                         item.llm_duration_ms = Some(duration_ms);
+                        item.translation_cached = cached;
                     }
                 }
                 AppEvent::TranslationFailed { id, error } => {
@@ -1004,6 +3073,15 @@ impl GuiApp {
                         item.exec_duration_ms = Some(duration_ms);
                         item.context_summary = Some(summarize_records(&records));
                         item.context_bindings = extract_context_bindings(&records);
+                        item.query_stats = None;
+                    }
+                    if self.settings.show_query_stats {
+                        self.load_query_stats(id);
+                    }
+                }
+                AppEvent::QueryStatsLoaded { id, result } => {
+                    if let Some(item) = self.feed_item_mut(id) {
+                        item.query_stats = Some(result);
                     }
                 }
                 AppEvent::QueryFailed {
@@ -1022,35 +3100,229 @@ impl GuiApp {
                         item.analysis_duration_ms = None;
                     }
                 }
-                AppEvent::AnalysisStarted { id } => {
-                    if let Some(item) = self.feed_item_mut(id) {
-                        item.analysis_pending = true;
-                        item.analysis_error = None;
+                AppEvent::AnalysisStarted { id } => {
+                    if let Some(item) = self.feed_item_mut(id) {
+                        item.analysis_pending = true;
+                        item.analysis = None;
+                        item.analysis_error = None;
+                        item.follow_ups.clear();
+                    }
+                }
+                AppEvent::AnalysisProgress { id, analysis } => {
+                    if let Some(item) = self.feed_item_mut(id) {
+                        if item.analysis_pending {
+                            item.analysis = Some(analysis);
+                        }
+                    }
+                }
+                AppEvent::AnalysisCompleted {
+                    id,
+                    analysis,
+                    duration_ms,
+                } => {
+                    if let Some(item) = self.feed.iter().find(|item| item.id == id) {
+                        if !analysis.summary.is_empty() {
+                            let finding = format!("{}. {}", analysis.title, analysis.summary);
+                            self.remember(MemoryKind::Finding, item.question(), &finding);
+                        }
+                    }
+                    if let Some(item) = self.feed_item_mut(id) {
+                        item.note_fallback(analysis.usage.as_ref());
+                        item.analysis = Some(analysis);
+                        item.analysis_duration_ms = Some(duration_ms);
+                        item.analysis_pending = false;
+                        item.analysis_error = None;
+                    }
+                }
+                AppEvent::AnalysisFailed {
+                    id,
+                    error,
+                    duration_ms,
+                } => {
+                    if let Some(item) = self.feed_item_mut(id) {
+                        item.analysis_error = Some(error);
+                        item.analysis_duration_ms = Some(duration_ms);
+                        item.analysis_pending = false;
+                    }
+                }
+                AppEvent::FollowUpsSuggested { id, follow_ups } => {
+                    if let Some(item) = self.feed_item_mut(id) {
+                        item.follow_ups = follow_ups;
+                    }
+                }
+                AppEvent::SessionSummarized {
+                    id,
+                    report,
+                    duration_ms,
+                } => {
+                    if let Some(item) = self.feed_item_mut(id) {
+                        item.state = FeedState::Ready;
+                        item.result = ResultPayload::Raw {
+                            text: render_incident_markdown(&report),
+                        };
+                        item.note_fallback(report.usage.as_ref());
+                        item.llm_usage = report.usage;
+                        item.llm_duration_ms = Some(duration_ms);
+                    }
+                }
+                AppEvent::SessionSummaryFailed { id, error } => {
+                    if let Some(item) = self.feed_item_mut(id) {
+                        item.state = FeedState::Error(error);
+                    }
+                }
+                AppEvent::MonitorsProposed {
+                    id,
+                    proposals,
+                    duration_ms,
+                } => {
+                    if let Some(item) = self.feed_item_mut(id) {
+                        item.state = FeedState::Ready;
+                        if proposals.monitors.is_empty() {
+                            item.result = ResultPayload::Raw {
+                                text: "No alert rule with a valid query came out of this \
+                                       session."
+                                    .to_string(),
+                            };
+                        }
+                        item.monitors = proposals
+                            .monitors
+                            .into_iter()
+                            .map(|monitor| MonitorCard {
+                                monitor,
+                                status: None,
+                            })
+                            .collect();
+                        item.note_fallback(proposals.usage.as_ref());
+                        item.llm_usage = proposals.usage;
+                        item.llm_duration_ms = Some(duration_ms);
+                    }
+                }
+                AppEvent::MonitorsFailed { id, error } => {
+                    if let Some(item) = self.feed_item_mut(id) {
+                        item.state = FeedState::Error(error);
+                    }
+                }
+                AppEvent::TaskFinished { id } => {
+                    self.in_flight.remove(&id);
+                }
+                AppEvent::WatchRefreshed {
+                    id,
+                    records,
+                    duration_ms,
+                } => {
+                    if let Some(item) = self.feed_item_mut(id) {
+                        let Some(watch) = item.watch.as_mut() else {
+                            continue;
+                        };
+                        let (added, removed) = diff_records(&watch.records, &records);
+                        watch.added = added;
+                        watch.removed = removed;
+                        watch.refreshing = false;
+                        watch.refreshes += 1;
+                        watch.error = None;
+                        item.result = classify_result(&records);
+                        item.exec_duration_ms = Some(duration_ms);
+                        item.context_summary = Some(summarize_records(&records));
+                        item.context_bindings = extract_context_bindings(&records);
+                        watch.records = records;
+                    }
+                }
+                AppEvent::WatchFailed { id, error } => {
+                    if let Some(watch) = self.feed_item_mut(id).and_then(|item| item.watch.as_mut())
+                    {
+                        watch.refreshing = false;
+                        watch.error = Some(error);
+                    }
+                }
+                AppEvent::DashboardTileLoaded {
+                    generation,
+                    index,
+                    result,
+                } => {
+                    if generation == self.dashboard.generation {
+                        if let Some(tile) = self.dashboard.tiles.get_mut(index) {
+                            *tile = TileState::Loaded(result);
+                        }
+                    }
+                }
+                AppEvent::PinnedLoaded {
+                    generation,
+                    cypher,
+                    result,
+                } => {
+                    if generation != self.pinned.generation {
+                        continue;
+                    }
+                    if let Some(run) = self.pinned.runs.get_mut(&cypher) {
+                        run.refreshing = false;
+                        run.result = Some(result);
+                    }
+                }
+                AppEvent::LogTargetResolved { request, result } => {
+                    let Some(panel) = self.log_panel.as_mut() else {
+                        continue;
+                    };
+                    if panel.request != request {
+                        continue;
+                    }
+                    match result {
+                        Ok(target) => {
+                            panel.target = Some(target);
+                            self.fetch_logs();
+                        }
+                        Err(error) => {
+                            panel.loading = false;
+                            panel.error = Some(error);
+                        }
                     }
                 }
-                AppEvent::AnalysisCompleted {
-                    id,
-                    analysis,
-                    duration_ms,
-                } => {
-                    if let Some(item) = self.feed_item_mut(id) {
-                        item.analysis = Some(analysis);
-                        item.analysis_duration_ms = Some(duration_ms);
-                        item.analysis_pending = false;
-                        item.analysis_error = None;
+                AppEvent::TimelineLoaded { request, result } => {
+                    if let Some(panel) = self.timeline_panel.as_mut() {
+                        if panel.request == request {
+                            panel.loading = false;
+                            match result {
+                                Ok(events) => {
+                                    panel.entries = build_timeline(&panel.records, &events)
+                                }
+                                Err(error) => panel.error = Some(error),
+                            }
+                        }
                     }
                 }
-                AppEvent::AnalysisFailed {
-                    id,
-                    error,
-                    duration_ms,
-                } => {
-                    if let Some(item) = self.feed_item_mut(id) {
-                        item.analysis_error = Some(error);
-                        item.analysis_duration_ms = Some(duration_ms);
-                        item.analysis_pending = false;
+                AppEvent::LogsFetched { request, result } => {
+                    if let Some(panel) = self.log_panel.as_mut() {
+                        if panel.request == request {
+                            panel.loading = false;
+                            match result {
+                                Ok(content) => {
+                                    panel.content = content;
+                                    panel.error = None;
+                                }
+                                Err(error) => panel.error = Some(error),
+                            }
+                        }
+                    }
+                }
+                AppEvent::InspectorEventsLoaded { request, result } => {
+                    if request == self.inspector.request {
+                        self.inspector.events = match result {
+                            Ok(records) => InspectorEvents::Loaded(events_section(&records)),
+                            Err(error) => InspectorEvents::Failed(error),
+                        };
                     }
                 }
+                AppEvent::AlertFired(alert) => {
+                    let id = self.next_id;
+                    self.next_id += 1;
+                    self.alerts.insert(
+                        0,
+                        AlertCard {
+                            id,
+                            fired_at: Instant::now(),
+                            alert,
+                        },
+                    );
+                }
                 AppEvent::ContextCompactionStarted => {
                     self.context_compacting = true;
                     self.context_compact_error = None;
@@ -1061,7 +3333,10 @@ impl GuiApp {
                     duration_ms,
                 } => {
                     self.context_compacting = false;
+                    self.remember(MemoryKind::Summary, "", &summary);
                     self.context_compact_summary = Some(summary);
+                    let price = self.active_price();
+                    self.usage_carry.add(usage.as_ref(), price.as_ref());
                     self.context_compact_usage = usage;
                     self.context_compact_duration_ms = Some(duration_ms);
                     self.context_compact_error = None;
@@ -1087,20 +3362,30 @@ impl GuiApp {
     }
 
     fn build_context_with_budget(&self) -> Vec<ConversationTurn> {
+        self.budgeted_context().0
+    }
+
+    /// Context turns that fit the budget, and whether older eligible turns had to be dropped.
+    fn budgeted_context(&self) -> (Vec<ConversationTurn>, bool) {
         let Some(budget) = self.context_budget_tokens() else {
-            return self.build_context(SHORT_TERM_CONTEXT_LIMIT);
+            let turns = self.build_context(SHORT_TERM_CONTEXT_LIMIT);
+            let dropped = self.build_context(usize::MAX).len() > turns.len();
+            return (turns, dropped);
         };
 
         let summary_tokens = self
             .context_compact_summary
             .as_deref()
-            .map(estimate_text_tokens)
+            .map(estimate_tokens)
             .unwrap_or(0);
         let mut remaining = budget.saturating_sub(summary_tokens);
         let mut turns = Vec::new();
+        let chain = self.follow_up_chain();
 
         for item in self.feed.iter().rev() {
-            if item.id < self.context_cutoff_id {
+            if (item.id < self.context_cutoff_id && !chain.contains(&item.id))
+                || item.user_text.starts_with('/')
+            {
                 continue;
             }
             if !matches!(item.state, FeedState::Ready) {
@@ -1110,14 +3395,15 @@ impl GuiApp {
                 continue;
             };
             let turn = ConversationTurn {
-                question: item.user_text.clone(),
+                question: item.question().to_string(),
                 cypher: cypher.clone(),
                 result_summary: item.context_summary.clone(),
                 bindings: item.context_bindings.clone(),
             };
             let turn_tokens = estimate_turn_tokens(&turn);
             if turn_tokens > remaining && !turns.is_empty() {
-                break;
+                turns.reverse();
+                return (turns, true);
             }
             if turn_tokens <= remaining || turns.is_empty() {
                 remaining = remaining.saturating_sub(turn_tokens);
@@ -1125,16 +3411,38 @@ impl GuiApp {
             }
         }
         turns.reverse();
-        turns
+        (turns, false)
+    }
+
+    /// Compacts in the background once the budget starts dropping turns, so older turns survive
+    /// as a summary. Tries once per newest feed item so a failing analyst isn't called in a loop.
+    fn maybe_auto_compact(&mut self) {
+        if self.context_compacting {
+            return;
+        }
+        let Some(newest) = self.feed.last().map(|item| item.id) else {
+            return;
+        };
+        if self.auto_compact_attempted_for == Some(newest) {
+            return;
+        }
+        if !self.budgeted_context().1 {
+            return;
+        }
+        self.auto_compact_attempted_for = Some(newest);
+        tracing::info!("Context budget exceeded, compacting automatically");
+        self.start_context_compaction();
     }
 
     fn build_context(&self, limit: usize) -> Vec<ConversationTurn> {
         let mut turns = Vec::new();
+        let chain = self.follow_up_chain();
         for item in self.feed.iter().rev() {
-            if turns.len() >= limit {
-                break;
+            let in_chain = chain.contains(&item.id);
+            if turns.len() >= limit && !in_chain {
+                continue;
             }
-            if item.id < self.context_cutoff_id {
+            if (item.id < self.context_cutoff_id && !in_chain) || item.user_text.starts_with('/') {
                 continue;
             }
             if !matches!(item.state, FeedState::Ready) {
@@ -1144,7 +3452,7 @@ impl GuiApp {
                 continue;
             };
             turns.push(ConversationTurn {
-                question: item.user_text.clone(),
+                question: item.question().to_string(),
                 cypher: cypher.clone(),
                 result_summary: item.context_summary.clone(),
                 bindings: item.context_bindings.clone(),
@@ -1154,6 +3462,24 @@ impl GuiApp {
         turns
     }
 
+    /// Ancestors of the newest item through follow-up buttons. They stay in context even past
+    /// a reset or the turn limit so a follow-up is answered with the investigation behind it.
+    fn follow_up_chain(&self) -> HashSet<u64> {
+        let mut chain = HashSet::new();
+        let mut next = self.feed.last().and_then(|item| item.follow_up_of);
+        while let Some(id) = next {
+            if !chain.insert(id) {
+                break;
+            }
+            next = self
+                .feed
+                .iter()
+                .find(|item| item.id == id)
+                .and_then(|item| item.follow_up_of);
+        }
+        chain
+    }
+
     fn build_context_for_compaction(&self, limit: usize) -> Vec<ConversationTurn> {
         self.build_context(limit)
     }
@@ -1167,78 +3493,668 @@ impl GuiApp {
         self.context_compacting = false;
     }
 
-    fn start_context_compaction(&mut self) {
-        if self.context_compacting {
+    fn start_context_compaction(&mut self) {
+        if self.context_compacting {
+            return;
+        }
+        let context = self.build_context_for_compaction(COMPACT_CONTEXT_LIMIT);
+        if context.is_empty() {
+            self.context_compact_error = Some("No context to compact.".to_string());
+            return;
+        }
+
+        let tx = self.events_tx.clone();
+        let analyst = self.pipeline.analyst.clone();
+        let runtime = self.runtime.clone();
+        let ctx = self.egui_ctx.clone();
+
+        self.context_compacting = true;
+        self.context_compact_error = None;
+
+        runtime.spawn(async move {
+            let send_event = |event| {
+                let _ = tx.send(event);
+                ctx.request_repaint();
+            };
+            send_event(AppEvent::ContextCompactionStarted);
+            let start = Instant::now();
+            match analyst.compact_context(&context).await {
+                Ok(result) => {
+                    let duration_ms = start.elapsed().as_millis();
+                    send_event(AppEvent::ContextCompactionCompleted {
+                        summary: result.summary,
+                        usage: result.usage,
+                        duration_ms,
+                    });
+                }
+                Err(err) => {
+                    send_event(AppEvent::ContextCompactionFailed {
+                        error: err.to_string(),
+                    });
+                }
+            }
+        });
+    }
+
+    fn update_pulse(&mut self) {
+        let interval = Duration::from_secs(5);
+        if self.last_pulse_update.elapsed() < interval {
+            return;
+        }
+        for cluster in &mut self.clusters {
+            let (node_count, prop_count, pod_count, service_count, namespace_count) = {
+                let state = cluster.session.cluster_state.load();
+                let node_count = state.get_node_count();
+                let prop_count = estimate_property_count(&state, node_count);
+                let pod_count = state.get_nodes_by_type(&ResourceType::Pod).count();
+                let service_count = state.get_nodes_by_type(&ResourceType::Service).count();
+                let namespace_count = state.get_nodes_by_type(&ResourceType::Namespace).count();
+                (
+                    node_count,
+                    prop_count,
+                    pod_count,
+                    service_count,
+                    namespace_count,
+                )
+            };
+            let pulse = &mut cluster.pulse;
+            push_sparkline(&mut pulse.nodes, node_count as f64);
+            push_sparkline(&mut pulse.props, prop_count as f64);
+            push_sparkline(&mut pulse.pods, pod_count as f64);
+            push_sparkline(&mut pulse.services, service_count as f64);
+            push_sparkline(&mut pulse.namespaces, namespace_count as f64);
+        }
+        {
+            let state = self.clusters[self.active_cluster]
+                .session
+                .cluster_state
+                .load();
+            self.suggestions = build_completions(&state);
+            self.suggested_questions = anomaly_questions(&state);
+        }
+        self.last_pulse_update = Instant::now();
+    }
+
+    /// Points new queries at another cluster. Earlier results stay in the feed, but the
+    /// conversation context is reset so follow-ups don't mix clusters.
+    fn switch_cluster(&mut self, index: usize) {
+        if index == self.active_cluster || index >= self.clusters.len() {
+            return;
+        }
+        let previous = self.active_cluster;
+        self.active_cluster = index;
+        self.pipeline.backend = self.clusters[index].session.backend.clone();
+        self.pipeline.cluster_state = self.clusters[index].session.cluster_state.clone();
+        self.pipeline.entity_index = self.clusters[index].session.entity_index.clone();
+        self.pipeline.agent_tools = kube_tools(
+            self.clusters[index]
+                .session
+                .kube_client
+                .clone()
+                .filter(|_| self.agent_kube_tools),
+        );
+        let instructions = |app: &Self, index: usize| {
+            app.operator_instructions
+                .for_cluster(&app.clusters[index].session.name)
+        };
+        if instructions(self, previous) != instructions(self, index) {
+            if let Some(config) = self.llm_models.get(self.active_model).cloned() {
+                self.rebuild_agents(config);
+            }
+        }
+        self.inspector = InspectorState::default();
+        if self.view == MainView::Dashboard {
+            self.refresh_dashboard();
+        } else {
+            self.dashboard.refreshed_at = None;
+        }
+        self.pinned.generation += 1;
+        self.pinned.runs.clear();
+        self.reset_context();
+        tracing::info!("Switched to cluster {}", self.clusters[index].session.label);
+    }
+
+    fn model_label(&self, index: usize) -> Option<String> {
+        let default_base_url = &self.llm_models.first()?.base_url;
+        Some(self.llm_models.get(index)?.label(default_base_url))
+    }
+
+    fn active_price(&self) -> Option<ModelPrice> {
+        self.llm_models
+            .get(self.active_model)
+            .and_then(|config| price_for_model(&config.model))
+    }
+
+    fn session_cost(&self) -> UsageCost {
+        let mut total = self.usage_carry;
+        for item in &self.feed {
+            total.merge(&item.usage_cost());
+        }
+        total
+    }
+
+    /// Whether `call` has to wait for confirmation because the session budget is spent. With
+    /// [`BudgetAction::Downgrade`] the app first switches to a cheaper configured model and
+    /// lets the call through.
+    fn hold_for_budget(&mut self, call: impl FnOnce() -> BudgetHeld) -> bool {
+        if !self.budget.is_exceeded(&self.session_cost()) {
+            return false;
+        }
+        if self.budget.action == BudgetAction::Downgrade {
+            if let Some(index) = cheaper_model(&self.llm_models, self.active_model) {
+                let from = self.model_label(self.active_model).unwrap_or_default();
+                self.switch_model(self.llm_models[index].clone());
+                if self.model_error.is_none() {
+                    let to = self.model_label(self.active_model).unwrap_or_default();
+                    tracing::info!("Session budget spent, switched from {from} to {to}");
+                    self.budget_notice =
+                        Some(format!("Budget spent: switched from {from} to {to}"));
+                    return false;
+                }
+            }
+        }
+        self.budget_held = Some(call());
+        true
+    }
+
+    /// Asks whether to run the call held back by the budget, once per call.
+    fn render_budget_dialog(&mut self, ctx: &egui::Context) {
+        let Some(held) = &self.budget_held else {
+            return;
+        };
+        let call = match held {
+            BudgetHeld::Ask { question, .. } => format!("Ask \"{}\"", truncate_text(question, 80)),
+            BudgetHeld::FixQuery(_) => "Ask the LLM to fix the failed query".to_string(),
+        };
+        let spent = self.budget.describe(&self.session_cost());
+        let model = self.model_label(self.active_model).unwrap_or_default();
+        let palette = self.palette.clone();
+        let mut open = true;
+        let mut run = false;
+        let mut cancel = false;
+        egui::Window::new("Session budget spent")
+            .id(egui::Id::new("budget-dialog"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                ui.label(RichText::new(call).color(palette.text_primary).size(13.0));
+                ui.add_space(4.0);
+                ui.label(
+                    RichText::new(format!("Spent {spent}. The call uses {model}."))
+                        .color(palette.text_muted)
+                        .size(12.0),
+                );
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    run = ui.button(tr("Run")).clicked();
+                    cancel = ui.button(tr("Cancel")).clicked();
+                });
+            });
+        if !open || cancel {
+            self.budget_held = None;
+        } else if run {
+            match self.budget_held.take() {
+                Some(BudgetHeld::Ask {
+                    question,
+                    investigate,
+                }) => self.start_ask(question, investigate),
+                Some(BudgetHeld::FixQuery(id)) => self.start_fix_query(id),
+                None => {}
+            }
+        }
+    }
+
+    fn render_budget_status(&self, ui: &mut egui::Ui) {
+        let spent = self.session_cost();
+        let Some(fraction) = self.budget.used_fraction(&spent) else {
+            return;
+        };
+        let color = if fraction >= 1.0 {
+            self.palette.danger
+        } else if fraction >= 0.8 {
+            self.palette.accent_warm
+        } else {
+            self.palette.text_muted
+        };
+        let mut hover = format!("Session budget: {}", self.budget.describe(&spent));
+        hover.push_str(match self.budget.action {
+            BudgetAction::Downgrade => {
+                "\nOnce spent, questions switch to a cheaper configured model, then ask first"
+            }
+            BudgetAction::Confirm => "\nOnce spent, every question asks for confirmation",
+        });
+        if let Some(notice) = &self.budget_notice {
+            hover.push('\n');
+            hover.push_str(notice);
+        }
+        ui.label(
+            RichText::new(format!("Budget {:.0}%", fraction * 100.0))
+                .color(color)
+                .size(11.0),
+        )
+        .on_hover_text(hover);
+    }
+
+    fn render_session_cost(&self, ui: &mut egui::Ui) {
+        let total = self.session_cost();
+        if total.tokens == 0 {
+            return;
+        }
+        let mut hover = format!(
+            "{} tokens used this session",
+            format_count(total.tokens as usize)
+        );
+        if total.unpriced_tokens > 0 {
+            hover.push_str(&format!(
+                "\n{} tokens from models without a price are not included \
+                 (see LLM_PRICING_CONFIG)",
+                total.unpriced_tokens
+            ));
+        }
+        let mut label = format!(
+            "{} · {} tokens",
+            format_cost(total.cost),
+            format_count(total.tokens as usize)
+        );
+        if total.unpriced_tokens > 0 {
+            label.push('+');
+        }
+        ui.label(
+            RichText::new(label)
+                .color(self.palette.text_muted)
+                .size(11.0),
+        )
+        .on_hover_text(hover);
+    }
+
+    /// Rebuilds the agents for `config` and uses them for every question asked from now on;
+    /// questions already running keep their model.
+    fn switch_model(&mut self, config: LlmConfig) {
+        let Some(config) = self.rebuild_agents(config) else {
+            return;
+        };
+        self.active_model = match self
+            .llm_models
+            .iter()
+            .position(|known| known.model == config.model && known.base_url == config.base_url)
+        {
+            Some(index) => index,
+            None => {
+                self.llm_models.push(config);
+                self.llm_models.len() - 1
+            }
+        };
+        tracing::info!(
+            "Switched to model {}",
+            self.model_label(self.active_model).unwrap_or_default()
+        );
+    }
+
+    /// Builds the agents for `config` into the pipeline and returns the config they use, or
+    /// records the error and keeps the current agents.
+    fn rebuild_agents(&mut self, mut config: LlmConfig) -> Option<LlmConfig> {
+        config.instructions = self
+            .operator_instructions
+            .for_cluster(&self.clusters[self.active_cluster].session.name);
+        let agents = match LlmAgents::try_new(config) {
+            Ok(agents) => agents,
+            Err(err) => {
+                self.model_error = Some(format!("Cannot use model: {err}"));
+                return None;
+            }
+        };
+        self.pipeline.translator = agents.translator;
+        self.pipeline.router = agents.router;
+        self.pipeline.agentic = agents.agentic;
+        self.pipeline.analyst = agents.analyst;
+        self.context_window_tokens = agents.context_window_tokens;
+        self.auto_compact_attempted_for = None;
+        self.model_error = None;
+        Some(agents.config)
+    }
+
+    /// Rebuilds the agents once a prompt file was edited, added or removed, so the next
+    /// question uses the new prompts.
+    fn reload_prompts(&mut self) {
+        if self.prompts_checked_at.elapsed() < PROMPT_RELOAD_INTERVAL {
+            return;
+        }
+        self.prompts_checked_at = Instant::now();
+        let fingerprint = PromptFingerprint::current();
+        if fingerprint == self.prompt_fingerprint {
+            return;
+        }
+        self.prompt_fingerprint = fingerprint;
+        let Some(config) = self.llm_models.get(self.active_model).cloned() else {
+            return;
+        };
+        tracing::info!("Prompt files changed, reloading the prompts");
+        self.rebuild_agents(config);
+    }
+
+    fn render_model_picker(&mut self, ui: &mut egui::Ui) {
+        let mut selected = None;
+        let mut custom = None;
+        let current = self.model_label(self.active_model).unwrap_or_default();
+        let labels: Vec<String> = (0..self.llm_models.len())
+            .filter_map(|index| self.model_label(index))
+            .collect();
+        egui::ComboBox::from_id_salt("llm-model")
+            .selected_text(
+                RichText::new(truncate_text(&current, 28))
+                    .color(self.palette.text_muted)
+                    .size(11.0),
+            )
+            .width(180.0)
+            .show_ui(ui, |ui| {
+                for (index, label) in labels.into_iter().enumerate() {
+                    if ui
+                        .selectable_label(index == self.active_model, label)
+                        .clicked()
+                        && index != self.active_model
+                    {
+                        selected = Some(self.llm_models[index].clone());
+                    }
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        TextEdit::singleline(&mut self.model_input)
+                            .hint_text("[backend:]model[@url]")
+                            .desired_width(180.0),
+                    );
+                    let submitted =
+                        response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if (ui.button("Use").clicked() || submitted)
+                        && !self.model_input.trim().is_empty()
+                    {
+                        custom = Some(self.model_input.trim().to_string());
+                    }
+                });
+                if let Some(error) = &self.model_error {
+                    ui.label(RichText::new(error).color(self.palette.danger).size(11.0));
+                }
+            })
+            .response
+            .on_hover_text("LLM model for new questions");
+        if let Some(spec) = custom {
+            match self.llm_models[0].alternative(&spec) {
+                Ok(config) => {
+                    self.switch_model(config);
+                    if self.model_error.is_none() {
+                        self.model_input.clear();
+                    }
+                }
+                Err(err) => self.model_error = Some(err.to_string()),
+            }
+        } else if let Some(config) = selected {
+            self.switch_model(config);
+        }
+    }
+
+    fn open_settings(&mut self) {
+        let saved = &self.settings.connection;
+        let active = self.llm_models.get(self.active_model);
+        let or_active = |value: &Option<String>, fallback: Option<&String>| {
+            value
+                .clone()
+                .or_else(|| fallback.cloned())
+                .unwrap_or_default()
+        };
+        self.settings_dialog = Some(SettingsDialog {
+            llm_backend: saved.llm_backend.clone().unwrap_or_default(),
+            llm_base_url: or_active(&saved.llm_base_url, active.map(|config| &config.base_url)),
+            llm_model: or_active(&saved.llm_model, active.map(|config| &config.model)),
+            llm_api_key: saved.llm_api_key.clone().unwrap_or_default(),
+            answer_language: active
+                .and_then(|config| config.answer_language.clone())
+                .unwrap_or_default(),
+            memgraph_url: saved.memgraph_url.clone().unwrap_or_default(),
+            poll_interval_secs: saved
+                .poll_interval_secs
+                .unwrap_or(DEFAULT_POLL_INTERVAL.as_secs()),
+            theme: self.settings.theme,
+            ui_scale: self.settings.ui_scale(),
+            locale: self.settings.locale(),
+            status: None,
+        });
+    }
+
+    fn render_settings_dialog(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = self.settings_dialog.as_mut() else {
+            return;
+        };
+        let palette = self.palette.clone();
+        let mut open = true;
+        let mut save = false;
+        let mut cancel = false;
+        egui::Window::new(tr("Settings"))
+            .id(egui::Id::new("settings-dialog"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("settings-grid")
+                    .num_columns(2)
+                    .spacing([12.0, 8.0])
+                    .show(ui, |ui| {
+                        ui.label(RichText::new("LLM").strong());
+                        ui.end_row();
+                        ui.label("Backend");
+                        egui::ComboBox::from_id_salt("settings-llm-backend")
+                            .selected_text(if dialog.llm_backend.is_empty() {
+                                "unchanged"
+                            } else {
+                                dialog.llm_backend.as_str()
+                            })
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(
+                                    &mut dialog.llm_backend,
+                                    String::new(),
+                                    "unchanged",
+                                );
+                                for backend in LLM_BACKENDS {
+                                    ui.selectable_value(
+                                        &mut dialog.llm_backend,
+                                        backend.to_string(),
+                                        *backend,
+                                    );
+                                }
+                            });
+                        ui.end_row();
+                        ui.label("Endpoint");
+                        ui.add(
+                            TextEdit::singleline(&mut dialog.llm_base_url)
+                                .hint_text("https://api.openai.com/v1")
+                                .desired_width(300.0),
+                        );
+                        ui.end_row();
+                        ui.label("Model");
+                        ui.add(TextEdit::singleline(&mut dialog.llm_model).desired_width(300.0));
+                        ui.end_row();
+                        ui.label("API key");
+                        ui.add(
+                            TextEdit::singleline(&mut dialog.llm_api_key)
+                                .password(true)
+                                .hint_text("from LLM_API_KEY")
+                                .desired_width(300.0),
+                        );
+                        ui.end_row();
+                        ui.label("Answer language");
+                        ui.add(
+                            TextEdit::singleline(&mut dialog.answer_language)
+                                .hint_text("left to the model")
+                                .desired_width(300.0),
+                        );
+                        ui.end_row();
+
+                        ui.label(RichText::new("Cluster").strong());
+                        ui.end_row();
+                        ui.label("Memgraph URL");
+                        ui.add(
+                            TextEdit::singleline(&mut dialog.memgraph_url)
+                                .hint_text("in-memory")
+                                .desired_width(300.0),
+                        );
+                        ui.end_row();
+                        ui.label("Resolver interval");
+                        ui.add(
+                            egui::DragValue::new(&mut dialog.poll_interval_secs)
+                                .range(1..=3600)
+                                .suffix(" s"),
+                        );
+                        ui.end_row();
+
+                        ui.label(RichText::new(tr("Appearance")).strong());
+                        ui.end_row();
+                        ui.label(tr("Theme"));
+                        ui.horizontal(|ui| {
+                            for theme in [
+                                ThemeChoice::Dark,
+                                ThemeChoice::Light,
+                                ThemeChoice::HighContrast,
+                                ThemeChoice::Custom,
+                            ] {
+                                ui.selectable_value(&mut dialog.theme, theme, theme.label());
+                            }
+                        });
+                        ui.end_row();
+                        ui.label(tr("UI scale"));
+                        ui.add(
+                            egui::Slider::new(&mut dialog.ui_scale, MIN_UI_SCALE..=MAX_UI_SCALE)
+                                .step_by(UI_SCALE_STEP as f64)
+                                .custom_formatter(|value, _| format!("{:.0}%", value * 100.0)),
+                        );
+                        ui.end_row();
+                        ui.label(tr("Language"));
+                        egui::ComboBox::from_id_salt("settings-locale")
+                            .selected_text(dialog.locale.label())
+                            .show_ui(ui, |ui| {
+                                for locale in Locale::ALL {
+                                    ui.selectable_value(&mut dialog.locale, locale, locale.label());
+                                }
+                            });
+                        ui.end_row();
+                    });
+                ui.add_space(6.0);
+                ui.label(
+                    RichText::new(
+                        "LLM, theme, scale and language changes apply right away; the graph \
+                         backend and resolver interval on the next launch. Flags and env vars \
+                         override these values.",
+                    )
+                    .color(palette.text_muted)
+                    .size(11.0),
+                );
+                if let Some(status) = &dialog.status {
+                    ui.label(RichText::new(status).color(palette.danger));
+                }
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    save = ui.button(tr("Save")).clicked();
+                    cancel = ui.button(tr("Cancel")).clicked();
+                });
+            });
+        if !open || cancel {
+            self.settings_dialog = None;
+        } else if save {
+            self.save_settings_dialog();
+        }
+    }
+
+    /// Writes the dialog to the settings file, then applies the theme and, when the LLM
+    /// fields differ from the active model, switches to the edited model.
+    fn save_settings_dialog(&mut self) {
+        let Some(dialog) = self.settings_dialog.as_mut() else {
             return;
+        };
+        let non_empty = |value: &str| {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        };
+        let connection = ConnectionSettings {
+            llm_backend: non_empty(&dialog.llm_backend),
+            llm_base_url: non_empty(&dialog.llm_base_url),
+            llm_model: non_empty(&dialog.llm_model),
+            llm_api_key: non_empty(&dialog.llm_api_key),
+            memgraph_url: non_empty(&dialog.memgraph_url),
+            poll_interval_secs: Some(dialog.poll_interval_secs),
+            answer_language: non_empty(&dialog.answer_language),
+        };
+        let llm_config = match self.llm_models.get(self.active_model) {
+            Some(active) => match settings_llm_config(active, &connection) {
+                Ok(config) => config,
+                Err(err) => {
+                    dialog.status = Some(err.to_string());
+                    return;
+                }
+            },
+            None => None,
+        };
+        let theme = dialog.theme;
+        let ui_scale = dialog.ui_scale;
+        let locale = dialog.locale;
+
+        self.settings.connection = connection;
+        if self.settings.theme != theme {
+            self.settings.theme = theme;
+            self.palette = Palette::from_settings(&self.settings);
+            setup_style(&self.egui_ctx, &self.palette);
         }
-        let context = self.build_context_for_compaction(COMPACT_CONTEXT_LIMIT);
-        if context.is_empty() {
-            self.context_compact_error = Some("No context to compact.".to_string());
+        self.settings.ui_scale = Some(ui_scale);
+        self.egui_ctx.set_zoom_factor(self.settings.ui_scale());
+        self.settings.locale = Some(locale);
+        set_locale(locale);
+        if let Err(err) = self.settings.save() {
+            if let Some(dialog) = self.settings_dialog.as_mut() {
+                dialog.status = Some(format!("Failed to save settings: {err}"));
+            }
             return;
         }
-
-        let tx = self.events_tx.clone();
-        let analyst = self.analyst.clone();
-        let runtime = self.runtime.clone();
-        let ctx = self.egui_ctx.clone();
-
-        self.context_compacting = true;
-        self.context_compact_error = None;
-
-        runtime.spawn(async move {
-            let send_event = |event| {
-                let _ = tx.send(event);
-                ctx.request_repaint();
-            };
-            send_event(AppEvent::ContextCompactionStarted);
-            let start = Instant::now();
-            match analyst.compact_context(&context).await {
-                Ok(result) => {
-                    let duration_ms = start.elapsed().as_millis();
-                    send_event(AppEvent::ContextCompactionCompleted {
-                        summary: result.summary,
-                        usage: result.usage,
-                        duration_ms,
-                    });
-                }
-                Err(err) => {
-                    send_event(AppEvent::ContextCompactionFailed {
-                        error: err.to_string(),
-                    });
+        if let Some(config) = llm_config {
+            for model in &mut self.llm_models {
+                model.answer_language = config.answer_language.clone();
+            }
+            self.switch_model(config);
+            if let Some(error) = self.model_error.clone() {
+                if let Some(dialog) = self.settings_dialog.as_mut() {
+                    dialog.status = Some(error);
                 }
+                return;
             }
-        });
+        }
+        self.settings_dialog = None;
     }
 
-    fn update_pulse(&mut self) {
-        let interval = Duration::from_secs(5);
-        if self.last_pulse_update.elapsed() < interval {
-            return;
-        }
-        let (node_count, prop_count, pod_count, service_count, namespace_count) = {
-            let guard = self
-                .cluster_state
-                .lock()
-                .expect("cluster state lock poisoned");
-            let node_count = guard.get_node_count();
-            let prop_count = estimate_property_count(&guard, node_count);
-            let pod_count = guard.get_nodes_by_type(&ResourceType::Pod).count();
-            let service_count = guard.get_nodes_by_type(&ResourceType::Service).count();
-            let namespace_count = guard.get_nodes_by_type(&ResourceType::Namespace).count();
-            (
-                node_count,
-                prop_count,
-                pod_count,
-                service_count,
-                namespace_count,
-            )
+    fn cycle_theme(&mut self) {
+        let next = match self.settings.theme {
+            ThemeChoice::Dark => ThemeChoice::Light,
+            ThemeChoice::Light => ThemeChoice::HighContrast,
+            ThemeChoice::HighContrast if !self.settings.custom_colors.is_empty() => {
+                ThemeChoice::Custom
+            }
+            ThemeChoice::HighContrast | ThemeChoice::Custom => ThemeChoice::Dark,
         };
-        push_sparkline(&mut self.pulse_nodes, node_count as f64);
-        push_sparkline(&mut self.pulse_props, prop_count as f64);
-        push_sparkline(&mut self.pulse_pods, pod_count as f64);
-        push_sparkline(&mut self.pulse_services, service_count as f64);
-        push_sparkline(&mut self.pulse_namespaces, namespace_count as f64);
-        self.last_pulse_update = Instant::now();
+        self.settings.theme = next;
+        self.palette = Palette::from_settings(&self.settings);
+        setup_style(&self.egui_ctx, &self.palette);
+        if let Err(err) = self.settings.save() {
+            tracing::warn!("Failed to save settings: {err}");
+        }
+    }
+
+    /// Sets the zoom factor of the whole UI, clamped to the supported range, and saves it.
+    fn set_ui_scale(&mut self, scale: f32) {
+        // Rounded so repeated steps do not accumulate float error in the settings file.
+        let scale = ((scale * 100.0).round() / 100.0).clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        self.settings.ui_scale = Some(scale);
+        self.egui_ctx.set_zoom_factor(scale);
+        if let Err(err) = self.settings.save() {
+            tracing::warn!("Failed to save settings: {err}");
+        }
     }
 
     fn update_autocomplete(&mut self) {
@@ -1248,19 +4164,349 @@ impl GuiApp {
             return;
         }
         let token_lower = token.to_lowercase();
-        self.filtered_suggestions = self
+        let filtered: Vec<String> = self
             .suggestions
             .iter()
             .filter(|suggestion| suggestion.to_lowercase().starts_with(&token_lower))
             .take(6)
             .cloned()
             .collect();
+        if filtered != self.filtered_suggestions {
+            self.suggestion_index = 0;
+        }
+        self.filtered_suggestions = filtered;
     }
 
     fn apply_suggestion(&mut self, suggestion: &str) {
         let replaced = replace_last_token(&self.input, suggestion);
         self.input = replaced;
         self.filtered_suggestions.clear();
+        self.suggestion_index = 0;
+        self.move_input_cursor_to_end();
+    }
+
+    fn move_input_cursor_to_end(&self) {
+        let id = egui::Id::new(QUESTION_INPUT_ID);
+        let mut state =
+            egui::text_edit::TextEditState::load(&self.egui_ctx, id).unwrap_or_default();
+        let end = egui::text::CCursor::new(self.input.chars().count());
+        state
+            .cursor
+            .set_char_range(Some(egui::text::CCursorRange::one(end)));
+        state.store(&self.egui_ctx, id);
+    }
+
+    fn clear_feed(&mut self) {
+        for item in &self.feed {
+            self.usage_carry.merge(&item.usage_cost());
+        }
+        self.feed.clear();
+        self.compare_pick = None;
+        self.inspector = InspectorState::default();
+        self.reset_context();
+    }
+
+    /// Global shortcuts, handled before the footer renders so the input box never sees the keys
+    /// it shouldn't (arrows, Tab, Esc).
+    fn open_command_palette(&mut self) {
+        let mut entries: Vec<PaletteEntry> = SLASH_COMMANDS
+            .iter()
+            .map(|(command, detail)| PaletteEntry {
+                category: "Command",
+                label: command.to_string(),
+                detail: detail.to_string(),
+                action: PaletteAction::Command(command.to_string()),
+            })
+            .collect();
+        entries.extend(self.settings.saved_queries.iter().map(|saved| {
+            PaletteEntry {
+                category: "Saved",
+                label: saved.name.clone(),
+                detail: saved
+                    .cypher
+                    .clone()
+                    .unwrap_or_else(|| saved.question.clone()),
+                action: PaletteAction::Saved(saved.clone()),
+            }
+        }));
+        entries.extend(
+            self.question_history()
+                .into_iter()
+                .rev()
+                .filter(|question| !question.starts_with('/'))
+                .map(|question| PaletteEntry {
+                    category: "Recent",
+                    label: question.clone(),
+                    detail: String::new(),
+                    action: PaletteAction::Question(question),
+                }),
+        );
+        {
+            let state = self.clusters[self.active_cluster]
+                .session
+                .cluster_state
+                .load();
+            entries.extend(state.get_nodes().take(PALETTE_MAX_RESOURCES).map(|node| {
+                let kind = node.resource_type.to_string();
+                let label = match &node.id.namespace {
+                    Some(namespace) => format!("{namespace}/{}", node.id.name),
+                    None => node.id.name.clone(),
+                };
+                PaletteEntry {
+                    category: "Resource",
+                    label,
+                    detail: kind.clone(),
+                    action: PaletteAction::Resource {
+                        kind,
+                        name: node.id.name.clone(),
+                        namespace: node.id.namespace.clone(),
+                    },
+                }
+            }));
+        }
+
+        self.command_palette = CommandPalette {
+            is_open: true,
+            entries,
+            ..CommandPalette::default()
+        };
+        self.command_palette.refresh_matches();
+        self.egui_ctx
+            .memory_mut(|mem| mem.request_focus(egui::Id::new(PALETTE_INPUT_ID)));
+    }
+
+    fn run_palette_action(&mut self, action: PaletteAction) {
+        self.command_palette.is_open = false;
+        self.input = match action {
+            PaletteAction::Command(command) => command,
+            PaletteAction::Question(question) => question,
+            PaletteAction::Saved(saved) => {
+                self.run_saved_query(saved);
+                return;
+            }
+            PaletteAction::Resource {
+                kind,
+                name,
+                namespace,
+            } => match namespace {
+                Some(namespace) => format!(
+                    "Show the {kind} {name} in namespace {namespace} and the resources connected to it"
+                ),
+                None => format!("Show the {kind} {name} and the resources connected to it"),
+            },
+        };
+        self.submit_question();
+    }
+
+    /// Keys for the command palette while it is open; everything else is blocked.
+    fn handle_palette_keys(&mut self, ctx: &egui::Context) {
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)) {
+            self.command_palette.is_open = false;
+            return;
+        }
+        let palette = &mut self.command_palette;
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp)) {
+            palette.selected = palette.selected.saturating_sub(1);
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown)) {
+            palette.selected = (palette.selected + 1).min(palette.matches.len().saturating_sub(1));
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter)) {
+            if let Some(action) = palette.selected_action() {
+                self.run_palette_action(action);
+            }
+        }
+    }
+
+    fn render_command_palette(&mut self, ctx: &egui::Context) {
+        if !self.command_palette.is_open {
+            return;
+        }
+        let mut clicked: Option<PaletteAction> = None;
+        let palette_colors = self.palette.clone();
+        egui::Window::new("Command Palette")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_TOP, [0.0, 80.0])
+            .fixed_size([560.0, 0.0])
+            .frame(
+                Frame::new()
+                    .fill(palette_colors.bg_panel)
+                    .stroke(Stroke::new(1.0, palette_colors.border))
+                    .corner_radius(CornerRadius::same(10))
+                    .inner_margin(Margin::same(12)),
+            )
+            .show(ctx, |ui| {
+                let state = &mut self.command_palette;
+                let response = ui.add_sized(
+                    [ui.available_width(), 32.0],
+                    TextEdit::singleline(&mut state.query)
+                        .id(egui::Id::new(PALETTE_INPUT_ID))
+                        .hint_text("Commands, saved queries, recent questions, resources...")
+                        .background_color(palette_colors.bg_elevated)
+                        .margin(Margin::symmetric(10, 6)),
+                );
+                if response.changed() {
+                    state.selected = 0;
+                    state.refresh_matches();
+                }
+                ui.add_space(8.0);
+                if state.matches.is_empty() {
+                    ui.label(
+                        RichText::new("No matches")
+                            .color(palette_colors.text_muted)
+                            .size(12.0),
+                    );
+                }
+                for (position, &index) in state.matches.iter().enumerate() {
+                    let entry = &state.entries[index];
+                    let selected = position == state.selected;
+                    let fill = if selected {
+                        palette_colors.accent.gamma_multiply(0.25)
+                    } else {
+                        palette_colors.bg_panel
+                    };
+                    let row = Frame::new()
+                        .fill(fill)
+                        .corner_radius(CornerRadius::same(6))
+                        .inner_margin(Margin::symmetric(8, 4))
+                        .show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new(entry.category)
+                                        .color(palette_colors.accent)
+                                        .size(10.0),
+                                );
+                                ui.label(
+                                    RichText::new(truncate_text(&entry.label, 60))
+                                        .color(palette_colors.text_primary)
+                                        .size(12.0),
+                                );
+                                if !entry.detail.is_empty() {
+                                    ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                                        ui.label(
+                                            RichText::new(truncate_text(&entry.detail, 40))
+                                                .color(palette_colors.text_muted)
+                                                .size(11.0),
+                                        );
+                                    });
+                                }
+                            });
+                        })
+                        .response
+                        .interact(egui::Sense::click());
+                    if row.clicked() {
+                        clicked = Some(entry.action.clone());
+                    }
+                }
+            });
+        if let Some(action) = clicked {
+            self.run_palette_action(action);
+        }
+    }
+
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        let zoom_in = ctx.input_mut(|i| {
+            i.consume_key(egui::Modifiers::COMMAND, egui::Key::Plus)
+                || i.consume_key(egui::Modifiers::COMMAND, egui::Key::Equals)
+        });
+        if zoom_in {
+            self.set_ui_scale(self.settings.ui_scale() + UI_SCALE_STEP);
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Minus)) {
+            self.set_ui_scale(self.settings.ui_scale() - UI_SCALE_STEP);
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Num0)) {
+            self.set_ui_scale(1.0);
+        }
+
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::K)) {
+            if self.command_palette.is_open {
+                self.command_palette.is_open = false;
+            } else {
+                self.open_command_palette();
+            }
+            return;
+        }
+        if self.command_palette.is_open {
+            self.handle_palette_keys(ctx);
+            return;
+        }
+
+        let input_id = egui::Id::new(QUESTION_INPUT_ID);
+        let input_focused = ctx.memory(|mem| mem.has_focus(input_id));
+        let suggestions_open = input_focused && !self.filtered_suggestions.is_empty();
+
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Enter)) {
+            self.submit_question();
+            ctx.memory_mut(|mem| mem.request_focus(input_id));
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::L)) {
+            self.clear_feed();
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)) {
+            if suggestions_open {
+                self.filtered_suggestions.clear();
+            } else if self.input_history.cursor.is_some() {
+                self.input = std::mem::take(&mut self.input_history.draft);
+                self.input_history.reset();
+            } else if self.input.is_empty() {
+                if let Some(id) = self.in_flight.keys().max().copied() {
+                    self.cancel_item(id);
+                }
+            } else {
+                self.input.clear();
+            }
+            ctx.memory_mut(|mem| mem.request_focus(input_id));
+        }
+        if !input_focused {
+            return;
+        }
+
+        let step = if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp)) {
+            Some(HistoryStep::Older)
+        } else if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown)) {
+            Some(HistoryStep::Newer)
+        } else {
+            None
+        };
+        if let Some(step) = step {
+            if suggestions_open {
+                let last = self.filtered_suggestions.len() - 1;
+                self.suggestion_index = match step {
+                    HistoryStep::Older => self.suggestion_index.saturating_sub(1),
+                    HistoryStep::Newer => (self.suggestion_index + 1).min(last),
+                };
+            } else {
+                let entries = self.question_history();
+                if let Some(text) = self.input_history.step(&entries, &self.input, step) {
+                    self.input = text;
+                    self.move_input_cursor_to_end();
+                }
+            }
+        }
+
+        if suggestions_open
+            && ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Tab))
+        {
+            let index = self
+                .suggestion_index
+                .min(self.filtered_suggestions.len() - 1);
+            let suggestion = self.filtered_suggestions[index].clone();
+            self.apply_suggestion(&suggestion);
+        }
+    }
+
+    fn question_history(&self) -> Vec<String> {
+        let mut entries: Vec<String> = Vec::new();
+        for item in &self.feed {
+            if entries.last() != Some(&item.user_text) {
+                entries.push(item.user_text.clone());
+            }
+        }
+        entries
     }
 
     fn open_inspector_from_row(&mut self, row: &RowCard) {
@@ -1281,15 +4527,68 @@ impl GuiApp {
             })
             .collect();
         self.inspector.relationships = vec![];
+
+        self.inspector.request += 1;
+        let object = row_object(row);
+        self.inspector.uid = object
+            .pointer("/metadata/uid")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        self.inspector.log_source = LogSource::from_object(&object);
+        let Some(kind) = DescribeKind::detect(&object) else {
+            self.inspector.describe = Vec::new();
+            self.inspector.events = InspectorEvents::None;
+            return;
+        };
+        self.inspector.describe = describe_object(kind, &object);
+        self.inspector.node_type = Some(kind.label().to_string());
+        let (Some(name), Some(namespace)) = (
+            object.pointer("/metadata/name").cloned(),
+            object.pointer("/metadata/namespace").cloned(),
+        ) else {
+            self.inspector.events = InspectorEvents::None;
+            return;
+        };
+        self.inspector.events = InspectorEvents::Loading;
+
+        let request = self.inspector.request;
+        let params = HashMap::from([
+            ("name".to_string(), name),
+            ("namespace".to_string(), namespace),
+        ]);
+        let backend = self.pipeline.backend.clone();
+        let tx = self.events_tx.clone();
+        let ctx = self.egui_ctx.clone();
+        self.runtime.spawn(async move {
+            let result = backend
+                .execute_query(kind.events_query(), Some(params))
+                .await
+                .map_err(|err| err.to_string());
+            let _ = tx.send(AppEvent::InspectorEventsLoaded { request, result });
+            ctx.request_repaint();
+        });
     }
 }
 
 impl eframe::App for GuiApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         if self.drain_events() {
+            self.maybe_auto_compact();
             ctx.request_repaint();
         }
         self.update_pulse();
+        self.reload_prompts();
+        self.handle_shortcuts(ctx);
+        self.render_command_palette(ctx);
+        self.refresh_watches();
+        self.refresh_pinned();
+        self.refresh_log_panel();
+        self.render_log_panel(ctx);
+        self.render_timeline_panel(ctx);
+        self.render_compare_panel(ctx);
+        self.render_settings_dialog(ctx);
+        self.render_template_dialog(ctx);
+        self.render_budget_dialog(ctx);
 
         let screen_width = ctx.available_rect().width();
         let inspector_width = if screen_width < 1100.0 { 0.0 } else { 320.0 };
@@ -1343,6 +4642,50 @@ impl eframe::App for GuiApp {
                             .stroke(Stroke::new(1.0, self.palette.border))
                             .corner_radius(CornerRadius::same(14)),
                         );
+                        ui.add_space(6.0);
+                        let settings_clicked = ui
+                            .add_sized(
+                                [28.0, 28.0],
+                                egui::Button::new(
+                                    RichText::new("⚙").color(self.palette.text_muted).size(12.0),
+                                )
+                                .fill(self.palette.bg_elevated)
+                                .stroke(Stroke::new(1.0, self.palette.border))
+                                .corner_radius(CornerRadius::same(14)),
+                            )
+                            .on_hover_text(tr("Settings"))
+                            .clicked();
+                        if settings_clicked {
+                            self.open_settings();
+                        }
+                        ui.add_space(6.0);
+                        let theme_clicked = ui
+                            .add_sized(
+                                [64.0, 28.0],
+                                egui::Button::new(
+                                    RichText::new(self.settings.theme.label())
+                                        .color(self.palette.text_muted)
+                                        .size(11.0),
+                                )
+                                .fill(self.palette.bg_elevated)
+                                .stroke(Stroke::new(1.0, self.palette.border))
+                                .corner_radius(CornerRadius::same(14)),
+                            )
+                            .on_hover_text("Switch theme")
+                            .clicked();
+                        if theme_clicked {
+                            self.cycle_theme();
+                        }
+                        if !self.llm_models.is_empty() {
+                            ui.add_space(6.0);
+                            self.render_model_picker(ui);
+                        }
+                        ui.add_space(6.0);
+                        self.render_session_cost(ui);
+                        if self.budget.is_enabled() {
+                            ui.add_space(6.0);
+                            self.render_budget_status(ui);
+                        }
                         ui.add_space(8.0);
                         let search_width = ui.available_width().clamp(180.0, 320.0);
                         ui.add_sized(
@@ -1374,24 +4717,23 @@ impl eframe::App for GuiApp {
             .show(ctx, |ui| {
                 ui.add_space(10.0);
                 let mut has_focus = false;
-                let mut input_id: Option<egui::Id> = None;
                 ui.horizontal(|ui| {
                     ui.add_space(16.0);
 
-                    let buttons_width = 140.0;
+                    let buttons_width = 250.0;
                     let available = ui.available_width() - buttons_width;
 
                     let response = ui.add_sized(
                         [available.max(220.0), 40.0],
                         TextEdit::singleline(&mut self.input)
-                            .hint_text("Show me the services connected to these OOMing pods...")
+                            .id(egui::Id::new(QUESTION_INPUT_ID))
+                            .hint_text(tr("Show me the services connected to these OOMing pods..."))
                             .font(TextStyle::Monospace)
                             .background_color(self.palette.bg_elevated)
                             .margin(Margin::symmetric(12, 8)),
                     );
                     self.input_rect = Some(response.rect);
                     has_focus = response.has_focus();
-                    input_id = Some(response.id);
 
                     if response.lost_focus() && ctx.input(|i| i.key_pressed(egui::Key::Enter)) {
                         self.submit_question();
@@ -1403,7 +4745,7 @@ impl eframe::App for GuiApp {
                             .add_sized(
                                 [120.0, 40.0],
                                 egui::Button::new(
-                                    RichText::new("RUN QUERY")
+                                    RichText::new(tr("RUN QUERY"))
                                         .color(self.palette.text_primary)
                                         .strong(),
                                 )
@@ -1415,21 +4757,33 @@ impl eframe::App for GuiApp {
                         {
                             self.submit_question();
                         }
+                        let stroke = if self.investigate_mode {
+                            self.palette.accent
+                        } else {
+                            self.palette.border
+                        };
+                        if ui
+                            .add_sized(
+                                [100.0, 40.0],
+                                egui::Button::selectable(
+                                    self.investigate_mode,
+                                    RichText::new(tr("Investigate"))
+                                        .color(self.palette.text_primary),
+                                )
+                                .stroke(Stroke::new(1.0, stroke))
+                                .corner_radius(CornerRadius::same(6)),
+                            )
+                            .on_hover_text(
+                                "Let the agent run several queries and conclude from all of \
+                                 them",
+                            )
+                            .clicked()
+                        {
+                            self.investigate_mode = !self.investigate_mode;
+                        }
                     });
                 });
                 self.update_autocomplete();
-                if has_focus
-                    && !self.filtered_suggestions.is_empty()
-                    && ctx.input(|i| i.key_pressed(egui::Key::Tab))
-                {
-                    if let Some(first) = self.filtered_suggestions.first().cloned() {
-                        self.apply_suggestion(&first);
-                        ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Tab));
-                        if let Some(id) = input_id {
-                            ctx.memory_mut(|mem| mem.request_focus(id));
-                        }
-                    }
-                }
                 let mut show_autocomplete = has_focus;
                 let mut autocomplete_rect = None;
                 if let Some(rect) = self.input_rect {
@@ -1464,13 +4818,18 @@ impl eframe::App for GuiApp {
                                         ui.set_width(rect.width());
                                         let suggestions = self.filtered_suggestions.clone();
                                         for (idx, suggestion) in suggestions.iter().enumerate() {
+                                            let stroke_color = if idx == self.suggestion_index {
+                                                self.palette.accent
+                                            } else {
+                                                self.palette.border
+                                            };
                                             let button = egui::Button::new(
                                                 RichText::new(suggestion)
                                                     .color(self.palette.text_primary)
                                                     .size(12.0),
                                             )
                                             .fill(self.palette.bg_primary)
-                                            .stroke(Stroke::new(1.0, self.palette.border))
+                                            .stroke(Stroke::new(1.0, stroke_color))
                                             .corner_radius(CornerRadius::same(6));
                                             if ui
                                                 .add_sized([rect.width() - 4.0, 28.0], button)
@@ -1488,6 +4847,7 @@ impl eframe::App for GuiApp {
                 }
             });
 
+        let mut open_logs: Option<LogSource> = None;
         if self.inspector.is_open && inspector_width > 0.0 {
             egui::SidePanel::right("inspector")
                 .exact_width(inspector_width)
@@ -1515,6 +4875,11 @@ impl eframe::App for GuiApp {
                                     if ui.button("X").clicked() {
                                         self.inspector.is_open = false;
                                     }
+                                    if let Some(source) = &self.inspector.log_source {
+                                        if ui.button("Logs").clicked() {
+                                            open_logs = Some(source.clone());
+                                        }
+                                    }
                                 });
                             });
                             ui.add_space(16.0);
@@ -1535,64 +4900,84 @@ impl eframe::App for GuiApp {
                                         .size(13.0),
                                 );
                             }
+                            let changed = self.inspector.uid.as_ref().and_then(|uid| {
+                                self.clusters[self.active_cluster]
+                                    .session
+                                    .changed_fields
+                                    .borrow()
+                                    .get(uid)
+                                    .filter(|fields| !fields.is_empty())
+                                    .cloned()
+                            });
+                            if let Some(fields) = changed {
+                                ui.add_space(4.0);
+                                ui.horizontal_wrapped(|ui| {
+                                    ui.label(
+                                        RichText::new("Changed")
+                                            .color(self.palette.text_muted)
+                                            .size(11.0),
+                                    );
+                                    for field in fields {
+                                        render_changed_badge(ui, &self.palette, &field);
+                                    }
+                                });
+                            }
 
                             ui.add_space(16.0);
                             ui.separator();
 
-                            ui.label(
-                                RichText::new("Properties")
-                                    .color(self.palette.text_muted)
-                                    .size(12.0)
-                                    .strong(),
-                            );
-                            ui.add_space(4.0);
-                            ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
-                                for property in &self.inspector.properties {
-                                    match &property.value {
-                                        InspectorValue::Text(value) => {
-                                            ui.horizontal_wrapped(|ui| {
-                                                ui.label(
-                                                    RichText::new(format!("{}:", property.key))
-                                                        .color(self.palette.text_muted)
-                                                        .size(13.0),
-                                                );
-                                                ui.label(
-                                                    RichText::new(value)
-                                                        .color(self.palette.text_primary)
-                                                        .size(13.0),
-                                                );
-                                            });
-                                            ui.add_space(2.0);
-                                        }
-                                        InspectorValue::Json(value) => {
+                            if self.inspector.describe.is_empty() {
+                                ui.label(
+                                    RichText::new("Properties")
+                                        .color(self.palette.text_muted)
+                                        .size(12.0)
+                                        .strong(),
+                                );
+                                ui.add_space(4.0);
+                                ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                                    render_inspector_properties(
+                                        ui,
+                                        &self.palette,
+                                        &self.inspector.properties,
+                                    );
+                                });
+                            } else {
+                                ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                                    for section in &self.inspector.describe {
+                                        render_describe_section(ui, &self.palette, section);
+                                    }
+                                    match &self.inspector.events {
+                                        InspectorEvents::None => {}
+                                        InspectorEvents::Loading => {
                                             ui.label(
-                                                RichText::new(format!("{}:", property.key))
+                                                RichText::new("Loading events…")
                                                     .color(self.palette.text_muted)
-                                                    .size(13.0),
+                                                    .italics(),
+                                            );
+                                        }
+                                        InspectorEvents::Loaded(section) => {
+                                            render_describe_section(ui, &self.palette, section);
+                                        }
+                                        InspectorEvents::Failed(error) => {
+                                            ui.label(
+                                                RichText::new(format!(
+                                                    "Events unavailable: {error}"
+                                                ))
+                                                .color(self.palette.danger),
                                             );
-                                            ui.add_space(4.0);
-                                            let lines = value.lines().count().clamp(3, 10);
-                                            let height = (lines as f32) * 16.0 + 12.0;
-                                            Frame::new()
-                                                .fill(self.palette.bg_primary)
-                                                .stroke(Stroke::new(1.0, self.palette.border))
-                                                .corner_radius(CornerRadius::same(6))
-                                                .inner_margin(Margin::same(6))
-                                                .show(ui, |ui| {
-                                                    let mut display = value.clone();
-                                                    ui.add_sized(
-                                                        [ui.available_width(), height],
-                                                        TextEdit::multiline(&mut display)
-                                                            .font(TextStyle::Monospace)
-                                                            .interactive(false)
-                                                            .desired_width(f32::INFINITY),
-                                                    );
-                                                });
-                                            ui.add_space(6.0);
                                         }
                                     }
-                                }
-                            });
+                                    egui::CollapsingHeader::new("All properties")
+                                        .default_open(false)
+                                        .show(ui, |ui| {
+                                            render_inspector_properties(
+                                                ui,
+                                                &self.palette,
+                                                &self.inspector.properties,
+                                            );
+                                        });
+                                });
+                            }
 
                             ui.add_space(16.0);
                             ui.separator();
@@ -1624,6 +5009,9 @@ impl eframe::App for GuiApp {
                         });
                 });
         }
+        if let Some(source) = open_logs {
+            self.open_logs(source);
+        }
 
         egui::CentralPanel::default()
             .frame(Frame::new().fill(self.palette.bg_primary))
@@ -1654,16 +5042,13 @@ impl eframe::App for GuiApp {
 
                 let mut reset_clicked = false;
                 let mut compact_clicked = false;
+                let mut selected_cluster = self.active_cluster;
                 ui.allocate_ui(Vec2::new(ui.available_width(), GRAPH_PULSE_HEIGHT), |ui| {
                     let (reset, compact) = render_graph_pulse(
                         ui,
                         &self.palette,
-                        &mut self.cluster_meta,
-                        &self.pulse_nodes,
-                        &self.pulse_props,
-                        &self.pulse_pods,
-                        &self.pulse_services,
-                        &self.pulse_namespaces,
+                        &self.clusters,
+                        &mut selected_cluster,
                         &context_label,
                         self.context_compact_summary.is_some(),
                         self.context_compacting,
@@ -1672,6 +5057,9 @@ impl eframe::App for GuiApp {
                     reset_clicked = reset;
                     compact_clicked = compact;
                 });
+                if selected_cluster != self.active_cluster {
+                    self.switch_cluster(selected_cluster);
+                }
                 if reset_clicked {
                     self.reset_context();
                 }
@@ -1696,17 +5084,133 @@ impl eframe::App for GuiApp {
                                 color: Color32::from_black_alpha(80),
                             })
                             .show(ui, |ui| {
+                                let mut next_view = None;
+                                let mut refresh_clicked = false;
+                                let mut report_format = None;
                                 ui.horizontal(|ui| {
-                                    ui.label(
-                                        RichText::new("Investigation Feed")
-                                            .color(self.palette.text_primary)
-                                            .size(14.0)
-                                            .strong(),
-                                    );
+                                    for (view, title) in [
+                                        (MainView::Feed, tr("Investigation Feed")),
+                                        (MainView::Dashboard, tr("Cluster Overview")),
+                                        (MainView::Pinned, tr("Dashboard")),
+                                        (MainView::Schema, tr("Schema")),
+                                    ] {
+                                        let color = if self.view == view {
+                                            self.palette.text_primary
+                                        } else {
+                                            self.palette.text_muted
+                                        };
+                                        if ui
+                                            .selectable_label(
+                                                self.view == view,
+                                                RichText::new(title)
+                                                    .color(color)
+                                                    .size(14.0)
+                                                    .strong(),
+                                            )
+                                            .clicked()
+                                        {
+                                            next_view = Some(view);
+                                        }
+                                    }
+                                    if matches!(self.view, MainView::Dashboard | MainView::Pinned) {
+                                        ui.with_layout(
+                                            Layout::right_to_left(Align::Center),
+                                            |ui| {
+                                                if ui.button(tr("Refresh")).clicked() {
+                                                    refresh_clicked = true;
+                                                }
+                                            },
+                                        );
+                                    } else if self.view == MainView::Feed {
+                                        ui.with_layout(
+                                            Layout::right_to_left(Align::Center),
+                                            |ui| {
+                                                ui.menu_button(tr("Export report"), |ui| {
+                                                    for format in
+                                                        [ReportFormat::Markdown, ReportFormat::Html]
+                                                    {
+                                                        if ui.button(format.label()).clicked() {
+                                                            report_format = Some(format);
+                                                            ui.close();
+                                                        }
+                                                    }
+                                                });
+                                            },
+                                        );
+                                    }
                                 });
+                                if let Some(format) = report_format {
+                                    if let Some(status) = self.export_report(format) {
+                                        self.report_status = Some(status);
+                                    }
+                                }
+                                if self.view == MainView::Feed {
+                                    if let Some(status) = &self.report_status {
+                                        ui.label(
+                                            RichText::new(status)
+                                                .color(self.palette.text_muted)
+                                                .size(11.0),
+                                        );
+                                    }
+                                }
+                                match next_view {
+                                    Some(MainView::Dashboard) => self.show_dashboard(),
+                                    Some(MainView::Schema) => self.show_schema(),
+                                    Some(view) => self.view = view,
+                                    None => {}
+                                }
+                                if refresh_clicked {
+                                    if self.view == MainView::Pinned {
+                                        self.pinned.generation += 1;
+                                        self.pinned.runs.clear();
+                                    } else {
+                                        self.refresh_dashboard();
+                                    }
+                                }
 
                                 ui.add_space(10.0);
 
+                                if self.view == MainView::Dashboard {
+                                    let mut drill = None;
+                                    render_dashboard(
+                                        ui,
+                                        &self.palette,
+                                        &self.dashboard,
+                                        &mut drill,
+                                    );
+                                    if let Some(tile) = drill {
+                                        self.drill_into_tile(tile);
+                                    }
+                                    return;
+                                }
+                                if self.view == MainView::Pinned {
+                                    let mut pin_action = None;
+                                    render_pinned(
+                                        ui,
+                                        &self.palette,
+                                        &self.settings.pinned_queries,
+                                        &self.pinned,
+                                        &mut pin_action,
+                                    );
+                                    if let Some(action) = pin_action {
+                                        self.handle_pin_action(action);
+                                    }
+                                    return;
+                                }
+                                if self.view == MainView::Schema {
+                                    let mut run = None;
+                                    render_schema_browser(
+                                        ui,
+                                        &self.palette,
+                                        &mut self.schema_browser,
+                                        &mut run,
+                                    );
+                                    if let Some((title, cypher)) = run {
+                                        self.run_canned_query(title, cypher);
+                                    }
+                                    return;
+                                }
+
                                 if let Some(error) = &self.context_compact_error {
                                     ui.label(
                                         RichText::new(error).color(self.palette.danger).size(11.0),
@@ -1737,34 +5241,106 @@ impl eframe::App for GuiApp {
                                     }
                                 }
 
+                                let mut alert_actions = Vec::new();
+                                for card in &self.alerts {
+                                    render_alert_card(ui, card, &self.palette, &mut alert_actions);
+                                }
+                                for action in alert_actions {
+                                    self.handle_alert_action(action);
+                                }
+
+                                let mut suggested = None;
+                                if !self.suggested_questions.is_empty() {
+                                    ui.horizontal_wrapped(|ui| {
+                                        ui.label(
+                                            RichText::new("Worth a look:")
+                                                .color(self.palette.text_muted)
+                                                .size(11.0),
+                                        );
+                                        for suggestion in &self.suggested_questions {
+                                            if ui
+                                                .button(
+                                                    RichText::new(&suggestion.prompt)
+                                                        .color(self.palette.accent_warm)
+                                                        .size(12.0),
+                                                )
+                                                .on_hover_text(&suggestion.question)
+                                                .clicked()
+                                            {
+                                                suggested = Some(suggestion.question.clone());
+                                            }
+                                        }
+                                    });
+                                    ui.add_space(8.0);
+                                }
+                                if let Some(question) = suggested {
+                                    self.input = question;
+                                    self.submit_question();
+                                }
+
                                 if self.feed.is_empty() {
                                     ui.label(
-                                        RichText::new("No investigations yet.")
+                                        RichText::new(tr("No investigations yet."))
                                             .color(self.palette.text_muted)
                                             .italics(),
                                     );
                                 }
 
-                                let mut run_request: Option<(u64, String)> = None;
-                                let mut select_request: Option<RowCard> = None;
+                                let mut actions: Vec<FeedAction> = Vec::new();
                                 for item in &self.feed {
+                                    let in_flight = self.in_flight.contains_key(&item.id);
+                                    let pinned = item
+                                        .cypher
+                                        .as_deref()
+                                        .is_some_and(|cypher| self.is_pinned(cypher));
                                     render_feed_item(
                                         ui,
                                         item,
                                         &self.palette,
-                                        |id, cypher| {
-                                            run_request = Some((id, cypher));
-                                        },
-                                        |row| {
-                                            select_request = Some(row.clone());
-                                        },
+                                        in_flight,
+                                        pinned,
+                                        self.compare_pick,
+                                        &mut actions,
                                     );
                                 }
-                                if let Some((id, cypher)) = run_request {
-                                    self.rerun_cypher(id, cypher);
-                                }
-                                if let Some(row) = select_request {
-                                    self.open_inspector_from_row(&row);
+                                for action in actions {
+                                    match action {
+                                        FeedAction::Run { id, cypher } => {
+                                            self.rerun_cypher(id, cypher)
+                                        }
+                                        FeedAction::Select(row) => {
+                                            self.open_inspector_from_row(&row)
+                                        }
+                                        FeedAction::Export { id, format } => {
+                                            self.export_result(id, format)
+                                        }
+                                        FeedAction::Cancel(id) => self.cancel_item(id),
+                                        FeedAction::ToggleWatch(id) => self.toggle_watch(id),
+                                        FeedAction::OpenLogs(source) => self.open_logs(source),
+                                        FeedAction::OpenTimeline(id) => self.open_timeline(id),
+                                        FeedAction::Compare(id) => self.pick_for_compare(id),
+                                        FeedAction::FixQuery(id) => self.fix_query(id),
+                                        FeedAction::TogglePin(id) => self.toggle_pin(id),
+                                        FeedAction::RunAgentStep { id, step } => {
+                                            self.run_agent_step(id, step)
+                                        }
+                                        FeedAction::FillInput(text) => {
+                                            self.input = text;
+                                            self.move_input_cursor_to_end();
+                                            ui.memory_mut(|mem| {
+                                                mem.request_focus(egui::Id::new(QUESTION_INPUT_ID))
+                                            });
+                                        }
+                                        FeedAction::Rate { id, target, rating } => {
+                                            self.rate_item(id, target, rating)
+                                        }
+                                        FeedAction::FollowUp { parent, follow_up } => {
+                                            self.run_follow_up(parent, follow_up)
+                                        }
+                                        FeedAction::AcceptMonitor { id, index } => {
+                                            self.accept_monitor(id, index)
+                                        }
+                                    }
                                 }
                             });
 
@@ -1783,12 +5359,8 @@ impl eframe::App for GuiApp {
 fn render_graph_pulse(
     ui: &mut egui::Ui,
     palette: &Palette,
-    cluster_meta: &mut ClusterMeta,
-    nodes: &[f64],
-    props: &[f64],
-    pods: &[f64],
-    services: &[f64],
-    namespaces: &[f64],
+    clusters: &[ClusterView],
+    selected_cluster: &mut usize,
     context_label: &str,
     context_has_summary: bool,
     context_compacting: bool,
@@ -1796,6 +5368,7 @@ fn render_graph_pulse(
 ) -> (bool, bool) {
     let mut reset_clicked = false;
     let mut compact_clicked = false;
+    let active = &clusters[*selected_cluster];
     Frame::new()
         .fill(palette.bg_panel)
         .stroke(Stroke::new(1.0, palette.border))
@@ -1816,31 +5389,42 @@ fn render_graph_pulse(
                         .strong(),
                 );
                 ui.label(RichText::new("v").color(palette.text_muted).size(12.0));
-                let status = if cluster_meta.connected {
-                    "Connected"
-                } else {
-                    "Disconnected"
-                };
-                let status_color = if cluster_meta.connected {
+                let health = active
+                    .session
+                    .health
+                    .as_ref()
+                    .map(|health| health.borrow().clone());
+                let (status, connected) = connection_status(health.as_ref(), Instant::now());
+                let status_color = if connected {
                     palette.success
                 } else {
                     palette.danger
                 };
                 ui.add_space(10.0);
-                ui.label(RichText::new(status).color(status_color).size(11.0));
+                let status = ui.label(RichText::new(status).color(status_color).size(11.0));
+                if let Some(error) = health.and_then(|health| health.last_error) {
+                    status.on_hover_text(error);
+                }
+                let failures = active.session.cluster_state.load().listing_failures.clone();
+                if let Some((label, details)) = listing_failures_status(&failures) {
+                    ui.label(RichText::new(label).color(palette.accent_warm).size(11.0))
+                        .on_hover_text(details);
+                }
+                // Keeps the sync age current between cluster diffs.
+                ui.ctx().request_repaint_after(Duration::from_secs(1));
                 ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
-                    let mut selected = cluster_meta.label.clone();
                     egui::ComboBox::from_id_salt("cluster-selector")
-                        .selected_text(selected.clone())
+                        .selected_text(active.session.label.clone())
                         .width(200.0)
                         .show_ui(ui, |ui| {
-                            ui.selectable_value(
-                                &mut selected,
-                                cluster_meta.label.clone(),
-                                cluster_meta.label.clone(),
-                            );
+                            for (index, cluster) in clusters.iter().enumerate() {
+                                ui.selectable_value(
+                                    selected_cluster,
+                                    index,
+                                    cluster.session.label.clone(),
+                                );
+                            }
                         });
-                    cluster_meta.label = selected;
                     ui.add_space(6.0);
                     ui.label(
                         RichText::new("Cluster:")
@@ -1849,7 +5433,7 @@ fn render_graph_pulse(
                     );
                     ui.add_space(10.0);
                     let backend_text =
-                        truncate_text(&format!("Backend: {}", cluster_meta.backend_label), 48);
+                        truncate_text(&format!("Backend: {}", active.session.backend_label), 48);
                     Frame::new()
                         .fill(palette.bg_elevated)
                         .stroke(Stroke::new(1.0, palette.border))
@@ -1921,23 +5505,30 @@ fn render_graph_pulse(
             ui.separator();
             ui.add_space(8.0);
 
+            let pulse = &active.pulse;
             ui.horizontal(|ui| {
-                pulse_metric_cell(ui, "Nodes", nodes, palette, palette.spark_nodes);
+                pulse_metric_cell(ui, "Nodes", &pulse.nodes, palette, palette.spark_nodes);
                 ui.add(egui::Separator::default().vertical());
                 ui.add_space(6.0);
-                pulse_metric_cell(ui, "Properties", props, palette, palette.spark_props);
+                pulse_metric_cell(ui, "Properties", &pulse.props, palette, palette.spark_props);
                 ui.add(egui::Separator::default().vertical());
                 ui.add_space(6.0);
-                pulse_metric_cell(ui, "Pods", pods, palette, palette.spark_pods);
+                pulse_metric_cell(ui, "Pods", &pulse.pods, palette, palette.spark_pods);
                 ui.add(egui::Separator::default().vertical());
                 ui.add_space(6.0);
-                pulse_metric_cell(ui, "Services", services, palette, palette.spark_services);
+                pulse_metric_cell(
+                    ui,
+                    "Services",
+                    &pulse.services,
+                    palette,
+                    palette.spark_services,
+                );
                 ui.add(egui::Separator::default().vertical());
                 ui.add_space(6.0);
                 pulse_metric_cell(
                     ui,
                     "Namespaces",
-                    namespaces,
+                    &pulse.namespaces,
                     palette,
                     palette.spark_namespaces,
                 );
@@ -2021,106 +5612,542 @@ fn skeleton_line(ui: &mut egui::Ui, width: f32, palette: &Palette) {
     );
 }
 
-fn render_item_stats(ui: &mut egui::Ui, item: &FeedItem, palette: &Palette) {
-    if item.llm_duration_ms.is_none()
-        && item.exec_duration_ms.is_none()
-        && item.llm_usage.is_none()
-        && item.analysis_duration_ms.is_none()
-        && item
-            .analysis
-            .as_ref()
-            .and_then(|a| a.usage.as_ref())
-            .is_none()
-        && item.route.is_none()
-    {
-        return;
-    }
-
-    ui.add_space(8.0);
-    ui.horizontal_wrapped(|ui| {
-        if let Some(ms) = item.llm_duration_ms {
-            ui.label(
-                RichText::new(format!("LLM {}", format_duration(ms)))
-                    .color(palette.text_muted)
-                    .size(11.0),
-            );
-        }
-        if let Some(usage) = &item.llm_usage {
-            ui.label(
-                RichText::new(format!(
-                    "tokens {}/{}/{}",
-                    usage.prompt_tokens, usage.completion_tokens, usage.total_tokens
-                ))
+fn render_item_stats(ui: &mut egui::Ui, item: &FeedItem, palette: &Palette) {
+    if item.llm_duration_ms.is_none()
+        && item.exec_duration_ms.is_none()
+        && item.llm_usage.is_none()
+        && item.analysis_duration_ms.is_none()
+        && item
+            .analysis
+            .as_ref()
+            .and_then(|a| a.usage.as_ref())
+            .is_none()
+    {
+        return;
+    }
+    let cost = item.usage_cost();
+
+    ui.add_space(8.0);
+    ui.horizontal_wrapped(|ui| {
+        if let (Some(model), Some(_)) = (&item.model, item.llm_duration_ms) {
+            ui.label(RichText::new(model).color(palette.text_muted).size(11.0));
+        }
+        if item.translation_cached {
+            ui.label(
+                RichText::new("cached translation")
+                    .color(palette.accent)
+                    .size(11.0),
+            )
+            .on_hover_text("The query was reused from an earlier identical question");
+        } else if let Some(ms) = item.llm_duration_ms {
+            ui.label(
+                RichText::new(format!("LLM {}", format_duration(ms)))
+                    .color(palette.text_muted)
+                    .size(11.0),
+            );
+        }
+        if let Some(usage) = &item.llm_usage {
+            ui.label(
+                RichText::new(format!(
+                    "tokens {}/{}/{}",
+                    usage.prompt_tokens, usage.completion_tokens, usage.total_tokens
+                ))
+                .color(palette.text_muted)
+                .size(11.0),
+            );
+            if let Some(cached) = usage.cached_tokens {
+                ui.label(
+                    RichText::new(format!("cached {cached}"))
+                        .color(palette.text_muted)
+                        .size(11.0),
+                );
+            }
+            if let Some(reasoning) = usage.reasoning_tokens {
+                ui.label(
+                    RichText::new(format!("reasoning {reasoning}"))
+                        .color(palette.text_muted)
+                        .size(11.0),
+                );
+            }
+        }
+        if let Some(ms) = item.exec_duration_ms {
+            ui.label(
+                RichText::new(format!("exec {}", format_duration(ms)))
+                    .color(palette.text_muted)
+                    .size(11.0),
+            );
+        }
+        if let Some(ms) = item.analysis_duration_ms {
+            ui.label(
+                RichText::new(format!("analysis {}", format_duration(ms)))
+                    .color(palette.text_muted)
+                    .size(11.0),
+            );
+        }
+        if item.price.is_some() && cost.tokens > 0 {
+            ui.label(
+                RichText::new(format!("cost {}", format_cost(cost.cost)))
+                    .color(palette.text_muted)
+                    .size(11.0),
+            );
+        }
+        if let Some(usage) = item
+            .analysis
+            .as_ref()
+            .and_then(|analysis| analysis.usage.as_ref())
+        {
+            ui.label(
+                RichText::new(format!(
+                    "analysis tokens {}/{}/{}",
+                    usage.prompt_tokens, usage.completion_tokens, usage.total_tokens
+                ))
+                .color(palette.text_muted)
+                .size(11.0),
+            );
+            if let Some(cached) = usage.cached_tokens {
+                ui.label(
+                    RichText::new(format!("analysis cached {cached}"))
+                        .color(palette.text_muted)
+                        .size(11.0),
+                );
+            }
+            if let Some(reasoning) = usage.reasoning_tokens {
+                ui.label(
+                    RichText::new(format!("analysis reasoning {reasoning}"))
+                        .color(palette.text_muted)
+                        .size(11.0),
+                );
+            }
+        }
+    });
+}
+
+fn render_alert_card(
+    ui: &mut egui::Ui,
+    card: &AlertCard,
+    palette: &Palette,
+    actions: &mut Vec<AlertAction>,
+) {
+    let alert = &card.alert;
+    ui.add_space(8.0);
+    Frame::new()
+        .fill(palette.bg_elevated)
+        .stroke(Stroke::new(1.5, palette.danger))
+        .corner_radius(CornerRadius::same(10))
+        .inner_margin(Margin::same(12))
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new("ALERT")
+                        .color(palette.danger)
+                        .size(12.0)
+                        .strong(),
+                );
+                ui.label(
+                    RichText::new(&alert.rule)
+                        .color(palette.text_primary)
+                        .size(14.0),
+                );
+                ui.label(
+                    RichText::new(format!(
+                        "{} • {} (observed {}) • {}s ago",
+                        alert.cluster,
+                        alert.condition,
+                        alert.observed,
+                        card.fired_at.elapsed().as_secs()
+                    ))
+                    .color(palette.text_muted)
+                    .size(11.0),
+                );
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui.button("Dismiss").clicked() {
+                        actions.push(AlertAction::Dismiss(card.id));
+                    }
+                    if ui.button("Show rows").clicked() {
+                        actions.push(AlertAction::ShowRows(card.id));
+                    }
+                });
+            });
+        });
+}
+
+fn render_dashboard(
+    ui: &mut egui::Ui,
+    palette: &Palette,
+    dashboard: &DashboardState,
+    drill: &mut Option<&'static DashboardTile>,
+) {
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing = Vec2::new(12.0, 12.0);
+        for (tile, state) in DASHBOARD_TILES.iter().zip(&dashboard.tiles) {
+            let (value, value_color) = match state {
+                TileState::Loading => ("…".to_string(), palette.text_muted),
+                TileState::Loaded(Ok(count)) => {
+                    let color = if !tile.alerting {
+                        palette.accent
+                    } else if *count > 0 {
+                        palette.danger
+                    } else {
+                        palette.success
+                    };
+                    (format_count(*count), color)
+                }
+                TileState::Loaded(Err(_)) => ("error".to_string(), palette.danger),
+            };
+            let response = Frame::new()
+                .fill(palette.bg_elevated)
+                .stroke(Stroke::new(1.0, palette.border))
+                .corner_radius(CornerRadius::same(10))
+                .inner_margin(Margin::same(14))
+                .show(ui, |ui| {
+                    ui.set_width(200.0);
+                    ui.label(
+                        RichText::new(tile.title)
+                            .color(palette.text_muted)
+                            .size(12.0)
+                            .strong(),
+                    );
+                    ui.label(RichText::new(value).color(value_color).size(32.0).strong());
+                    ui.label(
+                        RichText::new(tile.detail)
+                            .color(palette.text_muted)
+                            .size(11.0),
+                    );
+                })
+                .response
+                .interact(egui::Sense::click())
+                .on_hover_cursor(egui::CursorIcon::PointingHand);
+            let response = match state {
+                TileState::Loaded(Err(error)) => response.on_hover_text(error),
+                _ => response.on_hover_text("Open in the feed"),
+            };
+            if response.clicked() {
+                *drill = Some(tile);
+            }
+        }
+    });
+}
+
+const PINNED_ROWS_SHOWN: usize = 5;
+
+/// One card per pinned query: a single value as a metric, anything else as a small table.
+fn render_pinned(
+    ui: &mut egui::Ui,
+    palette: &Palette,
+    pinned: &[PinnedQuery],
+    state: &PinnedState,
+    action: &mut Option<PinAction>,
+) {
+    if pinned.is_empty() {
+        ui.label(
+            RichText::new(
+                "Nothing pinned yet. Use Pin on a feed result to keep its query here; it is \
+                 re-run on a schedule.",
+            )
+            .color(palette.text_muted)
+            .italics(),
+        );
+        return;
+    }
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing = Vec2::new(12.0, 12.0);
+        for (index, query) in pinned.iter().enumerate() {
+            let run = state.runs.get(&query.cypher);
+            Frame::new()
+                .fill(palette.bg_elevated)
+                .stroke(Stroke::new(1.0, palette.border))
+                .corner_radius(CornerRadius::same(10))
+                .inner_margin(Margin::same(14))
+                .show(ui, |ui| {
+                    ui.set_width(320.0);
+                    ui.label(
+                        RichText::new(truncate_text(&query.title, 60))
+                            .color(palette.text_muted)
+                            .size(12.0)
+                            .strong(),
+                    )
+                    .on_hover_text(query.cypher.as_str());
+                    match run.and_then(|run| run.result.as_ref()) {
+                        None => {
+                            ui.label(RichText::new("…").color(palette.text_muted).size(32.0));
+                        }
+                        Some(Err(error)) => {
+                            ui.label(
+                                RichText::new(truncate_text(error, 160))
+                                    .color(palette.danger)
+                                    .size(11.0),
+                            );
+                        }
+                        Some(Ok(records)) => match pinned_metric(records) {
+                            Some((column, value)) => {
+                                ui.label(
+                                    RichText::new(value)
+                                        .color(palette.accent)
+                                        .size(32.0)
+                                        .strong(),
+                                );
+                                ui.label(
+                                    RichText::new(column).color(palette.text_muted).size(11.0),
+                                );
+                            }
+                            None => render_pinned_rows(ui, index, records, palette),
+                        },
+                    }
+                    ui.add_space(6.0);
+                    ui.horizontal(|ui| {
+                        if let Some(run) = run {
+                            let status = if run.refreshing {
+                                "refreshing…".to_string()
+                            } else {
+                                format!("updated {} ago", format_age(run.last_run.elapsed()))
+                            };
+                            ui.label(
+                                RichText::new(format!(
+                                    "{status} · every {}",
+                                    format_age(query.interval())
+                                ))
+                                .color(palette.text_muted)
+                                .size(10.0),
+                            );
+                        }
+                        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                            if ui.small_button(tr("Unpin")).clicked() {
+                                *action = Some(PinAction::Unpin(index));
+                            }
+                            if ui
+                                .small_button("Open")
+                                .on_hover_text("Run in the feed")
+                                .clicked()
+                            {
+                                *action = Some(PinAction::Open(index));
+                            }
+                        });
+                    });
+                });
+        }
+    });
+}
+
+fn render_pinned_rows(ui: &mut egui::Ui, index: usize, records: &[Value], palette: &Palette) {
+    let objects: Vec<&Map<String, Value>> =
+        records.iter().filter_map(|row| row.as_object()).collect();
+    let Some(first) = objects.first() else {
+        ui.label(
+            RichText::new("No rows")
                 .color(palette.text_muted)
-                .size(11.0),
-            );
-            if let Some(cached) = usage.cached_tokens {
-                ui.label(
-                    RichText::new(format!("cached {cached}"))
-                        .color(palette.text_muted)
-                        .size(11.0),
-                );
-            }
-            if let Some(reasoning) = usage.reasoning_tokens {
+                .size(12.0),
+        );
+        return;
+    };
+    let mut columns: Vec<&String> = first.keys().collect();
+    columns.sort();
+    egui::Grid::new(("pinned-rows", index))
+        .striped(true)
+        .show(ui, |ui| {
+            for column in &columns {
                 ui.label(
-                    RichText::new(format!("reasoning {reasoning}"))
+                    RichText::new(column.as_str())
                         .color(palette.text_muted)
-                        .size(11.0),
+                        .size(11.0)
+                        .strong(),
                 );
             }
-        }
-        if let Some(ms) = item.exec_duration_ms {
-            ui.label(
-                RichText::new(format!("exec {}", format_duration(ms)))
-                    .color(palette.text_muted)
-                    .size(11.0),
-            );
-        }
-        if let Some(ms) = item.analysis_duration_ms {
-            ui.label(
-                RichText::new(format!("analysis {}", format_duration(ms)))
-                    .color(palette.text_muted)
-                    .size(11.0),
-            );
-        }
-        if let Some(route) = item.route {
-            let mut label = format!("route {}", route.as_str());
-            if let Some(steps) = item.agent_steps {
-                label.push_str(&format!(" steps {steps}"));
+            ui.end_row();
+            for object in objects.iter().take(PINNED_ROWS_SHOWN) {
+                for column in &columns {
+                    let value = object
+                        .get(column.as_str())
+                        .map(format_value)
+                        .unwrap_or_else(|| "-".to_string());
+                    ui.label(RichText::new(truncate_text(&value, 40)).size(11.0));
+                }
+                ui.end_row();
             }
-            ui.label(RichText::new(label).color(palette.text_muted).size(11.0));
-        }
-        if let Some(usage) = item
-            .analysis
-            .as_ref()
-            .and_then(|analysis| analysis.usage.as_ref())
-        {
-            ui.label(
-                RichText::new(format!(
-                    "analysis tokens {}/{}/{}",
-                    usage.prompt_tokens, usage.completion_tokens, usage.total_tokens
-                ))
-                .color(palette.text_muted)
-                .size(11.0),
+        });
+    ui.label(
+        RichText::new(format!("{} rows", format_count(records.len())))
+            .color(palette.text_muted)
+            .size(10.0),
+    );
+}
+
+/// Label list with a filter on the left, the selected label's properties and relationships on
+/// the right. Clicking **List** or **Count** on a relationship sets `run` to that example query.
+fn render_schema_browser(
+    ui: &mut egui::Ui,
+    palette: &Palette,
+    browser: &mut SchemaBrowser,
+    run: &mut Option<(String, String)>,
+) {
+    ui.horizontal_top(|ui| {
+        ui.vertical(|ui| {
+            ui.set_width(200.0);
+            ui.add(
+                TextEdit::singleline(&mut browser.filter)
+                    .hint_text("Filter labels")
+                    .desired_width(200.0),
             );
-            if let Some(cached) = usage.cached_tokens {
-                ui.label(
-                    RichText::new(format!("analysis cached {cached}"))
-                        .color(palette.text_muted)
-                        .size(11.0),
-                );
-            }
-            if let Some(reasoning) = usage.reasoning_tokens {
+            ui.add_space(6.0);
+            let filter = browser.filter.to_lowercase();
+            ScrollArea::vertical()
+                .id_salt("schema-labels")
+                .max_height(520.0)
+                .show(ui, |ui| {
+                    for label in browser
+                        .catalog
+                        .iter()
+                        .filter(|label| label.name.to_lowercase().contains(&filter))
+                    {
+                        let selected = browser.selected.as_deref() == Some(label.name.as_str());
+                        if ui
+                            .selectable_label(
+                                selected,
+                                RichText::new(&label.name).color(palette.text_primary),
+                            )
+                            .clicked()
+                        {
+                            browser.selected = Some(label.name.clone());
+                        }
+                    }
+                });
+        });
+        ui.separator();
+        ui.vertical(|ui| {
+            let Some(label) = browser
+                .selected
+                .as_ref()
+                .and_then(|name| browser.catalog.iter().find(|label| &label.name == name))
+            else {
                 ui.label(
-                    RichText::new(format!("analysis reasoning {reasoning}"))
-                        .color(palette.text_muted)
-                        .size(11.0),
+                    RichText::new("Select a label to see its properties and relationships.")
+                        .color(palette.text_muted),
                 );
-            }
-        }
+                return;
+            };
+            ScrollArea::vertical()
+                .id_salt("schema-details")
+                .max_height(560.0)
+                .show(ui, |ui| {
+                    ui.label(
+                        RichText::new(format!(":{}", label.name))
+                            .color(palette.text_primary)
+                            .size(16.0)
+                            .strong(),
+                    );
+                    ui.add_space(6.0);
+                    ui.label(
+                        RichText::new("Properties")
+                            .color(palette.text_muted)
+                            .strong(),
+                    );
+                    if label.properties.is_empty() {
+                        ui.label(
+                            RichText::new("No schema for this label.").color(palette.text_muted),
+                        );
+                    }
+                    egui::Grid::new("schema-properties")
+                        .striped(true)
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            for (name, data_type) in &label.properties {
+                                ui.label(
+                                    RichText::new(name).monospace().color(palette.text_primary),
+                                );
+                                ui.label(
+                                    RichText::new(data_type)
+                                        .monospace()
+                                        .color(palette.text_muted),
+                                );
+                                ui.end_row();
+                            }
+                        });
+                    ui.add_space(10.0);
+                    ui.label(
+                        RichText::new("Relationships")
+                            .color(palette.text_muted)
+                            .strong(),
+                    );
+                    for relationship in &label.relationships {
+                        ui.horizontal(|ui| {
+                            for example in &relationship.examples {
+                                if ui
+                                    .small_button(example.title)
+                                    .on_hover_text(&example.query)
+                                    .clicked()
+                                {
+                                    *run = Some((
+                                        format!("Schema: {}", relationship.pattern()),
+                                        example.query.clone(),
+                                    ));
+                                }
+                            }
+                            ui.label(
+                                RichText::new(relationship.pattern())
+                                    .monospace()
+                                    .color(palette.text_primary),
+                            );
+                        });
+                    }
+                });
+        });
     });
 }
 
+/// Graph Pulse status of the live sync and whether it is healthy, e.g. "Connected · synced 4s
+/// ago" or "Reconnecting (attempt 3) · last sync 2m ago"; failures of the graph backend rather
+/// than the cluster read "Graph backend failing".
+fn connection_status(health: Option<&ResolverHealth>, now: Instant) -> (String, bool) {
+    let Some(health) = health else {
+        return ("Loaded once".to_string(), true);
+    };
+    let age = format_age(now.saturating_duration_since(health.last_success));
+    if health.is_connected() {
+        (format!("Connected · synced {age} ago"), true)
+    } else {
+        let state = match health.last_error_class {
+            Some(ErrorClass::Backend) => "Graph backend failing",
+            _ => "Reconnecting",
+        };
+        (
+            format!(
+                "{state} (attempt {}) · last sync {age} ago",
+                health.consecutive_failures
+            ),
+            false,
+        )
+    }
+}
+
+/// Label and hover text for the kinds the last listing could not read; `None` when it read them
+/// all.
+fn listing_failures_status(failures: &[ListingFailure]) -> Option<(String, String)> {
+    let label = match failures {
+        [] => return None,
+        [failure] => format!("{} unavailable", failure.kind),
+        _ => format!("{} kinds unavailable", failures.len()),
+    };
+    let details = failures
+        .iter()
+        .map(|failure| {
+            let state = if failure.stale { "stale" } else { "missing" };
+            format!("{} ({state}): {}", failure.kind, failure.error)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some((label, details))
+}
+
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs < 60 {
+        format!("{secs}s")
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}h", secs / 3600)
+    }
+}
+
 fn format_duration(ms: u128) -> String {
     if ms >= 1000 {
         format!("{:.2}s", ms as f64 / 1000.0)
@@ -2129,102 +6156,137 @@ fn format_duration(ms: u128) -> String {
     }
 }
 
-#[derive(Default)]
-struct UsageAccumulator {
-    prompt_tokens: u32,
-    completion_tokens: u32,
-    total_tokens: u32,
-    reasoning_tokens: Option<u32>,
-    cached_tokens: Option<u32>,
-    seen: bool,
-    reasoning_complete: bool,
-    cached_complete: bool,
-}
-
-impl UsageAccumulator {
-    fn add(&mut self, usage: Option<&LlmUsage>) {
-        let Some(usage) = usage else {
-            return;
-        };
-        if !self.seen {
-            self.seen = true;
-            self.reasoning_complete = true;
-            self.cached_complete = true;
-            self.reasoning_tokens = Some(0);
-            self.cached_tokens = Some(0);
-        }
-        self.prompt_tokens = self.prompt_tokens.saturating_add(usage.prompt_tokens);
-        self.completion_tokens = self
-            .completion_tokens
-            .saturating_add(usage.completion_tokens);
-        self.total_tokens = self.total_tokens.saturating_add(usage.total_tokens);
-
-        if !self.reasoning_complete {
-            // Already missing in a prior call; keep None.
-        } else if let Some(tokens) = usage.reasoning_tokens {
-            self.reasoning_tokens = Some(self.reasoning_tokens.unwrap_or(0) + tokens);
-        } else {
-            self.reasoning_tokens = None;
-            self.reasoning_complete = false;
-        }
-
-        if !self.cached_complete {
-            // Already missing in a prior call; keep None.
-        } else if let Some(tokens) = usage.cached_tokens {
-            self.cached_tokens = Some(self.cached_tokens.unwrap_or(0) + tokens);
-        } else {
-            self.cached_tokens = None;
-            self.cached_complete = false;
-        }
+/// "Matched names: paymets-api → payments-api (Deployment), …" for the planned query card.
+/// The agent's intermediate queries, each collapsed to its step number and what it found.
+fn render_investigation_steps(
+    ui: &mut egui::Ui,
+    item: &FeedItem,
+    palette: &Palette,
+    actions: &mut Vec<FeedAction>,
+) {
+    for (index, step) in item.investigation.iter().enumerate() {
+        let summary = match &step.tool {
+            Some(call) => truncate_text(&call.describe(), 80),
+            None => step
+                .result_summary
+                .as_deref()
+                .map(|summary| truncate_text(summary, 80))
+                .unwrap_or_else(|| step.action.as_str().to_string()),
+        };
+        let id = ui.make_persistent_id(format!("agent-step-{}-{index}", item.id));
+        egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, false)
+            .show_header(ui, |ui| {
+                ui.label(
+                    RichText::new(format!("{} {}", tr("Step"), index + 1))
+                        .size(12.0)
+                        .color(palette.text_muted)
+                        .strong(),
+                );
+                ui.label(RichText::new(summary).size(11.0).color(palette.text_muted));
+            })
+            .body(|ui| {
+                Frame::new()
+                    .fill(palette.bg_panel)
+                    .stroke(Stroke::new(1.0, palette.border))
+                    .corner_radius(CornerRadius::same(8))
+                    .inner_margin(Margin::same(8))
+                    .show(ui, |ui| {
+                        if let Some(call) = &step.tool {
+                            render_tool_call(ui, &call.describe(), step, index, item.id, palette);
+                            return;
+                        }
+                        ui.horizontal(|ui| {
+                            let mut job = highlight_cypher(&step.cypher, palette);
+                            job.wrap.max_width = ui.available_width() - 60.0;
+                            job.wrap.break_anywhere = true;
+                            ui.add(egui::Label::new(job).wrap());
+                            ui.with_layout(Layout::right_to_left(Align::Min), |ui| {
+                                if ui
+                                    .add(
+                                        egui::Button::new(
+                                            RichText::new(tr("Run")).color(palette.text_primary),
+                                        )
+                                        .fill(palette.bg_elevated)
+                                        .stroke(Stroke::new(1.0, palette.border))
+                                        .corner_radius(CornerRadius::same(6)),
+                                    )
+                                    .on_hover_text("Open this step as its own feed item")
+                                    .clicked()
+                                {
+                                    actions.push(FeedAction::RunAgentStep {
+                                        id: item.id,
+                                        step: index,
+                                    });
+                                }
+                            });
+                        });
+                        if let Some(summary) = &step.result_summary {
+                            ui.add_space(4.0);
+                            ui.label(RichText::new(summary).size(11.0).color(palette.text_muted));
+                        }
+                    });
+            });
     }
+}
 
-    fn build(&self) -> Option<LlmUsage> {
-        if !self.seen {
-            return None;
-        }
-        Some(LlmUsage {
-            prompt_tokens: self.prompt_tokens,
-            completion_tokens: self.completion_tokens,
-            total_tokens: self.total_tokens,
-            reasoning_tokens: self.reasoning_tokens,
-            cached_tokens: self.cached_tokens,
-        })
-    }
+/// A tool call of the agent and what the tool returned, which has no query to run again.
+fn render_tool_call(
+    ui: &mut egui::Ui,
+    call: &str,
+    step: &AgentStep,
+    index: usize,
+    item_id: u64,
+    palette: &Palette,
+) {
+    ui.label(
+        RichText::new(call)
+            .monospace()
+            .size(12.0)
+            .color(palette.accent),
+    );
+    let Some(output) = &step.result_summary else {
+        return;
+    };
+    ui.add_space(4.0);
+    egui::ScrollArea::vertical()
+        .id_salt(format!("agent-tool-{item_id}-{index}"))
+        .max_height(200.0)
+        .show(ui, |ui| {
+            ui.label(
+                RichText::new(output)
+                    .monospace()
+                    .size(11.0)
+                    .color(palette.text_muted),
+            );
+        });
 }
 
-fn log_llm_call(label: &str, duration_ms: u128, usage: Option<&LlmUsage>) {
-    if let Some(usage) = usage {
-        tracing::info!(
-            "{label} LLM ({duration_ms} ms) tokens prompt={} completion={} total={} cached={:?} reasoning={:?}",
-            usage.prompt_tokens,
-            usage.completion_tokens,
-            usage.total_tokens,
-            usage.cached_tokens,
-            usage.reasoning_tokens
-        );
-    } else {
-        tracing::info!("{label} LLM ({duration_ms} ms)");
-    }
+fn format_entity_hints(hints: &[EntityHint]) -> String {
+    let entries: Vec<String> = hints
+        .iter()
+        .map(|hint| format!("{} → {} ({})", hint.mentioned, hint.name, hint.kind))
+        .collect();
+    format!("Matched names: {}", entries.join(", "))
 }
 
-fn estimate_text_tokens(text: &str) -> usize {
-    let chars = text.len();
-    if chars == 0 {
-        0
+/// Dollar amount with enough precision for the sub-cent cost of a single question.
+fn format_cost(cost: f64) -> String {
+    if cost >= 1.0 {
+        format!("${cost:.2}")
     } else {
-        (chars / 4).max(1)
+        format!("${cost:.4}")
     }
 }
 
 fn estimate_turn_tokens(turn: &ConversationTurn) -> usize {
-    let mut tokens = estimate_text_tokens(&turn.question);
-    tokens += estimate_text_tokens(&turn.cypher);
+    let mut tokens = estimate_tokens(&turn.question);
+    tokens += estimate_tokens(&turn.cypher);
     if let Some(summary) = &turn.result_summary {
-        tokens += estimate_text_tokens(summary);
+        tokens += estimate_tokens(summary);
     }
     if let Some(bindings) = &turn.bindings {
         let serialized = serde_json::to_string(bindings).unwrap_or_default();
-        tokens += estimate_text_tokens(&serialized);
+        tokens += estimate_tokens(&serialized);
     }
     tokens
 }
@@ -2235,7 +6297,7 @@ fn estimate_context_tokens(turns: &[ConversationTurn], summary: Option<&str>) ->
         tokens += estimate_turn_tokens(turn);
     }
     if let Some(summary) = summary {
-        tokens += estimate_text_tokens(summary);
+        tokens += estimate_tokens(summary);
     }
     tokens
 }
@@ -2244,8 +6306,10 @@ fn render_feed_item(
     ui: &mut egui::Ui,
     item: &FeedItem,
     palette: &Palette,
-    mut on_run: impl FnMut(u64, String),
-    mut on_select: impl FnMut(&RowCard),
+    in_flight: bool,
+    pinned: bool,
+    compare_pick: Option<u64>,
+    actions: &mut Vec<FeedAction>,
 ) {
     ui.add_space(10.0);
     Frame::new()
@@ -2263,7 +6327,31 @@ fn render_feed_item(
                         .size(14.0),
                 );
                 ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
-                    ui.label(RichText::new("...").color(palette.text_muted));
+                    if in_flight {
+                        let cancel = ui.add(
+                            egui::Button::new(
+                                RichText::new(tr("Cancel"))
+                                    .size(11.0)
+                                    .color(palette.text_primary),
+                            )
+                            .fill(palette.bg_panel)
+                            .stroke(Stroke::new(1.0, palette.danger))
+                            .corner_radius(CornerRadius::same(6)),
+                        );
+                        if cancel.clicked() {
+                            actions.push(FeedAction::Cancel(item.id));
+                        }
+                    } else {
+                        ui.label(RichText::new("...").color(palette.text_muted));
+                    }
+                    if let Some(route) = item.route {
+                        ui.label(
+                            RichText::new(route_label(route, item.agent_steps))
+                                .size(11.0)
+                                .color(palette.text_muted),
+                        )
+                        .on_hover_text(format!("Router decision: {}", route.as_str()));
+                    }
                 });
             });
 
@@ -2277,11 +6365,21 @@ fn render_feed_item(
                 )
                 .show_header(ui, |ui| {
                     ui.label(
-                        RichText::new("Planned Query")
+                        RichText::new(tr("Planned Query"))
                             .size(12.0)
                             .color(palette.text_muted)
                             .strong(),
                     );
+                    if matches!(item.state, FeedState::Ready) {
+                        render_rating_buttons(
+                            ui,
+                            item.id,
+                            FeedbackTarget::Translation,
+                            item.translation_rating,
+                            palette,
+                            actions,
+                        );
+                    }
                 })
                 .body(|ui| {
                     Frame::new()
@@ -2300,7 +6398,7 @@ fn render_feed_item(
                                     if ui
                                         .add(
                                             egui::Button::new(
-                                                RichText::new("Run")
+                                                RichText::new(tr("Run"))
                                                     .color(palette.text_primary)
                                                     .strong(),
                                             )
@@ -2310,12 +6408,16 @@ fn render_feed_item(
                                         )
                                         .clicked()
                                     {
-                                        on_run(item.id, cypher.clone());
+                                        actions.push(FeedAction::Run {
+                                            id: item.id,
+                                            cypher: cypher.clone(),
+                                        });
                                     }
                                     if ui
                                         .add(
                                             egui::Button::new(
-                                                RichText::new("Copy").color(palette.text_primary),
+                                                RichText::new(tr("Copy"))
+                                                    .color(palette.text_primary),
                                             )
                                             .fill(palette.bg_elevated)
                                             .stroke(Stroke::new(1.0, palette.border))
@@ -2325,9 +6427,79 @@ fn render_feed_item(
                                     {
                                         ui.ctx().copy_text(cypher.clone());
                                     }
+                                    if matches!(item.state, FeedState::Ready) {
+                                        let watching = item.watch.is_some();
+                                        let (label, stroke) = if watching {
+                                            (tr("Stop watching"), palette.accent)
+                                        } else {
+                                            (tr("Watch"), palette.border)
+                                        };
+                                        if ui
+                                            .add(
+                                                egui::Button::new(
+                                                    RichText::new(label)
+                                                        .color(palette.text_primary),
+                                                )
+                                                .fill(palette.bg_elevated)
+                                                .stroke(Stroke::new(1.0, stroke))
+                                                .corner_radius(CornerRadius::same(6)),
+                                            )
+                                            .on_hover_text(format!(
+                                                "Re-run every {}s and highlight changed rows",
+                                                WATCH_INTERVAL.as_secs()
+                                            ))
+                                            .clicked()
+                                        {
+                                            actions.push(FeedAction::ToggleWatch(item.id));
+                                        }
+                                        let (label, stroke) = if pinned {
+                                            (tr("Unpin"), palette.accent)
+                                        } else {
+                                            (tr("Pin"), palette.border)
+                                        };
+                                        if ui
+                                            .add(
+                                                egui::Button::new(
+                                                    RichText::new(label)
+                                                        .color(palette.text_primary),
+                                                )
+                                                .fill(palette.bg_elevated)
+                                                .stroke(Stroke::new(1.0, stroke))
+                                                .corner_radius(CornerRadius::same(6)),
+                                            )
+                                            .on_hover_text(
+                                                "Keep this query on the Dashboard tab, re-run on \
+                                                 a schedule",
+                                            )
+                                            .clicked()
+                                        {
+                                            actions.push(FeedAction::TogglePin(item.id));
+                                        }
+                                    }
                                 });
                             });
+                            if let Some(reasoning) = &item.reasoning {
+                                ui.add_space(6.0);
+                                ui.label(
+                                    RichText::new(reasoning)
+                                        .size(12.0)
+                                        .color(palette.text_muted)
+                                        .italics(),
+                                );
+                            }
+                            if !item.entity_hints.is_empty() {
+                                ui.add_space(6.0);
+                                ui.label(
+                                    RichText::new(format_entity_hints(&item.entity_hints))
+                                        .size(11.0)
+                                        .color(palette.text_muted),
+                                );
+                            }
                         });
+                    if !item.investigation.is_empty() {
+                        ui.add_space(6.0);
+                        render_investigation_steps(ui, item, palette, actions);
+                    }
                 });
             }
 
@@ -2349,7 +6521,7 @@ fn render_feed_item(
                 .show(ui, |ui| match &item.state {
                     FeedState::Translating => {
                         ui.label(
-                            RichText::new("Translating...")
+                            RichText::new(tr("Translating..."))
                                 .color(palette.text_muted)
                                 .italics(),
                         );
@@ -2363,7 +6535,7 @@ fn render_feed_item(
                     }
                     FeedState::Validating => {
                         ui.label(
-                            RichText::new("Validating...")
+                            RichText::new(tr("Validating..."))
                                 .color(palette.text_muted)
                                 .italics(),
                         );
@@ -2375,7 +6547,7 @@ fn render_feed_item(
                     }
                     FeedState::Running => {
                         ui.label(
-                            RichText::new("Running...")
+                            RichText::new(tr("Running..."))
                                 .color(palette.text_muted)
                                 .italics(),
                         );
@@ -2387,17 +6559,188 @@ fn render_feed_item(
                     }
                     FeedState::Error(err) => {
                         ui.colored_label(palette.danger, format!("Error: {err}"));
+                        render_error_hint(ui, item, err, palette, actions);
+                    }
+                    FeedState::Cancelled => {
+                        ui.label(
+                            RichText::new(tr("Cancelled."))
+                                .color(palette.text_muted)
+                                .italics(),
+                        );
                     }
                     FeedState::Ready => {
-                        if render_analysis(ui, item, palette) {
-                            ui.add_space(10.0);
+                        if let Some(reply) = &item.reply {
+                            render_reply(ui, item, reply, palette);
+                        } else if !item.monitors.is_empty() {
+                            render_monitors(ui, item, palette, actions);
+                        } else {
+                            if render_analysis(ui, item, palette, actions) {
+                                ui.add_space(10.0);
+                            }
+                            render_result(ui, item, palette, compare_pick, actions);
+                            render_watch_changes(ui, item, palette);
+                            render_query_stats(ui, item, palette);
                         }
-                        render_result(ui, item, palette, &mut on_select);
                     }
                 });
         });
 }
 
+/// Evaluates `rules` on `cluster` until `token` is cancelled, with a desktop notification and
+/// an alert card for every rule that fires.
+fn spawn_alert_loop(
+    runtime: &Handle,
+    cluster: &ClusterSession,
+    rules: Vec<AlertRule>,
+    token: &CancellationToken,
+    events_tx: &mpsc::Sender<AppEvent>,
+    egui_ctx: &egui::Context,
+) {
+    let tx = events_tx.clone();
+    let ctx = egui_ctx.clone();
+    runtime.spawn(run_alert_loop(
+        cluster.label.clone(),
+        cluster.backend.clone(),
+        rules,
+        cluster.diffs.clone(),
+        token.clone(),
+        move |alert| {
+            let notification = alert.clone();
+            tokio::task::spawn_blocking(move || show_desktop_notification(&notification));
+            let _ = tx.send(AppEvent::AlertFired(alert));
+            ctx.request_repaint();
+        },
+    ));
+}
+
+/// Proposed alert rules, each with its query, condition and an Accept button.
+fn render_monitors(
+    ui: &mut egui::Ui,
+    item: &FeedItem,
+    palette: &Palette,
+    actions: &mut Vec<FeedAction>,
+) {
+    for (index, card) in item.monitors.iter().enumerate() {
+        let monitor = &card.monitor;
+        Frame::new()
+            .fill(palette.bg_primary)
+            .stroke(Stroke::new(1.0, palette.border))
+            .corner_radius(CornerRadius::same(10))
+            .inner_margin(Margin::same(12))
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(&monitor.name)
+                            .color(palette.text_primary)
+                            .strong(),
+                    );
+                    ui.label(
+                        RichText::new(&monitor.condition)
+                            .color(palette.text_muted)
+                            .monospace()
+                            .size(12.0),
+                    );
+                });
+                if !monitor.rationale.is_empty() {
+                    ui.label(
+                        RichText::new(&monitor.rationale)
+                            .color(palette.text_muted)
+                            .size(12.0),
+                    );
+                }
+                ui.add_space(4.0);
+                ui.label(RichText::new(&monitor.query).monospace().size(12.0));
+                ui.add_space(6.0);
+                match &card.status {
+                    Some(status) => {
+                        ui.label(RichText::new(status).color(palette.text_muted).size(12.0));
+                    }
+                    None => {
+                        if ui.button(tr("Accept")).clicked() {
+                            actions.push(FeedAction::AcceptMonitor { id: item.id, index });
+                        }
+                    }
+                }
+            });
+        ui.add_space(6.0);
+    }
+}
+
+/// How the router handled the item, shown in its header.
+fn route_label(route: RouteDecision, steps: Option<usize>) -> String {
+    match (route, steps) {
+        (RouteDecision::Direct, _) => "answered from context".to_string(),
+        (RouteDecision::OneShot, _) => "single query".to_string(),
+        (RouteDecision::MultiTurn, Some(steps)) => format!("agent, {steps} steps"),
+        (RouteDecision::MultiTurn, None) => "agent".to_string(),
+        (RouteDecision::Clarify, _) => "needs clarification".to_string(),
+    }
+}
+
+/// The router's answer from the conversation, or its question back to the user.
+fn render_reply(ui: &mut egui::Ui, item: &FeedItem, reply: &str, palette: &Palette) {
+    let (title, stroke) = if item.route == Some(RouteDecision::Clarify) {
+        ("Clarification needed", palette.accent)
+    } else {
+        ("From the conversation", palette.border)
+    };
+    Frame::new()
+        .fill(palette.bg_primary)
+        .stroke(Stroke::new(1.0, stroke))
+        .corner_radius(CornerRadius::same(8))
+        .inner_margin(Margin::same(12))
+        .show(ui, |ui| {
+            ui.label(
+                RichText::new(title)
+                    .color(palette.text_muted)
+                    .size(12.0)
+                    .strong(),
+            );
+            ui.add_space(6.0);
+            ui.label(RichText::new(reply).color(palette.text_primary).size(13.0));
+        });
+}
+
+/// What a known error usually means and, when a query is at fault, a button that asks the
+/// translator to correct it.
+fn render_error_hint(
+    ui: &mut egui::Ui,
+    item: &FeedItem,
+    error: &str,
+    palette: &Palette,
+    actions: &mut Vec<FeedAction>,
+) {
+    let hint = error_hint(error);
+    if let Some(hint) = hint {
+        ui.label(
+            RichText::new(hint.hint)
+                .color(palette.text_muted)
+                .size(12.0),
+        );
+    }
+    if item.cypher.is_none() || hint.is_some_and(|hint| !hint.fixable) {
+        return;
+    }
+    ui.add_space(4.0);
+    if ui
+        .add(
+            egui::Button::new(
+                RichText::new(tr("Ask the LLM to fix this query"))
+                    .size(11.0)
+                    .color(palette.text_primary),
+            )
+            .fill(palette.bg_elevated)
+            .stroke(Stroke::new(1.0, palette.border))
+            .corner_radius(CornerRadius::same(6)),
+        )
+        .on_hover_text("Sends the query and this error back to the translator")
+        .clicked()
+    {
+        actions.push(FeedAction::FixQuery(item.id));
+    }
+}
+
 fn highlight_cypher(text: &str, palette: &Palette) -> LayoutJob {
     let mut job = LayoutJob::default();
     let keywords = [
@@ -2438,8 +6781,15 @@ fn render_result(
     ui: &mut egui::Ui,
     item: &FeedItem,
     palette: &Palette,
-    on_select: &mut impl FnMut(&RowCard),
+    compare_pick: Option<u64>,
+    actions: &mut Vec<FeedAction>,
 ) {
+    if matches!(
+        item.result,
+        ResultPayload::List { .. } | ResultPayload::Raw { .. }
+    ) {
+        render_export_bar(ui, item, palette, compare_pick, actions);
+    }
     match &item.result {
         ResultPayload::Empty => {
             ui.label(
@@ -2490,50 +6840,68 @@ fn render_result(
                     color: Color32::from_black_alpha(80),
                 });
             frame.show(ui, |ui| {
-                let mut extra_keys: Vec<String> = Vec::new();
-                if let Some(first) = rows.first() {
-                    for (key, _) in &first.fields {
-                        if key == "metadata_name"
-                            || key == "metadata_namespace"
-                            || key == "status"
-                            || key == "phase"
-                            || key == "kind"
-                        {
-                            continue;
-                        }
-                        extra_keys.push(key.clone());
-                    }
-                }
+                let columns = result_columns(rows);
+                let view_id = ui.make_persistent_id(("result-table-view", item.id));
+                let mut view: TableView = ui
+                    .data_mut(|data| data.get_temp(view_id))
+                    .unwrap_or_default();
+                view.sync(&columns);
 
-                let show_title = rows.iter().any(|r| r.title != "Row");
-                let show_namespace = rows.iter().any(|r| r.subtitle.is_some());
-                let show_status = rows.iter().any(|r| r.status.is_some());
-                let mut column_labels = Vec::new();
-                if show_title {
-                    column_labels.push("Name".to_string());
-                }
-                if show_namespace {
-                    column_labels.push("Namespace".to_string());
-                }
-                if show_status {
-                    column_labels.push("Status".to_string());
-                }
-                column_labels.extend(extra_keys.clone());
+                ui.horizontal(|ui| {
+                    ui.add(
+                        TextEdit::singleline(&mut view.filter)
+                            .hint_text("Filter rows")
+                            .desired_width(220.0),
+                    );
+                    ui.menu_button("Columns", |ui| {
+                        let order = view.order.clone();
+                        for (position, column) in order.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                let mut visible = !view.hidden.contains(column);
+                                if ui.checkbox(&mut visible, column.label()).changed() {
+                                    if visible {
+                                        view.hidden.remove(column);
+                                    } else {
+                                        view.hidden.insert(column.clone());
+                                    }
+                                }
+                                if ui
+                                    .add_enabled(position > 0, egui::Button::new("◀").small())
+                                    .clicked()
+                                {
+                                    view.order.swap(position, position - 1);
+                                }
+                                if ui
+                                    .add_enabled(
+                                        position + 1 < order.len(),
+                                        egui::Button::new("▶").small(),
+                                    )
+                                    .clicked()
+                                {
+                                    view.order.swap(position, position + 1);
+                                }
+                            });
+                        }
+                    });
+                });
 
-                let mut column_defs = Vec::new();
-                if show_title {
-                    column_defs.push(Column::initial(220.0).at_least(140.0).resizable(true));
-                }
-                if show_namespace {
-                    column_defs.push(Column::initial(160.0).at_least(120.0).resizable(true));
-                }
-                if show_status {
-                    column_defs.push(Column::initial(120.0).at_least(100.0).resizable(true));
-                }
-                for _ in &extra_keys {
-                    column_defs.push(Column::initial(160.0).at_least(120.0).resizable(true));
+                let visible_columns: Vec<TableColumn> = view
+                    .order
+                    .iter()
+                    .filter(|column| !view.hidden.contains(*column))
+                    .cloned()
+                    .collect();
+                let row_indices =
+                    visible_row_indices(rows, &visible_columns, &view.filter, view.sort.as_ref());
+                if row_indices.len() != rows.len() {
+                    ui.label(
+                        RichText::new(format!("{} of {} rows", row_indices.len(), rows.len()))
+                            .color(palette.text_muted)
+                            .size(11.0),
+                    );
                 }
 
+                let mut sort_clicked: Option<TableColumn> = None;
                 ScrollArea::horizontal()
                     .auto_shrink([false; 2])
                     .show(ui, |ui| {
@@ -2544,101 +6912,640 @@ fn render_result(
                             .cell_layout(Layout::left_to_right(Align::Center))
                             .min_scrolled_height(120.0)
                             .max_scroll_height(260.0);
-                        for col in &column_defs {
-                            table = table.column(*col);
+                        for column in &visible_columns {
+                            table = table.column(column.width());
                         }
                         table
                             .header(22.0, |mut header| {
-                                for header_label in &column_labels {
+                                for column in &visible_columns {
                                     header.col(|ui| {
-                                        ui.label(
-                                            RichText::new(header_label)
-                                                .color(palette.text_muted)
-                                                .size(11.0)
-                                                .strong(),
-                                        );
+                                        let marker = match &view.sort {
+                                            Some((sorted, true)) if sorted == column => " ▲",
+                                            Some((sorted, false)) if sorted == column => " ▼",
+                                            _ => "",
+                                        };
+                                        let response = ui
+                                            .add(
+                                                egui::Label::new(
+                                                    RichText::new(format!(
+                                                        "{}{marker}",
+                                                        column.label()
+                                                    ))
+                                                    .color(palette.text_muted)
+                                                    .size(11.0)
+                                                    .strong(),
+                                                )
+                                                .sense(egui::Sense::click()),
+                                            )
+                                            .on_hover_text("Click to sort");
+                                        if response.clicked() {
+                                            sort_clicked = Some(column.clone());
+                                        }
                                     });
                                 }
                             })
                             .body(|body| {
                                 let row_height = 26.0;
-                                body.rows(row_height, rows.len(), |mut row| {
-                                    let row_index = row.index();
-                                    let row_data = &rows[row_index];
-
-                                    if show_title {
+                                body.rows(row_height, row_indices.len(), |mut row| {
+                                    let row_data = &rows[row_indices[row.index()]];
+                                    let added = item.watch.as_ref().is_some_and(|watch| {
+                                        watch.added.contains(&row_key(row_data))
+                                    });
+                                    for column in &visible_columns {
                                         row.col(|ui| {
                                             let response =
-                                                ui.selectable_label(false, &row_data.title);
-                                            if response.clicked() {
-                                                on_select(row_data);
-                                            }
-                                        });
-                                    }
-                                    if show_namespace {
-                                        let namespace = row_data.subtitle.as_deref().unwrap_or("-");
-                                        row.col(|ui| {
-                                            let response = ui.selectable_label(false, namespace);
-                                            if response.clicked() {
-                                                on_select(row_data);
-                                            }
-                                        });
-                                    }
-                                    if show_status {
-                                        let status = row_data.status.as_deref().unwrap_or("-");
-                                        row.col(|ui| {
-                                            let response = ui.selectable_label(false, status);
-                                            if response.clicked() {
-                                                on_select(row_data);
-                                            }
-                                        });
-                                    }
-                                    for key in &extra_keys {
-                                        row.col(|ui| {
-                                            let value =
-                                                find_field(&row_data.fields, key).unwrap_or("-");
-                                            let response = ui.selectable_label(false, value);
+                                                ui.selectable_label(added, column.cell(row_data));
                                             if response.clicked() {
-                                                on_select(row_data);
+                                                actions.push(FeedAction::Select(row_data.clone()));
                                             }
+                                            response.context_menu(|ui| {
+                                                render_row_context_menu(ui, row_data, actions);
+                                            });
                                         });
                                     }
                                 });
                             });
                     });
+
+                if let Some(column) = sort_clicked {
+                    view.cycle_sort(column);
+                    ui.ctx().request_repaint();
+                }
+                ui.data_mut(|data| data.insert_temp(view_id, view));
             });
         }
-        ResultPayload::Graph { nodes, edges } => {
-            Frame::new()
-                .fill(palette.bg_primary)
-                .stroke(Stroke::new(1.0, palette.border))
-                .corner_radius(CornerRadius::same(8))
-                .inner_margin(Margin::same(0))
-                .shadow(egui::Shadow {
-                    offset: [0, 6],
-                    blur: 12,
-                    spread: 0,
-                    color: Color32::from_black_alpha(80),
-                })
-                .show(ui, |ui| {
-                    let size = Vec2::new(ui.available_width(), 300.0);
-                    let (response, painter) = ui.allocate_painter(size, egui::Sense::click());
-                    draw_graph(painter, response.rect, nodes, edges, palette);
-                });
+        ResultPayload::Graph { nodes, edges } => {
+            Frame::new()
+                .fill(palette.bg_primary)
+                .stroke(Stroke::new(1.0, palette.border))
+                .corner_radius(CornerRadius::same(8))
+                .inner_margin(Margin::same(0))
+                .shadow(egui::Shadow {
+                    offset: [0, 6],
+                    blur: 12,
+                    spread: 0,
+                    color: Color32::from_black_alpha(80),
+                })
+                .show(ui, |ui| {
+                    let hidden_id = ui.make_persistent_id(("graph-hidden-kinds", item.id));
+                    let mut hidden: HashSet<Option<ResourceType>> = ui
+                        .data_mut(|data| data.get_temp(hidden_id))
+                        .unwrap_or_default();
+                    if render_graph_legend(ui, nodes, &mut hidden, palette) {
+                        ui.data_mut(|data| data.insert_temp(hidden_id, hidden.clone()));
+                    }
+                    let size = Vec2::new(ui.available_width(), 300.0);
+                    let (response, painter) =
+                        ui.allocate_painter(size, egui::Sense::click_and_drag());
+                    let lasso_id = ui.make_persistent_id(("graph-lasso", item.id));
+                    let selected_id = ui.make_persistent_id(("graph-selected", item.id));
+                    let mut lasso: Vec<egui::Pos2> = ui
+                        .data_mut(|data| data.get_temp(lasso_id))
+                        .unwrap_or_default();
+                    let mut selected: HashSet<usize> = ui
+                        .data_mut(|data| data.get_temp(selected_id))
+                        .unwrap_or_default();
+                    if response.drag_started() {
+                        lasso.clear();
+                    }
+                    if response.dragged() {
+                        if let Some(pos) = response.interact_pointer_pos() {
+                            lasso.push(pos);
+                        }
+                    }
+                    if response.drag_stopped() {
+                        let positions = graph_positions(response.rect, nodes, &hidden);
+                        selected = (0..nodes.len())
+                            .filter(|index| {
+                                positions[*index].is_some_and(|pos| lasso_contains(&lasso, pos))
+                            })
+                            .collect();
+                        lasso.clear();
+                        let mut picked: Vec<usize> = selected.iter().copied().collect();
+                        picked.sort_unstable();
+                        let picked: Vec<&GraphNode> =
+                            picked.into_iter().map(|index| &nodes[index]).collect();
+                        if let Some(query) = selection_query(&picked) {
+                            actions.push(FeedAction::FillInput(query));
+                        }
+                    } else if response.clicked() {
+                        selected.clear();
+                    }
+                    draw_graph(
+                        painter.clone(),
+                        response.rect,
+                        nodes,
+                        edges,
+                        &hidden,
+                        &selected,
+                        palette,
+                    );
+                    if lasso.len() > 1 {
+                        let mut outline = lasso.clone();
+                        outline.push(lasso[0]);
+                        painter.add(egui::Shape::line(outline, Stroke::new(1.5, palette.accent)));
+                    }
+                    if selected.is_empty() {
+                        response.on_hover_text(
+                            "Drag around nodes to select them and get a query for them",
+                        );
+                    }
+                    ui.data_mut(|data| {
+                        data.insert_temp(lasso_id, lasso);
+                        data.insert_temp(selected_id, selected);
+                    });
+                });
+        }
+        ResultPayload::Raw { text } => {
+            let mut display = text.clone();
+            ui.add_sized(
+                [ui.available_width(), 120.0],
+                TextEdit::multiline(&mut display)
+                    .font(TextStyle::Monospace)
+                    .interactive(false),
+            );
+        }
+    }
+}
+
+fn render_export_bar(
+    ui: &mut egui::Ui,
+    item: &FeedItem,
+    palette: &Palette,
+    compare_pick: Option<u64>,
+    actions: &mut Vec<FeedAction>,
+) {
+    ui.horizontal(|ui| {
+        if let Some(status) = &item.export_status {
+            ui.label(RichText::new(status).size(11.0).color(palette.text_muted));
+        }
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            let picked = compare_pick == Some(item.id);
+            let (label, hint) = match compare_pick {
+                Some(_) if picked => (
+                    "Comparing…",
+                    "Click Compare on another result, or here again to cancel",
+                ),
+                Some(_) => ("Compare with picked", "Show the rows that differ"),
+                None => (
+                    tr("Compare"),
+                    "Pick this result, then another one to see the rows that differ",
+                ),
+            };
+            if ui
+                .add(
+                    egui::Button::new(RichText::new(label).size(11.0).color(if picked {
+                        palette.accent
+                    } else {
+                        palette.text_primary
+                    }))
+                    .fill(palette.bg_elevated)
+                    .stroke(Stroke::new(
+                        1.0,
+                        if picked {
+                            palette.accent
+                        } else {
+                            palette.border
+                        },
+                    ))
+                    .corner_radius(CornerRadius::same(6)),
+                )
+                .on_hover_text(hint)
+                .clicked()
+            {
+                actions.push(FeedAction::Compare(item.id));
+            }
+            if ui
+                .add(
+                    egui::Button::new(
+                        RichText::new(tr("Timeline"))
+                            .size(11.0)
+                            .color(palette.text_primary),
+                    )
+                    .fill(palette.bg_elevated)
+                    .stroke(Stroke::new(1.0, palette.border))
+                    .corner_radius(CornerRadius::same(6)),
+                )
+                .on_hover_text("Events and creation times of the objects in this result")
+                .clicked()
+            {
+                actions.push(FeedAction::OpenTimeline(item.id));
+            }
+            for format in [ExportFormat::Json, ExportFormat::Csv] {
+                if ui
+                    .add(
+                        egui::Button::new(
+                            RichText::new(format!("Export {}", format.label()))
+                                .size(11.0)
+                                .color(palette.text_primary),
+                        )
+                        .fill(palette.bg_elevated)
+                        .stroke(Stroke::new(1.0, palette.border))
+                        .corner_radius(CornerRadius::same(6)),
+                    )
+                    .clicked()
+                {
+                    actions.push(FeedAction::Export {
+                        id: item.id,
+                        format,
+                    });
+                }
+            }
+        });
+    });
+}
+
+fn timeline_color(kind: TimelineKind, palette: &Palette) -> Color32 {
+    match kind {
+        TimelineKind::Created => palette.success,
+        TimelineKind::Normal => palette.accent,
+        TimelineKind::Warning => palette.danger,
+    }
+}
+
+/// Plots `entries` (sorted by time) on a horizontal axis, one lane per kind. Hovering a marker
+/// shows the entry.
+/// The model configuration the Settings window asks for, or `None` when it is the active one.
+/// Unset fields keep the active value; the API key is dropped when the endpoint changes.
+fn settings_llm_config(
+    active: &LlmConfig,
+    connection: &ConnectionSettings,
+) -> CliResult<Option<LlmConfig>> {
+    let backend = match &connection.llm_backend {
+        Some(name) => name
+            .parse::<LLMBackend>()
+            .map_err(|err| format!("Unknown LLM backend '{name}': {err}"))?,
+        None => active.backend.clone(),
+    };
+    let base_url = connection
+        .llm_base_url
+        .clone()
+        .unwrap_or_else(|| active.base_url.clone());
+    let api_key = match &connection.llm_api_key {
+        Some(api_key) => Some(api_key.clone()),
+        None if base_url == active.base_url => active.api_key.clone(),
+        None => None,
+    };
+    let config = LlmConfig {
+        backend,
+        model: connection
+            .llm_model
+            .clone()
+            .unwrap_or_else(|| active.model.clone()),
+        base_url,
+        api_key,
+        answer_language: connection.answer_language.clone(),
+        ..active.clone()
+    };
+    let changed = connection.llm_backend.is_some()
+        || config.model != active.model
+        || config.base_url != active.base_url
+        || config.api_key != active.api_key
+        || config.answer_language != active.answer_language;
+    Ok(changed.then_some(config))
+}
+
+fn render_timeline_plot(ui: &mut egui::Ui, entries: &[TimelineEntry], palette: &Palette) {
+    let (rect, response) = ui.allocate_exact_size(
+        Vec2::new(ui.available_width(), TIMELINE_PLOT_HEIGHT),
+        egui::Sense::hover(),
+    );
+    let painter = ui.painter_at(rect);
+    let (Some(first), Some(last)) = (entries.first(), entries.last()) else {
+        return;
+    };
+    let span = (last.time - first.time).num_milliseconds().max(1) as f32;
+    let left = rect.left() + 8.0;
+    let width = rect.width() - 16.0;
+    let axis_y = rect.bottom() - 16.0;
+    painter.line_segment(
+        [egui::pos2(left, axis_y), egui::pos2(left + width, axis_y)],
+        Stroke::new(1.0, palette.border),
+    );
+    let font = FontId::new(10.0, FontFamily::Proportional);
+    painter.text(
+        egui::pos2(left, rect.bottom()),
+        Align2::LEFT_BOTTOM,
+        first.time.format("%m-%d %H:%M:%S").to_string(),
+        font.clone(),
+        palette.text_muted,
+    );
+    painter.text(
+        egui::pos2(left + width, rect.bottom()),
+        Align2::RIGHT_BOTTOM,
+        last.time.format("%m-%d %H:%M:%S").to_string(),
+        font,
+        palette.text_muted,
+    );
+
+    let hover = response.hover_pos();
+    let mut hovered: Option<(f32, &TimelineEntry)> = None;
+    for entry in entries {
+        let offset = (entry.time - first.time).num_milliseconds() as f32 / span;
+        let lane = match entry.kind {
+            TimelineKind::Created => 0.0,
+            TimelineKind::Normal => 1.0,
+            TimelineKind::Warning => 2.0,
+        };
+        let point = egui::pos2(left + offset * width, rect.top() + 8.0 + lane * 12.0);
+        painter.line_segment(
+            [point, egui::pos2(point.x, axis_y)],
+            Stroke::new(0.5, palette.border),
+        );
+        painter.circle_filled(point, 4.0, timeline_color(entry.kind, palette));
+        if let Some(pointer) = hover {
+            let distance = pointer.distance(point);
+            if distance < 8.0 && hovered.is_none_or(|(best, _)| distance < best) {
+                hovered = Some((distance, entry));
+            }
+        }
+    }
+    if let Some((_, entry)) = hovered {
+        response.on_hover_text_at_pointer(format!(
+            "{} · {} {}\n{}",
+            entry.time.format("%Y-%m-%d %H:%M:%S"),
+            entry.reason,
+            entry.resource,
+            entry.note
+        ));
+    }
+}
+
+/// The profiling overlay: engine counters and clause timings, or the backend's own profile
+/// rows when they are not in the in-memory engine's `stat`/`value`/`unit` shape.
+fn render_query_stats(ui: &mut egui::Ui, item: &FeedItem, palette: &Palette) {
+    let Some(stats) = &item.query_stats else {
+        return;
+    };
+    ui.add_space(8.0);
+    ui.label(
+        RichText::new("Query statistics")
+            .color(palette.text_muted)
+            .size(11.0)
+            .strong(),
+    );
+    match stats {
+        Err(error) => {
+            ui.label(
+                RichText::new(format!("Profiling failed: {error}"))
+                    .color(palette.danger)
+                    .size(11.0),
+            );
+        }
+        Ok(rows) => match query_stats_lines(rows) {
+            Some((counters, timings)) => {
+                for line in [counters, timings] {
+                    ui.label(
+                        RichText::new(line)
+                            .monospace()
+                            .color(palette.text_muted)
+                            .size(11.0),
+                    );
+                }
+            }
+            None => render_analysis_rows(ui, rows, palette),
+        },
+    }
+}
+
+/// Counters and the clause timings that took any time, one line each, from the in-memory
+/// engine's profile rows. `None` when the rows have another shape.
+fn query_stats_lines(rows: &[Value]) -> Option<(String, String)> {
+    let mut counters = Vec::new();
+    let mut timings = Vec::new();
+    for row in rows {
+        let stat = row.get("stat")?.as_str()?;
+        let value = row.get("value")?.as_u64()?;
+        let label = stat.replace('_', " ");
+        match row.get("unit")?.as_str()? {
+            "ms" if value > 0 => timings.push(format!("{label} {value} ms")),
+            "ms" => {}
+            _ => counters.push(format!("{label} {value}")),
+        }
+    }
+    if counters.is_empty() {
+        return None;
+    }
+    let timings = if timings.is_empty() {
+        "every stage under 1 ms".to_string()
+    } else {
+        timings.join(" · ")
+    };
+    Some((counters.join(" · "), timings))
+}
+
+fn render_watch_changes(ui: &mut egui::Ui, item: &FeedItem, palette: &Palette) {
+    let Some(watch) = &item.watch else {
+        return;
+    };
+    ui.add_space(8.0);
+    ui.horizontal_wrapped(|ui| {
+        ui.label(
+            RichText::new("LIVE")
+                .color(palette.success)
+                .size(11.0)
+                .strong(),
+        );
+        let summary = if watch.refreshes == 0 {
+            format!(
+                "every {}s • waiting for first refresh",
+                WATCH_INTERVAL.as_secs()
+            )
+        } else {
+            format!(
+                "every {}s • {} refreshes • +{} / -{} rows",
+                WATCH_INTERVAL.as_secs(),
+                watch.refreshes,
+                watch.added.len(),
+                watch.removed.len()
+            )
+        };
+        ui.label(RichText::new(summary).color(palette.text_muted).size(11.0));
+        if let Some(error) = &watch.error {
+            ui.label(
+                RichText::new(format!("refresh failed: {error}"))
+                    .color(palette.danger)
+                    .size(11.0),
+            );
+        }
+    });
+    for removed in watch.removed.iter().take(WATCH_MAX_REMOVED_SHOWN) {
+        ui.label(
+            RichText::new(format!("- {}", truncate_text(&format_value(removed), 160)))
+                .color(palette.danger)
+                .size(11.0)
+                .monospace(),
+        );
+    }
+    if watch.removed.len() > WATCH_MAX_REMOVED_SHOWN {
+        ui.label(
+            RichText::new(format!(
+                "... and {} more removed",
+                watch.removed.len() - WATCH_MAX_REMOVED_SHOWN
+            ))
+            .color(palette.text_muted)
+            .size(11.0),
+        );
+    }
+}
+
+/// Changed rows with their before and after values per column, then added and removed rows.
+fn render_compare_rows(ui: &mut egui::Ui, comparison: &RecordComparison, palette: &Palette) {
+    for changed in comparison.changed.iter().take(COMPARE_MAX_ROWS_SHOWN) {
+        ui.label(
+            RichText::new(format!("~ {}", changed.identity))
+                .color(palette.accent_warm)
+                .monospace()
+                .size(11.0),
+        );
+        for column in &changed.columns {
+            let value = |record: &Value| {
+                record
+                    .get(column)
+                    .map(|value| truncate_text(&format_value(value), 72))
+                    .unwrap_or_else(|| "—".to_string())
+            };
+            ui.label(
+                RichText::new(format!(
+                    "    {column}: {} → {}",
+                    value(&changed.before),
+                    value(&changed.after)
+                ))
+                .color(palette.text_primary)
+                .monospace()
+                .size(11.0),
+            );
         }
-        ResultPayload::Raw { text } => {
-            let mut display = text.clone();
-            ui.add_sized(
-                [ui.available_width(), 120.0],
-                TextEdit::multiline(&mut display)
-                    .font(TextStyle::Monospace)
-                    .interactive(false),
+    }
+    for (sign, rows, color) in [
+        ("+", &comparison.added, palette.success),
+        ("-", &comparison.removed, palette.danger),
+    ] {
+        for row in rows.iter().take(COMPARE_MAX_ROWS_SHOWN) {
+            ui.label(
+                RichText::new(format!("{sign} {}", truncate_text(&format_value(row), 160)))
+                    .color(color)
+                    .monospace()
+                    .size(11.0),
             );
         }
     }
+    let hidden = [
+        comparison.changed.len(),
+        comparison.added.len(),
+        comparison.removed.len(),
+    ]
+    .iter()
+    .map(|count| count.saturating_sub(COMPARE_MAX_ROWS_SHOWN))
+    .sum::<usize>();
+    if hidden > 0 {
+        ui.label(
+            RichText::new(format!("... and {hidden} more rows"))
+                .color(palette.text_muted)
+                .size(11.0),
+        );
+    }
+}
+
+fn record_key(record: &Value) -> String {
+    record.to_string()
+}
+
+fn row_key(row: &RowCard) -> String {
+    record_key(&Value::Object(row.raw_fields.iter().cloned().collect()))
+}
+
+/// Compares two result sets by row content: returns keys of new rows and the rows that vanished.
+fn diff_records(previous: &[Value], current: &[Value]) -> (HashSet<String>, Vec<Value>) {
+    let previous_keys: HashSet<String> = previous.iter().map(record_key).collect();
+    let current_keys: HashSet<String> = current.iter().map(record_key).collect();
+    let added = current_keys.difference(&previous_keys).cloned().collect();
+    let removed = previous
+        .iter()
+        .filter(|record| !current_keys.contains(&record_key(record)))
+        .cloned()
+        .collect();
+    (added, removed)
+}
+
+fn result_records(result: &ResultPayload) -> Option<Vec<Value>> {
+    match result {
+        ResultPayload::List { rows } => Some(
+            rows.iter()
+                .map(|row| Value::Object(row.raw_fields.iter().cloned().collect()))
+                .collect(),
+        ),
+        ResultPayload::Raw { text } => Some(
+            serde_json::from_str::<Vec<Value>>(text)
+                .unwrap_or_else(|_| vec![Value::String(text.clone())]),
+        ),
+        _ => None,
+    }
+}
+
+fn report_entry(item: &FeedItem) -> ReportEntry {
+    let records = match &item.result {
+        ResultPayload::Metric { label, value, unit } => {
+            let value = match unit {
+                Some(unit) => format!("{value} {unit}"),
+                None => value.clone(),
+            };
+            let mut record = Map::new();
+            record.insert(label.clone(), Value::String(value));
+            vec![Value::Object(record)]
+        }
+        result => result_records(result).unwrap_or_default(),
+    };
+    ReportEntry {
+        asked_at: item.asked_at,
+        question: item.user_text.clone(),
+        cypher: item.cypher.clone(),
+        records,
+        analysis: item.analysis.clone(),
+        error: match &item.state {
+            FeedState::Error(err) => Some(err.clone()),
+            _ => None,
+        },
+    }
+}
+
+fn render_rating_buttons(
+    ui: &mut egui::Ui,
+    id: u64,
+    target: FeedbackTarget,
+    current: Option<Rating>,
+    palette: &Palette,
+    actions: &mut Vec<FeedAction>,
+) {
+    let hint = match target {
+        FeedbackTarget::Translation => "query",
+        FeedbackTarget::Analysis => "answer",
+    };
+    for (rating, icon, color, verb) in [
+        (Rating::Up, "👍", palette.success, "Good"),
+        (Rating::Down, "👎", palette.danger, "Wrong"),
+    ] {
+        let color = if current == Some(rating) {
+            color
+        } else {
+            palette.text_muted
+        };
+        if ui
+            .add(egui::Button::new(RichText::new(icon).size(12.0).color(color)).frame(false))
+            .on_hover_text(format!("{verb} {hint}"))
+            .clicked()
+        {
+            actions.push(FeedAction::Rate { id, target, rating });
+        }
+    }
 }
 
-fn render_analysis(ui: &mut egui::Ui, item: &FeedItem, palette: &Palette) -> bool {
+fn render_analysis(
+    ui: &mut egui::Ui,
+    item: &FeedItem,
+    palette: &Palette,
+    actions: &mut Vec<FeedAction>,
+) -> bool {
     if !item.analysis_pending && item.analysis.is_none() && item.analysis_error.is_none() {
         return false;
     }
@@ -2649,15 +7556,27 @@ fn render_analysis(ui: &mut egui::Ui, item: &FeedItem, palette: &Palette) -> boo
         .corner_radius(CornerRadius::same(8))
         .inner_margin(Margin::same(12))
         .show(ui, |ui| {
-            ui.label(
-                RichText::new("SRE Answer")
-                    .color(palette.text_muted)
-                    .size(12.0)
-                    .strong(),
-            );
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new(tr("SRE Answer"))
+                        .color(palette.text_muted)
+                        .size(12.0)
+                        .strong(),
+                );
+                if item.analysis.is_some() && !item.analysis_pending {
+                    render_rating_buttons(
+                        ui,
+                        item.id,
+                        FeedbackTarget::Analysis,
+                        item.analysis_rating,
+                        palette,
+                        actions,
+                    );
+                }
+            });
             ui.add_space(8.0);
 
-            if item.analysis_pending {
+            if item.analysis_pending && item.analysis.is_none() {
                 ui.label(
                     RichText::new("Analyzing results...")
                         .color(palette.text_muted)
@@ -2715,24 +7634,55 @@ fn render_analysis(ui: &mut egui::Ui, item: &FeedItem, palette: &Palette) -> boo
                     render_analysis_rows(ui, &analysis.rows, palette);
                 }
 
-                if !analysis.follow_ups.is_empty() {
+                if !analysis.citations.is_empty() {
                     ui.add_space(10.0);
                     ui.label(
-                        RichText::new("Follow-ups")
+                        RichText::new("Sources")
                             .color(palette.text_muted)
                             .size(12.0)
                             .strong(),
                     );
                     ui.add_space(4.0);
-                    for follow in &analysis.follow_ups {
-                        ui.label(
-                            RichText::new(format!("• {follow}"))
-                                .color(palette.text_primary)
+                    for citation in &analysis.citations {
+                        ui.hyperlink_to(
+                            RichText::new(format!("[{}] {}", citation.number, citation.title))
                                 .size(12.0),
+                            &citation.url,
                         );
                     }
                 }
 
+                if item.analysis_pending {
+                    ui.add_space(8.0);
+                    let width = ui.available_width() * 0.5;
+                    skeleton_line(ui, width, palette);
+                }
+
+                if !item.follow_ups.is_empty() {
+                    ui.add_space(10.0);
+                    ui.label(
+                        RichText::new(tr("Follow-ups"))
+                            .color(palette.text_muted)
+                            .size(12.0)
+                            .strong(),
+                    );
+                    ui.add_space(4.0);
+                    for follow in &item.follow_ups {
+                        let button = egui::Button::new(
+                            RichText::new(format!("→ {}", follow.question))
+                                .color(palette.accent)
+                                .size(12.0),
+                        )
+                        .frame(false);
+                        if ui.add(button).on_hover_text(&follow.cypher).clicked() {
+                            actions.push(FeedAction::FollowUp {
+                                parent: item.id,
+                                follow_up: follow.clone(),
+                            });
+                        }
+                    }
+                }
+
                 if item.analysis_duration_ms.is_some()
                     || analysis.usage.is_some()
                     || !analysis.confidence.is_empty()
@@ -2856,11 +7806,172 @@ fn render_analysis_rows(ui: &mut egui::Ui, rows: &[Value], palette: &Palette) {
     }
 }
 
+/// Fixed color per resource kind, so a kind looks the same in every graph. Nodes of an unknown
+/// kind use the accent color.
+fn kind_color(kind: Option<&ResourceType>, palette: &Palette) -> Color32 {
+    const KIND_COLORS: [Color32; 12] = [
+        Color32::from_rgb(0x4F, 0x9B, 0xD9),
+        Color32::from_rgb(0xE0, 0x8A, 0x3C),
+        Color32::from_rgb(0x5C, 0xB8, 0x6B),
+        Color32::from_rgb(0xD9, 0x5B, 0x5B),
+        Color32::from_rgb(0x9B, 0x7B, 0xD4),
+        Color32::from_rgb(0xC9, 0xA2, 0x27),
+        Color32::from_rgb(0x3F, 0xB8, 0xB0),
+        Color32::from_rgb(0xD4, 0x6F, 0xB0),
+        Color32::from_rgb(0x8C, 0x9A, 0x3E),
+        Color32::from_rgb(0x6E, 0x8B, 0xA8),
+        Color32::from_rgb(0xB8, 0x7A, 0x58),
+        Color32::from_rgb(0x7F, 0xC4, 0xE8),
+    ];
+    match kind {
+        Some(kind) => {
+            let index = ResourceType::iter()
+                .position(|candidate| &candidate == kind)
+                .unwrap_or_default();
+            KIND_COLORS[index % KIND_COLORS.len()]
+        }
+        None => palette.accent,
+    }
+}
+
+/// One toggle per kind in the graph, with its color and node count. Returns true when a kind
+/// was shown or hidden.
+fn render_graph_legend(
+    ui: &mut egui::Ui,
+    nodes: &[GraphNode],
+    hidden: &mut HashSet<Option<ResourceType>>,
+    palette: &Palette,
+) -> bool {
+    let mut counts: BTreeMap<Option<ResourceType>, usize> = BTreeMap::new();
+    for node in nodes {
+        *counts.entry(node.kind.clone()).or_default() += 1;
+    }
+    let mut changed = false;
+    ui.horizontal_wrapped(|ui| {
+        ui.add_space(8.0);
+        for (kind, count) in counts {
+            let shown = !hidden.contains(&kind);
+            let name = kind
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| "Other".to_string());
+            let color = if shown {
+                kind_color(kind.as_ref(), palette)
+            } else {
+                palette.text_muted.gamma_multiply(0.5)
+            };
+            let text = RichText::new(format!("● {name} ({count})"))
+                .color(color)
+                .size(11.0);
+            let response = ui.selectable_label(shown, text).on_hover_text(if shown {
+                "Hide this kind"
+            } else {
+                "Show this kind"
+            });
+            if response.clicked() {
+                if shown {
+                    hidden.insert(kind);
+                } else {
+                    hidden.remove(&kind);
+                }
+                changed = true;
+            }
+        }
+    });
+    changed
+}
+
+/// Positions of the nodes on a circle; hidden nodes get none.
+fn graph_positions(
+    rect: egui::Rect,
+    nodes: &[GraphNode],
+    hidden: &HashSet<Option<ResourceType>>,
+) -> Vec<Option<egui::Pos2>> {
+    let center = rect.center();
+    let radius = rect.width().min(rect.height()) * 0.35;
+    let visible = nodes
+        .iter()
+        .filter(|node| !hidden.contains(&node.kind))
+        .count();
+    let mut slot = 0;
+    nodes
+        .iter()
+        .map(|node| {
+            if hidden.contains(&node.kind) {
+                return None;
+            }
+            let angle = slot as f32 / visible as f32 * std::f32::consts::TAU;
+            slot += 1;
+            Some(egui::pos2(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            ))
+        })
+        .collect()
+}
+
+/// Whether `point` lies inside the closed lasso outline (even-odd rule).
+fn lasso_contains(lasso: &[egui::Pos2], point: egui::Pos2) -> bool {
+    if lasso.len() < 3 {
+        return false;
+    }
+    let mut inside = false;
+    let mut previous = lasso[lasso.len() - 1];
+    for &current in lasso {
+        if (current.y > point.y) != (previous.y > point.y) {
+            let crossing = current.x
+                + (point.y - current.y) / (previous.y - current.y) * (previous.x - current.x);
+            if point.x < crossing {
+                inside = !inside;
+            }
+        }
+        previous = current;
+    }
+    inside
+}
+
+/// A query scaffold for the selected graph nodes: by UID when every node has one, by name
+/// otherwise, with the label when they all share a kind.
+fn selection_query(nodes: &[&GraphNode]) -> Option<String> {
+    let first = nodes.first()?;
+    let label = if nodes.iter().all(|node| node.kind == first.kind) {
+        first
+            .kind
+            .as_ref()
+            .map(|kind| format!(":{kind}"))
+            .unwrap_or_default()
+    } else {
+        String::new()
+    };
+    let (property, values): (&str, Vec<&String>) =
+        if let Some(uids) = nodes.iter().map(|node| node.uid.as_ref()).collect() {
+            ("uid", uids)
+        } else {
+            (
+                "name",
+                nodes
+                    .iter()
+                    .map(|node| node.name.as_ref().unwrap_or(&node.label))
+                    .collect(),
+            )
+        };
+    let values: Vec<String> = values
+        .into_iter()
+        .map(|value| Value::String(value.clone()).to_string())
+        .collect();
+    Some(format!(
+        "MATCH (n{label}) WHERE n.metadata.{property} IN [{}] RETURN n",
+        values.join(", ")
+    ))
+}
+
 fn draw_graph(
     painter: egui::Painter,
     rect: egui::Rect,
     nodes: &[GraphNode],
     edges: &[GraphEdge],
+    hidden: &HashSet<Option<ResourceType>>,
+    selected: &HashSet<usize>,
     palette: &Palette,
 ) {
     if nodes.is_empty() {
@@ -2873,10 +7984,6 @@ fn draw_graph(
         );
         return;
     }
-    let center = rect.center();
-    // Use a slightly better layout: distribute on circle
-    let radius = rect.width().min(rect.height()) * 0.35;
-
     // Draw background grid (subtle cyber aesthetic)
     let grid_step = 30.0;
     let grid_color = palette.bg_elevated.gamma_multiply(0.4);
@@ -2897,18 +8004,13 @@ fn draw_graph(
         y += grid_step;
     }
 
-    let positions: Vec<egui::Pos2> = (0..nodes.len())
-        .map(|idx| {
-            let angle = idx as f32 / nodes.len() as f32 * std::f32::consts::TAU;
-            egui::pos2(
-                center.x + radius * angle.cos(),
-                center.y + radius * angle.sin(),
-            )
-        })
-        .collect();
+    // Hidden nodes get no position, which also drops their edges.
+    let positions = graph_positions(rect, nodes, hidden);
 
     for edge in edges {
-        if let (Some(from), Some(to)) = (positions.get(edge.from), positions.get(edge.to)) {
+        if let (Some(Some(from)), Some(Some(to))) =
+            (positions.get(edge.from), positions.get(edge.to))
+        {
             // Glowing Edges
             painter.line_segment(
                 [*from, *to],
@@ -2943,18 +8045,18 @@ fn draw_graph(
     }
 
     for (idx, node) in nodes.iter().enumerate() {
-        if let Some(pos) = positions.get(idx) {
+        if let Some(Some(pos)) = positions.get(idx) {
+            let color = kind_color(node.kind.as_ref(), palette);
             // Cyber Node: Glow + Core
             // Outer Glow
-            painter.circle_filled(*pos, 14.0, palette.accent.gamma_multiply(0.1));
-            painter.circle_stroke(
-                *pos,
-                12.0,
-                Stroke::new(1.0, palette.accent.gamma_multiply(0.5)),
-            );
+            painter.circle_filled(*pos, 14.0, color.gamma_multiply(0.1));
+            painter.circle_stroke(*pos, 12.0, Stroke::new(1.0, color.gamma_multiply(0.5)));
 
             // Core
-            painter.circle_filled(*pos, 5.0, palette.accent);
+            painter.circle_filled(*pos, 5.0, color);
+            if selected.contains(&idx) {
+                painter.circle_stroke(*pos, 16.0, Stroke::new(2.0, palette.accent));
+            }
 
             // Label
             painter.text(
@@ -2973,201 +8075,37 @@ fn classify_result(records: &[Value]) -> ResultPayload {
         return ResultPayload::Empty;
     }
 
-    if let Some(graph) = parse_graph_payload(records) {
-        return graph;
-    }
-
-    if records.len() == 1 {
-        if let Some(obj) = records[0].as_object() {
-            if obj.len() == 1 {
-                if let Some((label, value)) = obj.iter().next() {
-                    let value_str = format_value(value);
-                    return ResultPayload::Metric {
-                        label: label.clone(),
-                        value: value_str,
-                        unit: None,
-                    };
-                }
-            }
-        }
-    }
-
-    if records.iter().all(|value| value.is_object()) {
-        let rows = records
-            .iter()
-            .filter_map(|value| value.as_object())
-            .map(summarize_row)
-            .collect();
-        return ResultPayload::List { rows };
-    }
-
-    ResultPayload::Raw {
-        text: serde_json::to_string_pretty(records).unwrap_or_else(|_| "[]".to_string()),
-    }
-}
-
-fn summarize_records(records: &[Value]) -> String {
-    if records.is_empty() {
-        return "rows=0".to_string();
-    }
-
-    let rows = records.len();
-    let mut columns: Vec<String> = records
-        .first()
-        .and_then(|v| v.as_object())
-        .map(|obj| obj.keys().cloned().collect())
-        .unwrap_or_default();
-    columns.sort();
-
-    let mut summary = format!("rows={rows}");
-    if !columns.is_empty() {
-        summary.push_str(", columns=");
-        summary.push_str(&columns.join(","));
-    }
-
-    let samples: Vec<String> = records
-        .iter()
-        .take(2)
-        .map(|value| summarize_record(value, &columns))
-        .collect();
-    if !samples.is_empty() {
-        summary.push_str("; sample=");
-        summary.push_str(&samples.join(" | "));
-    }
-
-    truncate_text(&summary, 400)
-}
-
-fn merge_params(
-    params: Option<HashMap<String, Value>>,
-    context: &[ConversationTurn],
-) -> Option<HashMap<String, Value>> {
-    let mut merged = params.unwrap_or_default();
-    if let Some(turn) = context.last() {
-        if let Some(bindings) = &turn.bindings {
-            for (key, value) in bindings {
-                merged.entry(key.clone()).or_insert_with(|| value.clone());
-            }
-        }
-    }
-    if merged.is_empty() {
-        None
-    } else {
-        Some(merged)
-    }
-}
-
-fn extract_context_bindings(records: &[Value]) -> Option<HashMap<String, Value>> {
-    if records.is_empty() {
-        return None;
-    }
-    let first = records.first().and_then(|value| value.as_object())?;
-    let columns: std::collections::HashSet<String> = first.keys().cloned().collect();
-    let has_pod = columns.contains("pod") || columns.contains("pod_name");
-    let has_service = columns.contains("service") || columns.contains("service_name");
-
-    let mut bindings = HashMap::new();
-
-    if let Some(value) = extract_uniform_value(records, &["pod", "pod_name"]) {
-        bindings.insert("pod_name".to_string(), value);
-    }
-    if has_pod {
-        if let Some(value) = extract_uniform_value(records, &["pod_namespace", "namespace"]) {
-            bindings.insert("pod_namespace".to_string(), value);
-        }
-    }
-    if let Some(value) = extract_uniform_value(records, &["service", "service_name"]) {
-        bindings.insert("service_name".to_string(), value);
-    }
-    if has_service {
-        if let Some(value) = extract_uniform_value(records, &["service_namespace"]) {
-            bindings.insert("service_namespace".to_string(), value);
-        } else if !has_pod {
-            if let Some(value) = extract_uniform_value(records, &["namespace"]) {
-                bindings.insert("service_namespace".to_string(), value);
-            }
-        }
-    }
-    if let Some(value) = extract_uniform_value(records, &["ingress", "ingress_name"]) {
-        bindings.insert("ingress_name".to_string(), value);
-    }
-    if let Some(value) = extract_uniform_value(records, &["ingress_namespace"]) {
-        bindings.insert("ingress_namespace".to_string(), value);
-    }
-    if let Some(value) = extract_uniform_value(records, &["host", "hostname"]) {
-        bindings.insert("host".to_string(), value);
-    }
-
-    if bindings.is_empty() {
-        None
-    } else {
-        Some(bindings)
+    if let Some(graph) = parse_graph_payload(records) {
+        return graph;
     }
-}
 
-fn extract_uniform_value(records: &[Value], keys: &[&str]) -> Option<Value> {
-    for key in keys {
-        let mut value: Option<Value> = None;
-        let mut count = 0usize;
-        for record in records {
-            let obj = record.as_object()?;
-            let entry = match obj.get(*key) {
-                Some(entry) => entry,
-                None => {
-                    value = None;
-                    count = 0;
-                    break;
-                }
-            };
-            count += 1;
-            match &value {
-                None => value = Some(entry.clone()),
-                Some(existing) if existing == entry => {}
-                Some(_) => {
-                    value = None;
-                    break;
+    if records.len() == 1 {
+        if let Some(obj) = records[0].as_object() {
+            if obj.len() == 1 {
+                if let Some((label, value)) = obj.iter().next() {
+                    let value_str = format_value(value);
+                    return ResultPayload::Metric {
+                        label: label.clone(),
+                        value: value_str,
+                        unit: None,
+                    };
                 }
             }
         }
-        if count == records.len() {
-            if let Some(found) = value {
-                return Some(found);
-            }
-        }
     }
-    None
-}
 
-fn summarize_record(value: &Value, columns: &[String]) -> String {
-    if let Some(obj) = value.as_object() {
-        let keys: Vec<String> = if columns.is_empty() {
-            let mut keys: Vec<String> = obj.keys().cloned().collect();
-            keys.sort();
-            keys
-        } else {
-            columns.to_vec()
-        };
-        let mut parts = Vec::new();
-        for key in keys.into_iter().take(6) {
-            let entry = obj
-                .get(&key)
-                .map(format_value)
-                .unwrap_or_else(|| "null".to_string());
-            parts.push(format!("{key}={}", truncate_text(&entry, 60)));
-        }
-        return parts.join(", ");
+    if records.iter().all(|value| value.is_object()) {
+        let rows = records
+            .iter()
+            .filter_map(|value| value.as_object())
+            .map(summarize_row)
+            .collect();
+        return ResultPayload::List { rows };
     }
 
-    truncate_text(&format_value(value), 120)
-}
-
-fn truncate_text(text: &str, max_len: usize) -> String {
-    if text.len() <= max_len {
-        return text.to_string();
+    ResultPayload::Raw {
+        text: serde_json::to_string_pretty(records).unwrap_or_else(|_| "[]".to_string()),
     }
-    let mut trimmed = text[..max_len.saturating_sub(3)].to_string();
-    trimmed.push_str("...");
-    trimmed
 }
 
 fn parse_graph_payload(records: &[Value]) -> Option<ResultPayload> {
@@ -3195,7 +8133,22 @@ fn parse_graph_payload(records: &[Value]) -> Option<ResultPayload> {
                         .map(|v| v.to_string())
                 })
                 .unwrap_or_else(|| format!("Node {idx}"));
-            GraphNode { label }
+            let kind = ["kind", "type"]
+                .iter()
+                .filter_map(|key| value.get(*key).and_then(Value::as_str))
+                .find_map(|kind| ResourceType::try_new(kind).ok());
+            let text = |pointers: [&str; 2]| {
+                pointers
+                    .iter()
+                    .find_map(|pointer| value.pointer(pointer).and_then(Value::as_str))
+                    .map(str::to_string)
+            };
+            GraphNode {
+                label,
+                kind,
+                uid: text(["/metadata/uid", "/uid"]),
+                name: text(["/metadata/name", "/name"]),
+            }
         })
         .collect();
 
@@ -3263,45 +8216,122 @@ fn summarize_row(obj: &Map<String, Value>) -> RowCard {
     }
 }
 
-fn format_value(value: &Value) -> String {
-    match value {
-        Value::Null => "null".to_string(),
-        Value::Bool(v) => v.to_string(),
-        Value::Number(v) => v.to_string(),
-        Value::String(v) => v.clone(),
-        Value::Array(arr) => format_array_value(arr),
-        Value::Object(obj) => format!("object({})", obj.len()),
-    }
+/// Pill naming a property the live sync changed in its latest update of the selected node.
+fn render_changed_badge(ui: &mut egui::Ui, palette: &Palette, field: &str) {
+    Frame::new()
+        .fill(palette.bg_primary)
+        .stroke(Stroke::new(1.0, palette.accent_warm))
+        .corner_radius(CornerRadius::same(8))
+        .inner_margin(Margin::symmetric(6, 1))
+        .show(ui, |ui| {
+            ui.label(RichText::new(field).color(palette.accent_warm).size(11.0));
+        });
 }
 
-fn format_array_value(arr: &[Value]) -> String {
-    if arr.is_empty() {
-        return "[]".to_string();
-    }
-
-    let max_items = 6usize;
-    let mut parts = Vec::new();
-    for value in arr.iter().take(max_items) {
-        parts.push(format_array_item(value));
-    }
-
-    let mut out = parts.join(", ");
-    if arr.len() > max_items {
-        out.push_str(&format!(", ... (+{})", arr.len() - max_items));
+/// Flat key/value dump of the selected row.
+fn render_inspector_properties(
+    ui: &mut egui::Ui,
+    palette: &Palette,
+    properties: &[InspectorProperty],
+) {
+    for property in properties {
+        match &property.value {
+            InspectorValue::Text(value) => {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(
+                        RichText::new(format!("{}:", property.key))
+                            .color(palette.text_muted)
+                            .size(13.0),
+                    );
+                    ui.label(RichText::new(value).color(palette.text_primary).size(13.0));
+                });
+                ui.add_space(2.0);
+            }
+            InspectorValue::Json(value) => {
+                ui.label(
+                    RichText::new(format!("{}:", property.key))
+                        .color(palette.text_muted)
+                        .size(13.0),
+                );
+                ui.add_space(4.0);
+                let lines = value.lines().count().clamp(3, 10);
+                let height = (lines as f32) * 16.0 + 12.0;
+                Frame::new()
+                    .fill(palette.bg_primary)
+                    .stroke(Stroke::new(1.0, palette.border))
+                    .corner_radius(CornerRadius::same(6))
+                    .inner_margin(Margin::same(6))
+                    .show(ui, |ui| {
+                        let mut display = value.clone();
+                        ui.add_sized(
+                            [ui.available_width(), height],
+                            TextEdit::multiline(&mut display)
+                                .font(TextStyle::Monospace)
+                                .interactive(false)
+                                .desired_width(f32::INFINITY),
+                        );
+                    });
+                ui.add_space(6.0);
+            }
+        }
     }
-
-    format!("[{out}]")
 }
 
-fn format_array_item(value: &Value) -> String {
-    match value {
-        Value::Null => "null".to_string(),
-        Value::Bool(v) => v.to_string(),
-        Value::Number(v) => v.to_string(),
-        Value::String(v) => v.clone(),
-        Value::Array(arr) => format!("array({})", arr.len()),
-        Value::Object(obj) => format!("object({})", obj.len()),
+/// One section of the describe layout: a key/value grid or a small table.
+fn render_describe_section(ui: &mut egui::Ui, palette: &Palette, section: &DescribeSection) {
+    ui.label(
+        RichText::new(section.title)
+            .color(palette.text_muted)
+            .size(12.0)
+            .strong(),
+    );
+    ui.add_space(4.0);
+    match &section.block {
+        DescribeBlock::Fields(fields) => {
+            egui::Grid::new(("describe-fields", section.title))
+                .num_columns(2)
+                .spacing([12.0, 4.0])
+                .show(ui, |ui| {
+                    for (label, value) in fields {
+                        ui.label(RichText::new(label).color(palette.text_muted).size(13.0));
+                        ui.add(
+                            egui::Label::new(
+                                RichText::new(value).color(palette.text_primary).size(13.0),
+                            )
+                            .wrap(),
+                        );
+                        ui.end_row();
+                    }
+                });
+        }
+        DescribeBlock::Table { rows, .. } if rows.is_empty() => {
+            ui.label(RichText::new("None").color(palette.text_muted).italics());
+        }
+        DescribeBlock::Table { columns, rows } => {
+            egui::Grid::new(("describe-table", section.title))
+                .num_columns(columns.len())
+                .striped(true)
+                .spacing([10.0, 4.0])
+                .show(ui, |ui| {
+                    for column in columns {
+                        ui.label(
+                            RichText::new(*column)
+                                .color(palette.text_muted)
+                                .size(11.0)
+                                .strong(),
+                        );
+                    }
+                    ui.end_row();
+                    for row in rows {
+                        for cell in row {
+                            ui.label(RichText::new(cell).color(palette.text_primary).size(12.0));
+                        }
+                        ui.end_row();
+                    }
+                });
+        }
     }
+    ui.add_space(10.0);
 }
 
 fn inspector_value(value: &Value) -> InspectorValue {
@@ -3315,6 +8345,236 @@ fn inspector_value(value: &Value) -> InspectorValue {
     }
 }
 
+/// Actions offered on right-click of a result row.
+fn render_row_context_menu(ui: &mut egui::Ui, row: &RowCard, actions: &mut Vec<FeedAction>) {
+    if let Some(source) = LogSource::from_object(&row_object(row)) {
+        if ui.button("View logs").clicked() {
+            actions.push(FeedAction::OpenLogs(source));
+            ui.close();
+        }
+    }
+    if ui.button("Copy as YAML").clicked() {
+        match serde_yaml::to_string(&row_object(row)) {
+            Ok(yaml) => ui.ctx().copy_text(yaml),
+            Err(err) => tracing::warn!("Failed to render row as YAML: {err}"),
+        }
+        ui.close();
+    }
+    let Some(target) = KubectlTarget::from_row(row) else {
+        ui.add_enabled(false, egui::Button::new("Copy kubectl command"))
+            .on_disabled_hover_text("Return the kind and name to get kubectl commands");
+        return;
+    };
+    ui.separator();
+    let mut verbs = vec!["get", "describe"];
+    if target.kind.eq_ignore_ascii_case("pod") {
+        verbs.push("logs");
+    }
+    for verb in verbs {
+        let command = target.command(verb);
+        if ui.button(format!("Copy `{command}`")).clicked() {
+            ui.ctx().copy_text(command);
+            ui.close();
+        }
+    }
+}
+
+/// The object behind a row: a single returned node as-is, otherwise the whole record.
+fn row_object(row: &RowCard) -> Value {
+    match row.raw_fields.as_slice() {
+        [(_, value @ Value::Object(_))] => value.clone(),
+        fields => Value::Object(fields.iter().cloned().collect()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct KubectlTarget {
+    kind: String,
+    name: String,
+    namespace: Option<String>,
+}
+
+impl KubectlTarget {
+    fn from_row(row: &RowCard) -> Option<Self> {
+        let object = row_object(row);
+        let lookup = |keys: &[&str]| {
+            keys.iter().find_map(|key| {
+                object
+                    .get(*key)
+                    .or_else(|| object.pointer(&format!("/{}", key.replace('_', "/"))))
+                    .and_then(|value| value.as_str())
+                    .filter(|value| !value.is_empty())
+                    .map(|value| value.to_string())
+            })
+        };
+        Some(Self {
+            kind: lookup(&["kind"])?,
+            name: lookup(&["metadata_name", "name"])?,
+            namespace: lookup(&["metadata_namespace", "namespace"]),
+        })
+    }
+
+    /// `kubectl <verb> [<kind>] [-n <namespace>] <name>`; `logs` only applies to pods, so the
+    /// kind is left out there.
+    fn command(&self, verb: &str) -> String {
+        let mut command = format!("kubectl {verb}");
+        if verb != "logs" {
+            command.push(' ');
+            command.push_str(&self.kind.to_lowercase());
+        }
+        if let Some(namespace) = &self.namespace {
+            command.push_str(&format!(" -n {namespace}"));
+        }
+        command.push(' ');
+        command.push_str(&self.name);
+        command
+    }
+}
+
+/// A column of a list result: one of the fixed row card slots or an extra record field.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum TableColumn {
+    Title,
+    Namespace,
+    Status,
+    Field(String),
+}
+
+impl TableColumn {
+    fn label(&self) -> &str {
+        match self {
+            TableColumn::Title => "Name",
+            TableColumn::Namespace => "Namespace",
+            TableColumn::Status => "Status",
+            TableColumn::Field(key) => key,
+        }
+    }
+
+    fn cell<'a>(&self, row: &'a RowCard) -> &'a str {
+        match self {
+            TableColumn::Title => &row.title,
+            TableColumn::Namespace => row.subtitle.as_deref().unwrap_or("-"),
+            TableColumn::Status => row.status.as_deref().unwrap_or("-"),
+            TableColumn::Field(key) => find_field(&row.fields, key).unwrap_or("-"),
+        }
+    }
+
+    fn width(&self) -> Column {
+        match self {
+            TableColumn::Title => Column::initial(220.0).at_least(140.0).resizable(true),
+            TableColumn::Status => Column::initial(120.0).at_least(100.0).resizable(true),
+            TableColumn::Namespace | TableColumn::Field(_) => {
+                Column::initial(160.0).at_least(120.0).resizable(true)
+            }
+        }
+    }
+}
+
+/// Per-table view state (sort, quick filter, column layout), kept in egui memory so it
+/// survives across frames without living on the feed item.
+#[derive(Debug, Clone, Default)]
+struct TableView {
+    /// Sorted column and whether the order is ascending.
+    sort: Option<(TableColumn, bool)>,
+    filter: String,
+    hidden: HashSet<TableColumn>,
+    order: Vec<TableColumn>,
+}
+
+impl TableView {
+    /// Keeps the user's order for known columns and appends any new ones at the end.
+    fn sync(&mut self, columns: &[TableColumn]) {
+        self.order.retain(|column| columns.contains(column));
+        for column in columns {
+            if !self.order.contains(column) {
+                self.order.push(column.clone());
+            }
+        }
+        self.hidden.retain(|column| columns.contains(column));
+        if self
+            .sort
+            .as_ref()
+            .is_some_and(|(column, _)| !columns.contains(column))
+        {
+            self.sort = None;
+        }
+    }
+
+    /// Header clicks cycle ascending → descending → unsorted.
+    fn cycle_sort(&mut self, column: TableColumn) {
+        self.sort = match self.sort.take() {
+            Some((current, true)) if current == column => Some((current, false)),
+            Some((current, false)) if current == column => None,
+            _ => Some((column, true)),
+        };
+    }
+}
+
+fn result_columns(rows: &[RowCard]) -> Vec<TableColumn> {
+    let mut columns = Vec::new();
+    if rows.iter().any(|r| r.title != "Row") {
+        columns.push(TableColumn::Title);
+    }
+    if rows.iter().any(|r| r.subtitle.is_some()) {
+        columns.push(TableColumn::Namespace);
+    }
+    if rows.iter().any(|r| r.status.is_some()) {
+        columns.push(TableColumn::Status);
+    }
+    if let Some(first) = rows.first() {
+        for (key, _) in &first.fields {
+            if matches!(
+                key.as_str(),
+                "metadata_name" | "metadata_namespace" | "status" | "phase" | "kind"
+            ) {
+                continue;
+            }
+            columns.push(TableColumn::Field(key.clone()));
+        }
+    }
+    columns
+}
+
+/// Indices of rows whose visible cells contain `filter` (case-insensitive), in sort order.
+fn visible_row_indices(
+    rows: &[RowCard],
+    columns: &[TableColumn],
+    filter: &str,
+    sort: Option<&(TableColumn, bool)>,
+) -> Vec<usize> {
+    let filter = filter.trim().to_lowercase();
+    let mut indices: Vec<usize> = rows
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| {
+            filter.is_empty()
+                || columns
+                    .iter()
+                    .any(|column| column.cell(row).to_lowercase().contains(&filter))
+        })
+        .map(|(index, _)| index)
+        .collect();
+    if let Some((column, ascending)) = sort {
+        indices.sort_by(|a, b| {
+            let ordering = compare_cells(column.cell(&rows[*a]), column.cell(&rows[*b]));
+            if *ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+    }
+    indices
+}
+
+/// Compares numerically when both cells parse as numbers, otherwise case-insensitively.
+fn compare_cells(a: &str, b: &str) -> std::cmp::Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
 fn find_field<'a>(fields: &'a [(String, String)], key: &str) -> Option<&'a str> {
     fields
         .iter()
@@ -3322,22 +8582,20 @@ fn find_field<'a>(fields: &'a [(String, String)], key: &str) -> Option<&'a str>
         .map(|(_, v)| v.as_str())
 }
 
-fn build_suggestions() -> Vec<String> {
-    let mut suggestions: Vec<String> = ResourceType::iter().map(|r| r.to_string()).collect();
-    suggestions.extend(vec![
-        "OOMKilledState".to_string(),
-        "Pod".to_string(),
-        "Container".to_string(),
-        "Namespace".to_string(),
-        "Deployment".to_string(),
-        "ReplicaSet".to_string(),
-        "DaemonSet".to_string(),
-        "Service".to_string(),
-        "Ingress".to_string(),
-    ]);
-    suggestions.sort();
-    suggestions.dedup();
-    suggestions
+/// Returns indices of entries whose label or detail contains every whitespace-separated term of
+/// the query (case-insensitive), capped at `PALETTE_MAX_RESULTS`.
+fn filter_palette_entries(entries: &[PaletteEntry], query: &str) -> Vec<usize> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| {
+            let haystack = format!("{} {}", entry.label, entry.detail).to_lowercase();
+            terms.iter().all(|term| haystack.contains(term))
+        })
+        .map(|(index, _)| index)
+        .take(PALETTE_MAX_RESULTS)
+        .collect()
 }
 
 fn current_token(input: &str) -> String {
@@ -3416,6 +8674,211 @@ fn format_count(value: usize) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn custom_palette_applies_overrides() {
+        let mut settings = GuiSettings {
+            theme: ThemeChoice::Custom,
+            custom_base: ThemeChoice::Light,
+            ..GuiSettings::default()
+        };
+        settings
+            .custom_colors
+            .insert("accent".to_string(), "#102030".to_string());
+        settings
+            .custom_colors
+            .insert("border".to_string(), "not-a-color".to_string());
+        let palette = Palette::from_settings(&settings);
+        assert!(!palette.dark);
+        assert_eq!(palette.accent, Color32::from_rgb(0x10, 0x20, 0x30));
+        assert_eq!(palette.border, Palette::light().border);
+        assert_eq!(
+            parse_hex_color("#ABCDEF"),
+            Some(Color32::from_rgb(0xAB, 0xCD, 0xEF))
+        );
+        assert_eq!(parse_hex_color("ABCDEF"), None);
+
+        settings.theme = ThemeChoice::HighContrast;
+        let palette = Palette::from_settings(&settings);
+        assert!(palette.high_contrast);
+        assert_eq!(palette.bg_panel, Color32::BLACK);
+        assert_eq!(palette.text_primary, Color32::WHITE);
+    }
+
+    #[test]
+    fn connection_status_reports_sync_age_and_retries() {
+        let now = Instant::now();
+        let mut health = ResolverHealth {
+            last_success: now - Duration::from_secs(4),
+            consecutive_failures: 0,
+            last_error: None,
+            last_error_class: None,
+        };
+        assert_eq!(
+            connection_status(Some(&health), now),
+            ("Connected · synced 4s ago".to_string(), true)
+        );
+
+        health.last_success = now - Duration::from_secs(150);
+        health.consecutive_failures = 3;
+        health.last_error = Some("connection refused".to_string());
+        assert_eq!(
+            connection_status(Some(&health), now),
+            (
+                "Reconnecting (attempt 3) · last sync 2m ago".to_string(),
+                false
+            )
+        );
+        health.last_error_class = Some(ErrorClass::Backend);
+        assert_eq!(
+            connection_status(Some(&health), now).0,
+            "Graph backend failing (attempt 3) · last sync 2m ago"
+        );
+        assert!(connection_status(None, now).1);
+    }
+
+    #[test]
+    fn listing_failures_name_the_unavailable_kinds() {
+        assert_eq!(listing_failures_status(&[]), None);
+        let mut failures = vec![ListingFailure {
+            kind: ResourceType::Ingress,
+            error: "the server could not find the requested resource".to_string(),
+            stale: true,
+        }];
+        assert_eq!(
+            listing_failures_status(&failures),
+            Some((
+                "Ingress unavailable".to_string(),
+                "Ingress (stale): the server could not find the requested resource".to_string()
+            ))
+        );
+        failures.push(ListingFailure {
+            kind: ResourceType::Node,
+            error: "forbidden".to_string(),
+            stale: false,
+        });
+        let (label, details) = listing_failures_status(&failures).unwrap();
+        assert_eq!(label, "2 kinds unavailable");
+        assert!(details.ends_with("\nNode (missing): forbidden"));
+    }
+
+    #[test]
+    fn input_history_steps_and_restores_draft() {
+        let entries = vec!["first".to_string(), "second".to_string()];
+        let mut history = InputHistory::default();
+        assert_eq!(
+            history.step(&entries, "draft", HistoryStep::Older),
+            Some("second".to_string())
+        );
+        assert_eq!(
+            history.step(&entries, "second", HistoryStep::Older),
+            Some("first".to_string())
+        );
+        assert_eq!(history.step(&entries, "first", HistoryStep::Older), None);
+        assert_eq!(
+            history.step(&entries, "first", HistoryStep::Newer),
+            Some("second".to_string())
+        );
+        assert_eq!(
+            history.step(&entries, "second", HistoryStep::Newer),
+            Some("draft".to_string())
+        );
+        assert_eq!(history.step(&entries, "draft", HistoryStep::Newer), None);
+    }
+
+    #[test]
+    fn filter_palette_entries_matches_all_terms() {
+        let entry = |label: &str, detail: &str| PaletteEntry {
+            category: "Resource",
+            label: label.to_string(),
+            detail: detail.to_string(),
+            action: PaletteAction::Question(label.to_string()),
+        };
+        let entries = vec![
+            entry("default/web-1", "Pod"),
+            entry("kube-system/coredns", "Deployment"),
+            entry("default/web", "Service"),
+        ];
+        assert_eq!(filter_palette_entries(&entries, ""), vec![0, 1, 2]);
+        assert_eq!(filter_palette_entries(&entries, "web"), vec![0, 2]);
+        assert_eq!(filter_palette_entries(&entries, "WEB pod"), vec![0]);
+        assert!(filter_palette_entries(&entries, "missing").is_empty());
+    }
+
+    #[test]
+    fn diff_records_reports_added_and_removed_rows() {
+        let previous = vec![
+            serde_json::json!({"name": "web-1", "phase": "Running"}),
+            serde_json::json!({"name": "web-2", "phase": "Running"}),
+        ];
+        let current = vec![
+            serde_json::json!({"name": "web-2", "phase": "Running"}),
+            serde_json::json!({"name": "web-3", "phase": "Pending"}),
+        ];
+        let (added, removed) = diff_records(&previous, &current);
+        assert_eq!(added.len(), 1);
+        assert!(added.contains(&record_key(&current[1])));
+        assert_eq!(removed, vec![previous[0].clone()]);
+
+        let row = summarize_row(current[1].as_object().unwrap());
+        assert_eq!(row_key(&row), record_key(&current[1]));
+    }
+
+    #[test]
+    fn visible_row_indices_filters_and_sorts() {
+        let rows: Vec<RowCard> = [("web-10", 2), ("api", 10), ("web-9", 0)]
+            .iter()
+            .map(|(name, restarts)| {
+                let record = serde_json::json!({"name": name, "restarts": restarts});
+                summarize_row(record.as_object().unwrap())
+            })
+            .collect();
+        let columns = result_columns(&rows);
+        assert_eq!(columns[0], TableColumn::Title);
+
+        let restarts = TableColumn::Field("restarts".to_string());
+        assert_eq!(
+            visible_row_indices(&rows, &columns, "", Some(&(restarts.clone(), true))),
+            vec![2, 0, 1]
+        );
+        assert_eq!(
+            visible_row_indices(&rows, &columns, "WEB", Some(&(restarts, false))),
+            vec![0, 2]
+        );
+
+        let mut view = TableView::default();
+        view.cycle_sort(TableColumn::Title);
+        view.cycle_sort(TableColumn::Title);
+        assert_eq!(view.sort, Some((TableColumn::Title, false)));
+        view.cycle_sort(TableColumn::Title);
+        assert_eq!(view.sort, None);
+    }
+
+    #[test]
+    fn kubectl_target_from_flat_and_nested_rows() {
+        let flat = serde_json::json!({
+            "kind": "Pod",
+            "metadata_name": "web-1",
+            "metadata_namespace": "shop",
+        });
+        let target = KubectlTarget::from_row(&summarize_row(flat.as_object().unwrap())).unwrap();
+        assert_eq!(
+            target.command("describe"),
+            "kubectl describe pod -n shop web-1"
+        );
+        assert_eq!(target.command("logs"), "kubectl logs -n shop web-1");
+
+        let nested = serde_json::json!({
+            "n": {"kind": "Node", "metadata": {"name": "worker-0"}},
+        });
+        let row = summarize_row(nested.as_object().unwrap());
+        assert_eq!(row_object(&row)["kind"], "Node");
+        let target = KubectlTarget::from_row(&row).unwrap();
+        assert_eq!(target.command("get"), "kubectl get node worker-0");
+
+        let bare = serde_json::json!({"count": 3});
+        assert!(KubectlTarget::from_row(&summarize_row(bare.as_object().unwrap())).is_none());
+    }
+
     #[test]
     fn format_count_adds_commas() {
         assert_eq!(format_count(0), "0");
@@ -3423,6 +8886,136 @@ mod tests {
         assert_eq!(format_count(1200300), "1,200,300");
     }
 
+    #[test]
+    fn settings_llm_config_keeps_unset_fields() {
+        let active = LlmConfig {
+            backend: LLMBackend::OpenAI,
+            base_url: "https://api.openai.com/v1".to_string(),
+            model: "gpt-5-mini".to_string(),
+            api_key: Some("secret".to_string()),
+            timeout_secs: 60,
+            structured_output: true,
+            prompt_profile: crate::agent::PromptProfile::Full,
+            schema_subset: false,
+            stream_analysis: true,
+            candidates: 1,
+            instructions: None,
+            docs: None,
+            answer_language: None,
+            fallbacks: Vec::new(),
+        };
+        let mut connection = ConnectionSettings {
+            llm_model: Some("gpt-5-mini".to_string()),
+            ..ConnectionSettings::default()
+        };
+        assert!(settings_llm_config(&active, &connection).unwrap().is_none());
+
+        connection.llm_model = Some("gpt-5-nano".to_string());
+        let config = settings_llm_config(&active, &connection).unwrap().unwrap();
+        assert_eq!(config.model, "gpt-5-nano");
+        assert_eq!(config.api_key.as_deref(), Some("secret"));
+
+        connection.llm_base_url = Some("http://127.0.0.1:8080/v1".to_string());
+        let config = settings_llm_config(&active, &connection).unwrap().unwrap();
+        assert_eq!(config.api_key, None);
+
+        connection.answer_language = Some("German".to_string());
+        let config = settings_llm_config(&active, &connection).unwrap().unwrap();
+        assert_eq!(config.answer_language.as_deref(), Some("German"));
+
+        connection.llm_backend = Some("no-such-backend".to_string());
+        assert!(settings_llm_config(&active, &connection).is_err());
+    }
+
+    #[test]
+    fn engine_profile_rows_become_stat_lines() {
+        let rows = vec![
+            serde_json::json!({"stat": "parse", "value": 0, "unit": "ms"}),
+            serde_json::json!({"stat": "match", "value": 12, "unit": "ms"}),
+            serde_json::json!({"stat": "nodes_scanned", "value": 340, "unit": "count"}),
+            serde_json::json!({"stat": "nodes_indexed", "value": 8, "unit": "count"}),
+        ];
+        assert_eq!(
+            query_stats_lines(&rows),
+            Some((
+                "nodes scanned 340 · nodes indexed 8".to_string(),
+                "match 12 ms".to_string()
+            ))
+        );
+        assert_eq!(
+            query_stats_lines(&[serde_json::json!({"OPERATOR": "* ScanAll", "ACTUAL HITS": 4})]),
+            None
+        );
+    }
+
+    #[test]
+    fn lasso_selection_builds_a_query() {
+        let square = [
+            egui::pos2(0.0, 0.0),
+            egui::pos2(10.0, 0.0),
+            egui::pos2(10.0, 10.0),
+            egui::pos2(0.0, 10.0),
+        ];
+        assert!(lasso_contains(&square, egui::pos2(5.0, 5.0)));
+        assert!(!lasso_contains(&square, egui::pos2(15.0, 5.0)));
+        assert!(!lasso_contains(&square[..2], egui::pos2(5.0, 0.0)));
+
+        let node = |name: &str, kind: ResourceType, uid: Option<&str>| GraphNode {
+            label: name.to_string(),
+            kind: Some(kind),
+            uid: uid.map(str::to_string),
+            name: Some(name.to_string()),
+        };
+        let web = node("web", ResourceType::Pod, Some("u1"));
+        let db = node("db", ResourceType::Pod, Some("u2"));
+        assert_eq!(
+            selection_query(&[&web, &db]).as_deref(),
+            Some(r#"MATCH (n:Pod) WHERE n.metadata.uid IN ["u1", "u2"] RETURN n"#)
+        );
+        let prod = node("prod", ResourceType::Namespace, None);
+        assert_eq!(
+            selection_query(&[&web, &prod]).as_deref(),
+            Some(r#"MATCH (n) WHERE n.metadata.name IN ["web", "prod"] RETURN n"#)
+        );
+        assert_eq!(selection_query(&[]), None);
+    }
+
+    #[test]
+    fn graph_nodes_keep_their_kind() {
+        let records = vec![serde_json::json!({
+            "nodes": [
+                {"label": "web", "kind": "Pod"},
+                {"name": "prod", "type": "Namespace"},
+                {"label": "thing", "kind": "Widget"},
+            ],
+            "edges": [{"from": 0, "to": 1, "label": "BelongsTo"}],
+        })];
+        let Some(ResultPayload::Graph { nodes, .. }) = parse_graph_payload(&records) else {
+            panic!("expected a graph payload");
+        };
+        let kinds: Vec<_> = nodes.iter().map(|node| node.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![Some(ResourceType::Pod), Some(ResourceType::Namespace), None]
+        );
+
+        assert_eq!(nodes[1].name.as_deref(), Some("prod"));
+        assert_eq!(nodes[0].uid, None);
+
+        let palette = Palette::dark();
+        assert_ne!(
+            kind_color(Some(&ResourceType::Pod), &palette),
+            kind_color(Some(&ResourceType::Namespace), &palette)
+        );
+        assert_eq!(kind_color(None, &palette), palette.accent);
+    }
+
+    #[test]
+    fn format_cost_keeps_sub_cent_precision() {
+        assert_eq!(format_cost(0.00042), "$0.0004");
+        assert_eq!(format_cost(12.345), "$12.35");
+    }
+
     #[test]
     fn current_token_picks_last_word() {
         assert_eq!(current_token("MATCH (p:Pod"), "Pod");
@@ -13,9 +13,11 @@ use ariadne_core::types::ResourceType;
 use strum::IntoEnumIterator;
 
 use crate::agent::{
-    Agentic, AnalysisResult, Analyst, ConversationTurn, LlmUsage, RouteDecision, Router, Translator,
+    estimate_tokens, Agentic, AnalysisResult, Analyst, ConversationTurn, LlmUsage, RouteDecision,
+    Router, ToolRegistry, Translation, Translator,
 };
 use crate::error::CliResult;
+use crate::telemetry::in_question;
 use crate::validation::validate_cypher;
 
 const SHORT_TERM_CONTEXT_LIMIT: usize = 4;
@@ -404,6 +406,7 @@ pub struct DioxusGuiArgs {
     pub translator: Arc<dyn Translator>,
     pub router: Arc<dyn Router>,
     pub agentic: Arc<dyn Agentic>,
+    pub agent_tools: ToolRegistry,
     pub analyst: Arc<dyn Analyst>,
     pub cluster_state: SharedClusterState,
     pub cluster_label: String,
@@ -418,6 +421,7 @@ struct AppContext {
     translator: Arc<dyn Translator>,
     router: Arc<dyn Router>,
     agentic: Arc<dyn Agentic>,
+    agent_tools: ToolRegistry,
     analyst: Arc<dyn Analyst>,
     cluster_state: SharedClusterState,
     shared: Arc<Mutex<SharedState>>,
@@ -590,6 +594,7 @@ pub fn run_gui_dioxus(args: DioxusGuiArgs) -> CliResult<()> {
         translator: args.translator,
         router: args.router,
         agentic: args.agentic,
+        agent_tools: args.agent_tools,
         analyst: args.analyst,
         cluster_state: args.cluster_state,
         shared: Arc::new(Mutex::new(SharedState {
@@ -911,6 +916,14 @@ fn render_analysis_block(item: &FeedItem) -> Element {
                     div { class: "analysis-title", "Highlights" }
                     {render_analysis_rows(&analysis.rows)}
                 }
+                if !analysis.citations.is_empty() {
+                    div { class: "analysis-title", "Sources" }
+                    for citation in analysis.citations.iter() {
+                        div { class: "question",
+                            a { href: "{citation.url}", "[{citation.number}] {citation.title}" }
+                        }
+                    }
+                }
                 if !analysis.follow_ups.is_empty() {
                     div { class: "analysis-title", "Follow-ups" }
                     for follow in analysis.follow_ups.iter() {
@@ -1167,11 +1180,12 @@ fn submit_question(context: &AppContext, question: String) {
     let translator = context.translator.clone();
     let router = context.router.clone();
     let agentic = context.agentic.clone();
+    let agent_tools = context.agent_tools.clone();
     let analyst = context.analyst.clone();
     let analysis_context = build_context_with_budget(&context, &read_shared(&context));
     let analysis_summary = read_shared(&context).context_compact_summary.clone();
 
-    runtime.spawn(async move {
+    runtime.spawn(in_question(async move {
         let mut usage_acc = UsageAccumulator::default();
 
         update_feed_item(&context, id, |item| {
@@ -1184,12 +1198,18 @@ fn submit_question(context: &AppContext, question: String) {
 
         let mut route = RouteDecision::OneShot;
         let route_start = Instant::now();
-        match router.classify(&question).await {
+        match router
+            .classify(&question, &analysis_context, analysis_summary.as_deref())
+            .await
+        {
             Ok(route_result) => {
                 let route_ms = route_start.elapsed().as_millis();
                 log_llm_call("router", route_ms, route_result.usage.as_ref());
                 usage_acc.add(route_result.usage.as_ref());
-                route = route_result.decision;
+                // This view has no place for replies, so it queries for those routes too.
+                if route_result.decision.needs_query() {
+                    route = route_result.decision;
+                }
                 update_feed_item(&context, id, |item| {
                     item.route = Some(route);
                     if route == RouteDecision::OneShot {
@@ -1216,6 +1236,7 @@ fn submit_question(context: &AppContext, question: String) {
                     &analysis_context,
                     analysis_summary.as_deref(),
                     backend.as_ref(),
+                    &agent_tools,
                 )
                 .await
             {
@@ -1356,12 +1377,25 @@ fn submit_question(context: &AppContext, question: String) {
                     &analysis_context,
                     analysis_summary.as_deref(),
                     feedback.as_deref(),
+                    &[],
+                    &[],
+                    &[],
                 )
                 .await;
             let llm_ms = llm_start.elapsed().as_millis();
 
             let result = match result {
-                Ok(result) => result,
+                Ok(Translation::Query(result)) => result,
+                // This renderer has no clarification turns; show the question as the outcome.
+                Ok(Translation::NeedsClarification { question, usage }) => {
+                    log_llm_call("translator", llm_ms, usage.as_ref());
+                    update_feed_item(&context, id, |item| {
+                        item.state = FeedState::Error(format!("Needs clarification: {question}"));
+                        item.llm_duration_ms = Some(llm_ms);
+                    });
+                    notify(&context);
+                    return;
+                }
                 Err(err) => {
                     update_feed_item(&context, id, |item| {
                         item.state = FeedState::Error(err.to_string());
@@ -1471,7 +1505,7 @@ fn submit_question(context: &AppContext, question: String) {
                 }
             }
         }
-    });
+    }));
 }
 
 fn rerun_cypher(context: &AppContext, id: u64, cypher: String) {
@@ -1499,7 +1533,7 @@ fn rerun_cypher(context: &AppContext, id: u64, cypher: String) {
     let analysis_context = build_context_with_budget(&context, &read_shared(&context));
     let analysis_summary = read_shared(&context).context_compact_summary.clone();
 
-    runtime.spawn(async move {
+    runtime.spawn(in_question(async move {
         match validate_cypher(&cypher) {
             Ok(()) => {
                 update_feed_item(&context, id, |item| {
@@ -1581,7 +1615,7 @@ fn rerun_cypher(context: &AppContext, id: u64, cypher: String) {
                 notify(&context);
             }
         }
-    });
+    }));
 }
 
 fn reset_context(context: &AppContext) {
@@ -1722,7 +1756,7 @@ fn build_context_with_budget_shared(
     let summary_tokens = shared
         .context_compact_summary
         .as_deref()
-        .map(estimate_text_tokens)
+        .map(estimate_tokens)
         .unwrap_or(0);
     let mut remaining = budget.saturating_sub(summary_tokens);
     let mut turns = Vec::new();
@@ -1798,12 +1832,12 @@ struct ClusterCounts {
 }
 
 fn cluster_counts(state: &SharedClusterState) -> ClusterCounts {
-    let guard = state.lock().expect("cluster state lock poisoned");
-    let node_count = guard.get_node_count();
-    let prop_count = estimate_property_count(&guard, node_count);
-    let pod_count = guard.get_nodes_by_type(&ResourceType::Pod).count();
-    let service_count = guard.get_nodes_by_type(&ResourceType::Service).count();
-    let namespace_count = guard.get_nodes_by_type(&ResourceType::Namespace).count();
+    let state = state.load();
+    let node_count = state.get_node_count();
+    let prop_count = estimate_property_count(&state, node_count);
+    let pod_count = state.get_nodes_by_type(&ResourceType::Pod).count();
+    let service_count = state.get_nodes_by_type(&ResourceType::Service).count();
+    let namespace_count = state.get_nodes_by_type(&ResourceType::Namespace).count();
     ClusterCounts {
         node_count,
         prop_count,
@@ -2449,6 +2483,7 @@ impl UsageAccumulator {
             total_tokens: self.total_tokens,
             reasoning_tokens: self.reasoning_tokens,
             cached_tokens: self.cached_tokens,
+            model: None,
         })
     }
 }
@@ -2492,24 +2527,15 @@ fn log_llm_call(label: &str, duration_ms: u128, usage: Option<&LlmUsage>) {
     }
 }
 
-fn estimate_text_tokens(text: &str) -> usize {
-    let chars = text.len();
-    if chars == 0 {
-        0
-    } else {
-        (chars / 4).max(1)
-    }
-}
-
 fn estimate_turn_tokens(turn: &ConversationTurn) -> usize {
-    let mut tokens = estimate_text_tokens(&turn.question);
-    tokens += estimate_text_tokens(&turn.cypher);
+    let mut tokens = estimate_tokens(&turn.question);
+    tokens += estimate_tokens(&turn.cypher);
     if let Some(summary) = &turn.result_summary {
-        tokens += estimate_text_tokens(summary);
+        tokens += estimate_tokens(summary);
     }
     if let Some(bindings) = &turn.bindings {
         let serialized = serde_json::to_string(bindings).unwrap_or_default();
-        tokens += estimate_text_tokens(&serialized);
+        tokens += estimate_tokens(&serialized);
     }
     tokens
 }
@@ -2520,7 +2546,7 @@ fn estimate_context_tokens(turns: &[ConversationTurn], summary: Option<&str>) ->
         tokens += estimate_turn_tokens(turn);
     }
     if let Some(summary) = summary {
-        tokens += estimate_text_tokens(summary);
+        tokens += estimate_tokens(summary);
     }
     tokens
 }
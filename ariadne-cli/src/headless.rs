@@ -0,0 +1,242 @@
+use std::io::Write;
+
+use clap::ValueEnum;
+use serde_json::{json, Value};
+
+use crate::agent::AnalysisResult;
+use crate::error::CliResult;
+use crate::pipeline::{
+    format_value, truncate_text, Pipeline, PipelineError, PipelineEvent, PipelineStage,
+};
+use crate::telemetry::in_question;
+
+pub const EXIT_OK: u8 = 0;
+pub const EXIT_TRANSLATION_FAILED: u8 = 2;
+pub const EXIT_VALIDATION_FAILED: u8 = 3;
+pub const EXIT_QUERY_FAILED: u8 = 4;
+pub const EXIT_EMPTY_RESULT: u8 = 5;
+/// A step failed with an error that may go away when the command is repeated, such as an
+/// unreachable backend or a rate-limited model.
+pub const EXIT_RETRIABLE_FAILURE: u8 = 6;
+
+const MAX_CELL_WIDTH: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Table,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryOptions {
+    pub input: String,
+    pub output: OutputFormat,
+    pub analyze: bool,
+    pub fail_on_empty: bool,
+}
+
+/// Runs a single question or Cypher query, prints the result to stdout and returns the exit code.
+/// Input that parses as Cypher is executed directly; anything else goes through the LLM.
+pub async fn run_query(pipeline: &Pipeline, options: &QueryOptions) -> CliResult<u8> {
+    in_question(answer_query(pipeline, options)).await
+}
+
+async fn answer_query(pipeline: &Pipeline, options: &QueryOptions) -> CliResult<u8> {
+    let input = options.input.trim();
+    let no_events = |_: PipelineEvent| {};
+    let is_cypher = ariadne_cypher::parse_query(input).is_ok();
+
+    let planned = if is_cypher {
+        pipeline
+            .validate(input, &no_events)
+            .map(|()| (input.to_string(), None))
+    } else {
+        pipeline
+            .plan(input, &[], None, &no_events)
+            .await
+            .map(|planned| (planned.cypher, planned.params))
+    };
+    let (cypher, params) = match planned {
+        Ok(planned) => planned,
+        Err(err) => return Ok(report_failure(&err)),
+    };
+
+    let records = match pipeline.execute(&cypher, params.clone(), &no_events).await {
+        Ok(records) => records,
+        Err(err) => return Ok(report_failure(&err)),
+    };
+
+    let analysis = if options.analyze {
+        pipeline
+            .analyze(input, &cypher, &records, &[], None, &no_events)
+            .await
+    } else {
+        None
+    };
+
+    let mut stdout = std::io::stdout().lock();
+    match options.output {
+        OutputFormat::Json => {
+            let payload = json!({
+                "input": input,
+                "cypher": cypher,
+                "params": params,
+                "rows": records,
+                "analysis": analysis.as_ref().map(analysis_to_json),
+            });
+            writeln!(stdout, "{}", serde_json::to_string_pretty(&payload)?)?;
+        }
+        OutputFormat::Table => {
+            if !is_cypher {
+                writeln!(stdout, "{cypher}\n")?;
+            }
+            write!(stdout, "{}", format_table(&records))?;
+            if let Some(analysis) = &analysis {
+                writeln!(stdout, "\n{}", format_analysis(analysis))?;
+            }
+        }
+    }
+
+    if options.fail_on_empty && records.is_empty() {
+        return Ok(EXIT_EMPTY_RESULT);
+    }
+    Ok(EXIT_OK)
+}
+
+fn report_failure(err: &PipelineError) -> u8 {
+    eprintln!("{err}");
+    if err.retriable {
+        return EXIT_RETRIABLE_FAILURE;
+    }
+    match err.stage {
+        PipelineStage::Translation => EXIT_TRANSLATION_FAILED,
+        PipelineStage::Validation => EXIT_VALIDATION_FAILED,
+        PipelineStage::Query => EXIT_QUERY_FAILED,
+    }
+}
+
+fn analysis_to_json(analysis: &AnalysisResult) -> Value {
+    json!({
+        "title": analysis.title,
+        "summary": analysis.summary,
+        "bullets": analysis.bullets,
+        "rows": analysis.rows,
+        "follow_ups": analysis.follow_ups,
+        "confidence": analysis.confidence,
+    })
+}
+
+fn format_analysis(analysis: &AnalysisResult) -> String {
+    let mut out = format!(
+        "{} (confidence: {})\n{}\n",
+        analysis.title, analysis.confidence, analysis.summary
+    );
+    for bullet in &analysis.bullets {
+        out.push_str(&format!("- {bullet}\n"));
+    }
+    if !analysis.follow_ups.is_empty() {
+        out.push_str("Follow-ups:\n");
+        for follow_up in &analysis.follow_ups {
+            out.push_str(&format!("> {follow_up}\n"));
+        }
+    }
+    out
+}
+
+fn format_table(records: &[Value]) -> String {
+    if records.is_empty() {
+        return "(no rows)\n".to_string();
+    }
+
+    let mut columns: Vec<String> = Vec::new();
+    for record in records {
+        match record.as_object() {
+            Some(obj) => {
+                for key in obj.keys() {
+                    if !columns.contains(key) {
+                        columns.push(key.clone());
+                    }
+                }
+            }
+            None => {
+                if !columns.iter().any(|column| column == "value") {
+                    columns.push("value".to_string());
+                }
+            }
+        }
+    }
+
+    let rows: Vec<Vec<String>> = records
+        .iter()
+        .map(|record| {
+            columns
+                .iter()
+                .map(|column| {
+                    let cell = match record.as_object() {
+                        Some(obj) => obj.get(column).map(format_value).unwrap_or_default(),
+                        None if column == "value" => format_value(record),
+                        None => String::new(),
+                    };
+                    truncate_text(&cell.replace('\n', " "), MAX_CELL_WIDTH)
+                })
+                .collect()
+        })
+        .collect();
+
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            rows.iter()
+                .map(|row| row[index].chars().count())
+                .chain(std::iter::once(column.chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let render_line = |cells: &[String]| -> String {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+
+    let mut out = render_line(&columns);
+    let separator: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    out.push_str(&render_line(&separator));
+    for row in &rows {
+        out.push_str(&render_line(row));
+    }
+    out.push_str(&format!("({} rows)\n", records.len()));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_table_aligns_columns() {
+        let records = vec![
+            json!({"name": "pod-a", "restarts": 12}),
+            json!({"name": "pod-long-name", "restarts": 0}),
+        ];
+        let table = format_table(&records);
+        assert_eq!(
+            table,
+            "name           restarts\n\
+-------------  --------\n\
+pod-a          12\n\
+pod-long-name  0\n\
+(2 rows)\n"
+        );
+    }
+
+    #[test]
+    fn format_table_reports_empty_results() {
+        assert_eq!(format_table(&[]), "(no rows)\n");
+    }
+}
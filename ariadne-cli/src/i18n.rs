@@ -0,0 +1,241 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// Language of the GUI chrome (buttons, tabs, status text). Answers and analyses are written
+/// by the LLM and are not affected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+    Spanish,
+    French,
+}
+
+impl Locale {
+    /// In discriminant order, which is also the column order of [`CATALOG`] after English.
+    pub const ALL: [Locale; 4] = [
+        Locale::English,
+        Locale::German,
+        Locale::Spanish,
+        Locale::French,
+    ];
+
+    /// The language's own name, as listed in the Settings window.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub fn label(&self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::German => "Deutsch",
+            Locale::Spanish => "Español",
+            Locale::French => "Français",
+        }
+    }
+
+    /// Parses a POSIX locale such as `de_DE.UTF-8` or a tag such as `es-MX` by its language.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let language = code
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match language.as_str() {
+            "en" => Some(Locale::English),
+            "de" => Some(Locale::German),
+            "es" => Some(Locale::Spanish),
+            "fr" => Some(Locale::French),
+            _ => None,
+        }
+    }
+
+    /// The locale of the environment (`LC_ALL`, then `LC_MESSAGES`, then `LANG`), when it is
+    /// one of the supported languages.
+    pub fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_code(&value))
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+/// Switches the language used by [`tr`] for the rest of the process.
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn set_locale(locale: Locale) {
+    CURRENT.store(locale as u8, Ordering::Relaxed);
+}
+
+pub fn locale() -> Locale {
+    Locale::ALL
+        .get(CURRENT.load(Ordering::Relaxed) as usize)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// `text` in the current locale. The English text is the catalog key, so anything missing from
+/// the catalog is shown in English.
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn tr(text: &'static str) -> &'static str {
+    translate(locale(), text)
+}
+
+fn translate(locale: Locale, text: &'static str) -> &'static str {
+    let column = match locale {
+        Locale::English => return text,
+        other => other as usize - 1,
+    };
+    CATALOG
+        .iter()
+        .find(|(english, _)| *english == text)
+        .map_or(text, |(_, translations)| translations[column])
+}
+
+/// English text, then German, Spanish and French.
+const CATALOG: &[(&str, [&str; 3])] = &[
+    ("RUN QUERY", ["AUSFÜHREN", "EJECUTAR", "EXÉCUTER"]),
+    (
+        "Show me the services connected to these OOMing pods...",
+        [
+            "Zeig mir die Services, die mit diesen OOM-Pods verbunden sind...",
+            "Muéstrame los servicios conectados a estos pods con OOM...",
+            "Montre-moi les services liés à ces pods en OOM...",
+        ],
+    ),
+    (
+        "Investigation Feed",
+        ["Untersuchungen", "Investigaciones", "Investigations"],
+    ),
+    (
+        "Cluster Overview",
+        [
+            "Cluster-Übersicht",
+            "Resumen del clúster",
+            "Vue d'ensemble du cluster",
+        ],
+    ),
+    ("Dashboard", ["Dashboard", "Panel", "Tableau de bord"]),
+    ("Schema", ["Schema", "Esquema", "Schéma"]),
+    ("Refresh", ["Aktualisieren", "Actualizar", "Actualiser"]),
+    (
+        "Export report",
+        [
+            "Bericht exportieren",
+            "Exportar informe",
+            "Exporter le rapport",
+        ],
+    ),
+    (
+        "No investigations yet.",
+        [
+            "Noch keine Untersuchungen.",
+            "Aún no hay investigaciones.",
+            "Aucune investigation pour l'instant.",
+        ],
+    ),
+    (
+        "Planned Query",
+        ["Geplante Abfrage", "Consulta planificada", "Requête prévue"],
+    ),
+    (
+        "SRE Answer",
+        ["SRE-Antwort", "Respuesta SRE", "Réponse SRE"],
+    ),
+    (
+        "Follow-ups",
+        [
+            "Folgefragen",
+            "Preguntas de seguimiento",
+            "Questions de suivi",
+        ],
+    ),
+    ("Run", ["Ausführen", "Ejecutar", "Exécuter"]),
+    ("Investigate", ["Untersuchen", "Investigar", "Enquêter"]),
+    ("Step", ["Schritt", "Paso", "Étape"]),
+    ("Cancel", ["Abbrechen", "Cancelar", "Annuler"]),
+    ("Copy", ["Kopieren", "Copiar", "Copier"]),
+    ("Watch", ["Beobachten", "Vigilar", "Surveiller"]),
+    (
+        "Stop watching",
+        [
+            "Nicht mehr beobachten",
+            "Dejar de vigilar",
+            "Arrêter la surveillance",
+        ],
+    ),
+    ("Pin", ["Anheften", "Fijar", "Épingler"]),
+    ("Unpin", ["Lösen", "Desfijar", "Désépingler"]),
+    ("Compare", ["Vergleichen", "Comparar", "Comparer"]),
+    ("Timeline", ["Zeitleiste", "Cronología", "Chronologie"]),
+    (
+        "Translating...",
+        ["Übersetze...", "Traduciendo...", "Traduction..."],
+    ),
+    (
+        "Validating...",
+        ["Prüfe...", "Validando...", "Validation..."],
+    ),
+    ("Running...", ["Läuft...", "Ejecutando...", "Exécution..."]),
+    ("Cancelled.", ["Abgebrochen.", "Cancelado.", "Annulé."]),
+    (
+        "Ask the LLM to fix this query",
+        [
+            "Das LLM diese Abfrage korrigieren lassen",
+            "Pedir al LLM que corrija esta consulta",
+            "Demander au LLM de corriger cette requête",
+        ],
+    ),
+    ("Settings", ["Einstellungen", "Ajustes", "Paramètres"]),
+    ("Save", ["Speichern", "Guardar", "Enregistrer"]),
+    ("Appearance", ["Darstellung", "Apariencia", "Apparence"]),
+    ("Theme", ["Design", "Tema", "Thème"]),
+    (
+        "UI scale",
+        [
+            "UI-Skalierung",
+            "Escala de la interfaz",
+            "Échelle de l'interface",
+        ],
+    ),
+    ("Language", ["Sprache", "Idioma", "Langue"]),
+    ("Accept", ["Übernehmen", "Aceptar", "Accepter"]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog_translates_and_falls_back_to_english() {
+        assert_eq!(translate(Locale::German, "Cancel"), "Abbrechen");
+        assert_eq!(translate(Locale::French, "Copy"), "Copier");
+        assert_eq!(translate(Locale::English, "Cancel"), "Cancel");
+        assert_eq!(
+            translate(Locale::Spanish, "Not in the catalog"),
+            "Not in the catalog"
+        );
+
+        for (index, (english, translations)) in CATALOG.iter().enumerate() {
+            assert!(
+                translations.iter().all(|text| !text.is_empty()),
+                "'{english}' has an empty translation"
+            );
+            assert!(
+                CATALOG[..index].iter().all(|(other, _)| other != english),
+                "'{english}' is listed twice"
+            );
+        }
+    }
+
+    #[test]
+    fn locales_parse_from_posix_names() {
+        assert_eq!(Locale::from_code("de_DE.UTF-8"), Some(Locale::German));
+        assert_eq!(Locale::from_code("es-MX"), Some(Locale::Spanish));
+        assert_eq!(Locale::from_code("fr"), Some(Locale::French));
+        assert_eq!(Locale::from_code("C.UTF-8"), None);
+        assert_eq!(Locale::from_code("ja_JP"), None);
+    }
+}
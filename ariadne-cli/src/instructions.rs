@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::error::CliResult;
+
+/// Operator instructions from the file named by `--operator-instructions`: text for every
+/// cluster, and text for single clusters by name.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OperatorInstructions {
+    #[serde(default)]
+    instructions: Option<String>,
+    #[serde(default)]
+    clusters: HashMap<String, ClusterInstructions>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ClusterInstructions {
+    instructions: String,
+}
+
+impl OperatorInstructions {
+    /// The instructions for `cluster`: the shared ones, then the cluster's own. `None` when
+    /// neither says anything.
+    pub fn for_cluster(&self, cluster: &str) -> Option<String> {
+        let parts: Vec<&str> = [
+            self.instructions.as_deref(),
+            self.clusters
+                .get(cluster)
+                .map(|cluster| cluster.instructions.as_str()),
+        ]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .collect();
+        (!parts.is_empty()).then(|| parts.join("\n\n"))
+    }
+}
+
+pub fn load_operator_instructions(path: &Path) -> CliResult<OperatorInstructions> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(toml::from_str(&contents)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cluster_instructions_follow_the_shared_ones() {
+        let instructions: OperatorInstructions = toml::from_str(
+            r#"
+            instructions = "Namespaces are named <team>-<env>."
+
+            [clusters.prod]
+            instructions = """
+            Escalate outages of payments-* to the payments on-call.
+            """
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            instructions.for_cluster("prod").as_deref(),
+            Some(
+                "Namespaces are named <team>-<env>.\n\n\
+                 Escalate outages of payments-* to the payments on-call."
+            )
+        );
+        assert_eq!(
+            instructions.for_cluster("staging").as_deref(),
+            Some("Namespaces are named <team>-<env>.")
+        );
+        assert_eq!(OperatorInstructions::default().for_cluster("prod"), None);
+    }
+}
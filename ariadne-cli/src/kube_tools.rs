@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::{Event, Pod, Service};
+use kube::api::{Api, ListParams};
+use serde_json::Value;
+
+use crate::agent::{required_arg, AgentTool, ToolRegistry};
+use crate::describe::{describe_object, DescribeBlock, DescribeKind, DescribeSection};
+use crate::error::CliResult;
+use crate::logs::{fetch_logs, LogSince, LogTarget};
+
+/// Log lines the agent gets back, the most recent ones.
+const MAX_AGENT_LOG_LINES: usize = 100;
+/// Events the agent gets back, the most recent ones.
+const MAX_AGENT_EVENTS: usize = 20;
+
+/// The agent's tools over the cluster API; none for a snapshot, which has no API to ask.
+pub fn kube_tools(client: Option<kube::Client>) -> ToolRegistry {
+    let Some(client) = client else {
+        return ToolRegistry::default();
+    };
+    ToolRegistry::new(vec![
+        Arc::new(GetLogs {
+            client: client.clone(),
+        }),
+        Arc::new(DescribeObject {
+            client: client.clone(),
+        }),
+        Arc::new(ListEvents { client }),
+    ])
+}
+
+struct GetLogs {
+    client: kube::Client,
+}
+
+#[async_trait]
+impl AgentTool for GetLogs {
+    fn name(&self) -> &'static str {
+        "get_logs"
+    }
+
+    fn description(&self) -> &'static str {
+        "the last hour of a pod's logs, up to 100 lines; args: namespace, pod, optional container"
+    }
+
+    async fn call(&self, args: &HashMap<String, Value>) -> CliResult<String> {
+        let target = LogTarget {
+            namespace: required_arg(args, "namespace")?.to_string(),
+            pod: required_arg(args, "pod")?.to_string(),
+            containers: Vec::new(),
+            container: required_arg(args, "container").ok().map(str::to_string),
+        };
+        let logs = fetch_logs(self.client.clone(), &target, LogSince::OneHour).await?;
+        let lines: Vec<&str> = logs.lines().collect();
+        let tail = &lines[lines.len().saturating_sub(MAX_AGENT_LOG_LINES)..];
+        if tail.is_empty() {
+            return Ok("no log lines in the last hour".to_string());
+        }
+        Ok(tail.join("\n"))
+    }
+}
+
+struct DescribeObject {
+    client: kube::Client,
+}
+
+#[async_trait]
+impl AgentTool for DescribeObject {
+    fn name(&self) -> &'static str {
+        "describe_object"
+    }
+
+    fn description(&self) -> &'static str {
+        "the live state of a Pod, Deployment or Service like kubectl describe; \
+         args: kind, namespace, name"
+    }
+
+    async fn call(&self, args: &HashMap<String, Value>) -> CliResult<String> {
+        let kind = required_arg(args, "kind")?;
+        let namespace = required_arg(args, "namespace")?;
+        let name = required_arg(args, "name")?;
+        let (kind, object) = match kind.to_ascii_lowercase().as_str() {
+            "pod" => {
+                let api: Api<Pod> = Api::namespaced(self.client.clone(), namespace);
+                (
+                    DescribeKind::Pod,
+                    serde_json::to_value(api.get(name).await?)?,
+                )
+            }
+            "deployment" => {
+                let api: Api<Deployment> = Api::namespaced(self.client.clone(), namespace);
+                (
+                    DescribeKind::Deployment,
+                    serde_json::to_value(api.get(name).await?)?,
+                )
+            }
+            "service" => {
+                let api: Api<Service> = Api::namespaced(self.client.clone(), namespace);
+                (
+                    DescribeKind::Service,
+                    serde_json::to_value(api.get(name).await?)?,
+                )
+            }
+            other => return Err(format!("cannot describe kind {other}").into()),
+        };
+        Ok(sections_text(&describe_object(kind, &object)))
+    }
+}
+
+struct ListEvents {
+    client: kube::Client,
+}
+
+#[async_trait]
+impl AgentTool for ListEvents {
+    fn name(&self) -> &'static str {
+        "list_events"
+    }
+
+    fn description(&self) -> &'static str {
+        "the recent events the API server still keeps for an object, newest first; args: uid"
+    }
+
+    async fn call(&self, args: &HashMap<String, Value>) -> CliResult<String> {
+        let uid = required_arg(args, "uid")?;
+        let api: Api<Event> = Api::all(self.client.clone());
+        let params = ListParams::default().fields(&format!("involvedObject.uid={uid}"));
+        let events = api.list(&params).await?;
+        let mut events = events
+            .items
+            .into_iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<_>, _>>()?;
+        events.sort_by(|a, b| event_time(b).cmp(&event_time(a)));
+        if events.is_empty() {
+            return Ok(format!("no events for uid {uid}"));
+        }
+        Ok(events
+            .iter()
+            .take(MAX_AGENT_EVENTS)
+            .map(event_line)
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+/// When an event last happened, as the RFC 3339 text the API returns.
+fn event_time(event: &Value) -> &str {
+    [
+        "/lastTimestamp",
+        "/eventTime",
+        "/metadata/creationTimestamp",
+    ]
+    .iter()
+    .find_map(|pointer| event.pointer(pointer)?.as_str())
+    .unwrap_or_default()
+}
+
+fn event_line(event: &Value) -> String {
+    let text = |pointer: &str| {
+        event
+            .pointer(pointer)
+            .and_then(Value::as_str)
+            .unwrap_or("-")
+    };
+    let count = event.get("count").and_then(Value::as_u64).unwrap_or(1);
+    format!(
+        "{} {} {} (x{count}): {}",
+        event_time(event),
+        text("/type"),
+        text("/reason"),
+        text("/message").trim()
+    )
+}
+
+/// The describe layout as plain text for the agent.
+fn sections_text(sections: &[DescribeSection]) -> String {
+    let mut text = String::new();
+    for section in sections {
+        text.push_str(section.title);
+        text.push_str(":\n");
+        match &section.block {
+            DescribeBlock::Fields(fields) => {
+                for (key, value) in fields {
+                    text.push_str(&format!("  {key}: {value}\n"));
+                }
+            }
+            DescribeBlock::Table { columns, rows } => {
+                text.push_str(&format!("  {}\n", columns.join(" | ")));
+                for row in rows {
+                    text.push_str(&format!("  {}\n", row.join(" | ")));
+                }
+            }
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn events_read_as_lines() {
+        let event = json!({
+            "type": "Warning",
+            "reason": "BackOff",
+            "message": "Back-off restarting failed container\n",
+            "count": 12,
+            "lastTimestamp": "2024-05-01T10:00:00Z",
+        });
+        assert_eq!(
+            event_line(&event),
+            "2024-05-01T10:00:00Z Warning BackOff (x12): Back-off restarting failed container"
+        );
+        assert_eq!(event_time(&json!({"type": "Normal"})), "");
+    }
+
+    #[test]
+    fn describe_sections_read_as_text() {
+        let sections = [
+            DescribeSection {
+                title: "Overview",
+                block: DescribeBlock::Fields(vec![("Name".to_string(), "web-1".to_string())]),
+            },
+            DescribeSection {
+                title: "Containers",
+                block: DescribeBlock::Table {
+                    columns: vec!["Name", "Ready"],
+                    rows: vec![vec!["app".to_string(), "false".to_string()]],
+                },
+            },
+        ];
+        assert_eq!(
+            sections_text(&sections),
+            "Overview:\n  Name: web-1\nContainers:\n  Name | Ready\n  app | false\n"
+        );
+    }
+}
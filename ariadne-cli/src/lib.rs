@@ -1,2 +1,3 @@
 mod error;
+mod read_only;
 pub mod validation;
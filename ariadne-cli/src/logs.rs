@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{Api, LogParams};
+use serde_json::Value;
+
+use ariadne_core::graph_backend::GraphBackend;
+use ariadne_core::payload_limits::PayloadLimits;
+
+use crate::describe::DescribeKind;
+use crate::error::CliResult;
+
+/// How often a followed log panel re-fetches.
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub const FOLLOW_INTERVAL: Duration = Duration::from_secs(2);
+
+const MAX_LOG_LINES: i64 = 2000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogSince {
+    FiveMinutes,
+    #[default]
+    OneHour,
+    SixHours,
+    OneDay,
+    All,
+}
+
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+impl LogSince {
+    pub const ALL: [LogSince; 5] = [
+        LogSince::FiveMinutes,
+        LogSince::OneHour,
+        LogSince::SixHours,
+        LogSince::OneDay,
+        LogSince::All,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogSince::FiveMinutes => "Last 5m",
+            LogSince::OneHour => "Last 1h",
+            LogSince::SixHours => "Last 6h",
+            LogSince::OneDay => "Last 24h",
+            LogSince::All => "All",
+        }
+    }
+
+    fn seconds(&self) -> Option<i64> {
+        match self {
+            LogSince::FiveMinutes => Some(5 * 60),
+            LogSince::OneHour => Some(60 * 60),
+            LogSince::SixHours => Some(6 * 60 * 60),
+            LogSince::OneDay => Some(24 * 60 * 60),
+            LogSince::All => None,
+        }
+    }
+}
+
+/// A pod whose logs can be fetched, with the containers to choose from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogTarget {
+    pub namespace: String,
+    pub pod: String,
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub containers: Vec<String>,
+    pub container: Option<String>,
+}
+
+/// Where a result object points for logs. `Logs` graph nodes only know their container's UID,
+/// so the pod name has to be looked up before fetching.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogSource {
+    Target(LogTarget),
+    LogsNode {
+        namespace: String,
+        pod_uid: String,
+        container: String,
+    },
+}
+
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+impl LogSource {
+    /// Recognises Pod, Container and Logs objects returned by a query.
+    pub fn from_object(object: &Value) -> Option<Self> {
+        let namespace = object.pointer("/metadata/namespace")?.as_str()?.to_string();
+        let name = object.pointer("/metadata/name")?.as_str()?.to_string();
+
+        if let Some(container_uid) = object.get("container_uid").and_then(Value::as_str) {
+            // Container UIDs look like `Container:<pod uid>:<type>:<name>`.
+            let mut parts = container_uid.splitn(4, ':');
+            let (Some("Container"), Some(pod_uid), Some(_), Some(container)) =
+                (parts.next(), parts.next(), parts.next(), parts.next())
+            else {
+                return None;
+            };
+            return Some(LogSource::LogsNode {
+                namespace,
+                pod_uid: pod_uid.to_string(),
+                container: container.to_string(),
+            });
+        }
+
+        if let Some(pod) = object.get("pod_name").and_then(Value::as_str) {
+            return Some(LogSource::Target(LogTarget {
+                namespace,
+                pod: pod.to_string(),
+                containers: vec![name.clone()],
+                container: Some(name),
+            }));
+        }
+
+        if DescribeKind::detect(object) != Some(DescribeKind::Pod) {
+            return None;
+        }
+        let containers: Vec<String> = object
+            .pointer("/spec/containers")
+            .and_then(Value::as_array)
+            .map(|containers| {
+                containers
+                    .iter()
+                    .filter_map(|container| container.get("name")?.as_str())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(LogSource::Target(LogTarget {
+            namespace,
+            pod: name,
+            container: containers.first().cloned(),
+            containers,
+        }))
+    }
+
+    /// Turns the source into a fetchable target, querying the graph for a `Logs` node's pod.
+    pub async fn resolve(self, backend: Arc<dyn GraphBackend>) -> CliResult<LogTarget> {
+        let (namespace, pod_uid, container) = match self {
+            LogSource::Target(target) => return Ok(target),
+            LogSource::LogsNode {
+                namespace,
+                pod_uid,
+                container,
+            } => (namespace, pod_uid, container),
+        };
+        let params = HashMap::from([("uid".to_string(), Value::String(pod_uid.clone()))]);
+        let records = backend
+            .execute_query(
+                "MATCH (p:Pod) WHERE p.metadata.uid = $uid RETURN p.metadata.name AS name"
+                    .to_string(),
+                Some(params),
+            )
+            .await?;
+        let pod = records
+            .first()
+            .and_then(|record| record.get("name"))
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("no pod with uid {pod_uid} in the graph"))?;
+        Ok(LogTarget {
+            namespace,
+            pod: pod.to_string(),
+            containers: vec![container.clone()],
+            container: Some(container),
+        })
+    }
+}
+
+/// The last [`MAX_LOG_LINES`] of the target's logs since `since`, cut to the resolver's default
+/// [`PayloadLimits::max_log_bytes`] so a chatty container cannot flood the viewer or a prompt.
+pub async fn fetch_logs(
+    client: kube::Client,
+    target: &LogTarget,
+    since: LogSince,
+) -> CliResult<String> {
+    let api: Api<Pod> = Api::namespaced(client, &target.namespace);
+    let params = LogParams {
+        container: target.container.clone(),
+        since_seconds: since.seconds(),
+        tail_lines: Some(MAX_LOG_LINES),
+        timestamps: true,
+        ..LogParams::default()
+    };
+    let logs = api.logs(&target.pod, &params).await?;
+    Ok(PayloadLimits::default().cap_logs(logs))
+}
+
+/// Lines containing `query` (case-insensitive); every line when the query is blank.
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn matching_lines<'a>(logs: &'a str, query: &str) -> Vec<&'a str> {
+    let query = query.trim().to_lowercase();
+    logs.lines()
+        .filter(|line| query.is_empty() || line.to_lowercase().contains(&query))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn log_source_from_pod_container_and_logs_objects() {
+        let pod = json!({
+            "metadata": {"name": "web-1", "namespace": "shop"},
+            "spec": {"containers": [{"name": "app"}, {"name": "sidecar"}]},
+        });
+        let Some(LogSource::Target(target)) = LogSource::from_object(&pod) else {
+            panic!("pod should be a log target");
+        };
+        assert_eq!(target.pod, "web-1");
+        assert_eq!(target.containers, vec!["app", "sidecar"]);
+        assert_eq!(target.container.as_deref(), Some("app"));
+
+        let container = json!({
+            "pod_name": "web-1",
+            "metadata": {"name": "sidecar", "namespace": "shop"},
+        });
+        let Some(LogSource::Target(target)) = LogSource::from_object(&container) else {
+            panic!("container should be a log target");
+        };
+        assert_eq!(target.pod, "web-1");
+        assert_eq!(target.container.as_deref(), Some("sidecar"));
+
+        let logs = json!({
+            "container_uid": "Container:1234-abcd:standard:app",
+            "metadata": {"name": "app", "namespace": "shop"},
+        });
+        assert_eq!(
+            LogSource::from_object(&logs),
+            Some(LogSource::LogsNode {
+                namespace: "shop".to_string(),
+                pod_uid: "1234-abcd".to_string(),
+                container: "app".to_string(),
+            })
+        );
+
+        assert_eq!(
+            LogSource::from_object(&json!({"metadata": {"name": "cm", "namespace": "shop"}})),
+            None
+        );
+    }
+
+    #[test]
+    fn matching_lines_ignores_case() {
+        let logs = "INFO started\nERROR failed to bind\ninfo ready\n";
+        assert_eq!(matching_lines(logs, "error"), vec!["ERROR failed to bind"]);
+        assert_eq!(matching_lines(logs, " ").len(), 3);
+    }
+}
@@ -1,57 +1,250 @@
 mod agent;
+mod alerts;
+mod budget;
+mod candidates;
+mod cluster;
+#[cfg(feature = "gui")]
+mod compare;
+#[cfg(feature = "gui")]
+mod dashboard;
+mod describe;
+mod docs;
+mod entities;
 mod error;
+mod error_hints;
+#[cfg(feature = "gui")]
+mod export;
+mod feedback;
+mod few_shot;
+#[cfg(feature = "gui")]
 mod gui;
+#[cfg(feature = "gui")]
 mod gui_dioxus;
+mod headless;
+mod i18n;
+mod instructions;
+mod kube_tools;
+mod logs;
+#[cfg(feature = "gui")]
+mod memory;
+mod pipeline;
+mod read_only;
+#[cfg(feature = "gui")]
+mod report;
+#[cfg(feature = "gui")]
+mod schema_browser;
+mod settings;
+#[cfg(feature = "gui")]
+mod suggestions;
+mod telemetry;
+#[cfg(feature = "gui")]
+mod templates;
+mod text_index;
+#[cfg(feature = "gui")]
+mod timeline;
+mod translation_cache;
+mod tui;
 mod validation;
+mod voting;
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use ::llm::builder::LLMBackend;
-use clap::{Parser, ValueEnum};
-use kube::config::KubeConfigOptions;
+use ariadne_core::redaction::{RedactionPolicy, DEFAULT_REDACTION_PATTERNS};
+use ariadne_core::state_resolver::DEFAULT_POLL_INTERVAL;
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 use tokio_util::sync::CancellationToken;
 
-use ariadne_core::graph_backend::GraphBackend;
-use ariadne_core::in_memory::InMemoryBackend;
-use ariadne_core::kube_client::SnapshotKubeClient;
-use ariadne_core::memgraph_async::MemgraphAsync;
-use ariadne_core::state_resolver::ClusterStateResolver;
-
 use crate::agent::{
-    context_window_tokens_for_model, Agentic, Analyst, LlmAgentic, LlmConfig, LlmRouter,
-    LlmTranslator, Router, SreAnalyst, Translator,
+    discover_models, install_default_prompts, remember_served_context_windows, LlmAgents,
+    LlmConfig, OllamaModel, PromptProfile,
 };
+#[cfg(feature = "gui")]
+use crate::alerts::{default_alert_rules_path, load_alert_rules};
+use crate::budget::BudgetAction;
+#[cfg(feature = "gui")]
+use crate::budget::SessionBudget;
+use crate::cluster::{connect_cluster, ClusterSpec};
+use crate::docs::DocsIndex;
 use crate::error::CliResult;
+use crate::feedback::FeedbackStore;
+#[cfg(feature = "gui")]
 use crate::gui::{run_gui, GuiArgs};
+#[cfg(feature = "gui")]
 use crate::gui_dioxus::{run_gui_dioxus, DioxusGuiArgs, DioxusRenderer};
+use crate::headless::{run_query, OutputFormat, QueryOptions};
+use crate::instructions::{load_operator_instructions, OperatorInstructions};
+use crate::kube_tools::kube_tools;
+#[cfg(feature = "gui")]
+use crate::memory::MemoryStore;
+use crate::pipeline::Pipeline;
+use crate::settings::{ConnectionSettings, GuiSettings};
+use crate::telemetry::{init_telemetry, shutdown_telemetry};
+use crate::translation_cache::TranslationCache;
+use crate::tui::{run_tui, TuiArgs};
+use crate::voting::{VotePolicy, DEFAULT_TRIGGERS};
+
+/// Default endpoint of llama.cpp's `llama-server`.
+const LOCAL_LLM_BASE_URL: &str = "http://127.0.0.1:8080/v1";
 
 #[derive(Parser, Debug)]
 #[command(name = "ariadne-cli")]
-#[command(about = "Interactive GUI for querying Kubernetes graphs", long_about = None)]
+#[command(about = "Interactive GUI and TUI for querying Kubernetes graphs", long_about = None)]
 struct Cli {
     #[arg(long, env = "CLUSTER")]
     cluster: String,
     #[arg(long, env = "KUBE_CONTEXT")]
     kube_context: Option<String>,
+    /// Additional clusters for the GUI switcher, as NAME=CONTEXT (repeatable)
+    #[arg(long = "extra-cluster", env = "EXTRA_CLUSTERS", value_delimiter = ',')]
+    extra_clusters: Vec<String>,
     #[arg(long, env = "KUBE_NAMESPACE")]
     kube_namespace: Option<String>,
     #[arg(long, env = "KUBE_SNAPSHOT_DIR")]
     snapshot_dir: Option<String>,
+    /// Record every listing of the live cluster into this directory, to replay it offline
+    #[arg(long, env = "KUBE_RECORD_DIR", conflicts_with_all = ["snapshot_dir", "replay_dir"])]
+    record_dir: Option<String>,
+    /// Stop adding to the trace of --record-dir once it takes this many MiB
+    #[arg(long, env = "KUBE_RECORD_MAX_MIB", default_value_t = 256)]
+    record_max_mib: u64,
+    /// Replay a directory written with --record-dir, one recorded listing per poll
+    #[arg(long, env = "KUBE_REPLAY_DIR", conflicts_with = "snapshot_dir")]
+    replay_dir: Option<String>,
     #[arg(long, env = "MEMGRAPH_URL")]
     memgraph_url: Option<String>,
-    #[arg(long, env = "LLM_BACKEND", default_value = "openai")]
-    llm_backend: LLMBackend,
+    /// Seconds between two listings of the live cluster [default: 5]
+    #[arg(long, env = "KUBE_POLL_INTERVAL_SECS")]
+    poll_interval_secs: Option<u64>,
+    /// Mask ConfigMap values and container env vars whose name matches --redact-patterns before
+    /// the graph and the LLM see them
+    #[arg(long, env = "REDACTION", default_value_t = true, action = ArgAction::Set)]
+    redaction: bool,
+    /// Case-insensitive name fragments whose values are masked
+    #[arg(
+        long,
+        env = "REDACT_PATTERNS",
+        value_delimiter = ',',
+        default_values_t = DEFAULT_REDACTION_PATTERNS.iter().map(ToString::to_string)
+    )]
+    redact_patterns: Vec<String>,
+    /// LLM provider [default: openai]
+    #[arg(long, env = "LLM_BACKEND")]
+    llm_backend: Option<LLMBackend>,
     #[arg(long, env = "LLM_BASE_URL")]
-    llm_base_url: String,
+    llm_base_url: Option<String>,
     #[arg(long, env = "LLM_MODEL")]
-    llm_model: String,
+    llm_model: Option<String>,
     #[arg(long, env = "LLM_API_KEY")]
     llm_api_key: Option<String>,
     #[arg(long, env = "LLM_TIMEOUT_SECS", default_value_t = 60)]
     llm_timeout_secs: u64,
     #[arg(long, env = "LLM_STRUCTURED_OUTPUT", default_value_t = true)]
     llm_structured_output: bool,
+    /// Use a model served on this machine (llama.cpp's llama-server, Ollama, ...) so no prompt
+    /// leaves the host; defaults the base URL, API key and prompt profile for it
+    #[arg(long, env = "LLM_LOCAL")]
+    llm_local: bool,
+    /// Schema detail in the system prompts [default: full, or compact with --llm-local]
+    #[arg(long, env = "LLM_PROMPT_PROFILE", value_enum)]
+    llm_prompt_profile: Option<PromptProfile>,
+    /// Describe only the node types a question needs in the translator's prompt
+    /// [default: true with the compact profile]
+    #[arg(long, env = "LLM_SCHEMA_SUBSET")]
+    llm_schema_subset: Option<bool>,
+    /// Stream the analyst's answer so it fills in while it is written; streamed answers report
+    /// no token usage
+    #[arg(long, env = "LLM_STREAM_ANALYSIS", default_value_t = true, action = ArgAction::Set)]
+    llm_stream_analysis: bool,
+    /// Queries the translator writes for each question; the first that passes validation (and
+    /// the dry run, with --llm-dry-run-candidates) is run
+    #[arg(
+        long,
+        env = "LLM_CANDIDATES",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u8).range(1..=5)
+    )]
+    llm_candidates: u8,
+    /// Run each candidate query with LIMIT 1 before picking one, preferring those that find rows
+    #[arg(long, env = "LLM_DRY_RUN_CANDIDATES")]
+    llm_dry_run_candidates: bool,
+    /// Language of analyses, follow-ups and reports, e.g. German; queries are not translated
+    #[arg(long, env = "ANSWER_LANGUAGE")]
+    answer_language: Option<String>,
+    /// Analyses of the same rows for questions matching --analysis-vote-on; the confidence
+    /// flags answers they disagree on
+    #[arg(
+        long,
+        env = "ANALYSIS_VOTES",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u8).range(1..=3)
+    )]
+    analysis_votes: u8,
+    /// Reuse the validated query of a question asked before, against the same cluster and model
+    #[arg(long, env = "TRANSLATION_CACHE", default_value_t = true, action = ArgAction::Set)]
+    translation_cache: bool,
+    /// Let the multi-turn agent read pod logs, live objects and events from the cluster API
+    #[arg(long, env = "AGENT_KUBE_TOOLS", default_value_t = true, action = ArgAction::Set)]
+    agent_kube_tools: bool,
+    /// Give the analyst the bundled Kubernetes documentation passages that match an answer, to
+    /// cite under it
+    #[arg(long, env = "K8S_DOCS", default_value_t = true, action = ArgAction::Set)]
+    k8s_docs: bool,
+    /// TOML file of more documentation passages, in the format of config/k8s_docs.toml
+    #[arg(long, env = "K8S_DOCS_FILE")]
+    k8s_docs_file: Option<PathBuf>,
+    /// Remember findings and context summaries per cluster across sessions and recall the
+    /// relevant ones for new questions (egui renderer)
+    #[arg(long, env = "MEMORY", default_value_t = true, action = ArgAction::Set)]
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    memory: bool,
+    /// Words that make a question worth several analyses; `*` votes on every question
+    #[arg(
+        long,
+        env = "ANALYSIS_VOTE_ON",
+        value_delimiter = ',',
+        default_values = DEFAULT_TRIGGERS
+    )]
+    analysis_vote_on: Vec<String>,
+    /// Other models offered by the GUI model picker, as [BACKEND:]MODEL[@BASE_URL] (repeatable)
+    #[arg(long = "llm-alt-model", env = "LLM_ALT_MODELS", value_delimiter = ',')]
+    llm_alt_models: Vec<String>,
+    /// Models to retry on, in order, when the LLM times out, is rate limited or fails with a
+    /// server error, as [BACKEND:]MODEL[@BASE_URL] (repeatable)
+    #[arg(
+        long = "llm-fallback-model",
+        env = "LLM_FALLBACK_MODELS",
+        value_delimiter = ','
+    )]
+    llm_fallback_models: Vec<String>,
+    /// Tokens the LLM calls of a GUI session may use before the budget action applies
+    #[arg(long, env = "LLM_BUDGET_TOKENS")]
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    llm_budget_tokens: Option<u64>,
+    /// Cost in USD the LLM calls of a GUI session may incur before the budget action applies;
+    /// only models with a price count
+    #[arg(long, env = "LLM_BUDGET_USD")]
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    llm_budget_usd: Option<f64>,
+    /// What the GUI does once the session budget is spent
+    #[arg(
+        long,
+        env = "LLM_BUDGET_ACTION",
+        default_value = "downgrade",
+        value_enum
+    )]
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    llm_budget_action: BudgetAction,
+    /// TOML file with alert rules evaluated after every cluster diff (egui renderer)
+    #[arg(long, env = "ALERT_RULES")]
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    alert_rules: Option<PathBuf>,
+    /// TOML file with instructions for the translator and the analyst, shared or per cluster
+    #[arg(long, env = "OPERATOR_INSTRUCTIONS")]
+    operator_instructions: Option<PathBuf>,
     #[arg(
         long,
         env = "GUI_RENDERER",
@@ -59,6 +252,36 @@ struct Cli {
         value_enum
     )]
     gui_renderer: GuiRenderer,
+    #[arg(long, env = "ARIADNE_UI", default_value = DEFAULT_UI, value_enum)]
+    ui: UiMode,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run a single question or Cypher query without starting a UI and print the results
+    Query {
+        /// Natural-language question or Cypher query
+        input: String,
+        #[arg(long, default_value = "table", value_enum)]
+        output: OutputFormat,
+        /// Also ask the analyst to summarize the results
+        #[arg(long)]
+        analyze: bool,
+        /// Exit with a non-zero status when the query returns no rows
+        #[arg(long)]
+        fail_on_empty: bool,
+    },
+}
+
+/// The GUI when it is compiled in, the TUI otherwise.
+const DEFAULT_UI: &str = if cfg!(feature = "gui") { "gui" } else { "tui" };
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum UiMode {
+    Gui,
+    Tui,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -72,119 +295,232 @@ fn main() -> CliResult<()> {
     init_logging()?;
 
     let cli = Cli::parse();
+    if let Err(err) = install_default_prompts() {
+        tracing::warn!("Failed to write the default prompts: {err}");
+    }
 
     let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()?;
+    init_telemetry(runtime.handle());
 
+    // Values saved from the GUI Settings window fill in whatever was not passed explicitly.
+    let saved = GuiSettings::load().connection;
     let memgraph_url = cli
         .memgraph_url
         .clone()
-        .or_else(|| std::env::var("MEMGRAPH_URI").ok());
-    let (backend, backend_label): (Arc<dyn GraphBackend>, String) =
-        if let Some(memgraph_url) = memgraph_url {
-            if !memgraph_url.starts_with("bolt://") {
-                return Err(
-                    format!("memgraph url must use bolt:// scheme (got {memgraph_url})").into(),
-                );
-            }
-            (
-                Arc::new(MemgraphAsync::try_new_from_url(&memgraph_url)?),
-                format!("memgraph ({memgraph_url})"),
-            )
-        } else {
-            (Arc::new(InMemoryBackend::new()), "in-memory".to_string())
-        };
-
-    let kube_opts = KubeConfigOptions {
-        context: cli.kube_context.clone(),
-        cluster: None,
-        user: None,
+        .or_else(|| std::env::var("MEMGRAPH_URI").ok())
+        .or_else(|| saved.memgraph_url.clone());
+    let poll_interval = cli
+        .poll_interval_secs
+        .or(saved.poll_interval_secs)
+        .map(|secs| Duration::from_secs(secs.max(1)))
+        .unwrap_or(DEFAULT_POLL_INTERVAL);
+    let redaction = if cli.redaction {
+        RedactionPolicy::new(&cli.redact_patterns)
+    } else {
+        RedactionPolicy::disabled()
+    };
+    let primary_spec = ClusterSpec {
+        name: cli.cluster.clone(),
+        kube_context: cli.kube_context.clone(),
+        kube_namespace: cli.kube_namespace.clone(),
+        snapshot_dir: cli.snapshot_dir.clone(),
+        record_dir: cli.record_dir.clone(),
+        record_max_bytes: cli.record_max_mib.saturating_mul(1024 * 1024),
+        replay_dir: cli.replay_dir.clone(),
+        memgraph_url,
+        poll_interval,
+        redaction: redaction.clone(),
+    };
+    let extra_specs = cli
+        .extra_clusters
+        .iter()
+        .map(|value| {
+            let spec = ClusterSpec::parse_extra(value, cli.kube_namespace.clone())?;
+            Ok(ClusterSpec {
+                poll_interval,
+                redaction: redaction.clone(),
+                ..spec
+            })
+        })
+        .collect::<CliResult<Vec<_>>>()?;
+    // Rules accepted from `/monitors` go to the default file, which is read when it exists.
+    #[cfg(feature = "gui")]
+    let alert_rules_path = cli.alert_rules.clone().or_else(default_alert_rules_path);
+    #[cfg(feature = "gui")]
+    let alert_rules = match (&cli.alert_rules, &alert_rules_path) {
+        (Some(path), _) => load_alert_rules(path)?,
+        (None, Some(path)) if path.exists() => load_alert_rules(path)?,
+        _ => Vec::new(),
+    };
+    let operator_instructions = match &cli.operator_instructions {
+        Some(path) => load_operator_instructions(path)?,
+        None => OperatorInstructions::default(),
     };
 
-    let resolver = runtime.block_on(async {
-        if let Some(snapshot_dir) = &cli.snapshot_dir {
-            let snapshot_client = SnapshotKubeClient::from_dir(snapshot_dir.clone())?;
-            ClusterStateResolver::new_with_kube_client(
-                cli.cluster.clone(),
-                Box::new(snapshot_client),
-            )
-            .await
-        } else {
-            ClusterStateResolver::new(
-                cli.cluster.clone(),
-                &kube_opts,
-                cli.kube_namespace.as_deref(),
-            )
-            .await
-        }
-    })?;
+    let token = CancellationToken::new();
+    let watch = cli.command.is_none().then_some(&token);
+    let primary = runtime.block_on(connect_cluster(&primary_spec, watch))?;
+    let backend = primary.backend.clone();
+    let backend_label = primary.backend_label.clone();
+    let cluster_state = primary.cluster_state.clone();
+    let entity_index = primary.entity_index.clone();
+    let cluster_label = primary.label.clone();
+    let agent_tools = kube_tools(primary.kube_client.clone().filter(|_| cli.agent_kube_tools));
+    let mut clusters = vec![primary];
 
-    let cluster_state = runtime.block_on(async { resolver.resolve().await })?;
-    if let Err(err) = runtime.block_on(async { backend.create(cluster_state.clone()).await }) {
-        tracing::error!("Graph backend initialization failed: {err}");
-        return Err(err.into());
+    let mut llm_config = llm_config_from_cli(&cli, &saved)?;
+    llm_config.instructions = operator_instructions.for_cluster(&primary_spec.name);
+    llm_config.fallbacks = cli
+        .llm_fallback_models
+        .iter()
+        .map(|spec| llm_config.alternative(spec))
+        .collect::<CliResult<_>>()?;
+    let mut llm_models = vec![llm_config.clone()];
+    for spec in &cli.llm_alt_models {
+        llm_models.push(llm_config.alternative(spec)?);
     }
+    if matches!(llm_config.backend, LLMBackend::Ollama) {
+        match runtime.block_on(discover_models(&llm_config.base_url)) {
+            Ok(served) => use_ollama_models(&llm_config, served, &mut llm_models),
+            Err(err) => tracing::warn!(
+                "Cannot list the models of the Ollama server at {}: {err}",
+                llm_config.base_url
+            ),
+        }
+    }
+    let LlmAgents {
+        translator,
+        router,
+        agentic,
+        analyst,
+        #[cfg(feature = "gui")]
+        context_window_tokens,
+        ..
+    } = LlmAgents::try_new(llm_config)?;
 
-    let token = CancellationToken::new();
-    runtime.block_on(async {
-        resolver.start_diff_loop(backend.clone(), token.clone());
-    });
-
-    let context_window_tokens = context_window_tokens_for_model(&cli.llm_model);
-    let llm_config = LlmConfig {
-        backend: cli.llm_backend,
-        base_url: cli.llm_base_url,
-        model: cli.llm_model,
-        api_key: cli.llm_api_key,
-        timeout_secs: cli.llm_timeout_secs,
-        structured_output: cli.llm_structured_output,
+    let feedback = Arc::new(FeedbackStore::load());
+    #[cfg(feature = "gui")]
+    let memory = cli.memory.then(|| Arc::new(MemoryStore::load()));
+    let analysis_votes = VotePolicy::new(cli.analysis_votes.into(), &cli.analysis_vote_on);
+    let translation_cache = cli
+        .translation_cache
+        .then(|| Arc::new(TranslationCache::default()));
+    let pipeline = Pipeline {
+        backend: backend.clone(),
+        cluster_state: cluster_state.clone(),
+        entity_index,
+        translator: translator.clone(),
+        router: router.clone(),
+        agentic: agentic.clone(),
+        analyst: analyst.clone(),
+        feedback: feedback.clone(),
+        suggest_followups: false,
+        dry_run_candidates: cli.llm_dry_run_candidates,
+        analysis_votes: analysis_votes.clone(),
+        translation_cache: translation_cache.clone(),
+        agent_tools: agent_tools.clone(),
     };
-    let translator: Arc<dyn Translator> = Arc::new(LlmTranslator::try_new(llm_config.clone())?);
-    let router: Arc<dyn Router> = Arc::new(LlmRouter::try_new(llm_config.clone())?);
-    let agentic: Arc<dyn Agentic> = Arc::new(LlmAgentic::try_new(llm_config.clone())?);
-    let analyst: Arc<dyn Analyst> = Arc::new(SreAnalyst::try_new(llm_config)?);
 
-    let cluster_label = {
-        let guard = cluster_state.lock().expect("cluster state lock poisoned");
-        let version = format_k8s_version(&guard.cluster.info);
-        format!("{} (K8s {})", guard.cluster.name, version)
-    };
+    if let Some(Command::Query {
+        input,
+        output,
+        analyze,
+        fail_on_empty,
+    }) = cli.command
+    {
+        let options = QueryOptions {
+            input,
+            output,
+            analyze,
+            fail_on_empty,
+        };
+        let result = runtime.block_on(run_query(&pipeline, &options));
+        token.cancel();
+        runtime.block_on(async {
+            backend.shutdown().await;
+            shutdown_telemetry().await;
+        });
+        let code = result?;
+        if code != 0 {
+            std::process::exit(code.into());
+        }
+        return Ok(());
+    }
 
-    let gui_result = match cli.gui_renderer {
-        GuiRenderer::Egui => run_gui(GuiArgs {
+    if matches!((cli.ui, cli.gui_renderer), (UiMode::Gui, GuiRenderer::Egui)) {
+        for spec in &extra_specs {
+            match runtime.block_on(connect_cluster(spec, Some(&token))) {
+                Ok(session) => clusters.push(session),
+                Err(err) => tracing::warn!("Skipping cluster {}: {err}", spec.name),
+            }
+        }
+    } else if !extra_specs.is_empty() {
+        tracing::warn!("Extra clusters are only supported by the egui renderer; ignoring them");
+    }
+
+    let gui_result = match (cli.ui, cli.gui_renderer) {
+        (UiMode::Tui, _) => run_tui(TuiArgs {
             runtime_handle: runtime.handle().clone(),
-            backend: backend.clone(),
+            pipeline,
+            cluster_label,
+            backend_label,
+        }),
+        #[cfg(not(feature = "gui"))]
+        (UiMode::Gui, _) => {
+            Err("this build has no GUI; run with --ui tui or rebuild with the `gui` feature".into())
+        }
+        #[cfg(feature = "gui")]
+        (UiMode::Gui, GuiRenderer::Egui) => run_gui(GuiArgs {
+            runtime_handle: runtime.handle().clone(),
+            clusters: clusters.clone(),
             translator: translator.clone(),
             router: router.clone(),
             agentic: agentic.clone(),
             analyst: analyst.clone(),
-            cluster_state: cluster_state.clone(),
             token: token.clone(),
-            cluster_label,
-            backend_label,
             context_window_tokens,
+            alert_rules,
+            alert_rules_path,
+            operator_instructions,
+            llm_models,
+            feedback,
+            memory,
+            dry_run_candidates: cli.llm_dry_run_candidates,
+            analysis_votes,
+            translation_cache,
+            agent_kube_tools: cli.agent_kube_tools,
+            budget: SessionBudget {
+                max_tokens: cli.llm_budget_tokens,
+                max_cost: cli.llm_budget_usd,
+                action: cli.llm_budget_action,
+            },
         }),
-        GuiRenderer::DioxusDesktop => run_gui_dioxus(DioxusGuiArgs {
+        #[cfg(feature = "gui")]
+        (UiMode::Gui, GuiRenderer::DioxusDesktop) => run_gui_dioxus(DioxusGuiArgs {
             runtime_handle: runtime.handle().clone(),
             renderer: DioxusRenderer::Desktop,
             backend: backend.clone(),
             translator: translator.clone(),
             router: router.clone(),
             agentic: agentic.clone(),
+            agent_tools: agent_tools.clone(),
             analyst: analyst.clone(),
             cluster_state: cluster_state.clone(),
             cluster_label,
             backend_label,
             context_window_tokens,
         }),
-        GuiRenderer::DioxusNative => run_gui_dioxus(DioxusGuiArgs {
+        #[cfg(feature = "gui")]
+        (UiMode::Gui, GuiRenderer::DioxusNative) => run_gui_dioxus(DioxusGuiArgs {
             runtime_handle: runtime.handle().clone(),
             renderer: DioxusRenderer::Native,
             backend: backend.clone(),
             translator: translator.clone(),
             router: router.clone(),
             agentic: agentic.clone(),
+            agent_tools: agent_tools.clone(),
             analyst: analyst.clone(),
             cluster_state: cluster_state.clone(),
             cluster_label,
@@ -194,17 +530,115 @@ fn main() -> CliResult<()> {
     };
 
     token.cancel();
-    runtime.block_on(async { backend.shutdown().await });
+    runtime.block_on(async {
+        for cluster in &clusters {
+            cluster.backend.shutdown().await;
+        }
+        shutdown_telemetry().await;
+    });
     gui_result
 }
 
-fn format_k8s_version(info: &k8s_openapi::apimachinery::pkg::version::Info) -> String {
-    let version = info.git_version.trim();
-    if version.is_empty() {
-        "unknown".to_string()
-    } else {
-        version.to_string()
+/// Takes the context windows of the Ollama server's models for the context budget and offers
+/// the models that are not configured yet in the GUI model picker.
+fn use_ollama_models(config: &LlmConfig, served: Vec<OllamaModel>, models: &mut Vec<LlmConfig>) {
+    // Ollama resolves a name without a tag to `:latest`.
+    let short_name = |name: &str| name.strip_suffix(":latest").unwrap_or(name).to_string();
+    if !served
+        .iter()
+        .any(|model| model.name == config.model || short_name(&model.name) == config.model)
+    {
+        let names: Vec<&str> = served.iter().map(|model| model.name.as_str()).collect();
+        tracing::warn!(
+            "Model {} is not pulled on the Ollama server; it has: {}",
+            config.model,
+            names.join(", ")
+        );
     }
+
+    let mut windows = HashMap::new();
+    for model in &served {
+        if let Some(tokens) = model.context_length {
+            windows.insert(model.name.clone(), tokens);
+            windows.insert(short_name(&model.name), tokens);
+        }
+    }
+    remember_served_context_windows(windows);
+
+    for model in served {
+        let known = models.iter().any(|known| {
+            known.base_url == config.base_url
+                && (known.model == model.name || known.model == short_name(&model.name))
+        });
+        if !known {
+            models.push(LlmConfig {
+                model: model.name,
+                ..config.clone()
+            });
+        }
+    }
+}
+
+fn llm_config_from_cli(cli: &Cli, saved: &ConnectionSettings) -> CliResult<LlmConfig> {
+    let backend = match (&cli.llm_backend, &saved.llm_backend) {
+        (Some(backend), _) => backend.clone(),
+        (None, Some(name)) => name
+            .parse::<LLMBackend>()
+            .map_err(|err| format!("invalid saved LLM backend '{name}': {err}"))?,
+        (None, None) => LLMBackend::OpenAI,
+    };
+    let model = cli
+        .llm_model
+        .clone()
+        .or_else(|| saved.llm_model.clone())
+        .ok_or("--llm-model (LLM_MODEL) is required")?;
+    let base_url = match (
+        cli.llm_base_url.clone().or(saved.llm_base_url.clone()),
+        cli.llm_local,
+    ) {
+        (Some(base_url), _) => base_url,
+        (None, true) => LOCAL_LLM_BASE_URL.to_string(),
+        (None, false) => return Err("--llm-base-url (LLM_BASE_URL) is required".into()),
+    };
+    // Local servers ignore the key, but OpenAI-compatible clients refuse to start without one.
+    let api_key = match (
+        cli.llm_api_key.clone().or(saved.llm_api_key.clone()),
+        cli.llm_local,
+    ) {
+        (Some(api_key), _) => Some(api_key),
+        (None, true) => Some("local".to_string()),
+        (None, false) => None,
+    };
+    let prompt_profile = cli.llm_prompt_profile.unwrap_or(if cli.llm_local {
+        PromptProfile::Compact
+    } else {
+        PromptProfile::Full
+    });
+    Ok(LlmConfig {
+        backend,
+        base_url,
+        model,
+        api_key,
+        timeout_secs: cli.llm_timeout_secs,
+        structured_output: cli.llm_structured_output,
+        prompt_profile,
+        schema_subset: cli
+            .llm_schema_subset
+            .unwrap_or(prompt_profile == PromptProfile::Compact),
+        stream_analysis: cli.llm_stream_analysis,
+        candidates: cli.llm_candidates.into(),
+        instructions: None,
+        docs: cli
+            .k8s_docs
+            .then(|| DocsIndex::load(cli.k8s_docs_file.as_deref()))
+            .transpose()?
+            .map(Arc::new),
+        answer_language: cli
+            .answer_language
+            .clone()
+            .or(saved.answer_language.clone()),
+        fallbacks: Vec::new(),
+    })
 }
 
 fn init_logging() -> CliResult<()> {
@@ -298,3 +732,31 @@ fn open_log_file(path: &Path) -> CliResult<std::fs::File> {
         .append(true)
         .open(path)?)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Switches that default to on take a value, so they can be turned off on the command line.
+    #[test]
+    fn boolean_flags_take_a_value() {
+        let parse = |args: &[&str]| {
+            Cli::try_parse_from(["ariadne-cli", "--cluster", "prod"].iter().chain(args))
+                .expect("parse")
+        };
+        let defaults = parse(&[]);
+        assert!(defaults.redaction);
+        assert!(defaults.k8s_docs);
+        assert!(defaults.memory);
+        assert!(defaults.llm_stream_analysis);
+        assert!(defaults.translation_cache);
+        assert!(defaults.agent_kube_tools);
+
+        assert!(!parse(&["--redaction", "false"]).redaction);
+        assert!(!parse(&["--k8s-docs", "false"]).k8s_docs);
+        assert!(!parse(&["--memory", "false"]).memory);
+        assert!(!parse(&["--llm-stream-analysis", "false"]).llm_stream_analysis);
+        assert!(!parse(&["--translation-cache", "false"]).translation_cache);
+        assert!(!parse(&["--agent-kube-tools", "false"]).agent_kube_tools);
+    }
+}
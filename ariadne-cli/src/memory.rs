@@ -0,0 +1,199 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::error::CliResult;
+use crate::settings::data_dir;
+use crate::text_index::TextIndex;
+
+/// Memories recalled for one question.
+const MAX_RECALLED: usize = 3;
+/// Cosine similarity below which a memory is not about the question.
+const MIN_SCORE: f64 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryKind {
+    /// The analyst's answer to a question.
+    Finding,
+    /// A compaction summary of the conversation.
+    Summary,
+}
+
+/// One remembered finding or summary, stored as a line of `memory.jsonl`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryRecord {
+    /// Name of the cluster as configured.
+    pub cluster: String,
+    pub kind: MemoryKind,
+    /// The question a finding answers; empty for summaries.
+    #[serde(default)]
+    pub question: String,
+    pub text: String,
+    /// RFC 3339 timestamp.
+    pub recorded_at: String,
+}
+
+/// Findings and context summaries of earlier sessions, kept per cluster so a new session can
+/// recall the ones that concern its questions.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    path: Option<PathBuf>,
+    /// What earlier sessions recorded. Records of this session are only appended to the file,
+    /// since the conversation already holds them.
+    earlier: Vec<MemoryRecord>,
+}
+
+impl MemoryStore {
+    /// Loads `$XDG_DATA_HOME/ariadne-cli/memory.jsonl`; an unreadable file yields an empty
+    /// store that still appends to it.
+    pub fn load() -> Self {
+        let Some(path) = data_dir().map(|dir| dir.join("memory.jsonl")) else {
+            return Self::default();
+        };
+        match Self::load_from(&path) {
+            Ok(store) => store,
+            Err(err) => {
+                tracing::warn!("Failed to load memory from {}: {err}", path.display());
+                Self {
+                    path: Some(path),
+                    earlier: Vec::new(),
+                }
+            }
+        }
+    }
+
+    pub fn load_from(path: &Path) -> CliResult<Self> {
+        let mut earlier = Vec::new();
+        if path.exists() {
+            for (index, line) in std::fs::read_to_string(path)?.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str(line) {
+                    Ok(record) => earlier.push(record),
+                    Err(err) => tracing::warn!(
+                        "Skipping memory line {} in {}: {err}",
+                        index + 1,
+                        path.display()
+                    ),
+                }
+            }
+        }
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            earlier,
+        })
+    }
+
+    /// Appends a finding or summary of `cluster` to the memory file.
+    pub fn remember(
+        &self,
+        cluster: &str,
+        kind: MemoryKind,
+        question: &str,
+        text: &str,
+    ) -> CliResult<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let record = MemoryRecord {
+            cluster: cluster.to_string(),
+            kind,
+            question: question.to_string(),
+            text: text.trim().to_string(),
+            recorded_at: Utc::now().to_rfc3339(),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(file, "{}", serde_json::to_string(&record)?)?;
+        Ok(())
+    }
+
+    /// Up to [`MAX_RECALLED`] memories of `cluster` from earlier sessions about `question`,
+    /// most similar first.
+    pub fn recall(&self, cluster: &str, question: &str) -> Vec<&MemoryRecord> {
+        let records: Vec<&MemoryRecord> = self
+            .earlier
+            .iter()
+            .filter(|record| record.cluster == cluster)
+            .collect();
+        let index = TextIndex::new(
+            records
+                .iter()
+                .map(|record| (record.question.as_str(), record.text.as_str())),
+        );
+        index
+            .search(question, MIN_SCORE)
+            .into_iter()
+            .take(MAX_RECALLED)
+            .map(|index| records[index])
+            .collect()
+    }
+}
+
+/// Recalled memories as a context section, each dated so the model can weigh its age.
+pub fn render_memories(memories: &[&MemoryRecord]) -> Option<String> {
+    if memories.is_empty() {
+        return None;
+    }
+    let mut text =
+        "Findings from earlier sessions on this cluster (they may be outdated):".to_string();
+    for memory in memories {
+        let date = memory.recorded_at.get(..10).unwrap_or(&memory.recorded_at);
+        text.push_str(&format!("\n- {date}: "));
+        if !memory.question.is_empty() {
+            text.push_str(&format!("asked \"{}\": ", memory.question));
+        }
+        text.push_str(&memory.text);
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn findings_of_earlier_sessions_are_recalled_per_cluster() {
+        let dir = std::env::temp_dir().join(format!("ariadne-memory-{}", std::process::id()));
+        let path = dir.join("memory.jsonl");
+        let _ = std::fs::remove_file(&path);
+        let store = MemoryStore::load_from(&path).unwrap();
+        store
+            .remember(
+                "prod",
+                MemoryKind::Finding,
+                "Which PVCs are pending in payments?",
+                "Three claims in payments wait for the fast-ssd storage class.",
+            )
+            .unwrap();
+        store
+            .remember(
+                "staging",
+                MemoryKind::Finding,
+                "Which PVCs are pending in payments?",
+                "All claims are bound.",
+            )
+            .unwrap();
+        assert!(store.recall("prod", "pending pvc in payments").is_empty());
+
+        let next_session = MemoryStore::load_from(&path).unwrap();
+        let recalled = next_session.recall("prod", "why are payments pods pending on pvc?");
+        assert_eq!(recalled.len(), 1);
+        assert!(recalled[0].text.contains("fast-ssd"));
+        assert!(next_session.recall("prod", "list ingresses").is_empty());
+        assert!(render_memories(&recalled)
+            .unwrap()
+            .contains("asked \"Which PVCs are pending in payments?\""));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
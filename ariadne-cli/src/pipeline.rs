@@ -0,0 +1,1389 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Instant;
+
+use ariadne_core::entity_index::SharedEntityIndex;
+use ariadne_core::errors::{AriadneError, ErrorClass};
+use ariadne_core::graph_backend::GraphBackend;
+use ariadne_core::state::SharedClusterState;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::agent::{
+    with_previous_question, AgentStep, Agentic, AnalysisResult, Analyst, ConversationTurn,
+    EntityCandidate, EntityHint, FollowUp, LlmUsage, QueryCandidate, RouteDecision, RouteResult,
+    Router, ToolRegistry, Translation, TranslationResult, Translator,
+};
+use crate::candidates::{self, Verdict};
+use crate::entities::{known_names, resolve_entities};
+use crate::error::CliResult;
+use crate::error_hints::error_hint;
+use crate::feedback::FeedbackStore;
+use crate::few_shot;
+use crate::translation_cache::{CacheKey, CachedTranslation, TranslationCache};
+use crate::validation::{unknown_labels, validate_cypher};
+use crate::voting::{merge_votes, VotePolicy};
+
+/// Translator retries after a validation failure, and again after an execution failure.
+const LLM_MAX_RETRIES: usize = 1;
+/// Approved and seeded translations offered to the translator as examples.
+const FEW_SHOT_EXAMPLES: usize = 3;
+/// Graph nodes from the entity index attached to a translation request.
+const ENTITY_CANDIDATES: usize = 5;
+
+/// Shared ask -> cypher -> results -> analysis loop used by every frontend.
+#[derive(Clone)]
+pub struct Pipeline {
+    pub backend: Arc<dyn GraphBackend>,
+    /// Live state of the same cluster, used to correct misspelled resource names.
+    pub cluster_state: SharedClusterState,
+    /// Index of the node names of the same cluster, used to tell the translator which nodes
+    /// the question is probably about.
+    pub entity_index: SharedEntityIndex,
+    pub translator: Arc<dyn Translator>,
+    pub router: Arc<dyn Router>,
+    pub agentic: Arc<dyn Agentic>,
+    pub analyst: Arc<dyn Analyst>,
+    pub feedback: Arc<FeedbackStore>,
+    /// Ask the analyst for follow-up questions with validated queries after each analysis.
+    pub suggest_followups: bool,
+    /// Run each query the translator offers as a choice with `LIMIT 1` before picking one.
+    pub dry_run_candidates: bool,
+    /// Questions whose rows are analyzed more than once, to flag answers the runs disagree on.
+    pub analysis_votes: VotePolicy,
+    /// Validated translations reused for repeated questions; `None` translates every time.
+    pub translation_cache: Option<Arc<TranslationCache>>,
+    /// Cluster API reads the agent may call between probe queries.
+    pub agent_tools: ToolRegistry,
+}
+
+#[derive(Debug, Clone)]
+pub enum PipelineEvent {
+    RouteDecided {
+        route: RouteDecision,
+        steps: Option<usize>,
+    },
+    TranslationStarted,
+    /// The router answered from the conversation or asked the user a question instead of
+    /// querying. Follows the [`PipelineEvent::RouteDecided`] for that route.
+    Replied {
+        text: String,
+    },
+    /// Words of the question were matched to resource names before translation.
+    EntitiesResolved {
+        hints: Vec<EntityHint>,
+    },
+    /// Intermediate queries the agent ran before settling on the final one.
+    AgentStepsCompleted {
+        steps: Vec<AgentStep>,
+    },
+    TranslationCompleted {
+        cypher: String,
+        params: Option<HashMap<String, Value>>,
+        reasoning: Option<String>,
+        usage: Option<LlmUsage>,
+        duration_ms: u128,
+        /// The query came from [`Pipeline::translation_cache`] instead of the translator.
+        cached: bool,
+    },
+    TranslationFailed {
+        error: String,
+    },
+    ValidationFailed {
+        error: String,
+        cypher: String,
+    },
+    QueryStarted {
+        cypher: String,
+        params: Option<HashMap<String, Value>>,
+    },
+    QueryCompleted {
+        cypher: String,
+        records: Vec<Value>,
+        duration_ms: u128,
+    },
+    QueryFailed {
+        error: String,
+        cypher: String,
+        duration_ms: u128,
+    },
+    AnalysisStarted,
+    /// The sections of the answer written so far, while the analyst streams it.
+    AnalysisProgress {
+        analysis: AnalysisResult,
+    },
+    AnalysisCompleted {
+        analysis: AnalysisResult,
+        duration_ms: u128,
+    },
+    AnalysisFailed {
+        error: String,
+        duration_ms: u128,
+    },
+    /// Follows a successful analysis when [`Pipeline::suggest_followups`] is set and at least
+    /// one suggestion passed validation.
+    FollowUpsSuggested {
+        follow_ups: Vec<FollowUp>,
+    },
+}
+
+pub type EventSink<'a> = &'a (dyn Fn(PipelineEvent) + Send + Sync);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineStage {
+    Translation,
+    Validation,
+    Query,
+}
+
+#[derive(Debug, Clone)]
+pub struct PipelineError {
+    pub stage: PipelineStage,
+    pub message: String,
+    /// What the error came from, when the failing step classified it.
+    pub class: Option<ErrorClass>,
+    /// Whether repeating the step may succeed, such as a query against a backend that was
+    /// briefly unreachable.
+    pub retriable: bool,
+}
+
+impl PipelineError {
+    fn new(stage: PipelineStage, message: impl Into<String>) -> Self {
+        Self {
+            stage,
+            message: message.into(),
+            class: None,
+            retriable: false,
+        }
+    }
+
+    /// Takes the class and retriability of `err` when it is an [`AriadneError`].
+    fn from_error(stage: PipelineStage, err: &(dyn std::error::Error + 'static)) -> Self {
+        let classified = err.downcast_ref::<AriadneError>();
+        Self {
+            stage,
+            message: err.to_string(),
+            class: classified.map(AriadneError::class),
+            retriable: classified.is_some_and(AriadneError::is_retriable),
+        }
+    }
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stage = match self.stage {
+            PipelineStage::Translation => "translation",
+            PipelineStage::Validation => "validation",
+            PipelineStage::Query => "query",
+        };
+        write!(f, "{stage} failed: {}", self.message)
+    }
+}
+
+impl std::error::Error for PipelineError {}
+
+#[derive(Debug, Clone)]
+pub struct PlannedQuery {
+    pub cypher: String,
+    pub params: Option<HashMap<String, Value>>,
+}
+
+/// What planning came up with: a query to run, or a question the user has to answer first.
+enum Plan {
+    Query(PlannedQuery),
+    Clarify(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct PipelineOutcome {
+    pub cypher: String,
+    pub params: Option<HashMap<String, Value>>,
+    pub records: Vec<Value>,
+    pub analysis: Option<AnalysisResult>,
+    /// Set instead of a query when the router replied directly; `cypher` is empty then.
+    pub reply: Option<String>,
+}
+
+/// One investigation step of the feed, as reports and the session summaries of the analyst
+/// see it. Only the GUI keeps a feed to build them from.
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+#[derive(Debug, Clone)]
+pub struct ReportEntry {
+    pub asked_at: DateTime<Utc>,
+    pub question: String,
+    pub cypher: Option<String>,
+    pub records: Vec<Value>,
+    pub analysis: Option<AnalysisResult>,
+    pub error: Option<String>,
+}
+
+impl Pipeline {
+    /// Routes, plans, validates, executes and analyzes a natural-language question. A query that
+    /// fails in a way the translator can fix, or that returns nothing because of a label the
+    /// graph does not have, is sent back to the translator with the error. When the router or
+    /// the translator needs the user to clarify the question, the outcome holds that question
+    /// as its reply; see [`clarified_question`] for asking again with the answer.
+    pub async fn ask(
+        &self,
+        question: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+        on_event: EventSink<'_>,
+    ) -> Result<PipelineOutcome, PipelineError> {
+        let mut usage_acc = UsageAccumulator::default();
+        on_event(PipelineEvent::TranslationStarted);
+        let route = self
+            .route(question, context, context_summary, &mut usage_acc)
+            .await;
+        if let Some(reply) = route.reply {
+            return Ok(replied(route.decision, reply, on_event));
+        }
+        let plan = self
+            .plan_routed(
+                route.decision,
+                question,
+                context,
+                context_summary,
+                &mut usage_acc,
+                on_event,
+            )
+            .await?;
+        let mut planned = match plan {
+            Plan::Query(planned) => planned,
+            Plan::Clarify(text) => return Ok(replied(RouteDecision::Clarify, text, on_event)),
+        };
+        let mut retries = 0usize;
+        let records = loop {
+            let result = self
+                .execute(&planned.cypher, planned.params.clone(), on_event)
+                .await;
+            if result.is_err() {
+                self.forget_translation(question, context);
+            }
+            let feedback = match &result {
+                Ok(records) if records.is_empty() => empty_result_feedback(&planned.cypher),
+                Ok(_) => None,
+                // A backend that is briefly unreachable fails any query, so rewriting won't help.
+                Err(err) if err.retriable => None,
+                Err(err) => error_hint(&err.message)
+                    .filter(|hint| hint.fixable)
+                    .map(|_| failed_query_feedback(&planned.cypher, &err.message)),
+            };
+            match feedback {
+                Some(feedback) if retries < LLM_MAX_RETRIES => {
+                    retries += 1;
+                    tracing::info!("Retrying translation after execution: {feedback}");
+                    let plan = self
+                        .translate_validated(
+                            question,
+                            context,
+                            context_summary,
+                            Some(feedback),
+                            &mut usage_acc,
+                            on_event,
+                        )
+                        .await?;
+                    planned = match plan {
+                        Plan::Query(planned) => planned,
+                        Plan::Clarify(text) => {
+                            return Ok(replied(RouteDecision::Clarify, text, on_event))
+                        }
+                    };
+                }
+                _ => break result?,
+            }
+        };
+        let analysis = self
+            .analyze(
+                question,
+                &planned.cypher,
+                &records,
+                context,
+                context_summary,
+                on_event,
+            )
+            .await;
+        Ok(PipelineOutcome {
+            cypher: planned.cypher,
+            params: planned.params,
+            records,
+            analysis,
+            reply: None,
+        })
+    }
+
+    /// Validates and runs an already known Cypher query, then analyzes its results.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub async fn run_cypher(
+        &self,
+        question: &str,
+        cypher: &str,
+        params: Option<HashMap<String, Value>>,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+        on_event: EventSink<'_>,
+    ) -> Result<PipelineOutcome, PipelineError> {
+        self.validate(cypher, on_event)?;
+        let records = self.execute(cypher, params.clone(), on_event).await?;
+        let analysis = self
+            .analyze(
+                question,
+                cypher,
+                &records,
+                context,
+                context_summary,
+                on_event,
+            )
+            .await;
+        Ok(PipelineOutcome {
+            cypher: cypher.to_string(),
+            params,
+            records,
+            analysis,
+            reply: None,
+        })
+    }
+
+    /// Turns a question into a validated Cypher query, via the agentic loop when the router
+    /// asks for it and via the one-shot translator otherwise. Callers of `plan` need a query,
+    /// so a route that would reply instead is translated one-shot, and a translator asking for
+    /// clarification fails the translation.
+    pub async fn plan(
+        &self,
+        question: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+        on_event: EventSink<'_>,
+    ) -> Result<PlannedQuery, PipelineError> {
+        let mut usage_acc = UsageAccumulator::default();
+        on_event(PipelineEvent::TranslationStarted);
+        let mut route = self
+            .route(question, context, context_summary, &mut usage_acc)
+            .await
+            .decision;
+        if !route.needs_query() {
+            route = RouteDecision::OneShot;
+        }
+        let plan = self
+            .plan_routed(
+                route,
+                question,
+                context,
+                context_summary,
+                &mut usage_acc,
+                on_event,
+            )
+            .await?;
+        require_query(plan, on_event)
+    }
+
+    /// Asks the router how to handle the question, falling back to a one-shot query when it
+    /// fails.
+    async fn route(
+        &self,
+        question: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+        usage_acc: &mut UsageAccumulator,
+    ) -> RouteResult {
+        let route_start = Instant::now();
+        match self
+            .router
+            .classify(question, context, context_summary)
+            .await
+        {
+            Ok(route_result) => {
+                let route_ms = route_start.elapsed().as_millis();
+                log_llm_call("router", route_ms, route_result.usage.as_ref());
+                usage_acc.add(route_result.usage.as_ref());
+                route_result
+            }
+            Err(err) => {
+                tracing::warn!("Router failed, falling back to one-shot: {err}");
+                RouteResult {
+                    decision: RouteDecision::OneShot,
+                    reply: None,
+                    usage: None,
+                }
+            }
+        }
+    }
+
+    /// Plans a query along `route`, which must be one that needs a query, adding the LLM usage
+    /// to `usage_acc` so later retries report the total.
+    async fn plan_routed(
+        &self,
+        route: RouteDecision,
+        question: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+        usage_acc: &mut UsageAccumulator,
+        on_event: EventSink<'_>,
+    ) -> Result<Plan, PipelineError> {
+        let steps = if route == RouteDecision::OneShot {
+            Some(0)
+        } else {
+            None
+        };
+        on_event(PipelineEvent::RouteDecided { route, steps });
+
+        if route == RouteDecision::MultiTurn {
+            match self
+                .run_agent(question, context, context_summary, usage_acc, on_event)
+                .await
+            {
+                Ok((planned, _)) => {
+                    self.validate(&planned.cypher, on_event)?;
+                    return Ok(Plan::Query(planned));
+                }
+                Err(err) => {
+                    tracing::warn!("Agentic planning failed, falling back to one-shot: {err}");
+                    on_event(PipelineEvent::RouteDecided {
+                        route: RouteDecision::OneShot,
+                        steps: Some(0),
+                    });
+                }
+            }
+        }
+
+        self.translate_validated(
+            question,
+            context,
+            context_summary,
+            None,
+            usage_acc,
+            on_event,
+        )
+        .await
+    }
+
+    /// Investigates a question with the agentic loop whatever the router would choose: the agent
+    /// runs intermediate queries, the final one is executed, and the analyst concludes from the
+    /// final rows together with what the intermediate steps found.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub async fn investigate(
+        &self,
+        question: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+        on_event: EventSink<'_>,
+    ) -> Result<PipelineOutcome, PipelineError> {
+        let mut usage_acc = UsageAccumulator::default();
+        on_event(PipelineEvent::TranslationStarted);
+        on_event(PipelineEvent::RouteDecided {
+            route: RouteDecision::MultiTurn,
+            steps: None,
+        });
+        let (planned, steps) = match self
+            .run_agent(question, context, context_summary, &mut usage_acc, on_event)
+            .await
+        {
+            Ok(plan) => plan,
+            Err(err) => {
+                tracing::error!("Investigation failed: {err}");
+                on_event(PipelineEvent::TranslationFailed {
+                    error: err.to_string(),
+                });
+                return Err(PipelineError::from_error(
+                    PipelineStage::Translation,
+                    err.as_ref(),
+                ));
+            }
+        };
+        self.validate(&planned.cypher, on_event)?;
+        let records = self
+            .execute(&planned.cypher, planned.params.clone(), on_event)
+            .await?;
+
+        let mut analysis_context = context.to_vec();
+        analysis_context.extend(
+            steps
+                .iter()
+                .enumerate()
+                .map(|(index, step)| ConversationTurn {
+                    question: match &step.tool {
+                        Some(call) => {
+                            format!("Investigation step {}: {}", index + 1, call.describe())
+                        }
+                        None => format!("Investigation step {}", index + 1),
+                    },
+                    cypher: step.cypher.clone(),
+                    result_summary: step.result_summary.clone(),
+                    bindings: None,
+                }),
+        );
+        let analysis = self
+            .analyze(
+                question,
+                &planned.cypher,
+                &records,
+                &analysis_context,
+                context_summary,
+                on_event,
+            )
+            .await;
+        Ok(PipelineOutcome {
+            cypher: planned.cypher,
+            params: planned.params,
+            records,
+            analysis,
+            reply: None,
+        })
+    }
+
+    /// Runs the agentic loop and reports its steps and final query. The final query is not
+    /// validated here.
+    async fn run_agent(
+        &self,
+        question: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+        usage_acc: &mut UsageAccumulator,
+        on_event: EventSink<'_>,
+    ) -> CliResult<(PlannedQuery, Vec<AgentStep>)> {
+        let plan_start = Instant::now();
+        let plan = self
+            .agentic
+            .plan(
+                question,
+                context,
+                context_summary,
+                self.backend.as_ref(),
+                &self.agent_tools,
+            )
+            .await?;
+        let plan_ms = plan_start.elapsed().as_millis();
+        log_llm_call("agentic", plan_ms, plan.usage.as_ref());
+        usage_acc.add(plan.usage.as_ref());
+        let params = merge_params(plan.params.clone(), context);
+        on_event(PipelineEvent::RouteDecided {
+            route: RouteDecision::MultiTurn,
+            steps: Some(plan.steps.len()),
+        });
+        on_event(PipelineEvent::AgentStepsCompleted {
+            steps: plan.steps.clone(),
+        });
+        on_event(PipelineEvent::TranslationCompleted {
+            cypher: plan.cypher.clone(),
+            params: params.clone(),
+            reasoning: None,
+            usage: usage_acc.build(),
+            duration_ms: plan_ms,
+            cached: false,
+        });
+        Ok((
+            PlannedQuery {
+                cypher: plan.cypher,
+                params,
+            },
+            plan.steps,
+        ))
+    }
+
+    /// Asks the translator to correct a query that failed validation or execution. The failing
+    /// query and the error are shown to the translator along with the original question.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub async fn fix_query(
+        &self,
+        question: &str,
+        cypher: &str,
+        error: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+        on_event: EventSink<'_>,
+    ) -> Result<PlannedQuery, PipelineError> {
+        on_event(PipelineEvent::RouteDecided {
+            route: RouteDecision::OneShot,
+            steps: Some(0),
+        });
+        let plan = self
+            .translate_validated(
+                question,
+                context,
+                context_summary,
+                Some(failed_query_feedback(cypher, error)),
+                &mut UsageAccumulator::default(),
+                on_event,
+            )
+            .await?;
+        require_query(plan, on_event)
+    }
+
+    /// One-shot translation, retried with the validation error as feedback while the issue is
+    /// retriable. A question asked before is answered from the translation cache, unless there
+    /// is feedback on the query it returned; the translation that follows replaces it.
+    async fn translate_validated(
+        &self,
+        question: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+        mut feedback: Option<String>,
+        usage_acc: &mut UsageAccumulator,
+        on_event: EventSink<'_>,
+    ) -> Result<Plan, PipelineError> {
+        let cache_key = self
+            .translation_cache
+            .as_ref()
+            .map(|_| self.translation_cache_key(question, context));
+        if let (Some(cache), Some(key), None) = (&self.translation_cache, &cache_key, &feedback) {
+            if let Some(cached) = cache.get(key) {
+                tracing::info!("Reusing the cached translation of '{question}'");
+                let params = merge_params(cached.params, context);
+                on_event(PipelineEvent::TranslationCompleted {
+                    cypher: cached.cypher.clone(),
+                    params: params.clone(),
+                    reasoning: cached.reasoning,
+                    usage: usage_acc.build(),
+                    duration_ms: 0,
+                    cached: true,
+                });
+                return Ok(Plan::Query(PlannedQuery {
+                    cypher: cached.cypher,
+                    params,
+                }));
+            }
+        }
+        let mut attempt = 0usize;
+        let examples = few_shot::examples_for(
+            &with_previous_question(question, context),
+            &self.feedback,
+            FEW_SHOT_EXAMPLES,
+        );
+        let hints = self.entity_hints(question);
+        if !hints.is_empty() {
+            on_event(PipelineEvent::EntitiesResolved {
+                hints: hints.clone(),
+            });
+        }
+        let candidates = self.entity_candidates(question);
+
+        loop {
+            attempt += 1;
+            on_event(PipelineEvent::TranslationStarted);
+            let llm_start = Instant::now();
+            let result = self
+                .translator
+                .translate(
+                    question,
+                    context,
+                    context_summary,
+                    feedback.as_deref(),
+                    &examples,
+                    &hints,
+                    &candidates,
+                )
+                .await;
+            let llm_ms = llm_start.elapsed().as_millis();
+
+            let result = match result {
+                Ok(Translation::Query(result)) => result,
+                Ok(Translation::NeedsClarification { question, usage }) => {
+                    log_llm_call("translator", llm_ms, usage.as_ref());
+                    usage_acc.add(usage.as_ref());
+                    return Ok(Plan::Clarify(question));
+                }
+                Err(err) => {
+                    tracing::error!("Translation failed: {err}");
+                    on_event(PipelineEvent::TranslationFailed {
+                        error: err.to_string(),
+                    });
+                    return Err(PipelineError::from_error(
+                        PipelineStage::Translation,
+                        err.as_ref(),
+                    ));
+                }
+            };
+            log_llm_call("translator", llm_ms, result.usage.as_ref());
+            usage_acc.add(result.usage.as_ref());
+            let result = self.pick_candidate(result, context).await;
+
+            let params = merge_params(result.params.clone(), context);
+
+            on_event(PipelineEvent::TranslationCompleted {
+                cypher: result.cypher.clone(),
+                params: params.clone(),
+                reasoning: result.reasoning.clone(),
+                usage: usage_acc.build(),
+                duration_ms: llm_ms,
+                cached: false,
+            });
+
+            match validate_cypher(&result.cypher) {
+                Ok(()) => {
+                    if let (Some(cache), Some(key)) = (&self.translation_cache, cache_key) {
+                        cache.insert(
+                            key,
+                            CachedTranslation {
+                                cypher: result.cypher.clone(),
+                                params: result.params,
+                                reasoning: result.reasoning,
+                            },
+                        );
+                    }
+                    return Ok(Plan::Query(PlannedQuery {
+                        cypher: result.cypher,
+                        params,
+                    }));
+                }
+                Err(issue) => {
+                    tracing::error!("Validation failed: {issue}");
+                    if attempt <= LLM_MAX_RETRIES && issue.retriable() {
+                        feedback = Some(issue.feedback());
+                        continue;
+                    }
+                    on_event(PipelineEvent::ValidationFailed {
+                        error: issue.to_string(),
+                        cypher: result.cypher,
+                    });
+                    return Err(PipelineError::new(
+                        PipelineStage::Validation,
+                        issue.to_string(),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Picks the most promising of the queries in `result`: valid before invalid, known labels
+    /// before unknown ones and, with [`Pipeline::dry_run_candidates`], rows before none. The
+    /// translator's order breaks ties. With every query rejected the first one is kept, so its
+    /// validation error is what the translator hears about.
+    async fn pick_candidate(
+        &self,
+        mut result: TranslationResult,
+        context: &[ConversationTurn],
+    ) -> TranslationResult {
+        if result.alternatives.is_empty() {
+            return result;
+        }
+        let mut queries = vec![QueryCandidate {
+            cypher: result.cypher.clone(),
+            params: result.params.clone(),
+        }];
+        queries.append(&mut result.alternatives);
+        let mut verdicts = Vec::with_capacity(queries.len());
+        for query in &queries {
+            let mut verdict = candidates::check(&query.cypher);
+            if verdict == Verdict::Valid && self.dry_run_candidates {
+                let params = merge_params(query.params.clone(), context);
+                verdict = match self
+                    .backend
+                    .execute_query(candidates::dry_run_query(&query.cypher), params)
+                    .await
+                {
+                    Ok(records) if records.is_empty() => Verdict::Empty,
+                    Ok(_) => Verdict::Rows,
+                    Err(err) => Verdict::Rejected(err.to_string()),
+                };
+            }
+            // Nothing ranks above this, and earlier queries win ties.
+            let unbeatable =
+                verdict == Verdict::Rows || (verdict == Verdict::Valid && !self.dry_run_candidates);
+            verdicts.push(verdict);
+            if unbeatable {
+                break;
+            }
+        }
+        tracing::info!(?verdicts, "Checked the translator's candidate queries");
+        match candidates::best(&verdicts) {
+            Some(picked) if picked > 0 => {
+                let query = queries.swap_remove(picked);
+                result.reasoning = Some(candidates::selection_note(&verdicts, picked));
+                result.cypher = query.cypher;
+                result.params = query.params;
+            }
+            _ => {}
+        }
+        result
+    }
+
+    /// The question, the query of the turn it may refine, the cluster and the translator's
+    /// schema version.
+    fn translation_cache_key(&self, question: &str, context: &[ConversationTurn]) -> CacheKey {
+        let cluster = {
+            let state = self.cluster_state.load();
+            format!("{}@{}", state.cluster.name, state.cluster.cluster_url)
+        };
+        CacheKey::new(
+            question,
+            context.last().map(|turn| turn.cypher.as_str()),
+            &cluster,
+            self.translator.schema_version(),
+        )
+    }
+
+    /// Drops the cached translation of `question`, whose query failed to run.
+    fn forget_translation(&self, question: &str, context: &[ConversationTurn]) {
+        if let Some(cache) = &self.translation_cache {
+            cache.remove(&self.translation_cache_key(question, context));
+        }
+    }
+
+    fn entity_hints(&self, question: &str) -> Vec<EntityHint> {
+        let names = {
+            let state = self.cluster_state.load();
+            known_names(&state)
+        };
+        resolve_entities(question, &names)
+    }
+
+    fn entity_candidates(&self, question: &str) -> Vec<EntityCandidate> {
+        let index = self
+            .entity_index
+            .lock()
+            .expect("entity index lock poisoned");
+        index
+            .search(question, ENTITY_CANDIDATES)
+            .into_iter()
+            .map(|entity| EntityCandidate {
+                uid: entity.uid,
+                name: entity.name,
+                kind: entity.kind.to_string(),
+                namespace: entity.namespace,
+            })
+            .collect()
+    }
+
+    /// Checks a query against the read-only and schema rules before it reaches the backend.
+    pub fn validate(&self, cypher: &str, on_event: EventSink<'_>) -> Result<(), PipelineError> {
+        validate_cypher(cypher).map_err(|issue| {
+            tracing::error!("Validation failed: {issue}");
+            on_event(PipelineEvent::ValidationFailed {
+                error: issue.to_string(),
+                cypher: cypher.to_string(),
+            });
+            PipelineError::new(PipelineStage::Validation, issue.to_string())
+        })
+    }
+
+    /// Executes a validated query against the graph backend.
+    pub async fn execute(
+        &self,
+        cypher: &str,
+        params: Option<HashMap<String, Value>>,
+        on_event: EventSink<'_>,
+    ) -> Result<Vec<Value>, PipelineError> {
+        on_event(PipelineEvent::QueryStarted {
+            cypher: cypher.to_string(),
+            params: params.clone(),
+        });
+        let exec_start = Instant::now();
+        match self.backend.execute_query(cypher.to_string(), params).await {
+            Ok(records) => {
+                on_event(PipelineEvent::QueryCompleted {
+                    cypher: cypher.to_string(),
+                    records: records.clone(),
+                    duration_ms: exec_start.elapsed().as_millis(),
+                });
+                Ok(records)
+            }
+            Err(err) => {
+                tracing::error!("Query failed: {err}");
+                on_event(PipelineEvent::QueryFailed {
+                    error: err.to_string(),
+                    cypher: cypher.to_string(),
+                    duration_ms: exec_start.elapsed().as_millis(),
+                });
+                Err(PipelineError::from_error(PipelineStage::Query, &err))
+            }
+        }
+    }
+
+    /// Asks the analyst to explain the records. Failures are reported as events only.
+    pub async fn analyze(
+        &self,
+        question: &str,
+        cypher: &str,
+        records: &[Value],
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+        on_event: EventSink<'_>,
+    ) -> Option<AnalysisResult> {
+        let summary = summarize_records(records);
+        on_event(PipelineEvent::AnalysisStarted);
+        let analysis_start = Instant::now();
+        let on_partial = |analysis: AnalysisResult| {
+            on_event(PipelineEvent::AnalysisProgress { analysis });
+        };
+        match self
+            .analyst
+            .analyze_streaming(
+                question,
+                cypher,
+                records,
+                &summary,
+                context,
+                context_summary,
+                &on_partial,
+            )
+            .await
+        {
+            Ok(analysis) => {
+                let analysis = if self.analysis_votes.applies(question) {
+                    on_event(PipelineEvent::AnalysisProgress {
+                        analysis: analysis.clone(),
+                    });
+                    self.vote(
+                        analysis,
+                        question,
+                        cypher,
+                        records,
+                        &summary,
+                        context,
+                        context_summary,
+                    )
+                    .await
+                } else {
+                    analysis
+                };
+                let analysis_ms = analysis_start.elapsed().as_millis();
+                log_llm_call("analysis", analysis_ms, analysis.usage.as_ref());
+                on_event(PipelineEvent::AnalysisCompleted {
+                    analysis: analysis.clone(),
+                    duration_ms: analysis_ms,
+                });
+                if self.suggest_followups {
+                    self.suggest_followups(question, cypher, records, &summary, context, on_event)
+                        .await;
+                }
+                Some(analysis)
+            }
+            Err(err) => {
+                let analysis_ms = analysis_start.elapsed().as_millis();
+                tracing::error!("Analysis failed: {err}");
+                on_event(PipelineEvent::AnalysisFailed {
+                    error: err.to_string(),
+                    duration_ms: analysis_ms,
+                });
+                None
+            }
+        }
+    }
+
+    /// Analyzes the rows again, [`VotePolicy::votes`] runs in all and concurrently, and merges
+    /// the answers into `first`. Runs that fail are left out of the vote.
+    #[allow(clippy::too_many_arguments)]
+    async fn vote(
+        &self,
+        first: AnalysisResult,
+        question: &str,
+        cypher: &str,
+        records: &[Value],
+        summary: &str,
+        context: &[ConversationTurn],
+        context_summary: Option<&str>,
+    ) -> AnalysisResult {
+        let runs = (1..self.analysis_votes.votes).map(|_| {
+            self.analyst
+                .analyze(question, cypher, records, summary, context, context_summary)
+        });
+        let mut others = Vec::new();
+        for result in futures::future::join_all(runs).await {
+            match result {
+                Ok(analysis) => others.push(analysis),
+                Err(err) => tracing::warn!("An analysis run for the vote failed: {err}"),
+            }
+        }
+        let mut usage = UsageAccumulator::default();
+        usage.add(first.usage.as_ref());
+        for other in &others {
+            usage.add(other.usage.as_ref());
+        }
+        let mut merged = merge_votes(first, &others, records);
+        merged.usage = usage.build();
+        merged
+    }
+
+    async fn suggest_followups(
+        &self,
+        question: &str,
+        cypher: &str,
+        records: &[Value],
+        summary: &str,
+        context: &[ConversationTurn],
+        on_event: EventSink<'_>,
+    ) {
+        let mut turns = context.to_vec();
+        turns.push(ConversationTurn {
+            question: question.to_string(),
+            cypher: cypher.to_string(),
+            result_summary: Some(summary.to_string()),
+            bindings: None,
+        });
+        let start = Instant::now();
+        match self.analyst.suggest_followups(records, &turns).await {
+            Ok(suggestions) => {
+                log_llm_call(
+                    "follow_ups",
+                    start.elapsed().as_millis(),
+                    suggestions.usage.as_ref(),
+                );
+                if !suggestions.follow_ups.is_empty() {
+                    on_event(PipelineEvent::FollowUpsSuggested {
+                        follow_ups: suggestions.follow_ups,
+                    });
+                }
+            }
+            Err(err) => tracing::warn!("Follow-up suggestions failed: {err}"),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct UsageAccumulator {
+    prompt_tokens: u32,
+    completion_tokens: u32,
+    total_tokens: u32,
+    reasoning_tokens: Option<u32>,
+    cached_tokens: Option<u32>,
+    /// The last fallback model that served one of the calls.
+    model: Option<String>,
+    seen: bool,
+    reasoning_complete: bool,
+    cached_complete: bool,
+}
+
+impl UsageAccumulator {
+    pub fn add(&mut self, usage: Option<&LlmUsage>) {
+        let Some(usage) = usage else {
+            return;
+        };
+        if !self.seen {
+            self.seen = true;
+            self.reasoning_complete = true;
+            self.cached_complete = true;
+            self.reasoning_tokens = Some(0);
+            self.cached_tokens = Some(0);
+        }
+        self.prompt_tokens = self.prompt_tokens.saturating_add(usage.prompt_tokens);
+        self.completion_tokens = self
+            .completion_tokens
+            .saturating_add(usage.completion_tokens);
+        self.total_tokens = self.total_tokens.saturating_add(usage.total_tokens);
+        if usage.model.is_some() {
+            self.model = usage.model.clone();
+        }
+
+        if !self.reasoning_complete {
+            // Already missing in a prior call; keep None.
+        } else if let Some(tokens) = usage.reasoning_tokens {
+            self.reasoning_tokens = Some(self.reasoning_tokens.unwrap_or(0) + tokens);
+        } else {
+            self.reasoning_tokens = None;
+            self.reasoning_complete = false;
+        }
+
+        if !self.cached_complete {
+            // Already missing in a prior call; keep None.
+        } else if let Some(tokens) = usage.cached_tokens {
+            self.cached_tokens = Some(self.cached_tokens.unwrap_or(0) + tokens);
+        } else {
+            self.cached_tokens = None;
+            self.cached_complete = false;
+        }
+    }
+
+    pub fn build(&self) -> Option<LlmUsage> {
+        if !self.seen {
+            return None;
+        }
+        Some(LlmUsage {
+            prompt_tokens: self.prompt_tokens,
+            completion_tokens: self.completion_tokens,
+            total_tokens: self.total_tokens,
+            reasoning_tokens: self.reasoning_tokens,
+            cached_tokens: self.cached_tokens,
+            model: self.model.clone(),
+        })
+    }
+}
+
+/// The outcome of a question answered with a reply instead of a query.
+fn replied(route: RouteDecision, reply: String, on_event: EventSink<'_>) -> PipelineOutcome {
+    on_event(PipelineEvent::RouteDecided { route, steps: None });
+    on_event(PipelineEvent::Replied {
+        text: reply.clone(),
+    });
+    PipelineOutcome {
+        cypher: String::new(),
+        params: None,
+        records: Vec::new(),
+        analysis: None,
+        reply: Some(reply),
+    }
+}
+
+/// The query of a plan, for callers that cannot ask the user back.
+fn require_query(plan: Plan, on_event: EventSink<'_>) -> Result<PlannedQuery, PipelineError> {
+    match plan {
+        Plan::Query(planned) => Ok(planned),
+        Plan::Clarify(question) => {
+            let error = format!("the question is ambiguous: {question}");
+            on_event(PipelineEvent::TranslationFailed {
+                error: error.clone(),
+            });
+            Err(PipelineError::new(PipelineStage::Translation, error))
+        }
+    }
+}
+
+/// The question to ask again once the user answered a clarifying question, so the router and
+/// the translator see what was unclear and how it was resolved.
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn clarified_question(original: &str, asked: &str, answer: &str) -> String {
+    format!(
+        "{}\nClarifying question: {}\nAnswer: {}",
+        original.trim(),
+        asked.trim(),
+        answer.trim()
+    )
+}
+
+/// Translator feedback for a query that failed validation or execution.
+fn failed_query_feedback(cypher: &str, error: &str) -> String {
+    format!(
+        "This query:\n{}\nfailed with: {}\nFix the Cypher so it runs against the schema.",
+        cypher.trim(),
+        error.trim()
+    )
+}
+
+/// Translator feedback for a query that returned no rows while matching labels the graph does
+/// not have. `None` when every label exists, as an empty result is then a plausible answer.
+fn empty_result_feedback(cypher: &str) -> Option<String> {
+    let unknown = unknown_labels(cypher);
+    if unknown.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "This query:\n{}\nreturned no rows. These labels are not in the graph schema: {}. \
+         Use the exact node labels of the schema.",
+        cypher.trim(),
+        unknown.join(", ")
+    ))
+}
+
+pub fn log_llm_call(label: &str, duration_ms: u128, usage: Option<&LlmUsage>) {
+    if let Some(usage) = usage {
+        tracing::info!(
+            "{label} LLM ({duration_ms} ms) tokens prompt={} completion={} total={} cached={:?} reasoning={:?}",
+            usage.prompt_tokens,
+            usage.completion_tokens,
+            usage.total_tokens,
+            usage.cached_tokens,
+            usage.reasoning_tokens
+        );
+    } else {
+        tracing::info!("{label} LLM ({duration_ms} ms)");
+    }
+}
+
+pub fn merge_params(
+    params: Option<HashMap<String, Value>>,
+    context: &[ConversationTurn],
+) -> Option<HashMap<String, Value>> {
+    let mut merged = params.unwrap_or_default();
+    if let Some(turn) = context.last() {
+        if let Some(bindings) = &turn.bindings {
+            for (key, value) in bindings {
+                merged.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+    }
+    if merged.is_empty() {
+        None
+    } else {
+        Some(merged)
+    }
+}
+
+pub fn summarize_records(records: &[Value]) -> String {
+    if records.is_empty() {
+        return "rows=0".to_string();
+    }
+
+    let rows = records.len();
+    let mut columns: Vec<String> = records
+        .first()
+        .and_then(|v| v.as_object())
+        .map(|obj| obj.keys().cloned().collect())
+        .unwrap_or_default();
+    columns.sort();
+
+    let mut summary = format!("rows={rows}");
+    if !columns.is_empty() {
+        summary.push_str(", columns=");
+        summary.push_str(&columns.join(","));
+    }
+
+    let samples: Vec<String> = records
+        .iter()
+        .take(2)
+        .map(|value| summarize_record(value, &columns))
+        .collect();
+    if !samples.is_empty() {
+        summary.push_str("; sample=");
+        summary.push_str(&samples.join(" | "));
+    }
+
+    truncate_text(&summary, 400)
+}
+
+pub fn summarize_record(value: &Value, columns: &[String]) -> String {
+    if let Some(obj) = value.as_object() {
+        let keys: Vec<String> = if columns.is_empty() {
+            let mut keys: Vec<String> = obj.keys().cloned().collect();
+            keys.sort();
+            keys
+        } else {
+            columns.to_vec()
+        };
+        let mut parts = Vec::new();
+        for key in keys.into_iter().take(6) {
+            let entry = obj
+                .get(&key)
+                .map(format_value)
+                .unwrap_or_else(|| "null".to_string());
+            parts.push(format!("{key}={}", truncate_text(&entry, 60)));
+        }
+        return parts.join(", ");
+    }
+
+    truncate_text(&format_value(value), 120)
+}
+
+pub fn extract_context_bindings(records: &[Value]) -> Option<HashMap<String, Value>> {
+    if records.is_empty() {
+        return None;
+    }
+    let first = records.first().and_then(|value| value.as_object())?;
+    let columns: std::collections::HashSet<String> = first.keys().cloned().collect();
+    let has_pod = columns.contains("pod") || columns.contains("pod_name");
+    let has_service = columns.contains("service") || columns.contains("service_name");
+
+    let mut bindings = HashMap::new();
+
+    if let Some(value) = extract_uniform_value(records, &["pod", "pod_name"]) {
+        bindings.insert("pod_name".to_string(), value);
+    }
+    if has_pod {
+        if let Some(value) = extract_uniform_value(records, &["pod_namespace", "namespace"]) {
+            bindings.insert("pod_namespace".to_string(), value);
+        }
+    }
+    if let Some(value) = extract_uniform_value(records, &["service", "service_name"]) {
+        bindings.insert("service_name".to_string(), value);
+    }
+    if has_service {
+        if let Some(value) = extract_uniform_value(records, &["service_namespace"]) {
+            bindings.insert("service_namespace".to_string(), value);
+        } else if !has_pod {
+            if let Some(value) = extract_uniform_value(records, &["namespace"]) {
+                bindings.insert("service_namespace".to_string(), value);
+            }
+        }
+    }
+    if let Some(value) = extract_uniform_value(records, &["ingress", "ingress_name"]) {
+        bindings.insert("ingress_name".to_string(), value);
+    }
+    if let Some(value) = extract_uniform_value(records, &["ingress_namespace"]) {
+        bindings.insert("ingress_namespace".to_string(), value);
+    }
+    if let Some(value) = extract_uniform_value(records, &["host", "hostname"]) {
+        bindings.insert("host".to_string(), value);
+    }
+
+    if bindings.is_empty() {
+        None
+    } else {
+        Some(bindings)
+    }
+}
+
+pub fn extract_uniform_value(records: &[Value], keys: &[&str]) -> Option<Value> {
+    for key in keys {
+        let mut value: Option<Value> = None;
+        let mut count = 0usize;
+        for record in records {
+            let obj = record.as_object()?;
+            let entry = match obj.get(*key) {
+                Some(entry) => entry,
+                None => {
+                    value = None;
+                    count = 0;
+                    break;
+                }
+            };
+            count += 1;
+            match &value {
+                None => value = Some(entry.clone()),
+                Some(existing) if existing == entry => {}
+                Some(_) => {
+                    value = None;
+                    break;
+                }
+            }
+        }
+        if count == records.len() {
+            if let Some(found) = value {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+pub fn truncate_text(text: &str, max_len: usize) -> String {
+    if text.len() <= max_len {
+        return text.to_string();
+    }
+    let mut end = max_len.saturating_sub(3);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let mut trimmed = text[..end].to_string();
+    trimmed.push_str("...");
+    trimmed
+}
+
+pub fn format_value(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(v) => v.to_string(),
+        Value::Number(v) => v.to_string(),
+        Value::String(v) => v.clone(),
+        Value::Array(arr) => format_array_value(arr),
+        Value::Object(obj) => format!("object({})", obj.len()),
+    }
+}
+
+pub fn format_array_value(arr: &[Value]) -> String {
+    if arr.is_empty() {
+        return "[]".to_string();
+    }
+
+    let max_items = 6usize;
+    let mut parts = Vec::new();
+    for value in arr.iter().take(max_items) {
+        parts.push(format_array_item(value));
+    }
+
+    let mut out = parts.join(", ");
+    if arr.len() > max_items {
+        out.push_str(&format!(", ... (+{})", arr.len() - max_items));
+    }
+
+    format!("[{out}]")
+}
+
+pub fn format_array_item(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(v) => v.to_string(),
+        Value::Number(v) => v.to_string(),
+        Value::String(v) => v.clone(),
+        Value::Array(arr) => format!("array({})", arr.len()),
+        Value::Object(obj) => format!("object({})", obj.len()),
+    }
+}
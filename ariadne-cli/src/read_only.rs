@@ -0,0 +1,137 @@
+//! A keyword scan for Cypher that changes the graph. It runs before the query is parsed, so a
+//! write is refused even when the parser does not understand the query or a backend would
+//! accept it.
+
+/// Keywords that start a clause writing data, the schema or the database configuration.
+const WRITE_KEYWORDS: &[&str] = &[
+    "CREATE",
+    "MERGE",
+    "DELETE",
+    "DETACH",
+    "SET",
+    "REMOVE",
+    "DROP",
+    "FOREACH",
+    "ALTER",
+    "GRANT",
+    "REVOKE",
+    "DENY",
+    "TERMINATE",
+];
+
+/// The first write keyword of `cypher` that is in a clause position, or `None` for a
+/// read-only query. Strings, quoted names, comments, properties (`n.set`), labels (`:Create`),
+/// parameters (`$delete`), map keys (`{merge: 1}`) and aliases (`AS remove`) are skipped.
+pub fn write_clause(cypher: &str) -> Option<&'static str> {
+    let chars: Vec<char> = cypher.chars().collect();
+    let mut previous_symbol: Option<char> = None;
+    let mut previous_word: Option<String> = None;
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        if c == '\'' || c == '"' || c == '`' {
+            index = skip_quoted(&chars, index);
+            previous_symbol = Some(c);
+            previous_word = None;
+            continue;
+        }
+        if c == '/' && chars.get(index + 1) == Some(&'/') {
+            while index < chars.len() && chars[index] != '\n' {
+                index += 1;
+            }
+            continue;
+        }
+        if c == '/' && chars.get(index + 1) == Some(&'*') {
+            index += 2;
+            while index < chars.len()
+                && !(chars[index] == '*' && chars.get(index + 1) == Some(&'/'))
+            {
+                index += 1;
+            }
+            index += 2;
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' {
+            let start = index;
+            while index < chars.len() && (chars[index].is_alphanumeric() || chars[index] == '_') {
+                index += 1;
+            }
+            let word: String = chars[start..index]
+                .iter()
+                .collect::<String>()
+                .to_uppercase();
+            let next = chars[index..].iter().find(|c| !c.is_whitespace());
+            let clause_position = !matches!(previous_symbol, Some('.' | ':' | '$'))
+                && next != Some(&':')
+                && previous_word.as_deref() != Some("AS");
+            if clause_position {
+                if let Some(keyword) = WRITE_KEYWORDS.iter().find(|keyword| **keyword == word) {
+                    return Some(keyword);
+                }
+            }
+            previous_symbol = None;
+            previous_word = Some(word);
+            continue;
+        }
+        if !c.is_whitespace() {
+            previous_symbol = Some(c);
+            previous_word = None;
+        }
+        index += 1;
+    }
+    None
+}
+
+/// Index just past the string or quoted name opening at `start`.
+fn skip_quoted(chars: &[char], start: usize) -> usize {
+    let quote = chars[start];
+    let mut index = start + 1;
+    while index < chars.len() {
+        if chars[index] == '\\' && quote != '`' {
+            index += 2;
+            continue;
+        }
+        if chars[index] == quote {
+            return index + 1;
+        }
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_clauses_are_found_in_any_case() {
+        assert_eq!(
+            write_clause("MATCH (p:Pod) DETACH DELETE p"),
+            Some("DETACH")
+        );
+        assert_eq!(
+            write_clause("match (p:Pod) set p.x = 1 return p"),
+            Some("SET")
+        );
+        assert_eq!(write_clause("CREATE(n:Pod) RETURN n"), Some("CREATE"));
+        assert_eq!(write_clause("DROP INDEX ON :Pod(name)"), Some("DROP"));
+        assert_eq!(
+            write_clause("MATCH (n) WITH n /* note */ MERGE (m:Pod) RETURN m"),
+            Some("MERGE")
+        );
+    }
+
+    #[test]
+    fn read_only_queries_pass() {
+        let queries = [
+            "MATCH (p:Pod) WHERE p.metadata.name = 'create-users' RETURN p",
+            "MATCH (p:Pod) WHERE p.status.reason = \"Evicted: \\\"delete\\\"\" RETURN p",
+            "MATCH (n:`Set`)-[:Create]->(m) RETURN n.set, m",
+            "MATCH (p:Pod) WHERE p.metadata.name = $delete RETURN {merge: p} AS remove",
+            "MATCH (p:Pod) // set the namespace filter later\nRETURN p",
+        ];
+        for query in queries {
+            assert_eq!(write_clause(query), None, "{query}");
+        }
+    }
+}
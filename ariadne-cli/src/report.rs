@@ -0,0 +1,350 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::agent::IncidentReport;
+use crate::error::CliResult;
+use crate::pipeline::{format_value, truncate_text, ReportEntry};
+
+/// Rows per query shown in the report; the rest are only counted.
+const MAX_REPORT_ROWS: usize = 10;
+const MAX_REPORT_CELL: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "Markdown",
+            ReportFormat::Html => "HTML",
+        }
+    }
+}
+
+pub struct Report<'a> {
+    pub cluster: &'a str,
+    pub generated_at: DateTime<Utc>,
+    pub entries: &'a [ReportEntry],
+}
+
+pub fn write_report(path: &Path, report: &Report<'_>, format: ReportFormat) -> CliResult<()> {
+    let content = match format {
+        ReportFormat::Markdown => render_markdown(report),
+        ReportFormat::Html => render_html(report),
+    };
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+pub fn render_markdown(report: &Report<'_>) -> String {
+    let mut out = format!(
+        "# Incident investigation: {}\n\nGenerated {} · {} steps\n",
+        report.cluster,
+        format_time(&report.generated_at),
+        report.entries.len()
+    );
+    for (index, entry) in report.entries.iter().enumerate() {
+        out.push_str(&format!(
+            "\n## {}. {}\n\n_{}_\n",
+            index + 1,
+            entry.question,
+            format_time(&entry.asked_at)
+        ));
+        if let Some(cypher) = &entry.cypher {
+            out.push_str(&format!("\n```cypher\n{}\n```\n", cypher.trim()));
+        }
+        if let Some(error) = &entry.error {
+            out.push_str(&format!("\n> **Error:** {error}\n"));
+        }
+        if let Some(analysis) = &entry.analysis {
+            out.push_str(&format!(
+                "\n**{}** (confidence: {})\n\n{}\n",
+                analysis.title, analysis.confidence, analysis.summary
+            ));
+            if !analysis.bullets.is_empty() {
+                out.push('\n');
+                for bullet in &analysis.bullets {
+                    out.push_str(&format!("- {bullet}\n"));
+                }
+            }
+            if !analysis.citations.is_empty() {
+                out.push_str("\nSources:\n");
+                for citation in &analysis.citations {
+                    out.push_str(&format!(
+                        "[{}] [{}]({})\n",
+                        citation.number, citation.title, citation.url
+                    ));
+                }
+            }
+        }
+        if entry.cypher.is_some() && entry.error.is_none() {
+            out.push('\n');
+            out.push_str(&markdown_table(&entry.records));
+        }
+    }
+    out
+}
+
+/// The incident report the analyst wrote for the session, as Markdown.
+pub fn render_incident_markdown(report: &IncidentReport) -> String {
+    let list = |items: &[String]| {
+        if items.is_empty() {
+            return "_None._\n".to_string();
+        }
+        items
+            .iter()
+            .map(|item| format!("- {item}\n"))
+            .collect::<String>()
+    };
+    format!(
+        "# {}\n\n## Timeline\n\n{}\n## Root cause hypothesis\n\n{}\n\n## Evidence\n\n{}\n\
+         ## Follow-up actions\n\n{}\nConfidence: {}\n",
+        report.title.trim(),
+        list(&report.timeline),
+        report.root_cause.trim(),
+        list(&report.evidence),
+        list(&report.actions),
+        report.confidence.trim()
+    )
+}
+
+pub fn render_html(report: &Report<'_>) -> String {
+    let mut body = format!(
+        "<h1>Incident investigation: {}</h1>\n<p>Generated {} &middot; {} steps</p>\n",
+        escape_html(report.cluster),
+        format_time(&report.generated_at),
+        report.entries.len()
+    );
+    for (index, entry) in report.entries.iter().enumerate() {
+        body.push_str(&format!(
+            "<h2>{}. {}</h2>\n<p><em>{}</em></p>\n",
+            index + 1,
+            escape_html(&entry.question),
+            format_time(&entry.asked_at)
+        ));
+        if let Some(cypher) = &entry.cypher {
+            body.push_str(&format!(
+                "<pre><code>{}</code></pre>\n",
+                escape_html(cypher.trim())
+            ));
+        }
+        if let Some(error) = &entry.error {
+            body.push_str(&format!(
+                "<blockquote><strong>Error:</strong> {}</blockquote>\n",
+                escape_html(error)
+            ));
+        }
+        if let Some(analysis) = &entry.analysis {
+            body.push_str(&format!(
+                "<p><strong>{}</strong> (confidence: {})</p>\n<p>{}</p>\n",
+                escape_html(&analysis.title),
+                escape_html(&analysis.confidence),
+                escape_html(&analysis.summary)
+            ));
+            if !analysis.bullets.is_empty() {
+                body.push_str("<ul>\n");
+                for bullet in &analysis.bullets {
+                    body.push_str(&format!("<li>{}</li>\n", escape_html(bullet)));
+                }
+                body.push_str("</ul>\n");
+            }
+            if !analysis.citations.is_empty() {
+                body.push_str("<p>Sources:</p>\n<ol>\n");
+                for citation in &analysis.citations {
+                    body.push_str(&format!(
+                        "<li value=\"{}\"><a href=\"{}\">{}</a></li>\n",
+                        citation.number,
+                        escape_html(&citation.url),
+                        escape_html(&citation.title)
+                    ));
+                }
+                body.push_str("</ol>\n");
+            }
+        }
+        if entry.cypher.is_some() && entry.error.is_none() {
+            body.push_str(&html_table(&entry.records));
+        }
+    }
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Incident investigation: {}</title>\n\
+         <style>body{{font-family:sans-serif;max-width:960px;margin:2em auto}}\
+         pre{{background:#f4f4f4;padding:8px}}table{{border-collapse:collapse}}\
+         td,th{{border:1px solid #ccc;padding:4px 8px;text-align:left}}</style>\n\
+         </head>\n<body>\n{body}</body>\n</html>\n",
+        escape_html(report.cluster)
+    )
+}
+
+fn format_time(time: &DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+/// Column names (union of object keys in first-seen order) and the displayed cells.
+fn table_cells(records: &[Value]) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut columns: Vec<String> = Vec::new();
+    for record in records.iter().take(MAX_REPORT_ROWS) {
+        match record.as_object() {
+            Some(obj) => {
+                for key in obj.keys() {
+                    if !columns.contains(key) {
+                        columns.push(key.clone());
+                    }
+                }
+            }
+            None => {
+                if !columns.iter().any(|column| column == "value") {
+                    columns.push("value".to_string());
+                }
+            }
+        }
+    }
+    let rows = records
+        .iter()
+        .take(MAX_REPORT_ROWS)
+        .map(|record| {
+            columns
+                .iter()
+                .map(|column| {
+                    let cell = match record.as_object() {
+                        Some(obj) => obj.get(column).map(format_value).unwrap_or_default(),
+                        None if column == "value" => format_value(record),
+                        None => String::new(),
+                    };
+                    truncate_text(&cell.replace('\n', " "), MAX_REPORT_CELL)
+                })
+                .collect()
+        })
+        .collect();
+    (columns, rows)
+}
+
+fn more_rows_note(records: &[Value]) -> Option<String> {
+    (records.len() > MAX_REPORT_ROWS).then(|| {
+        format!(
+            "… and {} more rows ({} total)",
+            records.len() - MAX_REPORT_ROWS,
+            records.len()
+        )
+    })
+}
+
+fn markdown_table(records: &[Value]) -> String {
+    if records.is_empty() {
+        return "_No rows._\n".to_string();
+    }
+    let (columns, rows) = table_cells(records);
+    let escape = |cell: &str| cell.replace('|', "\\|");
+    let mut out = format!(
+        "| {} |\n|{}\n",
+        columns
+            .iter()
+            .map(|column| escape(column))
+            .collect::<Vec<_>>()
+            .join(" | "),
+        " --- |".repeat(columns.len())
+    );
+    for row in rows {
+        out.push_str(&format!(
+            "| {} |\n",
+            row.iter()
+                .map(|cell| escape(cell))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        ));
+    }
+    if let Some(note) = more_rows_note(records) {
+        out.push_str(&format!("\n_{note}_\n"));
+    }
+    out
+}
+
+fn html_table(records: &[Value]) -> String {
+    if records.is_empty() {
+        return "<p><em>No rows.</em></p>\n".to_string();
+    }
+    let (columns, rows) = table_cells(records);
+    let mut out = String::from("<table>\n<tr>");
+    for column in &columns {
+        out.push_str(&format!("<th>{}</th>", escape_html(column)));
+    }
+    out.push_str("</tr>\n");
+    for row in rows {
+        out.push_str("<tr>");
+        for cell in row {
+            out.push_str(&format!("<td>{}</td>", escape_html(&cell)));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+    if let Some(note) = more_rows_note(records) {
+        out.push_str(&format!("<p><em>{note}</em></p>\n"));
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn sample_entries() -> Vec<ReportEntry> {
+        let asked_at = Utc.with_ymd_and_hms(2025, 3, 1, 12, 30, 0).unwrap();
+        vec![ReportEntry {
+            asked_at,
+            question: "Which pods are crashing?".to_string(),
+            cypher: Some("MATCH (p:Pod) RETURN p.metadata.name AS name".to_string()),
+            records: vec![json!({"name": "web|1"}), json!({"name": "<api>"})],
+            analysis: None,
+            error: None,
+        }]
+    }
+
+    #[test]
+    fn markdown_report_includes_query_and_rows() {
+        let entries = sample_entries();
+        let report = Report {
+            cluster: "prod",
+            generated_at: entries[0].asked_at,
+            entries: &entries,
+        };
+        let markdown = render_markdown(&report);
+        assert!(markdown.starts_with("# Incident investigation: prod\n"));
+        assert!(markdown.contains("## 1. Which pods are crashing?\n\n_2025-03-01 12:30:00 UTC_"));
+        assert!(markdown.contains("```cypher\nMATCH (p:Pod)"));
+        assert!(markdown.contains("| name |\n| --- |\n| web\\|1 |\n| <api> |\n"));
+    }
+
+    #[test]
+    fn html_report_escapes_cells() {
+        let entries = sample_entries();
+        let report = Report {
+            cluster: "prod",
+            generated_at: entries[0].asked_at,
+            entries: &entries,
+        };
+        let html = render_html(&report);
+        assert!(html.contains("<td>&lt;api&gt;</td>"));
+        assert!(html.contains("<h2>1. Which pods are crashing?</h2>"));
+    }
+}
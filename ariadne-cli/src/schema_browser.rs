@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+
+use ariadne_tools::examples::{relationship_examples, ExampleQuery};
+use ariadne_tools::schema::shorten_references;
+use ariadne_tools::{generate_schema, graph_relationships};
+
+/// A node label of the graph as shown by the schema browser.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaLabel {
+    pub name: String,
+    /// Top-level properties and their (shortened) types.
+    pub properties: Vec<(String, String)>,
+    /// Relationships in which this label is the source or the target.
+    pub relationships: Vec<SchemaRelationship>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaRelationship {
+    pub from: String,
+    pub edge: String,
+    pub to: String,
+    /// Read-only queries over this edge: a few connected pairs, then the targets with the most
+    /// sources when targets have a name.
+    pub examples: Vec<ExampleQuery>,
+}
+
+impl SchemaRelationship {
+    pub fn pattern(&self) -> String {
+        format!("(:{})-[:{}]->(:{})", self.from, self.edge, self.to)
+    }
+}
+
+/// Every label of the graph schema with its properties and relationships, sorted by name.
+/// Labels that only appear in relationships are listed without properties.
+pub fn schema_catalog() -> Vec<SchemaLabel> {
+    let schema = generate_schema();
+    let mut labels: BTreeMap<String, SchemaLabel> = BTreeMap::new();
+    for info in &schema {
+        let name = info.root_type.name.clone();
+        let properties = info
+            .root_type
+            .properties
+            .iter()
+            .map(|property| {
+                (
+                    property.name.clone(),
+                    shorten_references(&property.data_type),
+                )
+            })
+            .collect();
+        labels.insert(
+            name.clone(),
+            SchemaLabel {
+                name,
+                properties,
+                relationships: Vec::new(),
+            },
+        );
+    }
+
+    for relationship in graph_relationships() {
+        let relationship = SchemaRelationship {
+            examples: relationship_examples(&relationship, &schema),
+            from: relationship.from,
+            edge: relationship.edge,
+            to: relationship.to,
+        };
+        for name in [&relationship.from, &relationship.to] {
+            let label = labels.entry(name.clone()).or_insert_with(|| SchemaLabel {
+                name: name.clone(),
+                ..SchemaLabel::default()
+            });
+            if !label.relationships.contains(&relationship) {
+                label.relationships.push(relationship.clone());
+            }
+        }
+    }
+    labels.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::{unknown_labels, validate_cypher};
+
+    #[test]
+    fn catalog_lists_labels_with_properties_and_edges() {
+        let catalog = schema_catalog();
+        let pod = catalog
+            .iter()
+            .find(|label| label.name == "Pod")
+            .expect("Pod label");
+        assert!(pod.properties.iter().any(|(name, _)| name == "metadata"));
+        let runs_on = pod
+            .relationships
+            .iter()
+            .find(|relationship| relationship.edge == "RunsOn")
+            .expect("Pod RunsOn edge");
+        assert_eq!(runs_on.pattern(), "(:Pod)-[:RunsOn]->(:Node)");
+        assert_eq!(
+            runs_on.examples[0].query,
+            "MATCH (a:Pod)-[:RunsOn]->(b:Node) \
+             RETURN a.metadata.name AS source, b.metadata.name AS target LIMIT 25"
+        );
+
+        let names: Vec<&str> = catalog.iter().map(|label| label.name.as_str()).collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+    }
+
+    #[test]
+    fn example_queries_pass_validation() {
+        for label in schema_catalog() {
+            for relationship in &label.relationships {
+                for example in &relationship.examples {
+                    assert!(
+                        validate_cypher(&example.query).is_ok(),
+                        "{}: {}",
+                        relationship.pattern(),
+                        example.query
+                    );
+                    assert_eq!(unknown_labels(&example.query), Vec::<String>::new());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::CliResult;
+use crate::i18n::Locale;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeChoice {
+    #[default]
+    Dark,
+    Light,
+    /// Black background, white text and saturated accents for bright rooms and low vision.
+    HighContrast,
+    Custom,
+}
+
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+impl ThemeChoice {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ThemeChoice::Dark => "Dark",
+            ThemeChoice::Light => "Light",
+            ThemeChoice::HighContrast => "High contrast",
+            ThemeChoice::Custom => "Custom",
+        }
+    }
+}
+
+/// User preferences that survive restarts. Unknown or missing keys fall back to defaults so
+/// older settings files keep loading.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuiSettings {
+    pub theme: ThemeChoice,
+    /// Base theme for `custom`: `dark`, `light` or `high_contrast`.
+    pub custom_base: ThemeChoice,
+    /// Palette overrides for the custom theme, keyed by slot name (e.g. `"accent": "#4f9bd9"`).
+    pub custom_colors: BTreeMap<String, String>,
+    /// Questions saved with `/save`, offered in the command palette.
+    pub saved_queries: Vec<SavedQuery>,
+    /// Defaults for flags that were not given on the command line or in the environment.
+    pub connection: ConnectionSettings,
+    /// Zoom factor of the whole UI, changed with Ctrl +/-; unset means 1.0.
+    pub ui_scale: Option<f32>,
+    /// Feed results pinned to the Dashboard tab.
+    pub pinned_queries: Vec<PinnedQuery>,
+    /// Debug overlay: profile every query and show the engine's statistics under the result.
+    pub show_query_stats: bool,
+    /// Language of the GUI; unset follows `LC_ALL`/`LANG` and falls back to English.
+    pub locale: Option<Locale>,
+}
+
+pub const MIN_UI_SCALE: f32 = 0.5;
+pub const MAX_UI_SCALE: f32 = 3.0;
+
+/// Endpoints edited in the Settings window. Every field is optional; an explicit flag or env
+/// var always wins over the value stored here.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConnectionSettings {
+    /// `llm` backend name, e.g. `openai` or `ollama`.
+    pub llm_backend: Option<String>,
+    pub llm_base_url: Option<String>,
+    pub llm_model: Option<String>,
+    pub llm_api_key: Option<String>,
+    pub memgraph_url: Option<String>,
+    pub poll_interval_secs: Option<u64>,
+    /// Language the analyst answers in, e.g. `German`.
+    pub answer_language: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedQuery {
+    pub name: String,
+    /// May contain `{{placeholders}}` that are asked for when the query is run.
+    pub question: String,
+    /// Cypher run as is instead of translating `question`, which then only labels the result.
+    /// Its `{{placeholders}}` are passed as query parameters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cypher: Option<String>,
+}
+
+/// A query kept on the Dashboard tab and re-run every [`PinnedQuery::interval`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PinnedQuery {
+    pub title: String,
+    pub cypher: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<HashMap<String, Value>>,
+    /// Seconds between runs; unset means [`DEFAULT_PIN_INTERVAL_SECS`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_secs: Option<u64>,
+}
+
+pub const DEFAULT_PIN_INTERVAL_SECS: u64 = 60;
+pub const MIN_PIN_INTERVAL_SECS: u64 = 5;
+
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+impl PinnedQuery {
+    pub fn interval(&self) -> Duration {
+        let secs = self
+            .interval_secs
+            .unwrap_or(DEFAULT_PIN_INTERVAL_SECS)
+            .max(MIN_PIN_INTERVAL_SECS);
+        Duration::from_secs(secs)
+    }
+}
+
+impl GuiSettings {
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub fn locale(&self) -> Locale {
+        self.locale.or_else(Locale::from_env).unwrap_or_default()
+    }
+
+    /// The saved zoom factor, clamped to [`MIN_UI_SCALE`]..=[`MAX_UI_SCALE`].
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub fn ui_scale(&self) -> f32 {
+        match self.ui_scale {
+            Some(scale) if scale.is_finite() => scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE),
+            _ => 1.0,
+        }
+    }
+
+    pub fn load() -> Self {
+        let Some(path) = settings_path() else {
+            return Self::default();
+        };
+        match Self::load_from(&path) {
+            Ok(settings) => settings,
+            Err(err) => {
+                tracing::warn!("Failed to load settings from {}: {err}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    pub fn load_from(path: &Path) -> CliResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub fn save(&self) -> CliResult<()> {
+        let path = settings_path().ok_or("no settings directory available")?;
+        self.save_to(&path)
+    }
+
+    pub fn save_to(&self, path: &Path) -> CliResult<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        // The file may hold an API key.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn settings_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("settings.json"))
+}
+
+/// Per-user config directory for the CLI (`$XDG_CONFIG_HOME/ariadne-cli` or platform default).
+pub fn config_dir() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(path).join("ariadne-cli"));
+    }
+    let home = std::env::var("HOME").ok()?;
+    if cfg!(target_os = "macos") {
+        Some(PathBuf::from(home).join("Library/Application Support/ariadne-cli"))
+    } else {
+        Some(PathBuf::from(home).join(".config").join("ariadne-cli"))
+    }
+}
+
+/// Per-user data directory for the CLI (`$XDG_DATA_HOME/ariadne-cli` or platform default).
+pub fn data_dir() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("XDG_DATA_HOME") {
+        return Some(PathBuf::from(path).join("ariadne-cli"));
+    }
+    let home = std::env::var("HOME").ok()?;
+    if cfg!(target_os = "macos") {
+        Some(PathBuf::from(home).join("Library/Application Support/ariadne-cli"))
+    } else {
+        Some(PathBuf::from(home).join(".local/share").join("ariadne-cli"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip_and_defaults() {
+        let dir = std::env::temp_dir().join(format!("ariadne-settings-{}", std::process::id()));
+        let path = dir.join("settings.json");
+        assert_eq!(
+            GuiSettings::load_from(&path).unwrap(),
+            GuiSettings::default()
+        );
+
+        let mut settings = GuiSettings {
+            theme: ThemeChoice::Custom,
+            ..GuiSettings::default()
+        };
+        settings
+            .custom_colors
+            .insert("accent".to_string(), "#112233".to_string());
+        settings.connection.llm_model = Some("gpt-5-mini".to_string());
+        settings.connection.poll_interval_secs = Some(30);
+        settings.ui_scale = Some(1.25);
+        settings.show_query_stats = true;
+        settings.locale = Some(Locale::German);
+        settings.pinned_queries.push(PinnedQuery {
+            title: "Failing pods".to_string(),
+            cypher: "MATCH (p:Pod) RETURN count(p) AS pods".to_string(),
+            params: None,
+            interval_secs: Some(1),
+        });
+        settings.save_to(&path).unwrap();
+        assert_eq!(GuiSettings::load_from(&path).unwrap(), settings);
+        assert_eq!(
+            settings.pinned_queries[0].interval(),
+            Duration::from_secs(MIN_PIN_INTERVAL_SECS)
+        );
+
+        std::fs::write(&path, r#"{"theme":"light"}"#).unwrap();
+        let partial = GuiSettings::load_from(&path).unwrap();
+        assert_eq!(partial.theme, ThemeChoice::Light);
+        assert!(partial.custom_colors.is_empty());
+        assert_eq!(partial.connection, ConnectionSettings::default());
+        assert_eq!(partial.ui_scale(), 1.0);
+
+        std::fs::write(&path, r#"{"theme":"high_contrast","ui_scale":9.0}"#).unwrap();
+        let scaled = GuiSettings::load_from(&path).unwrap();
+        assert_eq!(scaled.theme, ThemeChoice::HighContrast);
+        assert_eq!(scaled.ui_scale(), MAX_UI_SCALE);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::{Node, PersistentVolumeClaim, Pod};
+use strum::IntoEnumIterator;
+
+use ariadne_core::state::ClusterState;
+use ariadne_core::types::{Edge, ResourceAttributes, ResourceType};
+
+const MAX_QUESTIONS: usize = 5;
+
+/// Container waiting reasons that mean a pod is stuck rather than starting up.
+const STUCK_REASONS: &[&str] = &[
+    "CrashLoopBackOff",
+    "ImagePullBackOff",
+    "ErrImagePull",
+    "CreateContainerConfigError",
+    "InvalidImageName",
+];
+
+/// A question generated from something unusual in the cluster. `prompt` is what the user sees,
+/// `question` is what gets asked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuggestedQuestion {
+    pub prompt: String,
+    pub question: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Anomaly {
+    /// Pods whose containers wait with the given reason.
+    StuckPods(String),
+    PendingPods,
+    NodesNotReady,
+    PendingClaims,
+    UnavailableDeployments,
+}
+
+/// Input completions taken from the graph schema and the live cluster: resource kinds,
+/// relationship types and namespace names.
+pub fn build_completions(state: &ClusterState) -> Vec<String> {
+    let mut completions: BTreeSet<String> = ResourceType::iter().map(|r| r.to_string()).collect();
+    completions.extend(Edge::iter().map(|edge| edge.to_string()));
+    completions.extend(
+        state
+            .get_nodes_by_type(&ResourceType::Namespace)
+            .map(|namespace| namespace.id.name.clone()),
+    );
+    completions.into_iter().collect()
+}
+
+/// Questions about the most widespread anomalies in the cluster, largest first.
+pub fn anomaly_questions(state: &ClusterState) -> Vec<SuggestedQuestion> {
+    let mut counts: BTreeMap<(Anomaly, Option<String>), usize> = BTreeMap::new();
+    for object in state.get_nodes() {
+        let namespace = object.id.namespace.clone();
+        let anomaly = match object.attributes.as_deref() {
+            Some(ResourceAttributes::Pod { pod }) => pod_anomaly(pod),
+            Some(ResourceAttributes::Node { node }) => {
+                (!node_ready(node)).then_some(Anomaly::NodesNotReady)
+            }
+            Some(ResourceAttributes::PersistentVolumeClaim { pvc }) => {
+                claim_pending(pvc).then_some(Anomaly::PendingClaims)
+            }
+            Some(ResourceAttributes::Deployment { deployment }) => {
+                deployment_unavailable(deployment).then_some(Anomaly::UnavailableDeployments)
+            }
+            _ => None,
+        };
+        if let Some(anomaly) = anomaly {
+            let namespace = match anomaly {
+                Anomaly::NodesNotReady => None,
+                _ => namespace,
+            };
+            *counts.entry((anomaly, namespace)).or_default() += 1;
+        }
+    }
+    questions_from_counts(counts)
+}
+
+fn questions_from_counts(
+    counts: BTreeMap<(Anomaly, Option<String>), usize>,
+) -> Vec<SuggestedQuestion> {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1));
+    counts
+        .into_iter()
+        .take(MAX_QUESTIONS)
+        .map(|((anomaly, namespace), count)| describe_anomaly(&anomaly, namespace, count))
+        .collect()
+}
+
+fn describe_anomaly(
+    anomaly: &Anomaly,
+    namespace: Option<String>,
+    count: usize,
+) -> SuggestedQuestion {
+    let plural = count != 1;
+    let (subject, verb) = match anomaly {
+        Anomaly::StuckPods(_) | Anomaly::PendingPods => (
+            if plural { "pods" } else { "pod" },
+            if plural { "are" } else { "is" },
+        ),
+        Anomaly::NodesNotReady => (
+            if plural { "nodes" } else { "node" },
+            if plural { "are" } else { "is" },
+        ),
+        Anomaly::PendingClaims => (
+            if plural { "PVCs" } else { "PVC" },
+            if plural { "are" } else { "is" },
+        ),
+        Anomaly::UnavailableDeployments => (
+            if plural { "deployments" } else { "deployment" },
+            if plural { "have" } else { "has" },
+        ),
+    };
+    let state = match anomaly {
+        Anomaly::StuckPods(reason) => reason.clone(),
+        Anomaly::PendingPods | Anomaly::PendingClaims => "Pending".to_string(),
+        Anomaly::NodesNotReady => "NotReady".to_string(),
+        Anomaly::UnavailableDeployments => "unavailable replicas".to_string(),
+    };
+    let location = namespace
+        .as_deref()
+        .map(|namespace| format!(" in {namespace}"))
+        .unwrap_or_default();
+    let scope = namespace
+        .as_deref()
+        .map(|namespace| format!(" in namespace {namespace}"))
+        .unwrap_or_default();
+    let question = match anomaly {
+        Anomaly::StuckPods(reason) => {
+            format!("Which pods{scope} are in {reason}, and which workloads own them?")
+        }
+        Anomaly::PendingPods => {
+            format!("Which pods{scope} are Pending, and on which nodes or claims do they wait?")
+        }
+        Anomaly::NodesNotReady => {
+            "Which nodes are not Ready, and which pods run on them?".to_string()
+        }
+        Anomaly::PendingClaims => {
+            format!("Which PVCs{scope} are Pending, and which storage classes do they use?")
+        }
+        Anomaly::UnavailableDeployments => {
+            format!(
+                "Which deployments{scope} have unavailable replicas, and what are their pods \
+                 doing?"
+            )
+        }
+    };
+    SuggestedQuestion {
+        prompt: format!("{count} {subject} {verb} {state}{location} — investigate?"),
+        question,
+    }
+}
+
+fn pod_anomaly(pod: &Pod) -> Option<Anomaly> {
+    let status = pod.status.as_ref()?;
+    let stuck = status
+        .container_statuses
+        .iter()
+        .flatten()
+        .chain(status.init_container_statuses.iter().flatten())
+        .find_map(|container| {
+            let reason = container
+                .state
+                .as_ref()?
+                .waiting
+                .as_ref()?
+                .reason
+                .as_deref()?;
+            STUCK_REASONS.contains(&reason).then(|| reason.to_string())
+        });
+    if let Some(reason) = stuck {
+        return Some(Anomaly::StuckPods(reason));
+    }
+    (status.phase.as_deref() == Some("Pending")).then_some(Anomaly::PendingPods)
+}
+
+fn node_ready(node: &Node) -> bool {
+    node.status
+        .as_ref()
+        .and_then(|status| status.conditions.as_ref())
+        .and_then(|conditions| conditions.iter().find(|c| c.type_ == "Ready"))
+        .is_none_or(|condition| condition.status == "True")
+}
+
+fn claim_pending(pvc: &PersistentVolumeClaim) -> bool {
+    pvc.status
+        .as_ref()
+        .and_then(|status| status.phase.as_deref())
+        == Some("Pending")
+}
+
+fn deployment_unavailable(deployment: &Deployment) -> bool {
+    deployment
+        .status
+        .as_ref()
+        .and_then(|status| status.unavailable_replicas)
+        .is_some_and(|unavailable| unavailable > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::{
+        ContainerState, ContainerStateWaiting, ContainerStatus, PodStatus,
+    };
+
+    fn pod_waiting(reason: &str) -> Pod {
+        Pod {
+            status: Some(PodStatus {
+                phase: Some("Running".to_string()),
+                container_statuses: Some(vec![ContainerStatus {
+                    name: "app".to_string(),
+                    state: Some(ContainerState {
+                        waiting: Some(ContainerStateWaiting {
+                            reason: Some(reason.to_string()),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pod_anomaly_prefers_stuck_containers() {
+        assert_eq!(
+            pod_anomaly(&pod_waiting("CrashLoopBackOff")),
+            Some(Anomaly::StuckPods("CrashLoopBackOff".to_string()))
+        );
+        assert_eq!(pod_anomaly(&pod_waiting("ContainerCreating")), None);
+
+        let pending = Pod {
+            status: Some(PodStatus {
+                phase: Some("Pending".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(pod_anomaly(&pending), Some(Anomaly::PendingPods));
+    }
+
+    #[test]
+    fn questions_are_ordered_by_count() {
+        let counts = BTreeMap::from([
+            ((Anomaly::PendingClaims, Some("data".to_string())), 1),
+            (
+                (
+                    Anomaly::StuckPods("CrashLoopBackOff".to_string()),
+                    Some("payments".to_string()),
+                ),
+                3,
+            ),
+        ]);
+        let questions = questions_from_counts(counts);
+        assert_eq!(
+            questions[0].prompt,
+            "3 pods are CrashLoopBackOff in payments — investigate?"
+        );
+        assert_eq!(
+            questions[1].prompt,
+            "1 PVC is Pending in data — investigate?"
+        );
+        assert!(questions[0].question.contains("namespace payments"));
+    }
+}
@@ -0,0 +1,511 @@
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+use tokio::runtime::Handle;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::agent::{LlmConfig, LlmUsage};
+
+/// Spans sent in one export request at most.
+const BATCH_SIZE: usize = 64;
+/// Longest time a finished span waits before it is exported.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long shutdown waits for the last spans to be exported.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+static EXPORTER: OnceLock<mpsc::UnboundedSender<ExportMessage>> = OnceLock::new();
+
+tokio::task_local! {
+    /// The span of the question being answered, which the LLM calls made for it are children of.
+    static QUESTION: SpanContext;
+}
+
+/// Where LLM call spans are sent, read from the standard `OTEL_*` variables. Spans go to the
+/// collector as OTLP over HTTP with JSON bodies. The calls made for one question share a trace,
+/// under a span of the question; see [`in_question`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtlpConfig {
+    pub traces_url: String,
+    pub headers: Vec<(String, String)>,
+    pub service_name: String,
+}
+
+impl OtlpConfig {
+    /// `None` unless `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT` or `OTEL_EXPORTER_OTLP_ENDPOINT` is
+    /// set; the latter gets `/v1/traces` appended.
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let traces_url = var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT").or_else(|| {
+            var("OTEL_EXPORTER_OTLP_ENDPOINT")
+                .map(|endpoint| format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+        })?;
+        let headers = var("OTEL_EXPORTER_OTLP_HEADERS")
+            .map(|headers| parse_headers(&headers))
+            .unwrap_or_default();
+        Some(Self {
+            traces_url,
+            headers,
+            service_name: var("OTEL_SERVICE_NAME").unwrap_or_else(|| "ariadne-cli".to_string()),
+        })
+    }
+}
+
+/// `key=value` pairs separated by commas, as in `OTEL_EXPORTER_OTLP_HEADERS`.
+fn parse_headers(headers: &str) -> Vec<(String, String)> {
+    headers
+        .split(',')
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            let key = key.trim();
+            (!key.is_empty()).then(|| (key.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+enum ExportMessage {
+    Span(FinishedSpan),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Starts exporting LLM call spans on `runtime` when an OTLP endpoint is configured.
+pub fn init_telemetry(runtime: &Handle) {
+    let Some(config) = OtlpConfig::from_env() else {
+        return;
+    };
+    let (tx, rx) = mpsc::unbounded_channel();
+    if EXPORTER.set(tx).is_err() {
+        return;
+    }
+    tracing::info!("Exporting LLM call spans to {}", config.traces_url);
+    runtime.spawn(export_loop(config, rx));
+}
+
+/// Exports the spans that are still waiting, giving up after a few seconds.
+pub async fn shutdown_telemetry() {
+    let Some(exporter) = EXPORTER.get() else {
+        return;
+    };
+    let (done_tx, done_rx) = oneshot::channel();
+    if exporter.send(ExportMessage::Flush(done_tx)).is_ok() {
+        let _ = tokio::time::timeout(SHUTDOWN_TIMEOUT, done_rx).await;
+    }
+}
+
+async fn export_loop(config: OtlpConfig, mut rx: mpsc::UnboundedReceiver<ExportMessage>) {
+    let client = match reqwest::Client::builder().timeout(EXPORT_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::warn!("Cannot export LLM call spans: {err}");
+            return;
+        }
+    };
+    let mut batch = Vec::new();
+    let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+    loop {
+        tokio::select! {
+            message = rx.recv() => match message {
+                Some(ExportMessage::Span(span)) => {
+                    batch.push(span);
+                    if batch.len() >= BATCH_SIZE {
+                        export(&client, &config, &mut batch).await;
+                    }
+                }
+                Some(ExportMessage::Flush(done)) => {
+                    export(&client, &config, &mut batch).await;
+                    let _ = done.send(());
+                }
+                None => {
+                    export(&client, &config, &mut batch).await;
+                    return;
+                }
+            },
+            _ = ticker.tick() => export(&client, &config, &mut batch).await,
+        }
+    }
+}
+
+async fn export(client: &reqwest::Client, config: &OtlpConfig, batch: &mut Vec<FinishedSpan>) {
+    if batch.is_empty() {
+        return;
+    }
+    let body = otlp_request(&config.service_name, batch);
+    batch.clear();
+    let mut request = client.post(&config.traces_url).json(&body);
+    for (key, value) in &config.headers {
+        request = request.header(key, value);
+    }
+    if let Err(err) = request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+    {
+        tracing::warn!(
+            "Failed to export LLM call spans to {}: {err}",
+            config.traces_url
+        );
+    }
+}
+
+/// The provider and model an agent calls, recorded on its spans.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanModel {
+    pub system: String,
+    pub model: String,
+}
+
+impl From<&LlmConfig> for SpanModel {
+    fn from(config: &LlmConfig) -> Self {
+        Self {
+            system: format!("{:?}", config.backend).to_lowercase(),
+            model: config.model.clone(),
+        }
+    }
+}
+
+/// Where a span sits in its trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SpanContext {
+    trace_id: u128,
+    span_id: u64,
+    parent_span_id: Option<u64>,
+}
+
+impl SpanContext {
+    /// A child of the question being answered, or the root of a trace of its own outside one.
+    fn new() -> Self {
+        let span_id = random_u64();
+        match QUESTION.try_with(|question| *question) {
+            Ok(question) => Self {
+                trace_id: question.trace_id,
+                span_id,
+                parent_span_id: Some(question.span_id),
+            },
+            Err(_) => Self {
+                trace_id: (u128::from(random_u64()) << 64) | u128::from(random_u64()),
+                span_id,
+                parent_span_id: None,
+            },
+        }
+    }
+}
+
+/// Runs `future` as the answer to one question: the LLM calls it makes share a trace, as
+/// children of a `question` span that lasts until `future` completes or is dropped. Inside
+/// another question, `future` joins its trace.
+pub async fn in_question<F: Future>(future: F) -> F::Output {
+    if QUESTION.try_with(|_| ()).is_ok() {
+        return future.await;
+    }
+    let span = QuestionSpan {
+        context: SpanContext::new(),
+        start: SystemTime::now(),
+        started: Instant::now(),
+    };
+    QUESTION.scope(span.context, future).await
+}
+
+/// Exported when dropped, so a question that is cancelled still parents the calls it made.
+struct QuestionSpan {
+    context: SpanContext,
+    start: SystemTime,
+    started: Instant,
+}
+
+impl Drop for QuestionSpan {
+    fn drop(&mut self) {
+        send(FinishedSpan {
+            operation: "question",
+            model: None,
+            context: self.context,
+            start: self.start,
+            duration: self.started.elapsed(),
+            usage: None,
+            error: None,
+        });
+    }
+}
+
+/// One LLM call, timed from [`LlmSpan::start`] until it is finished or failed. Nothing is
+/// recorded unless an OTLP endpoint is configured.
+pub struct LlmSpan {
+    operation: &'static str,
+    model: SpanModel,
+    context: SpanContext,
+    start: SystemTime,
+    started: Instant,
+}
+
+#[derive(Debug, Clone)]
+struct FinishedSpan {
+    operation: &'static str,
+    /// The model called; `None` for the span of a question.
+    model: Option<SpanModel>,
+    context: SpanContext,
+    start: SystemTime,
+    duration: Duration,
+    usage: Option<LlmUsage>,
+    error: Option<String>,
+}
+
+impl LlmSpan {
+    /// `operation` names what the call is for: `translate`, `analyze`, `compact`, ...
+    pub fn start(operation: &'static str, model: &SpanModel) -> Self {
+        Self {
+            operation,
+            model: model.clone(),
+            context: SpanContext::new(),
+            start: SystemTime::now(),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn finish(self, usage: Option<&LlmUsage>) {
+        self.export(usage.cloned(), None);
+    }
+
+    pub fn fail(self, error: &dyn fmt::Display) {
+        self.export(None, Some(error.to_string()));
+    }
+
+    fn export(self, usage: Option<LlmUsage>, error: Option<String>) {
+        send(FinishedSpan {
+            operation: self.operation,
+            model: Some(self.model),
+            context: self.context,
+            start: self.start,
+            duration: self.started.elapsed(),
+            usage,
+            error,
+        });
+    }
+}
+
+fn send(span: FinishedSpan) {
+    if let Some(exporter) = EXPORTER.get() {
+        let _ = exporter.send(ExportMessage::Span(span));
+    }
+}
+
+/// An OTLP `ExportTraceServiceRequest` in its JSON encoding.
+fn otlp_request(service_name: &str, spans: &[FinishedSpan]) -> Value {
+    let spans: Vec<Value> = spans.iter().map(otlp_span).collect();
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [string_attribute("service.name", service_name)],
+            },
+            "scopeSpans": [{
+                "scope": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+                "spans": spans,
+            }],
+        }],
+    })
+}
+
+fn otlp_span(span: &FinishedSpan) -> Value {
+    let start = span
+        .start
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let end = start + span.duration.as_nanos();
+    let mut value = json!({
+        "traceId": format!("{:032x}", span.context.trace_id),
+        "spanId": format!("{:016x}", span.context.span_id),
+        "startTimeUnixNano": start.to_string(),
+        "endTimeUnixNano": end.to_string(),
+    });
+    if let Some(parent) = span.context.parent_span_id {
+        value["parentSpanId"] = json!(format!("{parent:016x}"));
+    }
+    let Some(model) = &span.model else {
+        value["name"] = json!(span.operation);
+        // SPAN_KIND_INTERNAL
+        value["kind"] = json!(1);
+        return value;
+    };
+    let mut attributes = vec![
+        string_attribute("gen_ai.operation.name", "chat"),
+        string_attribute("gen_ai.system", &model.system),
+        string_attribute("gen_ai.request.model", &model.model),
+        string_attribute("ariadne.llm.operation", span.operation),
+    ];
+    if let Some(usage) = &span.usage {
+        attributes.push(int_attribute(
+            "gen_ai.usage.input_tokens",
+            usage.prompt_tokens.into(),
+        ));
+        attributes.push(int_attribute(
+            "gen_ai.usage.output_tokens",
+            usage.completion_tokens.into(),
+        ));
+        attributes.push(int_attribute(
+            "ariadne.llm.total_tokens",
+            usage.total_tokens.into(),
+        ));
+        if let Some(cached) = usage.cached_tokens {
+            attributes.push(int_attribute("ariadne.llm.cached_tokens", cached.into()));
+        }
+        if let Some(reasoning) = usage.reasoning_tokens {
+            attributes.push(int_attribute(
+                "ariadne.llm.reasoning_tokens",
+                reasoning.into(),
+            ));
+        }
+    }
+    let status = match &span.error {
+        Some(error) => json!({ "code": 2, "message": error }),
+        None => json!({ "code": 1 }),
+    };
+    value["name"] = json!(format!("{} {}", span.operation, model.model));
+    // SPAN_KIND_CLIENT
+    value["kind"] = json!(3);
+    value["attributes"] = json!(attributes);
+    value["status"] = status;
+    value
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn int_attribute(key: &str, value: u64) -> Value {
+    json!({ "key": key, "value": { "intValue": value.to_string() } })
+}
+
+/// A random id part; every `RandomState` is seeded differently.
+fn random_u64() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headers_are_split_into_pairs() {
+        assert_eq!(
+            parse_headers("x-api-key=secret, tenant = shop,broken"),
+            [
+                ("x-api-key".to_string(), "secret".to_string()),
+                ("tenant".to_string(), "shop".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn spans_carry_model_usage_and_status() {
+        let model = SpanModel {
+            system: "openai".to_string(),
+            model: "gpt-4o-mini".to_string(),
+        };
+        let span = FinishedSpan {
+            operation: "translate",
+            model: Some(model.clone()),
+            context: SpanContext::new(),
+            start: UNIX_EPOCH + Duration::from_secs(1),
+            duration: Duration::from_millis(250),
+            usage: Some(LlmUsage {
+                prompt_tokens: 1200,
+                completion_tokens: 80,
+                total_tokens: 1280,
+                cached_tokens: None,
+                reasoning_tokens: None,
+                model: None,
+            }),
+            error: None,
+        };
+        let failed = FinishedSpan {
+            operation: "analyze",
+            model: Some(model),
+            usage: None,
+            error: Some("timed out".to_string()),
+            ..span.clone()
+        };
+        let request = otlp_request("ariadne-cli", &[span, failed]);
+        let spans = &request["resourceSpans"][0]["scopeSpans"][0]["spans"];
+
+        let translate = &spans[0];
+        assert_eq!(translate["name"], "translate gpt-4o-mini");
+        assert_eq!(translate["traceId"].as_str().unwrap().len(), 32);
+        assert_eq!(translate["spanId"].as_str().unwrap().len(), 16);
+        assert_eq!(translate["startTimeUnixNano"], "1000000000");
+        assert_eq!(translate["endTimeUnixNano"], "1250000000");
+        assert_eq!(translate["status"]["code"], 1);
+        let attributes = translate["attributes"].as_array().unwrap();
+        assert!(attributes.contains(&int_attribute("gen_ai.usage.input_tokens", 1200)));
+        assert!(attributes.contains(&string_attribute("gen_ai.system", "openai")));
+
+        assert_eq!(spans[1]["status"]["code"], 2);
+        assert_eq!(spans[1]["status"]["message"], "timed out");
+    }
+
+    #[tokio::test]
+    async fn calls_of_a_question_share_its_trace() {
+        let model = SpanModel {
+            system: "openai".to_string(),
+            model: "gpt-4o-mini".to_string(),
+        };
+        let outside = LlmSpan::start("compact", &model).context;
+        assert_eq!(outside.parent_span_id, None);
+
+        let (question, calls) = in_question(async {
+            let question = QUESTION.with(|question| *question);
+            let translate = LlmSpan::start("translate", &model).context;
+            let analyze = in_question(async { LlmSpan::start("analyze", &model).context }).await;
+            (question, [translate, analyze])
+        })
+        .await;
+        assert_eq!(question.parent_span_id, None);
+        for call in calls {
+            assert_eq!(call.trace_id, question.trace_id);
+            assert_eq!(call.parent_span_id, Some(question.span_id));
+        }
+        assert_ne!(outside.trace_id, question.trace_id);
+
+        let span = FinishedSpan {
+            operation: "question",
+            model: None,
+            context: question,
+            start: UNIX_EPOCH,
+            duration: Duration::from_secs(2),
+            usage: None,
+            error: None,
+        };
+        let exported = otlp_span(&span);
+        assert_eq!(exported["name"], "question");
+        assert_eq!(exported["kind"], 1);
+        assert!(exported.get("parentSpanId").is_none());
+        let child = FinishedSpan {
+            operation: "translate",
+            model: Some(model),
+            context: calls[0],
+            ..span
+        };
+        assert_eq!(
+            otlp_span(&child)["parentSpanId"],
+            format!("{:016x}", question.span_id)
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::Value;
+
+/// Names of the `{{placeholders}}` in `text`, without duplicates, in order of first appearance.
+pub fn template_placeholders(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    replace_placeholders(text, |name| {
+        if !names.iter().any(|known| known == name) {
+            names.push(name.to_string());
+        }
+        None
+    });
+    names
+}
+
+/// Replaces every `{{name}}` with its value. Placeholders without a value are kept as written.
+pub fn fill_template(text: &str, values: &BTreeMap<String, String>) -> String {
+    replace_placeholders(text, |name| values.get(name).cloned())
+}
+
+/// Turns every `{{name}}` of a Cypher template into the query parameter `$name`, so values are
+/// passed as parameters instead of being spliced into the query.
+pub fn parameterize_template(cypher: &str) -> String {
+    replace_placeholders(cypher, |name| Some(format!("${name}")))
+}
+
+/// Query parameters for a Cypher template: one string per placeholder.
+pub fn template_params(values: &BTreeMap<String, String>) -> HashMap<String, Value> {
+    values
+        .iter()
+        .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+        .collect()
+}
+
+/// Calls `replace` for each `{{ name }}` whose name is an identifier and substitutes the
+/// returned text; `None` leaves the placeholder untouched.
+fn replace_placeholders(text: &str, mut replace: impl FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let placeholder = &rest[start..start + 2 + len + 2];
+        let name = rest[start + 2..start + 2 + len].trim();
+        out.push_str(&rest[..start]);
+        match is_identifier(name).then(|| replace(name)).flatten() {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(placeholder),
+        }
+        rest = &rest[start + placeholder.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_listed_and_filled() {
+        let question = "Pods in {{namespace}} restarted in the last {{ window }} ({{namespace}})";
+        assert_eq!(template_placeholders(question), vec!["namespace", "window"]);
+
+        let values = BTreeMap::from([("namespace".to_string(), "shop".to_string())]);
+        assert_eq!(
+            fill_template(question, &values),
+            "Pods in shop restarted in the last {{ window }} (shop)"
+        );
+        assert!(template_placeholders("no {{ not a name }} here {{").is_empty());
+    }
+
+    #[test]
+    fn cypher_placeholders_become_parameters() {
+        assert_eq!(
+            parameterize_template(
+                "MATCH (p:Pod) WHERE p.metadata.namespace = {{namespace}} RETURN p"
+            ),
+            "MATCH (p:Pod) WHERE p.metadata.namespace = $namespace RETURN p"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::few_shot::question_words;
+
+/// How much more a word of a text's title weighs than a word of its body.
+const TITLE_WEIGHT: f64 = 2.0;
+/// Words of questions and texts that say nothing about which concept is meant.
+const STOP_WORDS: &[&str] = &[
+    "how", "many", "each", "why", "when", "who", "where", "keep", "not", "can", "its", "one",
+    "has", "have", "been", "into", "than", "then", "only", "also", "such", "other", "them", "they",
+    "their", "was", "were", "will", "would", "should", "could", "most", "more", "some", "any",
+];
+
+/// Texts embedded as TF-IDF vectors over their words, so the ones closest to a question can be
+/// found by cosine similarity.
+#[derive(Debug, Default)]
+pub struct TextIndex {
+    /// Weight of each word, higher for words few texts use.
+    idf: HashMap<String, f64>,
+    /// The weight of each word of a text and the length of its vector.
+    vectors: Vec<(HashMap<String, f64>, f64)>,
+}
+
+impl TextIndex {
+    /// Indexes `texts` as `(title, body)` pairs; words of the title weigh more.
+    pub fn new<'a>(texts: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let texts: Vec<(HashSet<String>, HashSet<String>)> = texts
+            .into_iter()
+            .map(|(title, body)| {
+                (
+                    concept_words(title),
+                    concept_words(&format!("{title} {body}")),
+                )
+            })
+            .collect();
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for (_, words) in &texts {
+            for word in words {
+                *counts.entry(word.as_str()).or_default() += 1;
+            }
+        }
+        let total = texts.len() as f64;
+        let idf: HashMap<String, f64> = counts
+            .into_iter()
+            .map(|(word, count)| (word.to_string(), (total / count as f64).ln() + 1.0))
+            .collect();
+        let vectors = texts
+            .into_iter()
+            .map(|(title, words)| {
+                let weights: HashMap<String, f64> = words
+                    .into_iter()
+                    .map(|word| {
+                        let weight = if title.contains(&word) {
+                            idf[&word] * TITLE_WEIGHT
+                        } else {
+                            idf[&word]
+                        };
+                        (word, weight)
+                    })
+                    .collect();
+                let norm = weights.values().map(|weight| weight.powi(2)).sum::<f64>();
+                (weights, norm.sqrt())
+            })
+            .collect();
+        Self { idf, vectors }
+    }
+
+    /// The position of every text whose cosine similarity to `query` is at least `min_score`,
+    /// most similar first.
+    pub fn search(&self, query: &str, min_score: f64) -> Vec<usize> {
+        let words: Vec<String> = concept_words(query)
+            .into_iter()
+            .filter(|word| self.idf.contains_key(word))
+            .collect();
+        let norm = words
+            .iter()
+            .map(|word| self.idf[word].powi(2))
+            .sum::<f64>()
+            .sqrt();
+        if norm == 0.0 {
+            return Vec::new();
+        }
+        let mut scored: Vec<(f64, usize)> = self
+            .vectors
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (weights, text_norm))| {
+                let shared: f64 = words
+                    .iter()
+                    .filter_map(|word| Some(self.idf[word] * weights.get(word)?))
+                    .sum();
+                let score = shared / (norm * text_norm);
+                (score >= min_score).then_some((score, index))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        scored.into_iter().map(|(_, index)| index).collect()
+    }
+}
+
+/// The words of `text` as questions are matched, without words that name no concept.
+fn concept_words(text: &str) -> HashSet<String> {
+    let mut words = question_words(text);
+    words.retain(|word| !STOP_WORDS.contains(&word.as_str()));
+    words
+}
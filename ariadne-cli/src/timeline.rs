@@ -0,0 +1,152 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+/// Events concerning any of the objects in `$uids`, oldest first.
+pub const TIMELINE_QUERY: &str = "MATCH (e:Event)-[:Concerns]->(r) \
+     WHERE r.metadata.uid IN $uids \
+     RETURN r.kind AS kind, r.metadata.name AS resource, e.type AS type, e.reason AS reason, \
+     e.note AS note, e.metadata.creationTimestamp AS time \
+     ORDER BY time LIMIT 500";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineKind {
+    Created,
+    Normal,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineEntry {
+    pub time: DateTime<Utc>,
+    pub kind: TimelineKind,
+    pub resource: String,
+    pub reason: String,
+    pub note: String,
+}
+
+/// The Kubernetes objects of a result: whole-object rows and object-valued columns.
+fn result_objects(records: &[Value]) -> impl Iterator<Item = &Value> {
+    records.iter().flat_map(|record| {
+        let nested = record
+            .as_object()
+            .into_iter()
+            .flat_map(|obj| obj.values())
+            .filter(|value| value.is_object());
+        std::iter::once(record)
+            .chain(nested)
+            .filter(|value| value.pointer("/metadata/uid").is_some())
+    })
+}
+
+/// UIDs of the objects in a result, without duplicates.
+pub fn resource_uids(records: &[Value]) -> Vec<String> {
+    let mut uids: Vec<String> = Vec::new();
+    for object in result_objects(records) {
+        if let Some(uid) = object.pointer("/metadata/uid").and_then(Value::as_str) {
+            if !uids.iter().any(|known| known == uid) {
+                uids.push(uid.to_string());
+            }
+        }
+    }
+    uids
+}
+
+/// Merges the creation of the result's objects with the rows of [`TIMELINE_QUERY`], sorted by
+/// time. Rows without a parseable timestamp are left out.
+pub fn build_timeline(records: &[Value], events: &[Value]) -> Vec<TimelineEntry> {
+    let mut entries: Vec<TimelineEntry> = Vec::new();
+    for object in result_objects(records) {
+        let Some(time) = parse_time(object.pointer("/metadata/creationTimestamp")) else {
+            continue;
+        };
+        let resource = resource_label(
+            object.get("kind"),
+            object.pointer("/metadata/name").unwrap_or(&Value::Null),
+        );
+        let entry = TimelineEntry {
+            time,
+            kind: TimelineKind::Created,
+            resource,
+            reason: "Created".to_string(),
+            note: String::new(),
+        };
+        if !entries.contains(&entry) {
+            entries.push(entry);
+        }
+    }
+    entries.extend(events.iter().filter_map(|event| {
+        Some(TimelineEntry {
+            time: parse_time(event.get("time"))?,
+            kind: match event.get("type").and_then(Value::as_str) {
+                Some("Warning") => TimelineKind::Warning,
+                _ => TimelineKind::Normal,
+            },
+            resource: resource_label(
+                event.get("kind"),
+                event.get("resource").unwrap_or(&Value::Null),
+            ),
+            reason: text(event.get("reason")),
+            note: text(event.get("note")),
+        })
+    }));
+    entries.sort_by_key(|entry| entry.time);
+    entries
+}
+
+fn parse_time(value: Option<&Value>) -> Option<DateTime<Utc>> {
+    let text = value?.as_str()?;
+    DateTime::parse_from_rfc3339(text)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+fn resource_label(kind: Option<&Value>, name: &Value) -> String {
+    let name = text(Some(name));
+    match kind.and_then(Value::as_str) {
+        Some(kind) => format!("{kind}/{name}"),
+        None => name,
+    }
+}
+
+fn text(value: Option<&Value>) -> String {
+    value
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn resource_uids_cover_rows_and_nested_objects() {
+        let records = vec![
+            json!({"metadata": {"uid": "a", "name": "web"}}),
+            json!({"pod": {"metadata": {"uid": "b"}}, "node": {"metadata": {"uid": "a"}}}),
+            json!({"name": "flat"}),
+        ];
+        assert_eq!(resource_uids(&records), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn timeline_merges_creation_and_events_in_order() {
+        let records = vec![json!({
+            "kind": "Pod",
+            "metadata": {"uid": "a", "name": "web", "creationTimestamp": "2025-03-01T10:00:00Z"},
+        })];
+        let events = vec![
+            json!({"kind": "Pod", "resource": "web", "type": "Warning", "reason": "BackOff",
+                   "note": "Back-off restarting", "time": "2025-03-01T10:05:00Z"}),
+            json!({"kind": "Pod", "resource": "web", "type": "Normal", "reason": "Scheduled",
+                   "note": "", "time": "2025-03-01T10:00:01Z"}),
+            json!({"kind": "Pod", "resource": "web", "type": "Normal", "reason": "NoTime"}),
+        ];
+        let timeline = build_timeline(&records, &events);
+        let reasons: Vec<&str> = timeline.iter().map(|e| e.reason.as_str()).collect();
+        assert_eq!(reasons, vec!["Created", "Scheduled", "BackOff"]);
+        assert_eq!(timeline[0].resource, "Pod/web");
+        assert_eq!(timeline[2].kind, TimelineKind::Warning);
+    }
+}
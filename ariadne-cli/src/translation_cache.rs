@@ -0,0 +1,163 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+/// Translations kept before the oldest is dropped.
+pub const TRANSLATION_CACHE_CAPACITY: usize = 256;
+
+/// What a translation depends on: the question, the query it refines, the cluster it runs
+/// against and the translator's schema version.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    question: String,
+    previous_cypher: String,
+    cluster: String,
+    schema_version: u64,
+}
+
+impl CacheKey {
+    pub fn new(
+        question: &str,
+        previous_cypher: Option<&str>,
+        cluster: &str,
+        schema_version: u64,
+    ) -> Self {
+        Self {
+            question: normalize_question(question),
+            previous_cypher: previous_cypher
+                .map(|cypher| cypher.split_whitespace().collect::<Vec<_>>().join(" "))
+                .unwrap_or_default(),
+            cluster: cluster.to_string(),
+            schema_version,
+        }
+    }
+}
+
+/// A validated query the translator wrote for a [`CacheKey`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedTranslation {
+    pub cypher: String,
+    pub params: Option<HashMap<String, Value>>,
+    pub reasoning: Option<String>,
+}
+
+/// Validated translations of recent questions, so asking one again costs no LLM call. The
+/// oldest entry is dropped once [`TRANSLATION_CACHE_CAPACITY`] are kept.
+#[derive(Debug, Default)]
+pub struct TranslationCache {
+    entries: Mutex<Entries>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    translations: HashMap<CacheKey, CachedTranslation>,
+    order: VecDeque<CacheKey>,
+}
+
+impl TranslationCache {
+    pub fn get(&self, key: &CacheKey) -> Option<CachedTranslation> {
+        let entries = self
+            .entries
+            .lock()
+            .expect("translation cache lock poisoned");
+        entries.translations.get(key).cloned()
+    }
+
+    /// Stores `translation` for `key`, replacing an earlier one such as a query that failed.
+    pub fn insert(&self, key: CacheKey, translation: CachedTranslation) {
+        let mut entries = self
+            .entries
+            .lock()
+            .expect("translation cache lock poisoned");
+        if entries
+            .translations
+            .insert(key.clone(), translation)
+            .is_none()
+        {
+            entries.order.push_back(key);
+        }
+        while entries.order.len() > TRANSLATION_CACHE_CAPACITY {
+            if let Some(oldest) = entries.order.pop_front() {
+                entries.translations.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn remove(&self, key: &CacheKey) {
+        let mut entries = self
+            .entries
+            .lock()
+            .expect("translation cache lock poisoned");
+        if entries.translations.remove(key).is_some() {
+            entries.order.retain(|entry| entry != key);
+        }
+    }
+}
+
+/// `question` without differences that do not change its meaning: case, spacing and the
+/// punctuation at its end.
+fn normalize_question(question: &str) -> String {
+    question
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['?', '!', '.'])
+        .trim_end()
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translation(cypher: &str) -> CachedTranslation {
+        CachedTranslation {
+            cypher: cypher.to_string(),
+            params: None,
+            reasoning: None,
+        }
+    }
+
+    #[test]
+    fn equivalent_questions_share_an_entry() {
+        let cache = TranslationCache::default();
+        let key = CacheKey::new("List failing pods?", None, "prod", 7);
+        cache.insert(key, translation("MATCH (p:Pod) RETURN p"));
+
+        let same = CacheKey::new("  list   failing pods ", None, "prod", 7);
+        assert_eq!(
+            cache.get(&same),
+            Some(translation("MATCH (p:Pod) RETURN p"))
+        );
+        assert_eq!(
+            cache.get(&CacheKey::new("list failing pods", None, "staging", 7)),
+            None
+        );
+        assert_eq!(
+            cache.get(&CacheKey::new("list failing pods", None, "prod", 8)),
+            None
+        );
+        let refinement = CacheKey::new("list failing pods", Some("MATCH (n) RETURN n"), "prod", 7);
+        assert_eq!(cache.get(&refinement), None);
+
+        cache.remove(&same);
+        assert_eq!(cache.get(&same), None);
+    }
+
+    #[test]
+    fn the_oldest_entries_are_dropped() {
+        let cache = TranslationCache::default();
+        for index in 0..=TRANSLATION_CACHE_CAPACITY {
+            let key = CacheKey::new(&format!("question {index}"), None, "prod", 1);
+            cache.insert(key, translation("MATCH (n) RETURN n"));
+        }
+        assert_eq!(
+            cache.get(&CacheKey::new("question 0", None, "prod", 1)),
+            None
+        );
+        assert!(cache
+            .get(&CacheKey::new("question 1", None, "prod", 1))
+            .is_some());
+    }
+}
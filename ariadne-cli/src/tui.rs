@@ -0,0 +1,475 @@
+use std::sync::mpsc;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, List, ListItem, ListState, Paragraph, Row, Table, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use serde_json::Value;
+use tokio::runtime::Handle;
+
+use crate::agent::{AnalysisResult, ConversationTurn, RouteDecision};
+use crate::error::CliResult;
+use crate::pipeline::{
+    extract_context_bindings, format_value, summarize_records, Pipeline, PipelineEvent,
+};
+use crate::telemetry::in_question;
+
+const SHORT_TERM_CONTEXT_LIMIT: usize = 4;
+const MAX_TABLE_ROWS: usize = 200;
+const MAX_TABLE_COLUMNS: usize = 8;
+const EVENT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct TuiArgs {
+    pub runtime_handle: Handle,
+    pub pipeline: Pipeline,
+    pub cluster_label: String,
+    pub backend_label: String,
+}
+
+#[derive(Debug, Clone)]
+enum EntryState {
+    Translating,
+    Validating,
+    Running,
+    Analyzing,
+    Ready,
+    Error(String),
+}
+
+impl EntryState {
+    fn label(&self) -> &str {
+        match self {
+            EntryState::Translating => "translating",
+            EntryState::Validating => "validating",
+            EntryState::Running => "running",
+            EntryState::Analyzing => "analyzing",
+            EntryState::Ready => "ready",
+            EntryState::Error(_) => "error",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            EntryState::Ready => Color::Green,
+            EntryState::Error(_) => Color::Red,
+            _ => Color::Yellow,
+        }
+    }
+}
+
+struct Entry {
+    id: u64,
+    question: String,
+    cypher: Option<String>,
+    route: Option<RouteDecision>,
+    /// The router's answer or clarifying question, for entries that ran no query.
+    reply: Option<String>,
+    records: Vec<Value>,
+    analysis: Option<AnalysisResult>,
+    analysis_error: Option<String>,
+    state: EntryState,
+    exec_duration_ms: Option<u128>,
+}
+
+impl Entry {
+    fn new(id: u64, question: String) -> Self {
+        Self {
+            id,
+            question,
+            cypher: None,
+            route: None,
+            reply: None,
+            records: Vec::new(),
+            analysis: None,
+            analysis_error: None,
+            state: EntryState::Translating,
+            exec_duration_ms: None,
+        }
+    }
+}
+
+struct TuiApp {
+    runtime: Handle,
+    pipeline: Pipeline,
+    cluster_label: String,
+    backend_label: String,
+    entries: Vec<Entry>,
+    list_state: ListState,
+    next_id: u64,
+    input: String,
+    detail_scroll: u16,
+    events_tx: mpsc::Sender<(u64, PipelineEvent)>,
+    events_rx: mpsc::Receiver<(u64, PipelineEvent)>,
+    should_quit: bool,
+}
+
+pub fn run_tui(args: TuiArgs) -> CliResult<()> {
+    let mut terminal = ratatui::init();
+    let result = TuiApp::new(args).run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl TuiApp {
+    fn new(args: TuiArgs) -> Self {
+        let (events_tx, events_rx) = mpsc::channel();
+        Self {
+            runtime: args.runtime_handle,
+            pipeline: args.pipeline,
+            cluster_label: args.cluster_label,
+            backend_label: args.backend_label,
+            entries: Vec::new(),
+            list_state: ListState::default(),
+            next_id: 1,
+            input: String::new(),
+            detail_scroll: 0,
+            events_tx,
+            events_rx,
+            should_quit: false,
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> CliResult<()> {
+        while !self.should_quit {
+            self.drain_events();
+            terminal.draw(|frame| self.draw(frame))?;
+            if event::poll(EVENT_POLL_INTERVAL)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        self.handle_key(key.code, key.modifiers);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.should_quit = true;
+            }
+            KeyCode::Esc => self.should_quit = true,
+            KeyCode::Enter => self.submit_question(),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Up => self.select_relative(-1),
+            KeyCode::Down => self.select_relative(1),
+            KeyCode::PageUp => self.detail_scroll = self.detail_scroll.saturating_sub(10),
+            KeyCode::PageDown => self.detail_scroll = self.detail_scroll.saturating_add(10),
+            KeyCode::Char(c) => self.input.push(c),
+            _ => {}
+        }
+    }
+
+    fn select_relative(&mut self, delta: isize) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let current = self.list_state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, self.entries.len() as isize - 1);
+        self.list_state.select(Some(next as usize));
+        self.detail_scroll = 0;
+    }
+
+    fn submit_question(&mut self) {
+        let question = self.input.trim().to_string();
+        if question.is_empty() {
+            return;
+        }
+        self.input.clear();
+
+        let id = self.next_id;
+        self.next_id += 1;
+        let context = self.build_context();
+        self.entries.push(Entry::new(id, question.clone()));
+        self.list_state.select(Some(self.entries.len() - 1));
+        self.detail_scroll = 0;
+
+        let tx = self.events_tx.clone();
+        let pipeline = self.pipeline.clone();
+        self.runtime.spawn(in_question(async move {
+            let send_event = |event: PipelineEvent| {
+                let _ = tx.send((id, event));
+            };
+            let _ = pipeline.ask(&question, &context, None, &send_event).await;
+        }));
+    }
+
+    fn build_context(&self) -> Vec<ConversationTurn> {
+        let mut turns: Vec<ConversationTurn> = self
+            .entries
+            .iter()
+            .rev()
+            .filter(|entry| matches!(entry.state, EntryState::Ready))
+            .filter_map(|entry| {
+                let cypher = entry.cypher.clone()?;
+                Some(ConversationTurn {
+                    question: entry.question.clone(),
+                    cypher,
+                    result_summary: Some(summarize_records(&entry.records)),
+                    bindings: extract_context_bindings(&entry.records),
+                })
+            })
+            .take(SHORT_TERM_CONTEXT_LIMIT)
+            .collect();
+        turns.reverse();
+        turns
+    }
+
+    fn drain_events(&mut self) {
+        while let Ok((id, event)) = self.events_rx.try_recv() {
+            let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == id) else {
+                continue;
+            };
+            match event {
+                PipelineEvent::RouteDecided { route, .. } => entry.route = Some(route),
+                PipelineEvent::TranslationStarted => entry.state = EntryState::Translating,
+                PipelineEvent::Replied { text } => {
+                    entry.reply = Some(text);
+                    entry.state = EntryState::Ready;
+                }
+                PipelineEvent::EntitiesResolved { .. }
+                | PipelineEvent::AgentStepsCompleted { .. } => {}
+                PipelineEvent::TranslationCompleted { cypher, .. } => {
+                    entry.cypher = Some(cypher);
+                    entry.state = EntryState::Validating;
+                }
+                PipelineEvent::TranslationFailed { error } => {
+                    entry.state = EntryState::Error(error);
+                }
+                PipelineEvent::ValidationFailed { error, cypher } => {
+                    entry.cypher = Some(cypher);
+                    entry.state = EntryState::Error(error);
+                }
+                PipelineEvent::QueryStarted { cypher, .. } => {
+                    entry.cypher = Some(cypher);
+                    entry.state = EntryState::Running;
+                }
+                PipelineEvent::QueryCompleted {
+                    records,
+                    duration_ms,
+                    ..
+                } => {
+                    entry.records = records;
+                    entry.exec_duration_ms = Some(duration_ms);
+                    entry.state = EntryState::Ready;
+                }
+                PipelineEvent::QueryFailed {
+                    error, duration_ms, ..
+                } => {
+                    entry.exec_duration_ms = Some(duration_ms);
+                    entry.state = EntryState::Error(error);
+                }
+                PipelineEvent::AnalysisStarted => entry.state = EntryState::Analyzing,
+                PipelineEvent::AnalysisProgress { analysis } => entry.analysis = Some(analysis),
+                PipelineEvent::AnalysisCompleted { analysis, .. } => {
+                    entry.analysis = Some(analysis);
+                    entry.state = EntryState::Ready;
+                }
+                PipelineEvent::AnalysisFailed { error, .. } => {
+                    entry.analysis_error = Some(error);
+                    entry.state = EntryState::Ready;
+                }
+                PipelineEvent::FollowUpsSuggested { .. } => {}
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [header_area, body_area, input_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(5),
+            Constraint::Length(3),
+        ])
+        .areas(frame.area());
+
+        let header = Line::from(vec![
+            Span::styled(
+                "Ariadne ",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!("{} | {}  ", self.cluster_label, self.backend_label)),
+            Span::styled(
+                "Enter: ask  Up/Down: select  PgUp/PgDn: scroll  Esc: quit",
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]);
+        frame.render_widget(Paragraph::new(header), header_area);
+
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
+                .areas(body_area);
+        self.draw_history(frame, list_area);
+        self.draw_detail(frame, detail_area);
+
+        let input = Paragraph::new(self.input.as_str())
+            .block(Block::bordered().title("Ask a question about the cluster"));
+        frame.render_widget(input, input_area);
+        frame.set_cursor_position((
+            input_area.x + 1 + self.input.chars().count() as u16,
+            input_area.y + 1,
+        ));
+    }
+
+    fn draw_history(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("[{}] ", entry.state.label()),
+                        Style::default().fg(entry.state.color()),
+                    ),
+                    Span::raw(entry.question.clone()),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title("Investigations"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+    }
+
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title("Result");
+        let Some(entry) = self
+            .list_state
+            .selected()
+            .and_then(|index| self.entries.get(index))
+        else {
+            let hint = Paragraph::new("No investigations yet.").block(block);
+            frame.render_widget(hint, area);
+            return;
+        };
+
+        let [text_area, table_area] =
+            Layout::vertical([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(area);
+
+        let mut lines = vec![Line::from(Span::styled(
+            entry.question.clone(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ))];
+        if let Some(route) = entry.route {
+            lines.push(Line::from(Span::styled(
+                format!("route: {}", route.as_str()),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        if let Some(reply) = &entry.reply {
+            lines.push(Line::raw(""));
+            for line in reply.lines() {
+                lines.push(Line::from(Span::styled(
+                    line.to_string(),
+                    Style::default().fg(Color::Cyan),
+                )));
+            }
+        }
+        if let Some(cypher) = &entry.cypher {
+            lines.push(Line::raw(""));
+            for line in cypher.lines() {
+                lines.push(Line::from(Span::styled(
+                    line.to_string(),
+                    Style::default().fg(Color::Magenta),
+                )));
+            }
+        }
+        if let EntryState::Error(error) = &entry.state {
+            lines.push(Line::raw(""));
+            lines.push(Line::from(Span::styled(
+                format!("Error: {error}"),
+                Style::default().fg(Color::Red),
+            )));
+        }
+        if let Some(analysis) = &entry.analysis {
+            lines.push(Line::raw(""));
+            lines.push(Line::from(Span::styled(
+                format!("{} ({})", analysis.title, analysis.confidence),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            lines.push(Line::raw(analysis.summary.clone()));
+            for bullet in &analysis.bullets {
+                lines.push(Line::raw(format!("  - {bullet}")));
+            }
+            if !analysis.follow_ups.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "Follow-ups:",
+                    Style::default().fg(Color::DarkGray),
+                )));
+                for follow_up in &analysis.follow_ups {
+                    lines.push(Line::raw(format!("  > {follow_up}")));
+                }
+            }
+        } else if let Some(error) = &entry.analysis_error {
+            lines.push(Line::raw(""));
+            lines.push(Line::from(Span::styled(
+                format!("Analysis failed: {error}"),
+                Style::default().fg(Color::Red),
+            )));
+        }
+
+        let details = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((self.detail_scroll, 0));
+        frame.render_widget(details, text_area);
+
+        let title = match entry.exec_duration_ms {
+            Some(ms) => format!("Rows: {} ({ms} ms)", entry.records.len()),
+            None => format!("Rows: {}", entry.records.len()),
+        };
+        frame.render_widget(
+            records_table(&entry.records).block(Block::bordered().title(title)),
+            table_area,
+        );
+    }
+}
+
+fn records_table(records: &[Value]) -> Table<'static> {
+    let mut columns: Vec<String> = Vec::new();
+    for record in records.iter().take(MAX_TABLE_ROWS) {
+        if let Some(obj) = record.as_object() {
+            for key in obj.keys() {
+                if columns.len() < MAX_TABLE_COLUMNS && !columns.contains(key) {
+                    columns.push(key.clone());
+                }
+            }
+        }
+    }
+    if columns.is_empty() && !records.is_empty() {
+        columns.push("value".to_string());
+    }
+
+    let rows: Vec<Row> = records
+        .iter()
+        .take(MAX_TABLE_ROWS)
+        .map(|record| {
+            let cells: Vec<Cell> = match record.as_object() {
+                Some(obj) => columns
+                    .iter()
+                    .map(|column| Cell::from(obj.get(column).map(format_value).unwrap_or_default()))
+                    .collect(),
+                None => vec![Cell::from(format_value(record))],
+            };
+            Row::new(cells)
+        })
+        .collect();
+
+    let widths = vec![Constraint::Fill(1); columns.len().max(1)];
+    Table::new(rows, widths).header(
+        Row::new(columns.into_iter().map(Cell::from)).style(
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        ),
+    )
+}
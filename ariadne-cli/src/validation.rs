@@ -9,11 +9,15 @@ use ariadne_cypher::{
 };
 use strum::IntoEnumIterator;
 
+use crate::read_only::write_clause;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ValidationIssueKind {
     Parse,
     Semantic,
     Schema,
+    /// The query would change the graph. Never retried: asking for a write is refused.
+    ReadOnly,
 }
 
 #[derive(Debug, Clone)]
@@ -49,6 +53,16 @@ impl fmt::Display for ValidationIssue {
 impl std::error::Error for ValidationIssue {}
 
 pub fn validate_cypher(cypher: &str) -> Result<(), ValidationIssue> {
+    if let Some(keyword) = write_clause(cypher) {
+        tracing::warn!(keyword, cypher = %cypher, "Refusing a query that changes the graph");
+        return Err(ValidationIssue {
+            kind: ValidationIssueKind::ReadOnly,
+            message: format!(
+                "refused to run the query: its updating clause {keyword} would change the \
+                 graph, and only read-only queries are allowed"
+            ),
+        });
+    }
     let query = match parse_query(cypher) {
         Ok(query) => query,
         Err(err) => {
@@ -73,6 +87,33 @@ pub fn validate_cypher(cypher: &str) -> Result<(), ValidationIssue> {
     Ok(())
 }
 
+/// Labels of the query's named nodes that are not node labels of the graph, sorted. A node
+/// that takes part in no relationship passes validation with such a label but matches nothing.
+pub fn unknown_labels(cypher: &str) -> Vec<String> {
+    let Ok(query) = parse_query(cypher) else {
+        return Vec::new();
+    };
+    let mut var_labels: HashMap<String, HashSet<String>> = HashMap::new();
+    let mut patterns: Vec<Pattern> = Vec::new();
+    for clause in &query.clauses {
+        if let Clause::Match(m) = clause {
+            collect_pattern_labels(&m.pattern, &mut var_labels);
+            if let Some(expr) = &m.where_clause {
+                collect_from_expr(expr, &mut var_labels, &mut patterns);
+            }
+        }
+        collect_patterns_from_clause_exprs(clause, &mut var_labels, &mut patterns);
+    }
+    let mut unknown: Vec<String> = var_labels
+        .into_values()
+        .flatten()
+        .filter(|label| ResourceType::try_new(label).is_err())
+        .collect();
+    unknown.sort();
+    unknown.dedup();
+    unknown
+}
+
 fn validate_schema(query: &ariadne_cypher::Query) -> Result<(), ValidationIssue> {
     let mut var_labels: HashMap<String, HashSet<String>> = HashMap::new();
     let mut patterns: Vec<Pattern> = Vec::new();
@@ -0,0 +1,229 @@
+use std::collections::{BTreeSet, HashSet};
+
+use serde_json::Value;
+
+use crate::agent::AnalysisResult;
+
+/// Words that make a question worth the extra analyses by default.
+pub const DEFAULT_TRIGGERS: &[&str] = &[
+    "prod",
+    "production",
+    "outage",
+    "incident",
+    "root cause",
+    "security",
+];
+/// Confidences the analyst reports, least sure first.
+const CONFIDENCES: [&str; 3] = ["low", "medium", "high"];
+/// Record strings collected as facts an answer can mention.
+const MAX_RECORD_FACTS: usize = 2_000;
+
+/// When to run the analyst more than once on the same rows and compare the answers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VotePolicy {
+    /// Analyses per question; below two there is no voting.
+    pub votes: usize,
+    /// Lower-cased words or phrases of which a question must contain one; empty for every
+    /// question.
+    pub triggers: Vec<String>,
+}
+
+impl VotePolicy {
+    /// A policy for `triggers` as configured, where `*` stands for every question.
+    pub fn new(votes: usize, triggers: &[String]) -> Self {
+        let triggers: Vec<String> = triggers
+            .iter()
+            .map(|trigger| trigger.trim().to_lowercase())
+            .filter(|trigger| !trigger.is_empty())
+            .collect();
+        Self {
+            votes,
+            triggers: if triggers.iter().any(|trigger| trigger == "*") {
+                Vec::new()
+            } else {
+                triggers
+            },
+        }
+    }
+
+    pub fn applies(&self, question: &str) -> bool {
+        if self.votes < 2 {
+            return false;
+        }
+        let question = question.to_lowercase();
+        self.triggers.is_empty()
+            || self
+                .triggers
+                .iter()
+                .any(|trigger| contains_phrase(&question, trigger))
+    }
+}
+
+/// Whether `phrase` appears in `text` as whole words, so `prod` does not match `product`.
+fn contains_phrase(text: &str, phrase: &str) -> bool {
+    text.match_indices(phrase).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + phrase.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Merges `first`, the answer shown to the user, with `others` written for the same rows. The
+/// answers are compared by the facts they state: numbers, and names that occur in the rows.
+/// When they agree, the confidence is the lowest one reported; when they do not, it is `low`,
+/// names the disputed facts, and the diverging conclusions are added as bullets.
+pub fn merge_votes(
+    first: AnalysisResult,
+    others: &[AnalysisResult],
+    records: &[Value],
+) -> AnalysisResult {
+    if others.is_empty() {
+        return first;
+    }
+    let known = record_strings(records);
+    let first_facts = facts(&first, &known);
+    let runs = others.len() + 1;
+    let mut merged = first;
+    let mut disputed = BTreeSet::new();
+    let mut dissenting = 0;
+    let mut dissent = Vec::new();
+    for other in others {
+        let other_facts = facts(other, &known);
+        if other_facts != first_facts {
+            dissenting += 1;
+            disputed.extend(first_facts.symmetric_difference(&other_facts).cloned());
+            let summary = other.summary.trim();
+            if !summary.is_empty() && !dissent.contains(&summary) {
+                dissent.push(summary);
+            }
+        }
+    }
+    if disputed.is_empty() {
+        let lowest = std::iter::once(merged.confidence.as_str())
+            .chain(others.iter().map(|other| other.confidence.as_str()))
+            .min_by_key(|confidence| confidence_rank(confidence))
+            .unwrap_or_default()
+            .to_string();
+        merged.confidence = format!("{lowest} · {runs} runs agree");
+    } else {
+        let agreeing = runs - dissenting;
+        let disputed: Vec<String> = disputed.into_iter().take(5).collect();
+        merged.confidence = format!(
+            "low · {agreeing} of {runs} runs agree, disputed: {}",
+            disputed.join(", ")
+        );
+        merged.bullets.extend(
+            dissent
+                .into_iter()
+                .map(|summary| format!("Another run concluded: {summary}")),
+        );
+    }
+    merged
+}
+
+fn confidence_rank(confidence: &str) -> usize {
+    let confidence = confidence.trim().to_lowercase();
+    CONFIDENCES
+        .iter()
+        .position(|known| *known == confidence)
+        .unwrap_or(CONFIDENCES.len())
+}
+
+/// The lower-cased string values of the rows, which an answer may name.
+fn record_strings(records: &[Value]) -> HashSet<String> {
+    fn collect(value: &Value, out: &mut HashSet<String>) {
+        if out.len() >= MAX_RECORD_FACTS {
+            return;
+        }
+        match value {
+            Value::String(text) => {
+                out.insert(text.to_lowercase());
+            }
+            Value::Array(items) => items.iter().for_each(|item| collect(item, out)),
+            Value::Object(map) => map.values().for_each(|item| collect(item, out)),
+            _ => {}
+        }
+    }
+    let mut strings = HashSet::new();
+    for record in records {
+        collect(record, &mut strings);
+    }
+    strings
+}
+
+/// The numbers of an answer, and its words that are values of the rows.
+fn facts(analysis: &AnalysisResult, known: &HashSet<String>) -> BTreeSet<String> {
+    std::iter::once(analysis.summary.as_str())
+        .chain(analysis.bullets.iter().map(String::as_str))
+        .flat_map(str::split_whitespace)
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|word| {
+            !word.is_empty()
+                && (word.chars().all(|c| c.is_ascii_digit() || c == '.') || known.contains(word))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn answer(summary: &str, confidence: &str) -> AnalysisResult {
+        AnalysisResult {
+            title: "Failing pods".to_string(),
+            summary: summary.to_string(),
+            bullets: Vec::new(),
+            rows: Vec::new(),
+            follow_ups: Vec::new(),
+            confidence: confidence.to_string(),
+            citations: Vec::new(),
+            usage: None,
+        }
+    }
+
+    #[test]
+    fn triggers_match_whole_words() {
+        let policy = VotePolicy::new(3, &["Prod".to_string(), " ".to_string()]);
+        assert!(policy.applies("why is checkout down in prod?"));
+        assert!(!policy.applies("which product pods restart?"));
+        assert!(VotePolicy::new(2, &["*".to_string()]).applies("list pods"));
+        assert!(!VotePolicy::new(1, &[]).applies("list pods"));
+    }
+
+    #[test]
+    fn agreeing_runs_keep_the_lowest_confidence() {
+        let records = [json!({"pod": "web-1"}), json!({"pod": "web-2"})];
+        let merged = merge_votes(
+            answer("2 pods are failing: web-1 and web-2.", "high"),
+            &[answer("Pods web-2 and web-1 fail (2 in total).", "medium")],
+            &records,
+        );
+        assert_eq!(merged.confidence, "medium · 2 runs agree");
+        assert!(merged.bullets.is_empty());
+    }
+
+    #[test]
+    fn disagreements_are_flagged() {
+        let records = [json!({"pod": "web-1"}), json!({"pod": "web-2"})];
+        let merged = merge_votes(
+            answer("2 pods are failing: web-1 and web-2.", "high"),
+            &[
+                answer("2 pods are failing: web-1 and web-2.", "high"),
+                answer("Only web-1 is failing.", "high"),
+            ],
+            &records,
+        );
+        assert_eq!(
+            merged.confidence,
+            "low · 2 of 3 runs agree, disputed: 2, web-2"
+        );
+        assert_eq!(
+            merged.bullets,
+            ["Another run concluded: Only web-1 is failing."]
+        );
+    }
+}
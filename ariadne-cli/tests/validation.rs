@@ -1,4 +1,4 @@
-use ariadne_cli::validation::validate_cypher;
+use ariadne_cli::validation::{unknown_labels, validate_cypher, ValidationIssueKind};
 
 #[test]
 fn rejects_updating_clause() {
@@ -29,3 +29,20 @@ fn rejects_unknown_label() {
     let err = validate_cypher("MATCH (x:NotAReal)-[:Manages]->(p:Pod) RETURN x").unwrap_err();
     assert!(err.to_string().contains("Unknown label"));
 }
+
+#[test]
+fn lists_labels_missing_from_the_schema() {
+    let cypher = "MATCH (p:Pods) WHERE p.metadata.namespace = 'shop' RETURN p";
+    assert!(validate_cypher(cypher).is_ok());
+    assert_eq!(unknown_labels(cypher), vec!["Pods"]);
+    assert!(unknown_labels("MATCH (p:Pod)-[:RunsOn]->(n:Node) RETURN p").is_empty());
+}
+
+#[test]
+fn refuses_writes_before_parsing() {
+    let err =
+        validate_cypher("MATCH (p:Pod) FOREACH (x IN [1] | SET p.x = x) RETURN p").unwrap_err();
+    assert_eq!(err.kind, ValidationIssueKind::ReadOnly);
+    assert!(err.to_string().contains("read-only"));
+    assert!(!err.retriable());
+}
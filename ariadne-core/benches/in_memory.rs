@@ -0,0 +1,107 @@
+//! Query latency of the in-memory engine on synthetic clusters of 100, 1k and 10k pods, generated
+//! by [`ariadne_core::synth`] like the load tests of `ariadne-tools synth`.
+//!
+//! Run with `cargo bench -p ariadne-core --features synth --bench in_memory`; see
+//! docs/development.md for comparing against a saved baseline.
+
+use std::hint::black_box;
+
+use ariadne_core::graph_backend::GraphBackend;
+use ariadne_core::in_memory::InMemoryBackend;
+use ariadne_core::kube_client::SnapshotKubeClient;
+use ariadne_core::snapshot::write_snapshot_dir;
+use ariadne_core::state::SharedClusterState;
+use ariadne_core::state_resolver::ClusterStateResolver;
+use ariadne_core::synth::{synth_snapshot, SynthOptions};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tokio::runtime::Runtime;
+
+/// Pods of each synthetic cluster.
+const SIZES: &[usize] = &[100, 1_000, 10_000];
+
+/// Pods per namespace, so `checkout-1`, which the queries look at, exists at every size.
+const PODS_PER_NAMESPACE: usize = 50;
+
+const SEED: u64 = 42;
+
+const QUERIES: &[(&str, &str)] = &[
+    (
+        "label_scan",
+        "MATCH (p:Pod) WHERE p.metadata.namespace = 'checkout-1' RETURN p.metadata.name AS name",
+    ),
+    (
+        "relationship_expansion",
+        "MATCH (d:Deployment)-[:Manages]->(:ReplicaSet)-[:Manages]->(p:Pod)-[:RunsOn]->(n:Node) \
+         WHERE d.metadata.namespace = 'checkout-1' \
+         RETURN p.metadata.name AS pod, n.metadata.name AS node",
+    ),
+    (
+        "aggregation",
+        "MATCH (p:Pod)-[:RunsOn]->(n:Node) \
+         RETURN n.metadata.name AS node, p.status.phase AS phase, count(p) AS pods",
+    ),
+    (
+        "sorting",
+        "MATCH (p:Pod) RETURN p.metadata.namespace AS namespace, p.metadata.name AS name \
+         ORDER BY namespace DESC, name LIMIT 20",
+    ),
+];
+
+/// Resolves a synthetic cluster of `pods` pods the way the server resolves a snapshot.
+async fn synthetic_state(pods: usize) -> SharedClusterState {
+    let snapshot = synth_snapshot(&SynthOptions {
+        pods,
+        namespaces: (pods / PODS_PER_NAMESPACE).max(2),
+        nodes: None,
+        seed: SEED,
+    });
+    let dir = std::env::temp_dir().join(format!("ariadne-bench-{}-{pods}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("Failed to create the snapshot directory");
+    write_snapshot_dir(&dir, &snapshot).expect("Failed to write the synthetic snapshot");
+    let client = SnapshotKubeClient::from_dir(&dir).expect("Failed to read the snapshot");
+    let resolver =
+        ClusterStateResolver::new_with_kube_client("synthetic".to_string(), Box::new(client))
+            .await
+            .expect("Failed to resolve the synthetic snapshot");
+    let state = resolver
+        .resolve()
+        .await
+        .expect("Failed to resolve the synthetic snapshot");
+    let _ = std::fs::remove_dir_all(dir);
+    state
+}
+
+fn in_memory_queries(c: &mut Criterion) {
+    let runtime = Runtime::new().expect("Failed to start the tokio runtime");
+    let backends: Vec<(usize, InMemoryBackend)> = SIZES
+        .iter()
+        .map(|&size| {
+            let backend = InMemoryBackend::new();
+            let state = runtime.block_on(synthetic_state(size));
+            runtime
+                .block_on(backend.create(state))
+                .expect("Failed to load the synthetic state");
+            (size, backend)
+        })
+        .collect();
+
+    for (name, query) in QUERIES {
+        let mut group = c.benchmark_group(*name);
+        group.sample_size(10);
+        for (size, backend) in &backends {
+            group.throughput(Throughput::Elements(*size as u64));
+            group.bench_with_input(BenchmarkId::from_parameter(size), query, |b, query| {
+                b.iter(|| {
+                    let rows = runtime
+                        .block_on(backend.execute_query(query.to_string(), None))
+                        .expect("Benchmark query failed");
+                    black_box(rows)
+                })
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, in_memory_queries);
+criterion_main!(benches);
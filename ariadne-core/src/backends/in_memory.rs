@@ -1,3 +1,4 @@
+use crate::errors::AriadneError;
 use crate::graph_backend::GraphBackend;
 use crate::prelude::Result;
 use crate::state::{ClusterState, ClusterStateDiff, SharedClusterState};
@@ -12,7 +13,7 @@ use k8s_openapi::Metadata;
 use serde_json::{Map, Value};
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use strum::IntoEnumIterator;
 
@@ -20,7 +21,7 @@ use strum::IntoEnumIterator;
 struct QueryStats {
     parse_ms: u128,
     validate_ms: u128,
-    lock_ms: u128,
+    snapshot_ms: u128,
     exec_ms: u128,
     match_ms: u128,
     unwind_ms: u128,
@@ -47,6 +48,58 @@ struct QueryStats {
     return_clauses: usize,
 }
 
+impl QueryStats {
+    fn to_rows(&self) -> Vec<Value> {
+        let timings = [
+            ("parse", self.parse_ms),
+            ("validate", self.validate_ms),
+            ("snapshot", self.snapshot_ms),
+            ("exec", self.exec_ms),
+            ("match", self.match_ms),
+            ("unwind", self.unwind_ms),
+            ("with", self.with_ms),
+            ("with_project", self.with_project_ms),
+            ("with_filter", self.with_filter_ms),
+            ("with_sort", self.with_sort_ms),
+            ("with_distinct", self.with_distinct_ms),
+            ("with_skip_limit", self.with_skip_limit_ms),
+            ("return", self.return_ms),
+            ("return_project", self.return_project_ms),
+            ("return_sort", self.return_sort_ms),
+            ("return_distinct", self.return_distinct_ms),
+            ("return_skip_limit", self.return_skip_limit_ms),
+        ];
+        let counters = [
+            ("rows_peak", self.rows_peak),
+            ("rows_final", self.rows_final),
+            ("nodes_scanned", self.nodes_scanned),
+            ("nodes_indexed", self.nodes_indexed),
+            ("edges_scanned", self.edges_scanned),
+            ("edges_indexed", self.edges_indexed),
+            ("match_clauses", self.match_clauses),
+            ("unwind_clauses", self.unwind_clauses),
+            ("with_clauses", self.with_clauses),
+            ("return_clauses", self.return_clauses),
+        ];
+        let mut rows = Vec::with_capacity(timings.len() + counters.len());
+        for (stage, ms) in timings {
+            rows.push(serde_json::json!({
+                "stat": stage,
+                "value": ms as u64,
+                "unit": "ms",
+            }));
+        }
+        for (name, count) in counters {
+            rows.push(serde_json::json!({
+                "stat": name,
+                "value": count as u64,
+                "unit": "count",
+            }));
+        }
+        rows
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct InMemoryBackend {
     state: Mutex<Option<SharedClusterState>>,
@@ -57,12 +110,37 @@ impl InMemoryBackend {
         Self::default()
     }
 
-    fn state(&self) -> Result<SharedClusterState> {
+    /// The cluster state as it is now. A query runs on it to the end, however long it takes,
+    /// while the resolver stores newer states for the queries after it.
+    fn snapshot(&self) -> Result<Arc<ClusterState>> {
         let guard = self.state.lock().expect("state lock poisoned");
         guard
             .as_ref()
-            .cloned()
-            .ok_or_else(|| std::io::Error::other("in-memory backend not initialized").into())
+            .map(SharedClusterState::load)
+            .ok_or_else(|| AriadneError::backend("in-memory backend not initialized"))
+    }
+
+    fn run_query(
+        &self,
+        query: &str,
+        params: Option<HashMap<String, Value>>,
+        stats: &mut QueryStats,
+    ) -> Result<Vec<Value>> {
+        let parse_start = Instant::now();
+        let query_ast = parse_query(query).map_err(|err| AriadneError::parse(err.to_string()))?;
+        stats.parse_ms = parse_start.elapsed().as_millis();
+        let validate_start = Instant::now();
+        validate_query(&query_ast, ValidationMode::Engine)
+            .map_err(|err| AriadneError::parse(err.to_string()))?;
+        stats.validate_ms = validate_start.elapsed().as_millis();
+        let snapshot_start = Instant::now();
+        let state = self.snapshot()?;
+        stats.snapshot_ms = snapshot_start.elapsed().as_millis();
+        let params = params.unwrap_or_default();
+        let exec_start = Instant::now();
+        let output = execute_query_ast(&query_ast, &state, &params, stats);
+        stats.exec_ms = exec_start.elapsed().as_millis();
+        output
     }
 }
 
@@ -85,25 +163,7 @@ impl GraphBackend for InMemoryBackend {
     ) -> Result<Vec<Value>> {
         let started = Instant::now();
         let mut stats = QueryStats::default();
-        let result: Result<Vec<Value>> = (|| {
-            let parse_start = Instant::now();
-            let query_ast =
-                parse_query(&query).map_err(|err| std::io::Error::other(err.to_string()))?;
-            stats.parse_ms = parse_start.elapsed().as_millis();
-            let validate_start = Instant::now();
-            validate_query(&query_ast, ValidationMode::Engine)
-                .map_err(|err| std::io::Error::other(err.to_string()))?;
-            stats.validate_ms = validate_start.elapsed().as_millis();
-            let state = self.state()?;
-            let lock_start = Instant::now();
-            let guard = state.lock().expect("cluster state lock poisoned");
-            stats.lock_ms = lock_start.elapsed().as_millis();
-            let params = params.unwrap_or_default();
-            let exec_start = Instant::now();
-            let output = execute_query_ast(&query_ast, &guard, &params, &mut stats);
-            stats.exec_ms = exec_start.elapsed().as_millis();
-            output
-        })();
+        let result = self.run_query(&query, params, &mut stats);
 
         let elapsed_ms = started.elapsed().as_millis();
         tracing::info!("in_memory: execute_query ({elapsed_ms} ms): {query}");
@@ -122,10 +182,10 @@ impl GraphBackend for InMemoryBackend {
             stats.return_clauses
         );
         tracing::info!(
-            "in_memory: execute_query timings parse={}ms validate={}ms lock={}ms exec={}ms match={}ms unwind={}ms with={}ms return={}ms with_project={}ms with_filter={}ms with_sort={}ms with_distinct={}ms with_skip={}ms return_project={}ms return_sort={}ms return_distinct={}ms return_skip={}ms rows_peak={} rows_final={}",
+            "in_memory: execute_query timings parse={}ms validate={}ms snapshot={}ms exec={}ms match={}ms unwind={}ms with={}ms return={}ms with_project={}ms with_filter={}ms with_sort={}ms with_distinct={}ms with_skip={}ms return_project={}ms return_sort={}ms return_distinct={}ms return_skip={}ms rows_peak={} rows_final={}",
             stats.parse_ms,
             stats.validate_ms,
-            stats.lock_ms,
+            stats.snapshot_ms,
             stats.exec_ms,
             stats.match_ms,
             stats.unwind_ms,
@@ -146,6 +206,16 @@ impl GraphBackend for InMemoryBackend {
         result
     }
 
+    async fn profile_query(
+        &self,
+        query: String,
+        params: Option<HashMap<String, Value>>,
+    ) -> Result<Vec<Value>> {
+        let mut stats = QueryStats::default();
+        self.run_query(&query, params, &mut stats)?;
+        Ok(stats.to_rows())
+    }
+
     async fn shutdown(&self) {
         let mut guard = self.state.lock().expect("state lock poisoned");
         *guard = None;
@@ -193,12 +263,14 @@ fn execute_query_ast(
                 return output;
             }
             _ => {
-                return Err(std::io::Error::other("unsupported clause for engine").into());
+                return Err(AriadneError::backend("unsupported clause for engine"));
             }
         }
     }
 
-    Err(std::io::Error::other("query must include RETURN for in-memory engine").into())
+    Err(AriadneError::backend(
+        "query must include RETURN for in-memory engine",
+    ))
 }
 
 fn apply_match(
@@ -460,7 +532,7 @@ fn match_node_pattern(
     let label_type =
         if pattern.labels.len() == 1 {
             Some(ResourceType::try_new(&pattern.labels[0]).map_err(|_| {
-                std::io::Error::other(format!("unknown label: {}", pattern.labels[0]))
+                AriadneError::backend(format!("unknown label: {}", pattern.labels[0]))
             })?)
         } else {
             None
@@ -503,7 +575,7 @@ fn match_relationship_pattern(
 
     let left_label_type = if pattern.left.labels.len() == 1 {
         Some(ResourceType::try_new(&pattern.left.labels[0]).map_err(|_| {
-            std::io::Error::other(format!("unknown label: {}", pattern.left.labels[0]))
+            AriadneError::backend(format!("unknown label: {}", pattern.left.labels[0]))
         })?)
     } else {
         None
@@ -511,7 +583,7 @@ fn match_relationship_pattern(
     let right_label_type = if pattern.right.labels.len() == 1 {
         Some(
             ResourceType::try_new(&pattern.right.labels[0]).map_err(|_| {
-                std::io::Error::other(format!("unknown label: {}", pattern.right.labels[0]))
+                AriadneError::backend(format!("unknown label: {}", pattern.right.labels[0]))
             })?,
         )
     } else {
@@ -876,7 +948,7 @@ fn matches_labels(node: &GenericObject, labels: &[String]) -> Result<bool> {
     }
     let label = labels[0].as_str();
     let expected = ResourceType::try_new(label)
-        .map_err(|_| std::io::Error::other(format!("unknown label: {label}")))?;
+        .map_err(|_| AriadneError::backend(format!("unknown label: {label}")))?;
     Ok(node.resource_type == expected)
 }
 
@@ -900,7 +972,7 @@ fn project_rows_internal(
             match &item.expr {
                 Expr::Star => {
                     if item.alias.is_some() {
-                        return Err(std::io::Error::other("cannot alias RETURN *").into());
+                        return Err(AriadneError::backend("cannot alias RETURN *"));
                     }
                     for (k, v) in &row {
                         record.insert(k.clone(), v.clone());
@@ -929,7 +1001,7 @@ fn project_rows_aggregate(
     for (idx, item) in items.iter().enumerate() {
         if !contains_aggregate_expr(&item.expr) {
             if matches!(item.expr, Expr::Star) {
-                return Err(std::io::Error::other("cannot aggregate with RETURN *").into());
+                return Err(AriadneError::backend("cannot aggregate with RETURN *"));
             }
             non_agg_indices.push(idx);
         }
@@ -960,7 +1032,7 @@ fn project_rows_aggregate(
             } else {
                 key_iter
                     .next()
-                    .ok_or_else(|| std::io::Error::other("missing group key"))?
+                    .ok_or_else(|| AriadneError::backend("missing group key"))?
             };
             let key = projection_label(item, idx);
             record.insert(key, value);
@@ -983,7 +1055,7 @@ fn eval_aggregate(
             "count" => {
                 let target = args
                     .first()
-                    .ok_or_else(|| std::io::Error::other("count requires one argument"))?;
+                    .ok_or_else(|| AriadneError::backend("count requires one argument"))?;
                 let mut count = 0i64;
                 for row in rows {
                     let value = eval_expr(target, row, state, params, stats)?;
@@ -996,7 +1068,7 @@ fn eval_aggregate(
             "sum" => {
                 let target = args
                     .first()
-                    .ok_or_else(|| std::io::Error::other("sum requires one argument"))?;
+                    .ok_or_else(|| AriadneError::backend("sum requires one argument"))?;
                 let mut total = 0.0;
                 let mut seen = false;
                 for row in rows {
@@ -1014,7 +1086,7 @@ fn eval_aggregate(
             "avg" => {
                 let target = args
                     .first()
-                    .ok_or_else(|| std::io::Error::other("avg requires one argument"))?;
+                    .ok_or_else(|| AriadneError::backend("avg requires one argument"))?;
                 let mut total = 0.0;
                 let mut count = 0.0;
                 for row in rows {
@@ -1032,7 +1104,7 @@ fn eval_aggregate(
             "min" | "max" => {
                 let target = args
                     .first()
-                    .ok_or_else(|| std::io::Error::other("min/max require one argument"))?;
+                    .ok_or_else(|| AriadneError::backend("min/max require one argument"))?;
                 let mut current: Option<Value> = None;
                 for row in rows {
                     let value = eval_expr(target, row, state, params, stats)?;
@@ -1057,14 +1129,14 @@ fn eval_aggregate(
             "collect" => {
                 let target = args
                     .first()
-                    .ok_or_else(|| std::io::Error::other("collect requires one argument"))?;
+                    .ok_or_else(|| AriadneError::backend("collect requires one argument"))?;
                 let mut values = Vec::new();
                 for row in rows {
                     values.push(eval_expr(target, row, state, params, stats)?);
                 }
                 Ok(Value::Array(values))
             }
-            _ => Err(std::io::Error::other("unsupported aggregate function").into()),
+            _ => Err(AriadneError::backend("unsupported aggregate function")),
         },
         Expr::IndexAccess { expr, index } => {
             let base = eval_aggregate(expr, rows, state, params, stats)?;
@@ -1095,7 +1167,7 @@ fn eval_aggregate(
                 stats,
             )
         }
-        _ => Err(std::io::Error::other("unsupported aggregate expression").into()),
+        _ => Err(AriadneError::backend("unsupported aggregate expression")),
     }
 }
 
@@ -1182,7 +1254,7 @@ fn eval_aggregate_expr(
         Expr::UnaryOp { op, expr } => {
             let value = eval_aggregate_expr(expr, rows, state, params, stats)?;
             match op {
-                ariadne_cypher::UnaryOp::Not => Ok(Value::Bool(!value.as_bool().unwrap_or(false))),
+                ariadne_cypher::UnaryOp::Not => Ok(not_value(&value)),
                 ariadne_cypher::UnaryOp::Neg => Ok(Value::from(-value.as_f64().unwrap_or(0.0))),
                 ariadne_cypher::UnaryOp::Pos => Ok(Value::from(value.as_f64().unwrap_or(0.0))),
             }
@@ -1192,26 +1264,30 @@ fn eval_aggregate_expr(
             let r = eval_aggregate_expr(right, rows, state, params, stats)?;
             eval_binary_values(op, l, r)
         }
-        Expr::Parameter(name) => params.get(name).cloned().ok_or_else(|| {
-            std::io::Error::other(format!("parameter not provided: ${name}")).into()
-        }),
-        _ => Err(std::io::Error::other("unsupported aggregate expression shape").into()),
+        Expr::Parameter(name) => params
+            .get(name)
+            .cloned()
+            .ok_or_else(|| AriadneError::backend(format!("parameter not provided: ${name}"))),
+        _ => Err(AriadneError::backend(
+            "unsupported aggregate expression shape",
+        )),
     }
 }
 
 fn eval_binary_values(op: &ariadne_cypher::BinaryOp, left: Value, right: Value) -> Result<Value> {
     use ariadne_cypher::BinaryOp::*;
     match op {
-        Or => Ok(Value::Bool(
-            left.as_bool().unwrap_or(false) || right.as_bool().unwrap_or(false),
-        )),
-        And => Ok(Value::Bool(
-            left.as_bool().unwrap_or(false) && right.as_bool().unwrap_or(false),
-        )),
-        Xor => Ok(Value::Bool(
-            left.as_bool().unwrap_or(false) ^ right.as_bool().unwrap_or(false),
-        )),
+        Or => Ok(or_values(left.as_bool(), right.as_bool())),
+        And => Ok(and_values(left.as_bool(), right.as_bool())),
+        Xor => Ok(match (left.as_bool(), right.as_bool()) {
+            (Some(l), Some(r)) => Value::Bool(l ^ r),
+            _ => Value::Null,
+        }),
         Eq | Neq | Lt | Gt | Lte | Gte => {
+            // Comparing with null is unknown, as in Cypher, so `n.x <> 'a'` skips nodes without x.
+            if left.is_null() || right.is_null() {
+                return Ok(Value::Null);
+            }
             let cmp = compare_values(&left, &right);
             let result = match op {
                 Eq => cmp.map(|c| c == Ordering::Equal).unwrap_or(false),
@@ -1226,7 +1302,7 @@ fn eval_binary_values(op: &ariadne_cypher::BinaryOp, left: Value, right: Value)
         }
         StartsWith | EndsWith | Contains => {
             if left.is_null() || right.is_null() {
-                return Ok(Value::Bool(false));
+                return Ok(Value::Null);
             }
             let left_str = value_to_string(&left);
             let right_str = value_to_string(&right);
@@ -1255,6 +1331,32 @@ fn eval_binary_values(op: &ariadne_cypher::BinaryOp, left: Value, right: Value)
     }
 }
 
+/// `AND` in Cypher's three-valued logic, where `None` is null or anything that is not a boolean.
+fn and_values(left: Option<bool>, right: Option<bool>) -> Value {
+    match (left, right) {
+        (Some(false), _) | (_, Some(false)) => Value::Bool(false),
+        (Some(true), Some(true)) => Value::Bool(true),
+        _ => Value::Null,
+    }
+}
+
+/// `OR` in Cypher's three-valued logic, see [`and_values`].
+fn or_values(left: Option<bool>, right: Option<bool>) -> Value {
+    match (left, right) {
+        (Some(true), _) | (_, Some(true)) => Value::Bool(true),
+        (Some(false), Some(false)) => Value::Bool(false),
+        _ => Value::Null,
+    }
+}
+
+/// `NOT` in Cypher's three-valued logic: the negation of null is null.
+fn not_value(value: &Value) -> Value {
+    value
+        .as_bool()
+        .map(|b| Value::Bool(!b))
+        .unwrap_or(Value::Null)
+}
+
 fn eval_list_slice(
     base: Value,
     start: Option<&Expr>,
@@ -1411,8 +1513,9 @@ fn compare_keys(a: &[Value], b: &[Value], order: &[ariadne_cypher::OrderItem]) -
 fn compare_values(left: &Value, right: &Value) -> Option<Ordering> {
     match (left, right) {
         (Value::Null, Value::Null) => Some(Ordering::Equal),
-        (Value::Null, _) => Some(Ordering::Less),
-        (_, Value::Null) => Some(Ordering::Greater),
+        // Nulls sort after every other value, last in ascending and first in descending order.
+        (Value::Null, _) => Some(Ordering::Greater),
+        (_, Value::Null) => Some(Ordering::Less),
         (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
         (Value::Number(a), Value::Number(b)) => {
             let la = a.as_f64()?;
@@ -1466,7 +1569,7 @@ fn exists_node_pattern(
     let label_type =
         if pattern.labels.len() == 1 {
             Some(ResourceType::try_new(&pattern.labels[0]).map_err(|_| {
-                std::io::Error::other(format!("unknown label: {}", pattern.labels[0]))
+                AriadneError::backend(format!("unknown label: {}", pattern.labels[0]))
             })?)
         } else {
             None
@@ -1511,7 +1614,7 @@ fn exists_relationship_pattern(
 
     let left_label_type = if pattern.left.labels.len() == 1 {
         Some(ResourceType::try_new(&pattern.left.labels[0]).map_err(|_| {
-            std::io::Error::other(format!("unknown label: {}", pattern.left.labels[0]))
+            AriadneError::backend(format!("unknown label: {}", pattern.left.labels[0]))
         })?)
     } else {
         None
@@ -1519,7 +1622,7 @@ fn exists_relationship_pattern(
     let right_label_type = if pattern.right.labels.len() == 1 {
         Some(
             ResourceType::try_new(&pattern.right.labels[0]).map_err(|_| {
-                std::io::Error::other(format!("unknown label: {}", pattern.right.labels[0]))
+                AriadneError::backend(format!("unknown label: {}", pattern.right.labels[0]))
             })?,
         )
     } else {
@@ -1703,7 +1806,7 @@ fn eval_expr(
         Expr::UnaryOp { op, expr } => {
             let value = eval_expr(expr, row, state, params, stats)?;
             match op {
-                ariadne_cypher::UnaryOp::Not => Ok(Value::Bool(!value.as_bool().unwrap_or(false))),
+                ariadne_cypher::UnaryOp::Not => Ok(not_value(&value)),
                 ariadne_cypher::UnaryOp::Neg => Ok(Value::from(-value.as_f64().unwrap_or(0.0))),
                 ariadne_cypher::UnaryOp::Pos => Ok(Value::from(value.as_f64().unwrap_or(0.0))),
             }
@@ -1807,10 +1910,11 @@ fn eval_expr(
             }
         }
         Expr::FunctionCall { name, args } => eval_function(name, args, row, state, params, stats),
-        Expr::CountStar => Err(std::io::Error::other("count(*) not valid here").into()),
-        Expr::Parameter(name) => params.get(name).cloned().ok_or_else(|| {
-            std::io::Error::other(format!("parameter not provided: ${name}")).into()
-        }),
+        Expr::CountStar => Err(AriadneError::backend("count(*) not valid here")),
+        Expr::Parameter(name) => params
+            .get(name)
+            .cloned()
+            .ok_or_else(|| AriadneError::backend(format!("parameter not provided: ${name}"))),
     }
 }
 
@@ -1921,7 +2025,7 @@ fn eval_function(
         "size" => {
             let target = args
                 .first()
-                .ok_or_else(|| std::io::Error::other("size requires one argument"))?;
+                .ok_or_else(|| AriadneError::backend("size requires one argument"))?;
             let value = eval_expr(target, row, state, params, stats)?;
             let size = match value {
                 Value::Array(items) => items.len() as i64,
@@ -1934,7 +2038,7 @@ fn eval_function(
         "lower" | "upper" => {
             let target = args
                 .first()
-                .ok_or_else(|| std::io::Error::other("lower/upper require one argument"))?;
+                .ok_or_else(|| AriadneError::backend("lower/upper require one argument"))?;
             let value = eval_expr(target, row, state, params, stats)?;
             let text = value.as_str().unwrap_or_default();
             let out = if lower == "lower" {
@@ -1956,7 +2060,7 @@ fn eval_function(
         "tostring" => {
             let target = args
                 .first()
-                .ok_or_else(|| std::io::Error::other("toString requires one argument"))?;
+                .ok_or_else(|| AriadneError::backend("toString requires one argument"))?;
             let value = eval_expr(target, row, state, params, stats)?;
             Ok(Value::String(match value {
                 Value::String(s) => s,
@@ -1966,7 +2070,7 @@ fn eval_function(
         "tointeger" | "toint" => {
             let target = args
                 .first()
-                .ok_or_else(|| std::io::Error::other("toInteger requires one argument"))?;
+                .ok_or_else(|| AriadneError::backend("toInteger requires one argument"))?;
             let value = eval_expr(target, row, state, params, stats)?;
             let num = match value {
                 Value::Number(n) => n.as_i64().unwrap_or(0),
@@ -1985,7 +2089,7 @@ fn eval_function(
         "tofloat" => {
             let target = args
                 .first()
-                .ok_or_else(|| std::io::Error::other("toFloat requires one argument"))?;
+                .ok_or_else(|| AriadneError::backend("toFloat requires one argument"))?;
             let value = eval_expr(target, row, state, params, stats)?;
             let num = match value {
                 Value::Number(n) => n.as_f64().unwrap_or(0.0),
@@ -2004,7 +2108,7 @@ fn eval_function(
         "labels" => {
             let target = args
                 .first()
-                .ok_or_else(|| std::io::Error::other("labels requires one argument"))?;
+                .ok_or_else(|| AriadneError::backend("labels requires one argument"))?;
             let value = eval_expr(target, row, state, params, stats)?;
             match value {
                 Value::Object(map) => {
@@ -2030,7 +2134,7 @@ fn eval_function(
         "keys" => {
             let target = args
                 .first()
-                .ok_or_else(|| std::io::Error::other("keys requires one argument"))?;
+                .ok_or_else(|| AriadneError::backend("keys requires one argument"))?;
             let value = eval_expr(target, row, state, params, stats)?;
             match value {
                 Value::Object(map) => {
@@ -2044,7 +2148,7 @@ fn eval_function(
         }
         "replace" => {
             if args.len() < 3 {
-                return Err(std::io::Error::other("replace requires three arguments").into());
+                return Err(AriadneError::backend("replace requires three arguments"));
             }
             let value = eval_expr(&args[0], row, state, params, stats)?;
             let search = eval_expr(&args[1], row, state, params, stats)?;
@@ -2057,10 +2161,12 @@ fn eval_function(
             let repl = value_to_string(&replacement);
             Ok(Value::String(source.replace(&needle, &repl)))
         }
-        "count" | "sum" | "avg" | "min" | "max" | "collect" => {
-            Err(std::io::Error::other("aggregate functions must appear in projection").into())
-        }
-        _ => Err(std::io::Error::other(format!("unsupported function in engine: {name}")).into()),
+        "count" | "sum" | "avg" | "min" | "max" | "collect" => Err(AriadneError::backend(
+            "aggregate functions must appear in projection",
+        )),
+        _ => Err(AriadneError::backend(format!(
+            "unsupported function in engine: {name}"
+        ))),
     }
 }
 
@@ -2074,69 +2180,15 @@ fn eval_binary(
     stats: &mut QueryStats,
 ) -> Result<Value> {
     use ariadne_cypher::BinaryOp::*;
-    match op {
-        Or => Ok(Value::Bool(
-            eval_bool(left, row, state, params, stats)?
-                || eval_bool(right, row, state, params, stats)?,
-        )),
-        And => Ok(Value::Bool(
-            eval_bool(left, row, state, params, stats)?
-                && eval_bool(right, row, state, params, stats)?,
-        )),
-        Xor => Ok(Value::Bool(
-            eval_bool(left, row, state, params, stats)?
-                ^ eval_bool(right, row, state, params, stats)?,
-        )),
-        Eq | Neq | Lt | Gt | Lte | Gte => {
-            let l = eval_expr(left, row, state, params, stats)?;
-            let r = eval_expr(right, row, state, params, stats)?;
-            let cmp = compare_values(&l, &r);
-            let result = match op {
-                Eq => cmp.map(|c| c == Ordering::Equal).unwrap_or(false),
-                Neq => cmp.map(|c| c != Ordering::Equal).unwrap_or(true),
-                Lt => cmp.map(|c| c == Ordering::Less).unwrap_or(false),
-                Gt => cmp.map(|c| c == Ordering::Greater).unwrap_or(false),
-                Lte => cmp.map(|c| c != Ordering::Greater).unwrap_or(false),
-                Gte => cmp.map(|c| c != Ordering::Less).unwrap_or(false),
-                _ => false,
-            };
-            Ok(Value::Bool(result))
-        }
-        StartsWith | EndsWith | Contains => {
-            let l = eval_expr(left, row, state, params, stats)?;
-            let r = eval_expr(right, row, state, params, stats)?;
-            if l.is_null() || r.is_null() {
-                return Ok(Value::Bool(false));
-            }
-            let left_str = value_to_string(&l);
-            let right_str = value_to_string(&r);
-            let result = match op {
-                StartsWith => left_str.starts_with(&right_str),
-                EndsWith => left_str.ends_with(&right_str),
-                Contains => left_str.contains(&right_str),
-                _ => false,
-            };
-            Ok(Value::Bool(result))
-        }
-        Add | Sub | Mul | Div | Mod | Pow => {
-            let l = eval_expr(left, row, state, params, stats)?
-                .as_f64()
-                .unwrap_or(0.0);
-            let r = eval_expr(right, row, state, params, stats)?
-                .as_f64()
-                .unwrap_or(0.0);
-            let value = match op {
-                Add => l + r,
-                Sub => l - r,
-                Mul => l * r,
-                Div => l / r,
-                Mod => l % r,
-                Pow => l.powf(r),
-                _ => 0.0,
-            };
-            Ok(Value::from(value))
-        }
-    }
+    let l = eval_expr(left, row, state, params, stats)?;
+    // AND and OR skip the right side once the left one decides the result.
+    match (op, l.as_bool()) {
+        (And, Some(false)) => return Ok(Value::Bool(false)),
+        (Or, Some(true)) => return Ok(Value::Bool(true)),
+        _ => {}
+    }
+    let r = eval_expr(right, row, state, params, stats)?;
+    eval_binary_values(op, l, r)
 }
 
 fn value_to_string(value: &Value) -> String {
@@ -2345,7 +2397,7 @@ mod tests {
         ContainerState, ContainerStateTerminated, ContainerStatus, Pod, PodStatus,
     };
     use k8s_openapi::apimachinery::pkg::version::Info;
-    use std::sync::{Arc, Mutex};
+    use std::sync::Arc;
 
     fn dummy_cluster() -> Cluster {
         let id = ObjectIdentifier {
@@ -2645,7 +2697,7 @@ mod tests {
     fn backend_executes_query() {
         let mut state = ClusterState::new(dummy_cluster());
         state.add_node(pod("p1", "pod-one", "ns1"));
-        let shared = Arc::new(Mutex::new(state));
+        let shared = SharedClusterState::new(state);
 
         let backend = InMemoryBackend::new();
         let runtime = tokio::runtime::Runtime::new().unwrap();
@@ -2659,6 +2711,58 @@ mod tests {
         });
     }
 
+    #[test]
+    fn backend_queries_the_latest_stored_state() {
+        let shared = SharedClusterState::new(ClusterState::new(dummy_cluster()));
+        let backend = InMemoryBackend::new();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            backend.create(shared.clone()).await.unwrap();
+            let snapshot = backend.snapshot().unwrap();
+
+            let mut state = ClusterState::new(dummy_cluster());
+            state.add_node(pod("p1", "pod-one", "ns1"));
+            shared.store(state);
+
+            let results = backend
+                .execute_query("MATCH (p:Pod) RETURN count(p) AS total".to_string(), None)
+                .await
+                .unwrap();
+            assert_eq!(results[0].get("total").and_then(|v| v.as_i64()), Some(1));
+            assert_eq!(snapshot.get_node_count(), 0);
+        });
+    }
+
+    #[test]
+    fn backend_profiles_query() {
+        let mut state = ClusterState::new(dummy_cluster());
+        state.add_node(pod("p1", "pod-one", "ns1"));
+        state.add_node(pod("p2", "pod-two", "ns1"));
+        let shared = SharedClusterState::new(state);
+
+        let backend = InMemoryBackend::new();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            backend.create(shared.clone()).await.unwrap();
+            let rows = backend
+                .profile_query(
+                    "MATCH (p:Pod) RETURN p.metadata.name AS name".to_string(),
+                    None,
+                )
+                .await
+                .unwrap();
+            let stat = |name: &str| {
+                rows.iter()
+                    .find(|row| row.get("stat").and_then(|v| v.as_str()) == Some(name))
+                    .and_then(|row| row.get("value"))
+                    .and_then(|v| v.as_u64())
+            };
+            assert_eq!(stat("rows_final"), Some(2));
+            assert_eq!(stat("match_clauses"), Some(1));
+            assert_eq!(stat("return_clauses"), Some(1));
+        });
+    }
+
     #[test]
     fn executes_string_predicate() {
         let mut state = ClusterState::new(dummy_cluster());
@@ -2763,6 +2867,65 @@ mod tests {
         assert_eq!(results[0].get("total").and_then(|v| v.as_i64()), Some(3));
     }
 
+    #[test]
+    fn null_comparisons_are_unknown() {
+        let mut state = ClusterState::new(dummy_cluster());
+        let mut running = pod("p2", "pod-two", "ns1");
+        if let Some(ResourceAttributes::Pod { pod }) = running.attributes.as_deref_mut() {
+            Arc::make_mut(pod).status = Some(PodStatus {
+                phase: Some("Running".to_string()),
+                ..Default::default()
+            });
+        }
+        state.add_node(pod("p1", "pod-one", "ns1"));
+        state.add_node(running);
+        let names = |query: &str| {
+            let query = parse_query(query).unwrap();
+            validate_query(&query, ValidationMode::Engine).unwrap();
+            let mut stats = QueryStats::default();
+            execute_query_ast(&query, &state, &HashMap::new(), &mut stats)
+                .unwrap()
+                .iter()
+                .map(|row| {
+                    row.get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap()
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // pod-one has no phase, so neither the comparison nor its negation holds.
+        assert_eq!(
+            names(
+                "MATCH (p:Pod) WHERE p.status.phase <> 'Pending' \
+                 RETURN p.metadata.name AS name"
+            ),
+            ["pod-two"]
+        );
+        assert!(names(
+            "MATCH (p:Pod) WHERE NOT (p.status.phase = 'Running') \
+             RETURN p.metadata.name AS name"
+        )
+        .is_empty());
+        assert_eq!(
+            names(
+                "MATCH (p:Pod) WHERE p.status.phase = 'Running' \
+                 OR p.metadata.name = 'pod-one' RETURN p.metadata.name AS name"
+            )
+            .len(),
+            2
+        );
+        // Nulls sort last.
+        assert_eq!(
+            names(
+                "MATCH (p:Pod) RETURN p.metadata.name AS name, \
+                 p.status.phase AS phase ORDER BY phase"
+            ),
+            ["pod-two", "pod-one"]
+        );
+    }
+
     #[test]
     fn executes_exists_subquery() {
         let mut state = ClusterState::new(dummy_cluster());
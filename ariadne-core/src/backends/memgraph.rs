@@ -1,3 +1,6 @@
+use crate::graph_schema::{
+    SchemaCompatibility, GRAPH_SCHEMA_VERSION, INDEXED_PROPERTIES, SCHEMA_VERSION_LABEL,
+};
 use crate::prelude::*;
 use crate::state::{ClusterState, ClusterStateDiff, GraphEdge};
 use crate::types::{Edge, GenericObject, ResourceAttributes, ResourceType, LOGICAL_RESOURCE_TYPES};
@@ -7,7 +10,7 @@ use rsmgclient::{
 };
 use serde::Serialize;
 use serde_json::{Number, Value};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::Debug;
 use std::time::Instant;
 use strum::IntoEnumIterator;
@@ -22,6 +25,8 @@ pub enum MemgraphError {
     QueryError(String),
     #[error("CommitError: {0}")]
     CommitError(String),
+    #[error("SchemaVersionError: {0}")]
+    SchemaVersionError(String),
 }
 
 pub struct Memgraph {
@@ -125,6 +130,11 @@ impl QuerySpec {
 
 impl Memgraph {
     pub fn try_new_from_url(url: &str) -> Result<Self> {
+        Self::try_new(Self::connect_params_from_url(url)?)
+    }
+
+    /// Connection parameters for a `bolt://host:port` url.
+    pub fn connect_params_from_url(url: &str) -> Result<ConnectParams> {
         let binding = url.replace("bolt://", "");
         let vec = binding.split(":").collect::<Vec<_>>();
         assert_eq!(vec.len(), 2);
@@ -135,12 +145,11 @@ impl Memgraph {
 
         info!("Connecting to memgraph at {}:{}", host, port);
 
-        let params = ConnectParams {
+        Ok(ConnectParams {
             port,
             host: Some(host),
             ..Default::default()
-        };
-        Self::try_new(params)
+        })
     }
     pub fn try_new(params: ConnectParams) -> Result<Self> {
         let connect_params = ConnectParamsSnapshot::from_params(&params);
@@ -154,10 +163,43 @@ impl Memgraph {
             )))?;
         }
 
-        Ok(Self {
+        let mut graph = Self {
             connection,
             connect_params,
-        })
+        };
+        graph.check_schema_version()?;
+        Ok(graph)
+    }
+
+    /// The schema version the stored graph is stamped with, if any.
+    pub fn stored_schema_version(&mut self) -> Result<Option<u32>> {
+        let rows = self.execute_query(&format!(
+            "MATCH (s:{SCHEMA_VERSION_LABEL}) RETURN s.version AS version"
+        ))?;
+        Ok(rows
+            .iter()
+            .find_map(|row| row.get("version")?.as_u64())
+            .and_then(|version| u32::try_from(version).ok()))
+    }
+
+    /// Refuses a graph written by a newer binary and warns about one written by an older
+    /// binary, which the next `create` rebuilds.
+    fn check_schema_version(&mut self) -> Result<()> {
+        match SchemaCompatibility::of(self.stored_schema_version()?) {
+            SchemaCompatibility::Newer(version) => Err(MemgraphError::SchemaVersionError(format!(
+                "the graph was written with schema version {version}, newer than \
+                 {GRAPH_SCHEMA_VERSION}; upgrade ariadne or clear the database"
+            ))
+            .into()),
+            SchemaCompatibility::Older(version) => {
+                warn!(
+                    "The graph was written with schema version {version}, older than \
+                     {GRAPH_SCHEMA_VERSION}; it is rebuilt on the next create"
+                );
+                Ok(())
+            }
+            SchemaCompatibility::Unstamped | SchemaCompatibility::Compatible => Ok(()),
+        }
     }
 
     fn ensure_connected(&mut self) -> Result<()> {
@@ -220,6 +262,7 @@ impl Memgraph {
         self.connection
             .execute_without_results("MATCH (n) DETACH DELETE n;")
             .map_err(|e| MemgraphError::QueryError(e.to_string()))?;
+        self.execute_query_spec(&Self::get_schema_stamp_query())?;
 
         // Create nodes first (faster bulk load), then build indices.
         let mut unique_types: HashSet<ResourceType> = HashSet::new();
@@ -230,11 +273,9 @@ impl Memgraph {
             unique_types.insert(node.resource_type.clone());
         }
 
-        if !nodes.is_empty() {
-            self.connection
-                .commit()
-                .map_err(|e| MemgraphError::CommitError(e.to_string()))?;
-        }
+        self.connection
+            .commit()
+            .map_err(|e| MemgraphError::CommitError(e.to_string()))?;
 
         // Create indices after nodes to keep index build efficient.
         for resource_type in &unique_types {
@@ -341,7 +382,8 @@ impl Memgraph {
         }
 
         for node in &diff.modified_nodes {
-            let update_query = Self::get_update_query(node)?;
+            let update_query =
+                Self::get_update_query(node, diff.modified_fields.get(&node.id.uid))?;
             self.execute_query_spec(&update_query).map_err(|e| {
                 MemgraphError::QueryError(format!(
                     "Failed to update the node with id {:?} and type {}: {}",
@@ -437,10 +479,50 @@ impl Memgraph {
         }
     }
 
-    pub(crate) fn get_update_query(obj: &GenericObject) -> Result<QuerySpec> {
-        let properties = Self::get_properties_param(obj)?.unwrap_or(QueryParam::Null);
+    pub(crate) fn get_schema_stamp_query() -> QuerySpec {
+        let mut params = HashMap::new();
+        params.insert(
+            "version".to_string(),
+            QueryParam::Int(i64::from(GRAPH_SCHEMA_VERSION)),
+        );
+        QuerySpec::with_params(
+            format!("CREATE (:{SCHEMA_VERSION_LABEL} {{version: $version}})"),
+            params,
+        )
+    }
+
+    /// Query that updates `obj` in place: only `metadata` and the `changed` properties when
+    /// they are known, every property otherwise.
+    pub(crate) fn get_update_query(
+        obj: &GenericObject,
+        changed: Option<&BTreeSet<String>>,
+    ) -> Result<QuerySpec> {
         let mut params = HashMap::new();
         params.insert("uid".to_string(), QueryParam::String(obj.id.uid.clone()));
+        if let (Some(changed), Value::Object(mut properties)) = (changed, Self::get_as_json(obj)?) {
+            // `metadata` always goes along, since its resource version changes on every write.
+            let fields = std::iter::once("metadata").chain(
+                changed
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|f| *f != "metadata"),
+            );
+            let mut assignments = Vec::new();
+            for (index, field) in fields.enumerate() {
+                let value = properties.remove(field).unwrap_or(Value::Null);
+                params.insert(format!("p{index}"), Self::json_to_query_param(&value));
+                assignments.push(format!("n.`{}` = $p{index}", field.replace('`', "``")));
+            }
+            return Ok(QuerySpec::with_params(
+                format!(
+                    "MATCH (n:{:?}) WHERE n.metadata.uid = $uid SET {}",
+                    obj.resource_type,
+                    assignments.join(", ")
+                ),
+                params,
+            ));
+        }
+        let properties = Self::get_properties_param(obj)?.unwrap_or(QueryParam::Null);
         params.insert("props".to_string(), properties);
         Ok(QuerySpec::with_params(
             format!(
@@ -630,11 +712,10 @@ impl Memgraph {
     }
 
     pub(crate) fn get_create_indices_query(rt: &ResourceType) -> Vec<String> {
-        vec![
-            format!("CREATE INDEX ON :{rt:?}(metadata.name)"),
-            format!("CREATE INDEX ON :{rt:?}(metadata.uid)"),
-            format!("CREATE INDEX ON :{rt:?}(metadata.namespace)"),
-        ]
+        INDEXED_PROPERTIES
+            .iter()
+            .map(|property| format!("CREATE INDEX ON :{rt:?}({property})"))
+            .collect()
     }
 
     pub(crate) fn get_delete_node_query(obj: &GenericObject) -> QuerySpec {
@@ -1,9 +1,48 @@
+#[cfg(feature = "memgraph")]
 use crate::memgraph;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use thiserror::Error;
 
-#[derive(Error, Debug)]
-#[error(transparent)]
-pub struct AriadneError(Box<ErrorKind>);
+/// What an error came from, so callers can react to it without matching its message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    /// The Kubernetes API, its watch stores or the kubeconfig.
+    Kube,
+    /// The graph backend: Memgraph, the in-memory engine or the actor in front of them.
+    Backend,
+    /// Input that does not parse or validate: JSON, Cypher or a resource type.
+    Parse,
+    /// The language model provider.
+    Llm,
+    /// Local files and other I/O.
+    Io,
+}
+
+impl fmt::Display for ErrorClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let class = match self {
+            ErrorClass::Kube => "kube",
+            ErrorClass::Backend => "backend",
+            ErrorClass::Parse => "parse",
+            ErrorClass::Llm => "llm",
+            ErrorClass::Io => "io",
+        };
+        f.write_str(class)
+    }
+}
+
+/// An [`ErrorKind`] and what was being done when it happened, which its message starts with.
+#[derive(Debug)]
+pub struct AriadneError(Box<ErrorInner>);
+
+#[derive(Debug)]
+struct ErrorInner {
+    kind: ErrorKind,
+    /// Innermost first.
+    context: Vec<String>,
+}
 
 #[derive(Error, Debug)]
 #[error(transparent)]
@@ -18,10 +57,153 @@ pub enum ErrorKind {
     KubeconfigError(#[from] kube::config::KubeconfigError),
     #[error("KubeconfigInferError: {0}")]
     KubeconfigInferError(#[from] kube::config::InClusterError),
+    #[error("KubeStoreError: {message}")]
+    KubeStoreError { message: String, retriable: bool },
+    #[cfg(feature = "memgraph")]
     #[error("MemgraphError: {0}")]
     MemgraphError(#[from] memgraph::MemgraphError),
+    #[error("BackendError: {0}")]
+    BackendError(String),
+    #[error("ParseError: {0}")]
+    ParseError(String),
     #[error("InvalidResourceTypeError: {0}")]
     InvalidResourceTypeError(String),
+    #[error("LlmError: {message}")]
+    LlmError { message: String, retriable: bool },
+}
+
+impl ErrorKind {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            ErrorKind::SerdeJsonError(_)
+            | ErrorKind::ParseError(_)
+            | ErrorKind::InvalidResourceTypeError(_) => ErrorClass::Parse,
+            ErrorKind::IoError(_) => ErrorClass::Io,
+            ErrorKind::KubeClientError(_)
+            | ErrorKind::KubeconfigError(_)
+            | ErrorKind::KubeconfigInferError(_)
+            | ErrorKind::KubeStoreError { .. } => ErrorClass::Kube,
+            #[cfg(feature = "memgraph")]
+            ErrorKind::MemgraphError(_) => ErrorClass::Backend,
+            ErrorKind::BackendError(_) => ErrorClass::Backend,
+            ErrorKind::LlmError { .. } => ErrorClass::Llm,
+        }
+    }
+
+    /// Whether the same call may well succeed when repeated: timeouts, dropped connections,
+    /// rate limits and server errors. Errors in the request itself are not retriable.
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            ErrorKind::IoError(err) => is_transient_io(err.kind()),
+            ErrorKind::KubeClientError(err) => is_transient_kube(err),
+            ErrorKind::KubeStoreError { retriable, .. } | ErrorKind::LlmError { retriable, .. } => {
+                *retriable
+            }
+            #[cfg(feature = "memgraph")]
+            ErrorKind::MemgraphError(err) => matches!(
+                err,
+                memgraph::MemgraphError::ConnectionError(_)
+                    | memgraph::MemgraphError::CommitError(_)
+            ),
+            ErrorKind::SerdeJsonError(_)
+            | ErrorKind::KubeconfigError(_)
+            | ErrorKind::KubeconfigInferError(_)
+            | ErrorKind::BackendError(_)
+            | ErrorKind::ParseError(_)
+            | ErrorKind::InvalidResourceTypeError(_) => false,
+        }
+    }
+}
+
+fn is_transient_io(kind: std::io::ErrorKind) -> bool {
+    use std::io::ErrorKind as Io;
+    matches!(
+        kind,
+        Io::TimedOut
+            | Io::Interrupted
+            | Io::WouldBlock
+            | Io::ConnectionRefused
+            | Io::ConnectionReset
+            | Io::ConnectionAborted
+            | Io::NotConnected
+            | Io::BrokenPipe
+    )
+}
+
+fn is_transient_kube(err: &kube::Error) -> bool {
+    match err {
+        kube::Error::Api(response) => response.code == 429 || response.code >= 500,
+        kube::Error::HyperError(_) | kube::Error::Service(_) => true,
+        _ => false,
+    }
+}
+
+impl AriadneError {
+    /// A failure of the graph backend that is not the backend's own error type, such as a
+    /// query the in-memory engine cannot run.
+    pub fn backend(message: impl Into<String>) -> Self {
+        AriadneError::from(ErrorKind::BackendError(message.into()))
+    }
+
+    /// Input that does not parse.
+    pub fn parse(message: impl Into<String>) -> Self {
+        AriadneError::from(ErrorKind::ParseError(message.into()))
+    }
+
+    /// A failed call to the language model provider.
+    pub fn llm(message: impl Into<String>, retriable: bool) -> Self {
+        AriadneError::from(ErrorKind::LlmError {
+            message: message.into(),
+            retriable,
+        })
+    }
+
+    /// A watch store of the Kubernetes client that did not become ready.
+    pub fn kube_store(message: impl Into<String>, retriable: bool) -> Self {
+        AriadneError::from(ErrorKind::KubeStoreError {
+            message: message.into(),
+            retriable,
+        })
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.0.kind
+    }
+
+    pub fn class(&self) -> ErrorClass {
+        self.0.kind.class()
+    }
+
+    /// See [`ErrorKind::is_retriable`].
+    pub fn is_retriable(&self) -> bool {
+        self.0.kind.is_retriable()
+    }
+
+    /// What was being done when the error happened, innermost first.
+    pub fn context(&self) -> &[String] {
+        &self.0.context
+    }
+
+    /// Adds what was being done, such as `reading pods.json`, in front of the message.
+    pub fn with_context(mut self, context: impl Into<String>) -> Self {
+        self.0.context.push(context.into());
+        self
+    }
+}
+
+impl fmt::Display for AriadneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for context in self.0.context.iter().rev() {
+            write!(f, "{context}: ")?;
+        }
+        write!(f, "{}", self.0.kind)
+    }
+}
+
+impl std::error::Error for AriadneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        std::error::Error::source(&self.0.kind)
+    }
 }
 
 impl<E> From<E> for AriadneError
@@ -29,6 +211,77 @@ where
     ErrorKind: From<E>,
 {
     fn from(err: E) -> Self {
-        AriadneError(Box::new(ErrorKind::from(err)))
+        AriadneError(Box::new(ErrorInner {
+            kind: ErrorKind::from(err),
+            context: Vec::new(),
+        }))
+    }
+}
+
+/// Adds context to the error of a result, see [`AriadneError::with_context`].
+pub trait ErrorContext<T> {
+    fn context(self, context: impl Into<String>) -> Result<T, AriadneError>;
+    fn with_context<C, F>(self, context: F) -> Result<T, AriadneError>
+    where
+        C: Into<String>,
+        F: FnOnce() -> C;
+}
+
+impl<T, E> ErrorContext<T> for Result<T, E>
+where
+    E: Into<AriadneError>,
+{
+    fn context(self, context: impl Into<String>) -> Result<T, AriadneError> {
+        self.map_err(|err| err.into().with_context(context))
+    }
+
+    fn with_context<C, F>(self, context: F) -> Result<T, AriadneError>
+    where
+        C: Into<String>,
+        F: FnOnce() -> C,
+    {
+        self.map_err(|err| err.into().with_context(context()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_carry_their_class_retriability_and_context() {
+        let timed_out: Result<(), std::io::Error> = Err(std::io::Error::new(
+            std::io::ErrorKind::TimedOut,
+            "no answer",
+        ));
+        let err = timed_out
+            .context("listing pods")
+            .with_context(|| format!("resolving {}", "prod"))
+            .unwrap_err();
+        assert_eq!(err.class(), ErrorClass::Io);
+        assert!(err.is_retriable());
+        assert_eq!(err.context(), ["listing pods", "resolving prod"]);
+        assert_eq!(
+            err.to_string(),
+            "resolving prod: listing pods: IoError: no answer"
+        );
+
+        assert_eq!(AriadneError::backend("x").class(), ErrorClass::Backend);
+        assert_eq!(AriadneError::parse("x").class(), ErrorClass::Parse);
+        assert!(AriadneError::llm("overloaded", true).is_retriable());
+        assert_eq!(ErrorClass::Llm.to_string(), "llm");
+    }
+
+    #[cfg(feature = "memgraph")]
+    #[test]
+    fn memgraph_errors_are_backend_errors() {
+        let refused = AriadneError::from(memgraph::MemgraphError::QueryError("bad".into()));
+        assert_eq!(refused.class(), ErrorClass::Backend);
+        assert!(!refused.is_retriable());
+        assert_eq!(refused.to_string(), "MemgraphError: QueryError: bad");
+
+        let unreachable =
+            AriadneError::from(memgraph::MemgraphError::ConnectionError("refused".into()));
+        assert!(unreachable.is_retriable());
     }
 }
@@ -5,6 +5,7 @@ use std::time::{Duration, Instant};
 
 use tokio::sync::oneshot;
 
+use crate::errors::AriadneError;
 use crate::prelude::*;
 use crate::state::{ClusterStateDiff, GraphEdge, SharedClusterState};
 use crate::types::GenericObject;
@@ -23,7 +24,7 @@ pub(crate) trait GraphConnection {
 
 enum Command {
     Create {
-        lock: SharedClusterState,
+        state: SharedClusterState,
         resp: oneshot::Sender<Result<()>>,
     },
     Update {
@@ -79,10 +80,10 @@ impl GraphActor {
 
                 while let Ok(cmd) = rx.recv() {
                     match cmd {
-                        Command::Create { lock, resp } => {
+                        Command::Create { state, resp } => {
                             info!("{label}: create");
                             let (nodes, edges) = {
-                                let state = lock.lock().expect("Failed to lock cluster state");
+                                let state = state.load();
                                 let nodes = state.get_nodes().cloned().collect::<Vec<_>>();
                                 let edges = state.get_edges().collect::<Vec<_>>();
                                 (nodes, edges)
@@ -125,15 +126,14 @@ impl GraphActor {
                     }
                 }
             })
-            .map_err(|e| std::io::Error::other(format!("Failed to spawn {label} actor: {e}")))?;
+            .map_err(|e| AriadneError::backend(format!("Failed to spawn {label} actor: {e}")))?;
 
         match ready_rx.recv() {
             Ok(Ok(())) => Ok(Self { tx, label }),
             Ok(Err(err)) => Err(err),
-            Err(err) => Err(std::io::Error::other(format!(
+            Err(err) => Err(AriadneError::backend(format!(
                 "{label} actor failed to signal readiness: {err}"
-            ))
-            .into()),
+            ))),
         }
     }
 
@@ -141,17 +141,17 @@ impl GraphActor {
         let (resp_tx, resp_rx) = oneshot::channel();
         self.tx
             .send(Command::Create {
-                lock: cluster_state,
+                state: cluster_state,
                 resp: resp_tx,
             })
             .map_err(|e| {
-                std::io::Error::other(format!(
+                AriadneError::backend(format!(
                     "{label} actor is not available: {e}",
                     label = self.label
                 ))
             })?;
         resp_rx.await.map_err(|e| {
-            std::io::Error::other(format!(
+            AriadneError::backend(format!(
                 "{label} actor response dropped: {e}",
                 label = self.label
             ))
@@ -166,13 +166,13 @@ impl GraphActor {
                 resp: resp_tx,
             })
             .map_err(|e| {
-                std::io::Error::other(format!(
+                AriadneError::backend(format!(
                     "{label} actor is not available: {e}",
                     label = self.label
                 ))
             })?;
         resp_rx.await.map_err(|e| {
-            std::io::Error::other(format!(
+            AriadneError::backend(format!(
                 "{label} actor response dropped: {e}",
                 label = self.label
             ))
@@ -192,13 +192,13 @@ impl GraphActor {
                 resp: resp_tx,
             })
             .map_err(|e| {
-                std::io::Error::other(format!(
+                AriadneError::backend(format!(
                     "{label} actor is not available: {e}",
                     label = self.label
                 ))
             })?;
         resp_rx.await.map_err(|e| {
-            std::io::Error::other(format!(
+            AriadneError::backend(format!(
                 "{label} actor response dropped: {e}",
                 label = self.label
             ))
@@ -260,7 +260,7 @@ mod tests {
             edges: &[GraphEdge],
         ) -> Result<()> {
             if matches!(self.fail, FailMode::Create) {
-                return Err(std::io::Error::other("create failed").into());
+                return Err(AriadneError::backend("create failed"));
             }
             let mut state = self.state.lock().unwrap();
             state.calls.push("create".to_string());
@@ -271,7 +271,7 @@ mod tests {
 
         fn update_from_diff(&mut self, diff: &ClusterStateDiff) -> Result<()> {
             if matches!(self.fail, FailMode::Update) {
-                return Err(std::io::Error::other("update failed").into());
+                return Err(AriadneError::backend("update failed"));
             }
             let mut state = self.state.lock().unwrap();
             state.calls.push("update".to_string());
@@ -289,7 +289,7 @@ mod tests {
             _params: Option<&HashMap<String, Value>>,
         ) -> Result<Vec<Value>> {
             if matches!(self.fail, FailMode::Query) {
-                return Err(std::io::Error::other("query failed").into());
+                return Err(AriadneError::backend("query failed"));
             }
             let mut state = self.state.lock().unwrap();
             state.calls.push("query".to_string());
@@ -344,7 +344,7 @@ mod tests {
             Edge::PartOf,
         );
 
-        let shared_state = Arc::new(Mutex::new(cluster_state));
+        let shared_state = SharedClusterState::new(cluster_state);
         actor.create(shared_state).await.unwrap();
 
         let mut diff = ClusterStateDiff::default();
@@ -386,7 +386,7 @@ mod tests {
 
         let cluster = build_cluster("cluster-uid", "demo");
         let cluster_state = ClusterState::new(cluster);
-        let shared_state = Arc::new(Mutex::new(cluster_state));
+        let shared_state = SharedClusterState::new(cluster_state);
         assert!(actor.create(shared_state).await.is_err());
     }
 
@@ -430,7 +430,7 @@ mod tests {
     #[test]
     fn actor_reports_connect_failures() {
         let actor = GraphActor::spawn::<TestConnection, _>("test", move || {
-            Err(std::io::Error::other("connect failed").into())
+            Err(AriadneError::backend("connect failed"))
         });
         assert!(actor.is_err());
     }
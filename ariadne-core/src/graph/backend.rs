@@ -5,6 +5,9 @@ use std::collections::HashMap;
 use crate::prelude::Result;
 use crate::state::{ClusterStateDiff, SharedClusterState};
 
+/// A graph database holding a cluster's state. Failures are of class
+/// [`Backend`](crate::errors::ErrorClass::Backend), or [`Parse`](crate::errors::ErrorClass::Parse)
+/// for a query the backend cannot parse, and are retriable when the backend was unreachable.
 #[async_trait]
 pub trait GraphBackend: Send + Sync + std::fmt::Debug {
     async fn create(&self, cluster_state: SharedClusterState) -> Result<()>;
@@ -14,5 +17,13 @@ pub trait GraphBackend: Send + Sync + std::fmt::Debug {
         query: String,
         params: Option<HashMap<String, Value>>,
     ) -> Result<Vec<Value>>;
+    /// Executes the query with profiling enabled and returns the plan/statistics as rows.
+    async fn profile_query(
+        &self,
+        query: String,
+        params: Option<HashMap<String, Value>>,
+    ) -> Result<Vec<Value>> {
+        self.execute_query(format!("PROFILE {query}"), params).await
+    }
     async fn shutdown(&self);
 }
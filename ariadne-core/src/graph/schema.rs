@@ -6,24 +6,105 @@ use strum::IntoEnumIterator;
 
 type EdgeKey = (ResourceType, Edge, ResourceType);
 
+/// Paths of the node properties the backends index for every label.
+pub const INDEXED_PROPERTIES: &[&str] = &["metadata.name", "metadata.uid", "metadata.namespace"];
+
+/// Version of the graph model: its labels, relationships and how properties are stored. Bump it
+/// whenever a graph written by the previous version would be read wrongly.
+pub const GRAPH_SCHEMA_VERSION: u32 = 2;
+
+/// Label of the single node backends stamp a graph with; its `version` property is the
+/// [`GRAPH_SCHEMA_VERSION`] the graph was written with.
+pub const SCHEMA_VERSION_LABEL: &str = "AriadneSchema";
+
+/// How the schema version stamped on a stored graph relates to [`GRAPH_SCHEMA_VERSION`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaCompatibility {
+    /// The graph is empty or was written before graphs were stamped.
+    Unstamped,
+    Compatible,
+    /// Written by an older binary; rebuilding the graph brings it up to date.
+    Older(u32),
+    /// Written by a newer binary, whose graph this one would misread or downgrade.
+    Newer(u32),
+}
+
+impl SchemaCompatibility {
+    pub fn of(stored: Option<u32>) -> Self {
+        match stored {
+            None => Self::Unstamped,
+            Some(version) if version == GRAPH_SCHEMA_VERSION => Self::Compatible,
+            Some(version) if version < GRAPH_SCHEMA_VERSION => Self::Older(version),
+            Some(version) => Self::Newer(version),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct GraphRelationship {
     #[serde(rename = "from")]
     pub from: String,
     pub edge: String,
     pub to: String,
+    pub cardinality: Cardinality,
 }
 
 impl GraphRelationship {
     fn new(from: ResourceType, edge: Edge, to: ResourceType) -> Self {
         Self {
             from: from.to_string(),
+            cardinality: edge_cardinality(&edge),
             edge: edge.to_string(),
             to: to.to_string(),
         }
     }
 }
 
+/// How many nodes an edge typically connects on each side, source first: `many-to-one` means
+/// many sources share a target and each source has at most one. A hint, not a constraint the
+/// graph enforces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Cardinality {
+    OneToOne,
+    OneToMany,
+    ManyToOne,
+    ManyToMany,
+}
+
+pub fn edge_cardinality(edge: &Edge) -> Cardinality {
+    match edge {
+        Edge::BoundTo => Cardinality::OneToOne,
+        Edge::Manages | Edge::DefinesBackend | Edge::ContainsEndpoint | Edge::HasAddress => {
+            Cardinality::OneToMany
+        }
+        Edge::PartOf
+        | Edge::BelongsTo
+        | Edge::RunsOn
+        | Edge::Runs
+        | Edge::TargetsService
+        | Edge::ListedIn
+        | Edge::IsAddressOf
+        | Edge::UsesIdentity
+        | Edge::UsesProvisioner
+        | Edge::UsesStorageClass
+        | Edge::Concerns => Cardinality::ManyToOne,
+        Edge::IsClaimedBy
+        | Edge::MountsConfig
+        | Edge::InjectsConfig
+        | Edge::ClaimsVolume
+        | Edge::AppliesTo => Cardinality::ManyToMany,
+    }
+}
+
+/// Edges the resolver does not create yet; they stay out of the relationships until it does.
+pub const UNRESOLVED_EDGES: &[Edge] = &[
+    Edge::MountsConfig,
+    Edge::InjectsConfig,
+    Edge::UsesIdentity,
+    Edge::AppliesTo,
+];
+
 const BASE_RELATIONSHIPS: &[EdgeKey] = &[
     (ResourceType::AWX, Edge::Manages, ResourceType::Deployment),
     (ResourceType::AWX, Edge::Manages, ResourceType::Ingress),
@@ -256,6 +337,45 @@ pub fn graph_relationships() -> Vec<GraphRelationship> {
         .collect()
 }
 
+/// Where the relationships disagree with the `Edge` and `ResourceType` enums: edges neither in a
+/// relationship nor in [`UNRESOLVED_EDGES`], unresolved edges that are in one, relationships
+/// declared twice and endpoints that are not resource types.
+pub fn relationship_issues() -> Vec<String> {
+    let relationships = graph_relationships();
+    let mut issues = Vec::new();
+    for edge in Edge::iter() {
+        let name = edge.to_string();
+        let used = relationships
+            .iter()
+            .any(|relationship| relationship.edge == name);
+        match (used, UNRESOLVED_EDGES.contains(&edge)) {
+            (false, false) => issues.push(format!(
+                "edge {name} is in no relationship and not listed as unresolved"
+            )),
+            (true, true) => issues.push(format!(
+                "edge {name} is listed as unresolved but is in a relationship"
+            )),
+            _ => {}
+        }
+    }
+    let mut seen = HashSet::new();
+    for relationship in &relationships {
+        let pattern = format!(
+            "(:{})-[:{}]->(:{})",
+            relationship.from, relationship.edge, relationship.to
+        );
+        for endpoint in [&relationship.from, &relationship.to] {
+            if ResourceType::try_new(endpoint).is_err() {
+                issues.push(format!("{pattern}: {endpoint} is not a resource type"));
+            }
+        }
+        if !seen.insert(pattern.clone()) {
+            issues.push(format!("{pattern} is declared twice"));
+        }
+    }
+    issues
+}
+
 pub fn is_known_edge(source: &ResourceType, edge: &Edge, target: &ResourceType) -> bool {
     static EDGE_SET: OnceLock<HashSet<EdgeKey>> = OnceLock::new();
     let set = EDGE_SET.get_or_init(|| graph_relationship_specs().into_iter().collect());
@@ -272,4 +392,29 @@ mod tests {
             assert!(is_known_edge(&source, &edge, &target));
         }
     }
+
+    #[test]
+    fn relationships_match_the_enums() {
+        assert_eq!(relationship_issues(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn stored_schema_versions_are_compared_with_the_binary() {
+        assert_eq!(
+            SchemaCompatibility::of(None),
+            SchemaCompatibility::Unstamped
+        );
+        assert_eq!(
+            SchemaCompatibility::of(Some(GRAPH_SCHEMA_VERSION)),
+            SchemaCompatibility::Compatible
+        );
+        assert_eq!(
+            SchemaCompatibility::of(Some(GRAPH_SCHEMA_VERSION + 1)),
+            SchemaCompatibility::Newer(GRAPH_SCHEMA_VERSION + 1)
+        );
+        assert_eq!(
+            SchemaCompatibility::of(Some(0)),
+            SchemaCompatibility::Older(0)
+        );
+    }
 }
@@ -1,16 +1,23 @@
+use crate::errors::AriadneError;
 use crate::prelude::*;
+use crate::redaction::RedactionPolicy;
 use crate::snapshot::{
-    read_json_from_dir, read_list_from_dir, SNAPSHOT_CLUSTER_FILE, SNAPSHOT_CONFIG_MAPS_FILE,
-    SNAPSHOT_DAEMON_SETS_FILE, SNAPSHOT_DEPLOYMENTS_FILE, SNAPSHOT_ENDPOINT_SLICES_FILE,
-    SNAPSHOT_EVENTS_FILE, SNAPSHOT_INGRESSES_FILE, SNAPSHOT_JOBS_FILE, SNAPSHOT_NAMESPACES_FILE,
+    read_json_from_dir, read_list_from_dir, read_trace_failures_from_dir, read_trace_from_dir,
+    write_json_to_dir, write_list_to_dir, write_trace_failure_to_dir, write_trace_to_dir,
+    TraceFailure, SNAPSHOT_CLUSTER_FILE, SNAPSHOT_CONFIG_MAPS_FILE, SNAPSHOT_DAEMON_SETS_FILE,
+    SNAPSHOT_DEPLOYMENTS_FILE, SNAPSHOT_ENDPOINT_SLICES_FILE, SNAPSHOT_EVENTS_FILE,
+    SNAPSHOT_INGRESSES_FILE, SNAPSHOT_JOBS_FILE, SNAPSHOT_NAMESPACES_FILE,
     SNAPSHOT_NETWORK_POLICIES_FILE, SNAPSHOT_NODES_FILE, SNAPSHOT_PERSISTENT_VOLUMES_FILE,
     SNAPSHOT_PERSISTENT_VOLUME_CLAIMS_FILE, SNAPSHOT_PODS_FILE, SNAPSHOT_REPLICA_SETS_FILE,
     SNAPSHOT_SERVICES_FILE, SNAPSHOT_SERVICE_ACCOUNTS_FILE, SNAPSHOT_STATEFUL_SETS_FILE,
     SNAPSHOT_STORAGE_CLASSES_FILE,
 };
 use crate::tls::install_rustls_provider;
-use crate::types::Cluster;
+use crate::types::{Cluster, ObjectIdentifier};
 use std::any::type_name;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
 use async_trait::async_trait;
 use futures::{future, StreamExt};
@@ -24,6 +31,7 @@ use k8s_openapi::api::discovery::v1::EndpointSlice;
 use k8s_openapi::api::events::v1::Event;
 use k8s_openapi::api::networking::v1::{Ingress, NetworkPolicy};
 use k8s_openapi::api::storage::v1::StorageClass;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
 use k8s_openapi::apimachinery::pkg::version::Info;
 use kube::api::{ListParams, LogParams};
 use kube::config::KubeConfigOptions;
@@ -31,9 +39,12 @@ use kube::runtime::reflector::Store;
 use kube::runtime::{reflector, watcher, WatchStreamExt};
 use kube::{Api, Client, Config, Resource, ResourceExt};
 use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::fmt::Debug;
-use std::path::Path;
-use std::sync::Arc;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::task::JoinHandle;
 use tokio::time::timeout;
@@ -171,6 +182,9 @@ impl KubeClientImpl {
 
 const LAST_N_LOG_LINES: i64 = 50;
 const STORE_READY_TIMEOUT_SECONDS: u64 = 10;
+/// Objects requested per list call unless `KUBE_LIST_PAGE_SIZE` says otherwise, so a large
+/// cluster is read in many small responses instead of one that can time out on the apiserver.
+pub const DEFAULT_LIST_PAGE_SIZE: u32 = 500;
 
 #[async_trait]
 impl KubeClient for KubeClientImpl {
@@ -491,20 +505,19 @@ where
             match timeout(timeout_duration, store.wait_until_ready()).await {
                 Ok(wait_result) => {
                     if let Err(err) = wait_result {
-                        return Err(std::io::Error::other(format!(
-                            "{kind} store is not ready: {err}"
-                        ))
-                        .into());
+                        return Err(AriadneError::kube_store(
+                            format!("{kind} store is not ready: {err}"),
+                            false,
+                        ));
                     }
                     Ok(store.state())
                 }
                 Err(_elapsed) => {
                     warn!("Timed out waiting for {kind} store after {timeout_duration:?}",);
-                    Err(std::io::Error::new(
-                        std::io::ErrorKind::TimedOut,
+                    Err(AriadneError::kube_store(
                         format!("Timed out waiting for {kind} store readiness"),
-                    )
-                    .into())
+                        true,
+                    ))
                 }
             }
         }
@@ -520,6 +533,15 @@ fn store_ready_timeout() -> Duration {
         .unwrap_or(Duration::from_secs(STORE_READY_TIMEOUT_SECONDS))
 }
 
+/// Objects per page of a list call, read from `KUBE_LIST_PAGE_SIZE`.
+fn list_page_size() -> u32 {
+    std::env::var("KUBE_LIST_PAGE_SIZE")
+        .ok()
+        .and_then(|value| value.parse::<u32>().ok())
+        .filter(|size| *size > 0)
+        .unwrap_or(DEFAULT_LIST_PAGE_SIZE)
+}
+
 fn event_store_ready_timeout() -> Duration {
     std::env::var("KUBE_EVENT_STORE_READY_TIMEOUT_SECONDS")
         .ok()
@@ -843,6 +865,498 @@ impl KubeClient for SnapshotKubeClient {
     }
 }
 
+/// Forwards to another client and records every listing it returns into a directory, so an
+/// intermittent issue seen on a live cluster can be replayed offline with [`ReplayKubeClient`].
+/// The top-level files of the directory hold the latest response of each kind, which makes it a
+/// snapshot directory too, and its trace holds every response that differed from the one before
+/// and every listing that failed, so a replay fails the same polls. Pod logs are passed through
+/// without being recorded.
+pub struct RecordingKubeClient {
+    inner: Box<dyn KubeClient>,
+    dir: PathBuf,
+    options: RecordingOptions,
+    recorded: Mutex<Recorded>,
+}
+
+/// Default for [`RecordingOptions::max_trace_bytes`]: 256 MiB.
+pub const DEFAULT_MAX_TRACE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// How a [`RecordingKubeClient`] treats the listings it records.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordingOptions {
+    /// Masks ConfigMaps and pod specs before they are written, and before they are handed on,
+    /// so a recording holds no value the graph would not. Pass the resolver's policy.
+    pub redaction: RedactionPolicy,
+    /// How many bytes the trace may take on disk. The write that reaches it is the last one; the
+    /// top-level files keep being updated, and a replay serves the last traced listings from then
+    /// on.
+    pub max_trace_bytes: u64,
+}
+
+impl Default for RecordingOptions {
+    fn default() -> Self {
+        Self {
+            redaction: RedactionPolicy::default(),
+            max_trace_bytes: DEFAULT_MAX_TRACE_BYTES,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Recorded {
+    kinds: HashMap<&'static str, RecordedKind>,
+    trace_bytes: u64,
+}
+
+#[derive(Default)]
+struct RecordedKind {
+    calls: usize,
+    last_hash: Option<u64>,
+}
+
+impl RecordingKubeClient {
+    /// Creates `dir` and writes the cluster file of `cluster_name`, read through `inner`.
+    pub async fn new(
+        cluster_name: &str,
+        inner: Box<dyn KubeClient>,
+        dir: impl AsRef<Path>,
+        options: RecordingOptions,
+    ) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let cluster = Cluster::new(
+            ObjectIdentifier {
+                uid: format!("Cluster:{cluster_name}"),
+                name: cluster_name.to_string(),
+                namespace: None,
+                resource_version: None,
+            },
+            &inner.get_cluster_url().await?,
+            inner.apiserver_version().await?,
+        );
+        write_json_to_dir(&dir, SNAPSHOT_CLUSTER_FILE, &cluster)?;
+        Ok(RecordingKubeClient {
+            inner,
+            dir,
+            options,
+            recorded: Mutex::new(Recorded::default()),
+        })
+    }
+
+    /// Records `listing` as the outcome of the next listing of the kind of `filename` and hands
+    /// it back; a failed write is logged rather than failing the listing.
+    fn record<T: Serialize>(
+        &self,
+        filename: &'static str,
+        listing: Result<Vec<Arc<T>>>,
+    ) -> Result<Vec<Arc<T>>> {
+        if let Err(err) = self.try_record(filename, &listing) {
+            warn!(
+                "Failed to record {filename} into {}: {err}",
+                self.dir.display()
+            );
+        }
+        listing
+    }
+
+    fn try_record<T: Serialize>(
+        &self,
+        filename: &'static str,
+        listing: &Result<Vec<Arc<T>>>,
+    ) -> Result<()> {
+        let hash = match listing {
+            Ok(items) => Some(listing_hash(items)?),
+            Err(_) => None,
+        };
+        let (call, traced) = {
+            let mut recorded = self.recorded.lock().expect("Failed to lock recorded kinds");
+            let kind = recorded.kinds.entry(filename).or_default();
+            kind.calls += 1;
+            if hash.is_some() {
+                if kind.last_hash == hash {
+                    return Ok(());
+                }
+                kind.last_hash = hash;
+            }
+            let call = kind.calls;
+            (call, recorded.trace_bytes < self.options.max_trace_bytes)
+        };
+        let written = match listing {
+            Ok(items) => {
+                write_list_to_dir(&self.dir, filename, items)?;
+                if !traced {
+                    return Ok(());
+                }
+                write_trace_to_dir(&self.dir, filename, call, items)?
+            }
+            Err(_) if !traced => return Ok(()),
+            Err(err) => {
+                let failure = TraceFailure {
+                    message: err.to_string(),
+                    retriable: err.is_retriable(),
+                };
+                write_trace_failure_to_dir(&self.dir, filename, call, &failure)?
+            }
+        };
+        let bytes = fs::metadata(&written)?.len();
+        let mut recorded = self.recorded.lock().expect("Failed to lock recorded kinds");
+        let before = recorded.trace_bytes;
+        recorded.trace_bytes += bytes;
+        if before < self.options.max_trace_bytes
+            && recorded.trace_bytes >= self.options.max_trace_bytes
+        {
+            warn!(
+                "The trace in {} reached {} bytes; recording only the latest listings from now on",
+                self.dir.display(),
+                self.options.max_trace_bytes
+            );
+        }
+        Ok(())
+    }
+}
+
+fn listing_hash<T: Serialize>(items: &[Arc<T>]) -> Result<u64> {
+    let view: Vec<&T> = items.iter().map(|item| item.as_ref()).collect();
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_vec(&view)?.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+#[async_trait]
+impl KubeClient for RecordingKubeClient {
+    async fn get_namespaces(&self) -> Result<Vec<Arc<Namespace>>> {
+        self.record(SNAPSHOT_NAMESPACES_FILE, self.inner.get_namespaces().await)
+    }
+
+    async fn get_pods(&self) -> Result<Vec<Arc<Pod>>> {
+        let pods = self.inner.get_pods().await.map(|mut pods| {
+            self.options.redaction.redact_pod_specs(&mut pods);
+            pods
+        });
+        self.record(SNAPSHOT_PODS_FILE, pods)
+    }
+
+    async fn get_deployments(&self) -> Result<Vec<Arc<Deployment>>> {
+        let deployments = self.inner.get_deployments().await.map(|mut deployments| {
+            self.options.redaction.redact_pod_specs(&mut deployments);
+            deployments
+        });
+        self.record(SNAPSHOT_DEPLOYMENTS_FILE, deployments)
+    }
+
+    async fn get_stateful_sets(&self) -> Result<Vec<Arc<StatefulSet>>> {
+        let stateful_sets = self
+            .inner
+            .get_stateful_sets()
+            .await
+            .map(|mut stateful_sets| {
+                self.options.redaction.redact_pod_specs(&mut stateful_sets);
+                stateful_sets
+            });
+        self.record(SNAPSHOT_STATEFUL_SETS_FILE, stateful_sets)
+    }
+
+    async fn get_replica_sets(&self) -> Result<Vec<Arc<ReplicaSet>>> {
+        let replica_sets = self.inner.get_replica_sets().await.map(|mut replica_sets| {
+            self.options.redaction.redact_pod_specs(&mut replica_sets);
+            replica_sets
+        });
+        self.record(SNAPSHOT_REPLICA_SETS_FILE, replica_sets)
+    }
+
+    async fn get_daemon_sets(&self) -> Result<Vec<Arc<DaemonSet>>> {
+        let daemon_sets = self.inner.get_daemon_sets().await.map(|mut daemon_sets| {
+            self.options.redaction.redact_pod_specs(&mut daemon_sets);
+            daemon_sets
+        });
+        self.record(SNAPSHOT_DAEMON_SETS_FILE, daemon_sets)
+    }
+
+    async fn get_jobs(&self) -> Result<Vec<Arc<Job>>> {
+        let jobs = self.inner.get_jobs().await.map(|mut jobs| {
+            self.options.redaction.redact_pod_specs(&mut jobs);
+            jobs
+        });
+        self.record(SNAPSHOT_JOBS_FILE, jobs)
+    }
+
+    async fn get_ingresses(&self) -> Result<Vec<Arc<Ingress>>> {
+        self.record(SNAPSHOT_INGRESSES_FILE, self.inner.get_ingresses().await)
+    }
+
+    async fn get_services(&self) -> Result<Vec<Arc<Service>>> {
+        self.record(SNAPSHOT_SERVICES_FILE, self.inner.get_services().await)
+    }
+
+    async fn get_endpoint_slices(&self) -> Result<Vec<Arc<EndpointSlice>>> {
+        self.record(
+            SNAPSHOT_ENDPOINT_SLICES_FILE,
+            self.inner.get_endpoint_slices().await,
+        )
+    }
+
+    async fn get_network_policies(&self) -> Result<Vec<Arc<NetworkPolicy>>> {
+        self.record(
+            SNAPSHOT_NETWORK_POLICIES_FILE,
+            self.inner.get_network_policies().await,
+        )
+    }
+
+    async fn get_config_maps(&self) -> Result<Vec<Arc<ConfigMap>>> {
+        let config_maps = self.inner.get_config_maps().await.map(|mut config_maps| {
+            self.options.redaction.redact_config_maps(&mut config_maps);
+            config_maps
+        });
+        self.record(SNAPSHOT_CONFIG_MAPS_FILE, config_maps)
+    }
+
+    async fn get_storage_classes(&self) -> Result<Vec<Arc<StorageClass>>> {
+        self.record(
+            SNAPSHOT_STORAGE_CLASSES_FILE,
+            self.inner.get_storage_classes().await,
+        )
+    }
+
+    async fn get_persistent_volumes(&self) -> Result<Vec<Arc<PersistentVolume>>> {
+        self.record(
+            SNAPSHOT_PERSISTENT_VOLUMES_FILE,
+            self.inner.get_persistent_volumes().await,
+        )
+    }
+
+    async fn get_persistent_volume_claims(&self) -> Result<Vec<Arc<PersistentVolumeClaim>>> {
+        self.record(
+            SNAPSHOT_PERSISTENT_VOLUME_CLAIMS_FILE,
+            self.inner.get_persistent_volume_claims().await,
+        )
+    }
+
+    async fn get_nodes(&self) -> Result<Vec<Arc<Node>>> {
+        self.record(SNAPSHOT_NODES_FILE, self.inner.get_nodes().await)
+    }
+
+    async fn get_service_accounts(&self) -> Result<Vec<Arc<ServiceAccount>>> {
+        self.record(
+            SNAPSHOT_SERVICE_ACCOUNTS_FILE,
+            self.inner.get_service_accounts().await,
+        )
+    }
+
+    async fn apiserver_version(&self) -> Result<Info> {
+        self.inner.apiserver_version().await
+    }
+
+    async fn get_cluster_url(&self) -> Result<String> {
+        self.inner.get_cluster_url().await
+    }
+
+    async fn get_pod_logs(
+        &self,
+        namespace: &str,
+        pod_name: &str,
+        container: Option<String>,
+    ) -> Result<String> {
+        self.inner
+            .get_pod_logs(namespace, pod_name, container)
+            .await
+    }
+
+    async fn get_events(&self) -> Result<Vec<Arc<Event>>> {
+        self.record(SNAPSHOT_EVENTS_FILE, self.inner.get_events().await)
+    }
+}
+
+/// Serves a directory written by [`RecordingKubeClient`]: the `n`th listing of a kind returns the
+/// latest response recorded at or before call `n`, so polling the client replays the recorded
+/// changes in order. A kind keeps its last response once the trace runs out, a kind without a
+/// trace serves its top-level file on every call, as [`SnapshotKubeClient`] does, and a kind that
+/// was never recorded lists nothing. A listing that failed while recording fails again on the same
+/// call, with the recorded message and retriability, so a resolver retries the same polls.
+pub struct ReplayKubeClient {
+    cluster: Cluster,
+    namespaces: ReplayedKind<Namespace>,
+    pods: ReplayedKind<Pod>,
+    deployments: ReplayedKind<Deployment>,
+    stateful_sets: ReplayedKind<StatefulSet>,
+    replica_sets: ReplayedKind<ReplicaSet>,
+    daemon_sets: ReplayedKind<DaemonSet>,
+    jobs: ReplayedKind<Job>,
+    ingresses: ReplayedKind<Ingress>,
+    services: ReplayedKind<Service>,
+    endpoint_slices: ReplayedKind<EndpointSlice>,
+    network_policies: ReplayedKind<NetworkPolicy>,
+    config_maps: ReplayedKind<ConfigMap>,
+    storage_classes: ReplayedKind<StorageClass>,
+    persistent_volumes: ReplayedKind<PersistentVolume>,
+    persistent_volume_claims: ReplayedKind<PersistentVolumeClaim>,
+    nodes: ReplayedKind<Node>,
+    service_accounts: ReplayedKind<ServiceAccount>,
+    events: ReplayedKind<Event>,
+}
+
+struct ReplayedKind<T> {
+    responses: Vec<(usize, Vec<Arc<T>>)>,
+    failures: HashMap<usize, TraceFailure>,
+    calls: AtomicUsize,
+}
+
+impl<T: DeserializeOwned> ReplayedKind<T> {
+    fn from_dir(dir: &Path, filename: &str) -> Result<Self> {
+        let mut responses = read_trace_from_dir(dir, filename)?;
+        if responses.is_empty() && dir.join(filename).exists() {
+            responses.push((1, read_list_from_dir(dir, filename)?));
+        }
+        Ok(ReplayedKind {
+            responses,
+            failures: read_trace_failures_from_dir(dir, filename)?
+                .into_iter()
+                .collect(),
+            calls: AtomicUsize::new(0),
+        })
+    }
+
+    fn next(&self) -> Result<Vec<Arc<T>>> {
+        let call = self.calls.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(failure) = self.failures.get(&call) {
+            return Err(AriadneError::kube_store(
+                failure.message.clone(),
+                failure.retriable,
+            ));
+        }
+        Ok(self
+            .responses
+            .iter()
+            .rev()
+            .find(|(recorded, _)| *recorded <= call)
+            .or(self.responses.first())
+            .map(|(_, items)| items.clone())
+            .unwrap_or_default())
+    }
+}
+
+impl ReplayKubeClient {
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        Ok(ReplayKubeClient {
+            cluster: read_json_from_dir(dir, SNAPSHOT_CLUSTER_FILE)?,
+            namespaces: ReplayedKind::from_dir(dir, SNAPSHOT_NAMESPACES_FILE)?,
+            pods: ReplayedKind::from_dir(dir, SNAPSHOT_PODS_FILE)?,
+            deployments: ReplayedKind::from_dir(dir, SNAPSHOT_DEPLOYMENTS_FILE)?,
+            stateful_sets: ReplayedKind::from_dir(dir, SNAPSHOT_STATEFUL_SETS_FILE)?,
+            replica_sets: ReplayedKind::from_dir(dir, SNAPSHOT_REPLICA_SETS_FILE)?,
+            daemon_sets: ReplayedKind::from_dir(dir, SNAPSHOT_DAEMON_SETS_FILE)?,
+            jobs: ReplayedKind::from_dir(dir, SNAPSHOT_JOBS_FILE)?,
+            ingresses: ReplayedKind::from_dir(dir, SNAPSHOT_INGRESSES_FILE)?,
+            services: ReplayedKind::from_dir(dir, SNAPSHOT_SERVICES_FILE)?,
+            endpoint_slices: ReplayedKind::from_dir(dir, SNAPSHOT_ENDPOINT_SLICES_FILE)?,
+            network_policies: ReplayedKind::from_dir(dir, SNAPSHOT_NETWORK_POLICIES_FILE)?,
+            config_maps: ReplayedKind::from_dir(dir, SNAPSHOT_CONFIG_MAPS_FILE)?,
+            storage_classes: ReplayedKind::from_dir(dir, SNAPSHOT_STORAGE_CLASSES_FILE)?,
+            persistent_volumes: ReplayedKind::from_dir(dir, SNAPSHOT_PERSISTENT_VOLUMES_FILE)?,
+            persistent_volume_claims: ReplayedKind::from_dir(
+                dir,
+                SNAPSHOT_PERSISTENT_VOLUME_CLAIMS_FILE,
+            )?,
+            nodes: ReplayedKind::from_dir(dir, SNAPSHOT_NODES_FILE)?,
+            service_accounts: ReplayedKind::from_dir(dir, SNAPSHOT_SERVICE_ACCOUNTS_FILE)?,
+            events: ReplayedKind::from_dir(dir, SNAPSHOT_EVENTS_FILE)?,
+        })
+    }
+}
+
+#[async_trait]
+impl KubeClient for ReplayKubeClient {
+    async fn get_namespaces(&self) -> Result<Vec<Arc<Namespace>>> {
+        self.namespaces.next()
+    }
+
+    async fn get_pods(&self) -> Result<Vec<Arc<Pod>>> {
+        self.pods.next()
+    }
+
+    async fn get_deployments(&self) -> Result<Vec<Arc<Deployment>>> {
+        self.deployments.next()
+    }
+
+    async fn get_stateful_sets(&self) -> Result<Vec<Arc<StatefulSet>>> {
+        self.stateful_sets.next()
+    }
+
+    async fn get_replica_sets(&self) -> Result<Vec<Arc<ReplicaSet>>> {
+        self.replica_sets.next()
+    }
+
+    async fn get_daemon_sets(&self) -> Result<Vec<Arc<DaemonSet>>> {
+        self.daemon_sets.next()
+    }
+
+    async fn get_jobs(&self) -> Result<Vec<Arc<Job>>> {
+        self.jobs.next()
+    }
+
+    async fn get_ingresses(&self) -> Result<Vec<Arc<Ingress>>> {
+        self.ingresses.next()
+    }
+
+    async fn get_services(&self) -> Result<Vec<Arc<Service>>> {
+        self.services.next()
+    }
+
+    async fn get_endpoint_slices(&self) -> Result<Vec<Arc<EndpointSlice>>> {
+        self.endpoint_slices.next()
+    }
+
+    async fn get_network_policies(&self) -> Result<Vec<Arc<NetworkPolicy>>> {
+        self.network_policies.next()
+    }
+
+    async fn get_config_maps(&self) -> Result<Vec<Arc<ConfigMap>>> {
+        self.config_maps.next()
+    }
+
+    async fn get_storage_classes(&self) -> Result<Vec<Arc<StorageClass>>> {
+        self.storage_classes.next()
+    }
+
+    async fn get_persistent_volumes(&self) -> Result<Vec<Arc<PersistentVolume>>> {
+        self.persistent_volumes.next()
+    }
+
+    async fn get_persistent_volume_claims(&self) -> Result<Vec<Arc<PersistentVolumeClaim>>> {
+        self.persistent_volume_claims.next()
+    }
+
+    async fn get_nodes(&self) -> Result<Vec<Arc<Node>>> {
+        self.nodes.next()
+    }
+
+    async fn get_service_accounts(&self) -> Result<Vec<Arc<ServiceAccount>>> {
+        self.service_accounts.next()
+    }
+
+    async fn apiserver_version(&self) -> Result<Info> {
+        Ok(self.cluster.info.clone())
+    }
+
+    async fn get_cluster_url(&self) -> Result<String> {
+        Ok(self.cluster.cluster_url.clone())
+    }
+
+    async fn get_pod_logs(
+        &self,
+        _namespace: &str,
+        _pod_name: &str,
+        _container: Option<String>,
+    ) -> Result<String> {
+        warn!("ReplayKubeClient does not support pod logs");
+        Ok(String::new())
+    }
+
+    async fn get_events(&self) -> Result<Vec<Arc<Event>>> {
+        self.events.next()
+    }
+}
+
 fn make_store_and_watch<T>(
     api: Api<T>,
 ) -> (Store<T>, impl future::Future<Output = ()> + Send + 'static)
@@ -851,7 +1365,8 @@ where
     T::DynamicType: Default + Clone + Eq + std::hash::Hash + Send + Sync + 'static,
 {
     let (reader, writer) = reflector::store();
-    let fut = reflector(writer, watcher(api, Default::default()))
+    let config = watcher::Config::default().page_size(list_page_size());
+    let fut = reflector(writer, watcher(api, config))
         .modify(|item| {
             item.managed_fields_mut().clear();
         })
@@ -866,16 +1381,37 @@ where
     (reader, fut)
 }
 
+/// The custom resource definitions installed in the cluster of the kubeconfig `context`, or of
+/// the current context when none is given.
+pub async fn list_custom_resource_definitions(
+    context: Option<String>,
+) -> Result<Vec<Arc<CustomResourceDefinition>>> {
+    install_rustls_provider();
+    let options = KubeConfigOptions {
+        context,
+        cluster: None,
+        user: None,
+    };
+    let client = Client::try_from(Config::from_kubeconfig(&options).await?)?;
+    get_object(&Api::all(client)).await
+}
+
+/// Lists every object of `api`, one page of [`list_page_size`] objects at a time.
 async fn get_object<T: Clone + DeserializeOwned + Debug>(api: &Api<T>) -> Result<Vec<Arc<T>>> {
     let mut r: Vec<Arc<T>> = Vec::new();
     let mut continue_token: Option<String> = None;
+    let page = ListParams::default().limit(list_page_size());
     loop {
         let lp = match continue_token {
-            None => ListParams::default(),
-            Some(t) => ListParams::default().continue_token(&t),
+            None => page.clone(),
+            Some(t) => page.clone().continue_token(&t),
         };
         let pods = api.list(&lp).await?;
-        continue_token = pods.metadata.continue_.clone();
+        continue_token = pods
+            .metadata
+            .continue_
+            .clone()
+            .filter(|token| !token.is_empty());
 
         for p in pods {
             r.push(Arc::new(p))
@@ -0,0 +1,359 @@
+use crate::prelude::*;
+use crate::snapshot::{read_json_from_dir, write_json_to_dir, write_snapshot_dir};
+use crate::state_resolver::ObservedClusterSnapshot;
+use crate::types::{Cluster, ObjectIdentifier};
+use k8s_openapi::api::apps::v1::{Deployment, ReplicaSet};
+use k8s_openapi::api::core::v1::{
+    Container, Namespace, Node, ObjectReference, PersistentVolume, PersistentVolumeClaim,
+    PersistentVolumeClaimSpec, PersistentVolumeClaimVolumeSource, PersistentVolumeSpec, Pod,
+    PodSpec, Service, Volume,
+};
+use k8s_openapi::api::discovery::v1::{Endpoint, EndpointSlice};
+use k8s_openapi::api::networking::v1::{
+    HTTPIngressPath, HTTPIngressRuleValue, Ingress, IngressBackend, IngressRule,
+    IngressServiceBackend, IngressSpec, ServiceBackendPort,
+};
+use k8s_openapi::api::storage::v1::StorageClass;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference};
+use k8s_openapi::apimachinery::pkg::version::Info;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+/// File of a fixture directory with the queries to run against it and their rows.
+pub const FIXTURE_EXPECTED_FILE: &str = "expected.json";
+
+/// Name the cluster of every fixture is resolved under.
+pub const FIXTURE_CLUSTER_NAME: &str = "fixture";
+
+/// A query and the rows the graph of a fixture answers it with, in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedQuery {
+    pub query: String,
+    pub rows: Vec<Value>,
+}
+
+/// A small cluster with fixed names and uids whose graph answers a few queries in a known way.
+#[derive(Debug, Clone)]
+pub struct GoldenFixture {
+    pub name: &'static str,
+    pub snapshot: ObservedClusterSnapshot,
+    pub expected: Vec<ExpectedQuery>,
+}
+
+impl GoldenFixture {
+    /// Writes the snapshot and [`FIXTURE_EXPECTED_FILE`] to `dir`, which is created if needed.
+    pub fn write_to_dir(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+        write_snapshot_dir(dir, &self.snapshot)?;
+        write_json_to_dir(dir, FIXTURE_EXPECTED_FILE, &self.expected)?;
+        Ok(())
+    }
+}
+
+pub fn read_expected_from_dir(dir: &Path) -> Result<Vec<ExpectedQuery>> {
+    read_json_from_dir(dir, FIXTURE_EXPECTED_FILE)
+}
+
+/// The fixtures engine tests run against: a deployment chain, an ingress path and a storage
+/// chain.
+pub fn golden_fixtures() -> Vec<GoldenFixture> {
+    vec![deployment_chain(), ingress_path(), storage_chain()]
+}
+
+/// Deployment `web` managing two pods on node `node-a` through a replica set.
+fn deployment_chain() -> GoldenFixture {
+    let mut snapshot = fixture_snapshot();
+    snapshot.namespaces = vec![namespace("shop")];
+    snapshot.nodes = vec![Arc::new(Node {
+        metadata: meta("node-a", None, "node-a"),
+        ..Default::default()
+    })];
+    snapshot.deployments = vec![Arc::new(Deployment {
+        metadata: meta("web", Some("shop"), "deployment-web"),
+        ..Default::default()
+    })];
+    snapshot.replica_sets = vec![Arc::new(ReplicaSet {
+        metadata: ObjectMeta {
+            owner_references: Some(vec![owner(
+                "apps/v1",
+                "Deployment",
+                "web",
+                "deployment-web",
+            )]),
+            ..meta("web-7d9f8", Some("shop"), "replicaset-web-7d9f8")
+        },
+        ..Default::default()
+    })];
+    snapshot.pods = ["web-7d9f8-abcde", "web-7d9f8-fghij"]
+        .into_iter()
+        .map(|name| {
+            Arc::new(Pod {
+                metadata: ObjectMeta {
+                    owner_references: Some(vec![owner(
+                        "apps/v1",
+                        "ReplicaSet",
+                        "web-7d9f8",
+                        "replicaset-web-7d9f8",
+                    )]),
+                    ..meta(name, Some("shop"), &format!("pod-{name}"))
+                },
+                spec: Some(PodSpec {
+                    containers: vec![container("nginx")],
+                    node_name: Some("node-a".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    GoldenFixture {
+        name: "deployment-chain",
+        snapshot,
+        expected: vec![
+            expected(
+                "MATCH (d:Deployment)-[:Manages]->(rs:ReplicaSet)-[:Manages]->(p:Pod)\
+                 -[:RunsOn]->(n:Node) RETURN d.metadata.name AS deployment, \
+                 rs.metadata.name AS replica_set, p.metadata.name AS pod, \
+                 n.metadata.name AS node ORDER BY pod",
+                vec![
+                    json!({
+                        "deployment": "web",
+                        "replica_set": "web-7d9f8",
+                        "pod": "web-7d9f8-abcde",
+                        "node": "node-a",
+                    }),
+                    json!({
+                        "deployment": "web",
+                        "replica_set": "web-7d9f8",
+                        "pod": "web-7d9f8-fghij",
+                        "node": "node-a",
+                    }),
+                ],
+            ),
+            expected(
+                "MATCH (p:Pod)-[:BelongsTo]->(ns:Namespace) \
+                 RETURN ns.metadata.name AS namespace, count(p) AS pods",
+                vec![json!({ "namespace": "shop", "pods": 2 })],
+            ),
+            expected(
+                "MATCH (c:Container)-[:Runs]->(:Pod) RETURN count(c) AS containers",
+                vec![json!({ "containers": 2 })],
+            ),
+        ],
+    }
+}
+
+/// Host `shop.example.com` routed by ingress `storefront` to service `web` and its one pod.
+fn ingress_path() -> GoldenFixture {
+    let mut snapshot = fixture_snapshot();
+    snapshot.namespaces = vec![namespace("shop")];
+    snapshot.ingresses = vec![Arc::new(Ingress {
+        metadata: meta("storefront", Some("shop"), "ingress-storefront"),
+        spec: Some(IngressSpec {
+            rules: Some(vec![IngressRule {
+                host: Some("shop.example.com".to_string()),
+                http: Some(HTTPIngressRuleValue {
+                    paths: vec![HTTPIngressPath {
+                        path: Some("/".to_string()),
+                        path_type: "Prefix".to_string(),
+                        backend: IngressBackend {
+                            service: Some(IngressServiceBackend {
+                                name: "web".to_string(),
+                                port: Some(ServiceBackendPort {
+                                    number: Some(80),
+                                    ..Default::default()
+                                }),
+                            }),
+                            ..Default::default()
+                        },
+                    }],
+                }),
+            }]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })];
+    snapshot.services = vec![Arc::new(Service {
+        metadata: meta("web", Some("shop"), "service-web"),
+        ..Default::default()
+    })];
+    snapshot.endpoint_slices = vec![Arc::new(EndpointSlice {
+        metadata: ObjectMeta {
+            owner_references: Some(vec![owner("v1", "Service", "web", "service-web")]),
+            ..meta("web-x2k4p", Some("shop"), "endpointslice-web-x2k4p")
+        },
+        address_type: "IPv4".to_string(),
+        endpoints: vec![Endpoint {
+            addresses: vec!["10.0.0.11".to_string()],
+            target_ref: Some(ObjectReference {
+                kind: Some("Pod".to_string()),
+                name: Some("web-0".to_string()),
+                namespace: Some("shop".to_string()),
+                uid: Some("pod-web-0".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }],
+        ..Default::default()
+    })];
+    snapshot.pods = vec![Arc::new(Pod {
+        metadata: meta("web-0", Some("shop"), "pod-web-0"),
+        spec: Some(PodSpec {
+            containers: vec![container("nginx")],
+            ..Default::default()
+        }),
+        ..Default::default()
+    })];
+
+    GoldenFixture {
+        name: "ingress-path",
+        snapshot,
+        expected: vec![
+            expected(
+                "MATCH (h:Host)-[:IsClaimedBy]->(i:Ingress)-[:DefinesBackend]->\
+                 (:IngressServiceBackend)-[:TargetsService]->(s:Service) \
+                 RETURN h.name AS host, i.metadata.name AS ingress, s.metadata.name AS service",
+                vec![json!({
+                    "host": "shop.example.com",
+                    "ingress": "storefront",
+                    "service": "web",
+                })],
+            ),
+            expected(
+                "MATCH (s:Service)-[:Manages]->(:EndpointSlice)-[:ContainsEndpoint]->(:Endpoint)\
+                 -[:HasAddress]->(a:EndpointAddress)-[:IsAddressOf]->(p:Pod) \
+                 RETURN s.metadata.name AS service, a.address AS address, \
+                 p.metadata.name AS pod",
+                vec![json!({ "service": "web", "address": "10.0.0.11", "pod": "web-0" })],
+            ),
+        ],
+    }
+}
+
+/// Pod `db-0` claiming a volume bound to a persistent volume of storage class `fast-ssd`.
+fn storage_chain() -> GoldenFixture {
+    let mut snapshot = fixture_snapshot();
+    snapshot.namespaces = vec![namespace("data")];
+    snapshot.storage_classes = vec![Arc::new(StorageClass {
+        metadata: meta("fast-ssd", None, "storageclass-fast-ssd"),
+        provisioner: "ebs.csi.aws.com".to_string(),
+        ..Default::default()
+    })];
+    snapshot.persistent_volumes = vec![Arc::new(PersistentVolume {
+        metadata: meta("pv-0001", None, "persistentvolume-pv-0001"),
+        spec: Some(PersistentVolumeSpec {
+            storage_class_name: Some("fast-ssd".to_string()),
+            claim_ref: Some(ObjectReference {
+                kind: Some("PersistentVolumeClaim".to_string()),
+                name: Some("data-db-0".to_string()),
+                namespace: Some("data".to_string()),
+                uid: Some("persistentvolumeclaim-data-db-0".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })];
+    snapshot.persistent_volume_claims = vec![Arc::new(PersistentVolumeClaim {
+        metadata: meta("data-db-0", Some("data"), "persistentvolumeclaim-data-db-0"),
+        spec: Some(PersistentVolumeClaimSpec {
+            storage_class_name: Some("fast-ssd".to_string()),
+            volume_name: Some("pv-0001".to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })];
+    snapshot.pods = vec![Arc::new(Pod {
+        metadata: meta("db-0", Some("data"), "pod-db-0"),
+        spec: Some(PodSpec {
+            containers: vec![container("postgres")],
+            volumes: Some(vec![Volume {
+                name: "data".to_string(),
+                persistent_volume_claim: Some(PersistentVolumeClaimVolumeSource {
+                    claim_name: "data-db-0".to_string(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })];
+
+    GoldenFixture {
+        name: "storage-chain",
+        snapshot,
+        expected: vec![expected(
+            "MATCH (p:Pod)-[:ClaimsVolume]->(c:PersistentVolumeClaim)-[:BoundTo]->\
+             (v:PersistentVolume)-[:UsesStorageClass]->(s:StorageClass)-[:UsesProvisioner]->\
+             (pr:Provisioner) RETURN p.metadata.name AS pod, c.metadata.name AS claim, \
+             v.metadata.name AS volume, s.metadata.name AS storage_class, \
+             pr.name AS provisioner",
+            vec![json!({
+                "pod": "db-0",
+                "claim": "data-db-0",
+                "volume": "pv-0001",
+                "storage_class": "fast-ssd",
+                "provisioner": "ebs.csi.aws.com",
+            })],
+        )],
+    }
+}
+
+fn fixture_snapshot() -> ObservedClusterSnapshot {
+    ObservedClusterSnapshot::with_cluster(Cluster::new(
+        ObjectIdentifier {
+            uid: format!("Cluster:{FIXTURE_CLUSTER_NAME}"),
+            name: FIXTURE_CLUSTER_NAME.to_string(),
+            namespace: None,
+            resource_version: None,
+        },
+        "https://fixture.invalid",
+        Info::default(),
+    ))
+}
+
+fn expected(query: &str, rows: Vec<Value>) -> ExpectedQuery {
+    ExpectedQuery {
+        query: query.to_string(),
+        rows,
+    }
+}
+
+fn meta(name: &str, namespace: Option<&str>, uid: &str) -> ObjectMeta {
+    ObjectMeta {
+        name: Some(name.to_string()),
+        namespace: namespace.map(str::to_string),
+        uid: Some(uid.to_string()),
+        ..Default::default()
+    }
+}
+
+fn namespace(name: &str) -> Arc<Namespace> {
+    Arc::new(Namespace {
+        metadata: meta(name, None, &format!("namespace-{name}")),
+        ..Default::default()
+    })
+}
+
+fn owner(api_version: &str, kind: &str, name: &str, uid: &str) -> OwnerReference {
+    OwnerReference {
+        api_version: api_version.to_string(),
+        kind: kind.to_string(),
+        name: name.to_string(),
+        uid: uid.to_string(),
+        controller: Some(true),
+        ..Default::default()
+    }
+}
+
+fn container(name: &str) -> Container {
+    Container {
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,200 @@
+use std::sync::Arc;
+
+use k8s_openapi::api::events::v1::Event;
+use kube::ResourceExt;
+
+use crate::state_resolver::ObservedClusterSnapshot;
+
+/// Appended to an Event note that was cut.
+const ELLIPSIS: char = '…';
+
+/// Default for [`PayloadLimits::max_log_bytes`].
+pub const DEFAULT_MAX_LOG_BYTES: usize = 64 * 1024;
+
+/// Default for [`PayloadLimits::max_event_note_bytes`]: half the 1 KiB the API server allows on
+/// `events.k8s.io` notes, so the longest notes of a live cluster are shortened as well.
+pub const DEFAULT_MAX_EVENT_NOTE_BYTES: usize = 512;
+
+/// Caps on the attributes that grow with what workloads write rather than with the size of the
+/// cluster, so a chatty container cannot balloon the state and slow down every query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadLimits {
+    /// Longest log content kept, in bytes; the oldest lines are dropped beyond it.
+    pub max_log_bytes: usize,
+    /// Longest Event note kept, in bytes, including the `…` that marks a note whose end was
+    /// cut off.
+    pub max_event_note_bytes: usize,
+    /// Whether objects keep their `metadata.managedFields`, which no query needs.
+    pub keep_managed_fields: bool,
+}
+
+impl Default for PayloadLimits {
+    fn default() -> Self {
+        Self {
+            max_log_bytes: DEFAULT_MAX_LOG_BYTES,
+            max_event_note_bytes: DEFAULT_MAX_EVENT_NOTE_BYTES,
+            keep_managed_fields: false,
+        }
+    }
+}
+
+impl PayloadLimits {
+    /// Caps the Event notes of `snapshot` and drops the managed fields of its objects. Objects
+    /// within the limits are not copied, so they stay shared with the kube client.
+    pub fn apply(&self, snapshot: &mut ObservedClusterSnapshot) {
+        for event in &mut snapshot.events {
+            self.cap_event_note(event);
+        }
+        if self.keep_managed_fields {
+            return;
+        }
+        drop_managed_fields(&mut snapshot.namespaces);
+        drop_managed_fields(&mut snapshot.pods);
+        drop_managed_fields(&mut snapshot.deployments);
+        drop_managed_fields(&mut snapshot.stateful_sets);
+        drop_managed_fields(&mut snapshot.replica_sets);
+        drop_managed_fields(&mut snapshot.daemon_sets);
+        drop_managed_fields(&mut snapshot.jobs);
+        drop_managed_fields(&mut snapshot.ingresses);
+        drop_managed_fields(&mut snapshot.services);
+        drop_managed_fields(&mut snapshot.endpoint_slices);
+        drop_managed_fields(&mut snapshot.network_policies);
+        drop_managed_fields(&mut snapshot.config_maps);
+        drop_managed_fields(&mut snapshot.storage_classes);
+        drop_managed_fields(&mut snapshot.persistent_volumes);
+        drop_managed_fields(&mut snapshot.persistent_volume_claims);
+        drop_managed_fields(&mut snapshot.nodes);
+        drop_managed_fields(&mut snapshot.service_accounts);
+        drop_managed_fields(&mut snapshot.events);
+    }
+
+    /// The last `max_log_bytes` of `content`, starting at a line, behind a line that says how
+    /// much was dropped.
+    pub fn cap_logs(&self, content: String) -> String {
+        if content.len() <= self.max_log_bytes {
+            return content;
+        }
+        let mut start = content.len() - self.max_log_bytes;
+        while !content.is_char_boundary(start) {
+            start += 1;
+        }
+        if let Some(newline) = content[start..].find('\n') {
+            if start + newline + 1 < content.len() {
+                start += newline + 1;
+            }
+        }
+        format!("[{start} bytes truncated]\n{}", &content[start..])
+    }
+
+    fn cap_event_note(&self, event: &mut Arc<Event>) {
+        if event
+            .note
+            .as_ref()
+            .is_none_or(|note| note.len() <= self.max_event_note_bytes)
+        {
+            return;
+        }
+        if let Some(note) = Arc::make_mut(event).note.as_mut() {
+            let mut end = self
+                .max_event_note_bytes
+                .saturating_sub(ELLIPSIS.len_utf8());
+            while !note.is_char_boundary(end) {
+                end -= 1;
+            }
+            note.truncate(end);
+            note.push(ELLIPSIS);
+        }
+    }
+}
+
+fn drop_managed_fields<T: ResourceExt + Clone>(items: &mut [Arc<T>]) {
+    for item in items {
+        if !item.managed_fields().is_empty() {
+            Arc::make_mut(item).managed_fields_mut().clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::Pod;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ManagedFieldsEntry, ObjectMeta};
+
+    #[test]
+    fn logs_keep_their_last_lines() {
+        let limits = PayloadLimits {
+            max_log_bytes: 12,
+            ..Default::default()
+        };
+        assert_eq!(limits.cap_logs("short\n".to_string()), "short\n");
+        assert_eq!(
+            limits.cap_logs("first line\nsecond\nthird\n".to_string()),
+            "[18 bytes truncated]\nthird\n"
+        );
+        // A single long line is cut inside the line, never inside a character.
+        assert_eq!(
+            limits.cap_logs("ééééééééé".to_string()),
+            "[6 bytes truncated]\néééééé"
+        );
+    }
+
+    #[test]
+    fn event_notes_are_capped_and_managed_fields_dropped() {
+        let mut snapshot = ObservedClusterSnapshot::empty();
+        let managed = ObjectMeta {
+            managed_fields: Some(vec![ManagedFieldsEntry {
+                manager: Some("kubectl".to_string()),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+        let quiet = Arc::new(Event {
+            note: Some("Pulled".to_string()),
+            ..Default::default()
+        });
+        snapshot.events = vec![
+            Arc::new(Event {
+                note: Some("x".repeat(2000)),
+                ..Default::default()
+            }),
+            quiet.clone(),
+        ];
+        snapshot.pods = vec![Arc::new(Pod {
+            metadata: managed.clone(),
+            ..Default::default()
+        })];
+
+        PayloadLimits::default().apply(&mut snapshot);
+        let note = snapshot.events[0].note.as_ref().unwrap();
+        assert_eq!(note.len(), DEFAULT_MAX_EVENT_NOTE_BYTES);
+        assert!(note.ends_with('…'));
+        assert!(Arc::ptr_eq(&snapshot.events[1], &quiet));
+        assert!(snapshot.pods[0].managed_fields().is_empty());
+
+        snapshot.pods = vec![Arc::new(Pod {
+            metadata: managed,
+            ..Default::default()
+        })];
+        let keep = PayloadLimits {
+            keep_managed_fields: true,
+            ..Default::default()
+        };
+        keep.apply(&mut snapshot);
+        assert_eq!(snapshot.pods[0].managed_fields().len(), 1);
+    }
+
+    #[test]
+    fn capped_notes_fit_the_limit_with_their_ellipsis() {
+        let limits = PayloadLimits {
+            max_event_note_bytes: 8,
+            ..Default::default()
+        };
+        let mut event = Arc::new(Event {
+            note: Some("ééééé".to_string()),
+            ..Default::default()
+        });
+        limits.cap_event_note(&mut event);
+        assert_eq!(event.note.as_deref(), Some("éé…"));
+    }
+}
@@ -0,0 +1,433 @@
+use std::sync::Arc;
+
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::api::core::v1::{ConfigMap, EnvVar, Pod, PodSpec};
+use k8s_openapi::ByteString;
+use kube::ResourceExt;
+
+use crate::state_resolver::ObservedClusterSnapshot;
+
+/// Name fragments, matched case-insensitively, whose values are masked unless other patterns
+/// are configured.
+pub const DEFAULT_REDACTION_PATTERNS: &[&str] = &["password", "passwd", "secret", "token", "key"];
+
+/// What a masked value is replaced with.
+pub const REDACTED: &str = "<redacted>";
+
+/// Annotation in which `kubectl apply` keeps the whole applied manifest, values included.
+const LAST_APPLIED_CONFIGURATION: &str = "kubectl.kubernetes.io/last-applied-configuration";
+
+/// Which values the resolver masks before objects reach a graph backend and, through it, a
+/// prompt: ConfigMap values and container environment variables whose name contains one of
+/// the patterns, `name=value` and `name: value` lines with such a name inside other ConfigMap
+/// values, and the manifest `kubectl apply` leaves in an annotation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactionPolicy {
+    /// Lowercase.
+    patterns: Vec<String>,
+}
+
+impl Default for RedactionPolicy {
+    fn default() -> Self {
+        Self::new(DEFAULT_REDACTION_PATTERNS)
+    }
+}
+
+impl RedactionPolicy {
+    /// Masks the values of names that contain any of `patterns`; blank patterns are ignored.
+    pub fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            patterns: patterns
+                .into_iter()
+                .map(|pattern| pattern.as_ref().trim().to_lowercase())
+                .filter(|pattern| !pattern.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Masks nothing.
+    pub fn disabled() -> Self {
+        Self {
+            patterns: Vec::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.patterns.is_empty()
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Whether the value of a key or variable called `name` is masked.
+    pub fn matches(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.patterns
+            .iter()
+            .any(|pattern| name.contains(pattern.as_str()))
+    }
+
+    /// Masks the ConfigMaps, Pods and pod templates of `snapshot`. Objects with nothing to mask
+    /// are not copied, so they stay shared with the kube client.
+    pub fn redact_snapshot(&self, snapshot: &mut ObservedClusterSnapshot) {
+        self.redact_config_maps(&mut snapshot.config_maps);
+        self.redact_pod_specs(&mut snapshot.pods);
+        self.redact_pod_specs(&mut snapshot.deployments);
+        self.redact_pod_specs(&mut snapshot.stateful_sets);
+        self.redact_pod_specs(&mut snapshot.replica_sets);
+        self.redact_pod_specs(&mut snapshot.daemon_sets);
+        self.redact_pod_specs(&mut snapshot.jobs);
+    }
+
+    /// Masks the values of `config_maps`, for clients that write listings out before the
+    /// resolver sees them.
+    pub(crate) fn redact_config_maps(&self, config_maps: &mut [Arc<ConfigMap>]) {
+        if !self.is_enabled() {
+            return;
+        }
+        for config_map in config_maps {
+            self.redact_config_map(config_map);
+        }
+    }
+
+    fn redact_config_map(&self, config_map: &mut Arc<ConfigMap>) {
+        let data: Vec<(String, String)> = config_map
+            .data
+            .iter()
+            .flatten()
+            .filter_map(|(key, value)| Some((key.clone(), self.redact_value(key, value)?)))
+            .collect();
+        let binary_data: Vec<String> = config_map
+            .binary_data
+            .iter()
+            .flatten()
+            .filter(|(key, value)| self.matches(key) && value.0 != REDACTED.as_bytes())
+            .map(|(key, _)| key.clone())
+            .collect();
+        let last_applied = has_last_applied(&**config_map);
+        if data.is_empty() && binary_data.is_empty() && !last_applied {
+            return;
+        }
+
+        let config_map = Arc::make_mut(config_map);
+        if let Some(values) = config_map.data.as_mut() {
+            values.extend(data);
+        }
+        if let Some(values) = config_map.binary_data.as_mut() {
+            for key in binary_data {
+                values.insert(key, ByteString(REDACTED.as_bytes().to_vec()));
+            }
+        }
+        if last_applied {
+            mask_last_applied(config_map);
+        }
+    }
+
+    /// The masked `value` of ConfigMap key `key`, or `None` when nothing in it is masked.
+    fn redact_value(&self, key: &str, value: &str) -> Option<String> {
+        if self.matches(key) {
+            return (value != REDACTED).then(|| REDACTED.to_string());
+        }
+        let mut masked = false;
+        let lines: Vec<String> = value
+            .split('\n')
+            .map(|line| match self.redact_line(line) {
+                Some(line) => {
+                    masked = true;
+                    line
+                }
+                None => line.to_string(),
+            })
+            .collect();
+        masked.then(|| lines.join("\n"))
+    }
+
+    /// Masks the value of a `name=value` or `name: value` line whose name matches, such as a
+    /// line of a properties, dotenv or YAML file.
+    fn redact_line(&self, line: &str) -> Option<String> {
+        let separator = line.find(['=', ':'])?;
+        let name = line[..separator].trim();
+        let name = name.strip_prefix("export ").unwrap_or(name);
+        let name = name.trim_matches(|c| c == '"' || c == '\'');
+        if name.is_empty() || name.contains(char::is_whitespace) || !self.matches(name) {
+            return None;
+        }
+        let value = &line[separator + 1..];
+        if value.trim().is_empty() || value.trim() == REDACTED {
+            return None;
+        }
+        let indent = value.len() - value.trim_start().len();
+        Some(format!(
+            "{}{}{REDACTED}",
+            &line[..=separator],
+            &value[..indent]
+        ))
+    }
+
+    /// Masks the environment variables of Pods or of the pod templates of workloads.
+    pub(crate) fn redact_pod_specs<T: PodSpecOwner>(&self, items: &mut [Arc<T>]) {
+        if !self.is_enabled() {
+            return;
+        }
+        for item in items {
+            let env = item
+                .pod_spec()
+                .is_some_and(|spec| env_vars(spec).any(|var| self.masks_env_var(var)));
+            let last_applied = has_last_applied(&**item);
+            if !env && !last_applied {
+                continue;
+            }
+
+            let item = Arc::make_mut(item);
+            if let Some(spec) = item.pod_spec_mut() {
+                for var in env_vars_mut(spec) {
+                    if self.masks_env_var(var) {
+                        var.value = Some(REDACTED.to_string());
+                    }
+                }
+            }
+            if last_applied {
+                mask_last_applied(item);
+            }
+        }
+    }
+
+    /// Variables set from a Secret or ConfigMap reference carry no value to mask.
+    fn masks_env_var(&self, var: &EnvVar) -> bool {
+        self.matches(&var.name) && var.value.as_deref().is_some_and(|value| value != REDACTED)
+    }
+}
+
+fn has_last_applied<T: ResourceExt>(item: &T) -> bool {
+    item.annotations()
+        .get(LAST_APPLIED_CONFIGURATION)
+        .is_some_and(|value| value != REDACTED)
+}
+
+fn mask_last_applied<T: ResourceExt>(item: &mut T) {
+    item.annotations_mut()
+        .insert(LAST_APPLIED_CONFIGURATION.to_string(), REDACTED.to_string());
+}
+
+/// The environment variables of every container, init container and ephemeral container.
+fn env_vars(spec: &PodSpec) -> impl Iterator<Item = &EnvVar> {
+    let containers = spec
+        .containers
+        .iter()
+        .chain(spec.init_containers.iter().flatten())
+        .flat_map(|container| container.env.iter().flatten());
+    let ephemeral = spec
+        .ephemeral_containers
+        .iter()
+        .flatten()
+        .flat_map(|container| container.env.iter().flatten());
+    containers.chain(ephemeral)
+}
+
+fn env_vars_mut(spec: &mut PodSpec) -> impl Iterator<Item = &mut EnvVar> {
+    let containers = spec
+        .containers
+        .iter_mut()
+        .chain(spec.init_containers.iter_mut().flatten())
+        .flat_map(|container| container.env.iter_mut().flatten());
+    let ephemeral = spec
+        .ephemeral_containers
+        .iter_mut()
+        .flatten()
+        .flat_map(|container| container.env.iter_mut().flatten());
+    containers.chain(ephemeral)
+}
+
+/// A Pod, or a workload with the pod template its Pods are created from.
+pub(crate) trait PodSpecOwner: ResourceExt + Clone {
+    fn pod_spec(&self) -> Option<&PodSpec>;
+    fn pod_spec_mut(&mut self) -> Option<&mut PodSpec>;
+}
+
+impl PodSpecOwner for Pod {
+    fn pod_spec(&self) -> Option<&PodSpec> {
+        self.spec.as_ref()
+    }
+
+    fn pod_spec_mut(&mut self) -> Option<&mut PodSpec> {
+        self.spec.as_mut()
+    }
+}
+
+impl PodSpecOwner for ReplicaSet {
+    fn pod_spec(&self) -> Option<&PodSpec> {
+        self.spec.as_ref()?.template.as_ref()?.spec.as_ref()
+    }
+
+    fn pod_spec_mut(&mut self) -> Option<&mut PodSpec> {
+        self.spec.as_mut()?.template.as_mut()?.spec.as_mut()
+    }
+}
+
+macro_rules! pod_template_owner {
+    ($($kind:ty),*) => {
+        $(
+            impl PodSpecOwner for $kind {
+                fn pod_spec(&self) -> Option<&PodSpec> {
+                    self.spec.as_ref()?.template.spec.as_ref()
+                }
+
+                fn pod_spec_mut(&mut self) -> Option<&mut PodSpec> {
+                    self.spec.as_mut()?.template.spec.as_mut()
+                }
+            }
+        )*
+    };
+}
+
+pod_template_owner!(Deployment, StatefulSet, DaemonSet, Job);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::apps::v1::DeploymentSpec;
+    use k8s_openapi::api::core::v1::{Container, EnvVarSource, PodTemplateSpec, SecretKeySelector};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use std::collections::BTreeMap;
+
+    fn env(name: &str, value: &str) -> EnvVar {
+        EnvVar {
+            name: name.to_string(),
+            value: Some(value.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn pod_spec(env: Vec<EnvVar>) -> PodSpec {
+        PodSpec {
+            containers: vec![Container {
+                name: "app".to_string(),
+                env: Some(env),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn matching_config_map_values_and_env_vars_are_masked() {
+        let mut snapshot = ObservedClusterSnapshot::empty();
+        snapshot.config_maps = vec![Arc::new(ConfigMap {
+            metadata: ObjectMeta {
+                name: Some("app-config".to_string()),
+                annotations: Some(BTreeMap::from([(
+                    LAST_APPLIED_CONFIGURATION.to_string(),
+                    r#"{"data":{"DB_PASSWORD":"hunter2"}}"#.to_string(),
+                )])),
+                ..Default::default()
+            },
+            data: Some(BTreeMap::from([
+                ("DB_PASSWORD".to_string(), "hunter2".to_string()),
+                ("LOG_LEVEL".to_string(), "info".to_string()),
+                (
+                    "application.properties".to_string(),
+                    "db.url=postgres://db:5432\ndb.password = hunter2\n".to_string(),
+                ),
+            ])),
+            binary_data: Some(BTreeMap::from([(
+                "tls.key".to_string(),
+                ByteString(b"-----BEGIN".to_vec()),
+            )])),
+            ..Default::default()
+        })];
+        let untouched = Arc::new(Pod {
+            spec: Some(pod_spec(vec![env("LOG_LEVEL", "info")])),
+            ..Default::default()
+        });
+        snapshot.pods = vec![
+            Arc::new(Pod {
+                spec: Some(pod_spec(vec![
+                    env("API_TOKEN", "abc"),
+                    env("PORT", "8080"),
+                    EnvVar {
+                        name: "DB_PASSWORD".to_string(),
+                        value_from: Some(EnvVarSource {
+                            secret_key_ref: Some(SecretKeySelector {
+                                name: "db".to_string(),
+                                key: "password".to_string(),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                ])),
+                ..Default::default()
+            }),
+            untouched.clone(),
+        ];
+        snapshot.deployments = vec![Arc::new(Deployment {
+            spec: Some(DeploymentSpec {
+                template: PodTemplateSpec {
+                    spec: Some(pod_spec(vec![env("aws_secret_access_key", "xyz")])),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        })];
+
+        RedactionPolicy::default().redact_snapshot(&mut snapshot);
+
+        let config_map = &snapshot.config_maps[0];
+        let data = config_map.data.as_ref().unwrap();
+        assert_eq!(data["DB_PASSWORD"], REDACTED);
+        assert_eq!(data["LOG_LEVEL"], "info");
+        assert_eq!(
+            data["application.properties"],
+            "db.url=postgres://db:5432\ndb.password = <redacted>\n"
+        );
+        assert_eq!(
+            config_map.binary_data.as_ref().unwrap()["tls.key"].0,
+            REDACTED.as_bytes()
+        );
+        assert_eq!(
+            config_map.annotations()[LAST_APPLIED_CONFIGURATION],
+            REDACTED
+        );
+
+        let env = snapshot.pods[0].spec.as_ref().unwrap().containers[0]
+            .env
+            .as_ref()
+            .unwrap();
+        assert_eq!(env[0].value.as_deref(), Some(REDACTED));
+        assert_eq!(env[1].value.as_deref(), Some("8080"));
+        assert!(env[2].value.is_none() && env[2].value_from.is_some());
+        assert!(Arc::ptr_eq(&snapshot.pods[1], &untouched));
+
+        let template = snapshot.deployments[0].pod_spec().unwrap();
+        assert_eq!(
+            env_vars(template).next().unwrap().value.as_deref(),
+            Some(REDACTED)
+        );
+    }
+
+    #[test]
+    fn patterns_are_configurable() {
+        let policy = RedactionPolicy::new(["Cert", " "]);
+        assert_eq!(policy.patterns(), ["cert"]);
+        assert!(policy.matches("CLIENT_CERT"));
+        assert!(!policy.matches("DB_PASSWORD"));
+        assert!(!RedactionPolicy::disabled().is_enabled());
+
+        let mut snapshot = ObservedClusterSnapshot::empty();
+        let pod = Arc::new(Pod {
+            spec: Some(pod_spec(vec![env("DB_PASSWORD", "hunter2")])),
+            ..Default::default()
+        });
+        snapshot.pods = vec![pod.clone()];
+        RedactionPolicy::disabled().redact_snapshot(&mut snapshot);
+        assert!(Arc::ptr_eq(&snapshot.pods[0], &pod));
+    }
+}
@@ -1,6 +1,21 @@
 use crate::prelude::*;
+use crate::state_resolver::ObservedClusterSnapshot;
+use crate::types::Cluster;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::api::core::v1::{
+    ConfigMap, Namespace, Node, PersistentVolume, PersistentVolumeClaim, Pod, Service,
+    ServiceAccount,
+};
+use k8s_openapi::api::discovery::v1::EndpointSlice;
+use k8s_openapi::api::events::v1::Event;
+use k8s_openapi::api::networking::v1::{Ingress, NetworkPolicy};
+use k8s_openapi::api::storage::v1::StorageClass;
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -24,14 +39,31 @@ pub const SNAPSHOT_PERSISTENT_VOLUME_CLAIMS_FILE: &str = "persistentvolumeclaims
 pub const SNAPSHOT_NODES_FILE: &str = "nodes.json";
 pub const SNAPSHOT_SERVICE_ACCOUNTS_FILE: &str = "serviceaccounts.json";
 pub const SNAPSHOT_EVENTS_FILE: &str = "events.json";
+/// Optional: the cluster's custom resource definitions, read by `ariadne-tools` to describe
+/// custom kinds in the schema prompt.
+pub const SNAPSHOT_CUSTOM_RESOURCE_DEFINITIONS_FILE: &str = "customresourcedefinitions.json";
+/// Optional: the responses a `RecordingKubeClient` recorded, as `trace/<kind>/<call>.json`, where
+/// `<kind>` is the kind's file name without `.json` and `<call>` counts its listings from 1. A
+/// listing that failed is recorded as `trace/<kind>/<call>.error` instead.
+pub const SNAPSHOT_TRACE_DIR: &str = "trace";
+
+const TRACE_RESPONSE_EXTENSION: &str = "json";
+const TRACE_FAILURE_EXTENSION: &str = "error";
+
+/// A listing that failed while it was recorded, replayed as the same error.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceFailure {
+    pub message: String,
+    pub retriable: bool,
+}
 
 pub fn read_json_from_dir<T>(dir: &Path, filename: &str) -> Result<T>
 where
     T: DeserializeOwned,
 {
     let path = dir.join(filename);
-    let bytes = fs::read(&path)?;
-    Ok(serde_json::from_slice(&bytes)?)
+    let bytes = fs::read(&path).with_context(|| format!("reading {}", path.display()))?;
+    serde_json::from_slice(&bytes).with_context(|| format!("parsing {}", path.display()))
 }
 
 pub fn read_list_from_dir<T>(dir: &Path, filename: &str) -> Result<Vec<Arc<T>>>
@@ -60,17 +92,391 @@ where
     write_json_to_dir(dir, filename, &view)
 }
 
+fn trace_dir(dir: &Path, filename: &str) -> PathBuf {
+    dir.join(SNAPSHOT_TRACE_DIR)
+        .join(filename.trim_end_matches(".json"))
+}
+
+/// Records `items` as the response to the `call`th listing of the kind of `filename`.
+pub fn write_trace_to_dir<T>(
+    dir: &Path,
+    filename: &str,
+    call: usize,
+    items: &[Arc<T>],
+) -> Result<PathBuf>
+where
+    T: Serialize,
+{
+    let kind_dir = trace_dir(dir, filename);
+    fs::create_dir_all(&kind_dir)?;
+    write_list_to_dir(
+        &kind_dir,
+        &format!("{call:06}.{TRACE_RESPONSE_EXTENSION}"),
+        items,
+    )
+}
+
+/// Records `failure` as the outcome of the `call`th listing of the kind of `filename`.
+pub fn write_trace_failure_to_dir(
+    dir: &Path,
+    filename: &str,
+    call: usize,
+    failure: &TraceFailure,
+) -> Result<PathBuf> {
+    let kind_dir = trace_dir(dir, filename);
+    fs::create_dir_all(&kind_dir)?;
+    write_json_to_dir(
+        &kind_dir,
+        &format!("{call:06}.{TRACE_FAILURE_EXTENSION}"),
+        failure,
+    )
+}
+
+/// The trace files of the kind of `filename` with the given extension and their call numbers, in
+/// call order.
+fn trace_files(dir: &Path, filename: &str, extension: &str) -> Result<Vec<(usize, String)>> {
+    let kind_dir = trace_dir(dir, filename);
+    if !kind_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(&kind_dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(extension) {
+            continue;
+        }
+        let call = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<usize>().ok());
+        if let Some(call) = call {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            files.push((call, name.into_owned()));
+        }
+    }
+    files.sort_by_key(|(call, _)| *call);
+    Ok(files)
+}
+
+/// The recorded responses to listings of the kind of `filename` with their call numbers, in call
+/// order; empty when the directory has no trace of the kind.
+pub fn read_trace_from_dir<T>(dir: &Path, filename: &str) -> Result<Vec<(usize, Vec<Arc<T>>)>>
+where
+    T: DeserializeOwned,
+{
+    let kind_dir = trace_dir(dir, filename);
+    trace_files(dir, filename, TRACE_RESPONSE_EXTENSION)?
+        .into_iter()
+        .map(|(call, name)| Ok((call, read_list_from_dir(&kind_dir, &name)?)))
+        .collect()
+}
+
+/// The recorded failures of listings of the kind of `filename` with their call numbers, in call
+/// order; empty when none failed.
+pub fn read_trace_failures_from_dir(
+    dir: &Path,
+    filename: &str,
+) -> Result<Vec<(usize, TraceFailure)>> {
+    let kind_dir = trace_dir(dir, filename);
+    trace_files(dir, filename, TRACE_FAILURE_EXTENSION)?
+        .into_iter()
+        .map(|(call, name)| Ok((call, read_json_from_dir(&kind_dir, &name)?)))
+        .collect()
+}
+
+/// Writes every file of a snapshot directory, which must exist.
+pub fn write_snapshot_dir(dir: &Path, snapshot: &ObservedClusterSnapshot) -> Result<()> {
+    write_json_to_dir(dir, SNAPSHOT_CLUSTER_FILE, &snapshot.cluster)?;
+    write_list_to_dir(dir, SNAPSHOT_NAMESPACES_FILE, &snapshot.namespaces)?;
+    write_list_to_dir(dir, SNAPSHOT_PODS_FILE, &snapshot.pods)?;
+    write_list_to_dir(dir, SNAPSHOT_DEPLOYMENTS_FILE, &snapshot.deployments)?;
+    write_list_to_dir(dir, SNAPSHOT_STATEFUL_SETS_FILE, &snapshot.stateful_sets)?;
+    write_list_to_dir(dir, SNAPSHOT_REPLICA_SETS_FILE, &snapshot.replica_sets)?;
+    write_list_to_dir(dir, SNAPSHOT_DAEMON_SETS_FILE, &snapshot.daemon_sets)?;
+    write_list_to_dir(dir, SNAPSHOT_JOBS_FILE, &snapshot.jobs)?;
+    write_list_to_dir(dir, SNAPSHOT_INGRESSES_FILE, &snapshot.ingresses)?;
+    write_list_to_dir(dir, SNAPSHOT_SERVICES_FILE, &snapshot.services)?;
+    write_list_to_dir(
+        dir,
+        SNAPSHOT_ENDPOINT_SLICES_FILE,
+        &snapshot.endpoint_slices,
+    )?;
+    write_list_to_dir(
+        dir,
+        SNAPSHOT_NETWORK_POLICIES_FILE,
+        &snapshot.network_policies,
+    )?;
+    write_list_to_dir(dir, SNAPSHOT_CONFIG_MAPS_FILE, &snapshot.config_maps)?;
+    write_list_to_dir(
+        dir,
+        SNAPSHOT_STORAGE_CLASSES_FILE,
+        &snapshot.storage_classes,
+    )?;
+    write_list_to_dir(
+        dir,
+        SNAPSHOT_PERSISTENT_VOLUMES_FILE,
+        &snapshot.persistent_volumes,
+    )?;
+    write_list_to_dir(
+        dir,
+        SNAPSHOT_PERSISTENT_VOLUME_CLAIMS_FILE,
+        &snapshot.persistent_volume_claims,
+    )?;
+    write_list_to_dir(dir, SNAPSHOT_NODES_FILE, &snapshot.nodes)?;
+    write_list_to_dir(
+        dir,
+        SNAPSHOT_SERVICE_ACCOUNTS_FILE,
+        &snapshot.service_accounts,
+    )?;
+    write_list_to_dir(dir, SNAPSHOT_EVENTS_FILE, &snapshot.events)?;
+    Ok(())
+}
+
+/// Checks that an item of a snapshot file parses as the file's kind.
+type ItemCheck = fn(&Value) -> std::result::Result<(), serde_json::Error>;
+
+fn parses_as<T: DeserializeOwned>(item: &Value) -> std::result::Result<(), serde_json::Error> {
+    T::deserialize(item).map(|_| ())
+}
+
+/// The list files of a snapshot directory in the order the resolver adds their objects, so a
+/// later file's object replaces an earlier one with the same uid.
+const SNAPSHOT_LIST_FILES: &[(&str, ItemCheck)] = &[
+    (SNAPSHOT_NAMESPACES_FILE, parses_as::<Namespace>),
+    (SNAPSHOT_PODS_FILE, parses_as::<Pod>),
+    (SNAPSHOT_DEPLOYMENTS_FILE, parses_as::<Deployment>),
+    (SNAPSHOT_STATEFUL_SETS_FILE, parses_as::<StatefulSet>),
+    (SNAPSHOT_REPLICA_SETS_FILE, parses_as::<ReplicaSet>),
+    (SNAPSHOT_DAEMON_SETS_FILE, parses_as::<DaemonSet>),
+    (SNAPSHOT_JOBS_FILE, parses_as::<Job>),
+    (SNAPSHOT_INGRESSES_FILE, parses_as::<Ingress>),
+    (SNAPSHOT_SERVICES_FILE, parses_as::<Service>),
+    (SNAPSHOT_ENDPOINT_SLICES_FILE, parses_as::<EndpointSlice>),
+    (SNAPSHOT_NETWORK_POLICIES_FILE, parses_as::<NetworkPolicy>),
+    (SNAPSHOT_CONFIG_MAPS_FILE, parses_as::<ConfigMap>),
+    (SNAPSHOT_STORAGE_CLASSES_FILE, parses_as::<StorageClass>),
+    (
+        SNAPSHOT_PERSISTENT_VOLUMES_FILE,
+        parses_as::<PersistentVolume>,
+    ),
+    (
+        SNAPSHOT_PERSISTENT_VOLUME_CLAIMS_FILE,
+        parses_as::<PersistentVolumeClaim>,
+    ),
+    (SNAPSHOT_NODES_FILE, parses_as::<Node>),
+    (SNAPSHOT_SERVICE_ACCOUNTS_FILE, parses_as::<ServiceAccount>),
+    (SNAPSHOT_EVENTS_FILE, parses_as::<Event>),
+];
+
+/// Something in a snapshot directory that `SnapshotKubeClient::from_dir` or the resolver fails
+/// on or silently drops.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "issue", rename_all = "snake_case")]
+pub enum SnapshotIssue {
+    /// A required file does not exist or cannot be read; loading fails.
+    MissingFile { file: String, error: String },
+    /// A file is not JSON; loading fails.
+    MalformedJson {
+        file: String,
+        line: usize,
+        column: usize,
+        error: String,
+    },
+    /// A file is JSON but not a list of its kind, or one of its items is not an object of that
+    /// kind; loading fails.
+    WrongShape {
+        file: String,
+        index: Option<usize>,
+        error: String,
+    },
+    /// An object has no `metadata.uid` or `metadata.name`; resolving panics.
+    MissingIdentity {
+        file: String,
+        index: usize,
+        field: &'static str,
+    },
+    /// Objects share a uid; the graph keeps only the one read last.
+    DuplicateUid {
+        uid: String,
+        kept: String,
+        dropped: String,
+    },
+}
+
+impl fmt::Display for SnapshotIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotIssue::MissingFile { file, error } => {
+                write!(f, "{file}: cannot be read, loading fails: {error}")
+            }
+            SnapshotIssue::MalformedJson {
+                file,
+                line,
+                column,
+                error,
+            } => write!(
+                f,
+                "{file}:{line}:{column}: malformed JSON, loading fails: {error}"
+            ),
+            SnapshotIssue::WrongShape {
+                file,
+                index: Some(index),
+                error,
+            } => write!(f, "{file}[{index}]: wrong shape, loading fails: {error}"),
+            SnapshotIssue::WrongShape {
+                file,
+                index: None,
+                error,
+            } => write!(f, "{file}: wrong shape, loading fails: {error}"),
+            SnapshotIssue::MissingIdentity { file, index, field } => {
+                write!(f, "{file}[{index}]: no metadata.{field}, resolving panics")
+            }
+            SnapshotIssue::DuplicateUid { uid, kept, dropped } => {
+                write!(f, "uid {uid}: {kept} replaces {dropped}")
+            }
+        }
+    }
+}
+
+/// The issues of a snapshot directory, in file order. Empty when it loads as written.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SnapshotReport {
+    pub issues: Vec<SnapshotIssue>,
+}
+
+impl SnapshotReport {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for SnapshotReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.issues.is_empty() {
+            return writeln!(f, "Snapshot is valid");
+        }
+        for issue in &self.issues {
+            writeln!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads every file of a snapshot directory the way `SnapshotKubeClient::from_dir` does and
+/// reports each missing file, malformed or misshapen document, object without a uid or name
+/// and uid shared by several objects, instead of stopping at the first error.
+pub fn validate_snapshot_dir(dir: &Path) -> SnapshotReport {
+    let mut issues = Vec::new();
+    // Where each uid was seen last, as `file[index]`.
+    let mut seen: HashMap<String, String> = HashMap::new();
+
+    if let Some(cluster) = read_value(dir, SNAPSHOT_CLUSTER_FILE, &mut issues) {
+        match parses_as::<Cluster>(&cluster) {
+            Ok(()) => check_identity(&cluster, SNAPSHOT_CLUSTER_FILE, 0, &mut seen, &mut issues),
+            Err(error) => issues.push(SnapshotIssue::WrongShape {
+                file: SNAPSHOT_CLUSTER_FILE.to_string(),
+                index: None,
+                error: error.to_string(),
+            }),
+        }
+    }
+
+    for (file, check) in SNAPSHOT_LIST_FILES {
+        let Some(value) = read_value(dir, file, &mut issues) else {
+            continue;
+        };
+        let Value::Array(items) = value else {
+            issues.push(SnapshotIssue::WrongShape {
+                file: file.to_string(),
+                index: None,
+                error: "expected a JSON array".to_string(),
+            });
+            continue;
+        };
+        for (index, item) in items.iter().enumerate() {
+            if let Err(error) = check(item) {
+                issues.push(SnapshotIssue::WrongShape {
+                    file: file.to_string(),
+                    index: Some(index),
+                    error: error.to_string(),
+                });
+            } else {
+                check_identity(item, file, index, &mut seen, &mut issues);
+            }
+        }
+    }
+    SnapshotReport { issues }
+}
+
+fn read_value(dir: &Path, file: &str, issues: &mut Vec<SnapshotIssue>) -> Option<Value> {
+    let bytes = match fs::read(dir.join(file)) {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            issues.push(SnapshotIssue::MissingFile {
+                file: file.to_string(),
+                error: error.to_string(),
+            });
+            return None;
+        }
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            issues.push(SnapshotIssue::MalformedJson {
+                file: file.to_string(),
+                line: error.line(),
+                column: error.column(),
+                error: error.to_string(),
+            });
+            None
+        }
+    }
+}
+
+/// Records a missing uid or name of the object at `file[index]` and a uid it shares with an
+/// object read before it.
+fn check_identity(
+    object: &Value,
+    file: &str,
+    index: usize,
+    seen: &mut HashMap<String, String>,
+    issues: &mut Vec<SnapshotIssue>,
+) {
+    for field in ["uid", "name"] {
+        if object["metadata"][field].as_str().is_none() {
+            issues.push(SnapshotIssue::MissingIdentity {
+                file: file.to_string(),
+                index,
+                field,
+            });
+        }
+    }
+    if let Some(uid) = object["metadata"]["uid"].as_str() {
+        let location = format!("{file}[{index}]");
+        if let Some(dropped) = seen.insert(uid.to_string(), location.clone()) {
+            issues.push(SnapshotIssue::DuplicateUid {
+                uid: uid.to_string(),
+                kept: location,
+                dropped,
+            });
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::kube_client::{KubeClient, SnapshotKubeClient};
+    use crate::kube_client::{
+        KubeClient, RecordingKubeClient, RecordingOptions, ReplayKubeClient, SnapshotKubeClient,
+    };
+    use crate::redaction::REDACTED;
     use crate::state_resolver::ClusterStateResolver;
     use crate::types::{Cluster, ObjectIdentifier};
     use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
     use k8s_openapi::api::batch::v1::Job;
     use k8s_openapi::api::core::v1::{
-        ConfigMap, Namespace, Node, PersistentVolume, PersistentVolumeClaim, Pod, Service,
-        ServiceAccount,
+        ConfigMap, Container, EnvVar, Namespace, Node, PersistentVolume, PersistentVolumeClaim,
+        Pod, PodSpec, Service, ServiceAccount,
     };
     use k8s_openapi::api::discovery::v1::EndpointSlice;
     use k8s_openapi::api::events::v1::Event;
@@ -78,6 +484,7 @@ mod tests {
     use k8s_openapi::api::storage::v1::StorageClass;
     use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
     use k8s_openapi::apimachinery::pkg::version::Info;
+    use std::collections::BTreeMap;
     use std::fs;
     use std::path::PathBuf;
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -206,4 +613,300 @@ mod tests {
 
         Ok(())
     }
+
+    fn traced_namespace(name: &str) -> Arc<Namespace> {
+        Arc::new(Namespace {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                uid: Some(format!("ns-{name}")),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn recorded_traces_replay_in_call_order() -> Result<()> {
+        let seed = TempDir::new("ariadne_trace_seed");
+        let seed_dir = seed.path.as_path();
+        write_json_to_dir(seed_dir, SNAPSHOT_CLUSTER_FILE, &test_cluster())?;
+        write_empty_lists(seed_dir)?;
+        write_trace_to_dir(
+            seed_dir,
+            SNAPSHOT_NAMESPACES_FILE,
+            1,
+            &[traced_namespace("a")],
+        )?;
+        write_trace_to_dir(
+            seed_dir,
+            SNAPSHOT_NAMESPACES_FILE,
+            3,
+            &[traced_namespace("a"), traced_namespace("b")],
+        )?;
+
+        let recorded = TempDir::new("ariadne_trace_recorded");
+        let client = RecordingKubeClient::new(
+            "test",
+            Box::new(ReplayKubeClient::from_dir(seed_dir)?),
+            &recorded.path,
+            RecordingOptions::default(),
+        )
+        .await?;
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            seen.push(client.get_namespaces().await?.len());
+        }
+        assert_eq!(seen, vec![1, 1, 2, 2]);
+        client.get_pods().await?;
+
+        let trace: Vec<(usize, Vec<Arc<Namespace>>)> =
+            read_trace_from_dir(&recorded.path, SNAPSHOT_NAMESPACES_FILE)?;
+        let calls: Vec<usize> = trace.iter().map(|(call, _)| *call).collect();
+        assert_eq!(calls, vec![1, 3]);
+        let latest: Vec<Namespace> = read_json_from_dir(&recorded.path, SNAPSHOT_NAMESPACES_FILE)?;
+        assert_eq!(latest.len(), 2);
+
+        let replay = ReplayKubeClient::from_dir(&recorded.path)?;
+        let mut replayed = Vec::new();
+        for _ in 0..4 {
+            replayed.push(replay.get_namespaces().await?.len());
+        }
+        assert_eq!(replayed, seen);
+        assert!(replay.get_deployments().await?.is_empty());
+        assert_eq!(replay.get_cluster_url().await?, "https://example.invalid");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn failed_listings_replay_on_the_same_call() -> Result<()> {
+        let seed = TempDir::new("ariadne_trace_failure_seed");
+        let seed_dir = seed.path.as_path();
+        write_json_to_dir(seed_dir, SNAPSHOT_CLUSTER_FILE, &test_cluster())?;
+        write_empty_lists(seed_dir)?;
+        write_trace_to_dir(
+            seed_dir,
+            SNAPSHOT_NAMESPACES_FILE,
+            1,
+            &[traced_namespace("a")],
+        )?;
+        let failure = TraceFailure {
+            message: "the server is currently unable to handle the request".to_string(),
+            retriable: true,
+        };
+        write_trace_failure_to_dir(seed_dir, SNAPSHOT_NAMESPACES_FILE, 2, &failure)?;
+        write_trace_to_dir(
+            seed_dir,
+            SNAPSHOT_NAMESPACES_FILE,
+            3,
+            &[traced_namespace("a"), traced_namespace("b")],
+        )?;
+
+        let recorded = TempDir::new("ariadne_trace_failure_recorded");
+        let client = RecordingKubeClient::new(
+            "test",
+            Box::new(ReplayKubeClient::from_dir(seed_dir)?),
+            &recorded.path,
+            RecordingOptions::default(),
+        )
+        .await?;
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            seen.push(client.get_namespaces().await.ok().map(|items| items.len()));
+        }
+        assert_eq!(seen, vec![Some(1), None, Some(2), Some(2)]);
+
+        let failures = read_trace_failures_from_dir(&recorded.path, SNAPSHOT_NAMESPACES_FILE)?;
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, 2);
+        assert!(failures[0].1.retriable);
+        let trace: Vec<(usize, Vec<Arc<Namespace>>)> =
+            read_trace_from_dir(&recorded.path, SNAPSHOT_NAMESPACES_FILE)?;
+        let calls: Vec<usize> = trace.iter().map(|(call, _)| *call).collect();
+        assert_eq!(calls, vec![1, 3]);
+
+        let replay = ReplayKubeClient::from_dir(&recorded.path)?;
+        assert_eq!(replay.get_namespaces().await?.len(), 1);
+        let err = replay.get_namespaces().await.unwrap_err();
+        assert!(err.is_retriable());
+        assert_eq!(replay.get_namespaces().await?.len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn traces_stop_growing_at_their_cap() -> Result<()> {
+        let seed = TempDir::new("ariadne_trace_cap_seed");
+        let seed_dir = seed.path.as_path();
+        write_json_to_dir(seed_dir, SNAPSHOT_CLUSTER_FILE, &test_cluster())?;
+        write_empty_lists(seed_dir)?;
+        write_trace_to_dir(
+            seed_dir,
+            SNAPSHOT_NAMESPACES_FILE,
+            1,
+            &[traced_namespace("a")],
+        )?;
+        write_trace_to_dir(
+            seed_dir,
+            SNAPSHOT_NAMESPACES_FILE,
+            2,
+            &[traced_namespace("a"), traced_namespace("b")],
+        )?;
+
+        let recorded = TempDir::new("ariadne_trace_cap_recorded");
+        let client = RecordingKubeClient::new(
+            "test",
+            Box::new(ReplayKubeClient::from_dir(seed_dir)?),
+            &recorded.path,
+            RecordingOptions {
+                max_trace_bytes: 1,
+                ..RecordingOptions::default()
+            },
+        )
+        .await?;
+        client.get_namespaces().await?;
+        client.get_namespaces().await?;
+
+        let trace: Vec<(usize, Vec<Arc<Namespace>>)> =
+            read_trace_from_dir(&recorded.path, SNAPSHOT_NAMESPACES_FILE)?;
+        let calls: Vec<usize> = trace.iter().map(|(call, _)| *call).collect();
+        assert_eq!(calls, vec![1]);
+        let latest: Vec<Namespace> = read_json_from_dir(&recorded.path, SNAPSHOT_NAMESPACES_FILE)?;
+        assert_eq!(latest.len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn recordings_hold_masked_values() -> Result<()> {
+        let seed = TempDir::new("ariadne_redacted_seed");
+        let seed_dir = seed.path.as_path();
+        write_json_to_dir(seed_dir, SNAPSHOT_CLUSTER_FILE, &test_cluster())?;
+        write_empty_lists(seed_dir)?;
+        let config_map = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some("settings".to_string()),
+                uid: Some("cm-uid".to_string()),
+                ..Default::default()
+            },
+            data: Some(BTreeMap::from([(
+                "DB_PASSWORD".to_string(),
+                "hunter2".to_string(),
+            )])),
+            ..Default::default()
+        };
+        write_list_to_dir(seed_dir, SNAPSHOT_CONFIG_MAPS_FILE, &[Arc::new(config_map)])?;
+        let pod = Pod {
+            metadata: ObjectMeta {
+                name: Some("web".to_string()),
+                uid: Some("pod-uid".to_string()),
+                ..Default::default()
+            },
+            spec: Some(PodSpec {
+                containers: vec![Container {
+                    name: "web".to_string(),
+                    env: Some(vec![EnvVar {
+                        name: "DB_PASSWORD".to_string(),
+                        value: Some("hunter2".to_string()),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        write_list_to_dir(seed_dir, SNAPSHOT_PODS_FILE, &[Arc::new(pod)])?;
+
+        let recorded = TempDir::new("ariadne_redacted_recorded");
+        let client = RecordingKubeClient::new(
+            "test",
+            Box::new(SnapshotKubeClient::from_dir(seed_dir)?),
+            &recorded.path,
+            RecordingOptions::default(),
+        )
+        .await?;
+        let config_maps = client.get_config_maps().await?;
+        assert_eq!(
+            config_maps[0].data.as_ref().unwrap()["DB_PASSWORD"],
+            REDACTED
+        );
+        client.get_pods().await?;
+
+        for file in [SNAPSHOT_CONFIG_MAPS_FILE, SNAPSHOT_PODS_FILE] {
+            let written = fs::read_to_string(recorded.path.join(file))?;
+            assert!(written.contains(REDACTED), "{file} is not masked");
+            assert!(!written.contains("hunter2"), "{file} leaks the password");
+        }
+        let pods: Vec<(usize, Vec<Arc<Pod>>)> =
+            read_trace_from_dir(&recorded.path, SNAPSHOT_PODS_FILE)?;
+        let env = pods[0].1[0].spec.as_ref().unwrap().containers[0]
+            .env
+            .as_ref()
+            .unwrap();
+        assert_eq!(env[0].value.as_deref(), Some(REDACTED));
+        Ok(())
+    }
+
+    #[test]
+    fn validation_reports_what_loading_would_fail_on_or_drop() -> Result<()> {
+        let temp = TempDir::new("ariadne_snapshot_validate");
+        let dir = temp.path.as_path();
+        write_json_to_dir(dir, SNAPSHOT_CLUSTER_FILE, &test_cluster())?;
+        write_empty_lists(dir)?;
+        assert!(validate_snapshot_dir(dir).is_valid());
+
+        let meta = |name: &str, uid: Option<&str>| ObjectMeta {
+            name: Some(name.to_string()),
+            uid: uid.map(str::to_string),
+            ..Default::default()
+        };
+        let namespace = Namespace {
+            metadata: meta("default", Some("shared-uid")),
+            ..Default::default()
+        };
+        write_list_to_dir(dir, SNAPSHOT_NAMESPACES_FILE, &[Arc::new(namespace)])?;
+        let config_map = ConfigMap {
+            metadata: meta("settings", Some("shared-uid")),
+            ..Default::default()
+        };
+        write_list_to_dir(dir, SNAPSHOT_CONFIG_MAPS_FILE, &[Arc::new(config_map)])?;
+        let service = Service {
+            metadata: meta("web", None),
+            ..Default::default()
+        };
+        write_list_to_dir(dir, SNAPSHOT_SERVICES_FILE, &[Arc::new(service)])?;
+        fs::write(dir.join(SNAPSHOT_PODS_FILE), "[\n  {\"metadata\": }\n]")?;
+        fs::write(dir.join(SNAPSHOT_JOBS_FILE), "{}")?;
+        fs::remove_file(dir.join(SNAPSHOT_NODES_FILE))?;
+
+        let report = validate_snapshot_dir(dir);
+        let files: Vec<String> = report
+            .issues
+            .iter()
+            .map(|issue| issue.to_string().split(':').next().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                "pods.json",
+                "jobs.json",
+                "services.json[0]",
+                "uid shared-uid",
+                "nodes.json",
+            ]
+        );
+        assert!(matches!(
+            &report.issues[0],
+            SnapshotIssue::MalformedJson { line: 2, .. }
+        ));
+        assert_eq!(
+            report.issues[3],
+            SnapshotIssue::DuplicateUid {
+                uid: "shared-uid".to_string(),
+                kept: "configmaps.json[0]".to_string(),
+                dropped: "namespaces.json[0]".to_string(),
+            }
+        );
+        assert!(SnapshotKubeClient::from_dir(dir).is_err());
+
+        Ok(())
+    }
 }
@@ -1,18 +1,15 @@
+use crate::errors::{AriadneError, ErrorClass};
 use crate::prelude::*;
 
 use crate::create_generic_object;
+use crate::entity_index::{EntityIndex, SharedEntityIndex};
 use crate::graph_backend::GraphBackend;
+use crate::id_gen::logical_uid;
 use crate::kube_client::{CachedKubeClient, KubeClient};
-use crate::snapshot::{
-    write_json_to_dir, write_list_to_dir, SNAPSHOT_CLUSTER_FILE, SNAPSHOT_CONFIG_MAPS_FILE,
-    SNAPSHOT_DAEMON_SETS_FILE, SNAPSHOT_DEPLOYMENTS_FILE, SNAPSHOT_ENDPOINT_SLICES_FILE,
-    SNAPSHOT_EVENTS_FILE, SNAPSHOT_INGRESSES_FILE, SNAPSHOT_JOBS_FILE, SNAPSHOT_NAMESPACES_FILE,
-    SNAPSHOT_NETWORK_POLICIES_FILE, SNAPSHOT_NODES_FILE, SNAPSHOT_PERSISTENT_VOLUMES_FILE,
-    SNAPSHOT_PERSISTENT_VOLUME_CLAIMS_FILE, SNAPSHOT_PODS_FILE, SNAPSHOT_REPLICA_SETS_FILE,
-    SNAPSHOT_SERVICES_FILE, SNAPSHOT_SERVICE_ACCOUNTS_FILE, SNAPSHOT_STATEFUL_SETS_FILE,
-    SNAPSHOT_STORAGE_CLASSES_FILE,
-};
-use crate::state::ClusterState;
+use crate::payload_limits::PayloadLimits;
+use crate::redaction::RedactionPolicy;
+use crate::snapshot::write_snapshot_dir;
+use crate::state::{ClusterState, SharedClusterState};
 use crate::types::*;
 use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
 use k8s_openapi::api::batch::v1::Job;
@@ -34,7 +31,8 @@ use std::fmt::Debug;
 use std::fs;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
 use tokio::task::JoinHandle;
 use tokio::time::sleep;
 use tokio_util::sync::CancellationToken;
@@ -43,11 +41,75 @@ use tracing::{info, trace, warn};
 type IngressDerived = (Vec<Arc<Host>>, Vec<Arc<IngressServiceBackend>>);
 type EndpointSliceDerived = (Vec<Arc<Endpoint>>, Vec<Arc<EndpointAddress>>);
 
+/// How often the diff loop re-lists the cluster unless overridden with
+/// [`ClusterStateResolver::set_poll_interval`].
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// After this many failed iterations in a row the retry delay stops doubling.
+const MAX_BACKOFF_DOUBLINGS: u32 = 4;
+
+/// How often a kind is listed before its listing counts as failed.
+const LIST_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry of a failed listing; it doubles with every further retry.
+const LIST_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// A kind the last listing could not read, even after retries. The rest of the cluster is
+/// resolved without it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListingFailure {
+    pub kind: ResourceType,
+    pub error: String,
+    /// Whether the objects of an earlier listing stand in for the kind; without one the kind is
+    /// missing from the state.
+    pub stale: bool,
+}
+
+/// How the diff loop is doing, published after every iteration. A failed iteration (the kube
+/// API or the graph backend returned an error) is retried with a growing delay instead of
+/// stopping the loop.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolverHealth {
+    /// When the cluster was last listed and applied to the backend.
+    pub last_success: Instant,
+    /// Iterations that failed since `last_success`.
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    /// What `last_error` came from, such as the Kubernetes API or the graph backend.
+    pub last_error_class: Option<ErrorClass>,
+}
+
+impl ResolverHealth {
+    fn new() -> Self {
+        Self {
+            last_success: Instant::now(),
+            consecutive_failures: 0,
+            last_error: None,
+            last_error_class: None,
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.consecutive_failures == 0
+    }
+}
+
+/// How the resolver prepares every listing before it becomes the state.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResolverOptions {
+    pub redaction: RedactionPolicy,
+    pub payload_limits: PayloadLimits,
+}
+
 pub struct ClusterStateResolver {
     cluster: Cluster,
     kube_client: Arc<Box<dyn KubeClient>>,
     last_snapshot: Arc<Mutex<AugmentedClusterSnapshot>>,
-    last_state: Arc<Mutex<ClusterState>>,
+    last_state: SharedClusterState,
+    entity_index: SharedEntityIndex,
+    poll_interval: Duration,
+    health: watch::Sender<ResolverHealth>,
+    options: Arc<ResolverOptions>,
     #[allow(unused)]
     should_export_snapshot: bool,
 }
@@ -73,10 +135,13 @@ pub struct ObservedClusterSnapshot {
     pub nodes: Vec<Arc<Node>>,
     pub service_accounts: Vec<Arc<ServiceAccount>>,
     pub events: Vec<Arc<Event>>,
+    /// Kinds this listing could not read.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub listing_failures: Vec<ListingFailure>,
 }
 
 impl ObservedClusterSnapshot {
-    fn empty() -> Self {
+    pub(crate) fn empty() -> Self {
         ObservedClusterSnapshot {
             cluster: Cluster {
                 metadata: Default::default(),
@@ -102,10 +167,87 @@ impl ObservedClusterSnapshot {
             nodes: vec![],
             service_accounts: vec![],
             events: vec![],
+            listing_failures: vec![],
+        }
+    }
+
+    /// A snapshot of `cluster` without any objects.
+    pub fn with_cluster(cluster: Cluster) -> Self {
+        ObservedClusterSnapshot {
+            cluster,
+            ..Self::empty()
         }
     }
 }
 
+/// Lists the kinds of one snapshot, retrying each on its own and keeping the failures.
+#[derive(Default)]
+struct KindLister {
+    /// Failures of the previous snapshot, which tell whether its objects of a kind were listed.
+    previous_failures: Vec<ListingFailure>,
+    failures: Vec<ListingFailure>,
+    /// Error of the first kind that failed.
+    first_error: Option<AriadneError>,
+    /// Kinds listed so far, whether they failed or not.
+    kinds: usize,
+}
+
+impl KindLister {
+    /// Lists `kind`, retrying attempts that failed with a retriable error with a growing delay;
+    /// errors that will not go away, such as a forbidden kind, fail it at once. When the listing
+    /// fails for good the kind keeps the objects of the `previous` listing, if any, and is
+    /// recorded as failed: stale when the previous snapshot had listed it, missing otherwise.
+    async fn list<T, F, Fut>(
+        &mut self,
+        kind: ResourceType,
+        previous: Option<&Vec<Arc<T>>>,
+        list: F,
+    ) -> Vec<Arc<T>>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<Arc<T>>>>,
+    {
+        self.kinds += 1;
+        let mut delay = LIST_RETRY_DELAY;
+        for attempt in 1..=LIST_ATTEMPTS {
+            match list().await {
+                Ok(items) => return items,
+                Err(err) if attempt < LIST_ATTEMPTS && err.is_retriable() => {
+                    warn!(
+                        "Listing {kind} failed (attempt {attempt}), retrying in {delay:?}: {err}"
+                    );
+                    sleep(delay).await;
+                    delay *= 2;
+                }
+                Err(err) => {
+                    let stale = previous.is_some() && self.listed_before(&kind);
+                    warn!(
+                        "Listing {kind} failed, resolving without it ({}): {err}",
+                        if stale { "stale" } else { "missing" }
+                    );
+                    self.failures.push(ListingFailure {
+                        kind,
+                        error: err.to_string(),
+                        stale,
+                    });
+                    self.first_error.get_or_insert(err);
+                    break;
+                }
+            }
+        }
+        previous.cloned().unwrap_or_default()
+    }
+
+    /// Whether the previous snapshot holds objects of `kind` from a successful listing, its own
+    /// or a stale one.
+    fn listed_before(&self, kind: &ResourceType) -> bool {
+        self.previous_failures
+            .iter()
+            .find(|failure| &failure.kind == kind)
+            .is_none_or(|failure| failure.stale)
+    }
+}
+
 pub struct DerivedClusterSnapshot {
     pub containers: Vec<Arc<Container>>,
     pub hosts: Vec<Arc<Host>>,
@@ -140,9 +282,20 @@ impl ClusterStateResolver {
         Self::new_with_kube_client(cluster_name, Box::new(kube_client)).await
     }
 
+    /// Resolves the cluster `kube_client` reads with the default [`ResolverOptions`].
     pub async fn new_with_kube_client(
         cluster_name: String,
         kube_client: Box<dyn KubeClient>,
+    ) -> Result<Self> {
+        Self::new_with_options(cluster_name, kube_client, ResolverOptions::default()).await
+    }
+
+    /// Resolves the cluster `kube_client` reads, masking and capping every listing as
+    /// `options` say before it becomes the state.
+    pub async fn new_with_options(
+        cluster_name: String,
+        kube_client: Box<dyn KubeClient>,
+        options: ResolverOptions,
     ) -> Result<Self> {
         let cluster_url = kube_client.get_cluster_url().await?;
         let info = kube_client.apiserver_version().await?;
@@ -157,14 +310,21 @@ impl ClusterStateResolver {
             info,
         );
         let kube_client: Arc<Box<dyn KubeClient>> = Arc::new(kube_client);
-        let augmented = Self::get_augmented_snapshot(&cluster, kube_client.clone()).await?;
+        let options = Arc::new(options);
+        let augmented =
+            Self::get_augmented_snapshot(&cluster, kube_client.clone(), &options, None).await?;
 
-        let last_state = Arc::new(Mutex::new(Self::create_state(&augmented)));
+        let state = Self::create_state(&augmented);
+        let entity_index = Arc::new(Mutex::new(EntityIndex::build(&state)));
         Ok(ClusterStateResolver {
             cluster,
             kube_client,
             last_snapshot: Arc::new(Mutex::new(augmented)),
-            last_state,
+            last_state: SharedClusterState::new(state),
+            entity_index,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            health: watch::channel(ResolverHealth::new()).0,
+            options,
             should_export_snapshot: false,
         })
     }
@@ -172,9 +332,13 @@ impl ClusterStateResolver {
     async fn get_augmented_snapshot(
         cluster: &Cluster,
         kube_client: Arc<Box<dyn KubeClient>>,
+        options: &ResolverOptions,
+        previous: Option<&ObservedClusterSnapshot>,
     ) -> Result<AugmentedClusterSnapshot> {
-        let last_snapshot =
-            Self::get_observed_snapshot(cluster.clone(), kube_client.clone()).await?;
+        let mut last_snapshot =
+            Self::get_observed_snapshot(cluster.clone(), kube_client.clone(), previous).await?;
+        options.payload_limits.apply(&mut last_snapshot);
+        options.redaction.redact_snapshot(&mut last_snapshot);
         let derived_snapshot = Self::get_derived_snapshot(&last_snapshot)?;
         let augmented = AugmentedClusterSnapshot {
             observed: last_snapshot,
@@ -183,44 +347,146 @@ impl ClusterStateResolver {
         Ok(augmented)
     }
 
+    /// Lists every kind on its own, so a kind whose API is unavailable only costs that kind:
+    /// it keeps its objects from `previous` and is recorded in the snapshot's
+    /// `listing_failures`. Fails only when no kind could be listed.
     async fn get_observed_snapshot(
         cluster: Cluster,
         client: Arc<Box<dyn KubeClient>>,
+        previous: Option<&ObservedClusterSnapshot>,
     ) -> Result<ObservedClusterSnapshot> {
-        let namespaces = client.get_namespaces().await?;
-        let events: Vec<Arc<Event>> = client.get_events().await?;
-        let nodes = client
-            .get_nodes()
-            .await
-            .or_else(|_err| Result::Ok(vec![]))?;
-        let pods = client.get_pods().await?;
-        let deployments = client.get_deployments().await?;
-        let stateful_sets = client.get_stateful_sets().await?;
-        let replica_sets = client.get_replica_sets().await?;
-        let daemon_sets = client.get_daemon_sets().await?;
-        let jobs = client.get_jobs().await?;
-
-        let ingresses = client.get_ingresses().await?;
-        let services = client.get_services().await?;
-        let endpoint_slices = client.get_endpoint_slices().await?;
-        let network_policies = client.get_network_policies().await?;
-
-        let config_maps = client.get_config_maps().await?;
-
-        let storage_classes = client
-            .get_storage_classes()
-            .await
-            .or_else(|_err| Result::Ok(vec![]))?;
-        let persistent_volumes = client
-            .get_persistent_volumes()
-            .await
-            .or_else(|_err| Result::Ok(vec![]))?;
-        let persistent_volume_claims = client
-            .get_persistent_volume_claims()
-            .await
-            .or_else(|_err| Result::Ok(vec![]))?;
+        let mut lister = KindLister {
+            previous_failures: previous
+                .map(|p| p.listing_failures.clone())
+                .unwrap_or_default(),
+            ..KindLister::default()
+        };
+        let namespaces = lister
+            .list(
+                ResourceType::Namespace,
+                previous.map(|p| &p.namespaces),
+                || client.get_namespaces(),
+            )
+            .await;
+        let events = lister
+            .list(ResourceType::Event, previous.map(|p| &p.events), || {
+                client.get_events()
+            })
+            .await;
+        let nodes = lister
+            .list(ResourceType::Node, previous.map(|p| &p.nodes), || {
+                client.get_nodes()
+            })
+            .await;
+        let pods = lister
+            .list(ResourceType::Pod, previous.map(|p| &p.pods), || {
+                client.get_pods()
+            })
+            .await;
+        let deployments = lister
+            .list(
+                ResourceType::Deployment,
+                previous.map(|p| &p.deployments),
+                || client.get_deployments(),
+            )
+            .await;
+        let stateful_sets = lister
+            .list(
+                ResourceType::StatefulSet,
+                previous.map(|p| &p.stateful_sets),
+                || client.get_stateful_sets(),
+            )
+            .await;
+        let replica_sets = lister
+            .list(
+                ResourceType::ReplicaSet,
+                previous.map(|p| &p.replica_sets),
+                || client.get_replica_sets(),
+            )
+            .await;
+        let daemon_sets = lister
+            .list(
+                ResourceType::DaemonSet,
+                previous.map(|p| &p.daemon_sets),
+                || client.get_daemon_sets(),
+            )
+            .await;
+        let jobs = lister
+            .list(ResourceType::Job, previous.map(|p| &p.jobs), || {
+                client.get_jobs()
+            })
+            .await;
+
+        let ingresses = lister
+            .list(
+                ResourceType::Ingress,
+                previous.map(|p| &p.ingresses),
+                || client.get_ingresses(),
+            )
+            .await;
+        let services = lister
+            .list(ResourceType::Service, previous.map(|p| &p.services), || {
+                client.get_services()
+            })
+            .await;
+        let endpoint_slices = lister
+            .list(
+                ResourceType::EndpointSlice,
+                previous.map(|p| &p.endpoint_slices),
+                || client.get_endpoint_slices(),
+            )
+            .await;
+        let network_policies = lister
+            .list(
+                ResourceType::NetworkPolicy,
+                previous.map(|p| &p.network_policies),
+                || client.get_network_policies(),
+            )
+            .await;
+
+        let config_maps = lister
+            .list(
+                ResourceType::ConfigMap,
+                previous.map(|p| &p.config_maps),
+                || client.get_config_maps(),
+            )
+            .await;
+
+        let storage_classes = lister
+            .list(
+                ResourceType::StorageClass,
+                previous.map(|p| &p.storage_classes),
+                || client.get_storage_classes(),
+            )
+            .await;
+        let persistent_volumes = lister
+            .list(
+                ResourceType::PersistentVolume,
+                previous.map(|p| &p.persistent_volumes),
+                || client.get_persistent_volumes(),
+            )
+            .await;
+        let persistent_volume_claims = lister
+            .list(
+                ResourceType::PersistentVolumeClaim,
+                previous.map(|p| &p.persistent_volume_claims),
+                || client.get_persistent_volume_claims(),
+            )
+            .await;
+
+        let service_accounts = lister
+            .list(
+                ResourceType::ServiceAccount,
+                previous.map(|p| &p.service_accounts),
+                || client.get_service_accounts(),
+            )
+            .await;
 
-        let service_accounts = client.get_service_accounts().await?;
+        if lister.failures.len() == lister.kinds {
+            if let Some(err) = lister.first_error {
+                return Err(err.with_context("listing every kind of the cluster"));
+            }
+        }
 
         let snapshot = ObservedClusterSnapshot {
             cluster,
@@ -242,6 +508,7 @@ impl ClusterStateResolver {
             nodes,
             service_accounts,
             events,
+            listing_failures: lister.failures,
         };
         Ok(snapshot)
     }
@@ -271,96 +538,155 @@ impl ClusterStateResolver {
         let cluster = self.cluster.clone();
         let kube_client = self.kube_client.clone();
         let last_snapshot: Arc<Mutex<AugmentedClusterSnapshot>> = self.last_snapshot.clone();
-        let last_state: Arc<Mutex<ClusterState>> = self.last_state.clone();
-        let task = tokio::spawn(async move {
+        let last_state = self.last_state.clone();
+        let entity_index = self.entity_index.clone();
+        let poll_interval = self.poll_interval;
+        let health = self.health.clone();
+        let options = self.options.clone();
+        tokio::spawn(async move {
             Self::diff_loop(
                 cluster,
                 kube_client,
+                options,
                 last_snapshot,
                 last_state,
+                entity_index,
                 backend,
+                poll_interval,
+                health,
                 token,
             )
             .await
-            .expect("Diff loop failed");
-        });
-
-        task
+        })
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn diff_loop(
         cluster: Cluster,
         kube_client: Arc<Box<dyn KubeClient>>,
+        options: Arc<ResolverOptions>,
         last_snapshot: Arc<Mutex<AugmentedClusterSnapshot>>,
-        last_state: Arc<Mutex<ClusterState>>,
+        last_state: SharedClusterState,
+        entity_index: SharedEntityIndex,
         backend: Arc<dyn GraphBackend>,
+        poll_interval: Duration,
+        health: watch::Sender<ResolverHealth>,
         token: CancellationToken,
-    ) -> Result<()> {
-        let poll_interval: Duration = Duration::from_secs(5);
+    ) {
         let mut id: usize = 0;
         loop {
+            let failures = health.borrow().consecutive_failures;
+            let delay = poll_interval * 2u32.pow(failures.min(MAX_BACKOFF_DOUBLINGS));
             tokio::select! {
                 _ = token.cancelled() => {
                     break;
                 },
-                _ = sleep(poll_interval) => {
-
-                    let current_snapshot =
-                        Self::get_augmented_snapshot(&cluster, kube_client.clone()).await?;
-
-                    let new_cluster_state = Self::create_state(&current_snapshot);
-
-                    let previous_snapshot = {
-                        let last_snapshot_guard = last_snapshot
-                            .lock()
-                            .expect("Failed to lock last_snapshot for diff computation");
-                        last_snapshot_guard.observed.clone()
-                    };
-
-                    let state_diff = {
-                        let last_state_guard = last_state
-                            .lock()
-                            .expect("Failed to lock last_state for diff computation");
-                        last_state_guard.diff(
-                            &new_cluster_state,
-                            &previous_snapshot,
-                            &current_snapshot.observed,
-                        )
-                    };
-
-                    if !state_diff.is_empty() {
-                        info!(
-                            "Applying diff loop iteration {id}: +{} nodes, -{} nodes, ~{} nodes, +{} edges, -{} edges",
-                            state_diff.added_nodes.len(),
-                            state_diff.removed_nodes.len(),
-                            state_diff.modified_nodes.len(),
-                            state_diff.added_edges.len(),
-                            state_diff.removed_edges.len(),
-                        );
-                        backend.update(state_diff).await?;
-                    } else {
-                        trace!("Diff loop iteration {id}: no changes detected");
-                    }
-
-                    {
-                        let mut last_state_guard = last_state
-                            .lock()
-                            .expect("Failed to lock last_state for update");
-                        *last_state_guard = new_cluster_state;
-                    }
-
-                    {
-                        let mut last_snapshot_guard = last_snapshot
-                            .lock()
-                            .expect("Failed to lock last_snapshot for update");
-                        *last_snapshot_guard = current_snapshot;
+                _ = sleep(delay) => {
+                    let outcome = Self::apply_next_diff(
+                        &cluster,
+                        &kube_client,
+                        &options,
+                        &last_snapshot,
+                        &last_state,
+                        &entity_index,
+                        backend.as_ref(),
+                        id,
+                    )
+                    .await;
+                    match outcome {
+                        Ok(()) => health.send_modify(|health| {
+                            if !health.is_connected() {
+                                info!(
+                                    "Diff loop recovered after {} failed attempts",
+                                    health.consecutive_failures
+                                );
+                            }
+                            health.last_success = Instant::now();
+                            health.consecutive_failures = 0;
+                            health.last_error = None;
+                            health.last_error_class = None;
+                        }),
+                        Err(err) => health.send_modify(|health| {
+                            health.consecutive_failures += 1;
+                            warn!(
+                                "Diff loop iteration {id} failed (attempt {}): {err}",
+                                health.consecutive_failures
+                            );
+                            health.last_error = Some(err.to_string());
+                            health.last_error_class = Some(err.class());
+                        }),
                     }
-
                     id += 1;
                 },
             }
         }
         info!("Stopped diff_loop, number of loops {id}");
+    }
+
+    /// Lists the cluster once and applies its difference to the last state to `backend`.
+    #[allow(clippy::too_many_arguments)]
+    async fn apply_next_diff(
+        cluster: &Cluster,
+        kube_client: &Arc<Box<dyn KubeClient>>,
+        options: &ResolverOptions,
+        last_snapshot: &Mutex<AugmentedClusterSnapshot>,
+        last_state: &SharedClusterState,
+        entity_index: &Mutex<EntityIndex>,
+        backend: &dyn GraphBackend,
+        id: usize,
+    ) -> Result<()> {
+        let previous_snapshot = {
+            let last_snapshot_guard = last_snapshot
+                .lock()
+                .expect("Failed to lock last_snapshot for diff computation");
+            last_snapshot_guard.observed.clone()
+        };
+
+        let current_snapshot = Self::get_augmented_snapshot(
+            cluster,
+            kube_client.clone(),
+            options,
+            Some(&previous_snapshot),
+        )
+        .await?;
+
+        let new_cluster_state = Self::create_state(&current_snapshot);
+
+        let state_diff = last_state.load().diff(
+            &new_cluster_state,
+            &previous_snapshot,
+            &current_snapshot.observed,
+        );
+
+        if !state_diff.is_empty() {
+            info!(
+                "Applying diff loop iteration {id}: +{} nodes, -{} nodes, ~{} nodes, +{} edges, -{} edges",
+                state_diff.added_nodes.len(),
+                state_diff.removed_nodes.len(),
+                state_diff.modified_nodes.len(),
+                state_diff.added_edges.len(),
+                state_diff.removed_edges.len(),
+            );
+            entity_index
+                .lock()
+                .expect("Failed to lock entity_index for update")
+                .apply_diff(&state_diff);
+            backend
+                .update(state_diff)
+                .await
+                .with_context(|| format!("applying diff {id} to the graph backend"))?;
+        } else {
+            trace!("Diff loop iteration {id}: no changes detected");
+        }
+
+        last_state.store(new_cluster_state);
+
+        {
+            let mut last_snapshot_guard = last_snapshot
+                .lock()
+                .expect("Failed to lock last_snapshot for update");
+            *last_snapshot_guard = current_snapshot;
+        }
         Ok(())
     }
 
@@ -368,6 +694,7 @@ impl ClusterStateResolver {
     async fn get_logs(
         client: &Arc<Box<dyn KubeClient>>,
         containers: &[Arc<Container>],
+        limits: PayloadLimits,
     ) -> Vec<Logs> {
         let mut all_logs: Vec<Logs> = Vec::with_capacity(containers.len());
         let mut handles = Vec::new();
@@ -384,7 +711,7 @@ impl ClusterStateResolver {
                 let client = client.clone();
                 handles.push(tokio::spawn(async move {
                     match client.get_pod_logs(&ns, pod_name.as_str(), Some(container_name.clone())).await {
-                        Ok(content) => Some(Logs::new(&ns, &container_name, &container_uid, content)),
+                        Ok(content) => Some(Logs::new(&ns, &container_name, &container_uid, limits.cap_logs(content))),
                         Err(err) => {
                             trace!("Unable to fetch the logs for pod {ns}/{pod_name} and container {container_name}: {}", err);
                             None
@@ -401,7 +728,23 @@ impl ClusterStateResolver {
         all_logs
     }
 
-    pub async fn resolve(&self) -> Result<Arc<Mutex<ClusterState>>> {
+    /// Sets the delay between two cluster listings of the diff loop; takes effect for loops
+    /// started afterwards.
+    pub fn set_poll_interval(&mut self, poll_interval: Duration) {
+        self.poll_interval = poll_interval;
+    }
+
+    /// Follows the health of the diff loop started with [`Self::start_diff_loop`].
+    pub fn health(&self) -> watch::Receiver<ResolverHealth> {
+        self.health.subscribe()
+    }
+
+    /// The index of node names, kept in line with the graph by the diff loop.
+    pub fn entity_index(&self) -> SharedEntityIndex {
+        self.entity_index.clone()
+    }
+
+    pub async fn resolve(&self) -> Result<SharedClusterState> {
         Ok(self.last_state.clone())
     }
 
@@ -416,55 +759,13 @@ impl ClusterStateResolver {
             last_snapshot_guard.observed.clone()
         };
 
-        write_json_to_dir(dir, SNAPSHOT_CLUSTER_FILE, &snapshot.cluster)?;
-        write_list_to_dir(dir, SNAPSHOT_NAMESPACES_FILE, &snapshot.namespaces)?;
-        write_list_to_dir(dir, SNAPSHOT_PODS_FILE, &snapshot.pods)?;
-        write_list_to_dir(dir, SNAPSHOT_DEPLOYMENTS_FILE, &snapshot.deployments)?;
-        write_list_to_dir(dir, SNAPSHOT_STATEFUL_SETS_FILE, &snapshot.stateful_sets)?;
-        write_list_to_dir(dir, SNAPSHOT_REPLICA_SETS_FILE, &snapshot.replica_sets)?;
-        write_list_to_dir(dir, SNAPSHOT_DAEMON_SETS_FILE, &snapshot.daemon_sets)?;
-        write_list_to_dir(dir, SNAPSHOT_JOBS_FILE, &snapshot.jobs)?;
-        write_list_to_dir(dir, SNAPSHOT_INGRESSES_FILE, &snapshot.ingresses)?;
-        write_list_to_dir(dir, SNAPSHOT_SERVICES_FILE, &snapshot.services)?;
-        write_list_to_dir(
-            dir,
-            SNAPSHOT_ENDPOINT_SLICES_FILE,
-            &snapshot.endpoint_slices,
-        )?;
-        write_list_to_dir(
-            dir,
-            SNAPSHOT_NETWORK_POLICIES_FILE,
-            &snapshot.network_policies,
-        )?;
-        write_list_to_dir(dir, SNAPSHOT_CONFIG_MAPS_FILE, &snapshot.config_maps)?;
-        write_list_to_dir(
-            dir,
-            SNAPSHOT_STORAGE_CLASSES_FILE,
-            &snapshot.storage_classes,
-        )?;
-        write_list_to_dir(
-            dir,
-            SNAPSHOT_PERSISTENT_VOLUMES_FILE,
-            &snapshot.persistent_volumes,
-        )?;
-        write_list_to_dir(
-            dir,
-            SNAPSHOT_PERSISTENT_VOLUME_CLAIMS_FILE,
-            &snapshot.persistent_volume_claims,
-        )?;
-        write_list_to_dir(dir, SNAPSHOT_NODES_FILE, &snapshot.nodes)?;
-        write_list_to_dir(
-            dir,
-            SNAPSHOT_SERVICE_ACCOUNTS_FILE,
-            &snapshot.service_accounts,
-        )?;
-        write_list_to_dir(dir, SNAPSHOT_EVENTS_FILE, &snapshot.events)?;
-        Ok(())
+        write_snapshot_dir(dir, &snapshot)
     }
 
     fn create_state(augmented: &AugmentedClusterSnapshot) -> ClusterState {
         let snapshot = &augmented.observed;
         let mut state = ClusterState::new(snapshot.cluster.clone());
+        state.listing_failures = snapshot.listing_failures.clone();
         let cluster_uid: String = {
             let obj_id = ObjectIdentifier {
                 uid: snapshot.cluster.metadata.uid.as_ref().unwrap().to_string(),
@@ -520,13 +821,16 @@ impl ClusterStateResolver {
                 namespace: item.metadata.namespace.clone(),
                 resource_version: None,
             };
-            state.add_node(GenericObject {
-                id: obj_id.clone(),
-                resource_type: ResourceType::Container,
-                attributes: Some(Box::new(ResourceAttributes::Container {
-                    container: item.clone(),
-                })),
-            });
+            state.add_derived_node(
+                GenericObject {
+                    id: obj_id.clone(),
+                    resource_type: ResourceType::Container,
+                    attributes: Some(Box::new(ResourceAttributes::Container {
+                        container: item.clone(),
+                    })),
+                },
+                item.pod_uid.as_str(),
+            );
 
             Self::connect_part_of_and_belongs_to(
                 &mut state,
@@ -688,9 +992,10 @@ impl ClusterStateResolver {
         // Storage
         for item in &snapshot.storage_classes {
             let provisoner = &item.provisioner;
+            let provisioner_uid = logical_uid("Provisioner", &[provisoner]);
             if unique_provisoners.insert(&item.provisioner) {
                 let obj_id = ObjectIdentifier {
-                    uid: provisoner.clone(),
+                    uid: provisioner_uid.clone(),
                     name: provisoner.clone(),
                     namespace: item.metadata.namespace.clone(),
                     resource_version: None,
@@ -728,7 +1033,7 @@ impl ClusterStateResolver {
             state.add_edge(
                 item.metadata.uid.as_ref().unwrap(),
                 ResourceType::StorageClass,
-                provisoner,
+                &provisioner_uid,
                 ResourceType::Provisioner,
                 Edge::UsesProvisioner,
             );
@@ -1039,13 +1344,16 @@ impl ClusterStateResolver {
                 resource_version: ingress_service_backend.metadata.resource_version.clone(),
             };
 
-            state.add_node(GenericObject {
-                id: obj_id.clone(),
-                resource_type: ResourceType::IngressServiceBackend,
-                attributes: Some(Box::new(ResourceAttributes::IngressServiceBackend {
-                    ingress_service_backend: ingress_service_backend.clone(),
-                })),
-            });
+            state.add_derived_node(
+                GenericObject {
+                    id: obj_id.clone(),
+                    resource_type: ResourceType::IngressServiceBackend,
+                    attributes: Some(Box::new(ResourceAttributes::IngressServiceBackend {
+                        ingress_service_backend: ingress_service_backend.clone(),
+                    })),
+                },
+                ingress_service_backend.ingress_uid.as_str(),
+            );
             state.add_edge(
                 ingress_service_backend.ingress_uid.as_ref(),
                 ResourceType::Ingress,
@@ -1076,11 +1384,14 @@ impl ClusterStateResolver {
                 namespace: host.metadata.namespace.clone(),
                 resource_version: None,
             };
-            state.add_node(GenericObject {
-                id: obj_id.clone(),
-                resource_type: ResourceType::Host,
-                attributes: Some(Box::new(ResourceAttributes::Host { host: host.clone() })),
-            });
+            state.add_derived_node(
+                GenericObject {
+                    id: obj_id.clone(),
+                    resource_type: ResourceType::Host,
+                    attributes: Some(Box::new(ResourceAttributes::Host { host: host.clone() })),
+                },
+                host.ingress_uid.as_str(),
+            );
             state.add_edge(
                 &obj_id.uid,
                 ResourceType::Host,
@@ -1105,13 +1416,16 @@ impl ClusterStateResolver {
                 namespace: endpoint.metadata.namespace.clone(),
                 resource_version: endpoint.metadata.resource_version.clone(),
             };
-            state.add_node(GenericObject {
-                id: obj_id,
-                resource_type: ResourceType::Endpoint,
-                attributes: Some(Box::new(ResourceAttributes::Endpoint {
-                    endpoint: endpoint.clone(),
-                })),
-            });
+            state.add_derived_node(
+                GenericObject {
+                    id: obj_id,
+                    resource_type: ResourceType::Endpoint,
+                    attributes: Some(Box::new(ResourceAttributes::Endpoint {
+                        endpoint: endpoint.clone(),
+                    })),
+                },
+                endpoint.endpoint_slice_id.as_str(),
+            );
             // (EndpointSlice) -[:ContainsEndpoint]-> (Endpoint)
             state.add_edge(
                 endpoint.endpoint_slice_id.as_str(),
@@ -1129,13 +1443,16 @@ impl ClusterStateResolver {
                 namespace: endpoint_address.metadata.namespace.clone(),
                 resource_version: endpoint_address.metadata.resource_version.clone(),
             };
-            state.add_node(GenericObject {
-                id: obj_id.clone(),
-                resource_type: ResourceType::EndpointAddress,
-                attributes: Some(Box::new(ResourceAttributes::EndpointAddress {
-                    endpoint_address: endpoint_address.clone(),
-                })),
-            });
+            state.add_derived_node(
+                GenericObject {
+                    id: obj_id.clone(),
+                    resource_type: ResourceType::EndpointAddress,
+                    attributes: Some(Box::new(ResourceAttributes::EndpointAddress {
+                        endpoint_address: endpoint_address.clone(),
+                    })),
+                },
+                endpoint_address.endpoint_uid.as_str(),
+            );
 
             let endpoint_address_uid = endpoint_address.metadata.uid.as_ref().unwrap().as_str();
 
@@ -1273,7 +1590,7 @@ impl ClusterStateResolver {
                     spec.rules.as_ref().inspect(|rules| {
                         rules.iter().for_each(|rule| {
                             rule.host.as_ref().inspect(|host| {
-                                let host_uid = format!("Host:{ingress_id}:{host}");
+                                let host_uid = logical_uid("Host", &[ingress_id, host]);
                                 let obj_id = ObjectIdentifier {
                                     uid: host_uid.clone(),
                                     name: (*host).clone(),
@@ -1287,8 +1604,18 @@ impl ClusterStateResolver {
                                 http.paths.iter().for_each(|p| {
                                     p.backend.service.as_ref().inspect(|s| {
                                         let service_name = s.name.as_str();
-                                        let ingress_svc_backend_uid = format!(
-                                            "IngressServiceBackend:{ingress_id}:{service_name}"
+                                        let port = s
+                                            .port
+                                            .as_ref()
+                                            .and_then(|port| {
+                                                port.name.clone().or_else(|| {
+                                                    port.number.map(|number| number.to_string())
+                                                })
+                                            })
+                                            .unwrap_or_default();
+                                        let ingress_svc_backend_uid = logical_uid(
+                                            "IngressServiceBackend",
+                                            &[ingress_id, service_name, &port],
                                         );
                                         // Prepare for the edges:
                                         // 1. (Ingress) -[:DefinesBackend]-> (IngressBackend)
@@ -1378,8 +1705,10 @@ impl ClusterStateResolver {
                     });
 
                     endpoint.addresses.iter().for_each(|address| {
-                        let endpoint_address_uid =
-                            format!("EndpointAddress:{endpoint_uid}:{address}");
+                        let endpoint_address_uid = logical_uid(
+                            "EndpointAddress",
+                            &[endpoint_slice_id, address],
+                        );
                         let endpoint_address_id = ObjectIdentifier {
                             uid: endpoint_address_uid.clone(),
                             name: address.clone(),
@@ -1401,3 +1730,105 @@ impl ClusterStateResolver {
         Ok((endpoints, endpoint_addresss))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn namespace(name: &str) -> Arc<Namespace> {
+        let mut namespace = Namespace::default();
+        namespace.metadata.name = Some(name.to_string());
+        Arc::new(namespace)
+    }
+
+    /// Retriable errors are retried, others fail the kind at once.
+    #[tokio::test]
+    async fn failed_kinds_keep_their_previous_objects() {
+        let mut lister = KindLister::default();
+        let previous = vec![namespace("shop")];
+        let attempts = AtomicUsize::new(0);
+        let counter = &attempts;
+        let listed = lister
+            .list(
+                ResourceType::Namespace,
+                Some(&previous),
+                move || async move {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                    Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "no answer").into())
+                },
+            )
+            .await;
+        assert_eq!(listed, previous);
+        assert_eq!(attempts.load(Ordering::Relaxed), LIST_ATTEMPTS);
+
+        attempts.store(0, Ordering::Relaxed);
+        let pods: Vec<Arc<Pod>> = lister
+            .list(ResourceType::Pod, None, move || async move {
+                counter.fetch_add(1, Ordering::Relaxed);
+                Err(AriadneError::parse("forbidden"))
+            })
+            .await;
+        assert!(pods.is_empty());
+        assert_eq!(attempts.load(Ordering::Relaxed), 1);
+
+        let listed = lister
+            .list(ResourceType::Namespace, None, || async {
+                Ok(vec![namespace("ops")])
+            })
+            .await;
+        assert_eq!(listed, vec![namespace("ops")]);
+
+        assert_eq!(lister.kinds, 3);
+        assert_eq!(
+            lister
+                .failures
+                .iter()
+                .map(|failure| (failure.kind.clone(), failure.stale))
+                .collect::<Vec<_>>(),
+            vec![(ResourceType::Namespace, true), (ResourceType::Pod, false)]
+        );
+    }
+
+    /// A kind that never listed stays missing on later polls instead of turning stale, and a
+    /// forbidden kind is not retried on any of them.
+    #[tokio::test]
+    async fn kinds_that_never_listed_stay_missing() {
+        let attempts = AtomicUsize::new(0);
+        let counter = &attempts;
+        let forbidden = move || async move {
+            counter.fetch_add(1, Ordering::Relaxed);
+            Err::<Vec<Arc<Pod>>, _>(AriadneError::kube_store("pods are forbidden", false))
+        };
+
+        let mut first = KindLister::default();
+        let pods = first.list(ResourceType::Pod, None, forbidden).await;
+        assert_eq!(first.failures.len(), 1);
+        assert!(!first.failures[0].stale);
+
+        let mut second = KindLister {
+            previous_failures: first.failures.clone(),
+            ..KindLister::default()
+        };
+        let pods = second.list(ResourceType::Pod, Some(&pods), forbidden).await;
+        assert!(pods.is_empty());
+        assert_eq!(second.failures.len(), 1);
+        assert!(!second.failures[0].stale);
+        assert_eq!(attempts.load(Ordering::Relaxed), 2);
+
+        // A kind that listed once stays stale for as long as it keeps failing.
+        let mut third = KindLister {
+            previous_failures: vec![ListingFailure {
+                kind: ResourceType::Pod,
+                error: "timed out".to_string(),
+                stale: true,
+            }],
+            ..KindLister::default()
+        };
+        let previous = vec![Arc::new(Pod::default())];
+        third
+            .list(ResourceType::Pod, Some(&previous), forbidden)
+            .await;
+        assert!(third.failures[0].stale);
+    }
+}
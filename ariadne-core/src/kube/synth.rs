@@ -0,0 +1,558 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::state_resolver::ObservedClusterSnapshot;
+use crate::types::{Cluster, ObjectIdentifier};
+use k8s_openapi::api::apps::v1::{Deployment, DeploymentSpec, ReplicaSet, StatefulSet};
+use k8s_openapi::api::core::v1::{
+    ConfigMap, Container, Namespace, Node, ObjectReference, PersistentVolume,
+    PersistentVolumeClaim, PersistentVolumeClaimSpec, PersistentVolumeClaimVolumeSource,
+    PersistentVolumeSpec, Pod, PodSpec, PodStatus, Service, ServiceAccount, ServicePort,
+    ServiceSpec, Volume,
+};
+use k8s_openapi::api::discovery::v1::{Endpoint, EndpointSlice};
+use k8s_openapi::api::events::v1::Event;
+use k8s_openapi::api::networking::v1::{
+    HTTPIngressPath, HTTPIngressRuleValue, Ingress, IngressBackend, IngressRule,
+    IngressServiceBackend, IngressSpec, ServiceBackendPort,
+};
+use k8s_openapi::api::storage::v1::StorageClass;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference};
+use k8s_openapi::apimachinery::pkg::version::Info;
+
+/// Pods per node when the number of nodes is not given, about what a large cloud node runs.
+const PODS_PER_NODE: usize = 30;
+const MAX_REPLICAS: usize = 6;
+/// Every fifth workload is a stateful set with a volume per replica.
+const STATEFUL_SET_EVERY: usize = 5;
+/// Every fourth deployment is exposed through an ingress.
+const INGRESS_EVERY: usize = 4;
+/// One in this many pods has a warning event.
+const EVENT_EVERY: usize = 50;
+const STORAGE_CLASSES: [(&str, &str); 2] = [("gp3", "ebs.csi.aws.com"), ("efs", "efs.csi.aws.com")];
+const TEAMS: [&str; 8] = [
+    "payments",
+    "checkout",
+    "search",
+    "identity",
+    "catalog",
+    "billing",
+    "platform",
+    "analytics",
+];
+const APPS: [&str; 8] = [
+    "api",
+    "worker",
+    "frontend",
+    "gateway",
+    "scheduler",
+    "cache",
+    "indexer",
+    "notifier",
+];
+/// Characters Kubernetes uses for generated name suffixes.
+const SUFFIX_ALPHABET: &[u8] = b"bcdfghjklmnpqrstvwxz2456789";
+
+/// Size and seed of a synthetic cluster.
+#[derive(Debug, Clone)]
+pub struct SynthOptions {
+    pub pods: usize,
+    pub namespaces: usize,
+    /// One node per [`PODS_PER_NODE`] pods when not given.
+    pub nodes: Option<usize>,
+    /// Clusters generated with the same options and seed are identical.
+    pub seed: u64,
+}
+
+/// Generates a cluster with the topology of a real one for load tests and benchmarks: namespaces
+/// of deployments and stateful sets with their replica sets, pods, containers, services,
+/// endpoint slices and config maps, ingresses in front of some deployments, a persistent volume
+/// for every stateful replica and warning events on a few pods.
+pub fn synth_snapshot(options: &SynthOptions) -> ObservedClusterSnapshot {
+    let mut synth = Synth {
+        rng: Rng(options.seed),
+        snapshot: ObservedClusterSnapshot::with_cluster(Cluster::new(
+            ObjectIdentifier {
+                uid: "Cluster:synthetic".to_string(),
+                name: "synthetic".to_string(),
+                namespace: None,
+                resource_version: None,
+            },
+            "https://synthetic.invalid",
+            Info {
+                major: "1".to_string(),
+                minor: "31".to_string(),
+                git_version: "v1.31.0".to_string(),
+                ..Default::default()
+            },
+        )),
+        node_names: Vec::new(),
+        next_pod_ip: 0,
+    };
+    let nodes = match options.nodes {
+        Some(nodes) => nodes,
+        None => options.pods.div_ceil(PODS_PER_NODE),
+    };
+    synth.add_nodes(nodes.max(1));
+    synth.add_storage_classes();
+    let namespaces = options.namespaces.max(1);
+    for index in 0..namespaces {
+        let pods = options.pods / namespaces + usize::from(index < options.pods % namespaces);
+        let name = format!("{}-{index}", TEAMS[index % TEAMS.len()]);
+        synth.add_namespace(&name, pods);
+    }
+    synth.snapshot
+}
+
+/// The number of objects of each kind in a snapshot.
+pub fn object_counts(snapshot: &ObservedClusterSnapshot) -> BTreeMap<&'static str, usize> {
+    BTreeMap::from([
+        ("Namespace", snapshot.namespaces.len()),
+        ("Pod", snapshot.pods.len()),
+        ("Deployment", snapshot.deployments.len()),
+        ("StatefulSet", snapshot.stateful_sets.len()),
+        ("ReplicaSet", snapshot.replica_sets.len()),
+        ("DaemonSet", snapshot.daemon_sets.len()),
+        ("Job", snapshot.jobs.len()),
+        ("Ingress", snapshot.ingresses.len()),
+        ("Service", snapshot.services.len()),
+        ("EndpointSlice", snapshot.endpoint_slices.len()),
+        ("NetworkPolicy", snapshot.network_policies.len()),
+        ("ConfigMap", snapshot.config_maps.len()),
+        ("StorageClass", snapshot.storage_classes.len()),
+        ("PersistentVolume", snapshot.persistent_volumes.len()),
+        (
+            "PersistentVolumeClaim",
+            snapshot.persistent_volume_claims.len(),
+        ),
+        ("Node", snapshot.nodes.len()),
+        ("ServiceAccount", snapshot.service_accounts.len()),
+        ("Event", snapshot.events.len()),
+    ])
+}
+
+/// SplitMix64, which is small and all a reproducible cluster needs.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    /// A version 4 UUID.
+    fn uid(&mut self) -> String {
+        let (high, low) = (self.next(), self.next());
+        format!(
+            "{:08x}-{:04x}-4{:03x}-{:04x}-{:012x}",
+            high >> 32,
+            (high >> 16) & 0xffff,
+            high & 0x0fff,
+            ((low >> 48) & 0x3fff) | 0x8000,
+            low & 0xffff_ffff_ffff
+        )
+    }
+
+    fn suffix(&mut self, len: usize) -> String {
+        (0..len)
+            .map(|_| SUFFIX_ALPHABET[self.below(SUFFIX_ALPHABET.len())] as char)
+            .collect()
+    }
+}
+
+struct Synth {
+    rng: Rng,
+    snapshot: ObservedClusterSnapshot,
+    node_names: Vec<String>,
+    next_pod_ip: u32,
+}
+
+impl Synth {
+    fn meta(&mut self, name: &str, namespace: Option<&str>) -> ObjectMeta {
+        ObjectMeta {
+            name: Some(name.to_string()),
+            namespace: namespace.map(str::to_string),
+            uid: Some(self.rng.uid()),
+            ..Default::default()
+        }
+    }
+
+    fn add_nodes(&mut self, count: usize) {
+        for index in 0..count {
+            let name = format!("node-{index:05}");
+            let mut metadata = self.meta(&name, None);
+            metadata.labels = Some(BTreeMap::from([(
+                "topology.kubernetes.io/zone".to_string(),
+                format!("zone-{}", ["a", "b", "c"][index % 3]),
+            )]));
+            self.snapshot.nodes.push(Arc::new(Node {
+                metadata,
+                ..Default::default()
+            }));
+            self.node_names.push(name);
+        }
+    }
+
+    fn add_storage_classes(&mut self) {
+        for (name, provisioner) in STORAGE_CLASSES {
+            let metadata = self.meta(name, None);
+            self.snapshot.storage_classes.push(Arc::new(StorageClass {
+                metadata,
+                provisioner: provisioner.to_string(),
+                ..Default::default()
+            }));
+        }
+    }
+
+    fn add_namespace(&mut self, namespace: &str, mut pods: usize) {
+        let metadata = self.meta(namespace, None);
+        self.snapshot.namespaces.push(Arc::new(Namespace {
+            metadata,
+            ..Default::default()
+        }));
+        let metadata = self.meta("default", Some(namespace));
+        self.snapshot
+            .service_accounts
+            .push(Arc::new(ServiceAccount {
+                metadata,
+                ..Default::default()
+            }));
+
+        let mut workload = 0;
+        while pods > 0 {
+            let replicas = (1 + self.rng.below(MAX_REPLICAS)).min(pods);
+            let app = format!("{}-{workload}", APPS[self.rng.below(APPS.len())]);
+            let workload_pods = if workload % STATEFUL_SET_EVERY == STATEFUL_SET_EVERY - 1 {
+                self.add_stateful_set(namespace, &app, replicas)
+            } else {
+                let pods = self.add_deployment(namespace, &app, replicas);
+                if workload % INGRESS_EVERY == 0 {
+                    self.add_ingress(namespace, &app);
+                }
+                pods
+            };
+            self.add_service(namespace, &app, &workload_pods);
+            let metadata = self.meta(&format!("{app}-config"), Some(namespace));
+            self.snapshot.config_maps.push(Arc::new(ConfigMap {
+                metadata,
+                data: Some(BTreeMap::from([(
+                    "LOG_LEVEL".to_string(),
+                    "info".to_string(),
+                )])),
+                ..Default::default()
+            }));
+            pods -= replicas;
+            workload += 1;
+        }
+    }
+
+    fn add_deployment(&mut self, namespace: &str, app: &str, replicas: usize) -> Vec<Arc<Pod>> {
+        let metadata = self.meta(app, Some(namespace));
+        let deployment_owner = owner("apps/v1", "Deployment", &metadata);
+        self.snapshot.deployments.push(Arc::new(Deployment {
+            metadata,
+            spec: Some(DeploymentSpec {
+                replicas: Some(replicas as i32),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }));
+        let replica_set_name = format!("{app}-{}", self.rng.suffix(10));
+        let mut metadata = self.meta(&replica_set_name, Some(namespace));
+        metadata.owner_references = Some(vec![deployment_owner]);
+        let replica_set_owner = owner("apps/v1", "ReplicaSet", &metadata);
+        self.snapshot.replica_sets.push(Arc::new(ReplicaSet {
+            metadata,
+            ..Default::default()
+        }));
+        (0..replicas)
+            .map(|_| {
+                let name = format!("{replica_set_name}-{}", self.rng.suffix(5));
+                self.add_pod(namespace, &name, app, replica_set_owner.clone(), None)
+            })
+            .collect()
+    }
+
+    fn add_stateful_set(&mut self, namespace: &str, app: &str, replicas: usize) -> Vec<Arc<Pod>> {
+        let metadata = self.meta(app, Some(namespace));
+        let stateful_set_owner = owner("apps/v1", "StatefulSet", &metadata);
+        self.snapshot.stateful_sets.push(Arc::new(StatefulSet {
+            metadata,
+            ..Default::default()
+        }));
+        let (storage_class, _) = STORAGE_CLASSES[self.rng.below(STORAGE_CLASSES.len())];
+        (0..replicas)
+            .map(|ordinal| {
+                let claim =
+                    self.add_volume(namespace, &format!("data-{app}-{ordinal}"), storage_class);
+                let name = format!("{app}-{ordinal}");
+                self.add_pod(
+                    namespace,
+                    &name,
+                    app,
+                    stateful_set_owner.clone(),
+                    Some(claim),
+                )
+            })
+            .collect()
+    }
+
+    /// Adds a claim and the persistent volume bound to it, and returns the claim's name.
+    fn add_volume(&mut self, namespace: &str, claim: &str, storage_class: &str) -> String {
+        let claim_metadata = self.meta(claim, Some(namespace));
+        let volume_name = format!("pvc-{}", claim_metadata.uid.as_deref().unwrap_or_default());
+        let volume_metadata = self.meta(&volume_name, None);
+        self.snapshot
+            .persistent_volumes
+            .push(Arc::new(PersistentVolume {
+                metadata: volume_metadata,
+                spec: Some(PersistentVolumeSpec {
+                    storage_class_name: Some(storage_class.to_string()),
+                    claim_ref: Some(ObjectReference {
+                        kind: Some("PersistentVolumeClaim".to_string()),
+                        name: Some(claim.to_string()),
+                        namespace: Some(namespace.to_string()),
+                        uid: claim_metadata.uid.clone(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }));
+        self.snapshot
+            .persistent_volume_claims
+            .push(Arc::new(PersistentVolumeClaim {
+                metadata: claim_metadata,
+                spec: Some(PersistentVolumeClaimSpec {
+                    storage_class_name: Some(storage_class.to_string()),
+                    volume_name: Some(volume_name),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }));
+        claim.to_string()
+    }
+
+    fn add_pod(
+        &mut self,
+        namespace: &str,
+        name: &str,
+        app: &str,
+        owner: OwnerReference,
+        claim: Option<String>,
+    ) -> Arc<Pod> {
+        let mut metadata = self.meta(name, Some(namespace));
+        metadata.labels = Some(BTreeMap::from([("app".to_string(), app.to_string())]));
+        metadata.owner_references = Some(vec![owner]);
+        let mut containers = vec![container(app)];
+        if self.rng.below(3) == 0 {
+            containers.push(container("istio-proxy"));
+        }
+        let volumes = claim.map(|claim_name| {
+            vec![Volume {
+                name: "data".to_string(),
+                persistent_volume_claim: Some(PersistentVolumeClaimVolumeSource {
+                    claim_name,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }]
+        });
+        self.next_pod_ip += 1;
+        let ip = self.next_pod_ip;
+        let pod = Arc::new(Pod {
+            metadata,
+            spec: Some(PodSpec {
+                containers,
+                node_name: Some(self.node_names[self.rng.below(self.node_names.len())].clone()),
+                service_account_name: Some("default".to_string()),
+                volumes,
+                ..Default::default()
+            }),
+            status: Some(PodStatus {
+                phase: Some("Running".to_string()),
+                pod_ip: Some(format!(
+                    "10.{}.{}.{}",
+                    (ip >> 16) & 0xff,
+                    (ip >> 8) & 0xff,
+                    ip & 0xff
+                )),
+                ..Default::default()
+            }),
+        });
+        if self.rng.below(EVENT_EVERY) == 0 {
+            self.add_warning_event(&pod);
+        }
+        self.snapshot.pods.push(pod.clone());
+        pod
+    }
+
+    fn add_warning_event(&mut self, pod: &Pod) {
+        let pod_name = pod.metadata.name.as_deref().unwrap_or_default();
+        let namespace = pod.metadata.namespace.as_deref();
+        let event_name = format!("{pod_name}.{:016x}", self.rng.next());
+        let metadata = self.meta(&event_name, namespace);
+        self.snapshot.events.push(Arc::new(Event {
+            metadata,
+            reason: Some("BackOff".to_string()),
+            note: Some("Back-off restarting failed container".to_string()),
+            type_: Some("Warning".to_string()),
+            regarding: Some(ObjectReference {
+                kind: Some("Pod".to_string()),
+                name: Some(pod_name.to_string()),
+                namespace: namespace.map(str::to_string),
+                uid: pod.metadata.uid.clone(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }));
+    }
+
+    fn add_service(&mut self, namespace: &str, app: &str, pods: &[Arc<Pod>]) {
+        let metadata = self.meta(app, Some(namespace));
+        let service_owner = owner("v1", "Service", &metadata);
+        self.snapshot.services.push(Arc::new(Service {
+            metadata,
+            spec: Some(ServiceSpec {
+                selector: Some(BTreeMap::from([("app".to_string(), app.to_string())])),
+                ports: Some(vec![ServicePort {
+                    port: 80,
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }));
+        let endpoints = pods
+            .iter()
+            .map(|pod| Endpoint {
+                addresses: vec![pod
+                    .status
+                    .as_ref()
+                    .and_then(|status| status.pod_ip.clone())
+                    .unwrap_or_default()],
+                node_name: pod.spec.as_ref().and_then(|spec| spec.node_name.clone()),
+                target_ref: Some(ObjectReference {
+                    kind: Some("Pod".to_string()),
+                    name: pod.metadata.name.clone(),
+                    namespace: pod.metadata.namespace.clone(),
+                    uid: pod.metadata.uid.clone(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .collect();
+        let slice_name = format!("{app}-{}", self.rng.suffix(5));
+        let mut metadata = self.meta(&slice_name, Some(namespace));
+        metadata.labels = Some(BTreeMap::from([(
+            "kubernetes.io/service-name".to_string(),
+            app.to_string(),
+        )]));
+        metadata.owner_references = Some(vec![service_owner]);
+        self.snapshot.endpoint_slices.push(Arc::new(EndpointSlice {
+            metadata,
+            address_type: "IPv4".to_string(),
+            endpoints,
+            ..Default::default()
+        }));
+    }
+
+    fn add_ingress(&mut self, namespace: &str, app: &str) {
+        let metadata = self.meta(app, Some(namespace));
+        self.snapshot.ingresses.push(Arc::new(Ingress {
+            metadata,
+            spec: Some(IngressSpec {
+                rules: Some(vec![IngressRule {
+                    host: Some(format!("{app}.{namespace}.example.com")),
+                    http: Some(HTTPIngressRuleValue {
+                        paths: vec![HTTPIngressPath {
+                            path: Some("/".to_string()),
+                            path_type: "Prefix".to_string(),
+                            backend: IngressBackend {
+                                service: Some(IngressServiceBackend {
+                                    name: app.to_string(),
+                                    port: Some(ServiceBackendPort {
+                                        number: Some(80),
+                                        ..Default::default()
+                                    }),
+                                }),
+                                ..Default::default()
+                            },
+                        }],
+                    }),
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }));
+    }
+}
+
+fn owner(api_version: &str, kind: &str, metadata: &ObjectMeta) -> OwnerReference {
+    OwnerReference {
+        api_version: api_version.to_string(),
+        kind: kind.to_string(),
+        name: metadata.name.clone().unwrap_or_default(),
+        uid: metadata.uid.clone().unwrap_or_default(),
+        controller: Some(true),
+        ..Default::default()
+    }
+}
+
+fn container(name: &str) -> Container {
+    Container {
+        name: name.to_string(),
+        image: Some(format!("registry.example.com/{name}:1.0.0")),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kube_client::SnapshotKubeClient;
+    use crate::snapshot::write_snapshot_dir;
+    use crate::state_resolver::ClusterStateResolver;
+
+    #[test]
+    fn synthetic_clusters_are_reproducible_and_resolve() {
+        let options = SynthOptions {
+            pods: 500,
+            namespaces: 7,
+            nodes: None,
+            seed: 42,
+        };
+        let snapshot = synth_snapshot(&options);
+        let counts = object_counts(&snapshot);
+        assert_eq!(counts["Pod"], 500);
+        assert_eq!(counts["Namespace"], 7);
+        assert_eq!(counts["Node"], 17);
+        assert!(counts["StatefulSet"] > 0);
+        assert!(counts["Ingress"] > 0);
+        assert_eq!(counts["PersistentVolume"], counts["PersistentVolumeClaim"]);
+        assert_eq!(
+            serde_json::to_value(&synth_snapshot(&options).pods).unwrap(),
+            serde_json::to_value(&snapshot.pods).unwrap()
+        );
+
+        let dir = std::env::temp_dir().join(format!("ariadne-synth-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        write_snapshot_dir(&dir, &snapshot).unwrap();
+        let client = SnapshotKubeClient::from_dir(&dir).unwrap();
+        tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(ClusterStateResolver::new_with_kube_client(
+                "synthetic".to_string(),
+                Box::new(client),
+            ))
+            .unwrap();
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
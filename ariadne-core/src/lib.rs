@@ -1,4 +1,5 @@
 pub mod errors;
+#[cfg(feature = "memgraph")]
 #[path = "graph/actor.rs"]
 pub(crate) mod graph_actor;
 #[path = "graph/backend.rs"]
@@ -11,25 +12,39 @@ pub(crate) mod tls;
 
 pub mod prelude {
     use crate::errors;
+    pub use crate::errors::ErrorContext;
     pub type Result<T> = std::result::Result<T, errors::AriadneError>;
 }
 
 #[path = "state/diff.rs"]
 mod diff;
+#[path = "state/entity_index.rs"]
+pub mod entity_index;
+#[path = "kube/fixtures.rs"]
+pub mod fixtures;
 #[path = "state/id_gen.rs"]
 pub mod id_gen;
 #[path = "backends/in_memory.rs"]
 pub mod in_memory;
 #[path = "kube/client.rs"]
 pub mod kube_client;
+#[cfg(feature = "memgraph")]
 #[path = "backends/memgraph.rs"]
 pub mod memgraph;
+#[cfg(feature = "memgraph")]
 #[path = "backends/memgraph_async.rs"]
 pub mod memgraph_async;
+#[path = "kube/payload_limits.rs"]
+pub mod payload_limits;
+#[path = "kube/redaction.rs"]
+pub mod redaction;
 #[path = "kube/snapshot.rs"]
 pub mod snapshot;
 #[path = "state/mod.rs"]
 pub mod state;
 #[path = "kube/state_resolver.rs"]
 pub mod state_resolver;
+#[cfg(feature = "synth")]
+#[path = "kube/synth.rs"]
+pub mod synth;
 pub mod types;
@@ -1,4 +1,5 @@
 use crate::state_resolver::ObservedClusterSnapshot;
+use crate::types::ObjectHasher;
 use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
 use k8s_openapi::api::batch::v1::Job;
 use k8s_openapi::api::core::v1::{
@@ -128,9 +129,12 @@ impl<'a> ObservedClusterSnapshotDiff<'a> {
     }
 }
 
+/// Objects of `current` missing from `prev` are added, the other way round removed. An object in
+/// both is modified when its resource version changed or, for objects without one such as those
+/// of imported dumps, when its content hash did.
 fn diff_slices<'b, T>(current: &'b [Arc<T>], prev: &'b [Arc<T>]) -> Diff<'b, T>
 where
-    T: ResourceExt,
+    T: ResourceExt + ObjectHasher,
 {
     // Build maps keyed by UID (borrowed from the object's metadata)
     let mut prev_map: HashMap<&'b str, (&'b T, Option<&'b str>)> = HashMap::new();
@@ -154,9 +158,11 @@ where
                 }
                 Some((prev_item, prev_rv)) => {
                     let cur_rv = item.meta().resource_version.as_deref();
-                    if cur_rv != *prev_rv {
-                        // Changed resourceVersion -> treat as modified
-                        let _ = prev_item; // keep for clarity; not used further
+                    let changed = match (cur_rv, *prev_rv) {
+                        (None, None) => item.get_hash() != prev_item.get_hash(),
+                        (cur_rv, prev_rv) => cur_rv != prev_rv,
+                    };
+                    if changed {
                         modified.push(&**item);
                     }
                 }
@@ -288,6 +294,29 @@ mod tests {
         assert!(diff.modified.is_empty());
     }
 
+    #[test]
+    fn test_diff_resources_without_resource_versions() {
+        let namespace = |uid: &str, label: &str| {
+            Arc::new(Namespace {
+                metadata: ObjectMeta {
+                    uid: Some(uid.to_string()),
+                    labels: Some([("team".to_string(), label.to_string())].into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+        };
+        let current = vec![namespace("uid1", "shop"), namespace("uid2", "ops")];
+        let prev = vec![namespace("uid1", "shop"), namespace("uid2", "shop")];
+
+        let diff = diff_slices(&current, &prev);
+
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        let expected: HashSet<String> = ["uid2"].into_iter().map(|s| s.to_string()).collect();
+        assert_eq!(uids(&diff.modified), expected);
+    }
+
     #[test]
     fn test_diff_mixed_changes() {
         let current = vec![
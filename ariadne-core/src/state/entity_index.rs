@@ -0,0 +1,304 @@
+use crate::state::{ClusterState, ClusterStateDiff};
+use crate::types::{GenericObject, ResourceType};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Kinds left out of the index: their names are generated (hashes, addresses) or they are
+/// never named in a question.
+const UNINDEXED_TYPES: &[ResourceType] = &[
+    ResourceType::Cluster,
+    ResourceType::Event,
+    ResourceType::ReplicaSet,
+    ResourceType::EndpointSlice,
+    ResourceType::Endpoint,
+    ResourceType::EndpointAddress,
+    ResourceType::IngressServiceBackend,
+];
+
+/// Number of buckets the hashed features are folded into.
+const DIMENSIONS: u32 = 1 << 16;
+/// Weight of a whole word relative to each of its character trigrams.
+const WORD_WEIGHT: f32 = 1.0;
+const TRIGRAM_WEIGHT: f32 = 0.5;
+/// Weight of the kind and namespace words of an entity relative to its name.
+const CONTEXT_WEIGHT: f32 = 0.3;
+/// Matches scoring below this cosine similarity are not returned.
+const MIN_SCORE: f32 = 0.2;
+/// Question words that say nothing about which object is meant.
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "are", "for", "with", "that", "this", "from", "which", "what", "why", "how",
+    "does", "did", "has", "have", "not", "all", "any", "show", "list", "give", "find", "get",
+    "running", "there", "into", "its", "their", "them", "they",
+];
+
+/// A sparse, L2-normalised vector of hashed word and character trigram features.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Embedding(Vec<(u32, f32)>);
+
+impl Embedding {
+    fn dot(&self, other: &Embedding) -> f32 {
+        let (mut i, mut j, mut sum) = (0, 0, 0.0);
+        while i < self.0.len() && j < other.0.len() {
+            let (a, b) = (self.0[i], other.0[j]);
+            match a.0.cmp(&b.0) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    sum += a.1 * b.1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        sum
+    }
+}
+
+#[derive(Default)]
+struct EmbeddingBuilder {
+    features: HashMap<u32, f32>,
+}
+
+impl EmbeddingBuilder {
+    fn add_text(&mut self, text: &str, weight: f32) {
+        for word in words(text) {
+            self.add_feature(&format!("w:{word}"), WORD_WEIGHT * weight);
+            let padded: Vec<char> = format!("^{word}$").chars().collect();
+            for trigram in padded.windows(3) {
+                let trigram: String = trigram.iter().collect();
+                self.add_feature(&format!("t:{trigram}"), TRIGRAM_WEIGHT * weight);
+            }
+        }
+    }
+
+    fn add_feature(&mut self, feature: &str, weight: f32) {
+        *self
+            .features
+            .entry(fnv1a(feature) % DIMENSIONS)
+            .or_default() += weight;
+    }
+
+    fn build(self) -> Embedding {
+        let norm = self
+            .features
+            .values()
+            .map(|weight| weight * weight)
+            .sum::<f32>()
+            .sqrt();
+        if norm == 0.0 {
+            return Embedding::default();
+        }
+        let mut vector: Vec<(u32, f32)> = self
+            .features
+            .into_iter()
+            .map(|(bucket, weight)| (bucket, weight / norm))
+            .collect();
+        vector.sort_by_key(|(bucket, _)| *bucket);
+        Embedding(vector)
+    }
+}
+
+/// Lower-cased words of at least three characters, without stop words and a plural `s`, so
+/// `payments-api` and "payment API" share their words.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|word| word.chars().count() >= 3 && !STOP_WORDS.contains(&word.as_str()))
+        .map(|word| match word.strip_suffix('s') {
+            Some(stem) if stem.chars().count() >= 3 && !stem.ends_with('s') => stem.to_string(),
+            _ => word,
+        })
+}
+
+/// A stable hash, so the same feature lands in the same bucket in every process.
+fn fnv1a(text: &str) -> u32 {
+    text.bytes().fold(0x811c_9dc5, |hash: u32, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+#[derive(Debug, Clone)]
+struct IndexedEntity {
+    name: String,
+    kind: ResourceType,
+    namespace: Option<String>,
+    embedding: Embedding,
+}
+
+/// A node of the graph whose name resembles the searched text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityMatch {
+    pub uid: String,
+    pub name: String,
+    pub kind: ResourceType,
+    pub namespace: Option<String>,
+    /// Cosine similarity between the text and the node, in `0.0..=1.0`.
+    pub score: f32,
+}
+
+/// Embeddings of the names of the graph's nodes, together with their kind and namespace, for
+/// finding the objects a question refers to even when it spells them differently. The
+/// embeddings hash words and character trigrams, so no model is needed to build or query it.
+#[derive(Debug, Default)]
+pub struct EntityIndex {
+    entities: HashMap<String, IndexedEntity>,
+}
+
+pub type SharedEntityIndex = Arc<Mutex<EntityIndex>>;
+
+impl EntityIndex {
+    pub fn build(state: &ClusterState) -> Self {
+        let mut index = Self::default();
+        for node in state.get_nodes() {
+            index.insert(node);
+        }
+        index
+    }
+
+    /// Brings the index in line with a diff the resolver applied to the graph.
+    pub fn apply_diff(&mut self, diff: &ClusterStateDiff) {
+        for node in &diff.removed_nodes {
+            self.entities.remove(&node.id.uid);
+        }
+        for node in diff.added_nodes.iter().chain(&diff.modified_nodes) {
+            self.insert(node);
+        }
+    }
+
+    fn insert(&mut self, node: &GenericObject) {
+        if UNINDEXED_TYPES.contains(&node.resource_type) || node.id.name.is_empty() {
+            return;
+        }
+        let mut builder = EmbeddingBuilder::default();
+        builder.add_text(&node.id.name, 1.0);
+        builder.add_text(&node.resource_type.to_string(), CONTEXT_WEIGHT);
+        if let Some(namespace) = &node.id.namespace {
+            builder.add_text(namespace, CONTEXT_WEIGHT);
+        }
+        self.entities.insert(
+            node.id.uid.clone(),
+            IndexedEntity {
+                name: node.id.name.clone(),
+                kind: node.resource_type.clone(),
+                namespace: node.id.namespace.clone(),
+                embedding: builder.build(),
+            },
+        );
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Up to `limit` nodes most similar to `text`, best first.
+    pub fn search(&self, text: &str, limit: usize) -> Vec<EntityMatch> {
+        let mut builder = EmbeddingBuilder::default();
+        builder.add_text(text, 1.0);
+        let query = builder.build();
+        if query.0.is_empty() {
+            return Vec::new();
+        }
+        let mut matches: Vec<EntityMatch> = self
+            .entities
+            .iter()
+            .filter_map(|(uid, entity)| {
+                let score = query.dot(&entity.embedding);
+                (score >= MIN_SCORE).then(|| EntityMatch {
+                    uid: uid.clone(),
+                    name: entity.name.clone(),
+                    kind: entity.kind.clone(),
+                    namespace: entity.namespace.clone(),
+                    score,
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.uid.cmp(&b.uid))
+        });
+        matches.truncate(limit);
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ObjectIdentifier;
+
+    fn object(uid: &str, name: &str, kind: ResourceType, namespace: Option<&str>) -> GenericObject {
+        GenericObject {
+            id: ObjectIdentifier {
+                uid: uid.to_string(),
+                name: name.to_string(),
+                namespace: namespace.map(str::to_string),
+                resource_version: None,
+            },
+            resource_type: kind,
+            attributes: None,
+        }
+    }
+
+    fn index() -> EntityIndex {
+        let mut index = EntityIndex::default();
+        index.apply_diff(&ClusterStateDiff {
+            added_nodes: vec![
+                object("1", "payments-api", ResourceType::Deployment, Some("shop")),
+                object("2", "payments-db", ResourceType::StatefulSet, Some("shop")),
+                object("3", "checkout-web", ResourceType::Service, Some("shop")),
+                object("4", "shop", ResourceType::Namespace, None),
+                object(
+                    "5",
+                    "payments-api-7f9c",
+                    ResourceType::ReplicaSet,
+                    Some("shop"),
+                ),
+            ],
+            ..ClusterStateDiff::default()
+        });
+        index
+    }
+
+    #[test]
+    fn paraphrased_names_find_their_objects() {
+        let index = index();
+        assert_eq!(index.len(), 4);
+
+        let matches = index.search("why is the payment API deployment slow?", 3);
+        assert_eq!(matches[0].uid, "1");
+        assert_eq!(matches[0].kind, ResourceType::Deployment);
+        assert_eq!(matches[0].namespace.as_deref(), Some("shop"));
+
+        let matches = index.search("checkout web service", 1);
+        assert_eq!(matches[0].name, "checkout-web");
+        assert!(index.search("hello", 3).is_empty());
+    }
+
+    #[test]
+    fn diffs_add_and_remove_entities() {
+        let mut index = index();
+        index.apply_diff(&ClusterStateDiff {
+            removed_nodes: vec![object(
+                "1",
+                "payments-api",
+                ResourceType::Deployment,
+                Some("shop"),
+            )],
+            added_nodes: vec![object(
+                "6",
+                "orders-api",
+                ResourceType::Deployment,
+                Some("shop"),
+            )],
+            ..ClusterStateDiff::default()
+        });
+        assert!(index.search("payments api", 5).iter().all(|m| m.uid != "1"));
+        assert_eq!(index.search("orders api", 1)[0].uid, "6");
+    }
+}
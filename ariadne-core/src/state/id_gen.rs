@@ -1,5 +1,25 @@
 use std::collections::HashMap;
 
+/// Offset basis and prime of the 128-bit FNV-1a hash.
+const FNV_OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+const FNV_PRIME: u128 = 0x0000000001000000000000000000013b;
+
+/// Uid of a logical node, which Kubernetes does not give one: its kind and a 128-bit FNV-1a hash
+/// of the fields that identify it, such as the ingress and host name of a `Host`. Equal fields
+/// give equal uids in every resolve and build, so logical nodes of two snapshots compare by uid.
+/// Fields are hashed after their length, so `["a:b", "c"]` and `["a", "b:c"]` differ.
+pub fn logical_uid(kind: &str, identity: &[&str]) -> String {
+    let mut hash = FNV_OFFSET_BASIS;
+    for field in std::iter::once(kind).chain(identity.iter().copied()) {
+        let length = (field.len() as u64).to_le_bytes();
+        for byte in length.iter().chain(field.as_bytes()) {
+            hash ^= u128::from(*byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    format!("{kind}:{hash:032x}")
+}
+
 pub enum GetNextIdResult {
     Existing(u32),
     New(u32),
@@ -45,3 +65,22 @@ impl IdGen {
         self.str_to_id.get(str).copied()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logical_uids_are_stable_and_field_aware() {
+        let host = logical_uid("Host", &["ingress-uid", "shop.example.com"]);
+        assert_eq!(host, "Host:16ba063a3734a3c44d53ed66864ded0f");
+        assert_ne!(
+            logical_uid("Host", &["a:b", "c"]),
+            logical_uid("Host", &["a", "b:c"])
+        );
+        assert_ne!(
+            logical_uid("Host", &["provisioner"]),
+            logical_uid("Provisioner", &["provisioner"])
+        );
+    }
+}
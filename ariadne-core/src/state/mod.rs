@@ -1,13 +1,13 @@
 use crate::diff::{Diff, ObservedClusterSnapshotDiff};
 use crate::graph_schema;
 use crate::id_gen::{GetNextIdResult, IdGen};
-use crate::state_resolver::ObservedClusterSnapshot;
+use crate::state_resolver::{ListingFailure, ObservedClusterSnapshot};
 use crate::types::{Cluster, Edge, GenericObject, ResourceType};
 use kube::ResourceExt;
 use petgraph::graphmap::DiGraphMap;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex, OnceLock};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, RwLock};
 use tracing::log::trace;
 use tracing::warn;
 
@@ -54,6 +54,10 @@ pub struct ClusterStateDiff {
     pub added_nodes: Vec<GenericObject>,
     pub removed_nodes: Vec<GenericObject>,
     pub modified_nodes: Vec<GenericObject>,
+    /// Top-level properties, such as `spec` or `status`, that changed on each modified node, by
+    /// uid, so a backend can update only those. A modified node without an entry has to be
+    /// replaced whole.
+    pub modified_fields: HashMap<String, BTreeSet<String>>,
     pub added_edges: Vec<GraphEdge>,
     pub removed_edges: Vec<GraphEdge>,
 }
@@ -66,6 +70,16 @@ impl ClusterStateDiff {
             && self.added_edges.is_empty()
             && self.removed_edges.is_empty()
     }
+
+    /// Records `node` as modified, with the properties that changed since `previous` when the
+    /// previous version is known.
+    fn push_modified(&mut self, previous: Option<&GenericObject>, node: &GenericObject) {
+        if let Some(previous) = previous {
+            self.modified_fields
+                .insert(node.id.uid.clone(), node.changed_fields(previous));
+        }
+        self.modified_nodes.push(node.clone());
+    }
 }
 
 #[macro_export]
@@ -89,11 +103,15 @@ macro_rules! create_generic_object {
 #[derive(Debug)]
 pub struct ClusterState {
     pub cluster: Cluster,
+    /// Kinds the listing behind this state could not read, whose nodes are stale or missing.
+    pub listing_failures: Vec<ListingFailure>,
     graph: DiGraphMap<NodeId, Edge>,
     id_gen: IdGen,
     id_to_node: HashMap<NodeId, GenericObject>,
     nodes_by_type: HashMap<ResourceType, Vec<NodeId>>,
     edges_by_type: HashMap<Edge, Vec<(NodeId, NodeId)>>,
+    /// Logical nodes by the node they derive from, such as the `Host`s of an `Ingress`.
+    derived: HashMap<NodeId, Vec<NodeId>>,
 }
 
 type EdgeKey = (ResourceType, Edge, ResourceType);
@@ -109,11 +127,13 @@ impl ClusterState {
     pub fn new(cluster: Cluster) -> Self {
         ClusterState {
             cluster,
+            listing_failures: Vec::new(),
             graph: DiGraphMap::new(),
             id_gen: IdGen::new(),
             id_to_node: HashMap::new(),
             nodes_by_type: HashMap::new(),
             edges_by_type: HashMap::new(),
+            derived: HashMap::new(),
         }
     }
 
@@ -148,6 +168,41 @@ impl ClusterState {
         }
     }
 
+    /// Adds a logical node that only exists because of the node `parent_uid`, such as the `Host`
+    /// of an `Ingress`. The node is skipped when its parent is not in the state, and a diff that
+    /// removes the parent also removes the node and the nodes derived from it in turn.
+    pub fn add_derived_node(&mut self, node: GenericObject, parent_uid: &str) {
+        let Some(parent) = self.get_node(parent_uid) else {
+            trace!(
+                "Parent {parent_uid} of {} [{}] does not exist (skipping)",
+                node.id.uid,
+                node.resource_type
+            );
+            return;
+        };
+        let uid = node.id.uid.clone();
+        self.add_node(node);
+        if let Some(id) = self.get_node(&uid) {
+            let children = self.derived.entry(parent).or_default();
+            if !children.contains(&id) {
+                children.push(id);
+            }
+        }
+    }
+
+    /// Nodes derived from the node `uid`, directly or through other derived nodes.
+    fn derived_nodes(&self, uid: &str) -> Vec<&GenericObject> {
+        let mut pending: Vec<NodeId> = self.id_gen.get_id(uid).into_iter().collect();
+        let mut nodes = Vec::new();
+        while let Some(parent) = pending.pop() {
+            for child in self.derived.get(&parent).into_iter().flatten() {
+                pending.push(*child);
+                nodes.extend(self.id_to_node.get(child));
+            }
+        }
+        nodes
+    }
+
     pub fn add_edge(
         &mut self,
         source: &str,
@@ -343,7 +398,7 @@ impl ClusterState {
                 if let Some(uid) = item.meta().uid.as_deref() {
                     if processed.insert(uid.to_string()) {
                         match new_state.node_by_uid(uid) {
-                            Some(node) => out.modified_nodes.push(node.clone()),
+                            Some(node) => out.push_modified(prev_state.node_by_uid(uid), node),
                             None => warn!("Modified resource {uid} missing from new state"),
                         }
                     }
@@ -478,6 +533,20 @@ impl ClusterState {
             &mut processed_uids,
         );
 
+        // Logical nodes go with the objects they derive from.
+        let removed_parents: Vec<String> = diff
+            .removed_nodes
+            .iter()
+            .map(|node| node.id.uid.clone())
+            .collect();
+        for uid in removed_parents {
+            for node in self.derived_nodes(&uid) {
+                if processed_uids.insert(node.id.uid.clone()) {
+                    diff.removed_nodes.push(node.clone());
+                }
+            }
+        }
+
         let old_nodes = self.node_map();
         let new_nodes = new_state.node_map();
 
@@ -492,7 +561,7 @@ impl ClusterState {
                 }
                 Some(previous) => {
                     if *previous != *node {
-                        diff.modified_nodes.push((*node).clone());
+                        diff.push_modified(Some(*previous), node);
                         processed_uids.insert(uid.clone());
                     }
                 }
@@ -523,4 +592,145 @@ impl ClusterState {
     }
 }
 
-pub type SharedClusterState = Arc<Mutex<ClusterState>>;
+/// The latest state of a cluster, shared by the resolver, which replaces it after every listing,
+/// and the backends, servers and UIs that read it. A reader takes an immutable snapshot with
+/// [`Self::load`] and keeps it as long as it needs, so a long in-memory query never holds up the
+/// resolver and the resolver never holds up a reader: the lock only guards swapping an `Arc`.
+#[derive(Debug, Clone)]
+pub struct SharedClusterState(Arc<RwLock<Arc<ClusterState>>>);
+
+impl SharedClusterState {
+    pub fn new(state: ClusterState) -> Self {
+        Self(Arc::new(RwLock::new(Arc::new(state))))
+    }
+
+    /// The current state; later calls to [`Self::store`] do not change it.
+    pub fn load(&self) -> Arc<ClusterState> {
+        // The lock only guards an `Arc`, which a panicking holder cannot leave half-written.
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Replaces the current state for every later [`Self::load`].
+    pub fn store(&self, state: ClusterState) {
+        let state = Arc::new(state);
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = state;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ObjectIdentifier, ResourceAttributes};
+    use k8s_openapi::api::core::v1::{Pod, PodStatus};
+    use k8s_openapi::api::networking::v1::Ingress;
+    use k8s_openapi::apimachinery::pkg::version::Info;
+
+    fn cluster(name: &str) -> Cluster {
+        Cluster::new(
+            ObjectIdentifier {
+                uid: format!("Cluster:{name}"),
+                name: name.to_string(),
+                namespace: None,
+                resource_version: None,
+            },
+            "https://example.invalid",
+            Info::default(),
+        )
+    }
+
+    #[test]
+    fn loaded_states_outlive_the_stores_after_them() {
+        let shared = SharedClusterState::new(ClusterState::new(cluster("before")));
+        let reader = shared.clone();
+        let loaded = reader.load();
+        shared.store(ClusterState::new(cluster("after")));
+        assert_eq!(loaded.cluster.name, "before");
+        assert_eq!(reader.load().cluster.name, "after");
+    }
+
+    fn pod(resource_version: &str, phase: &str) -> GenericObject {
+        let mut pod = Pod::default();
+        pod.metadata.name = Some("web".to_string());
+        pod.metadata.uid = Some("pod-uid".to_string());
+        pod.metadata.resource_version = Some(resource_version.to_string());
+        pod.status = Some(PodStatus {
+            phase: Some(phase.to_string()),
+            ..PodStatus::default()
+        });
+        GenericObject {
+            id: ObjectIdentifier {
+                uid: "pod-uid".to_string(),
+                name: "web".to_string(),
+                namespace: None,
+                resource_version: Some(resource_version.to_string()),
+            },
+            resource_type: ResourceType::Pod,
+            attributes: Some(Box::new(ResourceAttributes::Pod { pod: Arc::new(pod) })),
+        }
+    }
+
+    #[test]
+    fn diffs_record_the_changed_properties_of_modified_nodes() {
+        let snapshot = ObservedClusterSnapshot::empty();
+        let mut before = ClusterState::new(cluster("test"));
+        before.add_node(pod("1", "Pending"));
+        let mut after = ClusterState::new(cluster("test"));
+        after.add_node(pod("2", "Running"));
+        let diff = before.diff(&after, &snapshot, &snapshot);
+        assert_eq!(diff.modified_nodes.len(), 1);
+        assert_eq!(
+            diff.modified_fields["pod-uid"],
+            BTreeSet::from(["status".to_string()])
+        );
+
+        // A new resource version alone changes nothing the graph shows.
+        let mut bumped = ClusterState::new(cluster("test"));
+        bumped.add_node(pod("3", "Running"));
+        let diff = after.diff(&bumped, &snapshot, &snapshot);
+        assert!(diff.modified_fields["pod-uid"].is_empty());
+    }
+
+    fn object(uid: &str, resource_type: ResourceType) -> GenericObject {
+        GenericObject {
+            id: ObjectIdentifier {
+                uid: uid.to_string(),
+                name: uid.to_string(),
+                namespace: None,
+                resource_version: None,
+            },
+            resource_type,
+            attributes: None,
+        }
+    }
+
+    #[test]
+    fn removed_objects_take_their_derived_nodes_along() {
+        let mut before = ClusterState::new(cluster("test"));
+        before.add_node(object("ingress", ResourceType::Ingress));
+        before.add_derived_node(
+            object("backend", ResourceType::IngressServiceBackend),
+            "ingress",
+        );
+        before.add_derived_node(object("host", ResourceType::Host), "ingress");
+        before.add_derived_node(object("orphan", ResourceType::Host), "missing");
+        assert_eq!(before.get_node_count(), 3);
+
+        let mut ingress = Ingress::default();
+        ingress.metadata.uid = Some("ingress".to_string());
+        let mut previous = ObservedClusterSnapshot::empty();
+        previous.ingresses.push(Arc::new(ingress));
+        let after = ClusterState::new(cluster("test"));
+        let diff = before.diff(&after, &previous, &ObservedClusterSnapshot::empty());
+
+        let mut removed: Vec<&str> = diff
+            .removed_nodes
+            .iter()
+            .map(|node| node.id.uid.as_str())
+            .collect();
+        removed.sort_unstable();
+        assert_eq!(removed, ["backend", "host", "ingress"]);
+    }
+}
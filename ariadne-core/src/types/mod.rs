@@ -1,4 +1,5 @@
 use crate::errors::{AriadneError, ErrorKind};
+use crate::id_gen::logical_uid;
 use crate::prelude::*;
 use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
 use k8s_openapi::api::batch::v1::Job;
@@ -12,6 +13,8 @@ use k8s_openapi::api::networking::v1::{Ingress, NetworkPolicy};
 use k8s_openapi::api::storage::v1::StorageClass;
 use schemars;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::sync::Arc;
 use strum::IntoEnumIterator;
@@ -99,6 +102,22 @@ mod tests {
             assert!(parsed.is_ok(), "missing ResourceType mapping: {resource}");
         }
     }
+
+    #[test]
+    fn object_hashes_ignore_bookkeeping_metadata() {
+        let mut pod = Pod::default();
+        pod.metadata.resource_version = Some("1".to_string());
+        let hash = pod.get_hash();
+
+        pod.metadata.resource_version = Some("2".to_string());
+        assert_eq!(pod.get_hash(), hash);
+
+        pod.status = Some(k8s_openapi::api::core::v1::PodStatus {
+            phase: Some("Running".to_string()),
+            ..Default::default()
+        });
+        assert_ne!(pod.get_hash(), hash);
+    }
 }
 
 #[derive(
@@ -242,6 +261,60 @@ pub struct GenericObject {
     pub attributes: Option<Box<ResourceAttributes>>,
 }
 
+/// Metadata properties that change on every write of an object without saying anything about it.
+const BOOKKEEPING_METADATA: &[&str] = &["resourceVersion", "managedFields"];
+
+impl GenericObject {
+    /// Hash of each top-level property of the object, such as `metadata`, `spec` and `status`.
+    /// The hash of `metadata` leaves out `resourceVersion` and `managedFields`, which change on
+    /// every write.
+    pub fn field_hashes(&self) -> BTreeMap<String, u64> {
+        let Some(attributes) = &self.attributes else {
+            return BTreeMap::new();
+        };
+        // Attributes serialize as `{"Variant": {"field": object}}`.
+        let object = match serde_json::to_value(attributes) {
+            Ok(Value::Object(variants)) => {
+                variants
+                    .into_iter()
+                    .next()
+                    .and_then(|(_, variant)| match variant {
+                        Value::Object(fields) => {
+                            fields.into_iter().next().map(|(_, object)| object)
+                        }
+                        _ => None,
+                    })
+            }
+            _ => None,
+        };
+        let Some(Value::Object(properties)) = object else {
+            return BTreeMap::new();
+        };
+        properties
+            .into_iter()
+            .map(|(name, mut value)| {
+                if name == "metadata" {
+                    strip_bookkeeping(&mut value);
+                }
+                (name, json_hash(&value))
+            })
+            .collect()
+    }
+
+    /// Top-level properties whose value differs from `previous`, including the ones only one of
+    /// the two has.
+    pub fn changed_fields(&self, previous: &GenericObject) -> BTreeSet<String> {
+        let before = previous.field_hashes();
+        let after = self.field_hashes();
+        before
+            .keys()
+            .chain(after.keys())
+            .filter(|field| before.get(*field) != after.get(*field))
+            .cloned()
+            .collect()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, schemars::    JsonSchema)]
 pub struct Cluster {
     pub metadata: k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta,
@@ -469,7 +542,7 @@ pub struct Logs {
 
 impl Logs {
     pub fn new(namespace: &str, name: &str, container_uid: &str, content: String) -> Self {
-        let uid = format!("Logs:{container_uid}");
+        let uid = logical_uid("Logs", &[container_uid]);
         let md = k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta {
             annotations: None,
             creation_timestamp: None,
@@ -552,10 +625,66 @@ fn as_object_meta(
     }
 }
 
+/// Removes the [`BOOKKEEPING_METADATA`] properties from serialized object metadata.
+fn strip_bookkeeping(metadata: &mut Value) {
+    if let Value::Object(metadata) = metadata {
+        for field in BOOKKEEPING_METADATA {
+            metadata.remove(*field);
+        }
+    }
+}
+
+fn json_hash(value: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Hash of what an object says, for telling whether two versions of it differ without comparing
+/// them field by field. Equal objects hash alike within a build; hashes are not meant to be stored.
 pub trait ObjectHasher {
     fn get_hash(&self) -> u64;
 }
 
+/// Implements [`ObjectHasher`] for Kubernetes kinds by hashing their serialized spec, status,
+/// data and metadata, leaving out the [`BOOKKEEPING_METADATA`] that changes on every write.
+macro_rules! impl_object_hasher {
+    ($($kind:ty),* $(,)?) => {
+        $(
+            impl ObjectHasher for $kind {
+                fn get_hash(&self) -> u64 {
+                    let mut value = serde_json::to_value(self).unwrap_or(Value::Null);
+                    if let Some(metadata) = value.get_mut("metadata") {
+                        strip_bookkeeping(metadata);
+                    }
+                    json_hash(&value)
+                }
+            }
+        )*
+    };
+}
+
+impl_object_hasher!(
+    Namespace,
+    Node,
+    Pod,
+    Deployment,
+    StatefulSet,
+    ReplicaSet,
+    DaemonSet,
+    Job,
+    Ingress,
+    Service,
+    EndpointSlice,
+    NetworkPolicy,
+    ConfigMap,
+    StorageClass,
+    PersistentVolume,
+    PersistentVolumeClaim,
+    ServiceAccount,
+    Event,
+);
+
 impl ObjectHasher for k8s_openapi::api::discovery::v1::Endpoint {
     fn get_hash(&self) -> u64 {
         let mut hasher = DefaultHasher::new();
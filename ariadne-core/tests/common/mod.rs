@@ -0,0 +1,90 @@
+//! Docker and Memgraph helpers shared by the tests that need the `memgraph` feature.
+
+// Each test crate includes this module and uses only some of it.
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use ariadne_core::memgraph::Memgraph;
+use ariadne_core::memgraph_async::MemgraphAsync;
+use rsmgclient::ConnectParams;
+use testcontainers::core::ContainerPort;
+use testcontainers::runners::{AsyncRunner, SyncRunner};
+use testcontainers::{Container, ContainerAsync, GenericImage};
+
+pub const MEMGRAPH_PORT: u16 = 7687;
+
+pub fn docker_available() -> bool {
+    if std::env::var("ARIADNE_RUN_DOCKER_TESTS")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+    {
+        return true;
+    }
+    if let Ok(host) = std::env::var("DOCKER_HOST") {
+        if !host.trim().is_empty() {
+            return true;
+        }
+    }
+    std::fs::metadata("/var/run/docker.sock").is_ok()
+}
+
+pub fn memgraph_image() -> GenericImage {
+    GenericImage::new("memgraph/memgraph-mage", "3.7.2")
+        .with_exposed_port(ContainerPort::Tcp(MEMGRAPH_PORT))
+}
+
+pub fn start_memgraph_sync() -> Container<GenericImage> {
+    if !docker_available() {
+        panic!("Docker not available; set ARIADNE_RUN_DOCKER_TESTS=1 to force");
+    }
+    SyncRunner::start(memgraph_image()).expect("failed to start memgraph container")
+}
+
+pub async fn start_memgraph_async() -> ContainerAsync<GenericImage> {
+    if !docker_available() {
+        panic!("Docker not available; set ARIADNE_RUN_DOCKER_TESTS=1 to force");
+    }
+    AsyncRunner::start(memgraph_image())
+        .await
+        .expect("failed to start memgraph container")
+}
+
+pub fn memgraph_params(host_port: u16) -> ConnectParams {
+    ConnectParams {
+        host: Some("127.0.0.1".to_string()),
+        port: host_port,
+        autocommit: true,
+        ..Default::default()
+    }
+}
+
+pub fn wait_for_memgraph(mut make_params: impl FnMut() -> ConnectParams) -> Memgraph {
+    let mut last_err = None;
+    for _ in 0..30 {
+        match Memgraph::try_new(make_params()) {
+            Ok(mg) => return mg,
+            Err(err) => {
+                last_err = Some(err);
+                std::thread::sleep(Duration::from_millis(500));
+            }
+        }
+    }
+    panic!("memgraph did not become ready: {last_err:?}");
+}
+
+pub async fn wait_for_memgraph_async(
+    mut make_params: impl FnMut() -> ConnectParams,
+) -> MemgraphAsync {
+    let mut last_err = None;
+    for _ in 0..30 {
+        match MemgraphAsync::try_new(make_params()) {
+            Ok(mg) => return mg,
+            Err(err) => {
+                last_err = Some(err);
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+        }
+    }
+    panic!("memgraph did not become ready: {last_err:?}");
+}
@@ -0,0 +1,335 @@
+//! Runs random queries from the part of Cypher both backends support against the in-memory
+//! engine and Memgraph, loaded with the same golden fixture state, and checks that they return
+//! the same rows in any order.
+//!
+//! Every run picks a new seed and prints it; `ARIADNE_DIFF_SEED` replays a run and
+//! `ARIADNE_DIFF_CASES` sets how many queries each fixture gets.
+
+mod common;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ariadne_core::fixtures::{golden_fixtures, FIXTURE_CLUSTER_NAME};
+use ariadne_core::graph_backend::GraphBackend;
+use ariadne_core::in_memory::InMemoryBackend;
+use ariadne_core::kube_client::SnapshotKubeClient;
+use ariadne_core::state::{ClusterState, SharedClusterState};
+use ariadne_core::state_resolver::ClusterStateResolver;
+use serde_json::Value;
+use testcontainers::core::ContainerPort;
+
+use common::{
+    docker_available, memgraph_params, start_memgraph_async, wait_for_memgraph_async, MEMGRAPH_PORT,
+};
+
+/// Queries per fixture unless `ARIADNE_DIFF_CASES` is set.
+const DEFAULT_CASES: usize = 200;
+
+/// Name literal that matches no object, so filters also get to reject everything.
+const MISSING_NAME: &str = "no-such-object";
+
+const COMPARISONS: &[&str] = &["=", "<>", "<", ">=", ">"];
+const PROPERTIES: &[&str] = &["metadata.name", "metadata.namespace"];
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// xorshift64*: enough to spread the cases, and replayable from its seed.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // The all-zero state would only ever yield zeros.
+        Self(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next() % 100 < percent
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+/// Labels, relationships and names of one fixture state, which the queries are built from so
+/// that most of them match something.
+struct Vocabulary {
+    labels: Vec<String>,
+    /// `(source label, edge type, target label)` of every relationship in the state.
+    hops: Vec<(String, String, String)>,
+    names: Vec<String>,
+    namespaces: Vec<String>,
+}
+
+impl Vocabulary {
+    fn of(state: &ClusterState) -> Self {
+        let labels: BTreeSet<String> = state
+            .get_nodes()
+            .map(|node| node.resource_type.to_string())
+            .collect();
+        let hops: BTreeSet<(String, String, String)> = state
+            .get_edges()
+            .map(|edge| {
+                (
+                    edge.source_type.to_string(),
+                    edge.edge_type.to_string(),
+                    edge.target_type.to_string(),
+                )
+            })
+            .collect();
+        let names: BTreeSet<String> = state
+            .get_nodes()
+            .map(|node| node.id.name.clone())
+            .filter(|name| !name.is_empty())
+            .collect();
+        let namespaces: BTreeSet<String> = state
+            .get_nodes()
+            .filter_map(|node| node.id.namespace.clone())
+            .collect();
+        Self {
+            labels: labels.into_iter().collect(),
+            hops: hops.into_iter().collect(),
+            names: names
+                .into_iter()
+                .chain([MISSING_NAME.to_string()])
+                .collect(),
+            namespaces: namespaces
+                .into_iter()
+                .chain([MISSING_NAME.to_string()])
+                .collect(),
+        }
+    }
+
+    /// A `MATCH`, an optional `WHERE` and a `RETURN`.
+    fn query(&self, rng: &mut Rng) -> String {
+        let (pattern, variables) = self.pattern(rng);
+        let mut query = format!("MATCH {pattern}");
+        if rng.chance(70) {
+            query.push_str(" WHERE ");
+            query.push_str(&self.predicate(rng, &variables, 2));
+        }
+        query.push(' ');
+        query.push_str(&self.projection(rng, &variables));
+        query
+    }
+
+    /// A node, a hop in either direction or a chain of two hops.
+    fn pattern(&self, rng: &mut Rng) -> (String, Vec<&'static str>) {
+        if self.hops.is_empty() || rng.chance(30) {
+            let label = rng.pick(&self.labels);
+            return (format!("(a:{label})"), vec!["a"]);
+        }
+        let (source, edge, target) = rng.pick(&self.hops);
+        // Chains only run forward: walking back over the same edge type could bind one
+        // relationship twice, which Cypher rules out and the in-memory engine does not check.
+        let next: Vec<_> = self
+            .hops
+            .iter()
+            .filter(|(next_source, _, _)| next_source == target)
+            .collect();
+        if !next.is_empty() && rng.chance(40) {
+            let (_, next_edge, next_target) = rng.pick(&next);
+            let pattern =
+                format!("(a:{source})-[:{edge}]->(b:{target})-[:{next_edge}]->(c:{next_target})");
+            return (pattern, vec!["a", "b", "c"]);
+        }
+        let pattern = if rng.chance(50) {
+            format!("(a:{source})-[:{edge}]->(b:{target})")
+        } else {
+            format!("(b:{target})<-[:{edge}]-(a:{source})")
+        };
+        (pattern, vec!["a", "b"])
+    }
+
+    /// Comparisons, string predicates and null checks on names and namespaces, combined with
+    /// `AND`, `OR` and `NOT` up to `depth` levels deep.
+    fn predicate(&self, rng: &mut Rng, variables: &[&str], depth: usize) -> String {
+        if depth > 0 && rng.chance(40) {
+            let left = self.predicate(rng, variables, depth - 1);
+            return match rng.below(3) {
+                0 => format!("({left} AND {})", self.predicate(rng, variables, depth - 1)),
+                1 => format!("({left} OR {})", self.predicate(rng, variables, depth - 1)),
+                _ => format!("NOT ({left})"),
+            };
+        }
+        let variable = rng.pick(variables);
+        match rng.below(4) {
+            0 => format!(
+                "{variable}.metadata.name {} {}",
+                rng.pick(COMPARISONS),
+                quote(rng.pick(&self.names))
+            ),
+            1 => format!(
+                "{variable}.metadata.namespace {} {}",
+                rng.pick(COMPARISONS),
+                quote(rng.pick(&self.namespaces))
+            ),
+            2 => {
+                let name: Vec<char> = rng.pick(&self.names).chars().collect();
+                let start = rng.below(name.len());
+                let end = start + 1 + rng.below(name.len() - start);
+                let (op, part) = match rng.below(3) {
+                    0 => ("STARTS WITH", &name[..end]),
+                    1 => ("ENDS WITH", &name[start..]),
+                    _ => ("CONTAINS", &name[start..end]),
+                };
+                let part: String = part.iter().collect();
+                format!("{variable}.metadata.name {op} {}", quote(&part))
+            }
+            _ => format!(
+                "{variable}.{} IS {}NULL",
+                rng.pick(PROPERTIES),
+                if rng.chance(50) { "NOT " } else { "" }
+            ),
+        }
+    }
+
+    /// Names and namespaces, optionally distinct, or an aggregate over all rows or grouped by
+    /// one of them; either may be sorted on every column and limited.
+    fn projection(&self, rng: &mut Rng, variables: &[&str]) -> String {
+        let mut columns: Vec<String> = (0..1 + rng.below(2))
+            .map(|index| {
+                let variable = rng.pick(variables);
+                format!("{variable}.{} AS c{index}", rng.pick(PROPERTIES))
+            })
+            .collect();
+        let mut distinct = "";
+        if rng.chance(40) {
+            columns.truncate(rng.below(2));
+            let variable = rng.pick(variables);
+            let aggregate = match rng.below(4) {
+                0 => "count(*)".to_string(),
+                1 => format!("count({variable})"),
+                2 => format!("min({variable}.metadata.name)"),
+                _ => format!("max({variable}.metadata.namespace)"),
+            };
+            columns.push(format!("{aggregate} AS total"));
+        } else if rng.chance(30) {
+            distinct = "DISTINCT ";
+        }
+        let mut clause = format!("RETURN {distinct}{}", columns.join(", "));
+        if rng.chance(40) {
+            // Sorting on every column leaves only identical rows tied, so the limit keeps the
+            // same rows on both backends.
+            let order: Vec<String> = columns
+                .iter()
+                .map(|column| {
+                    let alias = column.rsplit(" AS ").next().unwrap_or_default();
+                    let direction = if rng.chance(50) { " DESC" } else { "" };
+                    format!("{alias}{direction}")
+                })
+                .collect();
+            clause.push_str(&format!(
+                " ORDER BY {} LIMIT {}",
+                order.join(", "),
+                1 + rng.below(5)
+            ));
+        }
+        clause
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Rows serialized with sorted keys and then sorted themselves, so results compare regardless
+/// of the order either backend returns them in.
+fn canonical(rows: &[Value]) -> Vec<String> {
+    let mut rows: Vec<String> = rows
+        .iter()
+        .map(|row| match row {
+            Value::Object(columns) => {
+                let sorted: BTreeMap<&String, &Value> = columns.iter().collect();
+                serde_json::to_string(&sorted).unwrap()
+            }
+            other => other.to_string(),
+        })
+        .collect();
+    rows.sort();
+    rows
+}
+
+async fn resolve_fixture(dir: &Path) -> SharedClusterState {
+    let client = SnapshotKubeClient::from_dir(dir).unwrap();
+    let resolver = ClusterStateResolver::new_with_kube_client(
+        FIXTURE_CLUSTER_NAME.to_string(),
+        Box::new(client),
+    )
+    .await
+    .unwrap();
+    resolver.resolve().await.unwrap()
+}
+
+#[tokio::test]
+async fn random_queries_return_the_same_rows_on_both_backends() {
+    if !docker_available() {
+        eprintln!("Skipping differential test; Docker not available");
+        return;
+    }
+    let default_seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or(1);
+    let seed = env_or("ARIADNE_DIFF_SEED", default_seed);
+    let cases = env_or("ARIADNE_DIFF_CASES", DEFAULT_CASES);
+    eprintln!("Differential test with ARIADNE_DIFF_SEED={seed}, {cases} queries per fixture");
+
+    let container = start_memgraph_async().await;
+    let host_port = container
+        .get_host_port_ipv4(ContainerPort::Tcp(MEMGRAPH_PORT))
+        .await
+        .expect("failed to map memgraph port");
+    let memgraph = wait_for_memgraph_async(|| memgraph_params(host_port)).await;
+    let in_memory = InMemoryBackend::new();
+
+    let mut rng = Rng::new(seed);
+    let root = std::env::temp_dir().join(format!("ariadne-differential-{}", std::process::id()));
+    for fixture in golden_fixtures() {
+        let dir = root.join(fixture.name);
+        fixture.write_to_dir(&dir).unwrap();
+        let state = resolve_fixture(&dir).await;
+        let vocabulary = Vocabulary::of(&state.load());
+        in_memory.create(state.clone()).await.unwrap();
+        memgraph.create(state).await.unwrap();
+
+        for _ in 0..cases {
+            let query = vocabulary.query(&mut rng);
+            let expected = in_memory
+                .execute_query(query.clone(), None)
+                .await
+                .unwrap_or_else(|err| panic!("in-memory engine failed on `{query}`: {err}"));
+            let actual = memgraph
+                .execute_query(query.clone(), None)
+                .await
+                .unwrap_or_else(|err| panic!("Memgraph failed on `{query}`: {err}"));
+            assert_eq!(
+                canonical(&expected),
+                canonical(&actual),
+                "{}: in-memory engine (left) and Memgraph (right) disagree on `{query}`; \
+                 replay with ARIADNE_DIFF_SEED={seed}",
+                fixture.name
+            );
+        }
+    }
+    let _ = std::fs::remove_dir_all(root);
+    memgraph.shutdown().await;
+}
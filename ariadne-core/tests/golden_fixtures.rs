@@ -0,0 +1,59 @@
+use std::path::{Path, PathBuf};
+
+use ariadne_core::fixtures::{golden_fixtures, read_expected_from_dir, FIXTURE_CLUSTER_NAME};
+use ariadne_core::graph_backend::GraphBackend;
+use ariadne_core::in_memory::InMemoryBackend;
+use ariadne_core::kube_client::SnapshotKubeClient;
+use ariadne_core::state_resolver::ClusterStateResolver;
+
+/// Resolves the snapshot in `dir` into the in-memory backend and checks every expected query.
+async fn assert_fixture_dir(dir: &Path) {
+    let client = SnapshotKubeClient::from_dir(dir).unwrap();
+    let resolver = ClusterStateResolver::new_with_kube_client(
+        FIXTURE_CLUSTER_NAME.to_string(),
+        Box::new(client),
+    )
+    .await
+    .unwrap();
+    let backend = InMemoryBackend::new();
+    backend
+        .create(resolver.resolve().await.unwrap())
+        .await
+        .unwrap();
+
+    for expected in read_expected_from_dir(dir).unwrap() {
+        let rows = backend
+            .execute_query(expected.query.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(rows, expected.rows, "{}: {}", dir.display(), expected.query);
+    }
+}
+
+#[tokio::test]
+async fn golden_fixtures_return_expected_rows() {
+    let root = std::env::temp_dir().join(format!("ariadne-golden-{}", std::process::id()));
+    for fixture in golden_fixtures() {
+        let dir = root.join(fixture.name);
+        fixture.write_to_dir(&dir).unwrap();
+        assert_fixture_dir(&dir).await;
+    }
+    let _ = std::fs::remove_dir_all(root);
+}
+
+/// Checks fixtures rendered by `ariadne-tools fixtures` into `ARIADNE_FIXTURES_DIR`, if set.
+#[tokio::test]
+async fn rendered_fixtures_return_expected_rows() {
+    let Ok(root) = std::env::var("ARIADNE_FIXTURES_DIR") else {
+        return;
+    };
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(root)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    for dir in dirs {
+        assert_fixture_dir(&dir).await;
+    }
+}
@@ -1,94 +1,21 @@
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
+mod common;
 
-use ariadne_core::memgraph::Memgraph;
-use ariadne_core::memgraph_async::MemgraphAsync;
-use ariadne_core::state::{ClusterState, ClusterStateDiff, GraphEdge};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+
+use ariadne_core::state::{ClusterState, ClusterStateDiff, GraphEdge, SharedClusterState};
 use ariadne_core::types::{
     Cluster, Edge, GenericObject, ObjectIdentifier, ResourceAttributes, ResourceType,
 };
 use k8s_openapi::api::core::v1::Namespace;
 use k8s_openapi::apimachinery::pkg::version::Info;
-use rsmgclient::ConnectParams;
 use serde_json::Value;
 use testcontainers::core::ContainerPort;
-use testcontainers::runners::{AsyncRunner, SyncRunner};
-use testcontainers::{Container, ContainerAsync, GenericImage};
-
-const MEMGRAPH_PORT: u16 = 7687;
-
-fn docker_available() -> bool {
-    if std::env::var("ARIADNE_RUN_DOCKER_TESTS")
-        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
-        .unwrap_or(false)
-    {
-        return true;
-    }
-    if let Ok(host) = std::env::var("DOCKER_HOST") {
-        if !host.trim().is_empty() {
-            return true;
-        }
-    }
-    std::fs::metadata("/var/run/docker.sock").is_ok()
-}
-
-fn memgraph_image() -> GenericImage {
-    GenericImage::new("memgraph/memgraph-mage", "3.7.2")
-        .with_exposed_port(ContainerPort::Tcp(MEMGRAPH_PORT))
-}
 
-fn start_memgraph_sync() -> Container<GenericImage> {
-    if !docker_available() {
-        panic!("Docker not available; set ARIADNE_RUN_DOCKER_TESTS=1 to force");
-    }
-    SyncRunner::start(memgraph_image()).expect("failed to start memgraph container")
-}
-
-async fn start_memgraph_async() -> ContainerAsync<GenericImage> {
-    if !docker_available() {
-        panic!("Docker not available; set ARIADNE_RUN_DOCKER_TESTS=1 to force");
-    }
-    AsyncRunner::start(memgraph_image())
-        .await
-        .expect("failed to start memgraph container")
-}
-
-fn memgraph_params(host_port: u16) -> ConnectParams {
-    ConnectParams {
-        host: Some("127.0.0.1".to_string()),
-        port: host_port,
-        autocommit: true,
-        ..Default::default()
-    }
-}
-
-fn wait_for_memgraph(mut make_params: impl FnMut() -> ConnectParams) -> Memgraph {
-    let mut last_err = None;
-    for _ in 0..30 {
-        match Memgraph::try_new(make_params()) {
-            Ok(mg) => return mg,
-            Err(err) => {
-                last_err = Some(err);
-                std::thread::sleep(Duration::from_millis(500));
-            }
-        }
-    }
-    panic!("memgraph did not become ready: {last_err:?}");
-}
-
-async fn wait_for_memgraph_async(mut make_params: impl FnMut() -> ConnectParams) -> MemgraphAsync {
-    let mut last_err = None;
-    for _ in 0..30 {
-        match MemgraphAsync::try_new(make_params()) {
-            Ok(mg) => return mg,
-            Err(err) => {
-                last_err = Some(err);
-                tokio::time::sleep(Duration::from_millis(500)).await;
-            }
-        }
-    }
-    panic!("memgraph did not become ready: {last_err:?}");
-}
+use common::{
+    docker_available, memgraph_params, start_memgraph_async, start_memgraph_sync,
+    wait_for_memgraph, wait_for_memgraph_async, MEMGRAPH_PORT,
+};
 
 fn build_cluster(uid: &str, name: &str) -> (Cluster, GenericObject) {
     let id = ObjectIdentifier {
@@ -199,6 +126,7 @@ fn memgraph_update_from_diff_applies_changes() {
         added_nodes: vec![namespace_obj],
         removed_nodes: vec![],
         modified_nodes: vec![],
+        modified_fields: HashMap::new(),
         added_edges: vec![edge],
         removed_edges: vec![],
     };
@@ -210,6 +138,29 @@ fn memgraph_update_from_diff_applies_changes() {
         .expect("execute_query failed");
 
     assert_eq!(extract_count(&results, "cnt"), 1);
+
+    let previous = build_namespace("ns-uid", "test-namespace");
+    let mut labelled = build_namespace("ns-uid", "test-namespace");
+    if let Some(ResourceAttributes::Namespace { namespace }) = labelled.attributes.as_deref_mut() {
+        Arc::make_mut(namespace).metadata.labels =
+            Some(BTreeMap::from([("team".to_string(), "shop".to_string())]));
+    }
+    let changed: BTreeSet<String> = labelled.changed_fields(&previous);
+    assert_eq!(changed, BTreeSet::from(["metadata".to_string()]));
+    let diff = ClusterStateDiff {
+        modified_fields: HashMap::from([("ns-uid".to_string(), changed)]),
+        modified_nodes: vec![labelled],
+        ..ClusterStateDiff::default()
+    };
+    mg.update_from_diff(&diff)
+        .expect("targeted update_from_diff failed");
+
+    let results = mg
+        .execute_query(
+            "MATCH (n:Namespace) WHERE n.metadata.labels.team = 'shop' RETURN count(n) AS cnt",
+        )
+        .expect("execute_query failed");
+    assert_eq!(extract_count(&results, "cnt"), 1);
 }
 
 #[tokio::test]
@@ -238,7 +189,7 @@ async fn memgraph_async_create_and_query() {
         Edge::PartOf,
     );
 
-    mg.create(Arc::new(Mutex::new(state)))
+    mg.create(SharedClusterState::new(state))
         .await
         .expect("memgraph_async create failed");
 
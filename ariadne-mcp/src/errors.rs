@@ -1,4 +1,4 @@
-use ariadne_core::errors::AriadneError;
+use ariadne_core::errors::{AriadneError, ErrorClass};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
@@ -26,9 +26,36 @@ where
 }
 
 impl AppError {
+    /// Bad input is the client's fault; anything else is retriable (503) or not (502 for the
+    /// cluster, backend or model, 500 for the server itself).
     fn get_codes(&self) -> (StatusCode, u16) {
-        match *self.0 {
-            ErrorKind::Ariadne(_) => (StatusCode::BAD_REQUEST, 40001),
+        match &*self.0 {
+            ErrorKind::Ariadne(err) => match (err.class(), err.is_retriable()) {
+                (ErrorClass::Parse, _) => (StatusCode::BAD_REQUEST, 40001),
+                (_, true) => (StatusCode::SERVICE_UNAVAILABLE, 50301),
+                (ErrorClass::Io, false) => (StatusCode::INTERNAL_SERVER_ERROR, 50001),
+                (ErrorClass::Kube | ErrorClass::Backend | ErrorClass::Llm, false) => {
+                    (StatusCode::BAD_GATEWAY, 50201)
+                }
+            },
+        }
+    }
+
+    fn class(&self) -> ErrorClass {
+        match &*self.0 {
+            ErrorKind::Ariadne(err) => err.class(),
+        }
+    }
+
+    fn is_retriable(&self) -> bool {
+        match &*self.0 {
+            ErrorKind::Ariadne(err) => err.is_retriable(),
+        }
+    }
+
+    fn context(&self) -> Vec<String> {
+        match &*self.0 {
+            ErrorKind::Ariadne(err) => err.context().to_vec(),
         }
     }
 }
@@ -37,13 +64,25 @@ impl AppError {
 pub struct ErrorCode {
     pub code: u16,
     pub message: String,
+    pub class: Option<ErrorClass>,
+    /// Whether repeating the request may succeed.
+    #[serde(default)]
+    pub retriable: bool,
+    /// What the server was doing when the error happened, innermost first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<String>,
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status_code, code) = self.get_codes();
-        let message = self.to_string();
-        let body = Json(ErrorCode { code, message });
+        let body = Json(ErrorCode {
+            code,
+            message: self.to_string(),
+            class: Some(self.class()),
+            retriable: self.is_retriable(),
+            context: self.context(),
+        });
         (status_code, body).into_response()
     }
 }
@@ -12,8 +12,9 @@ use rmcp::{
 };
 use serde_json::json;
 
+use ariadne_core::errors::ErrorClass;
 use ariadne_core::graph_backend::GraphBackend;
-use ariadne_tools::{full_prompt, graph_relationships, schema_prompt};
+use ariadne_tools::{full_prompt_with, graph_relationships, schema_prompt, PromptVariables};
 use rmcp::service::RequestContext;
 use std::sync::{Arc, OnceLock};
 
@@ -59,7 +60,17 @@ impl KubeTool {
                 .await
                 .map_err(|e| {
                     tracing::error!(cypher = %query, error = %e, "execute_cypher_query failed");
-                    ErrorData::internal_error(e.to_string(), Some(json!({ "cypher": query })))
+                    let data = json!({
+                        "cypher": query,
+                        "class": e.class(),
+                        "retriable": e.is_retriable(),
+                        "context": e.context(),
+                    });
+                    if e.class() == ErrorClass::Parse {
+                        ErrorData::invalid_params(e.to_string(), Some(data))
+                    } else {
+                        ErrorData::internal_error(e.to_string(), Some(data))
+                    }
                 })?;
             records
         };
@@ -88,8 +99,16 @@ impl KubeTool {
 
 static PROMPT_CACHE: OnceLock<String> = OnceLock::new();
 
-fn current_prompt() -> &'static str {
-    PROMPT_CACHE.get_or_init(full_prompt).as_str()
+/// The prompt of the server's cluster; a server serves a single cluster.
+fn current_prompt(cluster_name: &str) -> &'static str {
+    PROMPT_CACHE
+        .get_or_init(|| {
+            full_prompt_with(&PromptVariables {
+                cluster_name: Some(cluster_name.to_string()),
+                ..Default::default()
+            })
+        })
+        .as_str()
 }
 
 #[tool_handler]
@@ -175,7 +194,10 @@ Use the tools to answer questions by querying the graph:\n\
                         ErrorData::invalid_params("No message provided to analyze_question", None)
                     })?;
 
-                let prompt = format!("{}\n\nUser question: '{question}'", current_prompt());
+                let prompt = format!(
+                    "{}\n\nUser question: '{question}'",
+                    current_prompt(&self.cluster_name)
+                );
                 Ok(GetPromptResult {
                     description: None,
                     messages: vec![PromptMessage {
@@ -1,15 +1,16 @@
 use ariadne_core::errors::AriadneError;
 use ariadne_core::graph_backend::GraphBackend;
-use ariadne_core::kube_client::SnapshotKubeClient;
+use ariadne_core::kube_client::{CachedKubeClient, KubeClient, SnapshotKubeClient};
 use ariadne_core::memgraph_async::MemgraphAsync;
-use ariadne_core::state_resolver::ClusterStateResolver;
+use ariadne_core::redaction::{RedactionPolicy, DEFAULT_REDACTION_PATTERNS};
+use ariadne_core::state_resolver::{ClusterStateResolver, ResolverOptions};
 use axum::http::header;
 use axum::middleware::map_response;
 use axum::response::Response;
 use axum::routing::get;
 use axum::Router;
 use axum_prometheus::PrometheusMetricLayer;
-use clap::{Parser, Subcommand};
+use clap::{ArgAction, Parser, Subcommand};
 use kube::config::KubeConfigOptions;
 use shadow_rs::shadow;
 use std::net::SocketAddr;
@@ -44,6 +45,17 @@ struct Cli {
     kube_context: Option<String>,
     #[arg(long, env = "KUBE_NAMESPACE")]
     kube_namespace: Option<String>,
+    /// Mask ConfigMap values and container env vars whose name matches --redact-patterns
+    #[arg(long, env = "REDACTION", default_value_t = true, action = ArgAction::Set)]
+    redaction: bool,
+    /// Case-insensitive name fragments whose values are masked
+    #[arg(
+        long,
+        env = "REDACT_PATTERNS",
+        value_delimiter = ',',
+        default_values_t = DEFAULT_REDACTION_PATTERNS.iter().map(ToString::to_string)
+    )]
+    redact_patterns: Vec<String>,
 }
 
 #[derive(Subcommand)]
@@ -141,14 +153,26 @@ async fn main() -> errors::Result<()> {
         cluster: None,
         user: None,
     };
+    let options = ResolverOptions {
+        redaction: if cli.redaction {
+            RedactionPolicy::new(&cli.redact_patterns)
+        } else {
+            RedactionPolicy::disabled()
+        },
+        ..Default::default()
+    };
 
     if let Some(Command::Snapshot {
         command: SnapshotCommand::Export { output_dir },
     }) = cli.command
     {
-        let resolver =
-            ClusterStateResolver::new(cluster_name.clone(), &kube_opts, kube_namespace.as_deref())
-                .await?;
+        let kube_client = CachedKubeClient::new(&kube_opts, kube_namespace.as_deref()).await?;
+        let resolver = ClusterStateResolver::new_with_options(
+            cluster_name.clone(),
+            Box::new(kube_client),
+            options,
+        )
+        .await?;
         resolver.export_observed_snapshot_dir(output_dir)?;
         info!("Snapshot export complete");
         return Ok(());
@@ -158,15 +182,14 @@ async fn main() -> errors::Result<()> {
         Arc::new(MemgraphAsync::try_new_from_url(memgraph_uri.as_str())?);
 
     let snapshot_dir: Option<String> = std::env::var("KUBE_SNAPSHOT_DIR").ok();
-    let resolver = if let Some(snapshot_dir) = snapshot_dir {
+    let kube_client: Box<dyn KubeClient> = if let Some(snapshot_dir) = snapshot_dir {
         info!("Loading snapshot from directory: {snapshot_dir}");
-        let snapshot_client = SnapshotKubeClient::from_dir(snapshot_dir)?;
-        ClusterStateResolver::new_with_kube_client(cluster_name.clone(), Box::new(snapshot_client))
-            .await?
+        Box::new(SnapshotKubeClient::from_dir(snapshot_dir)?)
     } else {
-        ClusterStateResolver::new(cluster_name.clone(), &kube_opts, kube_namespace.as_deref())
-            .await?
+        Box::new(CachedKubeClient::new(&kube_opts, kube_namespace.as_deref()).await?)
     };
+    let resolver =
+        ClusterStateResolver::new_with_options(cluster_name.clone(), kube_client, options).await?;
     let cluster_state = resolver.resolve().await?;
     memgraph.create(cluster_state.clone()).await?;
 
@@ -321,3 +344,18 @@ async fn shutdown_signal(token: CancellationToken) {
 
     println!("signal received, starting graceful shutdown");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redaction_can_be_turned_off() {
+        let cli = Cli::try_parse_from(["ariadne-mcp", "--cluster", "prod", "--redaction", "false"])
+            .expect("parse");
+        assert!(!cli.redaction);
+
+        let cli = Cli::try_parse_from(["ariadne-mcp", "--cluster", "prod"]).expect("parse");
+        assert!(cli.redaction);
+    }
+}
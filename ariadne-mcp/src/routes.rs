@@ -42,8 +42,7 @@ pub async fn create_route(
 
 #[tracing::instrument(level = "INFO")]
 async fn get_graph(State(state): State<AppState>) -> Json<DirectedGraph> {
-    let lock = state.cluster_state.lock().unwrap();
-    Json(lock.to_directed_graph())
+    Json(state.cluster_state.load().to_directed_graph())
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -58,10 +57,7 @@ async fn get_metadata(State(state): State<AppState>) -> Json<GraphMetadata> {
     let resource_types: Vec<ResourceType> = ResourceType::iter().collect();
     let edge_types: Vec<Edge> = Edge::iter().collect();
 
-    let cluster = {
-        let lock = state.cluster_state.lock().unwrap();
-        lock.cluster.clone()
-    };
+    let cluster = state.cluster_state.load().cluster.clone();
 
     Json(GraphMetadata {
         cluster,
@@ -0,0 +1,329 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use ariadne_core::graph_schema::{
+    graph_relationships, GRAPH_SCHEMA_VERSION, INDEXED_PROPERTIES, SCHEMA_VERSION_LABEL,
+};
+use ariadne_core::memgraph::Memgraph;
+use ariadne_core::prelude::Result;
+use rsmgclient::ConnectParams;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{generate_schema, graph_labels};
+
+/// What a database holds, as far as the graph model describes it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BackendContents {
+    pub labels: BTreeSet<String>,
+    /// `(:From)-[:Edge]->(:To)` patterns of the relationships.
+    pub relationships: BTreeSet<String>,
+    /// Top-level property keys of the nodes of each label.
+    pub properties: BTreeMap<String, BTreeSet<String>>,
+    /// `Label(property)` of every index.
+    pub indexes: BTreeSet<String>,
+    /// Schema version the graph is stamped with.
+    pub schema_version: Option<u32>,
+}
+
+/// How a database differs from the graph model.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BackendDrift {
+    /// Labels in the database that no relationship of the model starts or ends at.
+    pub unknown_labels: Vec<String>,
+    /// Relationships in the database the model does not declare between their labels.
+    pub unknown_relationships: Vec<String>,
+    /// `Label.property` of node properties the schema of their label does not have.
+    pub unknown_properties: Vec<String>,
+    /// `Label(property)` of the indexes ariadne creates that a label in the database lacks.
+    pub missing_indexes: Vec<String>,
+    /// Labels of the model without nodes in the database; a small cluster may simply lack them.
+    pub absent_labels: Vec<String>,
+    /// Relationships of the model without edges in the database.
+    pub absent_relationships: Vec<String>,
+    /// Schema version the graph is stamped with, when it is not the binary's.
+    pub other_schema_version: Option<u32>,
+}
+
+impl BackendDrift {
+    /// Compares what a database holds with the relationships and node schemas of the model.
+    pub fn between(contents: &BackendContents) -> Self {
+        let model_labels = graph_labels();
+        let mut labels = contents.labels.clone();
+        labels.remove(SCHEMA_VERSION_LABEL);
+        let model_relationships: BTreeSet<String> = graph_relationships()
+            .iter()
+            .map(|relationship| {
+                relationship_pattern(&relationship.from, &relationship.edge, &relationship.to)
+            })
+            .collect();
+        let model_properties: BTreeMap<String, BTreeSet<String>> = generate_schema()
+            .into_iter()
+            .map(|info| {
+                let properties = info
+                    .root_type
+                    .properties
+                    .into_iter()
+                    .map(|property| property.name)
+                    .collect();
+                (info.root_type.name, properties)
+            })
+            .collect();
+
+        let mut unknown_properties = Vec::new();
+        for (label, keys) in &contents.properties {
+            if let Some(known) = model_properties.get(label) {
+                unknown_properties
+                    .extend(keys.difference(known).map(|key| format!("{label}.{key}")));
+            }
+        }
+        let missing_indexes = labels
+            .intersection(&model_labels)
+            .flat_map(|label| {
+                INDEXED_PROPERTIES
+                    .iter()
+                    .map(move |property| format!("{label}({property})"))
+            })
+            .filter(|index| !contents.indexes.contains(index))
+            .collect();
+        Self {
+            unknown_labels: labels.difference(&model_labels).cloned().collect(),
+            unknown_relationships: contents
+                .relationships
+                .difference(&model_relationships)
+                .cloned()
+                .collect(),
+            unknown_properties,
+            missing_indexes,
+            absent_labels: model_labels.difference(&labels).cloned().collect(),
+            absent_relationships: model_relationships
+                .difference(&contents.relationships)
+                .cloned()
+                .collect(),
+            other_schema_version: contents
+                .schema_version
+                .filter(|version| *version != GRAPH_SCHEMA_VERSION),
+        }
+    }
+
+    /// Whether the database holds what the model does not know or lacks the indexes ariadne
+    /// looks nodes up by.
+    pub fn has_drift(&self) -> bool {
+        !self.unknown_labels.is_empty()
+            || !self.unknown_relationships.is_empty()
+            || !self.unknown_properties.is_empty()
+            || !self.missing_indexes.is_empty()
+            || self.other_schema_version.is_some()
+    }
+}
+
+impl fmt::Display for BackendDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.has_drift() {
+            writeln!(f, "The database matches the graph model.")?;
+        }
+        if let Some(version) = self.other_schema_version {
+            writeln!(
+                f,
+                "Schema version: {version}, this binary writes {GRAPH_SCHEMA_VERSION}"
+            )?;
+        }
+        write_names(f, "Unknown labels", &self.unknown_labels)?;
+        write_names(f, "Unknown relationships", &self.unknown_relationships)?;
+        write_names(f, "Unknown properties", &self.unknown_properties)?;
+        write_names(f, "Missing indexes", &self.missing_indexes)?;
+        write_names(f, "Labels without nodes", &self.absent_labels)?;
+        write_names(f, "Relationships without edges", &self.absent_relationships)
+    }
+}
+
+fn write_names(f: &mut fmt::Formatter<'_>, title: &str, names: &[String]) -> fmt::Result {
+    if names.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, "{title}: {}", names.join(", "))
+}
+
+fn relationship_pattern(from: &str, edge: &str, to: &str) -> String {
+    format!("(:{from})-[:{edge}]->(:{to})")
+}
+
+fn string_field<'a>(row: &'a Value, name: &str) -> &'a str {
+    row.get(name).and_then(Value::as_str).unwrap_or_default()
+}
+
+/// Node counts by label and relationship counts by type of a database.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BackendStats {
+    pub nodes: BTreeMap<String, u64>,
+    pub relationships: BTreeMap<String, u64>,
+}
+
+impl fmt::Display for BackendStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Nodes: {}", self.nodes.values().sum::<u64>())?;
+        for (label, count) in &self.nodes {
+            writeln!(f, "  {label}: {count}")?;
+        }
+        writeln!(
+            f,
+            "Relationships: {}",
+            self.relationships.values().sum::<u64>()
+        )?;
+        for (edge, count) in &self.relationships {
+            writeln!(f, "  {edge}: {count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the database at `graph_url` with the graph model.
+pub fn validate_backend(graph_url: &str) -> Result<BackendDrift> {
+    // Index info is not available inside the transactions queries otherwise run in.
+    let mut graph = Memgraph::try_new(ConnectParams {
+        autocommit: true,
+        ..Memgraph::connect_params_from_url(graph_url)?
+    })?;
+    Ok(BackendDrift::between(&backend_contents(&mut graph)?))
+}
+
+/// Reads the labels, relationships, property keys and indexes of a database.
+fn backend_contents(graph: &mut Memgraph) -> Result<BackendContents> {
+    let mut contents = BackendContents {
+        schema_version: graph.stored_schema_version()?,
+        ..Default::default()
+    };
+    for row in graph.execute_query("MATCH (n) UNWIND labels(n) AS label RETURN DISTINCT label")? {
+        if let Some(label) = row.get("label").and_then(Value::as_str) {
+            contents.labels.insert(label.to_string());
+        }
+    }
+    for row in graph.execute_query(
+        "MATCH (a)-[r]->(b) RETURN DISTINCT labels(a)[0] AS from, type(r) AS edge, \
+         labels(b)[0] AS to",
+    )? {
+        contents.relationships.insert(relationship_pattern(
+            string_field(&row, "from"),
+            string_field(&row, "edge"),
+            string_field(&row, "to"),
+        ));
+    }
+    for row in graph.execute_query(
+        "MATCH (n) UNWIND labels(n) AS label UNWIND keys(n) AS key RETURN DISTINCT label, key",
+    )? {
+        if let (Some(label), Some(key)) = (
+            row.get("label").and_then(Value::as_str),
+            row.get("key").and_then(Value::as_str),
+        ) {
+            contents
+                .properties
+                .entry(label.to_string())
+                .or_default()
+                .insert(key.to_string());
+        }
+    }
+    for row in graph.execute_query("SHOW INDEX INFO")? {
+        let label = row.get("label").and_then(Value::as_str);
+        // Nested properties are listed as their path, either joined or as a list of keys.
+        let property = match row.get("property") {
+            Some(Value::String(property)) => Some(property.clone()),
+            Some(Value::Array(keys)) => Some(
+                keys.iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join("."),
+            ),
+            _ => None,
+        };
+        if let (Some(label), Some(property)) = (label, property) {
+            contents.indexes.insert(format!("{label}({property})"));
+        }
+    }
+    Ok(contents)
+}
+
+/// Counts the nodes and relationships of the database at `graph_url`.
+pub fn backend_stats(graph_url: &str) -> Result<BackendStats> {
+    let mut graph = Memgraph::try_new_from_url(graph_url)?;
+    let nodes = graph.execute_query(
+        "MATCH (n) UNWIND labels(n) AS name RETURN name, count(*) AS count ORDER BY name",
+    )?;
+    let relationships = graph.execute_query(
+        "MATCH ()-[r]->() RETURN type(r) AS name, count(*) AS count ORDER BY name",
+    )?;
+    Ok(BackendStats {
+        nodes: counts(&nodes),
+        relationships: counts(&relationships),
+    })
+}
+
+fn counts(rows: &[Value]) -> BTreeMap<String, u64> {
+    rows.iter()
+        .filter_map(|row| {
+            let name = row.get("name")?.as_str()?;
+            let count = row.get("count")?.as_u64()?;
+            Some((name.to_string(), count))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contents_outside_the_model_are_drift() {
+        let mut contents = BackendContents {
+            labels: ["Pod", "Node", SCHEMA_VERSION_LABEL]
+                .map(String::from)
+                .into(),
+            relationships: ["(:Pod)-[:RunsOn]->(:Node)".to_string()].into(),
+            properties: [(
+                "Pod".to_string(),
+                ["metadata", "spec", "status"].map(String::from).into(),
+            )]
+            .into(),
+            indexes: ["Pod", "Node"]
+                .iter()
+                .flat_map(|label| {
+                    INDEXED_PROPERTIES
+                        .iter()
+                        .map(move |property| format!("{label}({property})"))
+                })
+                .collect(),
+            schema_version: Some(GRAPH_SCHEMA_VERSION),
+        };
+        let drift = BackendDrift::between(&contents);
+        assert!(!drift.has_drift(), "{drift}");
+        assert!(drift.absent_labels.contains(&"Service".to_string()));
+        assert!(drift
+            .absent_relationships
+            .contains(&"(:Pod)-[:BelongsTo]->(:Namespace)".to_string()));
+
+        contents.labels.insert("Widget".to_string());
+        contents
+            .relationships
+            .insert("(:Node)-[:RunsOn]->(:Pod)".to_string());
+        contents
+            .properties
+            .get_mut("Pod")
+            .unwrap()
+            .insert("owner".to_string());
+        contents.indexes.remove("Node(metadata.uid)");
+        let drift = BackendDrift::between(&contents);
+        assert_eq!(drift.unknown_labels, vec!["Widget".to_string()]);
+        assert_eq!(
+            drift.unknown_relationships,
+            vec!["(:Node)-[:RunsOn]->(:Pod)".to_string()]
+        );
+        assert_eq!(drift.unknown_properties, vec!["Pod.owner".to_string()]);
+        assert_eq!(
+            drift.missing_indexes,
+            vec!["Node(metadata.uid)".to_string()]
+        );
+
+        contents.schema_version = Some(GRAPH_SCHEMA_VERSION + 1);
+        let drift = BackendDrift::between(&contents);
+        assert_eq!(drift.other_schema_version, Some(GRAPH_SCHEMA_VERSION + 1));
+    }
+}
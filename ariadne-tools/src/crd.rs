@@ -0,0 +1,157 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use ariadne_core::prelude::Result;
+use ariadne_core::snapshot::{read_list_from_dir, SNAPSHOT_CUSTOM_RESOURCE_DEFINITIONS_FILE};
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use schemars::Schema;
+use serde_json::{json, Map, Value};
+
+use crate::schema::{self, SchemaInfo};
+
+/// What the properties named `metadata` of custom resources refer to, as for built-in kinds.
+const OBJECT_META_REFERENCE: &str = "#/$defs/io.k8s.apimachinery.pkg.apis.meta.v1.ObjectMeta";
+
+/// Reads custom resource definitions from a snapshot directory, or from a file holding either a
+/// list of them or the `List` that `kubectl get crd -o json` prints.
+pub fn load_crds(path: &Path) -> Result<Vec<Arc<CustomResourceDefinition>>> {
+    if path.is_dir() {
+        return read_list_from_dir(path, SNAPSHOT_CUSTOM_RESOURCE_DEFINITIONS_FILE);
+    }
+    let value: Value = serde_json::from_slice(&std::fs::read(path)?)?;
+    let items = match value {
+        Value::Object(mut list) if list.contains_key("items") => {
+            list.remove("items").unwrap_or_default()
+        }
+        value => value,
+    };
+    let crds: Vec<CustomResourceDefinition> = serde_json::from_value(items)?;
+    Ok(crds.into_iter().map(Arc::new).collect())
+}
+
+/// The node type of each custom resource, derived from the OpenAPI schema of its storage
+/// version. Nested objects become referenced types named after their path, such as
+/// `cert-manager.io.v1.CertificateSpec`.
+pub fn crd_schemas(crds: &[Arc<CustomResourceDefinition>]) -> Vec<SchemaInfo> {
+    crds.iter().filter_map(|crd| crd_schema(crd)).collect()
+}
+
+fn crd_schema(crd: &CustomResourceDefinition) -> Option<SchemaInfo> {
+    let version = crd
+        .spec
+        .versions
+        .iter()
+        .find(|version| version.storage)
+        .or_else(|| crd.spec.versions.iter().find(|version| version.served))?;
+    let open_api_schema = version.schema.as_ref()?.open_api_v3_schema.as_ref()?;
+    let kind = &crd.spec.names.kind;
+    let mut root = serde_json::to_value(open_api_schema).ok()?;
+    let mut defs = Map::new();
+    let prefix = format!("{}.{}.{kind}", crd.spec.group, version.name);
+    hoist_nested_objects(&mut root, &prefix, &mut defs);
+    if let Some(metadata) = root
+        .get_mut("properties")
+        .and_then(|properties| properties.get_mut("metadata"))
+    {
+        *metadata = json!({ "$ref": OBJECT_META_REFERENCE });
+    }
+    let mut document = root.as_object().cloned().unwrap_or_default();
+    document.insert("title".to_string(), json!(kind));
+    document.insert("$defs".to_string(), Value::Object(defs));
+    let schema = Schema::try_from(Value::Object(document)).ok()?;
+    Some(schema::get_schema(&schema))
+}
+
+/// Moves the object schemas with properties below `value` into `defs`, named `name` followed by
+/// their property path in PascalCase, and references them instead.
+fn hoist_nested_objects(value: &mut Value, name: &str, defs: &mut Map<String, Value>) {
+    let Some(properties) = value.get_mut("properties").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for (property_name, property) in properties.iter_mut() {
+        let nested_name = format!("{name}{}", pascal_case(property_name));
+        let is_array = property.get("type").and_then(Value::as_str) == Some("array");
+        let target = if is_array {
+            match property.get_mut("items") {
+                Some(items) => items,
+                None => continue,
+            }
+        } else {
+            property
+        };
+        if target.get("properties").is_some() {
+            hoist_nested_objects(target, &nested_name, defs);
+            let definition =
+                std::mem::replace(target, json!({ "$ref": format!("#/$defs/{nested_name}") }));
+            defs.insert(nested_name, definition);
+        }
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split(['-', '_', '.'])
+        .map(|segment| {
+            let mut chars = segment.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::write_compact_schema_prompt;
+
+    #[test]
+    fn custom_resources_get_a_node_type() {
+        let crd: CustomResourceDefinition = serde_json::from_value(json!({
+            "apiVersion": "apiextensions.k8s.io/v1",
+            "kind": "CustomResourceDefinition",
+            "metadata": { "name": "certificates.cert-manager.io" },
+            "spec": {
+                "group": "cert-manager.io",
+                "names": { "kind": "Certificate", "plural": "certificates" },
+                "scope": "Namespaced",
+                "versions": [{
+                    "name": "v1",
+                    "served": true,
+                    "storage": true,
+                    "schema": { "openAPIV3Schema": {
+                        "type": "object",
+                        "properties": {
+                            "apiVersion": { "type": "string" },
+                            "kind": { "type": "string" },
+                            "metadata": { "type": "object" },
+                            "spec": {
+                                "type": "object",
+                                "properties": {
+                                    "dnsNames": { "type": "array", "items": { "type": "string" } },
+                                    "issuerRef": {
+                                        "type": "object",
+                                        "properties": { "name": { "type": "string" } }
+                                    }
+                                }
+                            }
+                        }
+                    }}
+                }]
+            }
+        }))
+        .unwrap();
+        let schemas = crd_schemas(&[Arc::new(crd)]);
+        assert_eq!(schemas.len(), 1);
+        assert_eq!(schemas[0].root_type.name, "Certificate");
+
+        let prompt = write_compact_schema_prompt(schemas);
+        assert!(prompt.contains(
+            "  Certificate(apiVersion: STRING, kind: STRING, metadata: ObjectMeta, \
+             spec: CertificateSpec)\n"
+        ));
+        assert!(prompt.contains(
+            "  CertificateSpec(dnsNames: [STRING], issuerRef: CertificateSpecIssuerRef)\n"
+        ));
+    }
+}
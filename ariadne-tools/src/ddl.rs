@@ -0,0 +1,104 @@
+use ariadne_core::graph_schema::INDEXED_PROPERTIES;
+use clap::ValueEnum;
+
+use crate::graph_labels;
+
+/// Property every node is identified by.
+const UNIQUE_PROPERTY: &str = "metadata.uid";
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// Indexes on the nested properties, as ariadne creates them when it builds the graph.
+    Memgraph,
+    /// Named, idempotent statements. Neo4j cannot store maps as properties, so these target
+    /// properties flattened to their path, such as `metadata.uid`.
+    Neo4j,
+}
+
+/// The statements that pre-provision a database for the graph: a uniqueness constraint on the
+/// uid of every label and indexes on the properties ariadne looks nodes up by.
+pub fn ddl_statements(dialect: Dialect) -> Vec<String> {
+    let mut statements = Vec::new();
+    for label in graph_labels() {
+        match dialect {
+            Dialect::Memgraph => {
+                statements.push(format!(
+                    "CREATE CONSTRAINT ON (n:{label}) ASSERT n.{UNIQUE_PROPERTY} IS UNIQUE;"
+                ));
+                for property in INDEXED_PROPERTIES {
+                    statements.push(format!("CREATE INDEX ON :{label}({property});"));
+                }
+            }
+            Dialect::Neo4j => {
+                let prefix = snake_case(&label);
+                statements.push(format!(
+                    "CREATE CONSTRAINT {prefix}_{} IF NOT EXISTS FOR (n:{label}) \
+                     REQUIRE n.`{UNIQUE_PROPERTY}` IS UNIQUE;",
+                    snake_case(UNIQUE_PROPERTY)
+                ));
+                // The uniqueness constraint is backed by an index of its own.
+                for property in INDEXED_PROPERTIES
+                    .iter()
+                    .filter(|property| **property != UNIQUE_PROPERTY)
+                {
+                    statements.push(format!(
+                        "CREATE INDEX {prefix}_{} IF NOT EXISTS FOR (n:{label}) \
+                         ON (n.`{property}`);",
+                        snake_case(property)
+                    ));
+                }
+            }
+        }
+    }
+    statements
+}
+
+/// `endpoint_slice` for `EndpointSlice`, `metadata_uid` for `metadata.uid`.
+fn snake_case(name: &str) -> String {
+    let mut output = String::with_capacity(name.len() + 4);
+    let mut previous_lowercase = false;
+    for c in name.chars() {
+        if c.is_uppercase() && previous_lowercase {
+            output.push('_');
+        }
+        previous_lowercase = c.is_lowercase() || c.is_ascii_digit();
+        if c.is_alphanumeric() {
+            output.extend(c.to_lowercase());
+        } else {
+            output.push('_');
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_label_gets_a_uid_constraint_and_lookup_indexes() {
+        let memgraph = ddl_statements(Dialect::Memgraph);
+        assert!(memgraph.contains(
+            &"CREATE CONSTRAINT ON (n:Pod) ASSERT n.metadata.uid IS UNIQUE;".to_string()
+        ));
+        assert!(memgraph.contains(&"CREATE INDEX ON :Pod(metadata.namespace);".to_string()));
+        assert_eq!(
+            memgraph.len(),
+            graph_labels().len() * (1 + INDEXED_PROPERTIES.len())
+        );
+
+        let neo4j = ddl_statements(Dialect::Neo4j);
+        assert!(neo4j.contains(
+            &"CREATE CONSTRAINT endpoint_slice_metadata_uid IF NOT EXISTS FOR (n:EndpointSlice) \
+              REQUIRE n.`metadata.uid` IS UNIQUE;"
+                .to_string()
+        ));
+        assert!(neo4j.contains(
+            &"CREATE INDEX pod_metadata_name IF NOT EXISTS FOR (n:Pod) ON (n.`metadata.name`);"
+                .to_string()
+        ));
+        assert!(!neo4j
+            .iter()
+            .any(|statement| statement.starts_with("CREATE INDEX pod_metadata_uid")));
+    }
+}
@@ -0,0 +1,86 @@
+use clap::ValueEnum;
+
+use crate::GraphRelationship;
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramFormat {
+    /// One `(:From)-[:Edge]->(:To)` pattern per line, as the prompts list them.
+    Cypher,
+    /// A Mermaid flowchart, which GitHub and most wikis render inline.
+    Mermaid,
+    /// A PlantUML class diagram.
+    Plantuml,
+    /// A JSON array of `{from, edge, to, cardinality}` entries, for tools that build on the model.
+    Json,
+}
+
+/// Renders `relationships` in `format`.
+pub fn relationships_diagram(relationships: &[GraphRelationship], format: DiagramFormat) -> String {
+    match format {
+        DiagramFormat::Cypher => crate::relationships_prompt(relationships),
+        DiagramFormat::Mermaid => mermaid_diagram(relationships),
+        DiagramFormat::Plantuml => plantuml_diagram(relationships),
+        DiagramFormat::Json => json_entries(relationships),
+    }
+}
+
+fn mermaid_diagram(relationships: &[GraphRelationship]) -> String {
+    let mut output = String::from("flowchart LR\n");
+    for relationship in relationships {
+        output.push_str(&format!(
+            "  {} -->|{}| {}\n",
+            relationship.from, relationship.edge, relationship.to
+        ));
+    }
+    output
+}
+
+fn plantuml_diagram(relationships: &[GraphRelationship]) -> String {
+    let mut output = String::from("@startuml\nhide empty members\nleft to right direction\n");
+    for relationship in relationships {
+        output.push_str(&format!(
+            "{} --> {} : {}\n",
+            relationship.from, relationship.to, relationship.edge
+        ));
+    }
+    output.push_str("@enduml\n");
+    output
+}
+
+fn json_entries(relationships: &[GraphRelationship]) -> String {
+    let mut output = serde_json::to_string_pretty(relationships).unwrap_or_default();
+    output.push('\n');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph_relationships;
+
+    #[test]
+    fn diagrams_draw_every_relationship() {
+        let relationships = graph_relationships();
+
+        let mermaid = relationships_diagram(&relationships, DiagramFormat::Mermaid);
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("  Pod -->|RunsOn| Node\n"));
+        assert_eq!(mermaid.lines().count(), relationships.len() + 1);
+
+        let plantuml = relationships_diagram(&relationships, DiagramFormat::Plantuml);
+        assert!(plantuml.contains("\nPod --> Node : RunsOn\n"));
+        assert!(plantuml.ends_with("@enduml\n"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&relationships_diagram(&relationships, DiagramFormat::Json))
+                .unwrap();
+        let entries = json.as_array().unwrap();
+        assert_eq!(entries.len(), relationships.len());
+        assert!(entries.contains(&serde_json::json!({
+            "from": "Pod",
+            "edge": "RunsOn",
+            "to": "Node",
+            "cardinality": "many-to-one",
+        })));
+    }
+}
@@ -0,0 +1,128 @@
+use serde::Serialize;
+
+use crate::schema::SchemaInfo;
+use crate::GraphRelationship;
+
+/// A read-only query that shows how to traverse a relationship.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExampleQuery {
+    /// What the query does in one word, such as `List`.
+    pub title: &'static str,
+    pub query: String,
+}
+
+/// One or two example queries for `relationship`: a few connected pairs and, when the target
+/// has a name, the targets with the most sources. `schema` holds the node types whose
+/// properties decide how a node is named.
+pub fn relationship_examples(
+    relationship: &GraphRelationship,
+    schema: &[SchemaInfo],
+) -> Vec<ExampleQuery> {
+    let GraphRelationship { from, edge, to, .. } = relationship;
+    let pattern = format!("MATCH (a:{from})-[:{edge}]->(b:{to})");
+    let source = name_expression(schema, from, "a");
+    let target = name_expression(schema, to, "b");
+    let mut examples = vec![ExampleQuery {
+        title: "List",
+        query: format!("{pattern} RETURN {source} AS source, {target} AS target LIMIT 25"),
+    }];
+    if target != "b" {
+        examples.push(ExampleQuery {
+            title: "Count",
+            query: format!(
+                "{pattern} RETURN {target} AS target, count(a) AS sources \
+                 ORDER BY sources DESC LIMIT 10"
+            ),
+        });
+    }
+    examples
+}
+
+/// The example queries of every relationship, below its pattern, as the prompt lists them.
+pub(crate) fn examples_prompt(
+    relationships: &[GraphRelationship],
+    schema: &[SchemaInfo],
+) -> String {
+    let mut output = String::new();
+    for relationship in relationships {
+        output.push_str(&format!(
+            "(:{})-[:{}]->(:{})\n",
+            relationship.from, relationship.edge, relationship.to
+        ));
+        for example in relationship_examples(relationship, schema) {
+            output.push_str(&format!("  {}\n", example.query));
+        }
+    }
+    output
+}
+
+/// How an example query names a node: `metadata.name` for Kubernetes objects, `name` for
+/// logical nodes that have one, and the whole node otherwise.
+fn name_expression(schema: &[SchemaInfo], label: &str, variable: &str) -> String {
+    let has = |property: &str| {
+        schema
+            .iter()
+            .find(|info| info.root_type.name == label)
+            .is_some_and(|info| {
+                info.root_type
+                    .properties
+                    .iter()
+                    .any(|candidate| candidate.name == property)
+            })
+    };
+    if has("metadata") {
+        format!("{variable}.metadata.name")
+    } else if has("name") {
+        format!("{variable}.name")
+    } else {
+        variable.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_schema;
+    use ariadne_core::graph_schema::Cardinality;
+
+    #[test]
+    fn relationships_get_list_and_count_examples() {
+        let schema = generate_schema();
+        let runs_on = GraphRelationship {
+            from: "Pod".to_string(),
+            edge: "RunsOn".to_string(),
+            to: "Node".to_string(),
+            cardinality: Cardinality::ManyToOne,
+        };
+        assert_eq!(
+            relationship_examples(&runs_on, &schema),
+            vec![
+                ExampleQuery {
+                    title: "List",
+                    query: "MATCH (a:Pod)-[:RunsOn]->(b:Node) \
+                            RETURN a.metadata.name AS source, b.metadata.name AS target LIMIT 25"
+                        .to_string(),
+                },
+                ExampleQuery {
+                    title: "Count",
+                    query: "MATCH (a:Pod)-[:RunsOn]->(b:Node) \
+                            RETURN b.metadata.name AS target, count(a) AS sources \
+                            ORDER BY sources DESC LIMIT 10"
+                        .to_string(),
+                },
+            ]
+        );
+
+        let unnamed = GraphRelationship {
+            from: "AWX".to_string(),
+            edge: "Manages".to_string(),
+            to: "Widget".to_string(),
+            cardinality: Cardinality::OneToMany,
+        };
+        assert_eq!(
+            relationship_examples(&unnamed, &schema)[0].query,
+            "MATCH (a:AWX)-[:Manages]->(b:Widget) RETURN a AS source, b AS target LIMIT 25"
+        );
+        assert_eq!(relationship_examples(&unnamed, &schema).len(), 1);
+    }
+}
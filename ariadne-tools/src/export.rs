@@ -0,0 +1,246 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde_json::{json, Map, Value};
+
+use crate::schema::{map_reference, short_type_name, SchemaInfo, Type};
+
+const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// The node types as one JSON Schema document. Node types and the types they reference are
+/// definitions under `$defs`, node types by name and referenced types by their full path, and
+/// the document itself accepts any node type.
+pub fn to_json_schema(schema_list: &[SchemaInfo]) -> Value {
+    let definitions = referenced_types(schema_list);
+    let mut defs = Map::new();
+    for schema in schema_list {
+        defs.insert(
+            schema.root_type.name.clone(),
+            json_schema_object(&schema.root_type, &definitions),
+        );
+    }
+    for (full_name, definition) in &definitions {
+        if !defs.contains_key(*full_name) {
+            defs.insert(
+                full_name.to_string(),
+                json_schema_object(definition, &definitions),
+            );
+        }
+    }
+    let nodes: Vec<Value> = schema_list
+        .iter()
+        .map(|schema| json!({ "$ref": format!("#/$defs/{}", schema.root_type.name) }))
+        .collect();
+    json!({
+        "$schema": JSON_SCHEMA_DIALECT,
+        "title": "Ariadne graph node",
+        "anyOf": nodes,
+        "$defs": defs,
+    })
+}
+
+fn json_schema_object(ty: &Type, definitions: &BTreeMap<&str, &Type>) -> Value {
+    let properties: Map<String, Value> = ty
+        .properties
+        .iter()
+        .map(|property| {
+            (
+                property.name.clone(),
+                json_schema_type(&property.data_type, definitions),
+            )
+        })
+        .collect();
+    json!({ "type": "object", "properties": properties })
+}
+
+/// The JSON Schema of a data type as [`crate::schema`] writes it: `STRING`, `[INTEGER]`,
+/// `#/$defs/io.k8s...ObjectMeta` and so on. Types the schema cannot describe accept anything.
+fn json_schema_type(data_type: &str, definitions: &BTreeMap<&str, &Type>) -> Value {
+    if let Some(item) = data_type
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        return json!({ "type": "array", "items": json_schema_type(item, definitions) });
+    }
+    if data_type.starts_with("#/") {
+        let data_type = map_reference(data_type);
+        if let Some(name) = reference_name(&data_type) {
+            if definitions.contains_key(name) {
+                return json!({ "$ref": format!("#/$defs/{name}") });
+            }
+            return json!({ "type": "object" });
+        }
+        return json_schema_type(&data_type, definitions);
+    }
+    match data_type {
+        "STRING" => json!({ "type": "string" }),
+        "INTEGER" => json!({ "type": "integer" }),
+        "FLOAT" => json!({ "type": "number" }),
+        "BOOLEAN" => json!({ "type": "boolean" }),
+        "MAP" => json!({ "type": "object" }),
+        "NULL" => json!({ "type": "null" }),
+        "DATETIME_UTC" => json!({ "type": "string", "format": "date-time" }),
+        "NEVER" => json!(false),
+        _ => json!(true),
+    }
+}
+
+/// The node types as TypeScript interfaces, plus a `GraphNode` union of them. Every property is
+/// optional, as Kubernetes leaves most fields out when they are unset.
+pub fn to_typescript(schema_list: &[SchemaInfo]) -> String {
+    let definitions = referenced_types(schema_list);
+    let names = typescript_names(schema_list, &definitions);
+    let mut output = String::from("// Generated by `ariadne-tools export typescript`.\n\n");
+    for schema in schema_list {
+        output += &typescript_interface(&schema.root_type.name, &schema.root_type, &names);
+    }
+    for (full_name, definition) in &definitions {
+        output += &typescript_interface(&names[*full_name], definition, &names);
+    }
+    let union = schema_list
+        .iter()
+        .map(|schema| schema.root_type.name.as_str())
+        .collect::<Vec<_>>()
+        .join(" | ");
+    output += &format!("export type GraphNode = {union};\n");
+    output
+}
+
+fn typescript_interface(name: &str, ty: &Type, names: &HashMap<String, String>) -> String {
+    let mut output = format!("export interface {name} {{\n");
+    for property in &ty.properties {
+        output += &format!(
+            "  {}?: {};\n",
+            typescript_property_name(&property.name),
+            typescript_type(&property.data_type, names)
+        );
+    }
+    output += "}\n\n";
+    output
+}
+
+fn typescript_type(data_type: &str, names: &HashMap<String, String>) -> String {
+    if let Some(item) = data_type
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        return format!("{}[]", typescript_type(item, names));
+    }
+    if data_type.starts_with("#/") {
+        let data_type = map_reference(data_type);
+        if let Some(name) = reference_name(&data_type) {
+            return names
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| "Record<string, unknown>".to_string());
+        }
+        return typescript_type(&data_type, names);
+    }
+    match data_type {
+        "STRING" | "DATETIME_UTC" => "string",
+        "INTEGER" | "FLOAT" => "number",
+        "BOOLEAN" => "boolean",
+        "MAP" => "Record<string, unknown>",
+        "NULL" => "null",
+        "NEVER" => "never",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+fn typescript_property_name(name: &str) -> String {
+    if name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        name.to_string()
+    } else {
+        format!("\"{name}\"")
+    }
+}
+
+/// The interface name of each referenced type: its short name, or its full path in PascalCase
+/// when a node type or another referenced type already has that name.
+fn typescript_names(
+    schema_list: &[SchemaInfo],
+    definitions: &BTreeMap<&str, &Type>,
+) -> HashMap<String, String> {
+    let mut taken: HashSet<String> = schema_list
+        .iter()
+        .map(|schema| schema.root_type.name.clone())
+        .collect();
+    let mut short_name_uses: HashMap<&str, usize> = HashMap::new();
+    for full_name in definitions.keys() {
+        *short_name_uses
+            .entry(short_type_name(full_name))
+            .or_default() += 1;
+    }
+    let mut names = HashMap::new();
+    for full_name in definitions.keys() {
+        let short_name = short_type_name(full_name);
+        let name = if short_name_uses[short_name] == 1 && !taken.contains(short_name) {
+            short_name.to_string()
+        } else {
+            full_name
+                .split(['.', '-', '_'])
+                .map(|segment| {
+                    let mut chars = segment.chars();
+                    match chars.next() {
+                        Some(first) => first.to_uppercase().chain(chars).collect(),
+                        None => String::new(),
+                    }
+                })
+                .collect()
+        };
+        taken.insert(name.clone());
+        names.insert(full_name.to_string(), name);
+    }
+    names
+}
+
+/// The types referenced by any node type, by full path.
+fn referenced_types(schema_list: &[SchemaInfo]) -> BTreeMap<&str, &Type> {
+    schema_list
+        .iter()
+        .flat_map(|schema| &schema.definitions)
+        .map(|(full_name, definition)| (full_name.as_str(), definition))
+        .collect()
+}
+
+/// `io.k8s...ObjectMeta` for `#/$defs/io.k8s...ObjectMeta` or `#/definitions/io.k8s...ObjectMeta`.
+fn reference_name(data_type: &str) -> Option<&str> {
+    data_type
+        .strip_prefix("#/$defs/")
+        .or_else(|| data_type.strip_prefix("#/definitions/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_schema;
+
+    #[test]
+    fn exports_describe_every_node_type() {
+        let schema_list = generate_schema();
+
+        let document = to_json_schema(&schema_list);
+        let defs = document["$defs"].as_object().unwrap();
+        assert_eq!(
+            document["anyOf"].as_array().unwrap().len(),
+            schema_list.len()
+        );
+        assert_eq!(
+            defs["Pod"]["properties"]["metadata"]["$ref"],
+            "#/$defs/io.k8s.apimachinery.pkg.apis.meta.v1.ObjectMeta"
+        );
+        assert!(defs.contains_key("io.k8s.apimachinery.pkg.apis.meta.v1.ObjectMeta"));
+        assert_eq!(
+            defs["Cluster"]["properties"]["retrieved_at"],
+            json!({ "type": "string", "format": "date-time" })
+        );
+
+        let typescript = to_typescript(&schema_list);
+        assert!(typescript.contains("export interface Pod {\n  apiVersion?: string;\n"));
+        assert!(typescript.contains("  metadata?: ObjectMeta;\n"));
+        assert!(typescript.contains("export interface ObjectMeta {\n"));
+        assert!(typescript.contains("  finalizers?: string[];\n"));
+        assert!(typescript.contains("export interface IoK8sApiDiscoveryV1Endpoint {\n"));
+        assert!(typescript.contains("export type GraphNode = Cluster | ConfigMap | "));
+    }
+}
@@ -0,0 +1,416 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ariadne_core::prelude::Result;
+use ariadne_core::state_resolver::ObservedClusterSnapshot;
+use ariadne_core::synth::object_counts;
+use ariadne_core::types::{Cluster, ObjectIdentifier};
+use clap::ValueEnum;
+use k8s_openapi::api::core::v1::Namespace;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use k8s_openapi::apimachinery::pkg::version::Info;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Where `ariadne-tools import` reads the objects of a cluster from.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Files written by `kubectl get <kind> -A -o json` or `kubectl cluster-info dump`.
+    KubectlDump,
+}
+
+/// Core `v1` event fields and the `events.k8s.io/v1` fields they became.
+const CORE_EVENT_RENAMES: [(&str, &str); 6] = [
+    ("involvedObject", "regarding"),
+    ("message", "note"),
+    ("firstTimestamp", "deprecatedFirstTimestamp"),
+    ("lastTimestamp", "deprecatedLastTimestamp"),
+    ("count", "deprecatedCount"),
+    ("source", "deprecatedSource"),
+];
+
+/// A snapshot read from kubectl output and what was left out of it.
+#[derive(Debug, Clone)]
+pub struct KubectlImport {
+    pub snapshot: ObservedClusterSnapshot,
+    pub summary: ImportSummary,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ImportSummary {
+    /// Objects of each kind in the snapshot.
+    pub objects: BTreeMap<String, usize>,
+    /// Namespaces the dump does not hold but its objects are in, added with a made-up uid.
+    pub added_namespaces: Vec<String>,
+    /// Objects read more than once, such as pods in both `pods.json` and `all.json`.
+    pub duplicates: usize,
+    pub skipped: Vec<SkippedObject>,
+}
+
+/// An object or file the snapshot leaves out, and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedObject {
+    pub file: PathBuf,
+    /// Kind and `namespace/name` of the object; empty when the whole file is skipped.
+    pub object: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ImportSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (kind, count) in &self.objects {
+            writeln!(f, "{kind}: {count}")?;
+        }
+        if !self.added_namespaces.is_empty() {
+            writeln!(f, "Added namespaces: {}", self.added_namespaces.join(", "))?;
+        }
+        if self.duplicates > 0 {
+            writeln!(f, "Duplicates ignored: {}", self.duplicates)?;
+        }
+        for skipped in &self.skipped {
+            if skipped.object.is_empty() {
+                writeln!(f, "Skipped {}: {}", skipped.file.display(), skipped.reason)?;
+            } else {
+                writeln!(
+                    f,
+                    "Skipped {} in {}: {}",
+                    skipped.object,
+                    skipped.file.display(),
+                    skipped.reason
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the JSON files below `input`, or `input` itself when it is a file, into a snapshot of a
+/// cluster named `cluster_name`. A file may hold several documents, each an object or a `List`
+/// of them, as `kubectl cluster-info dump` prints. Kinds a snapshot has no place for, objects
+/// without a uid and objects that do not parse are skipped, core `v1` events are converted to
+/// `events.k8s.io/v1` ones, and namespaces the objects are in but the dump lacks are added.
+pub fn import_kubectl_dump(
+    input: &Path,
+    cluster_name: &str,
+    cluster_url: &str,
+) -> Result<KubectlImport> {
+    let mut files = Vec::new();
+    collect_json_files(input, &mut files)?;
+    files.sort();
+
+    let cluster = Cluster::new(
+        ObjectIdentifier {
+            uid: format!("Cluster:{cluster_name}"),
+            name: cluster_name.to_string(),
+            namespace: None,
+            resource_version: None,
+        },
+        cluster_url,
+        Info::default(),
+    );
+    let mut import = Importer {
+        snapshot: ObservedClusterSnapshot::with_cluster(cluster),
+        seen: HashSet::new(),
+        summary: ImportSummary::default(),
+    };
+    for file in files {
+        let bytes = std::fs::read(&file)?;
+        for document in serde_json::Deserializer::from_slice(&bytes).into_iter::<Value>() {
+            match document {
+                Ok(document) => import.add_document(&file, document),
+                Err(err) => {
+                    import.skip(&file, String::new(), format!("not JSON: {err}"));
+                    break;
+                }
+            }
+        }
+    }
+    import.add_missing_namespaces();
+
+    let mut summary = import.summary;
+    summary.objects = object_counts(&import.snapshot)
+        .into_iter()
+        .map(|(kind, count)| (kind.to_string(), count))
+        .collect();
+    Ok(KubectlImport {
+        snapshot: import.snapshot,
+        summary,
+    })
+}
+
+fn collect_json_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_json_files(&path, files)?;
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+struct Importer {
+    snapshot: ObservedClusterSnapshot,
+    /// Kind and uid of every object added.
+    seen: HashSet<(String, String)>,
+    summary: ImportSummary,
+}
+
+impl Importer {
+    fn add_document(&mut self, file: &Path, document: Value) {
+        let Value::Object(mut object) = document else {
+            self.skip(file, String::new(), "not a JSON object".to_string());
+            return;
+        };
+        let kind = object
+            .get("kind")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        // Items of typed lists such as `PodList` may lack their own kind.
+        if let Some(item_kind) = kind.strip_suffix("List") {
+            let Some(Value::Array(items)) = object.remove("items") else {
+                self.skip(file, String::new(), format!("{kind} without items"));
+                return;
+            };
+            for item in items {
+                match item {
+                    Value::Object(item) => self.add_object(file, item_kind, item),
+                    _ => self.skip(file, String::new(), "a list item is not an object".into()),
+                }
+            }
+        } else {
+            self.add_object(file, "", object);
+        }
+    }
+
+    fn add_object(&mut self, file: &Path, default_kind: &str, object: Map<String, Value>) {
+        let kind = object
+            .get("kind")
+            .and_then(Value::as_str)
+            .filter(|kind| !kind.is_empty())
+            .unwrap_or(default_kind)
+            .to_string();
+        let metadata = object.get("metadata").unwrap_or(&Value::Null);
+        let name = match metadata["namespace"].as_str() {
+            Some(namespace) => format!("{namespace}/{}", metadata["name"].as_str().unwrap_or("")),
+            None => metadata["name"].as_str().unwrap_or("").to_string(),
+        };
+        let described = format!("{kind} {name}");
+        let Some(uid) = metadata["uid"].as_str().map(str::to_string) else {
+            self.skip(file, described, "no metadata.uid".to_string());
+            return;
+        };
+        if !self.seen.insert((kind.clone(), uid)) {
+            self.summary.duplicates += 1;
+            return;
+        }
+
+        let snapshot = &mut self.snapshot;
+        let value = Value::Object(object);
+        let added = match kind.as_str() {
+            "Namespace" => push(&mut snapshot.namespaces, value),
+            "Pod" => push(&mut snapshot.pods, value),
+            "Deployment" => push(&mut snapshot.deployments, value),
+            "StatefulSet" => push(&mut snapshot.stateful_sets, value),
+            "ReplicaSet" => push(&mut snapshot.replica_sets, value),
+            "DaemonSet" => push(&mut snapshot.daemon_sets, value),
+            "Job" => push(&mut snapshot.jobs, value),
+            "Ingress" => push(&mut snapshot.ingresses, value),
+            "Service" => push(&mut snapshot.services, value),
+            "EndpointSlice" => push(&mut snapshot.endpoint_slices, value),
+            "NetworkPolicy" => push(&mut snapshot.network_policies, value),
+            "ConfigMap" => push(&mut snapshot.config_maps, value),
+            "StorageClass" => push(&mut snapshot.storage_classes, value),
+            "PersistentVolume" => push(&mut snapshot.persistent_volumes, value),
+            "PersistentVolumeClaim" => push(&mut snapshot.persistent_volume_claims, value),
+            "Node" => push(&mut snapshot.nodes, value),
+            "ServiceAccount" => push(&mut snapshot.service_accounts, value),
+            "Event" => events_v1(value).and_then(|event| push(&mut snapshot.events, event)),
+            "" => Err("no kind".to_string()),
+            _ => Err("not a kind of the graph".to_string()),
+        };
+        if let Err(reason) = added {
+            self.skip(file, described, reason);
+        }
+    }
+
+    /// Adds the namespaces of namespaced objects that the dump does not hold, as
+    /// `kubectl cluster-info dump` leaves them out.
+    fn add_missing_namespaces(&mut self) {
+        let known: BTreeSet<String> = self
+            .snapshot
+            .namespaces
+            .iter()
+            .filter_map(|namespace| namespace.metadata.name.clone())
+            .collect();
+        let snapshot = &self.snapshot;
+        let metadata = snapshot
+            .pods
+            .iter()
+            .map(|x| &x.metadata)
+            .chain(snapshot.deployments.iter().map(|x| &x.metadata))
+            .chain(snapshot.stateful_sets.iter().map(|x| &x.metadata))
+            .chain(snapshot.replica_sets.iter().map(|x| &x.metadata))
+            .chain(snapshot.daemon_sets.iter().map(|x| &x.metadata))
+            .chain(snapshot.jobs.iter().map(|x| &x.metadata))
+            .chain(snapshot.ingresses.iter().map(|x| &x.metadata))
+            .chain(snapshot.services.iter().map(|x| &x.metadata))
+            .chain(snapshot.endpoint_slices.iter().map(|x| &x.metadata))
+            .chain(snapshot.network_policies.iter().map(|x| &x.metadata))
+            .chain(snapshot.config_maps.iter().map(|x| &x.metadata))
+            .chain(
+                snapshot
+                    .persistent_volume_claims
+                    .iter()
+                    .map(|x| &x.metadata),
+            )
+            .chain(snapshot.service_accounts.iter().map(|x| &x.metadata))
+            .chain(snapshot.events.iter().map(|x| &x.metadata));
+        let missing: BTreeSet<String> = metadata
+            .filter_map(|metadata| metadata.namespace.clone())
+            .filter(|namespace| !known.contains(namespace))
+            .collect();
+        for name in missing {
+            self.snapshot.namespaces.push(Arc::new(Namespace {
+                metadata: ObjectMeta {
+                    name: Some(name.clone()),
+                    uid: Some(format!("Namespace:{name}")),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }));
+            self.summary.added_namespaces.push(name);
+        }
+    }
+
+    fn skip(&mut self, file: &Path, object: String, reason: String) {
+        self.summary.skipped.push(SkippedObject {
+            file: file.to_path_buf(),
+            object,
+            reason,
+        });
+    }
+}
+
+fn push<T: DeserializeOwned>(
+    list: &mut Vec<Arc<T>>,
+    value: Value,
+) -> std::result::Result<(), String> {
+    let item: T = serde_json::from_value(value).map_err(|err| err.to_string())?;
+    list.push(Arc::new(item));
+    Ok(())
+}
+
+/// Converts a core `v1` event, which is what kubectl prints, to the `events.k8s.io/v1` event a
+/// snapshot holds. Those have a required `eventTime`, taken from the last time the event was seen
+/// when the core event has none.
+fn events_v1(value: Value) -> std::result::Result<Value, String> {
+    let Value::Object(mut event) = value else {
+        return Err("not a JSON object".to_string());
+    };
+    if event.get("apiVersion").and_then(Value::as_str) == Some("events.k8s.io/v1") {
+        return Ok(Value::Object(event));
+    }
+    for (core, events) in CORE_EVENT_RENAMES {
+        if let Some(field) = event.remove(core) {
+            event.insert(events.to_string(), field);
+        }
+    }
+    if let Some(component) = event.remove("reportingComponent") {
+        event.insert("reportingController".to_string(), component);
+    }
+    if event.get("eventTime").is_none_or(Value::is_null) {
+        let seen = ["deprecatedLastTimestamp", "deprecatedFirstTimestamp"]
+            .iter()
+            .filter_map(|field| event.get(*field))
+            .chain(
+                event
+                    .get("metadata")
+                    .and_then(|m| m.get("creationTimestamp")),
+            )
+            .find(|time| time.is_string())
+            .cloned()
+            .ok_or_else(|| "the event has no time".to_string())?;
+        event.insert("eventTime".to_string(), seen);
+    }
+    event.insert("apiVersion".to_string(), Value::from("events.k8s.io/v1"));
+    Ok(Value::Object(event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn kubectl_lists_and_dumps_become_a_snapshot() {
+        let dir = std::env::temp_dir().join(format!("ariadne-import-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("shop")).unwrap();
+        let pod = json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "metadata": {"name": "web-0", "namespace": "shop", "uid": "pod-uid"},
+        });
+        let get_pods = json!({"apiVersion": "v1", "kind": "List", "items": [pod]});
+        std::fs::write(dir.join("pods.json"), get_pods.to_string()).unwrap();
+        // `kubectl cluster-info dump` writes typed lists whose items lack their kind.
+        let dump = [
+            json!({"kind": "PodList", "items": [
+                {"metadata": {"name": "web-0", "namespace": "shop", "uid": "pod-uid"}},
+            ]}),
+            json!({"kind": "EventList", "items": [{
+                "metadata": {"name": "web-0.1", "namespace": "shop", "uid": "event-uid"},
+                "involvedObject": {"kind": "Pod", "name": "web-0", "uid": "pod-uid"},
+                "reason": "BackOff",
+                "message": "Back-off restarting failed container",
+                "lastTimestamp": "2024-05-01T10:00:00Z",
+                "type": "Warning",
+            }]}),
+            json!({"kind": "ReplicationControllerList", "items": [
+                {"metadata": {"name": "legacy", "namespace": "shop", "uid": "rc-uid"}},
+            ]}),
+        ];
+        let dump: Vec<String> = dump.iter().map(Value::to_string).collect();
+        std::fs::write(dir.join("shop").join("dump.json"), dump.join("\n")).unwrap();
+
+        let import = import_kubectl_dump(&dir, "prod", "https://prod.invalid").unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let snapshot = &import.snapshot;
+        assert_eq!(snapshot.cluster.name, "prod");
+        assert_eq!(snapshot.pods.len(), 1);
+        assert_eq!(import.summary.duplicates, 1);
+        assert_eq!(snapshot.events.len(), 1);
+        let event = &snapshot.events[0];
+        assert_eq!(
+            event.note.as_deref(),
+            Some("Back-off restarting failed container")
+        );
+        assert_eq!(
+            event.regarding.as_ref().and_then(|r| r.uid.as_deref()),
+            Some("pod-uid")
+        );
+        assert_eq!(import.summary.added_namespaces, vec!["shop"]);
+        assert_eq!(
+            snapshot.namespaces[0].metadata.uid.as_deref(),
+            Some("Namespace:shop")
+        );
+        assert_eq!(import.summary.skipped.len(), 1);
+        assert_eq!(
+            import.summary.skipped[0].object,
+            "ReplicationController shop/legacy"
+        );
+    }
+}
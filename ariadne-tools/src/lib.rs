@@ -4,7 +4,8 @@ use ariadne_core::types::{
 use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
 use k8s_openapi::api::batch::v1::Job;
 use k8s_openapi::api::core::v1::{
-    ConfigMap, Namespace, Node, PersistentVolume, Pod, Service, ServiceAccount,
+    ConfigMap, Namespace, Node, PersistentVolume, PersistentVolumeClaim, Pod, Service,
+    ServiceAccount,
 };
 use k8s_openapi::api::discovery::v1::EndpointSlice;
 use k8s_openapi::api::events::v1::Event;
@@ -12,24 +13,100 @@ use k8s_openapi::api::networking::v1::{Ingress, NetworkPolicy};
 use k8s_openapi::api::storage::v1::StorageClass;
 use k8s_openapi::schemars::schema_for;
 use schemars::Schema;
+use std::collections::BTreeSet;
 
+#[cfg(feature = "memgraph")]
+pub mod backend;
+pub mod crd;
+pub mod ddl;
+pub mod diagram;
+pub mod examples;
+pub mod export;
+pub mod import;
+pub mod lint;
+pub mod report;
 pub mod schema;
+pub mod stats;
 
 pub use ariadne_core::graph_schema::{graph_relationships, GraphRelationship};
-pub use schema::SchemaInfo;
+pub use schema::{SchemaInfo, SchemaPromptOptions};
 
 const PROMPT_TEMPLATE: &str = include_str!("../../prompt.txt");
-const SCHEMA_PLACEHOLDER: &str = "{{SCHEMA}}";
-const RELATIONSHIPS_PLACEHOLDER: &str = "{{RELATIONSHIPS}}";
+const COMPACT_PROMPT_TEMPLATE: &str = include_str!("../../prompt_compact.txt");
+/// Replaced with the node types of the graph in a prompt template.
+pub const SCHEMA_PLACEHOLDER: &str = "{{SCHEMA}}";
+/// Replaced with the relationships between the node types in a prompt template.
+pub const RELATIONSHIPS_PLACEHOLDER: &str = "{{RELATIONSHIPS}}";
+/// Replaced with [`PromptVariables::cluster_name`] in a prompt template.
+pub const CLUSTER_NAME_PLACEHOLDER: &str = "{{CLUSTER_NAME}}";
+/// Replaced with [`PromptVariables::kubernetes_version`] in a prompt template.
+pub const KUBERNETES_VERSION_PLACEHOLDER: &str = "{{KUBERNETES_VERSION}}";
+/// Replaced with the comma-separated names of the node types a prompt describes.
+pub const RESOURCE_TYPES_PLACEHOLDER: &str = "{{RESOURCE_TYPES}}";
+/// Replaced with [`PromptVariables::instructions`] in a prompt template.
+pub const INSTRUCTIONS_PLACEHOLDER: &str = "{{INSTRUCTIONS}}";
+/// Replaced with example queries for each relationship of a prompt.
+pub const EXAMPLES_PLACEHOLDER: &str = "{{EXAMPLES}}";
+/// Placeholders every prompt template must contain.
+pub const REQUIRED_PLACEHOLDERS: [&str; 2] = [SCHEMA_PLACEHOLDER, RELATIONSHIPS_PLACEHOLDER];
+/// Every placeholder a prompt template may contain.
+pub const PROMPT_PLACEHOLDERS: [&str; 7] = [
+    SCHEMA_PLACEHOLDER,
+    RELATIONSHIPS_PLACEHOLDER,
+    EXAMPLES_PLACEHOLDER,
+    CLUSTER_NAME_PLACEHOLDER,
+    KUBERNETES_VERSION_PLACEHOLDER,
+    RESOURCE_TYPES_PLACEHOLDER,
+    INSTRUCTIONS_PLACEHOLDER,
+];
+/// What the variables of a prompt read when they are not set.
+const UNKNOWN_VARIABLE: &str = "unknown";
+
+// The built-in templates are checked when the crate is compiled.
+const _: () = assert!(
+    check_template(PROMPT_TEMPLATE).is_ok(),
+    "prompt.txt has an unknown placeholder or lacks a required one"
+);
+const _: () = assert!(
+    check_template(COMPACT_PROMPT_TEMPLATE).is_ok(),
+    "prompt_compact.txt has an unknown placeholder or lacks a required one"
+);
+
+/// Values of the placeholders that tailor a prompt to a deployment.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromptVariables {
+    pub cluster_name: Option<String>,
+    /// Such as `v1.31.2`.
+    pub kubernetes_version: Option<String>,
+    /// The operator's instructions, such as naming conventions; empty when not set.
+    pub instructions: Option<String>,
+}
 
 pub fn schema_prompt() -> String {
     let derived_schema = generate_schema();
     schema::write_schema_prompt(derived_schema)
 }
 
+/// [`schema_prompt`] without full type paths, as the compact prompt writes it.
+pub fn compact_schema_prompt() -> String {
+    schema::write_compact_schema_prompt(generate_schema())
+}
+
+/// Every node label of the graph, as the relationships between them name it.
+pub fn graph_labels() -> BTreeSet<String> {
+    graph_relationships()
+        .into_iter()
+        .flat_map(|relationship| [relationship.from, relationship.to])
+        .collect()
+}
+
 pub fn graph_relationships_prompt() -> String {
+    relationships_prompt(&graph_relationships())
+}
+
+pub(crate) fn relationships_prompt(relationships: &[GraphRelationship]) -> String {
     let mut output = String::new();
-    for relationship in graph_relationships() {
+    for relationship in relationships {
         output.push_str(&format!(
             "(:{})-[:{}]->(:{})\n",
             relationship.from, relationship.edge, relationship.to
@@ -39,14 +116,243 @@ pub fn graph_relationships_prompt() -> String {
 }
 
 pub fn full_prompt() -> String {
-    let schema = schema_prompt();
-    let relationships = graph_relationships_prompt();
-    PROMPT_TEMPLATE
+    prompt_from_template(PROMPT_TEMPLATE, false, None)
+}
+
+/// [`full_prompt`] with the placeholders of `variables` filled in.
+pub fn full_prompt_with(variables: &PromptVariables) -> String {
+    render_prompt(
+        PROMPT_TEMPLATE,
+        false,
+        None,
+        &SchemaPromptOptions::default(),
+        variables,
+    )
+}
+
+/// The prompt for small local models: fewer rules and a schema without full type paths.
+pub fn compact_prompt() -> String {
+    prompt_from_template(COMPACT_PROMPT_TEMPLATE, true, None)
+}
+
+/// [`full_prompt`] with only the node types in `labels` and the relationships between them.
+pub fn full_prompt_for(labels: &[&str]) -> String {
+    prompt_from_template(PROMPT_TEMPLATE, false, Some(labels))
+}
+
+/// [`compact_prompt`] with only the node types in `labels` and the relationships between them.
+pub fn compact_prompt_for(labels: &[&str]) -> String {
+    prompt_from_template(COMPACT_PROMPT_TEMPLATE, true, Some(labels))
+}
+
+/// The built-in template of [`compact_prompt`] when `compact` is set, of [`full_prompt`]
+/// otherwise.
+pub fn default_prompt_template(compact: bool) -> &'static str {
+    if compact {
+        COMPACT_PROMPT_TEMPLATE
+    } else {
+        PROMPT_TEMPLATE
+    }
+}
+
+/// Fills the placeholders of a prompt template with the schema, written the compact way when
+/// `compact` is set and limited to `labels` when given.
+pub fn prompt_from_template(template: &str, compact: bool, labels: Option<&[&str]>) -> String {
+    prompt_from_template_with(template, compact, labels, &SchemaPromptOptions::default())
+}
+
+/// [`prompt_from_template`] with the types and properties of the schema `options` keep.
+pub fn prompt_from_template_with(
+    template: &str,
+    compact: bool,
+    labels: Option<&[&str]>,
+    options: &SchemaPromptOptions,
+) -> String {
+    render_prompt(
+        template,
+        compact,
+        labels,
+        options,
+        &PromptVariables::default(),
+    )
+}
+
+/// [`prompt_from_template_with`] that also fills the placeholders of `variables` and
+/// [`RESOURCE_TYPES_PLACEHOLDER`].
+pub fn render_prompt(
+    template: &str,
+    compact: bool,
+    labels: Option<&[&str]>,
+    options: &SchemaPromptOptions,
+    variables: &PromptVariables,
+) -> String {
+    let (schema, relationships) = prompt_schema(labels, &options.custom_types);
+    let resource_types = schema
+        .iter()
+        .map(|info| info.root_type.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let examples = examples::examples_prompt(&relationships, &schema);
+    let schema = if compact {
+        schema::write_compact_schema_prompt_with(schema, options)
+    } else {
+        schema::write_schema_prompt_with(schema, options)
+    };
+    let prompt = fill_template(
+        template,
+        &schema,
+        &relationships_prompt(&relationships),
+        &examples,
+    );
+    prompt
+        .replace(
+            CLUSTER_NAME_PLACEHOLDER,
+            variable_value(variables.cluster_name.as_deref(), UNKNOWN_VARIABLE),
+        )
+        .replace(
+            KUBERNETES_VERSION_PLACEHOLDER,
+            variable_value(variables.kubernetes_version.as_deref(), UNKNOWN_VARIABLE),
+        )
+        .replace(RESOURCE_TYPES_PLACEHOLDER, &resource_types)
+        .replace(
+            INSTRUCTIONS_PLACEHOLDER,
+            variable_value(variables.instructions.as_deref(), ""),
+        )
+}
+
+fn variable_value<'a>(value: Option<&'a str>, unset: &'a str) -> &'a str {
+    value.map(str::trim).unwrap_or(unset)
+}
+
+/// Checks that a prompt template contains the [`REQUIRED_PLACEHOLDERS`] and no `{{...}}` other
+/// than the [`PROMPT_PLACEHOLDERS`].
+pub fn validate_template(template: &str) -> Result<(), String> {
+    match check_template(template) {
+        Ok(()) => Ok(()),
+        Err(TemplateError::UnknownPlaceholder(start)) => {
+            let rest = &template[start..];
+            let end = rest.find("}}").map_or(rest.len(), |end| end + 2);
+            Err(format!("unknown placeholder {}", &rest[..end]))
+        }
+        Err(TemplateError::MissingPlaceholder(placeholder)) => {
+            Err(format!("missing placeholder {placeholder}"))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TemplateError {
+    /// The `{{` at this byte offset does not start one of the [`PROMPT_PLACEHOLDERS`].
+    UnknownPlaceholder(usize),
+    MissingPlaceholder(&'static str),
+}
+
+/// [`validate_template`] as a `const fn`, so the built-in templates are checked at compile time.
+const fn check_template(template: &str) -> Result<(), TemplateError> {
+    let bytes = template.as_bytes();
+    let mut start = 0;
+    while start + 1 < bytes.len() {
+        if bytes[start] != b'{' || bytes[start + 1] != b'{' {
+            start += 1;
+            continue;
+        }
+        let mut index = 0;
+        while index < PROMPT_PLACEHOLDERS.len()
+            && !bytes_at(bytes, start, PROMPT_PLACEHOLDERS[index].as_bytes())
+        {
+            index += 1;
+        }
+        if index == PROMPT_PLACEHOLDERS.len() {
+            return Err(TemplateError::UnknownPlaceholder(start));
+        }
+        start += PROMPT_PLACEHOLDERS[index].len();
+    }
+    let mut index = 0;
+    while index < REQUIRED_PLACEHOLDERS.len() {
+        let placeholder = REQUIRED_PLACEHOLDERS[index].as_bytes();
+        let mut start = 0;
+        while start + placeholder.len() <= bytes.len() && !bytes_at(bytes, start, placeholder) {
+            start += 1;
+        }
+        if start + placeholder.len() > bytes.len() {
+            return Err(TemplateError::MissingPlaceholder(
+                REQUIRED_PLACEHOLDERS[index],
+            ));
+        }
+        index += 1;
+    }
+    Ok(())
+}
+
+/// Whether `bytes` holds `expected` from `start` on.
+const fn bytes_at(bytes: &[u8], start: usize, expected: &[u8]) -> bool {
+    if start + expected.len() > bytes.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < expected.len() {
+        if bytes[start + index] != expected[index] {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+/// The node types with `custom_types` and the relationships of a prompt, limited to `labels`
+/// when given.
+pub(crate) fn prompt_schema(
+    labels: Option<&[&str]>,
+    custom_types: &[SchemaInfo],
+) -> (Vec<SchemaInfo>, Vec<GraphRelationship>) {
+    let schema = generate_schema_with(custom_types);
+    let Some(labels) = labels else {
+        return (schema, graph_relationships());
+    };
+    let schema = schema
+        .into_iter()
+        .filter(|info| labels.contains(&info.root_type.name.as_str()))
+        .collect();
+    let relationships = graph_relationships()
+        .into_iter()
+        .filter(|relationship| {
+            labels.contains(&relationship.from.as_str())
+                && labels.contains(&relationship.to.as_str())
+        })
+        .collect();
+    (schema, relationships)
+}
+
+pub(crate) fn fill_template(
+    template: &str,
+    schema: &str,
+    relationships: &str,
+    examples: &str,
+) -> String {
+    template
         .replace(SCHEMA_PLACEHOLDER, schema.trim_end())
         .replace(RELATIONSHIPS_PLACEHOLDER, relationships.trim_end())
+        .replace(EXAMPLES_PLACEHOLDER, examples.trim_end())
+}
+
+/// [`generate_schema`] with `custom_types`, such as those of custom resources; custom types
+/// named like a built-in one are left out.
+pub fn generate_schema_with(custom_types: &[SchemaInfo]) -> Vec<SchemaInfo> {
+    let mut schema = generate_schema();
+    for custom_type in custom_types {
+        if !schema
+            .iter()
+            .any(|info| info.root_type.name == custom_type.root_type.name)
+        {
+            schema.push(custom_type.clone());
+        }
+    }
+    schema.sort_by_key(|x| x.root_type.name.clone());
+    schema
 }
 
-fn generate_schema() -> Vec<SchemaInfo> {
+/// The node types of the graph, sorted by name.
+pub fn generate_schema() -> Vec<SchemaInfo> {
     let logical_types: Vec<Schema> = vec![
         schema_for!(Cluster),
         schema_for!(Container),
@@ -69,6 +375,7 @@ fn generate_schema() -> Vec<SchemaInfo> {
         schema_for!(NetworkPolicy),
         schema_for!(Node),
         schema_for!(PersistentVolume),
+        schema_for!(PersistentVolumeClaim),
         schema_for!(Pod),
         schema_for!(ReplicaSet),
         schema_for!(Service),
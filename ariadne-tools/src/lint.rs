@@ -0,0 +1,41 @@
+use std::collections::BTreeSet;
+
+use ariadne_core::graph_schema::relationship_issues;
+use ariadne_core::types::LOGICAL_RESOURCE_TYPES;
+
+use crate::{generate_schema, graph_labels};
+
+/// Node types the relationships name but no resolver creates, so there are no properties to
+/// describe.
+const TYPES_WITHOUT_SCHEMA: &[&str] = &["AWX"];
+
+/// Where the graph model is inconsistent: the issues of [`relationship_issues`] plus the node
+/// types and logical types [`generate_schema`] has no schema for. Empty when it is consistent.
+pub fn lint_model() -> Vec<String> {
+    let mut issues = relationship_issues();
+    let described: BTreeSet<String> = generate_schema()
+        .into_iter()
+        .map(|info| info.root_type.name)
+        .collect();
+    let mut node_types = graph_labels();
+    node_types.extend(LOGICAL_RESOURCE_TYPES.iter().map(ToString::to_string));
+    for node_type in node_types {
+        if !described.contains(&node_type) && !TYPES_WITHOUT_SCHEMA.contains(&node_type.as_str()) {
+            issues.push(format!("node type {node_type} has no schema"));
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fails the tests of ariadne-tools when an edge, resource type or node schema is added
+    /// without the rest of the model.
+    #[test]
+    fn the_graph_model_is_consistent() {
+        let issues = lint_model();
+        assert!(issues.is_empty(), "{}", issues.join("\n"));
+    }
+}
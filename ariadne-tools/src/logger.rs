@@ -1,4 +1,5 @@
 use std::env;
+use std::io::Stderr;
 use tracing_subscriber::fmt::format::{DefaultFields, Format};
 use tracing_subscriber::fmt::SubscriberBuilder;
 use tracing_subscriber::EnvFilter;
@@ -12,7 +13,8 @@ pub fn setup(module: &str, log_level: &str) {
     subscriber.init();
 }
 
-pub fn get_subscriber() -> SubscriberBuilder<DefaultFields, Format, EnvFilter> {
+/// Logs to stderr, so the output of a command can be piped.
+pub fn get_subscriber() -> SubscriberBuilder<DefaultFields, Format, EnvFilter, fn() -> Stderr> {
     tracing_subscriber::fmt()
         .with_env_filter(
             EnvFilter::from_default_env(), // .add_directive("opentelemetry=TRACE".parse().unwrap())
@@ -20,6 +22,7 @@ pub fn get_subscriber() -> SubscriberBuilder<DefaultFields, Format, EnvFilter> {
                                            // .add_directive("opentelemetry_otlp=TRACE".parse().unwrap())
                                            // .add_directive("opentelemetry_sdk=TRACE".parse().unwrap()),
         )
+        .with_writer(std::io::stderr as fn() -> Stderr)
         .with_file(true)
         .with_line_number(true)
         .with_thread_ids(true)
@@ -1,10 +1,34 @@
 use crate::logger::setup;
-use clap::Parser;
+use ariadne_core::fixtures::golden_fixtures;
+use ariadne_core::kube_client::list_custom_resource_definitions;
+use ariadne_core::prelude::Result;
+use ariadne_core::snapshot::{validate_snapshot_dir, write_snapshot_dir};
+use ariadne_core::synth::{object_counts, synth_snapshot, SynthOptions};
+#[cfg(feature = "memgraph")]
+use ariadne_tools::backend::{backend_stats, validate_backend};
+use ariadne_tools::crd::{crd_schemas, load_crds};
+use ariadne_tools::ddl::{ddl_statements, Dialect};
+use ariadne_tools::diagram::{relationships_diagram, DiagramFormat};
+use ariadne_tools::export::{to_json_schema, to_typescript};
+use ariadne_tools::import::{import_kubectl_dump, ImportFormat};
+use ariadne_tools::lint::lint_model;
+use ariadne_tools::report::prompt_report;
+use ariadne_tools::schema::{
+    write_compact_schema_prompt_with, write_schema_prompt_with, SchemaPromptOptions,
+};
+use ariadne_tools::stats::snapshot_stats;
+use ariadne_tools::{
+    default_prompt_template, generate_schema, generate_schema_with, graph_relationships,
+    render_prompt, validate_template, PromptVariables,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
 #[cfg(feature = "build-info")]
 use shadow_rs::shadow;
+use std::path::PathBuf;
+use std::process::ExitCode;
 use tracing::info;
 pub mod logger;
-use ariadne_tools::{full_prompt, schema_prompt};
 
 #[cfg(feature = "build-info")]
 shadow!(build);
@@ -28,27 +52,482 @@ pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 struct AppArgs {
     #[arg(
         long,
-        help = "Print the full prompt template with schema and relationships"
+        global = true,
+        value_enum,
+        default_value_t = OutputFormat::Text,
+        help = "Print text to read or JSON for scripts"
     )]
-    full_prompt: bool,
+    output: OutputFormat,
+    #[command(subcommand)]
+    command: Command,
 }
 
-fn main() {
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    JsonSchema,
+    Typescript,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Command {
+    /// Print the node types of the graph and their properties
+    Schema {
+        #[arg(
+            long,
+            help = "Write types without full paths, as the compact prompt does"
+        )]
+        compact: bool,
+        #[command(flatten)]
+        options: PromptOptions,
+    },
+    /// Export the node types as one JSON Schema document or as TypeScript interfaces
+    Export {
+        #[arg(value_enum)]
+        format: ExportFormat,
+    },
+    /// Print the constraints and indexes that pre-provision a database for the graph
+    Ddl {
+        #[arg(long, value_enum, default_value_t = Dialect::Memgraph)]
+        dialect: Dialect,
+    },
+    /// Print the relationships between the node types
+    Relationships {
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = DiagramFormat::Cypher,
+            help = "How to draw the relationships as text"
+        )]
+        format: DiagramFormat,
+    },
+    /// Print the prompt template filled with the schema and relationships
+    FullPrompt {
+        #[arg(long, help = "Print the compact prompt used for small local models")]
+        compact: bool,
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Only include these node types and the relationships between them"
+        )]
+        labels: Vec<String>,
+        #[command(flatten)]
+        options: PromptOptions,
+        #[arg(
+            long,
+            help = "Print estimated tokens per section and node type instead of the prompt"
+        )]
+        report: bool,
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Fill this template instead of the built-in one"
+        )]
+        template: Option<PathBuf>,
+        #[command(flatten)]
+        variables: VariableArgs,
+    },
+    /// Check that every edge is in a relationship, every endpoint is a resource type and every
+    /// node type has a schema; exits with 1 on an inconsistency
+    Lint,
+    /// Compare the labels, relationships, properties and indexes of a running database with the
+    /// graph model; exits with 1 on drift
+    #[cfg(feature = "memgraph")]
+    ValidateBackend {
+        #[arg(long, env = "MEMGRAPH_URL", help = "Bolt URL of the database")]
+        graph_url: String,
+    },
+    /// Count the nodes and relationships of a snapshot's graph, with degree distributions and
+    /// the largest namespaces, or of a running database by label and type
+    Stats {
+        #[arg(help = "Snapshot directory to resolve and measure instead of a database")]
+        snapshot_dir: Option<PathBuf>,
+        #[arg(
+            long,
+            env = "MEMGRAPH_URL",
+            required_unless_present = "snapshot_dir",
+            help = "Bolt URL of the database"
+        )]
+        graph_url: Option<String>,
+    },
+    /// Check a snapshot directory for missing files, malformed JSON, objects without a uid or
+    /// name and shared uids; exits with 1 on an issue
+    ValidateSnapshot {
+        #[arg(help = "Snapshot directory to check")]
+        snapshot_dir: PathBuf,
+    },
+    /// Convert the objects of a cluster read with other tools into a snapshot directory
+    Import {
+        #[arg(long, value_enum, help = "What the input was written by")]
+        from: ImportFormat,
+        #[arg(help = "File or directory of JSON files to read")]
+        input: PathBuf,
+        #[arg(help = "Snapshot directory to write")]
+        output_dir: PathBuf,
+        #[arg(
+            long,
+            default_value = "imported",
+            help = "Name of the cluster in the snapshot"
+        )]
+        cluster_name: String,
+        #[arg(
+            long,
+            default_value = "",
+            help = "API server URL of the cluster in the snapshot"
+        )]
+        cluster_url: String,
+    },
+    /// Write the golden fixtures of the engine tests as snapshot directories with the rows their
+    /// queries are expected to return
+    Fixtures {
+        #[arg(help = "Directory to write one subdirectory per fixture to")]
+        output_dir: PathBuf,
+    },
+    /// Write a snapshot directory of a generated cluster with a realistic topology, for load
+    /// tests of the resolver, the backends and the in-memory engine
+    Synth {
+        #[arg(help = "Snapshot directory to write")]
+        output_dir: PathBuf,
+        #[arg(long, default_value_t = 1000)]
+        pods: usize,
+        #[arg(long, default_value_t = 20)]
+        namespaces: usize,
+        #[arg(long, help = "Number of nodes; one per 30 pods by default")]
+        nodes: Option<usize>,
+        #[arg(
+            long,
+            default_value_t = 0,
+            help = "Equal seeds generate equal clusters"
+        )]
+        seed: u64,
+    },
+}
+
+/// Values of the template placeholders that tailor a prompt to a deployment.
+#[derive(Args, Debug, Clone)]
+struct VariableArgs {
+    #[arg(long, help = "Value of {{CLUSTER_NAME}}")]
+    cluster_name: Option<String>,
+    #[arg(long, help = "Value of {{KUBERNETES_VERSION}}, such as v1.31.2")]
+    kubernetes_version: Option<String>,
+    #[arg(long, help = "Value of {{INSTRUCTIONS}}")]
+    instructions: Option<String>,
+}
+
+impl VariableArgs {
+    fn prompt_variables(&self) -> PromptVariables {
+        PromptVariables {
+            cluster_name: self.cluster_name.clone(),
+            kubernetes_version: self.kubernetes_version.clone(),
+            instructions: self.instructions.clone(),
+        }
+    }
+}
+
+/// How much of the schema a prompt writes.
+#[derive(Args, Debug, Clone)]
+struct PromptOptions {
+    #[arg(
+        long,
+        help = "Levels of referenced types to write below the node types"
+    )]
+    max_depth: Option<usize>,
+    #[arg(
+        long = "properties",
+        value_name = "TYPE=PROPERTY,...",
+        value_parser = parse_allowlist_entry,
+        help = "Only write these properties of a type; repeat for more types"
+    )]
+    property_allowlist: Vec<(String, Vec<String>)>,
+    #[arg(
+        long,
+        help = "Follow each property with the first sentence of its description"
+    )]
+    descriptions: bool,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Add the custom resources defined in a snapshot directory or in the output of \
+                `kubectl get crd -o json`"
+    )]
+    crds: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "CONTEXT",
+        conflicts_with = "crds",
+        help = "Add the custom resources installed in the cluster of this kubeconfig context"
+    )]
+    crds_from_context: Option<String>,
+}
+
+impl PromptOptions {
+    fn schema_options(&self) -> Result<SchemaPromptOptions> {
+        let crds = if let Some(path) = &self.crds {
+            load_crds(path)?
+        } else if let Some(context) = &self.crds_from_context {
+            tokio::runtime::Runtime::new()?
+                .block_on(list_custom_resource_definitions(Some(context.clone())))?
+        } else {
+            Vec::new()
+        };
+        Ok(SchemaPromptOptions {
+            max_depth: self.max_depth,
+            property_allowlist: self.property_allowlist.iter().cloned().collect(),
+            descriptions: self.descriptions,
+            custom_types: crd_schemas(&crds),
+        })
+    }
+}
+
+fn parse_allowlist_entry(entry: &str) -> std::result::Result<(String, Vec<String>), String> {
+    let (type_name, properties) = entry
+        .split_once('=')
+        .ok_or_else(|| format!("expected TYPE=PROPERTY,..., got {entry}"))?;
+    let properties = properties
+        .split(',')
+        .map(str::trim)
+        .filter(|property| !property.is_empty())
+        .map(str::to_string)
+        .collect();
+    Ok((type_name.trim().to_string(), properties))
+}
+
+fn main() -> ExitCode {
     setup("ariadne_tools", "debug");
     let args = AppArgs::parse();
     info!("Received args: {:?}", args);
 
-    let prompt = if args.full_prompt {
-        full_prompt()
-    } else {
-        schema_prompt()
-    };
-    println!("{prompt}");
+    match run(&args) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &AppArgs) -> Result<ExitCode> {
+    let json = args.output == OutputFormat::Json;
+    match &args.command {
+        Command::Schema { compact, options } => {
+            let options = options.schema_options()?;
+            let schema = generate_schema_with(&options.custom_types);
+            if json {
+                print_json(&schema)?;
+            } else if *compact {
+                println!("{}", write_compact_schema_prompt_with(schema, &options));
+            } else {
+                println!("{}", write_schema_prompt_with(schema, &options));
+            }
+        }
+        Command::Export { format } => match format {
+            ExportFormat::JsonSchema => print_json(&to_json_schema(&generate_schema()))?,
+            ExportFormat::Typescript => print!("{}", to_typescript(&generate_schema())),
+        },
+        Command::Ddl { dialect } => {
+            let statements = ddl_statements(*dialect);
+            if json {
+                print_json(&statements)?;
+            } else {
+                println!("{}", statements.join("\n"));
+            }
+        }
+        Command::Relationships { format } => {
+            let relationships = graph_relationships();
+            if json {
+                print_json(&relationships)?;
+            } else {
+                print!("{}", relationships_diagram(&relationships, *format));
+            }
+        }
+        Command::FullPrompt {
+            compact,
+            labels,
+            options,
+            report,
+            template,
+            variables,
+        } => {
+            let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+            let labels = (!labels.is_empty()).then_some(labels.as_slice());
+            let template = match template {
+                Some(path) => {
+                    let template = std::fs::read_to_string(path)?;
+                    validate_template(&template).map_err(|err| {
+                        std::io::Error::other(format!("{}: {err}", path.display()))
+                    })?;
+                    template
+                }
+                None => default_prompt_template(*compact).to_string(),
+            };
+            let options = options.schema_options()?;
+            if *report {
+                let report = prompt_report(&template, *compact, labels, &options);
+                if json {
+                    print_json(&report)?;
+                } else {
+                    print!("{report}");
+                }
+                return Ok(ExitCode::SUCCESS);
+            }
+            let prompt = render_prompt(
+                &template,
+                *compact,
+                labels,
+                &options,
+                &variables.prompt_variables(),
+            );
+            if json {
+                print_json(&serde_json::json!({ "prompt": prompt }))?;
+            } else {
+                println!("{prompt}");
+            }
+        }
+        Command::Lint => {
+            let issues = lint_model();
+            if json {
+                print_json(&issues)?;
+            } else if issues.is_empty() {
+                println!("The graph model is consistent.");
+            } else {
+                println!("{}", issues.join("\n"));
+            }
+            if !issues.is_empty() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        #[cfg(feature = "memgraph")]
+        Command::ValidateBackend { graph_url } => {
+            let drift = validate_backend(graph_url)?;
+            if json {
+                print_json(&drift)?;
+            } else {
+                print!("{drift}");
+            }
+            if drift.has_drift() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Stats {
+            snapshot_dir: Some(dir),
+            ..
+        } => {
+            let stats = tokio::runtime::Runtime::new()?.block_on(snapshot_stats(dir))?;
+            if json {
+                print_json(&stats)?;
+            } else {
+                print!("{stats}");
+            }
+        }
+        Command::Stats {
+            snapshot_dir: None,
+            graph_url,
+        } => {
+            let graph_url = graph_url.as_deref().ok_or_else(|| {
+                std::io::Error::other("a snapshot directory or --graph-url is required")
+            })?;
+            #[cfg(feature = "memgraph")]
+            {
+                let stats = backend_stats(graph_url)?;
+                if json {
+                    print_json(&stats)?;
+                } else {
+                    print!("{stats}");
+                }
+            }
+            #[cfg(not(feature = "memgraph"))]
+            return Err(std::io::Error::other(format!(
+                "cannot measure {graph_url}: ariadne-tools was built without the memgraph feature"
+            ))
+            .into());
+        }
+        Command::ValidateSnapshot { snapshot_dir } => {
+            let report = validate_snapshot_dir(snapshot_dir);
+            if json {
+                print_json(&report)?;
+            } else {
+                print!("{report}");
+            }
+            if !report.is_valid() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Import {
+            from: ImportFormat::KubectlDump,
+            input,
+            output_dir,
+            cluster_name,
+            cluster_url,
+        } => {
+            let import = import_kubectl_dump(input, cluster_name, cluster_url)?;
+            std::fs::create_dir_all(output_dir)?;
+            write_snapshot_dir(output_dir, &import.snapshot)?;
+            if json {
+                print_json(&import.summary)?;
+            } else {
+                print!("{}", import.summary);
+            }
+        }
+        Command::Fixtures { output_dir } => {
+            let mut dirs = Vec::new();
+            for fixture in golden_fixtures() {
+                let dir = output_dir.join(fixture.name);
+                fixture.write_to_dir(&dir)?;
+                dirs.push(dir);
+            }
+            if json {
+                print_json(&dirs)?;
+            } else {
+                for dir in dirs {
+                    println!("{}", dir.display());
+                }
+            }
+        }
+        Command::Synth {
+            output_dir,
+            pods,
+            namespaces,
+            nodes,
+            seed,
+        } => {
+            let snapshot = synth_snapshot(&SynthOptions {
+                pods: *pods,
+                namespaces: *namespaces,
+                nodes: *nodes,
+                seed: *seed,
+            });
+            std::fs::create_dir_all(output_dir)?;
+            write_snapshot_dir(output_dir, &snapshot)?;
+            let counts = object_counts(&snapshot);
+            if json {
+                print_json(&counts)?;
+            } else {
+                for (kind, count) in counts {
+                    println!("{kind}: {count}");
+                }
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_json(value: &impl Serialize) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ariadne_tools::{
+        compact_prompt, compact_prompt_for, full_prompt, full_prompt_for, full_prompt_with,
+        schema_prompt,
+    };
 
     #[test]
     fn test_get_schema() {
@@ -72,6 +551,7 @@ mod tests {
   NetworkPolicy: 4 properties (apiVersion: STRING, kind: STRING, metadata: #/$defs/io.k8s.apimachinery.pkg.apis.meta.v1.ObjectMeta, spec: #/$defs/io.k8s.api.networking.v1.NetworkPolicySpec)
   Node: 5 properties (apiVersion: STRING, kind: STRING, metadata: #/$defs/io.k8s.apimachinery.pkg.apis.meta.v1.ObjectMeta, spec: #/$defs/io.k8s.api.core.v1.NodeSpec, status: #/$defs/io.k8s.api.core.v1.NodeStatus)
   PersistentVolume: 5 properties (apiVersion: STRING, kind: STRING, metadata: #/$defs/io.k8s.apimachinery.pkg.apis.meta.v1.ObjectMeta, spec: #/$defs/io.k8s.api.core.v1.PersistentVolumeSpec, status: #/$defs/io.k8s.api.core.v1.PersistentVolumeStatus)
+  PersistentVolumeClaim: 5 properties (apiVersion: STRING, kind: STRING, metadata: #/$defs/io.k8s.apimachinery.pkg.apis.meta.v1.ObjectMeta, spec: #/$defs/io.k8s.api.core.v1.PersistentVolumeClaimSpec, status: #/$defs/io.k8s.api.core.v1.PersistentVolumeClaimStatus)
   Pod: 5 properties (apiVersion: STRING, kind: STRING, metadata: #/$defs/io.k8s.apimachinery.pkg.apis.meta.v1.ObjectMeta, spec: #/$defs/io.k8s.api.core.v1.PodSpec, status: #/$defs/io.k8s.api.core.v1.PodStatus)
   Provisioner: 2 properties (metadata: #/$defs/io.k8s.apimachinery.pkg.apis.meta.v1.ObjectMeta, name: STRING)
   ReplicaSet: 5 properties (apiVersion: STRING, kind: STRING, metadata: #/$defs/io.k8s.apimachinery.pkg.apis.meta.v1.ObjectMeta, spec: #/$defs/io.k8s.api.apps.v1.ReplicaSetSpec, status: #/$defs/io.k8s.api.apps.v1.ReplicaSetStatus)
@@ -310,4 +790,102 @@ Referenced types (used via `#/$defs/`):
 "#;
         assert_eq!(schema, expected);
     }
+
+    #[test]
+    fn test_compact_prompt() {
+        let prompt = compact_prompt();
+        assert!(prompt.len() < full_prompt().len() * 3 / 4);
+        assert!(!prompt.contains("{{"));
+        assert!(!prompt.contains("#/$defs/"));
+        assert!(prompt.contains(
+            "  Pod(apiVersion: STRING, kind: STRING, metadata: ObjectMeta, spec: PodSpec, \
+             status: PodStatus)\n"
+        ));
+        assert!(prompt.contains("containers: [Container]"));
+    }
+
+    #[test]
+    fn test_prompt_for_labels() {
+        let prompt = full_prompt_for(&["Pod", "Node"]);
+        assert!(prompt.len() < full_prompt().len() / 2);
+        assert!(prompt.contains("  Pod: 5 properties"));
+        assert!(prompt.contains("(:Pod)-[:RunsOn]->(:Node)"));
+        assert!(!prompt.contains("  Service: "));
+        assert!(!prompt.contains("(:Pod)-[:BelongsTo]->(:Namespace)"));
+
+        let prompt = compact_prompt_for(&["Pod", "Node"]);
+        assert!(prompt.len() < compact_prompt().len());
+        assert!(prompt.contains("(:Pod)-[:RunsOn]->(:Node)"));
+    }
+
+    #[test]
+    fn test_schema_prompt_options() {
+        let full = schema_prompt();
+        let node_types_only = write_schema_prompt_with(
+            generate_schema(),
+            &SchemaPromptOptions {
+                max_depth: Some(0),
+                ..Default::default()
+            },
+        );
+        assert!(node_types_only.contains("  Pod: 5 properties"));
+        assert!(!node_types_only.contains("  io.k8s.apimachinery.pkg.apis.meta.v1.ObjectMeta: "));
+
+        let one_level = write_schema_prompt_with(
+            generate_schema(),
+            &SchemaPromptOptions {
+                max_depth: Some(1),
+                ..Default::default()
+            },
+        );
+        assert!(one_level.contains("  io.k8s.api.core.v1.PodSpec: "));
+        assert!(!one_level.contains("  io.k8s.api.core.v1.PodAffinity: "));
+        assert!(one_level.len() < full.len());
+
+        let options = SchemaPromptOptions {
+            property_allowlist: [(
+                "Pod".to_string(),
+                vec!["metadata".to_string(), "status".to_string()],
+            )]
+            .into(),
+            descriptions: true,
+            ..Default::default()
+        };
+        let described = write_schema_prompt_with(generate_schema(), &options);
+        assert!(described.contains("  Pod: 2 properties (metadata: "));
+        assert!(described.contains("replicas: INTEGER /* "));
+    }
+
+    #[test]
+    fn test_prompt_variables() {
+        assert_eq!(full_prompt_with(&PromptVariables::default()), full_prompt());
+
+        let template = "Cluster {{CLUSTER_NAME}} runs Kubernetes {{KUBERNETES_VERSION}}.\n\
+                        Types: {{RESOURCE_TYPES}}\n{{SCHEMA}}\n{{RELATIONSHIPS}}\n{{INSTRUCTIONS}}";
+        assert_eq!(validate_template(template), Ok(()));
+        let variables = PromptVariables {
+            cluster_name: Some("prod-eu".to_string()),
+            instructions: Some("Team namespaces start with team-.".to_string()),
+            ..Default::default()
+        };
+        let labels = ["Pod", "Node"];
+        let prompt = render_prompt(
+            template,
+            true,
+            Some(&labels),
+            &SchemaPromptOptions::default(),
+            &variables,
+        );
+        assert!(prompt.starts_with("Cluster prod-eu runs Kubernetes unknown.\nTypes: Node, Pod\n"));
+        assert!(prompt.ends_with("(:Pod)-[:RunsOn]->(:Node)\nTeam namespaces start with team-."));
+
+        assert_eq!(
+            validate_template("{{SCHEMA}} {{RELATIONSHIPS}} {{HISTORY}}"),
+            Err("unknown placeholder {{HISTORY}}".to_string())
+        );
+        assert_eq!(
+            validate_template("{{SCHEMA}} {{CLUSTER_NAME}}"),
+            Err("missing placeholder {{RELATIONSHIPS}}".to_string())
+        );
+    }
 }
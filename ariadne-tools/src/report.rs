@@ -0,0 +1,173 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::examples::examples_prompt;
+use crate::schema::{self, SchemaInfo, SchemaPromptOptions};
+use crate::{fill_template, prompt_schema, relationships_prompt, EXAMPLES_PLACEHOLDER};
+
+/// Node types marked as the biggest contributors of a report.
+const BIGGEST: usize = 3;
+
+/// Estimated tokens of a prompt by section, so users know what to trim for small-context models.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PromptReport {
+    pub total_tokens: usize,
+    /// The template around the schema and relationships.
+    pub instruction_tokens: usize,
+    pub relationship_tokens: usize,
+    /// The example queries of the relationships, when the template has a place for them.
+    pub example_tokens: usize,
+    /// Largest first.
+    pub node_types: Vec<NodeTypeSize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NodeTypeSize {
+    pub name: String,
+    /// The node type with the types it references; referenced types shared by several node
+    /// types count for each of them.
+    pub tokens: usize,
+    /// The node type's own line.
+    pub own_tokens: usize,
+    /// Whether it is one of the biggest contributors.
+    pub biggest: bool,
+}
+
+/// Rough token count of `text`, at four bytes a token.
+pub fn estimate_tokens(text: &str) -> usize {
+    if text.is_empty() {
+        0
+    } else {
+        (text.len() / 4).max(1)
+    }
+}
+
+/// The size report of the prompt [`crate::prompt_from_template_with`] writes for the same
+/// arguments.
+pub fn prompt_report(
+    template: &str,
+    compact: bool,
+    labels: Option<&[&str]>,
+    options: &SchemaPromptOptions,
+) -> PromptReport {
+    let (schema_list, relationships) = prompt_schema(labels, &options.custom_types);
+    let write = |schema_list: Vec<SchemaInfo>, options: &SchemaPromptOptions| {
+        if compact {
+            schema::write_compact_schema_prompt_with(schema_list, options)
+        } else {
+            schema::write_schema_prompt_with(schema_list, options)
+        }
+    };
+    let headers = estimate_tokens(&write(Vec::new(), options));
+    let own_options = SchemaPromptOptions {
+        max_depth: Some(0),
+        ..options.clone()
+    };
+    let mut node_types: Vec<NodeTypeSize> = schema_list
+        .iter()
+        .map(|info| NodeTypeSize {
+            name: info.root_type.name.clone(),
+            tokens: estimate_tokens(&write(vec![info.clone()], options)).saturating_sub(headers),
+            own_tokens: estimate_tokens(&write(vec![info.clone()], &own_options))
+                .saturating_sub(headers),
+            biggest: false,
+        })
+        .collect();
+    node_types.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.name.cmp(&b.name)));
+    for node_type in node_types.iter_mut().take(BIGGEST) {
+        node_type.biggest = true;
+    }
+
+    let examples = if template.contains(EXAMPLES_PLACEHOLDER) {
+        examples_prompt(&relationships, &schema_list)
+    } else {
+        String::new()
+    };
+    let schema = write(schema_list, options);
+    let relationships = relationships_prompt(&relationships);
+    PromptReport {
+        total_tokens: estimate_tokens(&fill_template(template, &schema, &relationships, &examples)),
+        instruction_tokens: estimate_tokens(&fill_template(template, "", "", "")),
+        relationship_tokens: estimate_tokens(&relationships),
+        example_tokens: estimate_tokens(&examples),
+        node_types,
+    }
+}
+
+impl fmt::Display for PromptReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let share = |tokens: usize| tokens * 100 / self.total_tokens.max(1);
+        writeln!(f, "Prompt: ~{} tokens", self.total_tokens)?;
+        writeln!(
+            f,
+            "  Instructions: ~{} ({}%)",
+            self.instruction_tokens,
+            share(self.instruction_tokens)
+        )?;
+        writeln!(
+            f,
+            "  Relationships: ~{} ({}%)",
+            self.relationship_tokens,
+            share(self.relationship_tokens)
+        )?;
+        if self.example_tokens > 0 {
+            writeln!(
+                f,
+                "  Example queries: ~{} ({}%)",
+                self.example_tokens,
+                share(self.example_tokens)
+            )?;
+        }
+        writeln!(
+            f,
+            "  Node types, with the types they reference (shared types count for each):"
+        )?;
+        for node_type in &self.node_types {
+            writeln!(
+                f,
+                "    {}: ~{} ({}%), own line ~{}{}",
+                node_type.name,
+                node_type.tokens,
+                share(node_type.tokens),
+                node_type.own_tokens,
+                if node_type.biggest {
+                    "  <- biggest"
+                } else {
+                    ""
+                }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{default_prompt_template, full_prompt, generate_schema};
+
+    #[test]
+    fn report_ranks_node_types_by_size() {
+        let report = prompt_report(
+            default_prompt_template(false),
+            false,
+            None,
+            &SchemaPromptOptions::default(),
+        );
+        assert_eq!(report.total_tokens, estimate_tokens(&full_prompt()));
+        assert!(report.instruction_tokens > 0);
+        assert!(report.relationship_tokens > 0);
+        assert!(report.example_tokens > 0);
+        assert_eq!(report.node_types.len(), generate_schema().len());
+        assert!(report.node_types[0].biggest);
+        assert!(report.node_types[0].tokens >= report.node_types[1].tokens);
+        let pod = report
+            .node_types
+            .iter()
+            .find(|node_type| node_type.name == "Pod")
+            .unwrap();
+        assert!(pod.own_tokens < pod.tokens);
+        assert!(report.to_string().contains("  <- biggest\n"));
+    }
+}
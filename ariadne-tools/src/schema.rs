@@ -1,19 +1,29 @@
 use schemars::Schema;
+use serde::Serialize;
 use serde_json::{Map, Value};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::TryFrom;
 
+#[derive(Debug, Clone, Serialize)]
 pub struct Property {
     pub name: String,
     pub data_type: String,
+    /// The field's documentation from the OpenAPI schema, when it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
 }
 
 impl Property {
     pub fn new(name: String, data_type: String) -> Self {
-        Self { name, data_type }
+        Self {
+            name,
+            data_type,
+            description: None,
+        }
     }
 }
 
+#[derive(Debug, Clone, Serialize)]
 pub struct Type {
     pub name: String,
     pub properties: Vec<Property>,
@@ -25,6 +35,7 @@ impl Type {
     }
 }
 
+#[derive(Debug, Clone, Serialize)]
 pub struct SchemaInfo {
     pub root_type: Type,
     pub definitions: BTreeMap<String, Type>,
@@ -86,31 +97,140 @@ pub fn get_schema(schema: &Schema) -> SchemaInfo {
     SchemaInfo::new(root_type, definitions)
 }
 
+/// What the schema prompt writers keep of the schema, to trade prompt size against fidelity.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaPromptOptions {
+    /// Levels of referenced types written below the node types: 0 writes the node types only,
+    /// 1 also the types their properties reference, and so on. `None` writes all of them.
+    pub max_depth: Option<usize>,
+    /// The properties to write of the types named here, by short or full name. Types not named
+    /// keep all their properties.
+    pub property_allowlist: BTreeMap<String, Vec<String>>,
+    /// Whether to follow each property with the first sentence of its description.
+    pub descriptions: bool,
+    /// Node types written besides the built-in ones, such as those of custom resources. Used
+    /// where the prompt's schema is generated, not by the writers given a schema list.
+    pub custom_types: Vec<SchemaInfo>,
+}
+
 pub fn write_schema_prompt(schema_list: Vec<SchemaInfo>) -> String {
+    write_schema_prompt_with(schema_list, &SchemaPromptOptions::default())
+}
+
+/// [`write_schema_prompt`] with the types and properties `options` keep.
+pub fn write_schema_prompt_with(
+    schema_list: Vec<SchemaInfo>,
+    options: &SchemaPromptOptions,
+) -> String {
+    let schema_list = apply_options(schema_list, options);
     let mut prompt = String::from("Node properties:\n");
     let mut all_defs: BTreeMap<String, Type> = BTreeMap::new();
 
     for mut schema in schema_list {
-        let type_expr = to_type_expression(&schema.root_type);
+        let type_expr = to_type_expression(&schema.root_type, options.descriptions);
         prompt += &type_expr;
         all_defs.append(&mut schema.definitions);
     }
 
     prompt += "Referenced types (used via `#/$defs/`):\n";
     for (_, definition) in all_defs {
-        let type_expr = to_type_expression(&definition);
+        let type_expr = to_type_expression(&definition, options.descriptions);
         prompt += &type_expr;
     }
     prompt.push('\n');
     prompt
 }
 
-fn to_type_expression(root_type: &Type) -> String {
+/// A smaller variant of [`write_schema_prompt`] for models with short context windows: type
+/// references are reduced to their short names and property counts are left out.
+pub fn write_compact_schema_prompt(schema_list: Vec<SchemaInfo>) -> String {
+    write_compact_schema_prompt_with(schema_list, &SchemaPromptOptions::default())
+}
+
+/// [`write_compact_schema_prompt`] with the types and properties `options` keep.
+pub fn write_compact_schema_prompt_with(
+    schema_list: Vec<SchemaInfo>,
+    options: &SchemaPromptOptions,
+) -> String {
+    let schema_list = apply_options(schema_list, options);
+    let mut prompt = String::from("Node properties:\n");
+    let mut all_defs: BTreeMap<String, String> = BTreeMap::new();
+
+    for schema in schema_list {
+        prompt += &to_compact_type_expression(
+            &schema.root_type.name,
+            &schema.root_type,
+            options.descriptions,
+        );
+        for (full_name, definition) in &schema.definitions {
+            let name = short_type_name(full_name).to_string();
+            if !all_defs.contains_key(&name) {
+                let expression =
+                    to_compact_type_expression(&name, definition, options.descriptions);
+                all_defs.insert(name, expression);
+            }
+        }
+    }
+
+    prompt += "Nested types:\n";
+    for expression in all_defs.into_values() {
+        prompt += &expression;
+    }
+    prompt.push('\n');
+    prompt
+}
+
+fn to_compact_type_expression(name: &str, root_type: &Type, descriptions: bool) -> String {
+    let props = root_type
+        .properties
+        .iter()
+        .map(|property| {
+            format!(
+                "{}: {}{}",
+                property.name,
+                shorten_references(&property.data_type),
+                description_comment(property, descriptions)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("  {name}({props})\n")
+}
+
+/// Replaces every `#/$defs/io.k8s...PodSpec` style reference in a type expression with its short
+/// name (`PodSpec`).
+pub fn shorten_references(data_type: &str) -> String {
+    let mut output = String::with_capacity(data_type.len());
+    let mut rest = data_type;
+    while let Some(start) = rest.find("#/") {
+        output.push_str(&rest[..start]);
+        let reference = &rest[start..];
+        let end = reference
+            .find(|c: char| {
+                !(c.is_alphanumeric() || matches!(c, '#' | '/' | '$' | '.' | '_' | '-'))
+            })
+            .unwrap_or(reference.len());
+        let full_name = reference[..end].rsplit('/').next().unwrap_or_default();
+        output.push_str(short_type_name(full_name));
+        rest = &reference[end..];
+    }
+    output.push_str(rest);
+    output
+}
+
+fn to_type_expression(root_type: &Type, descriptions: bool) -> String {
     let name = root_type.name.as_str();
     let properties = root_type.properties.as_slice();
     let props_with_type = properties
         .iter()
-        .map(|property| format!("{}: {}", property.name, property.data_type))
+        .map(|property| {
+            format!(
+                "{}: {}{}",
+                property.name,
+                property.data_type,
+                description_comment(property, descriptions)
+            )
+        })
         .collect::<Vec<String>>()
         .join(", ");
     let prop_message = if properties.len() > 1 {
@@ -124,6 +244,84 @@ fn to_type_expression(root_type: &Type) -> String {
     )
 }
 
+/// ` /* First sentence of the description. */` when `descriptions` is set and the property has
+/// a description.
+fn description_comment(property: &Property, descriptions: bool) -> String {
+    let Some(description) = property.description.as_deref().filter(|_| descriptions) else {
+        return String::new();
+    };
+    let description = description.split_whitespace().collect::<Vec<_>>().join(" ");
+    let sentence = match description.find(". ") {
+        Some(end) => &description[..=end],
+        None => description.as_str(),
+    };
+    format!(" /* {} */", sentence.replace("*/", "* /"))
+}
+
+/// Drops the properties `options` do not allow and the referenced types deeper than its
+/// maximum depth.
+fn apply_options(
+    mut schema_list: Vec<SchemaInfo>,
+    options: &SchemaPromptOptions,
+) -> Vec<SchemaInfo> {
+    for schema in &mut schema_list {
+        allow_properties(&mut schema.root_type, &options.property_allowlist);
+        for definition in schema.definitions.values_mut() {
+            allow_properties(definition, &options.property_allowlist);
+        }
+        if let Some(max_depth) = options.max_depth {
+            let mut kept: BTreeSet<String> = BTreeSet::new();
+            let mut level: Vec<&Type> = vec![&schema.root_type];
+            for _ in 0..max_depth {
+                let mut next = Vec::new();
+                for ty in level {
+                    for property in &ty.properties {
+                        for name in referenced_definitions(&property.data_type) {
+                            if let Some(definition) = schema.definitions.get(name) {
+                                if kept.insert(name.to_string()) {
+                                    next.push(definition);
+                                }
+                            }
+                        }
+                    }
+                }
+                level = next;
+            }
+            schema.definitions.retain(|name, _| kept.contains(name));
+        }
+    }
+    schema_list
+}
+
+fn allow_properties(ty: &mut Type, allowlist: &BTreeMap<String, Vec<String>>) {
+    let allowed = allowlist
+        .get(&ty.name)
+        .or_else(|| allowlist.get(short_type_name(&ty.name)));
+    if let Some(allowed) = allowed {
+        ty.properties
+            .retain(|property| allowed.contains(&property.name));
+    }
+}
+
+/// The full names of the types a data type such as `[#/$defs/io.k8s...Container]` references.
+fn referenced_definitions(data_type: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = data_type;
+    while let Some(start) = rest.find("#/") {
+        let reference = &rest[start..];
+        let end = reference
+            .find(|c: char| {
+                !(c.is_alphanumeric() || matches!(c, '#' | '/' | '$' | '.' | '_' | '-'))
+            })
+            .unwrap_or(reference.len());
+        if let Some(name) = reference[..end].rsplit('/').next() {
+            names.push(name);
+        }
+        rest = &reference[end..];
+    }
+    names
+}
+
 fn process_schema(type_name: &str, schema: &Schema) -> Option<Type> {
     let object = schema.as_object()?;
     let properties = object.get("properties")?.as_object()?;
@@ -139,7 +337,12 @@ fn process_schema(type_name: &str, schema: &Schema) -> Option<Type> {
         };
         let mut type_name = get_type_name(&schema);
         normalize_container_type(prop_name, &mut type_name);
-        props_info.push(Property::new(prop_name.clone(), type_name));
+        let mut property = Property::new(prop_name.clone(), type_name);
+        property.description = prop_schema_value
+            .get("description")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        props_info.push(property);
     }
 
     Some(Type::new(type_name.to_string(), props_info))
@@ -258,7 +461,7 @@ fn extract_reference(object: &Map<String, Value>) -> Option<&str> {
     object.get("$ref").and_then(Value::as_str)
 }
 
-fn map_reference(reference: &str) -> String {
+pub(crate) fn map_reference(reference: &str) -> String {
     match reference {
         "#/$defs/io.k8s.apimachinery.pkg.apis.meta.v1.Time"
         | "#/$defs/io.k8s.apimachinery.pkg.apis.meta.v1.MicroTime"
@@ -268,7 +471,7 @@ fn map_reference(reference: &str) -> String {
     }
 }
 
-fn short_type_name(full_name: &str) -> &str {
+pub(crate) fn short_type_name(full_name: &str) -> &str {
     full_name.rsplit('.').next().unwrap_or(full_name)
 }
 
@@ -0,0 +1,193 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+
+use ariadne_core::kube_client::SnapshotKubeClient;
+use ariadne_core::prelude::Result;
+use ariadne_core::state::ClusterState;
+use ariadne_core::state_resolver::ClusterStateResolver;
+use serde::Serialize;
+
+/// How many namespaces [`GraphStats`] lists.
+const LARGEST_NAMESPACES: usize = 10;
+
+/// Sizes of the graph a cluster resolves into.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GraphStats {
+    /// Node counts by type.
+    pub nodes: BTreeMap<String, usize>,
+    /// Edge counts by `(:From)-[:Edge]->(:To)` pattern.
+    pub relationships: BTreeMap<String, usize>,
+    /// Degrees, incoming and outgoing edges together, of the nodes of each type.
+    pub degrees: BTreeMap<String, DegreeDistribution>,
+    /// Namespaces with the most nodes, largest first.
+    pub largest_namespaces: Vec<NamespaceSize>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct DegreeDistribution {
+    pub min: usize,
+    pub median: usize,
+    pub p90: usize,
+    pub p99: usize,
+    pub max: usize,
+    pub mean: f64,
+}
+
+impl DegreeDistribution {
+    /// Distribution of `degrees`, which must not be empty.
+    fn of(mut degrees: Vec<usize>) -> Self {
+        degrees.sort_unstable();
+        let total: usize = degrees.iter().sum();
+        Self {
+            min: degrees[0],
+            median: percentile(&degrees, 50),
+            p90: percentile(&degrees, 90),
+            p99: percentile(&degrees, 99),
+            max: degrees[degrees.len() - 1],
+            mean: total as f64 / degrees.len() as f64,
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[usize], percent: usize) -> usize {
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NamespaceSize {
+    pub namespace: String,
+    pub nodes: usize,
+}
+
+impl GraphStats {
+    pub fn of(state: &ClusterState) -> Self {
+        let mut relationships: BTreeMap<String, usize> = BTreeMap::new();
+        let mut degree_by_uid: HashMap<String, usize> = HashMap::new();
+        for edge in state.get_edges() {
+            let pattern = format!(
+                "(:{})-[:{}]->(:{})",
+                edge.source_type, edge.edge_type, edge.target_type
+            );
+            *relationships.entry(pattern).or_default() += 1;
+            *degree_by_uid.entry(edge.source).or_default() += 1;
+            *degree_by_uid.entry(edge.target).or_default() += 1;
+        }
+
+        let mut nodes: BTreeMap<String, usize> = BTreeMap::new();
+        let mut degrees_by_type: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        let mut namespaces: HashMap<String, usize> = HashMap::new();
+        for node in state.get_nodes() {
+            let node_type = node.resource_type.to_string();
+            let degree = degree_by_uid.get(&node.id.uid).copied().unwrap_or_default();
+            degrees_by_type
+                .entry(node_type.clone())
+                .or_default()
+                .push(degree);
+            *nodes.entry(node_type).or_default() += 1;
+            if let Some(namespace) = &node.id.namespace {
+                *namespaces.entry(namespace.clone()).or_default() += 1;
+            }
+        }
+
+        let mut largest_namespaces: Vec<NamespaceSize> = namespaces
+            .into_iter()
+            .map(|(namespace, nodes)| NamespaceSize { namespace, nodes })
+            .collect();
+        largest_namespaces.sort_by(|a, b| {
+            b.nodes
+                .cmp(&a.nodes)
+                .then_with(|| a.namespace.cmp(&b.namespace))
+        });
+        largest_namespaces.truncate(LARGEST_NAMESPACES);
+
+        Self {
+            nodes,
+            relationships,
+            degrees: degrees_by_type
+                .into_iter()
+                .map(|(node_type, degrees)| (node_type, DegreeDistribution::of(degrees)))
+                .collect(),
+            largest_namespaces,
+        }
+    }
+}
+
+impl fmt::Display for GraphStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Nodes: {}", self.nodes.values().sum::<usize>())?;
+        for (node_type, count) in &self.nodes {
+            writeln!(f, "  {node_type}: {count}")?;
+        }
+        writeln!(
+            f,
+            "Relationships: {}",
+            self.relationships.values().sum::<usize>()
+        )?;
+        for (pattern, count) in &self.relationships {
+            writeln!(f, "  {pattern}: {count}")?;
+        }
+        writeln!(f, "Degrees (min/median/p90/p99/max, mean):")?;
+        for (node_type, degrees) in &self.degrees {
+            writeln!(
+                f,
+                "  {node_type}: {}/{}/{}/{}/{}, {:.1}",
+                degrees.min, degrees.median, degrees.p90, degrees.p99, degrees.max, degrees.mean
+            )?;
+        }
+        writeln!(f, "Largest namespaces:")?;
+        for namespace in &self.largest_namespaces {
+            writeln!(f, "  {}: {}", namespace.namespace, namespace.nodes)?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolves the snapshot in `dir` and measures the graph it becomes.
+pub async fn snapshot_stats(dir: &Path) -> Result<GraphStats> {
+    let client = SnapshotKubeClient::from_dir(dir)?;
+    let resolver =
+        ClusterStateResolver::new_with_kube_client("snapshot".to_string(), Box::new(client))
+            .await?;
+    let state = resolver.resolve().await?.load();
+    Ok(GraphStats::of(&state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ariadne_core::fixtures::golden_fixtures;
+
+    #[test]
+    fn snapshots_are_measured_by_type_and_namespace() {
+        let dir = std::env::temp_dir().join(format!("ariadne-stats-{}", std::process::id()));
+        let fixture = golden_fixtures()
+            .into_iter()
+            .find(|fixture| fixture.name == "deployment-chain")
+            .unwrap();
+        fixture.write_to_dir(&dir).unwrap();
+        let stats = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(snapshot_stats(&dir))
+            .unwrap();
+        let _ = std::fs::remove_dir_all(dir);
+
+        assert_eq!(stats.nodes["Pod"], 2);
+        assert_eq!(stats.nodes["Deployment"], 1);
+        assert_eq!(stats.relationships["(:Pod)-[:RunsOn]->(:Node)"], 2);
+        assert_eq!(stats.relationships["(:ReplicaSet)-[:Manages]->(:Pod)"], 2);
+        assert!(stats.degrees["Node"].max >= 2);
+        assert_eq!(stats.largest_namespaces[0].namespace, "shop");
+        assert!(stats.to_string().contains("  Pod: 2\n"));
+    }
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let degrees = DegreeDistribution::of(vec![5, 1, 3, 2, 4]);
+        assert_eq!((degrees.min, degrees.median, degrees.max), (1, 3, 5));
+        assert_eq!((degrees.p90, degrees.p99), (5, 5));
+        assert_eq!(degrees.mean, 3.0);
+    }
+}